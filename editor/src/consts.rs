@@ -51,6 +51,7 @@ pub const BIG_NUDGE_AMOUNT: f64 = 10.;
 
 // TOOLS
 pub const DEFAULT_STROKE_WIDTH: f64 = 2.;
+pub const DEFAULT_FILL_GAP_TOLERANCE: f64 = 4.;
 
 // SELECT TOOL
 pub const SELECTION_TOLERANCE: f64 = 5.;
@@ -150,6 +151,7 @@ pub const COLOR_OVERLAY_YELLOW: &str = "#ffc848";
 pub const COLOR_OVERLAY_YELLOW_DULL: &str = "#d7ba8b";
 pub const COLOR_OVERLAY_GREEN: &str = "#63ce63";
 pub const COLOR_OVERLAY_RED: &str = "#ef5454";
+pub const COLOR_OVERLAY_RED_50: &str = "#ef545480";
 pub const COLOR_OVERLAY_GRAY: &str = "#cccccc";
 pub const COLOR_OVERLAY_GRAY_DARK: &str = "#555555";
 pub const COLOR_OVERLAY_GRAY_25: &str = "#cccccc40";
@@ -10,4 +10,5 @@ pub enum ClipboardMessage {
 	ReadClipboard { content: ClipboardContentRaw },
 	ReadSelection { content: Option<String>, cut: bool },
 	Write { content: ClipboardContent },
+	TextInputFocus { focused: bool },
 }
@@ -1,5 +1,8 @@
 use crate::messages::clipboard::utility_types::{ClipboardContent, ClipboardContentRaw};
+use crate::messages::portfolio::document::utility_types::document_metadata::LayerNodeIdentifier;
 use crate::messages::prelude::*;
+use glam::DAffine2;
+use graphene_std::path_bool::vector_from_path_data;
 use graphene_std::raster::Image;
 use graphite_proc_macros::{ExtractField, message_handler_data};
 
@@ -8,7 +11,10 @@ const CLIPBOARD_PREFIX_NODES: &str = "graphite/nodes: ";
 const CLIPBOARD_PREFIX_VECTOR: &str = "graphite/vector: ";
 
 #[derive(Debug, Clone, Default, ExtractField)]
-pub struct ClipboardMessageHandler {}
+pub struct ClipboardMessageHandler {
+	/// Whether a text input in the frontend has focus, which receives pasted text rather than the document.
+	text_input_focused: bool,
+}
 
 #[message_handler_data]
 impl MessageHandler<ClipboardMessage, ()> for ClipboardMessageHandler {
@@ -25,6 +31,14 @@ impl MessageHandler<ClipboardMessage, ()> for ClipboardMessageHandler {
 						responses.add(NodeGraphMessage::PasteNodes { serialized_nodes: nodes.to_string() });
 					} else if let Some(vector) = text.strip_prefix(CLIPBOARD_PREFIX_VECTOR) {
 						responses.add(PortfolioMessage::PasteSerializedVector { data: vector.to_string() });
+					} else if !self.text_input_focused
+						&& let Some(vector) = vector_from_path_data(text.trim()).ok().filter(|vector| !vector.segment_domain.ids().is_empty())
+					{
+						// Text which is SVG path data, such as `M 0 0 L 100 0 L 50 100 Z`, is pasted as a new path unless it's being pasted into a text input
+						let data = vec![(LayerNodeIdentifier::ROOT_PARENT, vector, DAffine2::IDENTITY)];
+						if let Ok(data) = serde_json::to_string(&data) {
+							responses.add(PortfolioMessage::PasteSerializedVector { data });
+						}
 					} else {
 						responses.add(FrontendMessage::TriggerSelectionWrite { content: text });
 					}
@@ -75,6 +89,7 @@ impl MessageHandler<ClipboardMessage, ()> for ClipboardMessageHandler {
 				};
 				responses.add(FrontendMessage::TriggerClipboardWrite { content: text });
 			}
+			ClipboardMessage::TextInputFocus { focused } => self.text_input_focused = focused,
 		}
 	}
 	advertise_actions!(ClipboardMessageDiscriminant;
@@ -83,3 +98,47 @@ impl MessageHandler<ClipboardMessage, ()> for ClipboardMessageHandler {
 		Paste,
 	);
 }
+
+#[cfg(test)]
+mod test_clipboard {
+	use super::*;
+	use crate::test_utils::test_prelude::*;
+
+	const PATH_DATA: &str = "M 0 0 L 100 0 L 50 100 Z";
+
+	async fn paste_text(editor: &mut EditorTestUtils, text: &str) -> Vec<FrontendMessage> {
+		let content = ClipboardContentRaw::Text(text.to_string());
+		editor.handle_message(ClipboardMessage::ReadClipboard { content }).await
+	}
+
+	#[tokio::test]
+	async fn path_data_is_pasted_as_a_layer() {
+		let mut editor = EditorTestUtils::create();
+		editor.new_document().await;
+
+		paste_text(&mut editor, PATH_DATA).await;
+
+		assert_eq!(editor.active_document().metadata().all_layers().count(), 1);
+	}
+
+	#[tokio::test]
+	async fn path_data_is_pasted_into_a_focused_text_input() {
+		let mut editor = EditorTestUtils::create();
+		editor.new_document().await;
+
+		editor.handle_message(ClipboardMessage::TextInputFocus { focused: true }).await;
+		let frontend_messages = paste_text(&mut editor, PATH_DATA).await;
+
+		assert_eq!(editor.active_document().metadata().all_layers().count(), 0);
+		assert!(
+			frontend_messages
+				.iter()
+				.any(|message| matches!(message, FrontendMessage::TriggerSelectionWrite { content } if content == PATH_DATA))
+		);
+
+		editor.handle_message(ClipboardMessage::TextInputFocus { focused: false }).await;
+		paste_text(&mut editor, PATH_DATA).await;
+
+		assert_eq!(editor.active_document().metadata().all_layers().count(), 1);
+	}
+}
@@ -1,4 +1,4 @@
-use crate::messages::frontend::utility_types::{ExportBounds, FileType};
+use crate::messages::frontend::utility_types::{BitDepth, ExportBounds, FileType};
 use crate::messages::prelude::*;
 
 #[impl_message(Message, DialogMessage, ExportDialog)]
@@ -6,6 +6,8 @@ use crate::messages::prelude::*;
 pub enum ExportDialogMessage {
 	FileType { file_type: FileType },
 	ScaleFactor { factor: f64 },
+	Quality { quality: f64 },
+	BitDepth { bit_depth: BitDepth },
 	TransparentBackground { transparent: bool },
 	ExportBounds { bounds: ExportBounds },
 
@@ -1,4 +1,4 @@
-use crate::messages::frontend::utility_types::{ExportBounds, FileType};
+use crate::messages::frontend::utility_types::{BitDepth, ExportBounds, FileType};
 use crate::messages::layout::utility_types::widget_prelude::*;
 use crate::messages::portfolio::document::utility_types::document_metadata::LayerNodeIdentifier;
 use crate::messages::prelude::*;
@@ -13,6 +13,8 @@ pub struct ExportDialogMessageContext<'a> {
 pub struct ExportDialogMessageHandler {
 	pub file_type: FileType,
 	pub scale_factor: f64,
+	pub quality: f64,
+	pub bit_depth: BitDepth,
 	pub bounds: ExportBounds,
 	pub transparent_background: bool,
 	pub artboards: HashMap<LayerNodeIdentifier, String>,
@@ -24,6 +26,8 @@ impl Default for ExportDialogMessageHandler {
 		Self {
 			file_type: Default::default(),
 			scale_factor: 1.,
+			quality: 90.,
+			bit_depth: BitDepth::Eight,
 			bounds: Default::default(),
 			transparent_background: false,
 			artboards: Default::default(),
@@ -40,6 +44,8 @@ impl MessageHandler<ExportDialogMessage, ExportDialogMessageContext<'_>> for Exp
 		match message {
 			ExportDialogMessage::FileType { file_type } => self.file_type = file_type,
 			ExportDialogMessage::ScaleFactor { factor } => self.scale_factor = factor,
+			ExportDialogMessage::Quality { quality } => self.quality = quality,
+			ExportDialogMessage::BitDepth { bit_depth } => self.bit_depth = bit_depth,
 			ExportDialogMessage::TransparentBackground { transparent } => self.transparent_background = transparent,
 			ExportDialogMessage::ExportBounds { bounds } => self.bounds = bounds,
 
@@ -52,6 +58,8 @@ impl MessageHandler<ExportDialogMessage, ExportDialogMessageContext<'_>> for Exp
 					name: portfolio.active_document().map(|document| document.name.clone()).unwrap_or_default(),
 					file_type: self.file_type,
 					scale_factor: self.scale_factor,
+					quality: self.quality,
+					bit_depth: if self.file_type.supports_16_bit() { self.bit_depth } else { BitDepth::Eight },
 					bounds: self.bounds,
 					transparent_background: self.file_type != FileType::Jpg && self.transparent_background,
 					artboard_name,
@@ -90,7 +98,21 @@ impl DialogLayoutHolder for ExportDialogMessageHandler {
 
 impl LayoutHolder for ExportDialogMessageHandler {
 	fn layout(&self) -> Layout {
-		let entries = [(FileType::Png, "PNG"), (FileType::Jpg, "JPG"), (FileType::Svg, "SVG")]
+		let file_types = [
+			(FileType::Png, "PNG"),
+			(FileType::Jpg, "JPG"),
+			(FileType::Webp, "WebP"),
+			(FileType::Avif, "AVIF"),
+			(FileType::Tiff, "TIFF"),
+			(FileType::Svg, "SVG"),
+			(FileType::Pdf, "PDF"),
+		]
+		.into_iter()
+		.filter(|(file_type, _)| file_type.is_available())
+		.collect::<Vec<_>>();
+		let selected_index = file_types.iter().position(|&(file_type, _)| file_type == self.file_type).map(|index| index as u32);
+
+		let entries = file_types
 			.into_iter()
 			.map(|(file_type, name)| {
 				RadioEntryData::new(format!("{file_type:?}"))
@@ -102,7 +124,7 @@ impl LayoutHolder for ExportDialogMessageHandler {
 		let export_type = vec![
 			TextLabel::new("File Type").table_align(true).min_width(100).widget_instance(),
 			Separator::new(SeparatorStyle::Unrelated).widget_instance(),
-			RadioInput::new(entries).selected_index(Some(self.file_type as u32)).widget_instance(),
+			RadioInput::new(entries).selected_index(selected_index).widget_instance(),
 		];
 
 		let resolution = vec![
@@ -112,12 +134,60 @@ impl LayoutHolder for ExportDialogMessageHandler {
 				.unit("")
 				.min(0.)
 				.max((1_u64 << f64::MANTISSA_DIGITS) as f64)
-				.disabled(self.file_type == FileType::Svg)
+				.disabled(matches!(self.file_type, FileType::Svg | FileType::Pdf))
 				.on_update(|number_input: &NumberInput| ExportDialogMessage::ScaleFactor { factor: number_input.value.unwrap() }.into())
 				.min_width(200)
 				.widget_instance(),
 		];
 
+		let quality_description = "Compression quality of JPG, WebP, and AVIF images. WebP images are compressed losslessly at 100%.";
+		let quality = vec![
+			TextLabel::new("Quality")
+				.tooltip_label("Quality")
+				.tooltip_description(quality_description)
+				.table_align(true)
+				.min_width(100)
+				.widget_instance(),
+			Separator::new(SeparatorStyle::Unrelated).widget_instance(),
+			NumberInput::new(Some(self.quality))
+				.unit("%")
+				.min(1.)
+				.max(100.)
+				.int()
+				.disabled(!self.file_type.has_quality())
+				.tooltip_label("Quality")
+				.tooltip_description(quality_description)
+				.on_update(|number_input: &NumberInput| ExportDialogMessage::Quality { quality: number_input.value.unwrap() }.into())
+				.min_width(200)
+				.widget_instance(),
+		];
+
+		let bit_depth_description = "Bits per color channel of PNG and TIFF images. 16-bit images suit print workflows that expect high bit depth files.\n\nThe artwork is rendered with 8-bit color, which 16-bit images widen to the full 16-bit range.";
+		let bit_depth_entries = [(BitDepth::Eight, "8-bit"), (BitDepth::Sixteen, "16-bit")]
+			.into_iter()
+			.map(|(bit_depth, name)| {
+				RadioEntryData::new(format!("{bit_depth:?}"))
+					.label(name)
+					.tooltip_label("Bit Depth")
+					.tooltip_description(bit_depth_description)
+					.on_update(move |_| ExportDialogMessage::BitDepth { bit_depth }.into())
+			})
+			.collect();
+
+		let bit_depth = vec![
+			TextLabel::new("Bit Depth")
+				.tooltip_label("Bit Depth")
+				.tooltip_description(bit_depth_description)
+				.table_align(true)
+				.min_width(100)
+				.widget_instance(),
+			Separator::new(SeparatorStyle::Unrelated).widget_instance(),
+			RadioInput::new(bit_depth_entries)
+				.selected_index(Some(self.bit_depth as u32))
+				.disabled(!self.file_type.supports_16_bit())
+				.widget_instance(),
+		];
+
 		let standard_bounds = vec![
 			(ExportBounds::AllArtwork, "All Artwork".to_string(), false),
 			(ExportBounds::Selection, "Selection".to_string(), !self.has_selection),
@@ -171,6 +241,8 @@ impl LayoutHolder for ExportDialogMessageHandler {
 		Layout(vec![
 			LayoutGroup::Row { widgets: export_type },
 			LayoutGroup::Row { widgets: resolution },
+			LayoutGroup::Row { widgets: quality },
+			LayoutGroup::Row { widgets: bit_depth },
 			LayoutGroup::Row { widgets: export_area },
 			LayoutGroup::Row { widgets: transparent_background },
 		])
@@ -1,4 +1,4 @@
-use super::utility_types::{DocumentDetails, MouseCursorIcon, OpenDocument};
+use super::utility_types::{DocumentDetails, MouseCursorIcon, OpenDocument, RasterEncoding};
 use crate::messages::app_window::app_window_message_handler::AppWindowPlatform;
 use crate::messages::frontend::utility_types::EyedropperPreviewImage;
 use crate::messages::input_mapper::utility_types::misc::ActionShortcut;
@@ -97,8 +97,15 @@ pub enum FrontendMessage {
 		svg: String,
 		name: String,
 		mime: String,
+		quality: f64,
 		size: (f64, f64),
 	},
+	TriggerRasterizeExport {
+		svg: String,
+		name: String,
+		size: (f64, f64),
+		encoding: RasterEncoding,
+	},
 	TriggerFetchAndOpenDocument {
 		name: String,
 		filename: String,
@@ -231,6 +238,9 @@ pub enum FrontendMessage {
 	UpdateDocumentBarLayout {
 		diff: Vec<WidgetDiff>,
 	},
+	UpdateDocumentDpi {
+		dpi: f64,
+	},
 	UpdateDocumentLayerDetails {
 		data: LayerPanelEntry,
 	},
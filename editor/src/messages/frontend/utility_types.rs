@@ -42,7 +42,11 @@ pub enum FileType {
 	#[default]
 	Png,
 	Jpg,
+	Webp,
+	Avif,
+	Tiff,
 	Svg,
+	Pdf,
 }
 
 impl FileType {
@@ -50,9 +54,138 @@ impl FileType {
 		match self {
 			FileType::Png => "image/png",
 			FileType::Jpg => "image/jpeg",
+			FileType::Webp => "image/webp",
+			FileType::Avif => "image/avif",
+			FileType::Tiff => "image/tiff",
 			FileType::Svg => "image/svg+xml",
+			FileType::Pdf => "application/pdf",
 		}
 	}
+
+	pub fn extension(self) -> &'static str {
+		match self {
+			FileType::Png => "png",
+			FileType::Jpg => "jpg",
+			FileType::Webp => "webp",
+			FileType::Avif => "avif",
+			FileType::Tiff => "tiff",
+			FileType::Svg => "svg",
+			FileType::Pdf => "pdf",
+		}
+	}
+
+	pub fn is_raster(self) -> bool {
+		!matches!(self, FileType::Svg | FileType::Pdf)
+	}
+
+	/// Whether the format uses lossy compression controlled by a quality setting.
+	pub fn has_quality(self) -> bool {
+		matches!(self, FileType::Jpg | FileType::Webp | FileType::Avif)
+	}
+
+	/// Whether the format can store 16 bits per color channel.
+	pub fn supports_16_bit(self) -> bool {
+		matches!(self, FileType::Png | FileType::Tiff)
+	}
+
+	/// Whether this build of the editor can export the format. The web build leaves out the AVIF and TIFF encoders, which would add considerably to its download size.
+	pub fn is_available(self) -> bool {
+		cfg!(not(target_family = "wasm")) || !matches!(self, FileType::Avif | FileType::Tiff)
+	}
+}
+
+#[derive(Clone, Copy, Debug, Default, Eq, PartialEq, serde::Serialize, serde::Deserialize, specta::Type)]
+pub enum BitDepth {
+	#[default]
+	Eight,
+	Sixteen,
+}
+
+/// The settings for encoding the rendered pixels of a raster export into an image file.
+#[derive(Clone, Copy, Debug, Default, PartialEq, serde::Serialize, serde::Deserialize, specta::Type)]
+pub struct RasterEncoding {
+	#[serde(rename = "fileType")]
+	pub file_type: FileType,
+	/// Compression quality from 1 to 100 for the lossy formats. WebP at 100 is compressed losslessly.
+	pub quality: f64,
+	#[serde(rename = "bitDepth")]
+	pub bit_depth: BitDepth,
+	#[serde(rename = "transparentBackground")]
+	pub transparent_background: bool,
+}
+
+impl RasterEncoding {
+	/// Whether the browser can encode this image from a canvas, otherwise the editor encodes the canvas pixels itself.
+	pub fn is_browser_encodable(&self) -> bool {
+		match self.file_type {
+			FileType::Png => self.bit_depth == BitDepth::Eight,
+			FileType::Jpg => true,
+			// Browsers only offer lossy WebP encoding
+			FileType::Webp => self.quality < 100.,
+			_ => false,
+		}
+	}
+
+	/// Encodes RGBA pixels with 8 bits per channel into the image file format.
+	/// The renderer produces 8 bits per channel, so 16-bit images hold those values widened to the full 16-bit range.
+	pub fn encode(&self, data: Vec<u8>, width: u32, height: u32) -> Result<Vec<u8>, String> {
+		#[cfg(not(target_family = "wasm"))]
+		use image::codecs::avif::AvifEncoder;
+		use image::codecs::jpeg::JpegEncoder;
+		use image::codecs::webp::WebPEncoder;
+		use image::{DynamicImage, ImageFormat, RgbaImage};
+
+		let format = self.file_type.extension().to_uppercase();
+		let Some(image) = RgbaImage::from_raw(width, height, data) else {
+			return Err("Failed to create image buffer for export".to_string());
+		};
+		let image = DynamicImage::ImageRgba8(image);
+		let image = match (self.bit_depth, self.transparent_background && self.file_type != FileType::Jpg) {
+			(BitDepth::Sixteen, _) if !self.file_type.supports_16_bit() => return Err(format!("{format} does not support 16-bit color")),
+			(BitDepth::Eight, true) => image,
+			(BitDepth::Eight, false) => image.to_rgb8().into(),
+			(BitDepth::Sixteen, true) => image.to_rgba16().into(),
+			(BitDepth::Sixteen, false) => image.to_rgb16().into(),
+		};
+
+		let quality = self.quality.round().clamp(1., 100.) as u8;
+		let mut encoded = Vec::new();
+		let mut cursor = std::io::Cursor::new(&mut encoded);
+
+		let result = match self.file_type {
+			FileType::Png => image.write_to(&mut cursor, ImageFormat::Png),
+			FileType::Jpg => image.write_with_encoder(JpegEncoder::new_with_quality(&mut cursor, quality)),
+			// The `image` crate only encodes WebP losslessly
+			#[cfg(not(target_family = "wasm"))]
+			FileType::Webp if quality < 100 => return encode_lossy_webp(&image, quality).map_err(|err| format!("Failed to encode {format}: {err}")),
+			// In the browser, lossy WebP images are encoded by the browser itself
+			#[cfg(target_family = "wasm")]
+			FileType::Webp if quality < 100 => return Err(format!("Lossy {format} encoding is left to the browser")),
+			FileType::Webp => image.write_with_encoder(WebPEncoder::new_lossless(&mut cursor)),
+			#[cfg(not(target_family = "wasm"))]
+			FileType::Avif => image.write_with_encoder(AvifEncoder::new_with_speed_quality(&mut cursor, 4, quality)),
+			#[cfg(not(target_family = "wasm"))]
+			FileType::Tiff => image.write_to(&mut cursor, ImageFormat::Tiff),
+			#[cfg(target_family = "wasm")]
+			FileType::Avif | FileType::Tiff => return Err(format!("{format} export is only available in the desktop app")),
+			FileType::Svg | FileType::Pdf => return Err(format!("{format} cannot be exported from an image buffer")),
+		};
+		if let Err(err) = result {
+			return Err(format!("Failed to encode {format}: {err}"));
+		}
+
+		Ok(encoded)
+	}
+}
+
+/// Encodes the image as a lossy WebP with libwebp, at a quality from 1 to 100.
+#[cfg(not(target_family = "wasm"))]
+fn encode_lossy_webp(image: &image::DynamicImage, quality: u8) -> Result<Vec<u8>, String> {
+	let image = image.to_rgba8();
+	let encoded = webp::Encoder::from_rgba(image.as_raw(), image.width(), image.height())
+		.encode_simple(false, quality as f32)
+		.map_err(|err| format!("{err:?}"))?;
+	Ok(encoded.to_vec())
 }
 
 #[derive(Clone, Copy, Debug, Default, Eq, PartialEq, serde::Serialize, serde::Deserialize, specta::Type)]
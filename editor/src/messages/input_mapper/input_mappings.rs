@@ -305,6 +305,13 @@ pub fn input_mappings(zoom_with_scroll: bool) -> Mapping {
 		entry!(KeyDown(MouseRight); action_dispatch=FillToolMessage::Abort),
 		entry!(KeyDown(Escape); action_dispatch=FillToolMessage::Abort),
 		//
+		// ShapeBuilderToolMessage
+		entry!(PointerMove; refresh_keys=[Alt], action_dispatch=ShapeBuilderToolMessage::PointerMove),
+		entry!(KeyDown(MouseLeft); action_dispatch=ShapeBuilderToolMessage::DragStart { remove: Alt }),
+		entry!(KeyUp(MouseLeft); action_dispatch=ShapeBuilderToolMessage::DragStop),
+		entry!(KeyDown(MouseRight); action_dispatch=ShapeBuilderToolMessage::Abort),
+		entry!(KeyDown(Escape); action_dispatch=ShapeBuilderToolMessage::Abort),
+		//
 		// BrushToolMessage
 		entry!(PointerMove; action_dispatch=BrushToolMessage::PointerMove),
 		entry!(KeyDown(MouseLeft); action_dispatch=BrushToolMessage::DragStart),
@@ -328,6 +335,7 @@ pub fn input_mappings(zoom_with_scroll: bool) -> Mapping {
 		entry!(KeyDown(KeyM); action_dispatch=ToolMessage::ActivateToolShapeRectangle),
 		entry!(KeyDown(KeyE); action_dispatch=ToolMessage::ActivateToolShapeEllipse),
 		entry!(KeyDown(KeyY); action_dispatch=ToolMessage::ActivateToolShape),
+		entry!(KeyDown(KeyM); modifiers=[Shift], action_dispatch=ToolMessage::ActivateToolShapeBuilder),
 		entry!(KeyDown(KeyB); action_dispatch=ToolMessage::ActivateToolBrush),
 		entry!(KeyDown(KeyD); action_dispatch=ToolMessage::ResetColors),
 		entry!(KeyDown(KeyX); modifiers=[Shift], action_dispatch=ToolMessage::SwapColors),
@@ -206,7 +206,12 @@ impl LayoutMessageHandler {
 									})
 									.collect::<Vec<_>>();
 
-								color_button.value = FillChoice::Gradient(GradientStops::new(gradient_stops));
+								// The color picker only edits the stops, so the rest of the gradient's blending choices are kept
+								let mut gradient_stops = GradientStops::new(gradient_stops);
+								if let FillChoice::Gradient(previous) = &color_button.value {
+									gradient_stops = gradient_stops.with_blending_of(previous);
+								}
+								color_button.value = FillChoice::Gradient(gradient_stops);
 								return (color_button.on_update.callback)(color_button);
 							}
 
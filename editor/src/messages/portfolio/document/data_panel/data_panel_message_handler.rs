@@ -13,7 +13,7 @@ use graphene_std::memo::IORecord;
 use graphene_std::raster_types::{CPU, GPU, Raster};
 use graphene_std::table::Table;
 use graphene_std::vector::Vector;
-use graphene_std::vector::style::{Fill, FillChoice};
+use graphene_std::vector::style::{Fill, FillChoice, GradientType};
 use graphene_std::{Artboard, Graphic};
 use std::any::Any;
 use std::sync::Arc;
@@ -384,6 +384,42 @@ impl TableRowLayout for Vector {
 							TextLabel::new("Fill Gradient End").narrow(true).widget_instance(),
 							TextLabel::new(format_dvec2(gradient.end)).narrow(true).widget_instance(),
 						]);
+						if gradient.gradient_type.has_minor_axis() {
+							table_rows.push(vec![
+								TextLabel::new("Fill Gradient Minor Axis Ratio").narrow(true).widget_instance(),
+								TextLabel::new(gradient.minor_axis_ratio.to_string()).narrow(true).widget_instance(),
+							]);
+						}
+						if gradient.gradient_type == GradientType::Radial {
+							table_rows.push(vec![
+								TextLabel::new("Fill Gradient Focal Offset").narrow(true).widget_instance(),
+								TextLabel::new(format_dvec2(gradient.focal_offset)).narrow(true).widget_instance(),
+							]);
+						}
+					}
+					Fill::Pattern(pattern) => {
+						table_rows.push(vec![
+							TextLabel::new("Fill").narrow(true).widget_instance(),
+							TextLabel::new(format!("Pattern ({} elements)", pattern.content.len())).narrow(true).widget_instance(),
+						]);
+						table_rows.push(vec![
+							TextLabel::new("Fill Pattern Spacing").narrow(true).widget_instance(),
+							TextLabel::new(format_dvec2(pattern.spacing)).narrow(true).widget_instance(),
+						]);
+						table_rows.push(vec![
+							TextLabel::new("Fill Pattern Offset").narrow(true).widget_instance(),
+							TextLabel::new(format_dvec2(pattern.offset)).narrow(true).widget_instance(),
+						]);
+					}
+					Fill::Image(image) => {
+						table_rows.push(vec![
+							TextLabel::new("Fill").narrow(true).widget_instance(),
+							TextLabel::new(format!("Image ({}x{})", image.image.width, image.image.height)).narrow(true).widget_instance(),
+						]);
+						table_rows.push(vec![
+							TextLabel::new("Fill Image Fit").narrow(true).widget_instance(),
+							TextLabel::new(format!("{:?}", image.fit)).narrow(true).widget_instance(),
+						]);
 					}
 				}
 
@@ -564,7 +600,7 @@ impl TableRowLayout for GradientStops {
 		"Gradient"
 	}
 	fn identifier(&self) -> String {
-		format!("Gradient ({} stops)", self.0.len())
+		format!("Gradient ({} stops)", self.len())
 	}
 	fn element_widget(&self, _index: usize) -> WidgetInstance {
 		ColorInput::new(FillChoice::Gradient(self.clone()))
@@ -148,6 +148,9 @@ pub enum DocumentMessage {
 	SetBlendModeForSelectedLayers {
 		blend_mode: BlendMode,
 	},
+	SetDpi {
+		dpi: f64,
+	},
 	SetGraphFadeArtwork {
 		percentage: f64,
 	},
@@ -105,6 +105,8 @@ pub struct DocumentMessageHandler {
 	pub graph_view_overlay_open: bool,
 	/// The current opacity of the faded node graph background that covers up the artwork.
 	pub graph_fade_artwork_percentage: f64,
+	/// The number of pixels per inch, which relates the physical lengths typed into number inputs, like `2in` or `12pt`, to pixels.
+	pub dpi: f64,
 
 	// =============================================
 	// Fields omitted from the saved document format
@@ -167,6 +169,7 @@ impl Default for DocumentMessageHandler {
 			graph_view_overlay_open: false,
 			snapping_state: SnappingState::default(),
 			graph_fade_artwork_percentage: 80.,
+			dpi: 96.,
 			// =============================================
 			// Fields omitted from the saved document format
 			// =============================================
@@ -1012,6 +1015,11 @@ impl MessageHandler<DocumentMessage, DocumentMessageContext<'_>> for DocumentMes
 					responses.add(GraphOperationMessage::BlendModeSet { layer, blend_mode });
 				}
 			}
+			DocumentMessage::SetDpi { dpi } => {
+				self.dpi = dpi;
+				responses.add(FrontendMessage::UpdateDocumentDpi { dpi });
+				responses.add(PortfolioMessage::UpdateDocumentWidgets);
+			}
 			DocumentMessage::SetGraphFadeArtwork { percentage } => {
 				self.graph_fade_artwork_percentage = percentage;
 				responses.add(FrontendMessage::UpdateGraphFadeArtwork { percentage });
@@ -2574,6 +2582,19 @@ impl DocumentMessageHandler {
 			.narrow(true)
 			.widget_instance(),
 			Separator::new(SeparatorStyle::Unrelated).widget_instance(),
+			NumberInput::new(Some(self.dpi))
+				.unit(" DPI")
+				.min(1.)
+				.tooltip_label("Document DPI")
+				.tooltip_description("The number of pixels per inch, used to convert physical lengths like \"2in\", \"12pt\", or \"3mm\" typed into number inputs.")
+				.on_update(|number_input: &NumberInput| {
+					DocumentMessage::SetDpi {
+						dpi: number_input.value.unwrap_or(96.),
+					}
+					.into()
+				})
+				.widget_instance(),
+			Separator::new(SeparatorStyle::Unrelated).widget_instance(),
 		];
 
 		widgets.extend(navigation_controls(&self.document_ptz, &self.navigation_handler, false));
@@ -13,7 +13,7 @@ use graphene_std::table::Table;
 use graphene_std::text::{Font, TypesettingConfig};
 use graphene_std::vector::PointId;
 use graphene_std::vector::VectorModificationType;
-use graphene_std::vector::style::{Fill, Stroke};
+use graphene_std::vector::style::{Fill, Stroke, WidthProfile};
 
 #[impl_message(Message, DocumentMessage, GraphOperation)]
 #[derive(PartialEq, Clone, Debug, serde::Serialize, serde::Deserialize)]
@@ -41,6 +41,10 @@ pub enum GraphOperationMessage {
 		layer: LayerNodeIdentifier,
 		stroke: Stroke,
 	},
+	WidthProfileSet {
+		layer: LayerNodeIdentifier,
+		width_profile: WidthProfile,
+	},
 	TransformChange {
 		layer: LayerNodeIdentifier,
 		transform: DAffine2,
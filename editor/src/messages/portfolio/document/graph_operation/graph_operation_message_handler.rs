@@ -14,7 +14,7 @@ use graphene_std::renderer::Quad;
 use graphene_std::renderer::convert_usvg_path::convert_usvg_path;
 use graphene_std::table::Table;
 use graphene_std::text::{Font, TypesettingConfig};
-use graphene_std::vector::style::{Fill, Gradient, GradientStops, GradientType, PaintOrder, Stroke, StrokeAlign, StrokeCap, StrokeJoin};
+use graphene_std::vector::style::{Fill, Gradient, GradientStops, GradientType, PaintOrder, Stroke, StrokeAlign, StrokeCap, StrokeJoin, WidthProfile};
 
 #[derive(ExtractField)]
 pub struct GraphOperationMessageContext<'a> {
@@ -65,6 +65,11 @@ impl MessageHandler<GraphOperationMessage, GraphOperationMessageContext<'_>> for
 					modify_inputs.stroke_set(stroke);
 				}
 			}
+			GraphOperationMessage::WidthProfileSet { layer, width_profile } => {
+				if let Some(mut modify_inputs) = ModifyInputsContext::new_with_layer(layer, network_interface, responses) {
+					modify_inputs.width_profile_set(width_profile);
+				}
+			}
 			GraphOperationMessage::TransformChange {
 				layer,
 				transform,
@@ -428,6 +433,7 @@ fn apply_usvg_stroke(stroke: &usvg::Stroke, modify_inputs: &mut ModifyInputsCont
 			paint_order: PaintOrder::StrokeAbove,
 			transform,
 			non_scaling: false,
+			width_profile: WidthProfile::default(),
 		})
 	}
 }
@@ -446,21 +452,44 @@ fn apply_usvg_fill(fill: &usvg::Fill, modify_inputs: &mut ModifyInputsContext, b
 			let stops = linear.stops().iter().map(|stop| (stop.offset().get() as f64, usvg_color(stop.color(), stop.opacity().get()))).collect();
 			let stops = GradientStops::new(stops);
 
-			Fill::Gradient(Gradient { start, end, gradient_type, stops })
+			Fill::Gradient(Gradient {
+				start,
+				end,
+				gradient_type,
+				stops,
+				..Default::default()
+			})
 		}
 		usvg::Paint::RadialGradient(radial) => {
 			let gradient_transform = usvg_transform(radial.transform());
 			let center = DVec2::new(radial.cx() as f64, radial.cy() as f64);
-			let edge = center + DVec2::X * radial.r().get() as f64;
+			let radius = radial.r().get() as f64;
+			let edge = center + DVec2::X * radius;
+			let focal_point = DVec2::new(radial.fx() as f64, radial.fy() as f64);
 			let (start, end) = (gradient_transform.transform_point2(center), gradient_transform.transform_point2(edge));
+			let focal_point = gradient_transform.transform_point2(focal_point);
+
+			// A gradient transform which scales the circle unevenly makes it an ellipse
+			let major_axis = gradient_transform.transform_vector2(DVec2::X * radius).length();
+			let minor_axis = gradient_transform.transform_vector2(DVec2::Y * radius).length();
+			let minor_axis_ratio = if major_axis > 0. { minor_axis / major_axis } else { 1. };
+
 			let (start, end) = (bounds_transform.inverse().transform_point2(start), bounds_transform.inverse().transform_point2(end));
+			let focal_offset = bounds_transform.inverse().transform_point2(focal_point) - start;
 
 			let gradient_type = GradientType::Radial;
 
 			let stops = radial.stops().iter().map(|stop| (stop.offset().get() as f64, usvg_color(stop.color(), stop.opacity().get()))).collect();
 			let stops = GradientStops::new(stops);
 
-			Fill::Gradient(Gradient { start, end, gradient_type, stops })
+			Fill::Gradient(Gradient {
+				start,
+				end,
+				gradient_type,
+				stops,
+				minor_axis_ratio,
+				focal_offset,
+			})
 		}
 		usvg::Paint::Pattern(_) => {
 			warn!("SVG patterns are not currently supported");
@@ -15,7 +15,7 @@ use graphene_std::subpath::Subpath;
 use graphene_std::table::Table;
 use graphene_std::text::{Font, TypesettingConfig};
 use graphene_std::vector::Vector;
-use graphene_std::vector::style::{Fill, Stroke};
+use graphene_std::vector::style::{Fill, Stroke, WidthProfile};
 use graphene_std::vector::{PointId, VectorModificationType};
 use graphene_std::{Graphic, NodeInputDecleration};
 
@@ -358,6 +358,8 @@ impl<'a> ModifyInputsContext<'a> {
 				let input_connector = InputConnector::node(fill_node_id, backup_gradient_index);
 				self.set_input_with_refresh(input_connector, NodeInput::value(TaggedValue::Gradient(gradient.clone()), false), true);
 			}
+			// Patterns and images have no backup input to remember them by
+			Fill::Pattern(_) | Fill::Image(_) => {}
 		}
 		let input_connector = InputConnector::node(fill_node_id, fill_index);
 		self.set_input_with_refresh(input_connector, NodeInput::value(TaggedValue::Fill(fill), false), false);
@@ -397,7 +399,8 @@ impl<'a> ModifyInputsContext<'a> {
 	}
 
 	pub fn stroke_set(&mut self, stroke: Stroke) {
-		let Some(stroke_node_id) = self.existing_proto_node_id(graphene_std::vector::stroke::IDENTIFIER, true) else {
+		let stroke_node_id = self.existing_proto_node_id(graphene_std::vector::stroke::IDENTIFIER, false).or_else(|| self.create_stroke_node());
+		let Some(stroke_node_id) = stroke_node_id else {
 			return;
 		};
 
@@ -423,6 +426,32 @@ impl<'a> ModifyInputsContext<'a> {
 		self.set_input_with_refresh(input_connector, NodeInput::value(TaggedValue::F64(stroke.dash_offset), false), true);
 	}
 
+	/// Create a new Stroke node inside the layer. It goes upstream of any existing Width Profile node, since a Stroke node replaces the width profile of the stroke it's given.
+	fn create_stroke_node(&mut self) -> Option<NodeId> {
+		let Some(width_profile_node_id) = self.existing_proto_node_id(graphene_std::vector::width_profile::IDENTIFIER, false) else {
+			return self.create_node(&DefinitionIdentifier::ProtoNode(graphene_std::vector::stroke::IDENTIFIER));
+		};
+		let Some(stroke_definition) = resolve_proto_node_type(graphene_std::vector::stroke::IDENTIFIER) else {
+			log::error!("Stroke does not exist in ModifyInputsContext::create_stroke_node");
+			return None;
+		};
+
+		let node_id = NodeId::new();
+		self.network_interface.insert_node(node_id, stroke_definition.default_node_template(), &[]);
+		self.network_interface.insert_node_between(&node_id, &InputConnector::node(width_profile_node_id, 0), 0, &[]);
+		self.network_interface.force_set_upstream_to_chain(&node_id, &[]);
+		Some(node_id)
+	}
+
+	pub fn width_profile_set(&mut self, width_profile: WidthProfile) {
+		let Some(width_profile_node_id) = self.existing_proto_node_id(graphene_std::vector::width_profile::IDENTIFIER, true) else {
+			return;
+		};
+
+		let input_connector = InputConnector::node(width_profile_node_id, graphene_std::vector::width_profile::ProfileInput::INDEX);
+		self.set_input_with_refresh(input_connector, NodeInput::value(TaggedValue::WidthProfile(width_profile), false), false);
+	}
+
 	/// Update the transform value of the upstream Transform node based a change to its existing value and the given parent transform.
 	/// A new Transform node is created if one does not exist, unless it would be given the identity transform.
 	pub fn transform_change_with_parent(&mut self, transform: DAffine2, transform_in: TransformIn, parent_transform: DAffine2, skip_rerender: bool) {
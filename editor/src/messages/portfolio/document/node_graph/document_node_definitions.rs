@@ -2087,6 +2087,7 @@ fn static_node_properties() -> NodeProperties {
 	map.insert("channel_mixer_properties".to_string(), Box::new(node_properties::channel_mixer_properties));
 	map.insert("fill_properties".to_string(), Box::new(node_properties::fill_properties));
 	map.insert("stroke_properties".to_string(), Box::new(node_properties::stroke_properties));
+	map.insert("appearance_properties".to_string(), Box::new(node_properties::appearance_properties));
 	map.insert("offset_path_properties".to_string(), Box::new(node_properties::offset_path_properties));
 	map.insert("selective_color_properties".to_string(), Box::new(node_properties::selective_color_properties));
 	map.insert("exposure_properties".to_string(), Box::new(node_properties::exposure_properties));
@@ -2451,8 +2452,10 @@ fn static_input_properties() -> InputProperties {
 	map.insert(
 		"assign_colors_gradient".to_string(),
 		Box::new(|node_id, index, context| {
-			let gradient_row = node_properties::color_widget(ParameterWidgetsInfo::new(node_id, index, true, context), ColorInput::default().allow_none(false));
-			Ok(vec![gradient_row])
+			let mut rows = Vec::new();
+			let gradient_row = node_properties::gradient_widget(ParameterWidgetsInfo::new(node_id, index, true, context), &mut rows);
+			rows.push(gradient_row);
+			Ok(rows)
 		}),
 	);
 	map.insert(
@@ -1687,6 +1687,12 @@ impl<'a> MessageHandler<NodeGraphMessage, NodeGraphMessageContext<'a>> for NodeG
 			}
 			NodeGraphMessage::SetInputValue { node_id, input_index, value } => {
 				let is_fill = matches!(value, TaggedValue::Fill(_));
+				if let TaggedValue::String(expression) = &value
+					&& input_index == graphene_std::math_nodes::math::ExpressionInput::INDEX
+					&& network_interface.reference(&node_id, selection_network_path) == Some(DefinitionIdentifier::ProtoNode(graphene_std::math_nodes::math::IDENTIFIER))
+				{
+					Self::update_math_variable_inputs(network_interface, node_id, expression, selection_network_path);
+				}
 				let input = NodeInput::value(value, false);
 				responses.add(NodeGraphMessage::SetInput {
 					input_connector: InputConnector::node(node_id, input_index),
@@ -2650,7 +2656,10 @@ impl NodeGraphMessageHandler {
 			.resolved_types
 			.node_graph_errors
 			.iter()
-			.find(|error| error.node_path.starts_with(breadcrumb_network_path) && error.node_path.len() > breadcrumb_network_path.len())?;
+			.find(|error| error.node_path.starts_with(breadcrumb_network_path) && error.node_path.len() > breadcrumb_network_path.len());
+		let Some(graph_error) = graph_error else {
+			return Self::math_expression_error(network_interface, breadcrumb_network_path);
+		};
 		let error = if graph_error.node_path.len() == breadcrumb_network_path.len() + 1 {
 			format!("{:?}", graph_error.error)
 		} else {
@@ -2658,6 +2667,76 @@ impl NodeGraphMessageHandler {
 		};
 		let error_node = graph_error.node_path[breadcrumb_network_path.len()];
 
+		Self::error_diagnostic(network_interface, error_node, error, breadcrumb_network_path)
+	}
+
+	/// Describes the error in the expression of a Math node in the network, if any of them can't be calculated.
+	fn math_expression_error(network_interface: &mut NodeNetworkInterface, breadcrumb_network_path: &[NodeId]) -> Option<NodeGraphErrorDiagnostic> {
+		use graphene_std::math_nodes::math::{ExpressionInput, IDENTIFIER};
+
+		let network = network_interface.nested_network(breadcrumb_network_path)?;
+		let mut math_nodes = network
+			.nodes
+			.iter()
+			.filter(|(node_id, _)| network_interface.reference(node_id, breadcrumb_network_path) == Some(DefinitionIdentifier::ProtoNode(IDENTIFIER)))
+			.collect::<Vec<_>>();
+		math_nodes.sort_unstable_by_key(|(node_id, _)| **node_id);
+
+		let (error_node, error) = math_nodes.into_iter().find_map(|(node_id, node)| {
+			let Some(TaggedValue::String(expression)) = node.inputs.get(ExpressionInput::INDEX)?.as_non_exposed_value() else {
+				return None;
+			};
+			let (message, _) = graphene_std::math_nodes::expression_error(expression)?;
+			Some((*node_id, format!("Invalid expression `{expression}`\n{message}")))
+		})?;
+
+		Self::error_diagnostic(network_interface, error_node, error, breadcrumb_network_path)
+	}
+
+	/// Gives a Math node an input for each named variable in its expression, so they can be wired like any other input.
+	/// Variables which remain in the expression keep their values and wires.
+	fn update_math_variable_inputs(network_interface: &mut NodeNetworkInterface, node_id: NodeId, expression: &str, network_path: &[NodeId]) {
+		use graphene_std::math_nodes::math::VariablesInput;
+
+		// Keep the current inputs while the expression is being typed and can't be parsed
+		let Some(names) = graphene_std::math_nodes::named_variables(expression) else { return };
+
+		let first_variable_index = VariablesInput::INDEX + 1;
+		let variable_indices = first_variable_index..network_interface.number_of_inputs(&node_id, network_path);
+		let current_names = variable_indices
+			.clone()
+			.map(|index| {
+				network_interface
+					.persistent_input_metadata(&node_id, index, network_path)
+					.map(|metadata| metadata.input_name.clone())
+					.unwrap_or_default()
+			})
+			.collect::<Vec<_>>();
+		if current_names == names {
+			return;
+		}
+
+		let mut kept_inputs = variable_indices
+			.clone()
+			.zip(current_names)
+			.filter(|(_, name)| names.contains(name))
+			.filter_map(|(index, name)| Some((name, network_interface.input_from_connector(&InputConnector::node(node_id, index), network_path)?.clone())))
+			.collect::<HashMap<_, _>>();
+
+		for index in variable_indices.rev() {
+			network_interface.remove_input(&node_id, index, network_path);
+		}
+		for (index, name) in (first_variable_index..).zip(&names) {
+			let description = format!(r#"The value of "{name}" when calculating the expression."#);
+			network_interface.add_input(&node_id, TaggedValue::F64(0.), false, -1, name, &description, network_path);
+
+			if let Some(input) = kept_inputs.remove(name) {
+				network_interface.set_input(&InputConnector::node(node_id, index), input, network_path);
+			}
+		}
+	}
+
+	fn error_diagnostic(network_interface: &mut NodeNetworkInterface, error_node: NodeId, error: String, breadcrumb_network_path: &[NodeId]) -> Option<NodeGraphErrorDiagnostic> {
 		let mut position = network_interface.position(&error_node, breadcrumb_network_path)?;
 		// Convert to graph space
 		position *= 24;
@@ -18,6 +18,7 @@ use graphene_std::animation::RealTimeMode;
 use graphene_std::extract_xy::XY;
 use graphene_std::path_bool::BooleanOperation;
 use graphene_std::raster::curve::Curve;
+use graphene_std::raster::develop_raw::{HighlightRecovery, RawColorSpace, RawDemosaicing, RawWhiteBalance};
 use graphene_std::raster::{
 	BlendMode, CellularDistanceFunction, CellularReturnType, Color, DomainWarpType, FractalType, LuminanceCalculation, NoiseType, RedGreenBlue, RedGreenBlueAlpha, RelativeAbsolute,
 	SelectiveColorChoice,
@@ -27,7 +28,10 @@ use graphene_std::text::{Font, TextAlign};
 use graphene_std::transform::{Footprint, ReferencePoint, Transform};
 use graphene_std::vector::QRCodeErrorCorrectionLevel;
 use graphene_std::vector::misc::{ArcType, CentroidType, ExtrudeJoiningAlgorithm, GridType, MergeByDistanceAlgorithm, PointSpacingType, RowsOrColumns, SpiralType};
-use graphene_std::vector::style::{Fill, FillChoice, FillType, GradientStops, GradientType, PaintOrder, StrokeAlign, StrokeCap, StrokeJoin};
+use graphene_std::vector::style::{
+	AppearanceEntry, AppearancePaint, AppearanceStack, Fill, FillChoice, FillType, GradientInterpolation, GradientStops, GradientType, HueDirection, ImageFit, PaintOrder, PatternFill, Stroke,
+	StrokeAlign, StrokeCap, StrokeJoin, WidthPoint, WidthProfile,
+};
 
 pub(crate) fn string_properties(text: &str) -> Vec<LayoutGroup> {
 	let widget = TextLabel::new(text).widget_instance();
@@ -185,12 +189,13 @@ pub(crate) fn property_from_type(
 						// ==========================
 						Some(x) if x == TypeId::of::<Vec<f64>>() => array_of_number_widget(default_info, TextInput::default()).into(),
 						Some(x) if x == TypeId::of::<Vec<DVec2>>() => array_of_vec2_widget(default_info, TextInput::default()).into(),
+						Some(x) if x == TypeId::of::<WidthProfile>() => width_profile_widget(default_info, TextInput::default()).into(),
 						// ============
 						// STRUCT TYPES
 						// ============
 						Some(x) if x == TypeId::of::<Table<Color>>() => color_widget(default_info, ColorInput::default().allow_none(true)),
-						Some(x) if x == TypeId::of::<Table<GradientStops>>() => color_widget(default_info, ColorInput::default().allow_none(false)),
-						Some(x) if x == TypeId::of::<GradientStops>() => color_widget(default_info, ColorInput::default().allow_none(false)),
+						Some(x) if x == TypeId::of::<Table<GradientStops>>() => gradient_widget(default_info, &mut extra_widgets),
+						Some(x) if x == TypeId::of::<GradientStops>() => gradient_widget(default_info, &mut extra_widgets),
 						Some(x) if x == TypeId::of::<Font>() => font_widget(default_info),
 						Some(x) if x == TypeId::of::<Curve>() => curve_widget(default_info),
 						Some(x) if x == TypeId::of::<Footprint>() => footprint_widget(default_info, &mut extra_widgets),
@@ -204,6 +209,7 @@ pub(crate) fn property_from_type(
 						// =========================
 						Some(x) if x == TypeId::of::<FillType>() => enum_choice::<FillType>().for_socket(default_info).property_row(),
 						Some(x) if x == TypeId::of::<GradientType>() => enum_choice::<GradientType>().for_socket(default_info).property_row(),
+						Some(x) if x == TypeId::of::<ImageFit>() => enum_choice::<ImageFit>().for_socket(default_info).property_row(),
 						Some(x) if x == TypeId::of::<RealTimeMode>() => enum_choice::<RealTimeMode>().for_socket(default_info).property_row(),
 						Some(x) if x == TypeId::of::<RedGreenBlue>() => enum_choice::<RedGreenBlue>().for_socket(default_info).property_row(),
 						Some(x) if x == TypeId::of::<RedGreenBlueAlpha>() => enum_choice::<RedGreenBlueAlpha>().for_socket(default_info).property_row(),
@@ -229,6 +235,10 @@ pub(crate) fn property_from_type(
 						Some(x) if x == TypeId::of::<CentroidType>() => enum_choice::<CentroidType>().for_socket(default_info).property_row(),
 						Some(x) if x == TypeId::of::<LuminanceCalculation>() => enum_choice::<LuminanceCalculation>().for_socket(default_info).property_row(),
 						Some(x) if x == TypeId::of::<QRCodeErrorCorrectionLevel>() => enum_choice::<QRCodeErrorCorrectionLevel>().for_socket(default_info).property_row(),
+						Some(x) if x == TypeId::of::<RawWhiteBalance>() => enum_choice::<RawWhiteBalance>().for_socket(default_info).property_row(),
+						Some(x) if x == TypeId::of::<HighlightRecovery>() => enum_choice::<HighlightRecovery>().for_socket(default_info).property_row(),
+						Some(x) if x == TypeId::of::<RawColorSpace>() => enum_choice::<RawColorSpace>().for_socket(default_info).property_row(),
+						Some(x) if x == TypeId::of::<RawDemosaicing>() => enum_choice::<RawDemosaicing>().for_socket(default_info).property_row(),
 						// =====
 						// OTHER
 						// =====
@@ -756,6 +766,38 @@ pub fn array_of_vec2_widget(parameter_widgets_info: ParameterWidgetsInfo, text_p
 	widgets
 }
 
+pub fn width_profile_widget(parameter_widgets_info: ParameterWidgetsInfo, text_props: TextInput) -> Vec<WidgetInstance> {
+	let ParameterWidgetsInfo { document_node, node_id, index, .. } = parameter_widgets_info;
+
+	let mut widgets = start_widgets(parameter_widgets_info);
+
+	let from_string = |string: &str| {
+		string
+			.split(|c: char| !c.is_alphanumeric() && !matches!(c, '.' | '+' | '-'))
+			.filter(|x| !x.is_empty())
+			.map(|x| x.parse::<f64>().ok())
+			.collect::<Option<Vec<_>>>()
+			.map(|numbers| WidthProfile::new(numbers.chunks_exact(3).map(|values| WidthPoint::new(values[0], values[1], values[2]))))
+			.map(TaggedValue::WidthProfile)
+	};
+
+	let Some(document_node) = document_node else { return Vec::new() };
+	let Some(input) = document_node.inputs.get(index) else {
+		log::warn!("A widget failed to be built because its node's input index is invalid.");
+		return vec![];
+	};
+	if let Some(TaggedValue::WidthProfile(x)) = &input.as_non_exposed_value() {
+		widgets.extend_from_slice(&[
+			Separator::new(SeparatorStyle::Unrelated).widget_instance(),
+			text_props
+				.value(x.to_string())
+				.on_update(optionally_update_value(move |x: &TextInput| from_string(&x.value), node_id, index))
+				.widget_instance(),
+		])
+	}
+	widgets
+}
+
 pub fn font_inputs(parameter_widgets_info: ParameterWidgetsInfo) -> (Vec<WidgetInstance>, Option<Vec<WidgetInstance>>) {
 	let ParameterWidgetsInfo {
 		persistent_data,
@@ -1157,6 +1199,104 @@ pub fn color_widget(parameter_widgets_info: ParameterWidgetsInfo, color_button:
 	LayoutGroup::Row { widgets }
 }
 
+/// A color widget for a gradient, followed by rows choosing how it blends between its stops.
+pub fn gradient_widget(parameter_widgets_info: ParameterWidgetsInfo, extra_widgets: &mut Vec<LayoutGroup>) -> LayoutGroup {
+	let ParameterWidgetsInfo { document_node, node_id, index, .. } = parameter_widgets_info;
+	let Some(document_node) = document_node else { return LayoutGroup::default() };
+	let input = document_node.inputs.get(index).and_then(|input| input.as_non_exposed_value()).cloned();
+
+	let color_row = color_widget(parameter_widgets_info, ColorInput::default().allow_none(false));
+	let mut rows = match input {
+		Some(TaggedValue::GradientStops(stops)) => gradient_blending_rows(&stops, node_id, index, TaggedValue::GradientStops),
+		Some(TaggedValue::GradientTable(table)) => match table.iter().next() {
+			Some(row) => gradient_blending_rows(row.element, node_id, index, |stops| TaggedValue::GradientTable(Table::new_from_element(stops))),
+			None => Vec::new(),
+		},
+		_ => Vec::new(),
+	};
+
+	let Some(last) = rows.pop() else { return color_row };
+	extra_widgets.push(color_row);
+	extra_widgets.extend(rows);
+	last
+}
+
+/// Builds a widget callback which replaces a gradient with a copy of it that has the widget's change made, with `wrap` turning it into the input's value.
+fn edited_gradient_stops<T>(
+	stops: &GradientStops,
+	node_id: NodeId,
+	index: usize,
+	wrap: impl Fn(GradientStops) -> TaggedValue + 'static + Send + Sync,
+	edit: impl Fn(&mut GradientStops, &T) + 'static + Send + Sync,
+) -> impl Fn(&T) -> Message + 'static + Send + Sync {
+	let stops = stops.clone();
+	update_value(
+		move |input: &T| {
+			let mut stops = stops.clone();
+			edit(&mut stops, input);
+			wrap(stops)
+		},
+		node_id,
+		index,
+	)
+}
+
+/// The rows choosing the space a gradient is blended in, the direction its hues turn, and the midpoint of the blend between each pair of neighboring stops.
+pub fn gradient_blending_rows(stops: &GradientStops, node_id: NodeId, index: usize, wrap: impl Fn(GradientStops) -> TaggedValue + Clone + 'static + Send + Sync) -> Vec<LayoutGroup> {
+	use choice::WidgetFactory;
+
+	let mut interpolation_row = vec![TextLabel::new("Interpolation").widget_instance()];
+	add_blank_assist(&mut interpolation_row);
+	interpolation_row.extend_from_slice(&[
+		Separator::new(SeparatorStyle::Unrelated).widget_instance(),
+		enum_choice::<GradientInterpolation>().build(
+			stops.interpolation,
+			|| edited_gradient_stops(stops, node_id, index, wrap.clone(), |stops, interpolation: &GradientInterpolation| stops.interpolation = *interpolation),
+			|| commit_value,
+		),
+	]);
+	if stops.interpolation == GradientInterpolation::Oklch {
+		interpolation_row.extend_from_slice(&[
+			Separator::new(SeparatorStyle::Related).widget_instance(),
+			enum_choice::<HueDirection>().build(
+				stops.hue_direction,
+				|| edited_gradient_stops(stops, node_id, index, wrap.clone(), |stops, hue_direction: &HueDirection| stops.hue_direction = *hue_direction),
+				|| commit_value,
+			),
+		]);
+	}
+	let mut rows = vec![LayoutGroup::Row { widgets: interpolation_row }];
+
+	if stops.len() > 1 {
+		let mut midpoints_row = vec![TextLabel::new("Midpoints").widget_instance()];
+		add_blank_assist(&mut midpoints_row);
+		for span in 0..stops.len() - 1 {
+			midpoints_row.extend_from_slice(&[
+				Separator::new(if span == 0 { SeparatorStyle::Unrelated } else { SeparatorStyle::Related }).widget_instance(),
+				NumberInput::new(Some(stops.midpoint(span) * 100.))
+					.percentage()
+					.min(1.)
+					.max(99.)
+					.tooltip_description(format!(
+						"Where the colors of stops {} and {} are blended evenly, as a percentage of the way between them.",
+						span + 1,
+						span + 2
+					))
+					.on_update(edited_gradient_stops(stops, node_id, index, wrap.clone(), move |stops, input: &NumberInput| {
+						if let Some(value) = input.value {
+							stops.set_midpoint(span, value / 100.);
+						}
+					}))
+					.on_commit(commit_value)
+					.widget_instance(),
+			]);
+		}
+		rows.push(LayoutGroup::Row { widgets: midpoints_row });
+	}
+
+	rows
+}
+
 pub fn font_widget(parameter_widgets_info: ParameterWidgetsInfo) -> LayoutGroup {
 	let (font_widgets, style_widgets) = font_inputs(parameter_widgets_info);
 	font_widgets.into_iter().chain(style_widgets.unwrap_or_default()).collect::<Vec<_>>().into()
@@ -1813,6 +1953,18 @@ pub(crate) fn generate_node_properties(node_id: NodeId, context: &mut NodeProper
 	}
 }
 
+/// Describes a pattern or image fill where a color input would otherwise show it as no fill, since editing it there would replace the fill.
+fn uneditable_fill_label(fill: &Fill) -> WidgetInstance {
+	let label = match fill {
+		Fill::Pattern(_) => "Pattern",
+		Fill::Image(_) => "Image",
+		Fill::None | Fill::Solid(_) | Fill::Gradient(_) => "",
+	};
+	TextLabel::new(label)
+		.tooltip_description("Patterns and images can't be edited with the color picker, but their settings may be changed below.")
+		.widget_instance()
+}
+
 /// Fill Node Widgets LayoutGroup
 pub(crate) fn fill_properties(node_id: NodeId, context: &mut NodePropertiesContext) -> Vec<LayoutGroup> {
 	use graphene_std::vector::fill::*;
@@ -1827,26 +1979,26 @@ pub(crate) fn fill_properties(node_id: NodeId, context: &mut NodePropertiesConte
 		}
 	};
 
-	let (fill, backup_color, backup_gradient) = if let (Some(TaggedValue::Fill(fill)), Some(TaggedValue::Color(backup_color)), Some(TaggedValue::Gradient(backup_gradient))) = (
-		&document_node.inputs[FillInput::<Color>::INDEX].as_value(),
-		&document_node.inputs[BackupColorInput::INDEX].as_value(),
-		&document_node.inputs[BackupGradientInput::INDEX].as_value(),
-	) {
-		(fill, backup_color, backup_gradient)
-	} else {
+	let Some(TaggedValue::Fill(fill)) = document_node.inputs[FillInput::<Color>::INDEX].as_value() else {
 		return vec![LayoutGroup::Row { widgets: widgets_first_row }];
 	};
+	// The pattern and image rows below only need the fill itself, but switching between solid and gradient fills also needs their backups
+	let backup_fills = match (document_node.inputs[BackupColorInput::INDEX].as_value(), document_node.inputs[BackupGradientInput::INDEX].as_value()) {
+		(Some(TaggedValue::Color(backup_color)), Some(TaggedValue::Gradient(backup_gradient))) => Some((Fill::from(backup_color.clone()), Fill::from(backup_gradient.clone()))),
+		_ => None,
+	};
+	let has_backups = backup_fills.is_some();
 	let fill2 = fill.clone();
-	let backup_color_fill: Fill = backup_color.clone().into();
-	let backup_gradient_fill: Fill = backup_gradient.clone().into();
 
 	widgets_first_row.push(Separator::new(SeparatorStyle::Unrelated).widget_instance());
-	widgets_first_row.push(
-		ColorInput::default()
-			.value(fill.clone().into())
+	widgets_first_row.push(match FillChoice::try_from(fill.clone()) {
+		Ok(fill_choice) => ColorInput::default()
+			.value(fill_choice)
 			.on_update(move |x: &ColorInput| Message::Batched {
 				messages: Box::new([
 					match &fill2 {
+						// Backups which are wired from other nodes are left as they are
+						_ if !has_backups => Message::NoOp,
 						Fill::None => NodeGraphMessage::SetInputValue {
 							node_id,
 							input_index: BackupColorInput::INDEX,
@@ -1865,6 +2017,8 @@ pub(crate) fn fill_properties(node_id: NodeId, context: &mut NodePropertiesConte
 							value: TaggedValue::Gradient(gradient.clone()),
 						}
 						.into(),
+						// Patterns and images have no backup input to remember them by
+						Fill::Pattern(_) | Fill::Image(_) => Message::NoOp,
 					},
 					NodeGraphMessage::SetInputValue {
 						node_id,
@@ -1876,58 +2030,135 @@ pub(crate) fn fill_properties(node_id: NodeId, context: &mut NodePropertiesConte
 			})
 			.on_commit(commit_value)
 			.widget_instance(),
-	);
+		Err(fill) => uneditable_fill_label(&fill),
+	});
 	let mut widgets = vec![LayoutGroup::Row { widgets: widgets_first_row }];
 
-	let fill_type_switch = {
-		let mut row = vec![TextLabel::new("").widget_instance()];
-		match fill {
-			Fill::Solid(_) | Fill::None => add_blank_assist(&mut row),
-			Fill::Gradient(gradient) => {
-				let reverse_button = IconButton::new("Reverse", 24)
-					.tooltip_description("Reverse the gradient color stops.")
+	if let Some((backup_color_fill, backup_gradient_fill)) = backup_fills {
+		let fill_type_switch = {
+			let mut row = vec![TextLabel::new("").widget_instance()];
+			match fill {
+				Fill::Solid(_) | Fill::None | Fill::Pattern(_) | Fill::Image(_) => add_blank_assist(&mut row),
+				Fill::Gradient(gradient) => {
+					let reverse_button = IconButton::new("Reverse", 24)
+						.tooltip_description("Reverse the gradient color stops.")
+						.on_update(update_value(
+							{
+								let gradient = gradient.clone();
+								move |_| {
+									let mut gradient = gradient.clone();
+									gradient.stops = gradient.stops.reversed();
+									TaggedValue::Fill(Fill::Gradient(gradient))
+								}
+							},
+							node_id,
+							FillInput::<Color>::INDEX,
+						))
+						.widget_instance();
+					row.push(Separator::new(SeparatorStyle::Unrelated).widget_instance());
+					row.push(reverse_button);
+				}
+			}
+
+			let entries = vec![
+				RadioEntryData::new("solid")
+					.label("Solid")
+					.on_update(update_value(move |_| TaggedValue::Fill(backup_color_fill.clone()), node_id, FillInput::<Color>::INDEX))
+					.on_commit(commit_value),
+				RadioEntryData::new("gradient")
+					.label("Gradient")
+					.on_update(update_value(move |_| TaggedValue::Fill(backup_gradient_fill.clone()), node_id, FillInput::<Color>::INDEX))
+					.on_commit(commit_value),
+			];
+
+			row.extend_from_slice(&[
+				Separator::new(SeparatorStyle::Unrelated).widget_instance(),
+				RadioInput::new(entries)
+					.selected_index(match fill {
+						Fill::None | Fill::Solid(_) => Some(0),
+						Fill::Gradient(_) => Some(1),
+						Fill::Pattern(_) | Fill::Image(_) => None,
+					})
+					.widget_instance(),
+			]);
+
+			LayoutGroup::Row { widgets: row }
+		};
+		widgets.push(fill_type_switch);
+	}
+
+	if let Fill::Pattern(pattern) = fill {
+		let pattern_vec2_row = |label: &str, value: DVec2, update: fn(&mut PatternFill, DVec2), min: Option<f64>| {
+			let mut row = vec![TextLabel::new(label).widget_instance()];
+			add_blank_assist(&mut row);
+			let max = (1_u64 << f64::MANTISSA_DIGITS) as f64;
+			let number_input = |component_value: f64, label: &str, set_component: fn(DVec2, f64) -> DVec2| {
+				let pattern = pattern.clone();
+				NumberInput::new(Some(component_value))
+					.label(label)
+					.unit(" px")
+					.min(min.unwrap_or(-max))
+					.max(max)
 					.on_update(update_value(
-						{
-							let gradient = gradient.clone();
-							move |_| {
-								let mut gradient = gradient.clone();
-								gradient.stops = gradient.stops.reversed();
-								TaggedValue::Fill(Fill::Gradient(gradient))
-							}
+						move |input: &NumberInput| {
+							let mut pattern = pattern.clone();
+							update(&mut pattern, set_component(value, input.value.unwrap()));
+							TaggedValue::Fill(Fill::Pattern(pattern))
 						},
 						node_id,
 						FillInput::<Color>::INDEX,
 					))
-					.widget_instance();
-				row.push(Separator::new(SeparatorStyle::Unrelated).widget_instance());
-				row.push(reverse_button);
-			}
-		}
+					.on_commit(commit_value)
+					.widget_instance()
+			};
+			row.extend_from_slice(&[
+				Separator::new(SeparatorStyle::Unrelated).widget_instance(),
+				number_input(value.x, "X", |value, x| DVec2::new(x, value.y)),
+				Separator::new(SeparatorStyle::Related).widget_instance(),
+				number_input(value.y, "Y", |value, y| DVec2::new(value.x, y)),
+			]);
+			LayoutGroup::Row { widgets: row }
+		};
 
-		let entries = vec![
-			RadioEntryData::new("solid")
-				.label("Solid")
-				.on_update(update_value(move |_| TaggedValue::Fill(backup_color_fill.clone()), node_id, FillInput::<Color>::INDEX))
-				.on_commit(commit_value),
-			RadioEntryData::new("gradient")
-				.label("Gradient")
-				.on_update(update_value(move |_| TaggedValue::Fill(backup_gradient_fill.clone()), node_id, FillInput::<Color>::INDEX))
-				.on_commit(commit_value),
-		];
+		widgets.push(pattern_vec2_row("Spacing", pattern.spacing, |pattern, spacing| pattern.spacing = spacing, Some(0.)));
+		widgets.push(pattern_vec2_row("Offset", pattern.offset, |pattern, offset| pattern.offset = offset, None));
+	}
+
+	if let Fill::Image(image) = fill {
+		let mut row = vec![TextLabel::new("Fit").widget_instance()];
+		add_blank_assist(&mut row);
+
+		let entries = [ImageFit::Cover, ImageFit::Contain, ImageFit::Stretch]
+			.into_iter()
+			.map(|fit| {
+				let image = image.clone();
+				RadioEntryData::new(format!("{fit:?}"))
+					.label(format!("{fit:?}"))
+					.on_update(update_value(
+						move |_| {
+							let mut image = image.clone();
+							image.fit = fit;
+							TaggedValue::Fill(Fill::Image(image))
+						},
+						node_id,
+						FillInput::<Color>::INDEX,
+					))
+					.on_commit(commit_value)
+			})
+			.collect();
 
 		row.extend_from_slice(&[
 			Separator::new(SeparatorStyle::Unrelated).widget_instance(),
-			RadioInput::new(entries).selected_index(Some(if fill.as_gradient().is_some() { 1 } else { 0 })).widget_instance(),
+			RadioInput::new(entries).selected_index(Some(image.fit as u32)).widget_instance(),
 		]);
 
-		LayoutGroup::Row { widgets: row }
-	};
-	widgets.push(fill_type_switch);
+		widgets.push(LayoutGroup::Row { widgets: row });
+	}
 
 	if let Fill::Gradient(gradient) = fill.clone() {
 		let mut row = vec![TextLabel::new("").widget_instance()];
 		match gradient.gradient_type {
-			GradientType::Linear => add_blank_assist(&mut row),
+			GradientType::Linear | GradientType::Reflected | GradientType::Conic | GradientType::Diamond => add_blank_assist(&mut row),
 			GradientType::Radial => {
 				let orientation = if (gradient.end.x - gradient.start.x).abs() > f64::EPSILON * 1e6 {
 					gradient.end.x > gradient.start.x
@@ -1956,7 +2187,7 @@ pub(crate) fn fill_properties(node_id: NodeId, context: &mut NodePropertiesConte
 
 		let gradient_for_closure = gradient.clone();
 
-		let entries = [GradientType::Linear, GradientType::Radial]
+		let entries = [GradientType::Linear, GradientType::Radial, GradientType::Reflected, GradientType::Conic, GradientType::Diamond]
 			.iter()
 			.map(|&grad_type| {
 				let gradient = gradient_for_closure.clone();
@@ -1990,6 +2221,13 @@ pub(crate) fn fill_properties(node_id: NodeId, context: &mut NodePropertiesConte
 		]);
 
 		widgets.push(LayoutGroup::Row { widgets: row });
+
+		let stops = gradient.stops.clone();
+		widgets.extend(gradient_blending_rows(&stops, node_id, FillInput::<Color>::INDEX, move |stops| {
+			let mut gradient = gradient.clone();
+			gradient.stops = stops;
+			TaggedValue::Fill(Fill::Gradient(gradient))
+		}));
 	}
 
 	widgets
@@ -2058,6 +2296,170 @@ pub fn stroke_properties(node_id: NodeId, context: &mut NodePropertiesContext) -
 	]
 }
 
+/// Builds a widget callback which replaces the node's whole appearance stack with a copy of it that has the widget's change made.
+fn edited_appearance_stack<T>(stack: &AppearanceStack, node_id: NodeId, edit: impl Fn(&mut AppearanceStack, &T) + 'static + Send + Sync) -> impl Fn(&T) -> Message + 'static + Send + Sync {
+	let stack = stack.clone();
+	update_value(
+		move |input: &T| {
+			let mut stack = stack.clone();
+			edit(&mut stack, input);
+			TaggedValue::AppearanceStack(stack)
+		},
+		node_id,
+		graphene_std::vector::appearance::StackInput::INDEX,
+	)
+}
+
+pub fn appearance_properties(node_id: NodeId, context: &mut NodePropertiesContext) -> Vec<LayoutGroup> {
+	use graphene_std::vector::appearance::*;
+
+	let mut header_row = start_widgets(ParameterWidgetsInfo::new(node_id, StackInput::INDEX, true, context));
+
+	let document_node = match get_document_node(node_id, context) {
+		Ok(document_node) => document_node,
+		Err(err) => {
+			log::error!("Could not get document node in appearance_properties: {err}");
+			return Vec::new();
+		}
+	};
+	let Some(TaggedValue::AppearanceStack(stack)) = document_node.inputs[StackInput::INDEX].as_non_exposed_value() else {
+		return vec![LayoutGroup::Row { widgets: header_row }];
+	};
+
+	header_row.extend_from_slice(&[
+		Separator::new(SeparatorStyle::Unrelated).widget_instance(),
+		TextButton::new("Add Fill")
+			.icon(Some("Add".into()))
+			.tooltip_description("Add a fill to the top of the stack.")
+			.on_update(edited_appearance_stack(stack, node_id, |stack, _: &TextButton| {
+				stack.0.push(AppearanceEntry::new(AppearancePaint::Fill(Fill::solid(Color::BLACK))))
+			}))
+			.widget_instance(),
+		Separator::new(SeparatorStyle::Related).widget_instance(),
+		TextButton::new("Add Stroke")
+			.icon(Some("Add".into()))
+			.tooltip_description("Add a stroke to the top of the stack.")
+			.on_update(edited_appearance_stack(stack, node_id, |stack, _: &TextButton| {
+				stack.0.push(AppearanceEntry::new(AppearancePaint::Stroke(Stroke::new(Some(Color::BLACK), 2.))))
+			}))
+			.widget_instance(),
+	]);
+	let mut widgets = vec![LayoutGroup::Row { widgets: header_row }];
+
+	let last_index = stack.0.len().saturating_sub(1);
+	for (index, entry) in stack.0.iter().enumerate() {
+		// The first row shows the entry's paint, along with buttons to reorder or remove it
+		let (label, color_input) = match &entry.paint {
+			AppearancePaint::Fill(fill) => {
+				let existing_gradient = fill.as_gradient().cloned();
+				let color_input = match FillChoice::try_from(fill.clone()) {
+					Ok(fill_choice) => ColorInput::default()
+						.value(fill_choice)
+						.on_update(edited_appearance_stack(stack, node_id, move |stack, input: &ColorInput| {
+							stack.0[index].paint = AppearancePaint::Fill(input.value.to_fill(existing_gradient.as_ref()));
+						}))
+						.on_commit(commit_value)
+						.widget_instance(),
+					Err(fill) => uneditable_fill_label(&fill),
+				};
+				(format!("Fill {}", index + 1), color_input)
+			}
+			AppearancePaint::Stroke(stroke) => {
+				let color_input = ColorInput::default()
+					.value(stroke.color.map_or(FillChoice::None, FillChoice::Solid))
+					.on_update(edited_appearance_stack(stack, node_id, move |stack, input: &ColorInput| {
+						if let AppearancePaint::Stroke(stroke) = &mut stack.0[index].paint {
+							stroke.color = input.value.as_solid();
+						}
+					}))
+					.on_commit(commit_value)
+					.widget_instance();
+				(format!("Stroke {}", index + 1), color_input)
+			}
+		};
+
+		let mut paint_row = vec![TextLabel::new(label).widget_instance()];
+		add_blank_assist(&mut paint_row);
+		paint_row.extend_from_slice(&[
+			Separator::new(SeparatorStyle::Unrelated).widget_instance(),
+			color_input,
+			Separator::new(SeparatorStyle::Related).widget_instance(),
+			IconButton::new("StackRaise", 24)
+				.tooltip_description("Move this up the stack, to be drawn over the one after it.")
+				.disabled(index == last_index)
+				.on_update(edited_appearance_stack(stack, node_id, move |stack, _: &IconButton| stack.0.swap(index, index + 1)))
+				.widget_instance(),
+			IconButton::new("StackLower", 24)
+				.tooltip_description("Move this down the stack, to be drawn under the one before it.")
+				.disabled(index == 0)
+				.on_update(edited_appearance_stack(stack, node_id, move |stack, _: &IconButton| stack.0.swap(index, index - 1)))
+				.widget_instance(),
+			IconButton::new("Trash", 24)
+				.tooltip_description("Remove this from the stack.")
+				.on_update(edited_appearance_stack(stack, node_id, move |stack, _: &IconButton| {
+					stack.0.remove(index);
+				}))
+				.widget_instance(),
+		]);
+		widgets.push(LayoutGroup::Row { widgets: paint_row });
+
+		if let AppearancePaint::Stroke(stroke) = &entry.paint {
+			let mut weight_row = vec![TextLabel::new("Weight").widget_instance()];
+			add_blank_assist(&mut weight_row);
+			weight_row.extend_from_slice(&[
+				Separator::new(SeparatorStyle::Unrelated).widget_instance(),
+				NumberInput::new(Some(stroke.weight))
+					.unit(" px")
+					.min(0.)
+					.on_update(edited_appearance_stack(stack, node_id, move |stack, input: &NumberInput| {
+						if let AppearancePaint::Stroke(stroke) = &mut stack.0[index].paint {
+							stroke.weight = input.value.unwrap_or_default();
+						}
+					}))
+					.on_commit(commit_value)
+					.widget_instance(),
+			]);
+			widgets.push(LayoutGroup::Row { widgets: weight_row });
+		}
+
+		// The last row sets how the entry is composited over everything drawn before it
+		let blend_mode_entries = BlendMode::list_svg_subset()
+			.iter()
+			.map(|category| {
+				category
+					.iter()
+					.map(|&blend_mode| {
+						MenuListEntry::new(format!("{blend_mode:?}"))
+							.label(blend_mode.to_string())
+							.on_update(edited_appearance_stack(stack, node_id, move |stack, _: &()| stack.0[index].blend_mode = blend_mode))
+							.on_commit(commit_value)
+					})
+					.collect()
+			})
+			.collect();
+
+		let mut blending_row = vec![TextLabel::new("Blending").widget_instance()];
+		add_blank_assist(&mut blending_row);
+		blending_row.extend_from_slice(&[
+			Separator::new(SeparatorStyle::Unrelated).widget_instance(),
+			NumberInput::new(Some(entry.opacity * 100.))
+				.percentage()
+				.on_update(edited_appearance_stack(stack, node_id, move |stack, input: &NumberInput| {
+					stack.0[index].opacity = input.value.unwrap_or(100.) / 100.;
+				}))
+				.on_commit(commit_value)
+				.widget_instance(),
+			Separator::new(SeparatorStyle::Related).widget_instance(),
+			DropdownInput::new(blend_mode_entries)
+				.selected_index(entry.blend_mode.index_in_list_svg_subset().map(|index| index as u32))
+				.widget_instance(),
+		]);
+		widgets.push(LayoutGroup::Row { widgets: blending_row });
+	}
+
+	widgets
+}
+
 pub fn offset_path_properties(node_id: NodeId, context: &mut NodePropertiesContext) -> Vec<LayoutGroup> {
 	use graphene_std::vector::offset_path::*;
 
@@ -2135,13 +2537,64 @@ pub fn math_properties(node_id: NodeId, context: &mut NodePropertiesContext) ->
 	let operand_b = number_widget(ParameterWidgetsInfo::new(node_id, OperandBInput::<f64>::INDEX, true, context), NumberInput::default());
 	let operand_a_hint = vec![TextLabel::new("(Operand A is the primary input)").widget_instance()];
 
-	vec![
+	let mut layout = vec![
 		LayoutGroup::Row { widgets: expression }.with_tooltip_description(r#"A math expression that may incorporate "A" and/or "B", such as "sqrt(A + B) - B^2"."#),
 		LayoutGroup::Row { widgets: operand_b }.with_tooltip_description(r#"The value of "B" when calculating the expression."#),
-		LayoutGroup::Row { widgets: operand_a_hint }.with_tooltip_description(r#""A" is fed by the value from the previous node in the primary data flow, or it is 0 if disconnected."#),
+	];
+	layout.splice(1..1, math_expression_error_properties(node_id, context));
+	layout.extend(math_variables_properties(node_id, context));
+	layout.push(LayoutGroup::Row { widgets: operand_a_hint }.with_tooltip_description(r#""A" is fed by the value from the previous node in the primary data flow, or it is 0 if disconnected."#));
+	layout
+}
+
+/// Rows describing the error in the Math node's expression, if it has one, with the part of the expression it concerns underlined.
+fn math_expression_error_properties(node_id: NodeId, context: &mut NodePropertiesContext) -> Vec<LayoutGroup> {
+	use graphene_std::math_nodes::math::*;
+
+	let Ok(document_node) = get_document_node(node_id, context) else { return Vec::new() };
+	let Some(TaggedValue::String(expression)) = document_node.inputs.get(ExpressionInput::INDEX).and_then(|input| input.as_non_exposed_value()) else {
+		return Vec::new();
+	};
+	let Some((message, span)) = graphene_std::math_nodes::expression_error(expression) else {
+		return Vec::new();
+	};
+
+	// Count characters rather than bytes so the underline lines up with the expression above it
+	let underline_start = expression[..span.start].chars().count();
+	let underline_length = expression[span].chars().count().max(1);
+	let underlined_expression = format!("{expression}\n{}{}", " ".repeat(underline_start), "^".repeat(underline_length));
+
+	let mut message_widgets = vec![TextLabel::new("Error").widget_instance()];
+	add_blank_assist(&mut message_widgets);
+	message_widgets.extend_from_slice(&[Separator::new(SeparatorStyle::Unrelated).widget_instance(), TextLabel::new(message).widget_instance()]);
+
+	let mut underline_widgets = vec![TextLabel::new("").widget_instance()];
+	add_blank_assist(&mut underline_widgets);
+	underline_widgets.extend_from_slice(&[
+		Separator::new(SeparatorStyle::Unrelated).widget_instance(),
+		TextLabel::new(underlined_expression).monospace(true).multiline(true).widget_instance(),
+	]);
+
+	vec![
+		LayoutGroup::Row { widgets: message_widgets }.with_tooltip_description("The expression can't be calculated, so the node outputs 0 until it's fixed."),
+		LayoutGroup::Row { widgets: underline_widgets },
 	]
 }
 
+/// A row for each variable in the Math node's expression besides "A" and "B", which are the inputs after its *Variables* input.
+fn math_variables_properties(node_id: NodeId, context: &mut NodePropertiesContext) -> Vec<LayoutGroup> {
+	use graphene_std::math_nodes::math::*;
+
+	let number_of_inputs = context.network_interface.number_of_inputs(&node_id, context.selection_network_path);
+	(VariablesInput::INDEX + 1..number_of_inputs)
+		.map(|index| {
+			let (_, description) = context.network_interface.displayed_input_name_and_description(&node_id, index, context.selection_network_path);
+			let widgets = number_widget(ParameterWidgetsInfo::new(node_id, index, true, context), NumberInput::default());
+			LayoutGroup::Row { widgets }.with_tooltip_description(description)
+		})
+		.collect()
+}
+
 pub struct ParameterWidgetsInfo<'a> {
 	persistent_data: &'a PersistentData,
 	document_node: Option<&'a DocumentNode>,
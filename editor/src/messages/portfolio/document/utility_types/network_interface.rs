@@ -3520,6 +3520,77 @@ impl NodeNetworkInterface {
 		self.unload_modify_import_export(network_path);
 	}
 
+	/// Inserts a new value input at insert index of a proto node whose inputs depend on its other inputs, like the named variables of the Math node. If the insert index is -1 it is inserted at the end.
+	pub fn add_input(&mut self, node_id: &NodeId, default_value: TaggedValue, exposed: bool, insert_index: isize, input_name: &str, input_description: &str, network_path: &[NodeId]) {
+		let Some(network) = self.network_mut(network_path) else {
+			log::error!("Could not get nested network in add_input");
+			return;
+		};
+		let Some(node) = network.nodes.get_mut(node_id) else {
+			log::error!("Could not get node in add_input");
+			return;
+		};
+
+		let input = NodeInput::value(default_value, exposed);
+		if insert_index == -1 {
+			node.inputs.push(input);
+		} else {
+			node.inputs.insert(insert_index as usize, input);
+		}
+
+		self.transaction_modified();
+
+		let Some(node_metadata) = self.node_metadata_mut(node_id, network_path) else {
+			log::error!("Could not get node_metadata in add_input");
+			return;
+		};
+		let new_input = (input_name, input_description).into();
+		if insert_index == -1 {
+			node_metadata.persistent_metadata.input_metadata.push(new_input);
+		} else {
+			node_metadata.persistent_metadata.input_metadata.insert(insert_index as usize, new_input);
+		}
+
+		// Update the metadata for the node
+		self.unload_outward_wires(network_path);
+		self.unload_node_click_targets(node_id, network_path);
+		self.unload_all_nodes_bounding_box(network_path);
+	}
+
+	// First disconnects the input, then removes it
+	pub fn remove_input(&mut self, node_id: &NodeId, input_index: usize, network_path: &[NodeId]) {
+		self.disconnect_input(&InputConnector::node(*node_id, input_index), network_path);
+
+		let Some(network) = self.network_mut(network_path) else {
+			log::error!("Could not get nested network in remove_input");
+			return;
+		};
+		let Some(node) = network.nodes.get_mut(node_id) else {
+			log::error!("Could not get node in remove_input");
+			return;
+		};
+		if input_index >= node.inputs.len() {
+			log::error!("Input index {input_index} is out of bounds in remove_input");
+			return;
+		}
+		node.inputs.remove(input_index);
+
+		self.transaction_modified();
+
+		let Some(node_metadata) = self.node_metadata_mut(node_id, network_path) else {
+			log::error!("Could not get node_metadata in remove_input");
+			return;
+		};
+		if input_index < node_metadata.persistent_metadata.input_metadata.len() {
+			node_metadata.persistent_metadata.input_metadata.remove(input_index);
+		}
+
+		// Update the metadata for the node
+		self.unload_outward_wires(network_path);
+		self.unload_node_click_targets(node_id, network_path);
+		self.unload_all_nodes_bounding_box(network_path);
+	}
+
 	/// The end index is before the export is removed, so moving to the end is the length of the current exports
 	pub fn reorder_export(&mut self, start_index: usize, mut end_index: usize, network_path: &[NodeId]) {
 		let mut encapsulating_network_path = network_path.to_vec();
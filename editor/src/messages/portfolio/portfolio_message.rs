@@ -1,6 +1,6 @@
 use super::document::utility_types::document_metadata::LayerNodeIdentifier;
 use super::utility_types::PanelType;
-use crate::messages::frontend::utility_types::{ExportBounds, FileType};
+use crate::messages::frontend::utility_types::{BitDepth, ExportBounds, FileType, RasterEncoding};
 use crate::messages::portfolio::document::utility_types::clipboards::Clipboard;
 use crate::messages::portfolio::utility_types::FontCatalog;
 use crate::messages::prelude::*;
@@ -138,11 +138,20 @@ pub enum PortfolioMessage {
 		name: String,
 		file_type: FileType,
 		scale_factor: f64,
+		quality: f64,
+		bit_depth: BitDepth,
 		bounds: ExportBounds,
 		transparent_background: bool,
 		artboard_name: Option<String>,
 		artboard_count: usize,
 	},
+	EncodeRasterizedExport {
+		name: String,
+		data: Vec<u8>,
+		width: u32,
+		height: u32,
+		encoding: RasterEncoding,
+	},
 	SubmitActiveGraphRender,
 	SubmitGraphRender {
 		document_id: DocumentId,
@@ -1105,6 +1105,7 @@ impl MessageHandler<PortfolioMessage, PortfolioMessageContext<'_>> for Portfolio
 					warn!("Tried to read non existent document");
 					return;
 				};
+				responses.add(FrontendMessage::UpdateDocumentDpi { dpi: document.dpi });
 				if !document.is_loaded {
 					document.is_loaded = true;
 					responses.add(PortfolioMessage::LoadDocumentResources { document_id });
@@ -1116,6 +1117,8 @@ impl MessageHandler<PortfolioMessage, PortfolioMessageContext<'_>> for Portfolio
 				name,
 				file_type,
 				scale_factor,
+				quality,
+				bit_depth,
 				bounds,
 				transparent_background,
 				artboard_name,
@@ -1126,6 +1129,8 @@ impl MessageHandler<PortfolioMessage, PortfolioMessageContext<'_>> for Portfolio
 					name,
 					file_type,
 					scale_factor,
+					quality,
+					bit_depth,
 					bounds,
 					transparent_background,
 					artboard_name,
@@ -1141,6 +1146,13 @@ impl MessageHandler<PortfolioMessage, PortfolioMessageContext<'_>> for Portfolio
 					});
 				}
 			}
+			PortfolioMessage::EncodeRasterizedExport { name, data, width, height, encoding } => match encoding.encode(data, width, height) {
+				Ok(content) => responses.add(FrontendMessage::TriggerSaveFile { name, content }),
+				Err(description) => responses.add(DialogMessage::DisplayDialogError {
+					title: "Unable to export document".to_string(),
+					description,
+				}),
+			},
 			PortfolioMessage::SubmitActiveGraphRender => {
 				if let Some(document_id) = self.active_document_id {
 					responses.add(PortfolioMessage::SubmitGraphRender { document_id, ignore_hash: false });
@@ -15,7 +15,7 @@ use graphene_std::subpath::Subpath;
 use graphene_std::table::Table;
 use graphene_std::text::{Font, TypesettingConfig};
 use graphene_std::vector::misc::ManipulatorPointId;
-use graphene_std::vector::style::{Fill, Gradient};
+use graphene_std::vector::style::{Fill, Gradient, WidthProfile};
 use graphene_std::vector::{PointId, SegmentId, VectorModificationType};
 use std::collections::VecDeque;
 
@@ -446,6 +446,18 @@ pub fn get_stroke_width(layer: LayerNodeIdentifier, network_interface: &NodeNetw
 	}
 }
 
+/// Gets the profile of the layer's Width Profile node, if it has one.
+pub fn get_width_profile(layer: LayerNodeIdentifier, network_interface: &NodeNetworkInterface) -> Option<WidthProfile> {
+	let profile_node_input_index = graphene_std::vector::width_profile::ProfileInput::INDEX;
+	if let TaggedValue::WidthProfile(profile) =
+		NodeGraphLayer::new(layer, network_interface).find_input(&DefinitionIdentifier::ProtoNode(graphene_std::vector::width_profile::IDENTIFIER), profile_node_input_index)?
+	{
+		Some(profile.clone())
+	} else {
+		None
+	}
+}
+
 /// Checks if a specified layer uses an upstream node matching the given name.
 pub fn is_layer_fed_by_node_of_name(layer: LayerNodeIdentifier, network_interface: &NodeNetworkInterface, identifier: &DefinitionIdentifier) -> bool {
 	NodeGraphLayer::new(layer, network_interface).find_node_inputs(identifier).is_some()
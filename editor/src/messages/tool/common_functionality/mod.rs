@@ -12,3 +12,4 @@ pub mod shapes;
 pub mod snapping;
 pub mod transformation_cage;
 pub mod utility_functions;
+pub mod width_profile_gizmo;
@@ -2,7 +2,7 @@ use super::snapping::{SnapCandidatePoint, SnapData, SnapManager};
 use super::transformation_cage::{BoundingBoxManager, SizeSnapData};
 use crate::consts::ROTATE_INCREMENT;
 use crate::messages::portfolio::document::node_graph::document_node_definitions::DefinitionIdentifier;
-use crate::messages::portfolio::document::utility_types::document_metadata::LayerNodeIdentifier;
+use crate::messages::portfolio::document::utility_types::document_metadata::{DocumentMetadata, LayerNodeIdentifier};
 use crate::messages::portfolio::document::utility_types::network_interface::{NodeNetworkInterface, OutputConnector};
 use crate::messages::portfolio::document::utility_types::transformation::Selected;
 use crate::messages::prelude::*;
@@ -13,14 +13,17 @@ use crate::messages::tool::utility_types::ToolType;
 use glam::{DAffine2, DVec2};
 use graph_craft::concrete;
 use graph_craft::document::value::TaggedValue;
+use graphene_std::path_bool::path_bool_lib::{self, PathSegment};
 use graphene_std::renderer::Quad;
-use graphene_std::subpath::{Bezier, BezierHandles};
+use graphene_std::subpath::{Bezier, BezierHandles, Subpath};
 use graphene_std::table::Table;
 use graphene_std::text::FontCache;
 use graphene_std::vector::algorithms::bezpath_algorithms::pathseg_compute_lookup_table;
-use graphene_std::vector::misc::{HandleId, ManipulatorPointId, dvec2_to_point};
+use graphene_std::vector::click_target::ClickTarget;
+use graphene_std::vector::misc::{HandleId, ManipulatorPointId, dvec2_to_point, point_to_dvec2};
 use graphene_std::vector::{HandleExt, PointId, SegmentId, Vector, VectorModification, VectorModificationType};
-use kurbo::{CubicBez, DEFAULT_ACCURACY, Line, ParamCurve, PathSeg, Point, QuadBez, Shape};
+use kurbo::{Affine, CubicBez, DEFAULT_ACCURACY, Line, ParamCurve, PathSeg, Point, QuadBez, Shape};
+use std::sync::Arc;
 
 /// Determines if a path should be extended. Goal in viewport space. Returns the path and if it is extending from the start, if applicable.
 pub fn should_extend(document: &DocumentMessageHandler, goal: DVec2, tolerance: f64, layers: impl Iterator<Item = LayerNodeIdentifier>) -> Option<(LayerNodeIdentifier, PointId, DVec2)> {
@@ -590,3 +593,73 @@ pub fn make_path_editable_is_allowed(network_interface: &mut NodeNetworkInterfac
 
 	Some(first_layer)
 }
+
+/// The path of a layer in document space, as input for boolean operations.
+/// Open subpaths are closed when `close_subpaths` is set, otherwise they're left open so they can bound regions as lines.
+pub fn layer_path_in_document(metadata: &DocumentMetadata, layer: LayerNodeIdentifier, close_subpaths: bool) -> Vec<PathSegment> {
+	let transform = Affine::new(metadata.transform_to_document(layer).to_cols_array());
+	let segment = |bezier: PathSeg| match transform * bezier {
+		PathSeg::Line(line) => PathSegment::Line(point_to_dvec2(line.p0), point_to_dvec2(line.p1)),
+		PathSeg::Quad(quad_bez) => PathSegment::Quadratic(point_to_dvec2(quad_bez.p0), point_to_dvec2(quad_bez.p1), point_to_dvec2(quad_bez.p2)),
+		PathSeg::Cubic(cubic_bez) => PathSegment::Cubic(point_to_dvec2(cubic_bez.p0), point_to_dvec2(cubic_bez.p1), point_to_dvec2(cubic_bez.p2), point_to_dvec2(cubic_bez.p3)),
+	};
+
+	metadata
+		.layer_outline(layer)
+		.flat_map(|subpath| if close_subpaths { subpath.iter_closed() } else { subpath.iter() })
+		.map(segment)
+		.collect()
+}
+
+/// The transform and the click targets of a layer, which are replaced whenever the graph output of the layer changes.
+#[derive(Clone, Debug)]
+pub struct LayerSource {
+	transform: DAffine2,
+	click_targets: Vec<Arc<ClickTarget>>,
+}
+
+impl LayerSource {
+	pub fn new(metadata: &DocumentMetadata, layer: LayerNodeIdentifier) -> Self {
+		Self {
+			transform: metadata.transform_to_document(layer),
+			click_targets: metadata.click_targets(layer).unwrap_or_default().to_vec(),
+		}
+	}
+
+	/// Compares the click targets by identity, which is enough to tell that the document was changed without comparing the geometry itself.
+	pub fn is_unchanged(&self, other: &Self) -> bool {
+		self.transform == other.transform
+			&& self.click_targets.len() == other.click_targets.len()
+			&& self.click_targets.iter().zip(&other.click_targets).all(|(target, other_target)| Arc::ptr_eq(target, other_target))
+	}
+}
+
+/// Splits the result of a boolean operation into closed subpaths wherever one segment doesn't continue from the end of the last.
+pub fn path_to_subpaths(path: &[PathSegment]) -> Vec<Subpath<PointId>> {
+	let segment = |bezier: PathSegment| match bezier {
+		PathSegment::Line(start, end) => PathSeg::Line(Line::new(dvec2_to_point(start), dvec2_to_point(end))),
+		PathSegment::Cubic(start, h1, h2, end) => PathSeg::Cubic(CubicBez::new(dvec2_to_point(start), dvec2_to_point(h1), dvec2_to_point(h2), dvec2_to_point(end))),
+		PathSegment::Quadratic(start, h1, end) => PathSeg::Quad(QuadBez::new(dvec2_to_point(start), dvec2_to_point(h1), dvec2_to_point(end))),
+		// Arcs are already approximated by cubic segments below, so this is never reached
+		PathSegment::Arc(start, _, _, _, _, _, end) => PathSeg::Line(Line::new(dvec2_to_point(start), dvec2_to_point(end))),
+	};
+
+	let mut subpaths = Vec::new();
+	let mut beziers = Vec::new();
+	for (index, bezier) in path.iter().enumerate() {
+		beziers.extend(bezier.arc_segment_to_cubics(std::f64::consts::FRAC_PI_2).into_iter().map(segment));
+
+		let next_start = path.get(index + 1).map(PathSegment::start);
+		if next_start.is_none_or(|start| !start.abs_diff_eq(bezier.end(), path_bool_lib::EPS.point)) {
+			// A closed subpath needs at least two segments, so a lone loop is split in half
+			if let [bezier] = beziers[..] {
+				let (first, second) = bezier.subdivide();
+				beziers = vec![first, second];
+			}
+			subpaths.push(Subpath::from_beziers(&beziers, true));
+			beziers.clear();
+		}
+	}
+
+	subpaths
+}
@@ -0,0 +1,164 @@
+use crate::consts::{COLOR_OVERLAY_BLUE, SELECTION_THRESHOLD};
+use crate::messages::portfolio::document::overlays::utility_types::OverlayContext;
+use crate::messages::portfolio::document::utility_types::document_metadata::LayerNodeIdentifier;
+use crate::messages::prelude::*;
+use crate::messages::tool::common_functionality::graph_modification_utils;
+use glam::{DAffine2, DVec2};
+use graphene_std::vector::algorithms::width_profile::{width_profile_frame, width_profile_nearest_position};
+use graphene_std::vector::style::{WidthPoint, WidthProfile};
+use kurbo::{Affine, BezPath};
+
+/// The side of the path whose width is adjusted by a width handle, relative to the direction of travel along the path.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum WidthSide {
+	Left,
+	Right,
+}
+
+/// A handle at the edge of the stroke on one side of a width point, drawn on one of the layer's subpaths.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct WidthHandle {
+	/// The index of the width point within the profile.
+	pub index: usize,
+	pub side: WidthSide,
+	/// The viewport position on the path where the width point lies.
+	pub anchor: DVec2,
+	/// The viewport position of the handle at the edge of the stroke.
+	pub position: DVec2,
+}
+
+/// The width points of a layer's Width Profile node, drawn as handles at the edges of the stroke along each of its subpaths.
+///
+/// The subpaths are kept in the space of the stroke's transform, where its weight applies.
+#[derive(Clone, Debug)]
+pub struct WidthProfileGizmo {
+	pub layer: LayerNodeIdentifier,
+	pub profile: WidthProfile,
+	half_weight: f64,
+	subpaths: Vec<BezPath>,
+	stroke_to_viewport: DAffine2,
+}
+
+impl WidthProfileGizmo {
+	/// Sets up the gizmo for a layer fed by a Width Profile node which has a stroke with some weight.
+	pub fn new(layer: LayerNodeIdentifier, document: &DocumentMessageHandler) -> Option<Self> {
+		let profile = graph_modification_utils::get_width_profile(layer, &document.network_interface)?;
+		let vector = document.metadata().layer_vector_data.get(&layer)?;
+		let stroke = vector.style.stroke()?;
+		if stroke.weight <= 0. {
+			return None;
+		}
+
+		let stroke_transform = if stroke.transform.matrix2.determinant() != 0. { stroke.transform } else { DAffine2::IDENTITY };
+		let subpaths = vector
+			.stroke_bezpath_iter()
+			.map(|mut bezpath| {
+				bezpath.apply_affine(Affine::new(stroke_transform.to_cols_array()));
+				bezpath
+			})
+			.collect();
+
+		Some(Self {
+			layer,
+			profile,
+			half_weight: stroke.weight / 2.,
+			subpaths,
+			stroke_to_viewport: document.metadata().transform_to_viewport(layer) * stroke_transform.inverse(),
+		})
+	}
+
+	/// The handles on both sides of every width point along every subpath.
+	pub fn handles(&self) -> impl Iterator<Item = WidthHandle> + '_ {
+		self.subpaths.iter().flat_map(move |subpath| {
+			self.profile.iter().enumerate().flat_map(move |(index, point)| {
+				let (anchor, normal) = width_profile_frame(subpath, point.position)?;
+				let handle = |side, offset: DVec2| WidthHandle {
+					index,
+					side,
+					anchor: self.stroke_to_viewport.transform_point2(anchor),
+					position: self.stroke_to_viewport.transform_point2(anchor + offset),
+				};
+				Some([
+					handle(WidthSide::Left, normal * point.left * self.half_weight),
+					handle(WidthSide::Right, -normal * point.right * self.half_weight),
+				])
+			})
+		})
+	}
+
+	/// The handle nearest to the viewport position, if any are close enough to be grabbed.
+	pub fn handle_under(&self, viewport_position: DVec2) -> Option<WidthHandle> {
+		self.handles()
+			.map(|handle| (handle.position.distance_squared(viewport_position), handle))
+			.filter(|&(distance_squared, _)| distance_squared <= SELECTION_THRESHOLD * SELECTION_THRESHOLD)
+			.min_by(|(a, _), (b, _)| a.total_cmp(b))
+			.map(|(_, handle)| handle)
+	}
+
+	/// Adds a width point with the widths already found there, if the viewport position lies on an edge of the stroke away from the path itself.
+	///
+	/// Returns the handle for the side of the new width point whose edge was clicked.
+	pub fn insert_point_on_edge(&mut self, viewport_position: DVec2) -> Option<WidthHandle> {
+		let viewport_to_stroke = self.stroke_to_viewport.inverse();
+		let stroke_position = viewport_to_stroke.transform_point2(viewport_position);
+
+		let (subpath, (position, _)) = self
+			.subpaths
+			.iter()
+			.filter_map(|subpath| Some((subpath, width_profile_nearest_position(subpath, stroke_position)?)))
+			.min_by(|(_, (_, a)), (_, (_, b))| a.total_cmp(b))?;
+		let (anchor, normal) = width_profile_frame(subpath, position)?;
+		let (left, right) = self.profile.evaluate(position);
+
+		let viewport_anchor = self.stroke_to_viewport.transform_point2(anchor);
+		if viewport_anchor.distance(viewport_position) <= SELECTION_THRESHOLD {
+			return None;
+		}
+
+		let side = [(WidthSide::Left, normal * left), (WidthSide::Right, -normal * right)].into_iter().find_map(|(side, offset)| {
+			let edge = self.stroke_to_viewport.transform_point2(anchor + offset * self.half_weight);
+			(edge.distance(viewport_position) <= SELECTION_THRESHOLD).then_some(side)
+		})?;
+
+		let index = self.profile.insert(WidthPoint::new(position, left, right));
+		self.handles().find(|handle| handle.index == index && handle.side == side)
+	}
+
+	/// Moves the handle's edge of the stroke to the viewport position, measured outward from the path, and optionally moves the opposite edge to match.
+	pub fn drag_handle(&mut self, handle: WidthHandle, viewport_position: DVec2, both_sides: bool) {
+		let Some(point) = self.profile.0.get(handle.index).copied() else { return };
+
+		// Measure from the subpath the handle was grabbed on, since the handles on each subpath adjust the same width point
+		let Some((anchor, normal)) = self
+			.subpaths
+			.iter()
+			.filter_map(|subpath| width_profile_frame(subpath, point.position))
+			.map(|(anchor, normal)| (self.stroke_to_viewport.transform_point2(anchor).distance_squared(handle.anchor), anchor, normal))
+			.min_by(|(a, ..), (b, ..)| a.total_cmp(b))
+			.map(|(_, anchor, normal)| (anchor, normal))
+		else {
+			return;
+		};
+
+		let outward = if handle.side == WidthSide::Left { normal } else { -normal };
+		let stroke_position = self.stroke_to_viewport.inverse().transform_point2(viewport_position);
+		let width = ((stroke_position - anchor).dot(outward) / self.half_weight).max(0.);
+
+		let point = &mut self.profile.0[handle.index];
+		if both_sides || handle.side == WidthSide::Left {
+			point.left = width;
+		}
+		if both_sides || handle.side == WidthSide::Right {
+			point.right = width;
+		}
+	}
+
+	/// Draws a line out to each handle from its width point on the path, with the active handle shown as selected.
+	pub fn draw(&self, overlay_context: &mut OverlayContext, active: Option<WidthHandle>) {
+		for handle in self.handles() {
+			let selected = active.is_some_and(|active| active.index == handle.index && active.side == handle.side);
+			overlay_context.line(handle.anchor, handle.position, Some(COLOR_OVERLAY_BLUE), None);
+			overlay_context.manipulator_handle(handle.position, selected, None);
+		}
+	}
+}
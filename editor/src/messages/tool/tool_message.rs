@@ -35,6 +35,8 @@ pub enum ToolMessage {
 	Shape(ShapeToolMessage),
 	#[child]
 	Text(TextToolMessage),
+	#[child]
+	ShapeBuilder(ShapeBuilderToolMessage),
 
 	#[child]
 	Brush(BrushToolMessage),
@@ -68,6 +70,7 @@ pub enum ToolMessage {
 	ActivateToolShapeEllipse,
 	ActivateToolShape,
 	ActivateToolText,
+	ActivateToolShapeBuilder,
 	// Raster tools
 	ActivateToolBrush,
 
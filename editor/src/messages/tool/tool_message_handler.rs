@@ -80,6 +80,7 @@ impl MessageHandler<ToolMessage, ToolMessageContext<'_>> for ToolMessageHandler
 				responses.add(ShapeToolMessage::SetShape { shape: ShapeType::Polygon });
 				responses.add(ShapeToolMessage::HideShapeTypeWidget { hide: false })
 			}
+			ToolMessage::ActivateToolShapeBuilder => responses.add_front(ToolMessage::ActivateTool { tool_type: ToolType::ShapeBuilder }),
 			ToolMessage::ActivateToolBrush => responses.add_front(ToolMessage::ActivateTool { tool_type: ToolType::Brush }),
 			ToolMessage::ActivateToolShapeLine | ToolMessage::ActivateToolShapeRectangle | ToolMessage::ActivateToolShapeEllipse => {
 				let shape = match message {
@@ -383,6 +384,7 @@ impl MessageHandler<ToolMessage, ToolMessageContext<'_>> for ToolMessageHandler
 			ActivateToolShapeEllipse,
 			ActivateToolShape,
 			ActivateToolText,
+			ActivateToolShapeBuilder,
 
 			ToggleSelectVsPath,
 
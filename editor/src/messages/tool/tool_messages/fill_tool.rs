@@ -1,15 +1,48 @@
 use super::tool_prelude::*;
+use crate::consts::DEFAULT_FILL_GAP_TOLERANCE;
 use crate::messages::portfolio::document::overlays::utility_types::OverlayContext;
+use crate::messages::portfolio::document::utility_types::document_metadata::LayerNodeIdentifier;
 use crate::messages::tool::common_functionality::graph_modification_utils::NodeGraphLayer;
-use graphene_std::vector::style::Fill;
+use crate::messages::tool::common_functionality::utility_functions::{LayerSource, layer_path_in_document, path_to_subpaths};
+use graph_craft::document::NodeId;
+use graphene_std::path_bool::path_bool_lib;
+use graphene_std::subpath::Subpath;
+use graphene_std::vector::PointId;
+use graphene_std::vector::style::{Fill, Stroke};
 
 #[derive(Default, ExtractField)]
 pub struct FillTool {
 	fsm_state: FillToolFsmState,
+	data: FillToolData,
+	options: FillOptions,
+}
+
+pub struct FillOptions {
+	fill_mode: FillMode,
+	gap_tolerance: f64,
+}
+
+impl Default for FillOptions {
+	fn default() -> Self {
+		Self {
+			fill_mode: FillMode::default(),
+			gap_tolerance: DEFAULT_FILL_GAP_TOLERANCE,
+		}
+	}
+}
+
+/// What a click of the Fill tool applies the color to.
+#[derive(PartialEq, Eq, Clone, Copy, Debug, Default, Hash, serde::Serialize, serde::Deserialize, specta::Type)]
+pub enum FillMode {
+	/// Sets the fill of the clicked layer.
+	#[default]
+	Layer,
+	/// Creates a new filled shape from the region enclosed by the paths around the click, even where they are open strokes.
+	Region,
 }
 
 #[impl_message(Message, ToolMessage, Fill)]
-#[derive(PartialEq, Clone, Debug, Hash, serde::Serialize, serde::Deserialize, specta::Type)]
+#[derive(PartialEq, Clone, Debug, serde::Serialize, serde::Deserialize, specta::Type)]
 pub enum FillToolMessage {
 	// Standard messages
 	Abort,
@@ -21,6 +54,13 @@ pub enum FillToolMessage {
 	PointerUp,
 	FillPrimaryColor,
 	FillSecondaryColor,
+	UpdateOptions { options: FillOptionsUpdate },
+}
+
+#[derive(PartialEq, Clone, Debug, serde::Serialize, serde::Deserialize, specta::Type)]
+pub enum FillOptionsUpdate {
+	FillMode(FillMode),
+	GapTolerance(f64),
 }
 
 impl ToolMetadata for FillTool {
@@ -37,14 +77,62 @@ impl ToolMetadata for FillTool {
 
 impl LayoutHolder for FillTool {
 	fn layout(&self) -> Layout {
-		Layout::default()
+		let fill_mode = RadioInput::new(vec![
+			RadioEntryData::new("Layer").label("Layer").tooltip_label("Fill Layer").on_update(move |_| {
+				FillToolMessage::UpdateOptions {
+					options: FillOptionsUpdate::FillMode(FillMode::Layer),
+				}
+				.into()
+			}),
+			RadioEntryData::new("Region")
+				.label("Region")
+				.tooltip_label("Fill Region")
+				.tooltip_description("Create a new filled shape from the area enclosed by the surrounding paths, including open strokes.")
+				.on_update(move |_| {
+					FillToolMessage::UpdateOptions {
+						options: FillOptionsUpdate::FillMode(FillMode::Region),
+					}
+					.into()
+				}),
+		])
+		.selected_index(Some((self.options.fill_mode == FillMode::Region) as u32))
+		.widget_instance();
+
+		let gap_tolerance = NumberInput::new(Some(self.options.gap_tolerance))
+			.unit(" px")
+			.label("Gap")
+			.tooltip_label("Gap Tolerance")
+			.tooltip_description("The distance by which the ends of paths may fall short of meeting while still enclosing a region.")
+			.min(0.)
+			.disabled(self.options.fill_mode != FillMode::Region)
+			.on_update(|number_input: &NumberInput| {
+				FillToolMessage::UpdateOptions {
+					options: FillOptionsUpdate::GapTolerance(number_input.value.unwrap()),
+				}
+				.into()
+			})
+			.widget_instance();
+
+		Layout(vec![LayoutGroup::Row {
+			widgets: vec![fill_mode, Separator::new(SeparatorStyle::Unrelated).widget_instance(), gap_tolerance],
+		}])
 	}
 }
 
 #[message_handler_data]
 impl<'a> MessageHandler<ToolMessage, &mut ToolActionMessageContext<'a>> for FillTool {
 	fn process_message(&mut self, message: ToolMessage, responses: &mut VecDeque<Message>, context: &mut ToolActionMessageContext<'a>) {
-		self.fsm_state.process_event(message, &mut (), context, &(), responses, true);
+		let ToolMessage::Fill(FillToolMessage::UpdateOptions { options }) = message else {
+			self.fsm_state.process_event(message, &mut self.data, context, &self.options, responses, true);
+			return;
+		};
+		match options {
+			FillOptionsUpdate::FillMode(fill_mode) => self.options.fill_mode = fill_mode,
+			FillOptionsUpdate::GapTolerance(gap_tolerance) => self.options.gap_tolerance = gap_tolerance,
+		}
+
+		self.send_layout(responses, LayoutTarget::ToolOptions);
+		responses.add(OverlaysMessage::Draw);
 	}
 	fn actions(&self) -> ActionList {
 		match self.fsm_state {
@@ -81,18 +169,117 @@ enum FillToolFsmState {
 	Filling,
 }
 
+#[derive(Clone, Debug, Default)]
+struct FillToolData {
+	/// The layers whose paths the regions are formed by, from top to bottom.
+	layers: Vec<LayerNodeIdentifier>,
+	/// The sources of `layers` when the regions were found, which are kept along with the gap tolerance to tell when the regions need to be found again.
+	sources: Vec<LayerSource>,
+	gap_tolerance: f64,
+	/// The regions enclosed by the paths, in document space.
+	regions: Vec<Vec<Subpath<PointId>>>,
+}
+
+impl FillToolData {
+	/// Finds the regions enclosed by the paths of the visible layers around the cursor, unless neither those layers nor their paths have changed since the last time.
+	/// These are the layers whose bounds contain the cursor, which a region around the cursor lies within, and the other layers overlapping their bounds which may split it.
+	fn update_regions(&mut self, document: &mut DocumentMessageHandler, input: &InputPreprocessorMessageHandler, gap_tolerance: f64) {
+		let mouse = document.metadata().document_to_viewport.inverse().transform_point2(input.mouse.position);
+		let selected_nodes = document.network_interface.selected_nodes();
+		let candidates = document
+			.metadata()
+			.all_layers()
+			.filter(|&layer| selected_nodes.layer_visible(layer, &document.network_interface) && !selected_nodes.layer_locked(layer, &document.network_interface))
+			.filter(|layer| !layer.has_children(document.metadata()) && !document.network_interface.is_artboard(&layer.to_node(), &[]))
+			.filter_map(|layer| Some((layer, document.metadata().bounding_box_document(layer)?)))
+			.collect::<Vec<_>>();
+
+		let contains_mouse = |[min, max]: [DVec2; 2]| min.cmple(mouse).all() && mouse.cmple(max).all();
+		let hit_bounds = candidates
+			.iter()
+			.map(|&(_, bounds)| bounds)
+			.filter(|&bounds| contains_mouse(bounds))
+			.reduce(|[min, max], [other_min, other_max]| [min.min(other_min), max.max(other_max)]);
+		let overlaps_hit_bounds = |[min, max]: [DVec2; 2]| hit_bounds.is_some_and(|[hit_min, hit_max]| min.cmple(hit_max).all() && hit_min.cmple(max).all());
+
+		let mut layers = candidates.into_iter().filter(|&(_, bounds)| overlaps_hit_bounds(bounds)).map(|(layer, _)| layer).collect::<Vec<_>>();
+		layers.retain(|&layer| !NodeGraphLayer::is_raster_layer(layer, &mut document.network_interface));
+
+		let sources = layers.iter().map(|&layer| LayerSource::new(document.metadata(), layer)).collect::<Vec<_>>();
+		if layers == self.layers
+			&& gap_tolerance == self.gap_tolerance
+			&& sources.len() == self.sources.len()
+			&& sources.iter().zip(&self.sources).all(|(source, old_source)| source.is_unchanged(old_source))
+		{
+			return;
+		}
+
+		let outlines = layers.iter().map(|&layer| layer_path_in_document(document.metadata(), layer, false)).collect::<Vec<_>>();
+		let paths = outlines.iter().collect::<Vec<_>>();
+		let regions = path_bool_lib::path_regions(&paths, gap_tolerance).unwrap_or_else(|error| {
+			log::error!("Boolean error {error:?} encountered while finding the regions enclosed by {} layers", layers.len());
+			Vec::new()
+		});
+
+		self.regions = regions.iter().map(|region| path_to_subpaths(region)).collect();
+		self.layers = layers;
+		self.sources = sources;
+		self.gap_tolerance = gap_tolerance;
+	}
+
+	fn hovered_region(&self, document: &DocumentMessageHandler, input: &InputPreprocessorMessageHandler) -> Option<&[Subpath<PointId>]> {
+		let mouse = document.metadata().document_to_viewport.inverse().transform_point2(input.mouse.position);
+		let contains = |region: &&Vec<Subpath<PointId>>| region.iter().filter(|subpath| subpath.contains_point(mouse)).count() % 2 == 1;
+		self.regions.iter().find(contains).map(|region| region.as_slice())
+	}
+}
+
+/// Adds a new layer with the given fill and the shape of a region, in document space, directly below the bottommost layer whose bounds overlap the region.
+fn insert_region_layer(region: &[Subpath<PointId>], fill: Fill, layers: &[LayerNodeIdentifier], document: &DocumentMessageHandler, responses: &mut VecDeque<Message>) {
+	let metadata = document.metadata();
+	let Some([region_min, region_max]) = region.first().and_then(|outline| outline.bounding_box()) else {
+		return;
+	};
+	let overlaps_region = |layer: &&LayerNodeIdentifier| {
+		metadata
+			.bounding_box_document(**layer)
+			.is_some_and(|[min, max]| min.cmple(region_max).all() && region_min.cmple(max).all())
+	};
+	let Some(&layer) = layers.iter().rev().find(overlaps_region) else { return };
+	let Some(parent) = layer.parent(metadata) else { return };
+	let Some(insert_index) = parent.children(metadata).position(|child| child == layer) else { return };
+
+	let document_to_parent = metadata.downstream_transform_to_document(layer).inverse();
+	let subpaths = region
+		.iter()
+		.map(|subpath| {
+			let mut subpath = subpath.clone();
+			subpath.apply_transform(document_to_parent);
+			subpath
+		})
+		.collect();
+
+	let id = NodeId::new();
+	let new_layer = LayerNodeIdentifier::new_unchecked(id);
+	responses.add(GraphOperationMessage::NewVectorLayer {
+		id,
+		subpaths,
+		parent,
+		insert_index: insert_index + 1,
+	});
+	responses.add(GraphOperationMessage::FillSet { layer: new_layer, fill });
+	responses.add(GraphOperationMessage::StrokeSet {
+		layer: new_layer,
+		stroke: Stroke::default(),
+	});
+	responses.add(NodeGraphMessage::SelectedNodesSet { nodes: vec![id] });
+}
+
 impl Fsm for FillToolFsmState {
-	type ToolData = ();
-	type ToolOptions = ();
-
-	fn transition(
-		self,
-		event: ToolMessage,
-		_tool_data: &mut Self::ToolData,
-		handler_data: &mut ToolActionMessageContext,
-		_tool_options: &Self::ToolOptions,
-		responses: &mut VecDeque<Message>,
-	) -> Self {
+	type ToolData = FillToolData;
+	type ToolOptions = FillOptions;
+
+	fn transition(self, event: ToolMessage, tool_data: &mut Self::ToolData, handler_data: &mut ToolActionMessageContext, tool_options: &Self::ToolOptions, responses: &mut VecDeque<Message>) -> Self {
 		let ToolActionMessageContext {
 			document,
 			global_tool_data,
@@ -108,9 +295,20 @@ impl Fsm for FillToolFsmState {
 				let use_secondary = input.keyboard.get(Key::Shift as usize);
 				let preview_color = if use_secondary { global_tool_data.secondary_color } else { global_tool_data.primary_color };
 
-				// Get the layer the user is hovering over
-				if let Some(layer) = document.click(input, viewport) {
-					overlay_context.fill_path_pattern(document.metadata().layer_outline(layer), document.metadata().transform_to_viewport(layer), &preview_color);
+				match tool_options.fill_mode {
+					FillMode::Layer => {
+						// Get the layer the user is hovering over
+						if let Some(layer) = document.click(input, viewport) {
+							overlay_context.fill_path_pattern(document.metadata().layer_outline(layer), document.metadata().transform_to_viewport(layer), &preview_color);
+						}
+					}
+					FillMode::Region => {
+						// Get the region the user is hovering over
+						tool_data.update_regions(document, input, tool_options.gap_tolerance);
+						if let Some(region) = tool_data.hovered_region(document, input) {
+							overlay_context.fill_path_pattern(region.iter(), document.metadata().document_to_viewport, &preview_color);
+						}
+					}
 				}
 
 				self
@@ -121,6 +319,24 @@ impl Fsm for FillToolFsmState {
 				self
 			}
 			(FillToolFsmState::Ready, color_event) => {
+				let fill = match color_event {
+					FillToolMessage::FillPrimaryColor => Fill::Solid(global_tool_data.primary_color.to_gamma_srgb()),
+					FillToolMessage::FillSecondaryColor => Fill::Solid(global_tool_data.secondary_color.to_gamma_srgb()),
+					_ => return self,
+				};
+
+				if tool_options.fill_mode == FillMode::Region {
+					tool_data.update_regions(document, input, tool_options.gap_tolerance);
+					let Some(region) = tool_data.hovered_region(document, input) else {
+						return self;
+					};
+
+					responses.add(DocumentMessage::AddTransaction);
+					insert_region_layer(region, fill, &tool_data.layers, document, responses);
+
+					return FillToolFsmState::Filling;
+				}
+
 				let Some(layer_identifier) = document.click(input, viewport) else {
 					return self;
 				};
@@ -128,11 +344,6 @@ impl Fsm for FillToolFsmState {
 				if NodeGraphLayer::is_raster_layer(layer_identifier, &mut document.network_interface) {
 					return self;
 				}
-				let fill = match color_event {
-					FillToolMessage::FillPrimaryColor => Fill::Solid(global_tool_data.primary_color.to_gamma_srgb()),
-					FillToolMessage::FillSecondaryColor => Fill::Solid(global_tool_data.secondary_color.to_gamma_srgb()),
-					_ => return self,
-				};
 
 				responses.add(DocumentMessage::AddTransaction);
 				responses.add(GraphOperationMessage::FillSet { layer: layer_identifier, fill });
@@ -168,6 +379,7 @@ impl Fsm for FillToolFsmState {
 
 #[cfg(test)]
 mod test_fill {
+	use super::{FillMode, FillOptionsUpdate};
 	pub use crate::test_utils::test_prelude::*;
 	use graphene_std::vector::fill;
 	use graphene_std::vector::style::Fill;
@@ -222,4 +434,58 @@ mod test_fill {
 		assert_eq!(fills.len(), 1);
 		assert_eq!(fills[0].as_solid().unwrap().to_rgba8_srgb(), Color::YELLOW.to_rgba8_srgb());
 	}
+
+	/// Fills the region at the given position formed by two squares, from 0 to 100 and from 50 to 150, which overlap between 50 and 100.
+	async fn fill_region_of_overlapping_squares(x: f64) -> EditorTestUtils {
+		let mut editor = EditorTestUtils::create();
+		editor.new_document().await;
+		editor.draw_rect(0., 0., 100., 100.).await;
+		editor.draw_rect(50., 0., 150., 100.).await;
+		editor.select_primary_color(Color::GREEN).await;
+		editor.select_tool(ToolType::Fill).await;
+		editor
+			.handle_message(FillToolMessage::UpdateOptions {
+				options: FillOptionsUpdate::FillMode(FillMode::Region),
+			})
+			.await;
+		editor.click_tool(ToolType::Fill, MouseKeys::LEFT, DVec2::new(x, 50.), ModifierKeys::empty()).await;
+		editor
+	}
+
+	/// The bounding box of the layer created for the filled region, which is left selected.
+	fn filled_region_bounds(editor: &EditorTestUtils) -> [DVec2; 2] {
+		let document = editor.active_document();
+		let metadata = document.metadata();
+		assert_eq!(metadata.all_layers().count(), 3, "The region should be filled by a new layer beside the squares");
+
+		let mut selected_layers = document.network_interface.selected_nodes().selected_layers(metadata).collect::<Vec<_>>();
+		assert_eq!(selected_layers.len(), 1);
+		metadata.bounding_box_document(selected_layers.remove(0)).unwrap()
+	}
+
+	#[tokio::test]
+	async fn region_of_overlap() {
+		let mut editor = fill_region_of_overlapping_squares(75.).await;
+
+		let [min, max] = filled_region_bounds(&editor);
+		assert!(
+			min.abs_diff_eq(DVec2::new(50., 0.), 1e-3) && max.abs_diff_eq(DVec2::new(100., 100.), 1e-3),
+			"Got the region {min} to {max}"
+		);
+
+		let fills = get_fills(&mut editor).await;
+		assert!(fills.iter().any(|fill| fill.as_solid().is_some_and(|color| color.to_rgba8_srgb() == Color::GREEN.to_rgba8_srgb())));
+	}
+
+	#[tokio::test]
+	async fn region_cut_by_a_layer_not_under_the_cursor() {
+		// Only the left square is under the cursor, but the edge of the right square still bounds the region
+		let editor = fill_region_of_overlapping_squares(25.).await;
+
+		let [min, max] = filled_region_bounds(&editor);
+		assert!(
+			min.abs_diff_eq(DVec2::new(0., 0.), 1e-3) && max.abs_diff_eq(DVec2::new(50., 100.), 1e-3),
+			"Got the region {min} to {max}"
+		);
+	}
 }
@@ -5,7 +5,7 @@ use crate::messages::portfolio::document::utility_types::document_metadata::Laye
 use crate::messages::tool::common_functionality::auto_panning::AutoPanning;
 use crate::messages::tool::common_functionality::graph_modification_utils::{NodeGraphLayer, get_gradient};
 use crate::messages::tool::common_functionality::snapping::SnapManager;
-use graphene_std::vector::style::{Fill, Gradient, GradientType};
+use graphene_std::vector::style::{DEFAULT_GRADIENT_MIDPOINT, Fill, Gradient, GradientStops, GradientType};
 
 #[derive(Default, ExtractField)]
 pub struct GradientTool {
@@ -120,22 +120,21 @@ impl<'a> MessageHandler<ToolMessage, &mut ToolActionMessageContext<'a>> for Grad
 
 impl LayoutHolder for GradientTool {
 	fn layout(&self) -> Layout {
-		let gradient_type = RadioInput::new(vec![
-			RadioEntryData::new("Linear").label("Linear").tooltip_label("Linear Gradient").on_update(move |_| {
-				GradientToolMessage::UpdateOptions {
-					options: GradientOptionsUpdate::Type(GradientType::Linear),
-				}
-				.into()
-			}),
-			RadioEntryData::new("Radial").label("Radial").tooltip_label("Radial Gradient").on_update(move |_| {
-				GradientToolMessage::UpdateOptions {
-					options: GradientOptionsUpdate::Type(GradientType::Radial),
-				}
-				.into()
-			}),
-		])
-		.selected_index(Some((self.options.gradient_type == GradientType::Radial) as u32))
-		.widget_instance();
+		let entries = [GradientType::Linear, GradientType::Radial, GradientType::Reflected, GradientType::Conic, GradientType::Diamond]
+			.into_iter()
+			.map(|gradient_type| {
+				RadioEntryData::new(format!("{gradient_type:?}"))
+					.label(format!("{gradient_type:?}"))
+					.tooltip_label(format!("{gradient_type:?} Gradient"))
+					.on_update(move |_| {
+						GradientToolMessage::UpdateOptions {
+							options: GradientOptionsUpdate::Type(gradient_type),
+						}
+						.into()
+					})
+			})
+			.collect();
+		let gradient_type = RadioInput::new(entries).selected_index(Some(self.options.gradient_type as u32)).widget_instance();
 
 		Layout(vec![LayoutGroup::Row { widgets: vec![gradient_type] }])
 	}
@@ -169,7 +168,13 @@ pub enum GradientDragTarget {
 	#[default]
 	End,
 	Step(usize),
+	/// The midpoint of the blend between the stop at the index and the next one.
+	Midpoint(usize),
 	New,
+	/// The end of the minor axis, for gradient types which have one.
+	MinorAxis,
+	/// The focal point of a radial gradient.
+	Focal,
 }
 
 /// Contains information about the selected gradient handle
@@ -182,14 +187,24 @@ struct SelectedGradient {
 	initial_gradient: Gradient,
 }
 
-fn calculate_insertion(start: DVec2, end: DVec2, stops: &[(f64, graphene_std::Color)], mouse: DVec2) -> Option<f64> {
+/// The viewport positions of the midpoint handles between each pair of stops, leaving out those of spans too short to fit one between their stops.
+fn midpoint_handles(start: DVec2, end: DVec2, stops: &GradientStops) -> impl Iterator<Item = (usize, DVec2)> + '_ {
+	(0..stops.len().saturating_sub(1)).filter_map(move |index| {
+		let span_length = (stops[index + 1].0 - stops[index].0) * start.distance(end);
+		let position = stops.midpoint_position(index)?;
+		(span_length > MANIPULATOR_GROUP_MARKER_SIZE * 4.).then(|| (index, start.lerp(end, position)))
+	})
+}
+
+fn calculate_insertion(start: DVec2, end: DVec2, stops: &GradientStops, mouse: DVec2) -> Option<f64> {
 	let distance = (end - start).angle_to(mouse - start).sin() * (mouse - start).length();
 	let projection = ((end - start).angle_to(mouse - start)).cos() * start.distance(mouse) / start.distance(end);
 
 	if distance.abs() < SEGMENT_INSERTION_DISTANCE && (0. ..=1.).contains(&projection) {
-		for (position, _) in stops {
-			let stop_pos = start.lerp(end, *position);
-			if stop_pos.distance_squared(mouse) < (MANIPULATOR_GROUP_MARKER_SIZE * 2.).powi(2) {
+		let stop_positions = stops.iter().map(|(position, _)| start.lerp(end, *position));
+		let midpoint_positions = midpoint_handles(start, end, stops).map(|(_, position)| position);
+		for handle_pos in stop_positions.chain(midpoint_positions) {
+			if handle_pos.distance_squared(mouse) < (MANIPULATOR_GROUP_MARKER_SIZE * 2.).powi(2) {
 				return None;
 			}
 		}
@@ -254,6 +269,21 @@ impl SelectedGradient {
 				self.gradient.start = self.transform.inverse().transform_point2(drag_start);
 				self.gradient.end = transformed_mouse;
 			}
+			GradientDragTarget::MinorAxis => {
+				let (start, end) = (self.transform.transform_point2(self.gradient.start), self.transform.transform_point2(self.gradient.end));
+				if let Some(direction) = (end - start).try_normalize() {
+					self.gradient.minor_axis_ratio = (mouse - start).dot(direction.perp()).abs() / start.distance(end);
+				}
+			}
+			GradientDragTarget::Focal => {
+				let unit_transform = self.gradient.unit_transform(self.transform);
+				if unit_transform.matrix2.determinant() != 0. {
+					// Keep the focal point within the gradient's ellipse, outside of which the gradient would become a cone
+					let focal_point = unit_transform.inverse().transform_point2(mouse).clamp_length_max(1.);
+					let focal_point = unit_transform.transform_point2(focal_point);
+					self.gradient.focal_offset = self.transform.inverse().transform_point2(focal_point) - self.gradient.start;
+				}
+			}
 			GradientDragTarget::Step(s) => {
 				let (start, end) = (self.transform.transform_point2(self.gradient.start), self.transform.transform_point2(self.gradient.end));
 
@@ -268,6 +298,18 @@ impl SelectedGradient {
 				self.gradient.stops.sort();
 				self.dragging = GradientDragTarget::Step(self.gradient.stops.iter().position(|x| *x == new_pos).unwrap());
 			}
+			GradientDragTarget::Midpoint(index) => {
+				let (start, end) = (self.transform.transform_point2(self.gradient.start), self.transform.transform_point2(self.gradient.end));
+
+				// Find the fraction of the way between the span's stops of the closest point on the line
+				let position = ((end - start).angle_to(mouse - start)).cos() * start.distance(mouse) / start.distance(end);
+				let (Some(&(span_start, _)), Some(&(span_end, _))) = (self.gradient.stops.get(index), self.gradient.stops.get(index + 1)) else {
+					return;
+				};
+				if span_end > span_start {
+					self.gradient.stops.set_midpoint(index, (position - span_start) / (span_end - span_start));
+				}
+			}
 		}
 		self.render_gradient(responses);
 	}
@@ -350,7 +392,7 @@ impl Fsm for GradientToolFsmState {
 						&gradient
 					};
 
-					let Gradient { start, end, stops, .. } = gradient;
+					let Gradient { start, end, stops, gradient_type, .. } = gradient;
 					let (start, end) = (transform.transform_point2(*start), transform.transform_point2(*end));
 
 					fn color_to_hex(color: graphene_std::Color) -> String {
@@ -361,6 +403,16 @@ impl Fsm for GradientToolFsmState {
 					let end_hex = stops.last().map(|(_, c)| color_to_hex(*c)).unwrap_or(String::from(COLOR_OVERLAY_BLUE));
 
 					overlay_context.line(start, end, None, None);
+					if gradient_type.has_minor_axis() {
+						let minor_axis_end = gradient.minor_axis_end(transform);
+						overlay_context.line(start, minor_axis_end, None, None);
+						overlay_context.manipulator_handle(minor_axis_end, dragging == Some(GradientDragTarget::MinorAxis), None);
+					}
+					let focal_point = transform.transform_point2(gradient.focal_point());
+					if *gradient_type == GradientType::Radial && focal_point.distance_squared(start) > 0.5 {
+						overlay_context.line(start, focal_point, None, None);
+						overlay_context.manipulator_handle(focal_point, dragging == Some(GradientDragTarget::Focal), None);
+					}
 					overlay_context.gradient_color_stop(start, dragging == Some(GradientDragTarget::Start), &start_hex);
 					overlay_context.gradient_color_stop(end, dragging == Some(GradientDragTarget::End), &end_hex);
 
@@ -371,6 +423,10 @@ impl Fsm for GradientToolFsmState {
 						overlay_context.gradient_color_stop(start.lerp(end, position), dragging == Some(GradientDragTarget::Step(index)), &color_to_hex(color));
 					}
 
+					for (index, position) in midpoint_handles(start, end, stops) {
+						overlay_context.manipulator_handle(position, dragging == Some(GradientDragTarget::Midpoint(index)), None);
+					}
+
 					if let (Some(projection), Some(dir)) = (calculate_insertion(start, end, stops, mouse), (end - start).try_normalize()) {
 						let perp = dir.perp();
 						let point = start.lerp(end, projection);
@@ -407,7 +463,9 @@ impl Fsm for GradientToolFsmState {
 					GradientDragTarget::Step(index) => {
 						selected_gradient.gradient.stops.remove(index);
 					}
-					GradientDragTarget::New => {}
+					// Deleting a midpoint returns it to halfway between its stops
+					GradientDragTarget::Midpoint(index) => selected_gradient.gradient.stops.set_midpoint(index, DEFAULT_GRADIENT_MIDPOINT),
+					GradientDragTarget::New | GradientDragTarget::MinorAxis | GradientDragTarget::Focal => {}
 				};
 
 				// The gradient has only one point and so should become a fill
@@ -491,6 +549,43 @@ impl Fsm for GradientToolFsmState {
 				for layer in document.network_interface.selected_nodes().selected_visible_layers(&document.network_interface) {
 					let Some(gradient) = get_gradient(layer, &document.network_interface) else { continue };
 					let transform = gradient_space_transform(layer, document);
+
+					// Check for dragging the minor axis or focal point handles, beneath the others
+					let mut handles = Vec::new();
+					if gradient.gradient_type.has_minor_axis() {
+						handles.push((gradient.minor_axis_end(transform), GradientDragTarget::MinorAxis));
+					}
+					if gradient.gradient_type == GradientType::Radial && gradient.focal_offset != DVec2::ZERO {
+						handles.push((transform.transform_point2(gradient.focal_point()), GradientDragTarget::Focal));
+					}
+					for (pos, dragging_target) in handles {
+						if pos.distance_squared(mouse) < tolerance {
+							dragging = true;
+							tool_data.selected_gradient = Some(SelectedGradient {
+								layer: Some(layer),
+								transform,
+								gradient: gradient.clone(),
+								dragging: dragging_target,
+								initial_gradient: gradient.clone(),
+							})
+						}
+					}
+
+					// Check for dragging a midpoint, beneath the stops which may be drawn over it
+					let (start, end) = (transform.transform_point2(gradient.start), transform.transform_point2(gradient.end));
+					for (index, pos) in midpoint_handles(start, end, &gradient.stops) {
+						if pos.distance_squared(mouse) < tolerance {
+							dragging = true;
+							tool_data.selected_gradient = Some(SelectedGradient {
+								layer: Some(layer),
+								transform,
+								gradient: gradient.clone(),
+								dragging: GradientDragTarget::Midpoint(index),
+								initial_gradient: gradient.clone(),
+							})
+						}
+					}
+
 					// Check for dragging step
 					for (index, (pos, _)) in gradient.stops.iter().enumerate() {
 						let pos = transform.transform_point2(gradient.start.lerp(gradient.end, *pos));
@@ -510,6 +605,12 @@ impl Fsm for GradientToolFsmState {
 					for (pos, dragging_target) in [(gradient.start, GradientDragTarget::Start), (gradient.end, GradientDragTarget::End)] {
 						let pos = transform.transform_point2(pos);
 						if pos.distance_squared(mouse) < tolerance {
+							// Alt-dragging the start of a radial gradient pulls its focal point out of it
+							let dragging_target = if dragging_target == GradientDragTarget::Start && gradient.gradient_type == GradientType::Radial && input.keyboard.get(Key::Alt as usize) {
+								GradientDragTarget::Focal
+							} else {
+								dragging_target
+							};
 							dragging = true;
 							tool_data.selected_gradient = Some(SelectedGradient {
 								layer: Some(layer),
@@ -686,7 +787,10 @@ impl Fsm for GradientToolFsmState {
 				} else {
 					vec![HintInfo::mouse(MouseMotion::LmbDrag, "Draw Gradient"), HintInfo::keys([Key::Shift], "15° Increments").prepend_plus()]
 				};
-				HintData(vec![HintGroup(hints)])
+				HintData(vec![
+					HintGroup(hints),
+					HintGroup(vec![HintInfo::keys_and_mouse([Key::Alt], MouseMotion::LmbDrag, "Move Radial Focal Point")]),
+				])
 			}
 			GradientToolFsmState::Drawing => HintData(vec![
 				HintGroup(vec![HintInfo::mouse(MouseMotion::Rmb, ""), HintInfo::keys([Key::Escape], "Cancel").prepend_slash()]),
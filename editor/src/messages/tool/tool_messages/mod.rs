@@ -8,6 +8,7 @@ pub mod navigate_tool;
 pub mod path_tool;
 pub mod pen_tool;
 pub mod select_tool;
+pub mod shape_builder_tool;
 pub mod shape_tool;
 pub mod spline_tool;
 pub mod text_tool;
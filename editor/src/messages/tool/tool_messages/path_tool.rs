@@ -12,6 +12,7 @@ use crate::messages::portfolio::document::overlays::utility_functions::{path_ove
 use crate::messages::portfolio::document::overlays::utility_types::{DrawHandles, OverlayContext};
 use crate::messages::portfolio::document::utility_types::clipboards::Clipboard;
 use crate::messages::portfolio::document::utility_types::document_metadata::{DocumentMetadata, LayerNodeIdentifier};
+use crate::messages::portfolio::document::utility_types::misc::GroupFolderType;
 use crate::messages::portfolio::document::utility_types::network_interface::NodeNetworkInterface;
 use crate::messages::portfolio::document::utility_types::transformation::Axis;
 use crate::messages::preferences::SelectionMode;
@@ -23,7 +24,9 @@ use crate::messages::tool::common_functionality::shape_editor::{
 };
 use crate::messages::tool::common_functionality::snapping::{SnapCache, SnapCandidatePoint, SnapConstraint, SnapData, SnapManager};
 use crate::messages::tool::common_functionality::utility_functions::{calculate_segment_angle, find_two_param_best_approximate, make_path_editable_is_allowed};
+use crate::messages::tool::common_functionality::width_profile_gizmo::{WidthHandle, WidthProfileGizmo};
 use graphene_std::Color;
+use graphene_std::path_bool::BooleanOperation;
 use graphene_std::renderer::Quad;
 use graphene_std::subpath::pathseg_points;
 use graphene_std::transform::ReferencePoint;
@@ -334,6 +337,23 @@ impl LayoutHolder for PathTool {
 			.disabled(!self.tool_data.make_path_editable_is_allowed)
 			.widget_instance();
 
+		let knife_button = TextButton::new("Knife")
+			.icon(Some("Cut".into()))
+			.tooltip_label("Knife")
+			.tooltip_description("Cuts the selected closed shapes into separate pieces along the selected open paths.")
+			.on_update(|_| boolean_operation_message(BooleanOperation::Knife))
+			.widget_instance();
+		let trim_inside_button = TextButton::new("Trim Inside")
+			.tooltip_label("Trim Inside")
+			.tooltip_description("Keeps the parts of the selected open paths which lie inside the selected closed shapes.")
+			.on_update(|_| boolean_operation_message(BooleanOperation::TrimInside))
+			.widget_instance();
+		let trim_outside_button = TextButton::new("Trim Outside")
+			.tooltip_label("Trim Outside")
+			.tooltip_description("Keeps the parts of the selected open paths which lie outside the selected closed shapes.")
+			.on_update(|_| boolean_operation_message(BooleanOperation::TrimOutside))
+			.widget_instance();
+
 		let [_checkbox, _dropdown] = {
 			let pivot_gizmo_type_widget = pivot_gizmo_type_widget(self.tool_data.pivot_gizmo.state, PivotToolSource::Path);
 			[pivot_gizmo_type_widget[0].clone(), pivot_gizmo_type_widget[2].clone()]
@@ -365,6 +385,12 @@ impl LayoutHolder for PathTool {
 				path_overlay_mode_widget,
 				unrelated_seperator.clone(),
 				path_node_button,
+				unrelated_seperator.clone(),
+				knife_button,
+				related_seperator.clone(),
+				trim_inside_button,
+				related_seperator.clone(),
+				trim_outside_button,
 				// checkbox.clone(),
 				// related_seperator.clone(),
 				// dropdown.clone(),
@@ -377,6 +403,14 @@ impl LayoutHolder for PathTool {
 	}
 }
 
+/// Combines the selected layers in a Boolean Operation node which cuts or trims them with the given operation.
+fn boolean_operation_message(operation: BooleanOperation) -> Message {
+	DocumentMessage::GroupSelectedLayers {
+		group_folder_type: GroupFolderType::BooleanOperation(operation),
+	}
+	.into()
+}
+
 #[message_handler_data]
 impl<'a> MessageHandler<ToolMessage, &mut ToolActionMessageContext<'a>> for PathTool {
 	fn process_message(&mut self, message: ToolMessage, responses: &mut VecDeque<Message>, context: &mut ToolActionMessageContext<'a>) {
@@ -501,6 +535,12 @@ impl<'a> MessageHandler<ToolMessage, &mut ToolActionMessageContext<'a>> for Path
 				Escape,
 				RightClick
 			),
+			PathToolFsmState::DraggingWidthPoint => actions!(PathToolMessageDiscriminant;
+				PointerMove,
+				DragStop,
+				Escape,
+				RightClick
+			),
 		}
 	}
 }
@@ -552,6 +592,7 @@ enum PathToolFsmState {
 		selection_shape: SelectionShapeType,
 	},
 	SlidingPoint,
+	DraggingWidthPoint,
 }
 
 #[derive(Default)]
@@ -600,6 +641,8 @@ struct PathToolData {
 	frontier_handles_info: Option<HashMap<LayerNodeIdentifier, HashMap<SegmentId, Vec<PointId>>>>,
 	adjacent_anchor_offset: Option<DVec2>,
 	sliding_point_info: Option<SlidingPointInfo>,
+	/// The width profile being edited and its handle being dragged, while changing the width of a stroke.
+	width_point_drag: Option<(WidthProfileGizmo, WidthHandle)>,
 	started_drawing_from_inside: bool,
 	first_selected_with_single_click: bool,
 	stored_selection: Option<HashMap<LayerNodeIdentifier, SelectedLayerState>>,
@@ -1779,6 +1822,17 @@ impl Fsm for PathToolFsmState {
 					}
 				}
 
+				// Draw the width handles along the strokes of layers with a width profile
+				if let Some((gizmo, handle)) = &tool_data.width_point_drag {
+					gizmo.draw(&mut overlay_context, Some(*handle));
+				} else {
+					for layer in document.network_interface.selected_nodes().selected_visible_and_unlocked_layers(&document.network_interface) {
+						let Some(gizmo) = WidthProfileGizmo::new(layer, document) else { continue };
+						let hovered = if self == Self::Ready { gizmo.handle_under(input.mouse.position) } else { None };
+						gizmo.draw(&mut overlay_context, hovered);
+					}
+				}
+
 				match self {
 					Self::Ready => {
 						tool_data.update_closest_segment(
@@ -1994,7 +2048,7 @@ impl Fsm for PathToolFsmState {
 							}
 						}
 					}
-					Self::SlidingPoint => {}
+					Self::SlidingPoint | Self::DraggingWidthPoint => {}
 				}
 
 				responses.add(PathToolMessage::SelectedPointUpdated);
@@ -2022,6 +2076,45 @@ impl Fsm for PathToolFsmState {
 				tool_data.selection_mode = None;
 				tool_data.lasso_polygon.clear();
 
+				// Grab a width handle, or add a width point where the edge of a stroke with a width profile is clicked, unless a point of the path is under the cursor
+				let point_under_cursor = shape_editor
+					.find_nearest_visible_point_indices(
+						&document.network_interface,
+						input.mouse.position,
+						SELECTION_THRESHOLD,
+						tool_options.path_overlay_mode,
+						tool_data.frontier_handles_info.as_ref(),
+					)
+					.is_some();
+				let width_point_drag = if point_under_cursor {
+					None
+				} else {
+					document
+						.network_interface
+						.selected_nodes()
+						.selected_visible_and_unlocked_layers(&document.network_interface)
+						.filter_map(|layer| WidthProfileGizmo::new(layer, document))
+						.find_map(|mut gizmo| {
+							if let Some(handle) = gizmo.handle_under(input.mouse.position) {
+								return Some((gizmo, handle, false));
+							}
+							let handle = gizmo.insert_point_on_edge(input.mouse.position)?;
+							Some((gizmo, handle, true))
+						})
+				};
+				if let Some((gizmo, handle, inserted)) = width_point_drag {
+					responses.add(DocumentMessage::StartTransaction);
+					if inserted {
+						responses.add(GraphOperationMessage::WidthProfileSet {
+							layer: gizmo.layer,
+							width_profile: gizmo.profile.clone(),
+						});
+					}
+					tool_data.drag_start_pos = input.mouse.position;
+					tool_data.width_point_drag = Some((gizmo, handle));
+					return PathToolFsmState::DraggingWidthPoint;
+				}
+
 				tool_data.mouse_down(
 					shape_editor,
 					document,
@@ -2213,6 +2306,19 @@ impl Fsm for PathToolFsmState {
 				tool_data.slide_point(input.mouse.position, responses, &document.network_interface, shape_editor);
 				PathToolFsmState::SlidingPoint
 			}
+			(PathToolFsmState::DraggingWidthPoint, PathToolMessage::PointerMove { equidistant, .. }) => {
+				let both_sides = input.keyboard.get(equidistant as usize);
+
+				if let Some((gizmo, handle)) = &mut tool_data.width_point_drag {
+					gizmo.drag_handle(*handle, input.mouse.position, both_sides);
+					responses.add(GraphOperationMessage::WidthProfileSet {
+						layer: gizmo.layer,
+						width_profile: gizmo.profile.clone(),
+					});
+				}
+
+				PathToolFsmState::DraggingWidthPoint
+			}
 			(
 				PathToolFsmState::Ready,
 				PathToolMessage::PointerMove {
@@ -2388,6 +2494,14 @@ impl Fsm for PathToolFsmState {
 				tool_data.snap_manager.cleanup(responses);
 				PathToolFsmState::Ready
 			}
+			(PathToolFsmState::DraggingWidthPoint, PathToolMessage::Escape | PathToolMessage::RightClick) => {
+				tool_data.width_point_drag = None;
+
+				responses.add(DocumentMessage::AbortTransaction);
+				responses.add(OverlaysMessage::Draw);
+
+				PathToolFsmState::Ready
+			}
 			(PathToolFsmState::SlidingPoint, PathToolMessage::Escape | PathToolMessage::RightClick) => {
 				tool_data.sliding_point_info = None;
 
@@ -2397,6 +2511,14 @@ impl Fsm for PathToolFsmState {
 				PathToolFsmState::Ready
 			}
 			// Mouse up
+			(PathToolFsmState::DraggingWidthPoint, PathToolMessage::DragStop { .. }) => {
+				tool_data.width_point_drag = None;
+
+				responses.add(DocumentMessage::EndTransaction);
+				responses.add(OverlaysMessage::Draw);
+
+				PathToolFsmState::Ready
+			}
 			(PathToolFsmState::Drawing { selection_shape }, PathToolMessage::DragStop { extend_selection, shrink_selection }) => {
 				let extend_selection = input.keyboard.get(extend_selection as usize);
 				let shrink_selection = input.keyboard.get(shrink_selection as usize);
@@ -3127,6 +3249,7 @@ impl Fsm for PathToolFsmState {
 				PathToolFsmState::Ready
 			}
 			(_, PathToolMessage::Abort) => {
+				tool_data.width_point_drag = None;
 				responses.add(OverlaysMessage::Draw);
 				PathToolFsmState::Ready
 			}
@@ -3660,6 +3783,10 @@ fn update_dynamic_hints(
 			]),
 		]),
 		PathToolFsmState::SlidingPoint => HintData(vec![HintGroup(vec![HintInfo::mouse(MouseMotion::Rmb, ""), HintInfo::keys([Key::Escape], "Cancel").prepend_slash()])]),
+		PathToolFsmState::DraggingWidthPoint => HintData(vec![
+			HintGroup(vec![HintInfo::mouse(MouseMotion::Rmb, ""), HintInfo::keys([Key::Escape], "Cancel").prepend_slash()]),
+			HintGroup(vec![HintInfo::keys([Key::Alt], "Equal Widths on Both Sides")]),
+		]),
 	};
 	hint_data.send_layout(responses);
 	responses.add(ToolMessage::UpdateHints);
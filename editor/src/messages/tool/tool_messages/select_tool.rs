@@ -202,8 +202,10 @@ impl SelectTool {
 
 	fn boolean_widgets(&self, selected_count: usize) -> impl Iterator<Item = WidgetInstance> + use<> {
 		let list = <BooleanOperation as graphene_std::choice_type::ChoiceTypeStatic>::list();
-		list.iter().flat_map(|i| i.iter()).map(move |(operation, info)| {
-			IconButton::new(info.icon.unwrap(), 24)
+		// Operations without an icon are only offered by the Boolean Operation node
+		list.iter().flat_map(|i| i.iter()).filter_map(move |(operation, info)| {
+			let icon = info.icon?;
+			let button = IconButton::new(icon, 24)
 				.tooltip_label(info.label)
 				.tooltip_description(info.description.unwrap_or_default())
 				.disabled(selected_count == 0)
@@ -211,7 +213,8 @@ impl SelectTool {
 					let group_folder_type = GroupFolderType::BooleanOperation(*operation);
 					DocumentMessage::GroupSelectedLayers { group_folder_type }.into()
 				})
-				.widget_instance()
+				.widget_instance();
+			Some(button)
 		})
 	}
 }
@@ -1988,3 +1991,38 @@ pub fn filter_nested_selection(metadata: &DocumentMetadata, new_selected: &HashS
 
 	filtered_selection
 }
+
+#[cfg(test)]
+mod test_select {
+	use super::SelectTool;
+	use crate::messages::layout::utility_types::layout_widget::{LayoutHolder, Widget};
+	pub use crate::test_utils::test_prelude::*;
+	use graphene_std::choice_type::ChoiceTypeStatic;
+	use graphene_std::path_bool::BooleanOperation;
+
+	#[test]
+	fn boolean_buttons_are_the_operations_with_icons() {
+		let layout = SelectTool::default().layout();
+		let button_labels = layout
+			.iter()
+			.filter_map(|widget| match &*widget.widget {
+				Widget::IconButton(button) => Some(button.tooltip_label.as_str()),
+				_ => None,
+			})
+			.collect::<Vec<_>>();
+
+		for (operation, info) in BooleanOperation::list().iter().flat_map(|group| group.iter()) {
+			assert_eq!(button_labels.contains(&info.label), info.icon.is_some(), "{operation:?} should have a button only if it has an icon");
+		}
+	}
+
+	#[tokio::test]
+	async fn options_bar_is_built_when_activating_the_tool() {
+		let mut editor = EditorTestUtils::create();
+		editor.new_document().await;
+		editor.select_tool(ToolType::Fill).await;
+		editor.select_tool(ToolType::Select).await;
+		editor.draw_rect(0., 0., 100., 100.).await;
+		editor.select_tool(ToolType::Select).await;
+	}
+}
@@ -0,0 +1,450 @@
+use super::tool_prelude::*;
+use crate::consts::{COLOR_OVERLAY_BLUE_50, COLOR_OVERLAY_RED_50};
+use crate::messages::portfolio::document::graph_operation::utility_types::TransformIn;
+use crate::messages::portfolio::document::overlays::utility_types::OverlayContext;
+use crate::messages::portfolio::document::utility_types::document_metadata::LayerNodeIdentifier;
+use crate::messages::tool::common_functionality::graph_modification_utils::NodeGraphLayer;
+use crate::messages::tool::common_functionality::utility_functions::{LayerSource, layer_path_in_document, path_to_subpaths};
+use graph_craft::document::NodeId;
+use graphene_std::path_bool::path_bool_lib::{self, FillRule, PathBooleanOperation, PathSegment};
+use graphene_std::subpath::Subpath;
+use graphene_std::vector::PointId;
+
+#[derive(Default, ExtractField)]
+pub struct ShapeBuilderTool {
+	fsm_state: ShapeBuilderToolFsmState,
+	data: ShapeBuilderToolData,
+}
+
+#[impl_message(Message, ToolMessage, ShapeBuilder)]
+#[derive(PartialEq, Clone, Debug, Hash, serde::Serialize, serde::Deserialize, specta::Type)]
+pub enum ShapeBuilderToolMessage {
+	// Standard messages
+	Abort,
+	Overlays { context: OverlayContext },
+
+	// Tool-specific messages
+	DragStart { remove: Key },
+	DragStop,
+	PointerMove,
+}
+
+impl ToolMetadata for ShapeBuilderTool {
+	fn icon_name(&self) -> String {
+		"BooleanUnion".into()
+	}
+	fn tooltip_label(&self) -> String {
+		"Shape Builder Tool".into()
+	}
+	fn tool_type(&self) -> crate::messages::tool::utility_types::ToolType {
+		ToolType::ShapeBuilder
+	}
+}
+
+impl LayoutHolder for ShapeBuilderTool {
+	fn layout(&self) -> Layout {
+		Layout::default()
+	}
+}
+
+#[message_handler_data]
+impl<'a> MessageHandler<ToolMessage, &mut ToolActionMessageContext<'a>> for ShapeBuilderTool {
+	fn process_message(&mut self, message: ToolMessage, responses: &mut VecDeque<Message>, context: &mut ToolActionMessageContext<'a>) {
+		self.fsm_state.process_event(message, &mut self.data, context, &(), responses, true);
+	}
+
+	fn actions(&self) -> ActionList {
+		match self.fsm_state {
+			ShapeBuilderToolFsmState::Ready => actions!(ShapeBuilderToolMessageDiscriminant;
+				DragStart,
+				PointerMove,
+			),
+			ShapeBuilderToolFsmState::Dragging => actions!(ShapeBuilderToolMessageDiscriminant;
+				DragStop,
+				PointerMove,
+				Abort,
+			),
+		}
+	}
+}
+
+impl ToolTransition for ShapeBuilderTool {
+	fn event_to_message_map(&self) -> EventToMessageMap {
+		EventToMessageMap {
+			tool_abort: Some(ShapeBuilderToolMessage::Abort.into()),
+			overlay_provider: Some(|context| ShapeBuilderToolMessage::Overlays { context }.into()),
+			..Default::default()
+		}
+	}
+}
+
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+enum ShapeBuilderToolFsmState {
+	#[default]
+	Ready,
+	Dragging,
+}
+
+/// One of the pieces which the outlines of the selected layers break each other into.
+#[derive(Clone, Debug)]
+struct Region {
+	/// The outline of the region in document space, followed by the outlines of any holes in it.
+	path: Vec<PathSegment>,
+	/// The same outline as `path`, for drawing and hit testing.
+	subpaths: Vec<Subpath<PointId>>,
+	/// The indices of the layers which cover the region, from top to bottom.
+	layers: Vec<usize>,
+}
+
+impl Region {
+	fn contains(&self, point: DVec2) -> bool {
+		self.subpaths.iter().filter(|subpath| subpath.contains_point(point)).count() % 2 == 1
+	}
+}
+
+#[derive(Clone, Debug, Default)]
+struct ShapeBuilderToolData {
+	/// The selected layers which the regions are built from, from top to bottom.
+	layers: Vec<LayerNodeIdentifier>,
+	/// The sources of `layers` when the regions were found, which are kept to tell when the regions need to be found again.
+	sources: Vec<LayerSource>,
+	regions: Vec<Region>,
+	/// The indices of the regions which the current drag has passed over, in the order they were reached.
+	dragged_regions: Vec<usize>,
+	/// Whether the current drag deletes the regions instead of merging them.
+	remove: bool,
+}
+
+impl ShapeBuilderToolData {
+	/// Breaks the outlines of the selected layers into regions, unless neither the selection nor the layers have changed since the last time.
+	fn update_regions(&mut self, document: &mut DocumentMessageHandler) {
+		let selected_layers = document
+			.network_interface
+			.selected_nodes()
+			.selected_visible_and_unlocked_layers(&document.network_interface)
+			.collect::<HashSet<_>>();
+		let mut layers = document
+			.metadata()
+			.all_layers()
+			.filter(|layer| selected_layers.contains(layer) && !layer.has_children(document.metadata()) && !document.network_interface.is_artboard(&layer.to_node(), &[]))
+			.collect::<Vec<_>>();
+		layers.retain(|&layer| !NodeGraphLayer::is_raster_layer(layer, &mut document.network_interface));
+
+		let sources = layers.iter().map(|&layer| LayerSource::new(document.metadata(), layer)).collect::<Vec<_>>();
+		if layers == self.layers && sources.len() == self.sources.len() && sources.iter().zip(&self.sources).all(|(source, old_source)| source.is_unchanged(old_source)) {
+			return;
+		}
+
+		let outlines = layers.iter().map(|&layer| layer_path_in_document(document.metadata(), layer, true)).collect::<Vec<_>>();
+		let paths = outlines.iter().map(|outline| (outline, FillRule::NonZero)).collect::<Vec<_>>();
+		let regions = path_bool_lib::path_fracture(&paths).unwrap_or_else(|error| {
+			log::error!("Boolean error {error:?} encountered while finding the regions of {} layers", layers.len());
+			Vec::new()
+		});
+
+		self.regions = regions
+			.into_iter()
+			.map(|region| Region {
+				subpaths: path_to_subpaths(&region.path),
+				path: region.path,
+				layers: region.covering_paths,
+			})
+			.collect();
+		self.layers = layers;
+		self.sources = sources;
+	}
+
+	fn hovered_region(&self, document: &DocumentMessageHandler, input: &InputPreprocessorMessageHandler) -> Option<usize> {
+		let mouse = document.metadata().document_to_viewport.inverse().transform_point2(input.mouse.position);
+		self.regions.iter().position(|region| region.contains(mouse))
+	}
+
+	/// Merges the dragged regions into a new layer, or deletes them, and replaces the layers they were cut from with what remains of them.
+	fn apply(&self, document: &DocumentMessageHandler, responses: &mut VecDeque<Message>) {
+		let Some(&first_region) = self.dragged_regions.first() else { return };
+		let is_dragged = |index: usize| self.dragged_regions.contains(&index);
+
+		let affected_layers = (0..self.layers.len())
+			.filter(|layer| self.dragged_regions.iter().any(|&region| self.regions[region].layers.contains(layer)))
+			.collect::<Vec<_>>();
+		if affected_layers.is_empty() {
+			return;
+		}
+
+		// The merged shape takes on the appearance of the topmost layer under the region where the drag began
+		let style_layer = (!self.remove).then(|| self.regions[first_region].layers[0]);
+
+		responses.add(DocumentMessage::AddTransaction);
+
+		// Going from the bottom to the top keeps the stack positions of the layers yet to be replaced unchanged
+		let mut new_layers = Vec::new();
+		for &layer_index in affected_layers.iter().rev() {
+			let layer = self.layers[layer_index];
+
+			let remaining = self.regions.iter().enumerate().filter(|(index, region)| !is_dragged(*index) && region.layers.contains(&layer_index));
+			let remaining = union(remaining.map(|(_, region)| &region.path));
+			if !remaining.is_empty() {
+				new_layers.extend(insert_layer_like(layer, remaining, document, responses));
+			}
+
+			if style_layer == Some(layer_index) {
+				let merged = union(self.dragged_regions.iter().map(|&index| &self.regions[index].path));
+				new_layers.extend(insert_layer_like(layer, merged, document, responses));
+			}
+
+			responses.add(NodeGraphMessage::DeleteNodes {
+				node_ids: vec![layer.to_node()],
+				delete_children: true,
+			});
+		}
+
+		// Keep the results selected so more regions can be built from them
+		let untouched_layers = (0..self.layers.len()).filter(|layer| !affected_layers.contains(layer)).map(|layer| self.layers[layer].to_node());
+		let nodes = new_layers.into_iter().chain(untouched_layers).collect();
+		responses.add(NodeGraphMessage::SelectedNodesSet { nodes });
+		responses.add(NodeGraphMessage::RunDocumentGraph);
+	}
+}
+
+impl Fsm for ShapeBuilderToolFsmState {
+	type ToolData = ShapeBuilderToolData;
+	type ToolOptions = ();
+
+	fn transition(
+		self,
+		event: ToolMessage,
+		tool_data: &mut Self::ToolData,
+		tool_action_data: &mut ToolActionMessageContext,
+		_tool_options: &Self::ToolOptions,
+		responses: &mut VecDeque<Message>,
+	) -> Self {
+		let ToolActionMessageContext { document, input, .. } = tool_action_data;
+
+		let ToolMessage::ShapeBuilder(event) = event else { return self };
+		match (self, event) {
+			(_, ShapeBuilderToolMessage::Overlays { context: mut overlay_context }) => {
+				tool_data.update_regions(document);
+
+				let remove = if self == ShapeBuilderToolFsmState::Dragging {
+					tool_data.remove
+				} else {
+					input.keyboard.get(Key::Alt as usize)
+				};
+				let color = if remove { COLOR_OVERLAY_RED_50 } else { COLOR_OVERLAY_BLUE_50 };
+
+				let hovered_region = tool_data.hovered_region(document, input);
+				let highlighted_regions = tool_data
+					.dragged_regions
+					.iter()
+					.copied()
+					.chain(hovered_region.filter(|region| !tool_data.dragged_regions.contains(region)));
+				for region in highlighted_regions {
+					overlay_context.fill_path(tool_data.regions[region].subpaths.iter(), document.metadata().document_to_viewport, color);
+				}
+
+				self
+			}
+			(ShapeBuilderToolFsmState::Ready, ShapeBuilderToolMessage::DragStart { remove }) => {
+				tool_data.update_regions(document);
+				let Some(region) = tool_data.hovered_region(document, input) else { return self };
+
+				tool_data.remove = input.keyboard.key(remove);
+				tool_data.dragged_regions = vec![region];
+				responses.add(OverlaysMessage::Draw);
+
+				ShapeBuilderToolFsmState::Dragging
+			}
+			(ShapeBuilderToolFsmState::Dragging, ShapeBuilderToolMessage::PointerMove) => {
+				if let Some(region) = tool_data.hovered_region(document, input)
+					&& !tool_data.dragged_regions.contains(&region)
+				{
+					tool_data.dragged_regions.push(region);
+				}
+				responses.add(OverlaysMessage::Draw);
+
+				self
+			}
+			(_, ShapeBuilderToolMessage::PointerMove) => {
+				responses.add(OverlaysMessage::Draw);
+				self
+			}
+			(ShapeBuilderToolFsmState::Dragging, ShapeBuilderToolMessage::DragStop) => {
+				tool_data.apply(document, responses);
+				tool_data.dragged_regions.clear();
+
+				ShapeBuilderToolFsmState::Ready
+			}
+			(ShapeBuilderToolFsmState::Dragging, ShapeBuilderToolMessage::Abort) => {
+				tool_data.dragged_regions.clear();
+				responses.add(OverlaysMessage::Draw);
+
+				ShapeBuilderToolFsmState::Ready
+			}
+			_ => self,
+		}
+	}
+
+	fn update_hints(&self, responses: &mut VecDeque<Message>) {
+		let hint_data = match self {
+			ShapeBuilderToolFsmState::Ready => HintData(vec![HintGroup(vec![
+				HintInfo::mouse(MouseMotion::LmbDrag, "Merge Regions"),
+				HintInfo::keys([Key::Alt], "Delete Regions").prepend_plus(),
+			])]),
+			ShapeBuilderToolFsmState::Dragging => HintData(vec![HintGroup(vec![HintInfo::mouse(MouseMotion::Rmb, ""), HintInfo::keys([Key::Escape], "Cancel").prepend_slash()])]),
+		};
+
+		hint_data.send_layout(responses);
+	}
+
+	fn update_cursor(&self, responses: &mut VecDeque<Message>) {
+		responses.add(FrontendMessage::UpdateMouseCursor { cursor: MouseCursorIcon::Default });
+	}
+}
+
+/// Joins regions back into a single outline.
+fn union<'a>(paths: impl Iterator<Item = &'a Vec<PathSegment>>) -> Vec<Subpath<PointId>> {
+	let paths = paths.map(|path| (path, FillRule::NonZero)).collect::<Vec<_>>();
+	match path_bool_lib::path_boolean_many(&paths, PathBooleanOperation::Union) {
+		Ok(result) => result.iter().flat_map(|path| path_to_subpaths(path)).collect(),
+		Err(error) => {
+			log::error!("Boolean error {error:?} encountered while joining {} regions", paths.len());
+			Vec::new()
+		}
+	}
+}
+
+/// Adds a vector layer with the given outline, in document space, directly above `layer`, with the same name, transform, fill, and stroke.
+fn insert_layer_like(layer: LayerNodeIdentifier, outline: Vec<Subpath<PointId>>, document: &DocumentMessageHandler, responses: &mut VecDeque<Message>) -> Option<NodeId> {
+	let metadata = document.metadata();
+	let parent = layer.parent(metadata)?;
+	let insert_index = parent.children(metadata).position(|child| child == layer)?;
+
+	let layer_to_document = metadata.transform_to_document(layer);
+	let local_transform = metadata.downstream_transform_to_document(layer).inverse() * layer_to_document;
+	let subpaths = outline
+		.into_iter()
+		.map(|mut subpath| {
+			subpath.apply_transform(layer_to_document.inverse());
+			subpath
+		})
+		.collect();
+
+	let id = NodeId::new();
+	let new_layer = LayerNodeIdentifier::new_unchecked(id);
+	responses.add(GraphOperationMessage::NewVectorLayer { id, subpaths, parent, insert_index });
+	responses.add(GraphOperationMessage::TransformSet {
+		layer: new_layer,
+		transform: local_transform,
+		transform_in: TransformIn::Local,
+		skip_rerender: true,
+	});
+	if let Some(vector) = document.network_interface.compute_modified_vector(layer) {
+		responses.add(GraphOperationMessage::FillSet {
+			layer: new_layer,
+			fill: vector.style.fill().clone(),
+		});
+		if let Some(stroke) = vector.style.stroke() {
+			responses.add(GraphOperationMessage::StrokeSet { layer: new_layer, stroke });
+		}
+	}
+	responses.add(NodeGraphMessage::SetDisplayName {
+		node_id: id,
+		alias: document.network_interface.display_name(&layer.to_node(), &[]),
+		skip_adding_history_step: true,
+	});
+
+	Some(id)
+}
+
+#[cfg(test)]
+mod test_shape_builder {
+	pub use crate::test_utils::test_prelude::*;
+
+	/// Two selected squares, from 0 to 100 and from 50 to 150, which overlap between 50 and 100.
+	async fn overlapping_squares() -> EditorTestUtils {
+		let mut editor = EditorTestUtils::create();
+		editor.new_document().await;
+		editor.draw_rect(0., 0., 100., 100.).await;
+		editor.draw_rect(50., 0., 150., 100.).await;
+		editor.handle_message(DocumentMessage::SelectAllLayers).await;
+		editor.select_tool(ToolType::ShapeBuilder).await;
+		editor
+	}
+
+	/// The bounding boxes of all layers, from left to right.
+	fn bounding_boxes(editor: &EditorTestUtils) -> Vec<[DVec2; 2]> {
+		let metadata = editor.active_document().metadata();
+		let mut bounding_boxes = metadata.all_layers().filter_map(|layer| metadata.bounding_box_document(layer)).collect::<Vec<_>>();
+		bounding_boxes.sort_by(|a, b| a[0].x.total_cmp(&b[0].x));
+		bounding_boxes
+	}
+
+	fn assert_bounding_boxes(editor: &EditorTestUtils, expected: &[[(f64, f64); 2]]) {
+		let bounding_boxes = bounding_boxes(editor);
+		assert_eq!(bounding_boxes.len(), expected.len(), "Expected the layers {expected:?}, got {bounding_boxes:?}");
+		for (bounding_box, expected) in bounding_boxes.iter().zip(expected) {
+			let expected = expected.map(DVec2::from);
+			assert!(
+				bounding_box[0].abs_diff_eq(expected[0], 1e-3) && bounding_box[1].abs_diff_eq(expected[1], 1e-3),
+				"Expected the layers {expected:?}, got {bounding_boxes:?}"
+			);
+		}
+	}
+
+	#[tokio::test]
+	async fn merge_all_regions() {
+		let mut editor = overlapping_squares().await;
+		editor.drag_path(&[DVec2::new(25., 50.), DVec2::new(75., 50.), DVec2::new(125., 50.)], ModifierKeys::empty()).await;
+
+		assert_bounding_boxes(&editor, &[[(0., 0.), (150., 100.)]]);
+	}
+
+	#[tokio::test]
+	async fn merge_overlap() {
+		let mut editor = overlapping_squares().await;
+		editor.drag_path(&[DVec2::new(75., 50.), DVec2::new(80., 50.)], ModifierKeys::empty()).await;
+
+		// The overlap becomes its own layer, between what remains of the squares on either side
+		assert_bounding_boxes(&editor, &[[(0., 0.), (50., 100.)], [(50., 0.), (100., 100.)], [(100., 0.), (150., 100.)]]);
+	}
+
+	#[tokio::test]
+	async fn delete_overlap() {
+		let mut editor = overlapping_squares().await;
+		editor.drag_path(&[DVec2::new(75., 50.), DVec2::new(80., 50.)], ModifierKeys::ALT).await;
+
+		assert_bounding_boxes(&editor, &[[(0., 0.), (50., 100.)], [(100., 0.), (150., 100.)]]);
+	}
+
+	#[tokio::test]
+	async fn delete_whole_layer() {
+		let mut editor = overlapping_squares().await;
+		editor.drag_path(&[DVec2::new(25., 50.), DVec2::new(75., 50.)], ModifierKeys::ALT).await;
+
+		// Nothing remains of the left square, and the right square loses the overlap
+		assert_bounding_boxes(&editor, &[[(100., 0.), (150., 100.)]]);
+	}
+
+	#[tokio::test]
+	async fn abort_leaves_layers_unchanged() {
+		let mut editor = overlapping_squares().await;
+		editor.move_mouse(25., 50., ModifierKeys::empty(), MouseKeys::empty()).await;
+		editor.left_mousedown(25., 50., ModifierKeys::empty()).await;
+		editor.move_mouse(125., 50., ModifierKeys::empty(), MouseKeys::LEFT).await;
+		editor.press(Key::Escape, ModifierKeys::empty()).await;
+		editor.left_mouseup(125., 50., ModifierKeys::empty()).await;
+
+		assert_bounding_boxes(&editor, &[[(0., 0.), (100., 100.)], [(50., 0.), (150., 100.)]]);
+	}
+
+	#[tokio::test]
+	async fn regions_are_found_again_after_the_document_changes() {
+		let mut editor = overlapping_squares().await;
+		editor.drag_path(&[DVec2::new(75., 50.), DVec2::new(80., 50.)], ModifierKeys::ALT).await;
+
+		// The two remaining pieces are still selected, and merging them has to use their new outlines rather than those of the squares
+		editor.drag_path(&[DVec2::new(25., 50.), DVec2::new(125., 50.)], ModifierKeys::empty()).await;
+		assert_bounding_boxes(&editor, &[[(0., 0.), (150., 100.)]]);
+		assert_eq!(editor.active_document().metadata().all_layers().count(), 1);
+	}
+}
@@ -381,6 +381,7 @@ pub enum ToolType {
 	Rectangle, // Shape tool alias
 	Ellipse,   // Shape tool alias
 	Text,
+	ShapeBuilder,
 
 	// Raster tool group
 	Brush,
@@ -432,6 +433,7 @@ fn list_tools_in_groups() -> Vec<Vec<ToolRole>> {
 			ToolRole::Shape(ShapeType::Ellipse),
 			ToolRole::Normal(Box::<shape_tool::ShapeTool>::default()),
 			ToolRole::Normal(Box::<text_tool::TextTool>::default()),
+			ToolRole::Normal(Box::<shape_builder_tool::ShapeBuilderTool>::default()),
 		],
 		vec![
 			// Raster tool group
@@ -480,6 +482,7 @@ pub fn tool_message_to_tool_type(tool_message: &ToolMessage) -> ToolType {
 		ToolMessage::Spline(_) => ToolType::Spline,
 		ToolMessage::Shape(_) => ToolType::Shape, // Includes the Line, Rectangle, and Ellipse aliases
 		ToolMessage::Text(_) => ToolType::Text,
+		ToolMessage::ShapeBuilder(_) => ToolType::ShapeBuilder,
 
 		// Raster tool group
 		ToolMessage::Brush(_) => ToolType::Brush,
@@ -512,6 +515,7 @@ pub fn tool_type_to_activate_tool_message(tool_type: ToolType) -> ToolMessageDis
 		ToolType::Ellipse => ToolMessageDiscriminant::ActivateToolShapeEllipse,     // Shape tool alias
 		ToolType::Shape => ToolMessageDiscriminant::ActivateToolShape,
 		ToolType::Text => ToolMessageDiscriminant::ActivateToolText,
+		ToolType::ShapeBuilder => ToolMessageDiscriminant::ActivateToolShapeBuilder,
 
 		// Raster tool group
 		ToolType::Brush => ToolMessageDiscriminant::ActivateToolBrush,
@@ -1,4 +1,4 @@
-use crate::messages::frontend::utility_types::{ExportBounds, FileType};
+use crate::messages::frontend::utility_types::{ExportBounds, FileType, RasterEncoding};
 use crate::messages::prelude::*;
 use glam::{DAffine2, DVec2, UVec2};
 use graph_craft::document::value::{RenderOutput, TaggedValue};
@@ -226,10 +226,10 @@ impl NodeGraphExecutor {
 	pub fn submit_document_export(&mut self, document: &mut DocumentMessageHandler, document_id: DocumentId, mut export_config: ExportConfig) -> Result<(), String> {
 		let network = document.network_interface.document_network().clone();
 
-		let export_format = if export_config.file_type == FileType::Svg {
-			graphene_std::application_io::ExportFormat::Svg
-		} else {
-			graphene_std::application_io::ExportFormat::Raster
+		let export_format = match export_config.file_type {
+			FileType::Svg => graphene_std::application_io::ExportFormat::Svg,
+			FileType::Pdf => graphene_std::application_io::ExportFormat::Pdf,
+			FileType::Png | FileType::Jpg | FileType::Webp | FileType::Avif | FileType::Tiff => graphene_std::application_io::ExportFormat::Raster,
 		};
 
 		// Calculate the bounding box of the region to be exported
@@ -432,76 +432,56 @@ impl NodeGraphExecutor {
 			name,
 			size,
 			scale_factor,
-			#[cfg(feature = "gpu")]
+			quality,
+			bit_depth,
 			transparent_background,
 			artboard_name,
 			artboard_count,
 			..
 		} = export_config;
 
-		let file_extension = match file_type {
-			FileType::Svg => "svg",
-			FileType::Png => "png",
-			FileType::Jpg => "jpg",
-		};
 		let base_name = match (artboard_name, artboard_count) {
 			(Some(artboard_name), count) if count > 1 => format!("{name} - {artboard_name}"),
 			_ => name,
 		};
-		let name = format!("{base_name}.{file_extension}");
+		let name = format!("{base_name}.{}", file_type.extension());
+
+		let encoding = RasterEncoding {
+			file_type,
+			quality,
+			bit_depth,
+			transparent_background,
+		};
 
 		match node_graph_output {
 			TaggedValue::RenderOutput(RenderOutput {
 				data: RenderOutputType::Svg { svg, .. },
 				..
-			}) => {
+			}) if file_type != FileType::Pdf => {
+				let size = (size * scale_factor).into();
 				if file_type == FileType::Svg {
 					responses.add(FrontendMessage::TriggerSaveFile { name, content: svg.into_bytes() });
-				} else {
+				} else if encoding.is_browser_encodable() {
 					let mime = file_type.to_mime().to_string();
-					let size = (size * scale_factor).into();
-					responses.add(FrontendMessage::TriggerExportImage { svg, name, mime, size });
+					let quality = quality / 100.;
+					responses.add(FrontendMessage::TriggerExportImage { svg, name, mime, quality, size });
+				} else {
+					// The browser rasterizes the SVG and hands the pixels back to be encoded by `PortfolioMessage::EncodeRasterizedExport`
+					responses.add(FrontendMessage::TriggerRasterizeExport { svg, name, size, encoding });
 				}
 			}
+			TaggedValue::RenderOutput(RenderOutput {
+				data: RenderOutputType::Pdf { data }, ..
+			}) if file_type == FileType::Pdf => {
+				let data = data.map_err(|error| format!("Failed to render the document as a PDF: {error}"))?;
+				responses.add(FrontendMessage::TriggerSaveFile { name, content: data });
+			}
 			#[cfg(feature = "gpu")]
 			TaggedValue::RenderOutput(RenderOutput {
 				data: RenderOutputType::Buffer { data, width, height },
 				..
-			}) if file_type != FileType::Svg => {
-				use image::buffer::ConvertBuffer;
-				use image::{ImageFormat, RgbImage, RgbaImage};
-
-				let Some(image) = RgbaImage::from_raw(width, height, data) else {
-					return Err("Failed to create image buffer for export".to_string());
-				};
-
-				let mut encoded = Vec::new();
-				let mut cursor = std::io::Cursor::new(&mut encoded);
-
-				match file_type {
-					FileType::Png => {
-						let result = if transparent_background {
-							image.write_to(&mut cursor, ImageFormat::Png)
-						} else {
-							let image: RgbImage = image.convert();
-							image.write_to(&mut cursor, ImageFormat::Png)
-						};
-						if let Err(err) = result {
-							return Err(format!("Failed to encode PNG: {err}"));
-						}
-					}
-					FileType::Jpg => {
-						let image: RgbImage = image.convert();
-						let result = image.write_to(&mut cursor, ImageFormat::Jpeg);
-						if let Err(err) = result {
-							return Err(format!("Failed to encode JPG: {err}"));
-						}
-					}
-					FileType::Svg => {
-						return Err("SVG cannot be exported from an image buffer".to_string());
-					}
-				}
-
+			}) if file_type.is_raster() => {
+				let encoded = encoding.encode(data, width, height)?;
 				responses.add(FrontendMessage::TriggerSaveFile { name, content: encoded });
 			}
 			_ => {
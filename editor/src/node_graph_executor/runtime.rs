@@ -1,5 +1,5 @@
 use super::*;
-use crate::messages::frontend::utility_types::{ExportBounds, FileType};
+use crate::messages::frontend::utility_types::{BitDepth, ExportBounds, FileType};
 use glam::{DAffine2, DVec2};
 use graph_craft::document::value::TaggedValue;
 use graph_craft::document::{NodeId, NodeNetwork};
@@ -81,6 +81,8 @@ pub struct ExportConfig {
 	pub name: String,
 	pub file_type: FileType,
 	pub scale_factor: f64,
+	pub quality: f64,
+	pub bit_depth: BitDepth,
 	pub bounds: ExportBounds,
 	pub transparent_background: bool,
 	pub size: DVec2,
@@ -54,6 +54,8 @@ export function createInputManager(editor: Editor, dialog: DialogState, portfoli
 		{ target: window, eventName: "wheel", action: (e: WheelEvent) => onWheelScroll(e), options: { passive: false } },
 		{ target: window, eventName: "modifyinputfield", action: (e: CustomEvent) => onModifyInputField(e) },
 		{ target: window, eventName: "focusout", action: () => (canvasFocused = false) },
+		{ target: window, eventName: "focusin", action: (e: FocusEvent) => onTextInputFocusChange(e) },
+		{ target: window, eventName: "focusout", action: (e: FocusEvent) => onTextInputFocusChange(e) },
 		{ target: window.document, eventName: "contextmenu", action: (e: MouseEvent) => onContextMenu(e) },
 		{ target: window.document, eventName: "fullscreenchange", action: () => fullscreen.fullscreenModeChanged() },
 		{ target: window.document.body, eventName: "paste", action: (e: ClipboardEvent) => onPaste(e) },
@@ -294,6 +296,12 @@ export function createInputManager(editor: Editor, dialog: DialogState, portfoli
 		textToolInteractiveInputElement = e.detail;
 	}
 
+	// Tells the editor whether pasted text goes into a focused text input, since on desktop the backend handles pasting even into text inputs.
+	// Focus moving between two inputs fires a `focusout` followed by a `focusin`, so the last one leaves the correct state.
+	function onTextInputFocusChange(e: FocusEvent) {
+		editor.handle.setTextInputFocused(e.type === "focusin" && targetIsTextField(e.target || undefined));
+	}
+
 	// Window events
 
 	async function onBeforeUnload(e: BeforeUnloadEvent) {
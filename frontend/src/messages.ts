@@ -354,27 +354,52 @@ export type HSV = { h: number; s: number; v: number };
 export type RGBA = { r: number; g: number; b: number; a: number };
 export type RGB = { r: number; g: number; b: number };
 
+export type GradientInterpolation = "Srgb" | "LinearSrgb" | "Oklab" | "Oklch";
+export type HueDirection = "Shorter" | "Longer" | "Increasing" | "Decreasing";
+
 export class Gradient {
 	readonly stops!: { position: number; color: Color }[];
 
-	constructor(stops: { position: number; color: Color }[]) {
+	// For the span following each stop, the fraction of the way to the next stop where its colors are blended evenly
+	readonly midpoints!: number[];
+
+	readonly interpolation!: GradientInterpolation;
+
+	readonly hueDirection!: HueDirection;
+
+	constructor(stops: { position: number; color: Color }[], midpoints: number[] = [], interpolation: GradientInterpolation = "Srgb", hueDirection: HueDirection = "Shorter") {
 		this.stops = stops;
+		this.midpoints = midpoints;
+		this.interpolation = interpolation;
+		this.hueDirection = hueDirection;
 	}
 
 	toLinearGradientCSS(): string {
 		if (this.stops.length === 1) {
 			return `linear-gradient(to right, ${this.stops[0].color.toHexOptionalAlpha()} 0%, ${this.stops[0].color.toHexOptionalAlpha()} 100%)`;
 		}
-		const pieces = this.stops.map((stop) => `${stop.color.toHexOptionalAlpha()} ${stop.position * 100}%`);
-		return `linear-gradient(to right, ${pieces.join(", ")})`;
+		return this.cssWithPieces(this.stops.map((stop) => stop.color.toHexOptionalAlpha()));
 	}
 
 	toLinearGradientCSSNoAlpha(): string {
 		if (this.stops.length === 1) {
 			return `linear-gradient(to right, ${this.stops[0].color.toHexNoAlpha()} 0%, ${this.stops[0].color.toHexNoAlpha()} 100%)`;
 		}
-		const pieces = this.stops.map((stop) => `${stop.color.toHexNoAlpha()} ${stop.position * 100}%`);
-		return `linear-gradient(to right, ${pieces.join(", ")})`;
+		return this.cssWithPieces(this.stops.map((stop) => stop.color.toHexNoAlpha()));
+	}
+
+	// Builds the CSS gradient from the stop colors, blended in the gradient's interpolation space with color hints at any midpoints which aren't halfway
+	cssWithPieces(colors: string[]): string {
+		const pieces = this.stops.flatMap((stop, index) => {
+			const piece = `${colors[index]} ${stop.position * 100}%`;
+			const midpoint = this.midpoints[index];
+			const next = this.stops[index + 1];
+			if (next === undefined || midpoint === undefined || midpoint === 0.5) return [piece];
+			return [piece, `${(stop.position + (next.position - stop.position) * midpoint) * 100}%`];
+		});
+
+		const space = { Srgb: "", LinearSrgb: " in srgb-linear", Oklab: " in oklab", Oklch: ` in oklch ${this.hueDirection.toLowerCase()} hue` }[this.interpolation];
+		return `linear-gradient(to right${space}, ${pieces.join(", ")})`;
 	}
 
 	firstColor(): Color | undefined {
@@ -656,6 +681,10 @@ export class UpdateDocumentArtwork extends JsMessage {
 	readonly svg!: string;
 }
 
+export class UpdateDocumentDpi extends JsMessage {
+	readonly dpi!: number;
+}
+
 export class UpdateDocumentScrollbars extends JsMessage {
 	@TupleToVec2
 	readonly position!: XY;
@@ -776,10 +805,30 @@ export class TriggerExportImage extends JsMessage {
 
 	readonly mime!: string;
 
+	readonly quality!: number;
+
 	@TupleToVec2
 	readonly size!: XY;
 }
 
+export type RasterEncoding = {
+	fileType: "Png" | "Jpg" | "Webp" | "Avif" | "Tiff" | "Svg" | "Pdf";
+	quality: number;
+	bitDepth: "Eight" | "Sixteen";
+	transparentBackground: boolean;
+};
+
+export class TriggerRasterizeExport extends JsMessage {
+	readonly svg!: string;
+
+	readonly name!: string;
+
+	@TupleToVec2
+	readonly size!: XY;
+
+	readonly encoding!: RasterEncoding;
+}
+
 export class TriggerSaveFile extends JsMessage {
 	readonly name!: string;
 
@@ -953,11 +1002,11 @@ export class ColorInput extends WidgetProps {
 		if (value instanceof Gradient) return value;
 		const gradient = value["Gradient"];
 		if (gradient) {
-			const stops = gradient.map(([position, color]: [number, color: { red: number; green: number; blue: number; alpha: number }]) => ({
+			const stops = gradient.stops.map(([position, color]: [number, color: { red: number; green: number; blue: number; alpha: number }]) => ({
 				position,
 				color: new Color(color.red, color.green, color.blue, color.alpha),
 			}));
-			return new Gradient(stops);
+			return new Gradient(stops, gradient.midpoints, gradient.interpolation, gradient.hue_direction);
 		}
 
 		if (value instanceof Color) return value;
@@ -1697,6 +1746,7 @@ export const messageMakers: Record<string, MessageMaker> = {
 	TriggerOpenLaunchDocuments,
 	TriggerPersistenceRemoveDocument,
 	TriggerPersistenceWriteDocument,
+	TriggerRasterizeExport,
 	TriggerSaveActiveDocument,
 	TriggerSaveDocument,
 	TriggerSaveFile,
@@ -1716,6 +1766,7 @@ export const messageMakers: Record<string, MessageMaker> = {
 	UpdateDialogColumn2,
 	UpdateDocumentArtwork,
 	UpdateDocumentBarLayout,
+	UpdateDocumentDpi,
 	UpdateDocumentLayerDetails,
 	UpdateDocumentLayerStructure,
 	UpdateDocumentRulers,
@@ -12,6 +12,7 @@ import {
 	UpdateNodeGraphControlBarLayout,
 	UpdateGraphViewOverlay,
 	UpdateGraphFadeArtwork,
+	UpdateDocumentDpi,
 } from "@graphite/messages";
 import type { Layout } from "@graphite/messages";
 
@@ -26,6 +27,8 @@ export function createDocumentState(editor: Editor) {
 		// Graph view overlay
 		graphViewOverlayOpen: false,
 		fadeArtwork: 100,
+		// Pixels per inch of the physical lengths typed into number inputs
+		dpi: 96,
 	});
 	const { subscribe, update } = state;
 
@@ -75,6 +78,14 @@ export function createDocumentState(editor: Editor) {
 		});
 	});
 
+	// Relate physical lengths to pixels
+	editor.subscriptions.subscribeJsMessage(UpdateDocumentDpi, (data) => {
+		update((state) => {
+			state.dpi = data.dpi;
+			return state;
+		});
+	});
+
 	// Show or hide the graph view overlay
 	editor.subscriptions.subscribeJsMessage(UpdateGraphViewOverlay, (data) => {
 		update((state) => {
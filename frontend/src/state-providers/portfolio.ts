@@ -6,6 +6,7 @@ import {
 	TriggerFetchAndOpenDocument,
 	TriggerSaveDocument,
 	TriggerExportImage,
+	TriggerRasterizeExport,
 	TriggerSaveFile,
 	TriggerImport,
 	TriggerOpen,
@@ -16,7 +17,7 @@ import {
 	UpdateLayersPanelState,
 } from "@graphite/messages";
 import { downloadFile, downloadFileBlob, upload } from "@graphite/utility-functions/files";
-import { rasterizeSVG } from "@graphite/utility-functions/rasterization";
+import { rasterizeSVG, rasterizeSVGCanvas } from "@graphite/utility-functions/rasterization";
 
 export function createPortfolioState(editor: Editor) {
 	const { subscribe, update } = writable({
@@ -71,14 +72,22 @@ export function createPortfolioState(editor: Editor) {
 		downloadFile(data.name, data.content);
 	});
 	editor.subscriptions.subscribeJsMessage(TriggerExportImage, async (data) => {
-		const { svg, name, mime, size } = data;
+		const { svg, name, mime, quality, size } = data;
 
 		// Fill the canvas with white if it'll be a JPEG (which does not support transparency and defaults to black)
 		const backgroundColor = mime.endsWith("jpeg") ? "white" : undefined;
 
 		// Rasterize the SVG to an image file
 		try {
-			const blob = await rasterizeSVG(svg, size.x, size.y, mime, backgroundColor);
+			const blob = await rasterizeSVG(svg, size.x, size.y, mime, backgroundColor, quality);
+
+			// Browsers fall back to PNG for formats they can't encode, such as lossy WebP in Safari
+			if (blob.type !== mime) {
+				const format = name.split(".").pop()?.toUpperCase() || mime;
+				const description = `This browser is unable to encode ${format} images at the chosen quality. Export at 100% quality or use another browser.`;
+				editor.handle.errorDialog("Unable to export document", description);
+				return;
+			}
 
 			// Have the browser download the file to the user's disk
 			downloadFileBlob(name, blob);
@@ -86,6 +95,20 @@ export function createPortfolioState(editor: Editor) {
 			// Fail silently if there's an error rasterizing the SVG, such as a zero-sized image
 		}
 	});
+	editor.subscriptions.subscribeJsMessage(TriggerRasterizeExport, async (data) => {
+		const { svg, name, size, encoding } = data;
+
+		// Rasterize the SVG and have the editor encode its pixels into a format which the browser can't produce itself
+		try {
+			const canvas = await rasterizeSVGCanvas(svg, size.x, size.y);
+			const imageData = canvas.getContext("2d")?.getImageData(0, 0, canvas.width, canvas.height);
+			if (!imageData) return;
+
+			editor.handle.encodeRasterizedExport(name, new Uint8Array(imageData.data.buffer), imageData.width, imageData.height, encoding);
+		} catch {
+			// Fail silently if there's an error rasterizing the SVG, such as a zero-sized image
+		}
+	});
 	editor.subscriptions.subscribeJsMessage(UpdateDataPanelState, async (data) => {
 		update((state) => {
 			state.dataPanelOpen = data.open;
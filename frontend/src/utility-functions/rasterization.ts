@@ -34,7 +34,8 @@ export async function rasterizeSVGCanvas(svg: string, width: number, height: num
 }
 
 // Rasterize the string of an SVG document at a given width and height and turn it into the blob data of an image file matching the given MIME type
-export async function rasterizeSVG(svg: string, width: number, height: number, mime: string, backgroundColor?: string): Promise<Blob> {
+// The quality, from 0 to 1, is used by lossy formats like JPEG and WebP
+export async function rasterizeSVG(svg: string, width: number, height: number, mime: string, backgroundColor?: string, quality?: number): Promise<Blob> {
 	if (!width || !height) throw new Error("Width and height must be nonzero when given to rasterizeSVG()");
 
 	const canvas = await rasterizeSVGCanvas(svg, width, height, backgroundColor);
@@ -43,7 +44,7 @@ export async function rasterizeSVG(svg: string, width: number, height: number, m
 	const blob = await new Promise<Blob | undefined>((resolve) => {
 		canvas.toBlob((blob) => {
 			resolve(blob || undefined);
-		}, mime);
+		}, mime, quality);
 	});
 
 	if (!blob) throw new Error("Converting canvas to blob data failed in rasterizeSVG()");
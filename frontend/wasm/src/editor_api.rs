@@ -8,6 +8,7 @@ use crate::helpers::translate_key;
 use crate::{EDITOR_HANDLE, EDITOR_HAS_CRASHED, Error, MESSAGE_BUFFER};
 use editor::consts::FILE_EXTENSION;
 use editor::messages::clipboard::utility_types::ClipboardContentRaw;
+use editor::messages::frontend::utility_types::RasterEncoding;
 use editor::messages::input_mapper::utility_types::input_keyboard::ModifierKeys;
 use editor::messages::input_mapper::utility_types::input_mouse::{EditorMouseState, ScrollDelta};
 use editor::messages::portfolio::document::utility_types::document_metadata::LayerNodeIdentifier;
@@ -19,6 +20,7 @@ use graph_craft::document::NodeId;
 use graphene_std::raster::Image;
 use graphene_std::raster::color::Color;
 use js_sys::{Object, Reflect};
+use math_parser::units::{FieldUnit, UnitSystem};
 use serde::Serialize;
 use serde_wasm_bindgen::{self, from_value};
 use std::cell::RefCell;
@@ -757,6 +759,24 @@ impl EditorHandle {
 		self.dispatch(message);
 	}
 
+	/// Tells the editor whether a text input has focus, so pasted text goes into it rather than becoming artwork
+	#[wasm_bindgen(js_name = setTextInputFocused)]
+	pub fn set_text_input_focused(&self, focused: bool) {
+		let message = ClipboardMessage::TextInputFocus { focused };
+		self.dispatch(message);
+	}
+
+	/// The pixels of a rasterized export, which are encoded into an image file in a format the browser can't produce itself
+	#[wasm_bindgen(js_name = encodeRasterizedExport)]
+	pub fn encode_rasterized_export(&self, name: String, data: Vec<u8>, width: u32, height: u32, encoding: JsValue) -> Result<(), JsValue> {
+		// Deserializing from TS type: `{ fileType: string; quality: number; bitDepth: string; transparentBackground: boolean }`
+		let encoding = serde_wasm_bindgen::from_value::<RasterEncoding>(encoding)?;
+		let message = PortfolioMessage::EncodeRasterizedExport { name, data, width, height, encoding };
+		self.dispatch(message);
+
+		Ok(())
+	}
+
 	/// Pastes an image
 	#[wasm_bindgen(js_name = pasteImage)]
 	pub fn paste_image(
@@ -882,12 +902,25 @@ impl EditorHandle {
 
 // ============================================================================
 
+/// Evaluates the expression entered into a number input, converting the units it uses into the unit displayed by the input.
+/// Percentages are of the input's current value, except in inputs which are themselves measured in percent, and physical lengths use the document's DPI.
 #[wasm_bindgen(js_name = evaluateMathExpression)]
-pub fn evaluate_math_expression(expression: &str) -> Option<f64> {
-	let value = math_parser::evaluate(expression)
+pub fn evaluate_math_expression(expression: &str, unit: &str, current_value: Option<f64>, dpi: f64) -> Option<f64> {
+	let (field_unit, percentage_reference) = match unit.trim() {
+		"px" => (FieldUnit::Pixels, current_value),
+		"°" => (FieldUnit::Degrees, current_value),
+		"%" => (FieldUnit::Unitless, Some(100.)),
+		_ => (FieldUnit::Unitless, current_value),
+	};
+	let units = UnitSystem {
+		dpi,
+		field_unit,
+		percentage_reference,
+	};
+
+	let value = math_parser::evaluate_in_field(expression, &units)
 		.inspect_err(|err| error!("Math parser error on \"{expression}\": {err}"))
 		.ok()?
-		.0
 		.inspect_err(|err| error!("Math evaluate error on \"{expression}\": {err} "))
 		.ok()?;
 	let Some(real) = value.as_real() else {
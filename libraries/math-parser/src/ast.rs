@@ -1,6 +1,7 @@
 use crate::value::Complex;
+use std::fmt::{Display, Formatter};
 
-#[derive(Debug, PartialEq, Eq)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
 pub struct Unit {
 	// Exponent of length unit (meters)
 	pub length: i32,
@@ -8,6 +9,8 @@ pub struct Unit {
 	pub mass: i32,
 	// Exponent of time unit (seconds)
 	pub time: i32,
+	// Exponent of angle unit (radians)
+	pub angle: i32,
 }
 
 impl Default for Unit {
@@ -17,16 +20,57 @@ impl Default for Unit {
 }
 
 impl Unit {
-	pub const BASE_UNIT: Unit = Unit { length: 0, mass: 0, time: 0 };
+	pub const BASE_UNIT: Unit = Unit {
+		length: 0,
+		mass: 0,
+		time: 0,
+		angle: 0,
+	};
 
-	pub const LENGTH: Unit = Unit { length: 1, mass: 0, time: 0 };
-	pub const MASS: Unit = Unit { length: 0, mass: 1, time: 0 };
-	pub const TIME: Unit = Unit { length: 0, mass: 0, time: 1 };
+	pub const LENGTH: Unit = Unit {
+		length: 1,
+		mass: 0,
+		time: 0,
+		angle: 0,
+	};
+	pub const MASS: Unit = Unit {
+		length: 0,
+		mass: 1,
+		time: 0,
+		angle: 0,
+	};
+	pub const TIME: Unit = Unit {
+		length: 0,
+		mass: 0,
+		time: 1,
+		angle: 0,
+	};
+	pub const ANGLE: Unit = Unit {
+		length: 0,
+		mass: 0,
+		time: 0,
+		angle: 1,
+	};
 
-	pub const VELOCITY: Unit = Unit { length: 1, mass: 0, time: -1 };
-	pub const ACCELERATION: Unit = Unit { length: 1, mass: 0, time: -2 };
+	pub const VELOCITY: Unit = Unit {
+		length: 1,
+		mass: 0,
+		time: -1,
+		angle: 0,
+	};
+	pub const ACCELERATION: Unit = Unit {
+		length: 1,
+		mass: 0,
+		time: -2,
+		angle: 0,
+	};
 
-	pub const FORCE: Unit = Unit { length: 1, mass: 1, time: -2 };
+	pub const FORCE: Unit = Unit {
+		length: 1,
+		mass: 1,
+		time: -2,
+		angle: 0,
+	};
 
 	pub fn base_unit() -> Self {
 		Self::BASE_UNIT
@@ -37,10 +81,34 @@ impl Unit {
 	}
 }
 
+impl Display for Unit {
+	fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+		if self.is_base() {
+			return write!(f, "unitless");
+		}
+
+		let exponents = [("m", self.length), ("g", self.mass), ("s", self.time), ("rad", self.angle)];
+		let mut first = true;
+		for (symbol, exponent) in exponents.into_iter().filter(|&(_, exponent)| exponent != 0) {
+			if !first {
+				write!(f, "·")?;
+			}
+			first = false;
+
+			match exponent {
+				1 => write!(f, "{symbol}")?,
+				exponent => write!(f, "{symbol}^{exponent}")?,
+			}
+		}
+		Ok(())
+	}
+}
+
 #[derive(Debug, PartialEq)]
 pub enum Literal {
 	Float(f64),
 	Complex(Complex),
+	Bool(bool),
 }
 
 impl From<f64> for Literal {
@@ -55,7 +123,50 @@ pub enum BinaryOp {
 	Sub,
 	Mul,
 	Div,
+	Mod,
 	Pow,
+	Eq,
+	Ne,
+	Lt,
+	Le,
+	Gt,
+	Ge,
+	And,
+	Or,
+}
+
+impl BinaryOp {
+	/// Whether the operator compares its operands, producing a boolean.
+	pub fn is_comparison(self) -> bool {
+		matches!(self, Self::Eq | Self::Ne | Self::Lt | Self::Le | Self::Gt | Self::Ge)
+	}
+
+	/// Whether the operator combines boolean operands.
+	pub fn is_logical(self) -> bool {
+		matches!(self, Self::And | Self::Or)
+	}
+}
+
+impl Display for BinaryOp {
+	fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+		let symbol = match self {
+			Self::Add => "+",
+			Self::Sub => "-",
+			Self::Mul => "*",
+			Self::Div => "/",
+			Self::Mod => "%",
+			Self::Pow => "^",
+			Self::Eq => "==",
+			Self::Ne => "!=",
+			Self::Lt => "<",
+			Self::Le => "<=",
+			Self::Gt => ">",
+			Self::Ge => ">=",
+			Self::And => "and",
+			Self::Or => "or",
+		};
+		write!(f, "{symbol}")
+	}
 }
 
 #[derive(Debug, PartialEq, Clone, Copy)]
@@ -63,6 +174,19 @@ pub enum UnaryOp {
 	Neg,
 	Sqrt,
 	Fac,
+	Not,
+}
+
+impl Display for UnaryOp {
+	fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+		let symbol = match self {
+			Self::Neg => "-",
+			Self::Sqrt => "sqrt",
+			Self::Fac => "!",
+			Self::Not => "not",
+		};
+		write!(f, "{symbol}")
+	}
 }
 
 #[derive(Debug, PartialEq)]
@@ -72,4 +196,6 @@ pub enum Node {
 	FnCall { name: String, expr: Vec<Node> },
 	BinOp { lhs: Box<Node>, op: BinaryOp, rhs: Box<Node> },
 	UnaryOp { expr: Box<Node>, op: UnaryOp },
+	Component { expr: Box<Node>, name: String },
+	Conditional { condition: Box<Node>, if_true: Box<Node>, if_false: Box<Node> },
 }
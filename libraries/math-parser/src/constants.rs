@@ -1,4 +1,5 @@
 use crate::value::{Number, Value};
+use glam::{DVec2, DVec4};
 use lazy_static::lazy_static;
 use num_complex::{Complex, ComplexFloat};
 use std::collections::HashMap;
@@ -117,6 +118,136 @@ lazy_static! {
 			}),
 		);
 
+		map.insert(
+			"min",
+			Box::new(|values| match values {
+				[] => None,
+				values => values.iter().map(Value::as_real).try_fold(f64::INFINITY, |min, value| Some(min.min(value?))).map(Value::from_f64),
+			}),
+		);
+
+		map.insert(
+			"max",
+			Box::new(|values| match values {
+				[] => None,
+				values => values.iter().map(Value::as_real).try_fold(f64::NEG_INFINITY, |max, value| Some(max.max(value?))).map(Value::from_f64),
+			}),
+		);
+
+		map.insert(
+			"clamp",
+			Box::new(|values| match values {
+				[Value::Number(Number::Real(value)), Value::Number(Number::Real(min)), Value::Number(Number::Real(max))] => Some(Value::from_f64(value.max(*min).min(*max))),
+				_ => None,
+			}),
+		);
+
+		map.insert(
+			"lerp",
+			Box::new(|values| match values {
+				[Value::Number(Number::Real(start)), Value::Number(Number::Real(end)), Value::Number(Number::Real(t))] => Some(Value::from_f64(start + (end - start) * t)),
+				[Value::Vec2(start), Value::Vec2(end), Value::Number(Number::Real(t))] => Some(Value::Vec2(start.lerp(*end, *t))),
+				[Value::Color(start), Value::Color(end), Value::Number(Number::Real(t))] => Some(Value::Color(start.lerp(*end, *t))),
+				_ => None,
+			}),
+		);
+
+		map.insert(
+			"smoothstep",
+			Box::new(|values| match values {
+				[Value::Number(Number::Real(edge0)), Value::Number(Number::Real(edge1)), Value::Number(Number::Real(x))] => {
+					let t = ((x - edge0) / (edge1 - edge0)).clamp(0., 1.);
+					Some(Value::from_f64(t * t * (3. - 2. * t)))
+				}
+				_ => None,
+			}),
+		);
+
+		map.insert(
+			"vec2",
+			Box::new(|values| match values {
+				[Value::Number(Number::Real(x)), Value::Number(Number::Real(y))] => Some(Value::Vec2(DVec2::new(*x, *y))),
+				_ => None,
+			}),
+		);
+
+		map.insert(
+			"length",
+			Box::new(|values| match values {
+				[Value::Vec2(vec)] => Some(Value::from_f64(vec.length())),
+				_ => None,
+			}),
+		);
+
+		map.insert(
+			"distance",
+			Box::new(|values| match values {
+				[Value::Vec2(a), Value::Vec2(b)] => Some(Value::from_f64(a.distance(*b))),
+				_ => None,
+			}),
+		);
+
+		map.insert(
+			"dot",
+			Box::new(|values| match values {
+				[Value::Vec2(a), Value::Vec2(b)] => Some(Value::from_f64(a.dot(*b))),
+				_ => None,
+			}),
+		);
+
+		map.insert(
+			"cross",
+			Box::new(|values| match values {
+				[Value::Vec2(a), Value::Vec2(b)] => Some(Value::from_f64(a.perp_dot(*b))),
+				_ => None,
+			}),
+		);
+
+		map.insert(
+			"normalize",
+			Box::new(|values| match values {
+				[Value::Vec2(vec)] => Some(Value::Vec2(vec.normalize_or_zero())),
+				_ => None,
+			}),
+		);
+
+		map.insert(
+			"angle",
+			Box::new(|values| match values {
+				[Value::Vec2(vec)] => Some(Value::from_f64(vec.y.atan2(vec.x))),
+				_ => None,
+			}),
+		);
+
+		map.insert(
+			"rotate",
+			Box::new(|values| match values {
+				[Value::Vec2(vec), Value::Number(Number::Real(angle))] => Some(Value::Vec2(DVec2::from_angle(*angle).rotate(*vec))),
+				_ => None,
+			}),
+		);
+
+		map.insert(
+			"rgb",
+			Box::new(|values| match values {
+				[Value::Number(Number::Real(r)), Value::Number(Number::Real(g)), Value::Number(Number::Real(b))] => Some(Value::Color(DVec4::new(*r, *g, *b, 1.))),
+				_ => None,
+			}),
+		);
+
+		map.insert(
+			"rgba",
+			Box::new(|values| match values {
+				[
+					Value::Number(Number::Real(r)),
+					Value::Number(Number::Real(g)),
+					Value::Number(Number::Real(b)),
+					Value::Number(Number::Real(a)),
+				] => Some(Value::Color(DVec4::new(*r, *g, *b, *a))),
+				_ => None,
+			}),
+		);
+
 		map
 	};
 }
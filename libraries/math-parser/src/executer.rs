@@ -1,4 +1,4 @@
-use crate::ast::{Literal, Node};
+use crate::ast::{BinaryOp, Literal, Node, UnaryOp};
 use crate::constants::DEFAULT_FUNCTIONS;
 use crate::context::{EvalContext, FunctionProvider, ValueProvider};
 use crate::value::{Number, Value};
@@ -6,13 +6,21 @@ use thiserror::Error;
 
 #[derive(Debug, Error)]
 pub enum EvalError {
-	#[error("Missing value: {0}")]
+	#[error("Unknown variable `{0}`")]
 	MissingValue(String),
 
-	#[error("Missing function: {0}")]
+	#[error("Unknown function `{0}`")]
 	MissingFunction(String),
-	#[error("Wrong type for function call")]
-	TypeError,
+	#[error("Wrong arguments for the function `{0}`")]
+	InvalidArguments(String),
+	#[error("Can't use `{0}` with these values")]
+	InvalidBinaryOp(BinaryOp),
+	#[error("Can't use `{0}` with this value")]
+	InvalidUnaryOp(UnaryOp),
+	#[error("The condition must be a boolean or real number")]
+	InvalidCondition,
+	#[error("No component `{0}` on this value")]
+	MissingComponent(String),
 }
 
 impl Node {
@@ -21,19 +29,40 @@ impl Node {
 			Node::Lit(lit) => match lit {
 				Literal::Float(num) => Ok(Value::from_f64(*num)),
 				Literal::Complex(num) => Ok(Value::Number(Number::Complex(*num))),
+				Literal::Bool(value) => Ok(Value::Bool(*value)),
 			},
 
-			Node::BinOp { lhs, op, rhs } => match (lhs.eval(context)?, rhs.eval(context)?) {
-				(Value::Number(lhs), Value::Number(rhs)) => Ok(Value::Number(lhs.binary_op(*op, rhs))),
-			},
-			Node::UnaryOp { expr, op } => match expr.eval(context)? {
-				Value::Number(num) => Ok(Value::Number(num.unary_op(*op))),
-			},
+			Node::BinOp { lhs, op, rhs } => {
+				let lhs = lhs.eval(context)?;
+
+				// Short-circuit the logical operators, so the right-hand side may be left undefined
+				let short_circuit = match op {
+					BinaryOp::And => Some(false),
+					BinaryOp::Or => Some(true),
+					_ => None,
+				};
+				if let Some(result) = short_circuit {
+					if lhs.as_bool().ok_or(EvalError::InvalidBinaryOp(*op))? == result {
+						return Ok(Value::Bool(result));
+					}
+				}
+
+				lhs.binary_op(*op, rhs.eval(context)?).ok_or(EvalError::InvalidBinaryOp(*op))
+			}
+			Node::UnaryOp { expr, op } => expr.eval(context)?.unary_op(*op).ok_or(EvalError::InvalidUnaryOp(*op)),
+			Node::Component { expr, name } => expr.eval(context)?.component(name).ok_or_else(|| EvalError::MissingComponent(name.clone())),
+			Node::Conditional { condition, if_true, if_false } => {
+				if condition.eval(context)?.as_bool().ok_or(EvalError::InvalidCondition)? {
+					if_true.eval(context)
+				} else {
+					if_false.eval(context)
+				}
+			}
 			Node::Var(name) => context.get_value(name).ok_or_else(|| EvalError::MissingValue(name.clone())),
 			Node::FnCall { name, expr } => {
 				let values = expr.iter().map(|expr| expr.eval(context)).collect::<Result<Vec<Value>, EvalError>>()?;
 				if let Some(function) = DEFAULT_FUNCTIONS.get(&name.as_str()) {
-					function(&values).ok_or(EvalError::TypeError)
+					function(&values).ok_or_else(|| EvalError::InvalidArguments(name.clone()))
 				} else if let Some(val) = context.run_function(name, &values) {
 					Ok(val)
 				} else {
@@ -96,5 +125,29 @@ mod tests {
 			 op: BinaryOp::Pow,
 			 rhs: Box::new(Node::Lit(Literal::Float(3.0))),
 		 },
+		test_modulo: Value::from_f64(1.0) => Node::BinOp {
+			lhs: Box::new(Node::Lit(Literal::Float(-5.0))),
+			op: BinaryOp::Mod,
+			rhs: Box::new(Node::Lit(Literal::Float(2.0))),
+		},
+		test_less_than: Value::Bool(true) => Node::BinOp {
+			lhs: Box::new(Node::Lit(Literal::Float(2.0))),
+			op: BinaryOp::Lt,
+			rhs: Box::new(Node::Lit(Literal::Float(3.0))),
+		},
+		test_not: Value::Bool(true) => Node::UnaryOp {
+			expr: Box::new(Node::Lit(Literal::Float(0.0))),
+			op: UnaryOp::Not,
+		},
+		test_conditional: Value::from_f64(4.0) => Node::Conditional {
+			condition: Box::new(Node::Lit(Literal::Float(0.0))),
+			if_true: Box::new(Node::Lit(Literal::Float(3.0))),
+			if_false: Box::new(Node::Lit(Literal::Float(4.0))),
+		},
+		test_short_circuit: Value::Bool(false) => Node::BinOp {
+			lhs: Box::new(Node::Lit(Literal::Float(0.0))),
+			op: BinaryOp::And,
+			rhs: Box::new(Node::Var("undefined".to_string())),
+		},
 	}
 }
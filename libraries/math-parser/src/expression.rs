@@ -0,0 +1,208 @@
+use crate::ast::{Node, Unit};
+use crate::context::{EvalContext, NothingMap, ValueProvider};
+use crate::executer::EvalError;
+use crate::parser::{Name, NameKind, ParseError, Span, parse_with_names};
+use crate::units::UnitSystem;
+use crate::value::Value;
+use thiserror::Error;
+
+/// An error in evaluating a compiled expression, along with the part of the expression which caused it.
+#[derive(Debug, Error)]
+#[error("{error}")]
+pub struct ExpressionEvalError {
+	pub error: EvalError,
+	pub span: Span,
+}
+
+/// An expression which is parsed once and may then be evaluated many times with different values for its variables.
+#[derive(Debug, PartialEq)]
+pub struct CompiledExpression {
+	node: Node,
+	unit: Unit,
+	variables: Vec<String>,
+	names: Vec<Name>,
+	source_length: usize,
+}
+
+impl CompiledExpression {
+	pub fn compile(expression: &str) -> Result<Self, ParseError> {
+		let (node, unit, names) = parse_with_names(expression, &UnitSystem::default())?;
+
+		let mut variables = Vec::new();
+		collect_variables(&node, &mut variables);
+
+		Ok(Self {
+			node,
+			unit,
+			variables,
+			names,
+			source_length: expression.len(),
+		})
+	}
+
+	/// The names of the free variables in the expression, in the order they first appear.
+	pub fn variables(&self) -> &[String] {
+		&self.variables
+	}
+
+	pub fn unit(&self) -> Unit {
+		self.unit
+	}
+
+	/// Evaluates the expression with the given values for its variables, in the same order as [`Self::variables`].
+	pub fn evaluate(&self, values: &[Value]) -> Result<Value, ExpressionEvalError> {
+		let values = VariableValues { names: &self.variables, values };
+		self.node.eval(&EvalContext::new(values, NothingMap)).map_err(|error| {
+			let span = self.error_span(&error);
+			ExpressionEvalError { error, span }
+		})
+	}
+
+	/// The first appearance of the name which an error concerns, or the whole expression for errors which aren't about a name.
+	fn error_span(&self, error: &EvalError) -> Span {
+		let (kind, name) = match error {
+			EvalError::MissingValue(name) => (NameKind::Variable, name),
+			EvalError::MissingFunction(name) | EvalError::InvalidArguments(name) => (NameKind::Function, name),
+			EvalError::MissingComponent(name) => (NameKind::Component, name),
+			_ => return 0..self.source_length,
+		};
+
+		self.names
+			.iter()
+			.find(|candidate| candidate.kind == kind && &candidate.name == name)
+			.map_or(0..self.source_length, |name| name.span.clone())
+	}
+}
+
+struct VariableValues<'a> {
+	names: &'a [String],
+	values: &'a [Value],
+}
+
+impl ValueProvider for VariableValues<'_> {
+	fn get_value(&self, name: &str) -> Option<Value> {
+		let index = self.names.iter().position(|variable| variable == name)?;
+		self.values.get(index).copied()
+	}
+}
+
+fn collect_variables(node: &Node, variables: &mut Vec<String>) {
+	match node {
+		Node::Lit(_) => {}
+		Node::Var(name) => {
+			if !variables.contains(name) {
+				variables.push(name.clone());
+			}
+		}
+		Node::FnCall { expr, .. } => expr.iter().for_each(|expr| collect_variables(expr, variables)),
+		Node::BinOp { lhs, rhs, .. } => {
+			collect_variables(lhs, variables);
+			collect_variables(rhs, variables);
+		}
+		Node::UnaryOp { expr, .. } | Node::Component { expr, .. } => collect_variables(expr, variables),
+		Node::Conditional { condition, if_true, if_false } => {
+			collect_variables(condition, variables);
+			collect_variables(if_true, variables);
+			collect_variables(if_false, variables);
+		}
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use crate::ast::{BinaryOp, UnaryOp};
+
+	#[test]
+	fn variables_in_order_of_appearance() {
+		let expression = CompiledExpression::compile("width * count + sqrt(width) - max(count, gap) > 0 ? offset.x : 1").unwrap();
+		assert_eq!(expression.variables(), ["width", "count", "gap", "offset"]);
+	}
+
+	#[test]
+	fn evaluate_many_times() {
+		let expression = CompiledExpression::compile("index % columns * spacing").unwrap();
+		assert_eq!(expression.variables(), ["index", "columns", "spacing"]);
+
+		let results = (0..6)
+			.map(|index| expression.evaluate(&[(index as f64).into(), 4_f64.into(), 10_f64.into()]).unwrap())
+			.collect::<Vec<_>>();
+		assert_eq!(results, [0., 10., 20., 30., 0., 10.].map(Value::from_f64));
+	}
+
+	#[test]
+	fn missing_value() {
+		let expression = CompiledExpression::compile("a + b").unwrap();
+		let error = expression.evaluate(&[1_f64.into()]).unwrap_err();
+		assert!(matches!(&error.error, EvalError::MissingValue(name) if name == "b"));
+		assert_eq!(error.span, 4..5);
+	}
+
+	#[test]
+	fn error_spans() {
+		let error = CompiledExpression::compile("A + sinn(A)").unwrap().evaluate(&[1_f64.into()]).unwrap_err();
+		assert_eq!(error.to_string(), "Unknown function `sinn`");
+		assert_eq!(error.span, 4..8);
+
+		let error = CompiledExpression::compile("2 * A.x").unwrap().evaluate(&[1_f64.into()]).unwrap_err();
+		assert_eq!(error.span, 6..7);
+
+		let error = CompiledExpression::compile("A * (1 + 2m)").unwrap_err();
+		assert_eq!(error.to_string(), "Can't use `+` between unitless and m values");
+		assert_eq!(error.span(), 5..11);
+
+		let error = CompiledExpression::compile("1 + 2m * 3").unwrap_err();
+		assert_eq!(error.span(), 0..10);
+
+		let error = CompiledExpression::compile("sqrt(4) $ 2").unwrap_err();
+		assert_eq!(error.to_string(), "Unexpected `$`");
+		assert_eq!(error.span(), 8..9);
+
+		let error = CompiledExpression::compile("(A + 1").unwrap_err();
+		assert_eq!(error.to_string(), "Unexpected end of expression");
+		assert_eq!(error.span(), 6..6);
+	}
+
+	#[test]
+	fn operator_errors() {
+		let compile_error = |expression| CompiledExpression::compile(expression).unwrap_err();
+		let eval_error = |expression| CompiledExpression::compile(expression).unwrap().evaluate(&[]).unwrap_err().error;
+
+		// Comparisons
+		let error = compile_error("1 < 2 < 3");
+		assert_eq!(error.to_string(), "Can't chain `<` after another comparison, so join them with `and` instead");
+		assert_eq!(error.span(), 0..9);
+		let error = compile_error("1 == 1 != true");
+		assert_eq!(error.span(), 0..14);
+		assert_eq!(compile_error("1m == 2s").to_string(), "Can't use `==` between m and s values");
+		assert!(matches!(eval_error("true < 1"), EvalError::InvalidBinaryOp(BinaryOp::Lt)));
+
+		// Logical operators
+		assert_eq!(compile_error("1m and 1").to_string(), "Can't use `and` between m and unitless values");
+		assert_eq!(compile_error("not 1m").to_string(), "Can't use `not` on a m value");
+		assert!(matches!(eval_error("vec2(1, 2) or 1"), EvalError::InvalidBinaryOp(BinaryOp::Or)));
+
+		// Modulo
+		assert_eq!(compile_error("5m mod 2s").to_string(), "Can't use `%` between m and s values");
+		assert_eq!(compile_error("5 mod").to_string(), "Unexpected end of expression");
+		assert!(matches!(eval_error("vec2(1, 2) mod rgb(1, 1, 1)"), EvalError::InvalidBinaryOp(BinaryOp::Mod)));
+
+		// Conditionals
+		assert_eq!(compile_error("1 ? 2m : 3s").to_string(), "The results of the conditional have different units: m and s");
+		assert!(matches!(eval_error("vec2(1, 2) ? 1 : 2"), EvalError::InvalidCondition));
+
+		// Booleans
+		assert!(matches!(eval_error("true + 1"), EvalError::InvalidBinaryOp(BinaryOp::Add)));
+		assert!(matches!(eval_error("-false"), EvalError::InvalidUnaryOp(UnaryOp::Neg)));
+	}
+
+	#[test]
+	fn factorial_of_negative_integer() {
+		let expression = CompiledExpression::compile("A!").unwrap();
+		assert_eq!(expression.evaluate(&[4_f64.into()]).unwrap(), Value::from_f64(24.));
+
+		let error = expression.evaluate(&[(-2_f64).into()]).unwrap_err();
+		assert!(matches!(error.error, EvalError::InvalidUnaryOp(crate::ast::UnaryOp::Fac)));
+		assert_eq!(error.span, 0..2);
+	}
+}
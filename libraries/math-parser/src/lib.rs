@@ -4,13 +4,16 @@ pub mod ast;
 mod constants;
 pub mod context;
 pub mod executer;
+pub mod expression;
 pub mod parser;
+pub mod units;
 pub mod value;
 
 use ast::Unit;
 use context::{EvalContext, ValueMap};
 use executer::EvalError;
-use parser::ParseError;
+use parser::{ParseError, TypeError};
+use units::UnitSystem;
 use value::Value;
 
 pub fn evaluate(expression: &str) -> Result<(Result<Value, EvalError>, Unit), ParseError> {
@@ -19,10 +22,27 @@ pub fn evaluate(expression: &str) -> Result<(Result<Value, EvalError>, Unit), Pa
 	expr.map(|(node, unit)| (node.eval(&context), unit))
 }
 
+/// Evaluates an expression entered into a number field, converting the units it uses, such as in `12pt + 3mm` or `45deg`, into the unit of the field.
+pub fn evaluate_in_field(expression: &str, units: &UnitSystem) -> Result<Result<Value, EvalError>, ParseError> {
+	let (node, unit) = ast::Node::try_parse_from_str_with_units(expression, units)?;
+	let scale = units.field_scale(unit).ok_or(ParseError::Type {
+		error: TypeError::IncompatibleFieldUnit(unit, units.field_unit),
+		span: 0..expression.len(),
+	})?;
+
+	let context = EvalContext::default();
+	Ok(node.eval(&context).and_then(|value| {
+		let op = ast::BinaryOp::Mul;
+		value.binary_op(op, Value::from_f64(scale)).ok_or(EvalError::InvalidBinaryOp(op))
+	}))
+}
+
 #[cfg(test)]
 mod tests {
 	use super::*;
 	use ast::Unit;
+	use glam::DVec2;
+	use units::FieldUnit;
 	use value::Number;
 
 	const EPSILON: f64 = 1e-10_f64;
@@ -75,6 +95,12 @@ mod tests {
 								assert!((actual_f - expected_f).abs() < EPSILON, "Expected {}, but got {}", expected_f, actual_f);
 							}
 						}
+						(Value::Bool(actual_b), Value::Bool(expected_b)) => {
+							assert!(actual_b == expected_b, "Expected {}, but got {}", expected_b, actual_b);
+						}
+						(Value::Vec2(actual_v), Value::Vec2(expected_v)) => {
+							assert!(actual_v.abs_diff_eq(expected_v, EPSILON), "Expected {}, but got {}", expected_v, actual_v);
+						}
 						// Handle mismatched types
 						_ => panic!("Mismatched types: expected {:?}, got {:?}", expected_value, actual_value),
 					}
@@ -88,7 +114,7 @@ mod tests {
 		// Basic arithmetic and units
 		infix_addition: "5 + 5" => (10., Unit::BASE_UNIT),
 		infix_subtraction_units: "5m - 3m" => (2., Unit::LENGTH),
-		infix_multiplication_units: "4s * 4s" => (16., Unit { length: 0, mass: 0, time: 2 }),
+		infix_multiplication_units: "4s * 4s" => (16., Unit { length: 0, mass: 0, time: 2, angle: 0 }),
 		infix_division_units: "8m/2s" => (4., Unit::VELOCITY),
 
 		// Order of operations
@@ -126,6 +152,14 @@ mod tests {
 		add_multiply_subtract_sqrt: "4 + 3 * (2 + 1) - sqrt(25)" => (8., Unit::BASE_UNIT),
 		add_sqrt_subtract_nested_multiply: "10 + sqrt(64) - (5 * (2 + 1))" => (3., Unit::BASE_UNIT),
 
+		// Factorial
+		factorial_whole: "5!" => (120., Unit::BASE_UNIT),
+		factorial_zero: "0!" => (1., Unit::BASE_UNIT),
+		factorial_precedence: "3! + 1" => (7., Unit::BASE_UNIT),
+		factorial_half: "0.5!" => (std::f64::consts::PI.sqrt() / 2., Unit::BASE_UNIT),
+		factorial_negative_half: "(-0.5)!" => (std::f64::consts::PI.sqrt(), Unit::BASE_UNIT),
+		factorial_overflow: "200!" => (f64::INFINITY, Unit::BASE_UNIT),
+
 		// Mathematical constants
 		constant_pi: "pi" => (std::f64::consts::PI, Unit::BASE_UNIT),
 		constant_e: "e" => (std::f64::consts::E, Unit::BASE_UNIT),
@@ -133,6 +167,8 @@ mod tests {
 		constant_tau: "tau" => (2.0 * std::f64::consts::PI, Unit::BASE_UNIT),
 		constant_infinity: "inf" => (f64::INFINITY, Unit::BASE_UNIT),
 		constant_infinity_symbol: "∞" => (f64::INFINITY, Unit::BASE_UNIT),
+		constant_infinity_word: "infinity" => (f64::INFINITY, Unit::BASE_UNIT),
+		constant_implicit_multiplication: "2πe" => (2. * std::f64::consts::PI * std::f64::consts::E, Unit::BASE_UNIT),
 		multiply_pi: "2 * pi" => (2.0 * std::f64::consts::PI, Unit::BASE_UNIT),
 		add_e_constant: "e + 1" => (std::f64::consts::E + 1.0, Unit::BASE_UNIT),
 		multiply_phi_constant: "phi * 2" => (1.61803398875 * 2.0, Unit::BASE_UNIT),
@@ -145,5 +181,134 @@ mod tests {
 		trig_tan_pi_div_four: "tan(pi/4)" => (1.0, Unit::BASE_UNIT),
 		trig_sin_tau: "sin(tau)" => (0.0, Unit::BASE_UNIT),
 		trig_cos_tau_div_two: "cos(tau/2)" => (-1.0, Unit::BASE_UNIT),
+
+		// Modulo
+		modulo_remainder: "10 % 4" => (2., Unit::BASE_UNIT),
+		modulo_negative: "-1 % 4" => (3., Unit::BASE_UNIT),
+		modulo_precedence: "1 + 7 % 4 * 2" => (7., Unit::BASE_UNIT),
+		modulo_units: "7m % 2m" => (1., Unit::LENGTH),
+		modulo_keyword: "10 mod 4" => (2., Unit::BASE_UNIT),
+		modulo_keyword_units: "7m mod 2m" => (1., Unit::LENGTH),
+
+		// Comparisons
+		comparison_equal: "8 % 4 == 0" => (true, Unit::BASE_UNIT),
+		comparison_not_equal: "3 != 3" => (false, Unit::BASE_UNIT),
+		comparison_less: "2 < 3" => (true, Unit::BASE_UNIT),
+		comparison_less_equal: "3 <= 3" => (true, Unit::BASE_UNIT),
+		comparison_greater: "2 + 2 > 5" => (false, Unit::BASE_UNIT),
+		comparison_greater_equal: "5 >= 2 * 3" => (false, Unit::BASE_UNIT),
+		comparison_units: "5m > 3m" => (true, Unit::BASE_UNIT),
+		comparison_parenthesized_chain: "(1 < 2) == (3 < 4)" => (true, Unit::BASE_UNIT),
+
+		// Booleans
+		boolean_true: "true" => (true, Unit::BASE_UNIT),
+		boolean_false: "false or 1 > 2" => (false, Unit::BASE_UNIT),
+		boolean_comparison: "(2 > 3) == false" => (true, Unit::BASE_UNIT),
+		boolean_condition: "false ? 1 : 2" => (2., Unit::BASE_UNIT),
+
+		// Logical operators
+		logical_and: "1 < 2 and 3 < 4" => (true, Unit::BASE_UNIT),
+		logical_and_symbol: "1 < 2 && 3 > 4" => (false, Unit::BASE_UNIT),
+		logical_or: "1 > 2 or 3 < 4" => (true, Unit::BASE_UNIT),
+		logical_or_symbol: "1 > 2 || 3 > 4" => (false, Unit::BASE_UNIT),
+		logical_not: "not 1 > 2" => (true, Unit::BASE_UNIT),
+		logical_not_symbol: "!(1 < 2) or 0" => (false, Unit::BASE_UNIT),
+		logical_precedence: "1 > 2 and 1 > 2 or 3 > 2" => (true, Unit::BASE_UNIT),
+		comparison_not_equal_without_spaces: "2!=3" => (true, Unit::BASE_UNIT),
+
+		// Conditionals
+		conditional_true: "3 > 2 ? 3 : 2" => (3., Unit::BASE_UNIT),
+		conditional_false: "2 > 3 ? 2 : 3" => (3., Unit::BASE_UNIT),
+		conditional_nested: "0 ? 1 : 0 ? 2 : 3" => (3., Unit::BASE_UNIT),
+		conditional_arithmetic: "1 == 1 ? 2 + 3 : 4 * 5" => (5., Unit::BASE_UNIT),
+		conditional_units: "1 < 2 ? 5m : 3m" => (5., Unit::LENGTH),
+
+		// Interpolation and range functions
+		function_min: "min(3, 1, 2)" => (1., Unit::BASE_UNIT),
+		function_max: "max(3, 1 + 4)" => (5., Unit::BASE_UNIT),
+		function_clamp: "clamp(12, 0, 10)" => (10., Unit::BASE_UNIT),
+		function_lerp: "lerp(10, 20, 0.25)" => (12.5, Unit::BASE_UNIT),
+		function_smoothstep: "smoothstep(0, 2, 1)" => (0.5, Unit::BASE_UNIT),
+		function_smoothstep_clamped: "smoothstep(0, 1, 2)" => (1., Unit::BASE_UNIT),
+
+		// Vectors
+		vec2_constructor: "vec2(1, 2)" => (DVec2::new(1., 2.), Unit::BASE_UNIT),
+		vec2_add: "vec2(1, 2) + vec2(3, 4)" => (DVec2::new(4., 6.), Unit::BASE_UNIT),
+		vec2_scale: "2 * vec2(1, 2) / 4" => (DVec2::new(0.5, 1.), Unit::BASE_UNIT),
+		vec2_negate: "-vec2(1, -2)" => (DVec2::new(-1., 2.), Unit::BASE_UNIT),
+		vec2_component: "vec2(3, 4).y - vec2(3, 4).x" => (1., Unit::BASE_UNIT),
+		vec2_length: "length(vec2(3, 4))" => (5., Unit::BASE_UNIT),
+		vec2_distance: "distance(vec2(1, 1), vec2(4, 5))" => (5., Unit::BASE_UNIT),
+		vec2_dot: "dot(vec2(1, 2), vec2(3, 4))" => (11., Unit::BASE_UNIT),
+		vec2_cross: "cross(vec2(1, 0), vec2(0, 1))" => (1., Unit::BASE_UNIT),
+		vec2_normalize: "normalize(vec2(0, 5))" => (DVec2::new(0., 1.), Unit::BASE_UNIT),
+		vec2_rotate: "rotate(vec2(1, 0), pi / 2)" => (DVec2::new(0., 1.), Unit::BASE_UNIT),
+		vec2_angle: "angle(vec2(0, 2))" => (std::f64::consts::FRAC_PI_2, Unit::BASE_UNIT),
+		vec2_lerp: "lerp(vec2(0, 0), vec2(2, 4), 0.5)" => (DVec2::new(1., 2.), Unit::BASE_UNIT),
+		vec2_equal: "vec2(1, 2) == vec2(1, 2)" => (true, Unit::BASE_UNIT),
+
+		// Colors
+		color_component: "rgb(0.2, 0.4, 0.6).g" => (0.4, Unit::BASE_UNIT),
+		color_alpha: "rgba(1, 1, 1, 0.5).a" => (0.5, Unit::BASE_UNIT),
+		color_default_alpha: "rgb(1, 0, 0).a" => (1., Unit::BASE_UNIT),
+		color_scale: "(rgb(0.5, 0.5, 0.5) * 2).r" => (1., Unit::BASE_UNIT),
+		color_lerp: "lerp(rgb(0, 0, 0), rgb(1, 1, 1), 0.25).b" => (0.25, Unit::BASE_UNIT),
+
+		// Design units
+		design_inch: "2in" => (0.0508, Unit::LENGTH),
+		design_point_and_millimeter: "72pt - 5.4mm" => (0.02, Unit::LENGTH),
+		design_degree: "90deg" => (std::f64::consts::FRAC_PI_2, Unit::ANGLE),
+		design_degree_sign: "180°" => (std::f64::consts::PI, Unit::ANGLE),
+		design_turn: "0.5turn - 90deg" => (std::f64::consts::FRAC_PI_2, Unit::ANGLE),
+		design_trig_degrees: "sin(30deg)" => (0.5, Unit::BASE_UNIT),
+		design_percent: "50%" => (0.5, Unit::BASE_UNIT),
+		design_percent_then_operator: "50% * 4" => (2., Unit::BASE_UNIT),
+	}
+
+	macro_rules! test_field {
+		($($name:ident: $input:expr_2021, $units:expr_2021 => $expected:expr_2021),* $(,)?) => {
+			$(
+				#[test]
+				fn $name() {
+					let expected: Option<f64> = $expected;
+					let actual = evaluate_in_field($input, &$units).ok().map(|value| value.unwrap().as_real().unwrap());
+
+					match (actual, expected) {
+						(Some(actual), Some(expected)) => assert!((actual - expected).abs() < EPSILON, "Expected {}, but got {}", expected, actual),
+						_ => assert!(actual.is_none() && expected.is_none(), "Expected {:?}, but got {:?}", expected, actual),
+					}
+				}
+			)*
+		};
+	}
+
+	fn field(field_unit: FieldUnit, percentage_reference: Option<f64>) -> UnitSystem {
+		UnitSystem {
+			field_unit,
+			percentage_reference,
+			..Default::default()
+		}
+	}
+
+	test_field! {
+		field_points_and_millimeters: "12pt + 3mm", field(FieldUnit::Pixels, None) => Some(16. + 0.3 / 2.54 * 96.),
+		field_inches_at_dpi: "2 in", UnitSystem { dpi: 300., ..field(FieldUnit::Pixels, None) } => Some(600.),
+		field_picas: "1pc", field(FieldUnit::Pixels, None) => Some(16.),
+		field_bare_number: "10 + 5", field(FieldUnit::Pixels, None) => Some(15.),
+		field_pixels_with_bare_factor: "10px * 3", field(FieldUnit::Pixels, None) => Some(30.),
+		field_degrees: "0.25turn + 45deg", field(FieldUnit::Degrees, None) => Some(135.),
+		field_radians: "180deg", field(FieldUnit::Radians, None) => Some(std::f64::consts::PI),
+		field_percent_of_reference: "50%", field(FieldUnit::Pixels, Some(200.)) => Some(100.),
+		field_percent_with_pixels: "50% + 10px", field(FieldUnit::Pixels, Some(200.)) => Some(110.),
+		field_percentage: "50%", field(FieldUnit::Unitless, Some(100.)) => Some(50.),
+		field_modulo: "10 % 4", field(FieldUnit::Unitless, Some(100.)) => Some(2.),
+		field_inches_plus_bare_number: "1in + 1", field(FieldUnit::Pixels, None) => Some(97.),
+		field_bare_number_minus_inches_at_dpi: "10 - 0.1in", UnitSystem { dpi: 300., ..field(FieldUnit::Pixels, None) } => Some(-20.),
+		field_degrees_plus_bare_number: "0.5turn + 10", field(FieldUnit::Degrees, None) => Some(190.),
+		field_bare_number_compared_with_inches: "1in > 95 ? 1 : 0", field(FieldUnit::Pixels, None) => Some(1.),
+		field_bare_number_times_inches: "2 * 1in", field(FieldUnit::Pixels, None) => Some(192.),
+		field_unitless_length_and_bare_number: "1in + 1", field(FieldUnit::Unitless, None) => None,
+		field_incompatible_angle: "2in", field(FieldUnit::Degrees, None) => None,
+		field_incompatible_time: "2s", field(FieldUnit::Pixels, None) => None,
 	}
 }
@@ -1,13 +1,16 @@
 use crate::ast::{BinaryOp, Literal, Node, UnaryOp, Unit};
 use crate::context::EvalContext;
+use crate::units::{FieldUnit, METERS_PER_INCH, UnitSystem};
 use crate::value::{Complex, Number, Value};
 use lazy_static::lazy_static;
 use num_complex::ComplexFloat;
 use pest::Parser;
+use pest::error::InputLocation;
 use pest::iterators::{Pair, Pairs};
 use pest::pratt_parser::{Assoc, Op, PrattParser};
 use pest_derive::Parser;
 use std::num::{ParseFloatError, ParseIntError};
+use std::ops::Range;
 use thiserror::Error;
 
 #[derive(Parser)]
@@ -17,53 +20,160 @@ struct ExprParser;
 lazy_static! {
 	static ref PRATT_PARSER: PrattParser<Rule> = {
 		PrattParser::new()
+			.op(Op::infix(Rule::conditional, Assoc::Right))
+			.op(Op::infix(Rule::or, Assoc::Left))
+			.op(Op::infix(Rule::and, Assoc::Left))
+			.op(Op::prefix(Rule::not))
+			.op(Op::infix(Rule::eq, Assoc::Left)
+				| Op::infix(Rule::ne, Assoc::Left)
+				| Op::infix(Rule::lt, Assoc::Left)
+				| Op::infix(Rule::le, Assoc::Left)
+				| Op::infix(Rule::gt, Assoc::Left)
+				| Op::infix(Rule::ge, Assoc::Left))
 			.op(Op::infix(Rule::add, Assoc::Left) | Op::infix(Rule::sub, Assoc::Left))
-			.op(Op::infix(Rule::mul, Assoc::Left) | Op::infix(Rule::div, Assoc::Left) | Op::infix(Rule::paren, Assoc::Left))
+			.op(Op::infix(Rule::mul, Assoc::Left) | Op::infix(Rule::div, Assoc::Left) | Op::infix(Rule::r#mod, Assoc::Left) | Op::infix(Rule::paren, Assoc::Left))
 			.op(Op::infix(Rule::pow, Assoc::Right))
-			.op(Op::postfix(Rule::fac) | Op::postfix(Rule::EOI))
+			.op(Op::postfix(Rule::fac) | Op::postfix(Rule::component) | Op::postfix(Rule::EOI))
 			.op(Op::prefix(Rule::sqrt))
 			.op(Op::prefix(Rule::neg))
 	};
 }
 
+/// A part of the source of an expression, as a range of byte offsets into it.
+pub type Span = Range<usize>;
+
 #[derive(Error, Debug)]
 pub enum TypeError {
-	#[error("Invalid BinOp: {0:?} {1:?} {2:?}")]
+	#[error("Can't use `{1}` between {0} and {2} values")]
 	InvalidBinaryOp(Unit, BinaryOp, Unit),
 
-	#[error("Invalid UnaryOp: {0:?}")]
+	#[error("Can't use `{1}` on a {0} value")]
 	InvalidUnaryOp(Unit, UnaryOp),
+
+	#[error("The results of the conditional have different units: {0} and {1}")]
+	MismatchedConditional(Unit, Unit),
+
+	#[error("Can't convert a {0} value into {1}")]
+	IncompatibleFieldUnit(Unit, FieldUnit),
 }
 
 #[derive(Error, Debug)]
 pub enum ParseError {
-	#[error("ParseIntError: {0}")]
-	ParseInt(#[from] ParseIntError),
-	#[error("ParseFloatError: {0}")]
-	ParseFloat(#[from] ParseFloatError),
+	#[error("Invalid integer: {error}")]
+	ParseInt { error: ParseIntError, span: Span },
+	#[error("Invalid number: {error}")]
+	ParseFloat { error: ParseFloatError, span: Span },
+
+	#[error("{error}")]
+	Type { error: TypeError, span: Span },
+
+	#[error("{message}")]
+	Syntax { message: String, span: Span },
+}
+
+impl ParseError {
+	/// The part of the expression which caused the error.
+	pub fn span(&self) -> Span {
+		match self {
+			Self::ParseInt { span, .. } | Self::ParseFloat { span, .. } | Self::Type { span, .. } | Self::Syntax { span, .. } => span.clone(),
+		}
+	}
+
+	fn syntax(error: pest::error::Error<Rule>, source: &str) -> Self {
+		let start = match error.location {
+			InputLocation::Pos(position) => position,
+			InputLocation::Span((start, _)) => start,
+		};
+
+		match source[start..].chars().next() {
+			Some(character) => Self::Syntax {
+				message: format!("Unexpected `{character}`"),
+				span: start..start + character.len_utf8(),
+			},
+			None => Self::Syntax {
+				message: "Unexpected end of expression".to_string(),
+				span: start..start,
+			},
+		}
+	}
+}
 
-	#[error("TypeError: {0}")]
-	Type(#[from] TypeError),
+/// What a name in an expression refers to.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) enum NameKind {
+	Variable,
+	Function,
+	Component,
+}
 
-	#[error("PestError: {0}")]
-	Pest(#[from] Box<pest::error::Error<Rule>>),
+/// A name in an expression and where it appears, used to locate evaluation errors which concern it.
+#[derive(Debug, Clone, PartialEq)]
+pub(crate) struct Name {
+	pub kind: NameKind,
+	pub name: String,
+	pub span: Span,
 }
 
 impl Node {
 	pub fn try_parse_from_str(s: &str) -> Result<(Node, Unit), ParseError> {
-		let pairs = ExprParser::parse(Rule::program, s).map_err(Box::new)?;
-		let (node, metadata) = parse_expr(pairs)?;
+		Self::try_parse_from_str_with_units(s, &UnitSystem::default())
+	}
+
+	/// Parses an expression whose design units, like pixels and percentages, are interpreted with the given unit system.
+	pub fn try_parse_from_str_with_units(s: &str, units: &UnitSystem) -> Result<(Node, Unit), ParseError> {
+		let pairs = ExprParser::parse(Rule::program, s).map_err(|error| ParseError::syntax(error, s))?;
+		let (node, metadata) = parse_expr(pairs, units)?;
 		Ok((node, metadata.unit))
 	}
 }
 
+/// Parses an expression along with the names it contains, in the order they appear.
+pub(crate) fn parse_with_names(s: &str, units: &UnitSystem) -> Result<(Node, Unit, Vec<Name>), ParseError> {
+	let pairs = ExprParser::parse(Rule::program, s).map_err(|error| ParseError::syntax(error, s))?;
+
+	let mut names = Vec::new();
+	collect_names(pairs.clone(), &mut names);
+
+	let (node, metadata) = parse_expr(pairs, units)?;
+	Ok((node, metadata.unit, names))
+}
+
+fn collect_names(pairs: Pairs<Rule>, names: &mut Vec<Name>) {
+	let name = |kind, pair: Pair<Rule>| Name {
+		kind,
+		name: pair.as_str().to_string(),
+		span: span(&pair),
+	};
+
+	for pair in pairs {
+		match pair.as_rule() {
+			Rule::ident => names.push(name(NameKind::Variable, pair)),
+			Rule::component => names.push(name(NameKind::Component, pair.into_inner().next().expect("component always has 1 child"))),
+			Rule::fn_call => {
+				let mut inner = pair.into_inner();
+				names.push(name(NameKind::Function, inner.next().expect("fn_call always has 2 children")));
+				collect_names(inner, names);
+			}
+			_ => collect_names(pair.into_inner(), names),
+		}
+	}
+}
+
+fn span(pair: &Pair<Rule>) -> Span {
+	pair.as_span().start()..pair.as_span().end()
+}
+
 struct NodeMetadata {
 	pub unit: Unit,
+	/// The part of the source which the node was parsed from.
+	pub span: Span,
+	/// Whether the node is a comparison outside of parentheses, which another comparison can't follow.
+	pub comparison: bool,
 }
 
 impl NodeMetadata {
-	pub fn new(unit: Unit) -> Self {
-		Self { unit }
+	pub fn new(unit: Unit, span: Span) -> Self {
+		Self { unit, span, comparison: false }
 	}
 }
 
@@ -74,7 +184,6 @@ fn parse_unit(pairs: Pairs<Rule>) -> Result<(Unit, f64), ParseError> {
 	let mut time = 0;
 
 	for pair in pairs {
-		println!("found rule: {:?}", pair.as_rule());
 		match pair.as_rule() {
 			Rule::nano => scale *= 1e-9,
 			Rule::micro => scale *= 1e-6,
@@ -96,11 +205,27 @@ fn parse_unit(pairs: Pairs<Rule>) -> Result<(Unit, f64), ParseError> {
 		}
 	}
 
-	Ok((Unit { length, mass, time }, scale))
+	Ok((Unit { length, mass, time, angle: 0 }, scale))
+}
+
+fn parse_design_unit(pair: Pair<Rule>, units: &UnitSystem) -> (Unit, f64) {
+	match pair.as_rule() {
+		Rule::pixel => units.pixel(),
+		Rule::point => (Unit::LENGTH, METERS_PER_INCH / 72.),
+		Rule::pica => (Unit::LENGTH, METERS_PER_INCH / 6.),
+		Rule::inch => (Unit::LENGTH, METERS_PER_INCH),
+		Rule::degree => (Unit::ANGLE, std::f64::consts::PI / 180.),
+		Rule::radian => (Unit::ANGLE, 1.),
+		Rule::turn => (Unit::ANGLE, std::f64::consts::TAU),
+		Rule::percent => units.percent(),
+		rule => unreachable!("unexpected rule: {:?}", rule),
+	}
 }
 
 fn parse_const(pair: Pair<Rule>) -> Literal {
 	match pair.as_rule() {
+		Rule::boolean_true => Literal::Bool(true),
+		Rule::boolean_false => Literal::Bool(false),
 		Rule::infinity => Literal::Float(f64::INFINITY),
 		Rule::imaginary_unit => Literal::Complex(Complex::new(0.0, 1.0)),
 		Rule::pi => Literal::Float(std::f64::consts::PI),
@@ -111,15 +236,15 @@ fn parse_const(pair: Pair<Rule>) -> Literal {
 	}
 }
 
-fn parse_lit(mut pairs: Pairs<Rule>) -> Result<(Literal, Unit), ParseError> {
+fn parse_lit(mut pairs: Pairs<Rule>, units: &UnitSystem) -> Result<(Literal, Unit), ParseError> {
 	let literal = match pairs.next() {
 		Some(lit) => match lit.as_rule() {
 			Rule::int => {
-				let value = lit.as_str().parse::<i32>()? as f64;
+				let value = lit.as_str().parse::<i32>().map_err(|error| ParseError::ParseInt { error, span: span(&lit) })? as f64;
 				Literal::Float(value)
 			}
 			Rule::float => {
-				let value = lit.as_str().parse::<f64>()?;
+				let value = lit.as_str().parse::<f64>().map_err(|error| ParseError::ParseFloat { error, span: span(&lit) })?;
 				Literal::Float(value)
 			}
 			Rule::unit => {
@@ -132,15 +257,16 @@ fn parse_lit(mut pairs: Pairs<Rule>) -> Result<(Literal, Unit), ParseError> {
 	};
 
 	if let Some(unit_pair) = pairs.next() {
-		let unit_pairs = unit_pair.into_inner(); // Get the inner pairs for the unit
-		let (unit, scale) = parse_unit(unit_pairs)?;
-
-		println!("found unit: {unit:?}");
+		let (unit, scale) = match unit_pair.as_rule() {
+			Rule::design_unit => parse_design_unit(unit_pair.into_inner().next().expect("design_unit always has 1 child"), units),
+			_ => parse_unit(unit_pair.into_inner())?, // Get the inner pairs for the unit
+		};
 
 		Ok((
 			match literal {
 				Literal::Float(num) => Literal::Float(num * scale),
 				Literal::Complex(num) => Literal::Complex(num * scale),
+				Literal::Bool(_) => unreachable!("booleans aren't followed by units"),
 			},
 			unit,
 		))
@@ -149,50 +275,74 @@ fn parse_lit(mut pairs: Pairs<Rule>) -> Result<(Literal, Unit), ParseError> {
 	}
 }
 
-fn parse_expr(pairs: Pairs<Rule>) -> Result<(Node, NodeMetadata), ParseError> {
+/// Scales a unitless operand into the field's unit when the other operand shares the field's dimension, so the `1` in `1in + 1` entered into a pixel field means one pixel.
+fn in_field_unit(node: Node, unit: Unit, other_unit: Unit, units: &UnitSystem) -> (Node, Unit) {
+	let (dimension, scale) = units.field_dimension();
+	if !unit.is_base() || dimension.is_base() || other_unit != dimension {
+		return (node, unit);
+	}
+
+	let node = Node::BinOp {
+		lhs: Box::new(node),
+		op: BinaryOp::Mul,
+		rhs: Box::new(Node::Lit(Literal::Float(scale))),
+	};
+	(node, dimension)
+}
+
+fn parse_expr(pairs: Pairs<Rule>, units: &UnitSystem) -> Result<(Node, NodeMetadata), ParseError> {
 	PRATT_PARSER
 		.map_primary(|primary| {
 			Ok(match primary.as_rule() {
 				Rule::lit => {
-					let (lit, unit) = parse_lit(primary.into_inner())?;
+					let span = span(&primary);
+					let (lit, unit) = parse_lit(primary.into_inner(), units)?;
 
-					(Node::Lit(lit), NodeMetadata { unit })
+					(Node::Lit(lit), NodeMetadata::new(unit, span))
 				}
 				Rule::fn_call => {
+					let span = span(&primary);
 					let mut pairs = primary.into_inner();
 					let name = pairs.next().expect("fn_call always has 2 children").as_str().to_string();
 
 					(
 						Node::FnCall {
 							name,
-							expr: pairs.map(|p| parse_expr(p.into_inner()).map(|expr| expr.0)).collect::<Result<Vec<Node>, ParseError>>()?,
+							expr: pairs.map(|p| parse_expr(p.into_inner(), units).map(|expr| expr.0)).collect::<Result<Vec<Node>, ParseError>>()?,
 						},
-						NodeMetadata::new(Unit::BASE_UNIT),
+						NodeMetadata::new(Unit::BASE_UNIT, span),
 					)
 				}
 				Rule::constant => {
+					let span = span(&primary);
 					let lit = parse_const(primary.into_inner().next().expect("constant should have atleast 1 child"));
 
-					(Node::Lit(lit), NodeMetadata::new(Unit::BASE_UNIT))
+					(Node::Lit(lit), NodeMetadata::new(Unit::BASE_UNIT, span))
 				}
 				Rule::ident => {
 					let name = primary.as_str().to_string();
 
-					(Node::Var(name), NodeMetadata::new(Unit::BASE_UNIT))
+					(Node::Var(name), NodeMetadata::new(Unit::BASE_UNIT, span(&primary)))
+				}
+				Rule::expr => {
+					let (node, metadata) = parse_expr(primary.into_inner(), units)?;
+					(node, NodeMetadata { comparison: false, ..metadata })
 				}
-				Rule::expr => parse_expr(primary.into_inner())?,
 				Rule::float => {
-					let value = primary.as_str().parse::<f64>()?;
-					(Node::Lit(Literal::Float(value)), NodeMetadata::new(Unit::BASE_UNIT))
+					let span = span(&primary);
+					let value = primary.as_str().parse::<f64>().map_err(|error| ParseError::ParseFloat { error, span: span.clone() })?;
+					(Node::Lit(Literal::Float(value)), NodeMetadata::new(Unit::BASE_UNIT, span))
 				}
 				rule => unreachable!("unexpected rule: {:?}", rule),
 			})
 		})
 		.map_prefix(|op, rhs| {
 			let (rhs, rhs_metadata) = rhs?;
+			let span = op.as_span().start()..rhs_metadata.span.end;
 			let op = match op.as_rule() {
 				Rule::neg => UnaryOp::Neg,
 				Rule::sqrt => UnaryOp::Sqrt,
+				Rule::not => UnaryOp::Not,
 
 				rule => unreachable!("unexpected rule: {:?}", rule),
 			};
@@ -202,50 +352,125 @@ fn parse_expr(pairs: Pairs<Rule>) -> Result<(Node, NodeMetadata), ParseError> {
 
 			let unit = if !unit.is_base() {
 				match op {
-					UnaryOp::Sqrt if unit.length % 2 == 0 && unit.mass % 2 == 0 && unit.time % 2 == 0 => Unit {
+					UnaryOp::Sqrt if unit.length % 2 == 0 && unit.mass % 2 == 0 && unit.time % 2 == 0 && unit.angle % 2 == 0 => Unit {
 						length: unit.length / 2,
 						mass: unit.mass / 2,
 						time: unit.time / 2,
+						angle: unit.angle / 2,
 					},
 					UnaryOp::Neg => unit,
-					op => return Err(ParseError::Type(TypeError::InvalidUnaryOp(unit, op))),
+					op => {
+						let error = TypeError::InvalidUnaryOp(unit, op);
+						return Err(ParseError::Type { error, span });
+					}
 				}
 			} else {
 				Unit::BASE_UNIT
 			};
 
-			Ok((node, NodeMetadata::new(unit)))
+			Ok((node, NodeMetadata::new(unit, span)))
 		})
 		.map_postfix(|lhs, op| {
 			let (lhs_node, lhs_metadata) = lhs?;
+			if op.as_rule() == Rule::EOI {
+				return Ok((lhs_node, lhs_metadata));
+			}
+			let metadata = NodeMetadata::new(lhs_metadata.unit, lhs_metadata.span.start..op.as_span().end());
 
 			let op = match op.as_rule() {
-				Rule::EOI => return Ok((lhs_node, lhs_metadata)),
+				Rule::component => {
+					let name = op.into_inner().next().expect("component always has 1 child").as_str().to_string();
+					return Ok((Node::Component { expr: Box::new(lhs_node), name }, metadata));
+				}
 				Rule::fac => UnaryOp::Fac,
 				rule => unreachable!("unexpected rule: {:?}", rule),
 			};
 
-			if !lhs_metadata.unit.is_base() {
-				return Err(ParseError::Type(TypeError::InvalidUnaryOp(lhs_metadata.unit, op)));
+			if !metadata.unit.is_base() {
+				let error = TypeError::InvalidUnaryOp(metadata.unit, op);
+				return Err(ParseError::Type { error, span: metadata.span });
 			}
 
-			Ok((Node::UnaryOp { expr: Box::new(lhs_node), op }, lhs_metadata))
+			Ok((Node::UnaryOp { expr: Box::new(lhs_node), op }, metadata))
 		})
 		.map_infix(|lhs, op, rhs| {
 			let (lhs, lhs_metadata) = lhs?;
 			let (rhs, rhs_metadata) = rhs?;
+			let span = lhs_metadata.span.start..rhs_metadata.span.end;
+
+			if op.as_rule() == Rule::conditional {
+				let (if_true, if_true_metadata) = parse_expr(op.into_inner().next().expect("conditional always has 1 child").into_inner(), units)?;
+				if if_true_metadata.unit != rhs_metadata.unit {
+					let error = TypeError::MismatchedConditional(if_true_metadata.unit, rhs_metadata.unit);
+					return Err(ParseError::Type { error, span });
+				}
+
+				let node = Node::Conditional {
+					condition: Box::new(lhs),
+					if_true: Box::new(if_true),
+					if_false: Box::new(rhs),
+				};
+
+				return Ok((node, NodeMetadata::new(if_true_metadata.unit, span)));
+			}
 
 			let op = match op.as_rule() {
 				Rule::add => BinaryOp::Add,
 				Rule::sub => BinaryOp::Sub,
 				Rule::mul => BinaryOp::Mul,
 				Rule::div => BinaryOp::Div,
+				Rule::r#mod => BinaryOp::Mod,
 				Rule::pow => BinaryOp::Pow,
+				Rule::eq => BinaryOp::Eq,
+				Rule::ne => BinaryOp::Ne,
+				Rule::lt => BinaryOp::Lt,
+				Rule::le => BinaryOp::Le,
+				Rule::gt => BinaryOp::Gt,
+				Rule::ge => BinaryOp::Ge,
+				Rule::and => BinaryOp::And,
+				Rule::or => BinaryOp::Or,
 				Rule::paren => BinaryOp::Mul,
 				rule => unreachable!("unexpected rule: {:?}", rule),
 			};
 
-			let (lhs_unit, rhs_unit) = (lhs_metadata.unit, rhs_metadata.unit);
+			// A bare number added to or compared with a quantity in the field's dimension is taken to be in the field's unit
+			let (lhs, rhs, lhs_unit, rhs_unit) = if matches!(op, BinaryOp::Add | BinaryOp::Sub | BinaryOp::Mod) || op.is_comparison() {
+				let (lhs, lhs_unit) = in_field_unit(lhs, lhs_metadata.unit, rhs_metadata.unit, units);
+				let (rhs, rhs_unit) = in_field_unit(rhs, rhs_metadata.unit, lhs_metadata.unit, units);
+				(lhs, rhs, lhs_unit, rhs_unit)
+			} else {
+				(lhs, rhs, lhs_metadata.unit, rhs_metadata.unit)
+			};
+			let invalid_binary_op = |lhs_unit, rhs_unit| ParseError::Type {
+				error: TypeError::InvalidBinaryOp(lhs_unit, op, rhs_unit),
+				span: span.clone(),
+			};
+
+			// Chains like `1 < 2 < 3` would otherwise compare the boolean result of the first comparison with the next operand
+			if op.is_comparison() && lhs_metadata.comparison {
+				let message = format!("Can't chain `{op}` after another comparison, so join them with `and` instead");
+				return Err(ParseError::Syntax { message, span });
+			}
+
+			// Comparisons need operands of the same unit and logical operators need unitless operands, and both produce a unitless boolean
+			if op.is_comparison() || op.is_logical() {
+				let valid = if op.is_comparison() { lhs_unit == rhs_unit } else { lhs_unit.is_base() && rhs_unit.is_base() };
+				if !valid {
+					return Err(invalid_binary_op(lhs_unit, rhs_unit));
+				}
+
+				let node = Node::BinOp {
+					lhs: Box::new(lhs),
+					op,
+					rhs: Box::new(rhs),
+				};
+
+				let metadata = NodeMetadata {
+					comparison: op.is_comparison(),
+					..NodeMetadata::new(Unit::BASE_UNIT, span)
+				};
+				return Ok((node, metadata));
+			}
 
 			let unit = match (!lhs_unit.is_base(), !rhs_unit.is_base()) {
 				(true, true) => match op {
@@ -253,26 +478,22 @@ fn parse_expr(pairs: Pairs<Rule>) -> Result<(Node, NodeMetadata), ParseError> {
 						length: lhs_unit.length + rhs_unit.length,
 						mass: lhs_unit.mass + rhs_unit.mass,
 						time: lhs_unit.time + rhs_unit.time,
+						angle: lhs_unit.angle + rhs_unit.angle,
 					},
 					BinaryOp::Div => Unit {
 						length: lhs_unit.length - rhs_unit.length,
 						mass: lhs_unit.mass - rhs_unit.mass,
 						time: lhs_unit.time - rhs_unit.time,
+						angle: lhs_unit.angle - rhs_unit.angle,
 					},
-					BinaryOp::Add | BinaryOp::Sub => {
-						if lhs_unit == rhs_unit {
-							lhs_unit
-						} else {
-							return Err(ParseError::Type(TypeError::InvalidBinaryOp(lhs_unit, op, rhs_unit)));
-						}
-					}
-					BinaryOp::Pow => {
-						return Err(ParseError::Type(TypeError::InvalidBinaryOp(lhs_unit, op, rhs_unit)));
+					BinaryOp::Add | BinaryOp::Sub | BinaryOp::Mod if lhs_unit == rhs_unit => lhs_unit,
+					_ => {
+						return Err(invalid_binary_op(lhs_unit, rhs_unit));
 					}
 				},
 
 				(true, false) => match op {
-					BinaryOp::Add | BinaryOp::Sub => return Err(ParseError::Type(TypeError::InvalidBinaryOp(lhs_unit, op, Unit::BASE_UNIT))),
+					BinaryOp::Add | BinaryOp::Sub | BinaryOp::Mod => return Err(invalid_binary_op(lhs_unit, Unit::BASE_UNIT)),
 					BinaryOp::Pow => {
 						//TODO: improve error type
 						//TODO: support 1 / int
@@ -282,18 +503,19 @@ fn parse_expr(pairs: Pairs<Rule>) -> Result<(Node, NodeMetadata), ParseError> {
 									length: lhs_unit.length * val as i32,
 									mass: lhs_unit.mass * val as i32,
 									time: lhs_unit.time * val as i32,
+									angle: lhs_unit.angle * val as i32,
 								}
 							} else {
-								return Err(ParseError::Type(TypeError::InvalidBinaryOp(lhs_unit, op, Unit::BASE_UNIT)));
+								return Err(invalid_binary_op(lhs_unit, Unit::BASE_UNIT));
 							}
 						} else {
-							return Err(ParseError::Type(TypeError::InvalidBinaryOp(lhs_unit, op, Unit::BASE_UNIT)));
+							return Err(invalid_binary_op(lhs_unit, Unit::BASE_UNIT));
 						}
 					}
 					_ => lhs_unit,
 				},
 				(false, true) => match op {
-					BinaryOp::Add | BinaryOp::Sub | BinaryOp::Pow => return Err(ParseError::Type(TypeError::InvalidBinaryOp(Unit::BASE_UNIT, op, rhs_unit))),
+					BinaryOp::Add | BinaryOp::Sub | BinaryOp::Mod | BinaryOp::Pow => return Err(invalid_binary_op(Unit::BASE_UNIT, rhs_unit)),
 					_ => rhs_unit,
 				},
 				(false, false) => Unit::BASE_UNIT,
@@ -305,7 +527,7 @@ fn parse_expr(pairs: Pairs<Rule>) -> Result<(Node, NodeMetadata), ParseError> {
 				rhs: Box::new(rhs),
 			};
 
-			Ok((node, NodeMetadata::new(unit)))
+			Ok((node, NodeMetadata::new(unit, span)))
 		})
 		.parse(pairs)
 }
@@ -353,6 +575,48 @@ mod tests {
 			expr: Box::new(Node::Lit(Literal::Float(16.0))),
 			op: UnaryOp::Sqrt,
 		},
+		test_parse_conditional: "A > B ? A : B" => Node::Conditional {
+			condition: Box::new(Node::BinOp {
+				lhs: Box::new(Node::Var("A".to_string())),
+				op: BinaryOp::Gt,
+				rhs: Box::new(Node::Var("B".to_string())),
+			}),
+			if_true: Box::new(Node::Var("A".to_string())),
+			if_false: Box::new(Node::Var("B".to_string())),
+		},
+		test_parse_logical_keywords: "not a and b or c" => Node::BinOp {
+			lhs: Box::new(Node::BinOp {
+				lhs: Box::new(Node::UnaryOp {
+					expr: Box::new(Node::Var("a".to_string())),
+					op: UnaryOp::Not,
+				}),
+				op: BinaryOp::And,
+				rhs: Box::new(Node::Var("b".to_string())),
+			}),
+			op: BinaryOp::Or,
+			rhs: Box::new(Node::Var("c".to_string())),
+		},
+		test_parse_keyword_prefixed_ident: "notable + order" => Node::BinOp {
+			lhs: Box::new(Node::Var("notable".to_string())),
+			op: BinaryOp::Add,
+			rhs: Box::new(Node::Var("order".to_string())),
+		},
+		test_parse_boolean: "not true" => Node::UnaryOp {
+			expr: Box::new(Node::Lit(Literal::Bool(true))),
+			op: UnaryOp::Not,
+		},
+		test_parse_keyword_prefixed_ident_after_boolean: "falsehood mod modulus" => Node::BinOp {
+			lhs: Box::new(Node::Var("falsehood".to_string())),
+			op: BinaryOp::Mod,
+			rhs: Box::new(Node::Var("modulus".to_string())),
+		},
+		test_parse_component: "vec2(1, 2).x" => Node::Component {
+			expr: Box::new(Node::FnCall {
+				name: "vec2".to_string(),
+				expr: vec![Node::Lit(Literal::Float(1.0)), Node::Lit(Literal::Float(2.0))],
+			}),
+			name: "x".to_string(),
+		},
 		test_parse_sqr_ident: "sqr(16)" => Node::FnCall {
 			 name:"sqr".to_string(),
 			 expr: vec![Node::Lit(Literal::Float(16.0))]
@@ -0,0 +1,92 @@
+use crate::ast::Unit;
+use std::f64::consts::PI;
+
+pub(crate) const METERS_PER_INCH: f64 = 0.0254;
+
+/// The unit which the number field receiving the result of an expression is measured in.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum FieldUnit {
+	#[default]
+	Unitless,
+	Pixels,
+	Degrees,
+	Radians,
+}
+
+impl std::fmt::Display for FieldUnit {
+	fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+		let name = match self {
+			Self::Unitless => "a unitless number",
+			Self::Pixels => "pixels",
+			Self::Degrees => "degrees",
+			Self::Radians => "radians",
+		};
+		write!(f, "{name}")
+	}
+}
+
+/// The settings which relate the design units of an expression, such as `12pt + 3mm`, `45deg`, or `50%`, to the unit of the field it's entered into.
+///
+/// Lengths are measured in meters and angles in radians while the expression is calculated, and its result is converted into the field's unit afterwards.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct UnitSystem {
+	/// The number of pixels per inch, which relates pixels to physical lengths like points, inches, and millimeters.
+	pub dpi: f64,
+	/// The unit of the field which the result is converted into.
+	pub field_unit: FieldUnit,
+	/// The value, in the field's unit, which `100%` refers to. Without one, `100%` is the unitless number 1.
+	pub percentage_reference: Option<f64>,
+}
+
+impl Default for UnitSystem {
+	fn default() -> Self {
+		Self {
+			dpi: 96.,
+			field_unit: FieldUnit::default(),
+			percentage_reference: None,
+		}
+	}
+}
+
+impl UnitSystem {
+	/// The dimension of the field's unit, and the size of one of its units in meters or radians.
+	pub fn field_dimension(&self) -> (Unit, f64) {
+		match self.field_unit {
+			FieldUnit::Unitless => (Unit::BASE_UNIT, 1.),
+			FieldUnit::Pixels => self.pixel(),
+			FieldUnit::Degrees => (Unit::ANGLE, PI / 180.),
+			FieldUnit::Radians => (Unit::ANGLE, 1.),
+		}
+	}
+
+	/// The factor which converts a result of the given unit into the field's unit, if they are compatible.
+	///
+	/// Unitless results are taken to already be in the field's unit.
+	pub fn field_scale(&self, unit: Unit) -> Option<f64> {
+		let (dimension, scale) = self.field_dimension();
+
+		if unit.is_base() {
+			Some(1.)
+		} else if unit == dimension {
+			Some(scale.recip())
+		} else {
+			None
+		}
+	}
+
+	/// The size of one pixel in meters.
+	pub(crate) fn pixel(&self) -> (Unit, f64) {
+		(Unit::LENGTH, METERS_PER_INCH / self.dpi)
+	}
+
+	/// One percent of the reference value, in the dimension of the field's unit.
+	pub(crate) fn percent(&self) -> (Unit, f64) {
+		match self.percentage_reference {
+			Some(reference) => {
+				let (dimension, scale) = self.field_dimension();
+				(dimension, reference * scale / 100.)
+			}
+			None => (Unit::BASE_UNIT, 0.01),
+		}
+	}
+}
@@ -1,12 +1,18 @@
 use crate::ast::{BinaryOp, UnaryOp};
+use glam::{DVec2, DVec4};
 use num_complex::ComplexFloat;
 use std::f64::consts::PI;
+use std::ops::{Add, Div, Mul, Sub};
 
 pub type Complex = num_complex::Complex<f64>;
 
 #[derive(Debug, PartialEq, Clone, Copy)]
 pub enum Value {
 	Number(Number),
+	Bool(bool),
+	Vec2(DVec2),
+	/// A color with linear RGBA channels in the range 0 to 1.
+	Color(DVec4),
 }
 
 impl Value {
@@ -20,6 +26,143 @@ impl Value {
 			_ => None,
 		}
 	}
+
+	/// Interprets the value as a condition, where any nonzero number is true.
+	pub fn as_bool(&self) -> Option<bool> {
+		match self {
+			Self::Bool(val) => Some(*val),
+			Self::Number(Number::Real(val)) => Some(*val != 0.),
+			Self::Number(Number::Complex(val)) => Some(*val != Complex::new(0., 0.)),
+			Self::Vec2(_) | Self::Color(_) => None,
+		}
+	}
+
+	/// Accesses a named component of a vector (`x`, `y`) or color (`r`, `g`, `b`, `a`).
+	pub fn component(&self, name: &str) -> Option<Value> {
+		let component = match (self, name) {
+			(Self::Vec2(vec), "x") => vec.x,
+			(Self::Vec2(vec), "y") => vec.y,
+			(Self::Color(color), "r") => color.x,
+			(Self::Color(color), "g") => color.y,
+			(Self::Color(color), "b") => color.z,
+			(Self::Color(color), "a") => color.w,
+			_ => return None,
+		};
+		Some(Value::from_f64(component))
+	}
+
+	pub fn binary_op(self, op: BinaryOp, other: Value) -> Option<Value> {
+		match op {
+			BinaryOp::And => Some(Value::Bool(self.as_bool()? && other.as_bool()?)),
+			BinaryOp::Or => Some(Value::Bool(self.as_bool()? || other.as_bool()?)),
+			BinaryOp::Eq | BinaryOp::Ne => {
+				let equal = match (self, other) {
+					(Value::Bool(lhs), Value::Bool(rhs)) => lhs == rhs,
+					(Value::Number(lhs), Value::Number(rhs)) => lhs.as_complex() == rhs.as_complex(),
+					(Value::Vec2(lhs), Value::Vec2(rhs)) => lhs == rhs,
+					(Value::Color(lhs), Value::Color(rhs)) => lhs == rhs,
+					_ => return None,
+				};
+				Some(Value::Bool(equal == (op == BinaryOp::Eq)))
+			}
+			BinaryOp::Lt | BinaryOp::Le | BinaryOp::Gt | BinaryOp::Ge => {
+				let (lhs, rhs) = (self.as_real()?, other.as_real()?);
+				let result = match op {
+					BinaryOp::Lt => lhs < rhs,
+					BinaryOp::Le => lhs <= rhs,
+					BinaryOp::Gt => lhs > rhs,
+					_ => lhs >= rhs,
+				};
+				Some(Value::Bool(result))
+			}
+			_ => match (self, other) {
+				(Value::Number(lhs), Value::Number(rhs)) => lhs.binary_op(op, rhs).map(Value::Number),
+				(Value::Vec2(lhs), Value::Vec2(rhs)) => componentwise_op(op, lhs, rhs, DVec2::rem_euclid).map(Value::Vec2),
+				(Value::Vec2(lhs), Value::Number(Number::Real(rhs))) => componentwise_op(op, lhs, DVec2::splat(rhs), DVec2::rem_euclid).map(Value::Vec2),
+				(Value::Number(Number::Real(lhs)), Value::Vec2(rhs)) => componentwise_op(op, DVec2::splat(lhs), rhs, DVec2::rem_euclid).map(Value::Vec2),
+				(Value::Color(lhs), Value::Color(rhs)) => componentwise_op(op, lhs, rhs, DVec4::rem_euclid).map(Value::Color),
+				(Value::Color(lhs), Value::Number(Number::Real(rhs))) => componentwise_op(op, lhs, DVec4::splat(rhs), DVec4::rem_euclid).map(Value::Color),
+				(Value::Number(Number::Real(lhs)), Value::Color(rhs)) => componentwise_op(op, DVec4::splat(lhs), rhs, DVec4::rem_euclid).map(Value::Color),
+				_ => None,
+			},
+		}
+	}
+
+	pub fn unary_op(self, op: UnaryOp) -> Option<Value> {
+		match (self, op) {
+			(_, UnaryOp::Not) => Some(Value::Bool(!self.as_bool()?)),
+			(Value::Number(num), op) => num.unary_op(op).map(Value::Number),
+			(Value::Vec2(vec), UnaryOp::Neg) => Some(Value::Vec2(-vec)),
+			(Value::Color(color), UnaryOp::Neg) => Some(Value::Color(-color)),
+			(Value::Bool(_) | Value::Vec2(_) | Value::Color(_), _) => None,
+		}
+	}
+}
+
+/// The factorial of whole numbers, extended to other real numbers by the gamma function as `x! = Γ(x + 1)`.
+///
+/// Returns `None` for negative whole numbers, where it's undefined.
+fn factorial(x: f64) -> Option<f64> {
+	if x == f64::INFINITY {
+		return Some(x);
+	}
+	if x.fract() == 0. {
+		if x < 0. {
+			return None;
+		}
+		// Beyond 170!, the result is too large to represent and the loop reaches infinity
+		return Some((2..=x.min(171.) as u32).map(f64::from).product());
+	}
+	Some(gamma(x + 1.))
+}
+
+/// The gamma function, using the reflection formula for arguments below one half and the Lanczos approximation above.
+fn gamma(x: f64) -> f64 {
+	const G: f64 = 7.;
+	const COEFFICIENTS: [f64; 9] = [
+		0.999_999_999_999_809_9,
+		676.520_368_121_885_1,
+		-1_259.139_216_722_402_8,
+		771.323_428_777_653_1,
+		-176.615_029_162_140_6,
+		12.507_343_278_686_905,
+		-0.138_571_095_265_720_12,
+		9.984_369_578_019_572e-6,
+		1.505_632_735_149_311_6e-7,
+	];
+
+	if x < 0.5 {
+		return std::f64::consts::PI / ((std::f64::consts::PI * x).sin() * gamma(1. - x));
+	}
+
+	let x = x - 1.;
+	let sum = COEFFICIENTS[1..]
+		.iter()
+		.enumerate()
+		.fold(COEFFICIENTS[0], |sum, (index, coefficient)| sum + coefficient / (x + index as f64 + 1.));
+	let t = x + G + 0.5;
+	(2. * std::f64::consts::PI).sqrt() * t.powf(x + 0.5) * (-t).exp() * sum
+}
+
+/// Applies an arithmetic operator to each component of a vector or color, where the exponentiation of vectors is undefined.
+fn componentwise_op<T>(op: BinaryOp, lhs: T, rhs: T, rem_euclid: impl Fn(T, T) -> T) -> Option<T>
+where
+	T: Add<Output = T> + Sub<Output = T> + Mul<Output = T> + Div<Output = T>,
+{
+	match op {
+		BinaryOp::Add => Some(lhs + rhs),
+		BinaryOp::Sub => Some(lhs - rhs),
+		BinaryOp::Mul => Some(lhs * rhs),
+		BinaryOp::Div => Some(lhs / rhs),
+		BinaryOp::Mod => Some(rem_euclid(lhs, rhs)),
+		_ => None,
+	}
+}
+
+impl From<bool> for Value {
+	fn from(x: bool) -> Self {
+		Self::Bool(x)
+	}
 }
 
 impl From<f64> for Value {
@@ -28,10 +171,19 @@ impl From<f64> for Value {
 	}
 }
 
+impl From<DVec2> for Value {
+	fn from(x: DVec2) -> Self {
+		Self::Vec2(x)
+	}
+}
+
 impl core::fmt::Display for Value {
 	fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
 		match self {
 			Value::Number(num) => num.fmt(f),
+			Value::Bool(val) => val.fmt(f),
+			Value::Vec2(vec) => write!(f, "vec2({}, {})", vec.x, vec.y),
+			Value::Color(color) => write!(f, "rgba({}, {}, {}, {})", color.x, color.y, color.z, color.w),
 		}
 	}
 }
@@ -52,7 +204,7 @@ impl std::fmt::Display for Number {
 }
 
 impl Number {
-	pub fn binary_op(self, op: BinaryOp, other: Number) -> Number {
+	pub fn binary_op(self, op: BinaryOp, other: Number) -> Option<Number> {
 		match (self, other) {
 			(Number::Real(lhs), Number::Real(rhs)) => {
 				let result = match op {
@@ -60,9 +212,11 @@ impl Number {
 					BinaryOp::Sub => lhs - rhs,
 					BinaryOp::Mul => lhs * rhs,
 					BinaryOp::Div => lhs / rhs,
+					BinaryOp::Mod => lhs.rem_euclid(rhs),
 					BinaryOp::Pow => lhs.powf(rhs),
+					_ => return None,
 				};
-				Number::Real(result)
+				Some(Number::Real(result))
 			}
 
 			(Number::Complex(lhs), Number::Complex(rhs)) => {
@@ -71,9 +225,11 @@ impl Number {
 					BinaryOp::Sub => lhs - rhs,
 					BinaryOp::Mul => lhs * rhs,
 					BinaryOp::Div => lhs / rhs,
+					BinaryOp::Mod => lhs % rhs,
 					BinaryOp::Pow => lhs.powc(rhs),
+					_ => return None,
 				};
-				Number::Complex(result)
+				Some(Number::Complex(result))
 			}
 
 			(Number::Real(lhs), Number::Complex(rhs)) => {
@@ -83,9 +239,11 @@ impl Number {
 					BinaryOp::Sub => lhs_complex - rhs,
 					BinaryOp::Mul => lhs_complex * rhs,
 					BinaryOp::Div => lhs_complex / rhs,
+					BinaryOp::Mod => lhs_complex % rhs,
 					BinaryOp::Pow => lhs_complex.powc(rhs),
+					_ => return None,
 				};
-				Number::Complex(result)
+				Some(Number::Complex(result))
 			}
 
 			(Number::Complex(lhs), Number::Real(rhs)) => {
@@ -95,31 +253,43 @@ impl Number {
 					BinaryOp::Sub => lhs - rhs_complex,
 					BinaryOp::Mul => lhs * rhs_complex,
 					BinaryOp::Div => lhs / rhs_complex,
+					BinaryOp::Mod => lhs % rhs_complex,
 					BinaryOp::Pow => lhs.powf(rhs),
+					_ => return None,
 				};
-				Number::Complex(result)
+				Some(Number::Complex(result))
 			}
 		}
 	}
 
-	pub fn unary_op(self, op: UnaryOp) -> Number {
+	pub fn unary_op(self, op: UnaryOp) -> Option<Number> {
 		match self {
 			Number::Real(real) => match op {
-				UnaryOp::Neg => Number::Real(-real),
-				UnaryOp::Sqrt => Number::Real(real.sqrt()),
+				UnaryOp::Neg => Some(Number::Real(-real)),
+				UnaryOp::Sqrt => Some(Number::Real(real.sqrt())),
 
-				UnaryOp::Fac => todo!("Implement factorial"),
+				UnaryOp::Fac => factorial(real).map(Number::Real),
+				UnaryOp::Not => None,
 			},
 
 			Number::Complex(complex) => match op {
-				UnaryOp::Neg => Number::Complex(-complex),
-				UnaryOp::Sqrt => Number::Complex(complex.sqrt()),
+				UnaryOp::Neg => Some(Number::Complex(-complex)),
+				UnaryOp::Sqrt => Some(Number::Complex(complex.sqrt())),
 
-				UnaryOp::Fac => todo!("Implement factorial"),
+				// The gamma function isn't implemented for complex numbers
+				UnaryOp::Fac => None,
+				UnaryOp::Not => None,
 			},
 		}
 	}
 
+	fn as_complex(self) -> Complex {
+		match self {
+			Number::Real(real) => Complex::new(real, 0.),
+			Number::Complex(complex) => complex,
+		}
+	}
+
 	pub fn from_f64(x: f64) -> Self {
 		Self::Real(x)
 	}
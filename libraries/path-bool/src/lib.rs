@@ -23,15 +23,15 @@ pub(crate) use util::*;
 
 pub use intersection_path_segment::path_segment_intersection;
 #[cfg(feature = "parsing")]
-pub use parsing::path_data::{path_from_path_data, path_to_path_data};
-pub use path_boolean::{BooleanError, EPS, FillRule, PathBooleanOperation, path_boolean};
+pub use parsing::path_data::{PathDataError, path_from_path_data, path_to_path_data, subpaths_from_path_data};
+pub use path_boolean::{BooleanError, EPS, FillRule, FracturedRegion, PathBooleanOperation, path_boolean, path_boolean_many, path_fracture, path_knife, path_regions, path_trim};
 pub use path_segment::PathSegment;
 
 #[cfg(test)]
 mod test {
 	use crate::path_boolean::{self, FillRule, PathBooleanOperation};
 	use crate::path_data::{path_from_path_data, path_to_path_data};
-	use path_boolean::path_boolean;
+	use path_boolean::{path_boolean, path_boolean_many, path_knife, path_regions, path_trim};
 
 	#[test]
 	fn square() {
@@ -330,4 +330,75 @@ mod test {
 		// Add more specific assertions about the resulting path if needed
 		assert!(!result[0].is_empty());
 	}
+	#[test]
+	fn many_touching_squares() {
+		// A 2x2 grid of squares sharing their inner edges, which must disappear from the union.
+		let squares = ["M 0,0 H 20 V 20 H 0 Z", "M 20,0 H 40 V 20 H 20 Z", "M 0,20 H 20 V 40 H 0 Z", "M 20,20 H 40 V 40 H 20 Z"].map(|data| path_from_path_data(data).unwrap());
+		let paths = squares.iter().map(|square| (square, FillRule::NonZero)).collect::<Vec<_>>();
+
+		let result = path_boolean_many(&paths, PathBooleanOperation::Union).unwrap();
+
+		assert_eq!(result.len(), 1, "Expected 1 resulting path for Union operation");
+		let on_outline = |point: glam::DVec2| [point.x, point.y].iter().any(|&coordinate| coordinate.abs() < 1e-6 || (coordinate - 40.).abs() < 1e-6);
+		assert!(result[0].iter().all(|segment| on_outline(segment.start()) && on_outline(segment.end())));
+	}
+	#[test]
+	fn many_overlapping_squares() {
+		let squares = ["M 0,0 H 20 V 20 H 0 Z", "M 10,0 H 30 V 20 H 10 Z", "M 15,0 H 35 V 20 H 15 Z"].map(|data| path_from_path_data(data).unwrap());
+		let paths = squares.iter().map(|square| (square, FillRule::NonZero)).collect::<Vec<_>>();
+
+		let intersection = path_boolean_many(&paths, PathBooleanOperation::Intersection).unwrap();
+		assert_eq!(intersection.len(), 1, "Expected 1 resulting path for Intersection operation");
+		assert!(intersection[0].iter().all(|segment| (15. - 1e-6..=20. + 1e-6).contains(&segment.start().x)));
+
+		// Only covered by a single square: 0..10 and 30..35.
+		let exclusion = path_boolean_many(&paths, PathBooleanOperation::Exclusion).unwrap();
+		assert_eq!(exclusion.len(), 1, "Expected 1 resulting path for Exclusion operation");
+		let path_string = path_to_path_data(&exclusion[0], 0.001);
+		assert_eq!(path_string.chars().filter(|c| c.eq_ignore_ascii_case(&'M')).count(), 2, "Expected 2 subpaths for Exclusion operation");
+
+		// Covered by an odd number of squares: 0..10, 15..20, and 30..35.
+		let xor = path_boolean_many(&paths, PathBooleanOperation::Xor).unwrap();
+		assert_eq!(xor.len(), 1, "Expected 1 resulting path for Xor operation");
+		let path_string = path_to_path_data(&xor[0], 0.001);
+		assert_eq!(path_string.chars().filter(|c| c.eq_ignore_ascii_case(&'M')).count(), 3, "Expected 3 subpaths for Xor operation");
+
+		// The first square without any of the others: 0..10.
+		let difference = path_boolean_many(&paths, PathBooleanOperation::Difference).unwrap();
+		assert_eq!(difference.len(), 1, "Expected 1 resulting path for Difference operation");
+		assert!(difference[0].iter().all(|segment| (-1e-6..=10. + 1e-6).contains(&segment.start().x)));
+	}
+	#[test]
+	fn regions_between_strokes() {
+		// An open box whose lid falls 3 units short of the sides at both ends, and a triangle of lines whose corners don't quite touch.
+		let open_box = path_from_path_data("M 0,0 L 0,100 L 100,100 L 100,0").unwrap();
+		let lid = path_from_path_data("M 3,10 L 97,10").unwrap();
+		assert!(path_regions(&[&open_box, &lid], 0.).unwrap().is_empty());
+		assert_eq!(path_regions(&[&open_box, &lid], 5.).unwrap().len(), 1);
+
+		let sides = ["M 0,0 L 100,0", "M 100,0 L 50,90", "M 48,88 L 2,2"].map(|data| path_from_path_data(data).unwrap());
+		let sides = sides.iter().collect::<Vec<_>>();
+		assert!(path_regions(&sides, 0.).unwrap().is_empty());
+		assert_eq!(path_regions(&sides, 5.).unwrap().len(), 1);
+	}
+	#[test]
+	fn knife_and_trim() {
+		// A ring cut through by a line across its middle, and by a line which stops inside its solid part
+		let ring = path_from_path_data("M 0,0 L 40,0 L 40,40 L 0,40 Z M 10,10 L 10,30 L 30,30 L 30,10 Z").unwrap();
+		let across = path_from_path_data("M -10,20 L 50,20").unwrap();
+		let partway = path_from_path_data("M 20,-10 L 20,5").unwrap();
+
+		assert_eq!(path_knife(&ring, FillRule::EvenOdd, &[&across]).unwrap().len(), 2);
+		assert_eq!(path_knife(&ring, FillRule::EvenOdd, &[&partway]).unwrap().len(), 1);
+		assert_eq!(path_knife(&ring, FillRule::EvenOdd, &[&across, &partway]).unwrap().len(), 2);
+
+		// The line crosses the ring's outline four times, leaving two pieces inside its solid part and three outside
+		let inside = path_trim(&across, &ring, FillRule::EvenOdd, true).unwrap();
+		let outside = path_trim(&across, &ring, FillRule::EvenOdd, false).unwrap();
+		assert_eq!(inside.len(), 2);
+		assert_eq!(outside.len(), 3);
+		let length = |path: &[crate::PathSegment]| path.iter().map(|segment| segment.start().distance(segment.end())).sum::<f64>();
+		assert!((length(&inside) - 20.).abs() < 1e-6);
+		assert!((length(&outside) - 40.).abs() < 1e-6);
+	}
 }
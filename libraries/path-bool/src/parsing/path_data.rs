@@ -1,168 +1,550 @@
 use crate::BooleanError;
-use crate::path::{Path, path_from_commands, path_to_commands};
+use crate::path::{Path, path_from_commands, subpaths_from_commands};
 use crate::path_command::{AbsolutePathCommand, PathCommand, RelativePathCommand};
+use crate::path_segment::PathSegment;
 use glam::DVec2;
-use regex::Regex;
 
-pub fn commands_from_path_data(d: &str) -> Result<Vec<PathCommand>, BooleanError> {
-	let re_float = Regex::new(r"^\s*,?\s*(-?\d*(?:\d\.|\.\d|\d)\d*(?:[eE][+\-]?\d+)?)").unwrap();
-	let re_cmd = Regex::new(r"^\s*([MLCSQTAZHVmlhvcsqtaz])").unwrap();
-	let re_bool = Regex::new(r"^\s*,?\s*([01])").unwrap();
+/// The number of decimal places kept in the numbers of generated path data.
+const DECIMAL_PLACES: usize = 12;
 
-	let mut i = 0;
-	let mut last_cmd = 'M';
-	let mut commands = Vec::new();
+/// Reads path data following the grammar in <https://www.w3.org/TR/SVG2/paths.html#PathDataBNF>.
+struct PathDataParser<'a> {
+	data: &'a [u8],
+	index: usize,
+}
 
-	let get_cmd = |i: &mut usize, last_cmd: char| -> Option<char> {
-		if *i >= d.len() - 1.min(d.len()) {
-			return None;
+impl PathDataParser<'_> {
+	fn peek(&self) -> Option<u8> {
+		self.data.get(self.index).copied()
+	}
+
+	fn error(&self) -> BooleanError {
+		BooleanError::InvalidPathData(self.index)
+	}
+
+	fn skip_whitespace(&mut self) {
+		while matches!(self.peek(), Some(b' ' | b'\t' | b'\n' | b'\r' | b'\x0C')) {
+			self.index += 1;
 		}
+	}
 
-		if let Some(cap) = re_cmd.captures(&d[*i..]) {
-			*i += cap[0].len();
-			Some(cap[1].chars().next().unwrap())
-		} else {
-			match last_cmd {
-				'M' => Some('L'),
-				'm' => Some('l'),
-				'z' | 'Z' => None,
-				_ => Some(last_cmd),
+	fn skip_comma_whitespace(&mut self) {
+		self.skip_whitespace();
+		if self.peek() == Some(b',') {
+			self.index += 1;
+			self.skip_whitespace();
+		}
+	}
+
+	fn skip_digits(&mut self) -> usize {
+		let start = self.index;
+		while self.peek().is_some_and(|byte| byte.is_ascii_digit()) {
+			self.index += 1;
+		}
+		self.index - start
+	}
+
+	fn number(&mut self) -> Result<f64, BooleanError> {
+		let start = self.index;
+
+		if matches!(self.peek(), Some(b'+' | b'-')) {
+			self.index += 1;
+		}
+		let mut digits = self.skip_digits();
+		if self.peek() == Some(b'.') {
+			self.index += 1;
+			digits += self.skip_digits();
+		}
+		if digits == 0 {
+			self.index = start;
+			return Err(self.error());
+		}
+
+		// An exponent without digits isn't part of the number, which leaves the `e` to be rejected as the next command
+		if matches!(self.peek(), Some(b'e' | b'E')) {
+			let exponent_start = self.index;
+			self.index += 1;
+			if matches!(self.peek(), Some(b'+' | b'-')) {
+				self.index += 1;
+			}
+			if self.skip_digits() == 0 {
+				self.index = exponent_start;
 			}
 		}
-	};
 
-	let get_float = |i: &mut usize| -> f64 {
-		if let Some(cap) = re_float.captures(&d[*i..]) {
-			*i += cap[0].len();
-			cap[1].parse().unwrap()
+		let number = std::str::from_utf8(&self.data[start..self.index]).ok().and_then(|number| number.parse::<f64>().ok());
+		let Some(number) = number.filter(|number| number.is_finite()) else {
+			self.index = start;
+			return Err(self.error());
+		};
+
+		self.skip_comma_whitespace();
+		Ok(number)
+	}
+
+	fn flag(&mut self) -> Result<bool, BooleanError> {
+		let flag = match self.peek() {
+			Some(b'0') => false,
+			Some(b'1') => true,
+			_ => return Err(self.error()),
+		};
+		self.index += 1;
+		self.skip_comma_whitespace();
+		Ok(flag)
+	}
+
+	fn point(&mut self) -> Result<DVec2, BooleanError> {
+		Ok(DVec2::new(self.number()?, self.number()?))
+	}
+
+	/// Reads the next command along with its letter, which may be left out to repeat the previous command.
+	fn command(&mut self, last_command: Option<u8>) -> Result<(u8, PathCommand), BooleanError> {
+		let Some(next) = self.peek() else { return Err(self.error()) };
+		let command_start = self.index;
+		let letter = if next.is_ascii_alphabetic() {
+			self.index += 1;
+			self.skip_whitespace();
+			next
 		} else {
-			panic!("Invalid path data. Expected a number at index {}, got {}", i, &d[*i..]);
+			// Numbers without a command letter repeat the previous command, except after a moveto where they are linetos
+			match last_command {
+				Some(b'M') => b'L',
+				Some(b'm') => b'l',
+				Some(b'Z' | b'z') | None => return Err(self.error()),
+				Some(letter) => letter,
+			}
+		};
+
+		if last_command.is_none() && !matches!(letter, b'M' | b'm') {
+			return Err(BooleanError::InvalidPathData(command_start));
 		}
-	};
 
-	let get_bool = |i: &mut usize| -> bool {
-		if let Some(cap) = re_bool.captures(&d[*i..]) {
-			*i += cap[0].len();
-			&cap[1] == "1"
-		} else {
-			panic!("Invalid path data. Expected a flag at index {i}");
-		}
-	};
-
-	while let Some(cmd) = get_cmd(&mut i, last_cmd) {
-		last_cmd = cmd;
-		match cmd {
-			'M' => commands.push(PathCommand::Absolute(AbsolutePathCommand::M(DVec2::new(get_float(&mut i), get_float(&mut i))))),
-			'L' => commands.push(PathCommand::Absolute(AbsolutePathCommand::L(DVec2::new(get_float(&mut i), get_float(&mut i))))),
-			'C' => commands.push(PathCommand::Absolute(AbsolutePathCommand::C(
-				DVec2::new(get_float(&mut i), get_float(&mut i)),
-				DVec2::new(get_float(&mut i), get_float(&mut i)),
-				DVec2::new(get_float(&mut i), get_float(&mut i)),
-			))),
-			'S' => commands.push(PathCommand::Absolute(AbsolutePathCommand::S(
-				DVec2::new(get_float(&mut i), get_float(&mut i)),
-				DVec2::new(get_float(&mut i), get_float(&mut i)),
-			))),
-			'Q' => commands.push(PathCommand::Absolute(AbsolutePathCommand::Q(
-				DVec2::new(get_float(&mut i), get_float(&mut i)),
-				DVec2::new(get_float(&mut i), get_float(&mut i)),
-			))),
-			'T' => commands.push(PathCommand::Absolute(AbsolutePathCommand::T(DVec2::new(get_float(&mut i), get_float(&mut i))))),
-			'A' => commands.push(PathCommand::Absolute(AbsolutePathCommand::A(
-				get_float(&mut i),
-				get_float(&mut i),
-				get_float(&mut i),
-				get_bool(&mut i),
-				get_bool(&mut i),
-				DVec2::new(get_float(&mut i), get_float(&mut i)),
-			))),
-			'Z' | 'z' => commands.push(PathCommand::Absolute(AbsolutePathCommand::Z)),
-			'H' => commands.push(PathCommand::Absolute(AbsolutePathCommand::H(get_float(&mut i)))),
-			'V' => commands.push(PathCommand::Absolute(AbsolutePathCommand::V(get_float(&mut i)))),
-			'm' => commands.push(PathCommand::Relative(RelativePathCommand::M(get_float(&mut i), get_float(&mut i)))),
-			'l' => commands.push(PathCommand::Relative(RelativePathCommand::L(get_float(&mut i), get_float(&mut i)))),
-			'h' => commands.push(PathCommand::Relative(RelativePathCommand::H(get_float(&mut i)))),
-			'v' => commands.push(PathCommand::Relative(RelativePathCommand::V(get_float(&mut i)))),
-			'c' => commands.push(PathCommand::Relative(RelativePathCommand::C(
-				get_float(&mut i),
-				get_float(&mut i),
-				get_float(&mut i),
-				get_float(&mut i),
-				get_float(&mut i),
-				get_float(&mut i),
-			))),
-			's' => commands.push(PathCommand::Relative(RelativePathCommand::S(
-				get_float(&mut i),
-				get_float(&mut i),
-				get_float(&mut i),
-				get_float(&mut i),
-			))),
-			'q' => commands.push(PathCommand::Relative(RelativePathCommand::Q(
-				get_float(&mut i),
-				get_float(&mut i),
-				get_float(&mut i),
-				get_float(&mut i),
-			))),
-			't' => commands.push(PathCommand::Relative(RelativePathCommand::T(get_float(&mut i), get_float(&mut i)))),
-			'a' => commands.push(PathCommand::Relative(RelativePathCommand::A(
-				get_float(&mut i),
-				get_float(&mut i),
-				get_float(&mut i),
-				get_bool(&mut i),
-				get_bool(&mut i),
-				get_float(&mut i),
-				get_float(&mut i),
-			))),
-			_ => return Err(BooleanError::InvalidPathCommand(cmd)),
+		let command = match letter {
+			b'M' => PathCommand::Absolute(AbsolutePathCommand::M(self.point()?)),
+			b'L' => PathCommand::Absolute(AbsolutePathCommand::L(self.point()?)),
+			b'H' => PathCommand::Absolute(AbsolutePathCommand::H(self.number()?)),
+			b'V' => PathCommand::Absolute(AbsolutePathCommand::V(self.number()?)),
+			b'C' => PathCommand::Absolute(AbsolutePathCommand::C(self.point()?, self.point()?, self.point()?)),
+			b'S' => PathCommand::Absolute(AbsolutePathCommand::S(self.point()?, self.point()?)),
+			b'Q' => PathCommand::Absolute(AbsolutePathCommand::Q(self.point()?, self.point()?)),
+			b'T' => PathCommand::Absolute(AbsolutePathCommand::T(self.point()?)),
+			b'A' => PathCommand::Absolute(AbsolutePathCommand::A(self.number()?, self.number()?, self.number()?, self.flag()?, self.flag()?, self.point()?)),
+			b'Z' | b'z' => PathCommand::Absolute(AbsolutePathCommand::Z),
+			b'm' => PathCommand::Relative(RelativePathCommand::M(self.number()?, self.number()?)),
+			b'l' => PathCommand::Relative(RelativePathCommand::L(self.number()?, self.number()?)),
+			b'h' => PathCommand::Relative(RelativePathCommand::H(self.number()?)),
+			b'v' => PathCommand::Relative(RelativePathCommand::V(self.number()?)),
+			b'c' => PathCommand::Relative(RelativePathCommand::C(self.number()?, self.number()?, self.number()?, self.number()?, self.number()?, self.number()?)),
+			b's' => PathCommand::Relative(RelativePathCommand::S(self.number()?, self.number()?, self.number()?, self.number()?)),
+			b'q' => PathCommand::Relative(RelativePathCommand::Q(self.number()?, self.number()?, self.number()?, self.number()?)),
+			b't' => PathCommand::Relative(RelativePathCommand::T(self.number()?, self.number()?)),
+			b'a' => PathCommand::Relative(RelativePathCommand::A(
+				self.number()?,
+				self.number()?,
+				self.number()?,
+				self.flag()?,
+				self.flag()?,
+				self.number()?,
+				self.number()?,
+			)),
+			_ => return Err(BooleanError::InvalidPathCommand(letter as char)),
+		};
+
+		Ok((letter, command))
+	}
+}
+
+/// Path data which doesn't follow the path data grammar, along with what was parsed before the first error.
+///
+/// As SVG renderers draw a path up to its first error, the commands before it remain usable.
+#[derive(Debug)]
+pub struct PathDataError<T> {
+	/// The commands before the error, in the form the parsing function returns.
+	pub parsed: T,
+	pub error: BooleanError,
+}
+
+impl<T> PathDataError<T> {
+	fn map<U>(self, f: impl FnOnce(T) -> U) -> PathDataError<U> {
+		PathDataError {
+			parsed: f(self.parsed),
+			error: self.error,
+		}
+	}
+}
+
+impl<T> std::fmt::Display for PathDataError<T> {
+	fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+		self.error.fmt(f)
+	}
+}
+
+/// Parses SVG path data into its commands, including arcs, smooth curves, relative commands, and implicitly repeated commands.
+///
+/// Empty path data gives no commands, while anything which doesn't follow the path data grammar is an error which holds the complete commands before it.
+pub fn commands_from_path_data(d: &str) -> Result<Vec<PathCommand>, PathDataError<Vec<PathCommand>>> {
+	let mut parser = PathDataParser { data: d.as_bytes(), index: 0 };
+	let mut commands = Vec::new();
+	let mut last_command = None;
+
+	parser.skip_whitespace();
+
+	while parser.peek().is_some() {
+		match parser.command(last_command) {
+			Ok((letter, command)) => {
+				commands.push(command);
+				last_command = Some(letter);
+			}
+			Err(error) => return Err(PathDataError { parsed: commands, error }),
 		}
 	}
 
 	Ok(commands)
 }
 
-pub fn path_from_path_data(d: &str) -> Result<Path, BooleanError> {
-	Ok(path_from_commands(commands_from_path_data(d)?).collect())
+pub fn path_from_path_data(d: &str) -> Result<Path, PathDataError<Path>> {
+	let path = |commands| path_from_commands(commands).collect();
+	commands_from_path_data(d).map(path).map_err(|error| error.map(path))
+}
+
+type Subpaths = Vec<(Path, bool)>;
+
+/// Parses SVG path data into the segments of each of its subpaths, along with whether the subpath is closed by a `Z` command.
+///
+/// Arcs are kept as [`PathSegment::Arc`], which [`PathSegment::arc_segment_to_cubics`] can turn into cubic segments.
+pub fn subpaths_from_path_data(d: &str) -> Result<Subpaths, PathDataError<Subpaths>> {
+	commands_from_path_data(d).map(subpaths_from_commands).map_err(|error| error.map(subpaths_from_commands))
 }
 
+/// Writes the path as compact SVG path data.
+///
+/// Segments which start within `eps` of the end of the previous segment continue its subpath, and subpaths which end within `eps` of their start are closed.
+/// Each command is written relative to the current point when that's shorter, and lines along an axis and smooth curves use their shorthand commands.
 pub fn path_to_path_data(path: &Path, eps: f64) -> String {
-	path_to_commands(path.iter(), eps)
-		.map(|cmd| match cmd {
-			PathCommand::Absolute(abs_cmd) => match abs_cmd {
-				AbsolutePathCommand::H(dx) => format!("H {dx:.12}"),
-				AbsolutePathCommand::V(dy) => format!("V {dy:.12}"),
-				AbsolutePathCommand::M(p) => format!("M {:.12},{:.12}", p.x, p.y),
-				AbsolutePathCommand::L(p) => format!("L {:.12},{:.12}", p.x, p.y),
-				AbsolutePathCommand::C(p1, p2, p3) => format!("C {:.12},{:.12} {:.12},{:.12} {:.12},{:.12}", p1.x, p1.y, p2.x, p2.y, p3.x, p3.y),
-				AbsolutePathCommand::S(p1, p2) => {
-					format!("S {:.12},{:.12} {:.12},{:.12}", p1.x, p1.y, p2.x, p2.y)
-				}
-				AbsolutePathCommand::Q(p1, p2) => {
-					format!("Q {:.12},{:.12} {:.12},{:.12}", p1.x, p1.y, p2.x, p2.y)
-				}
-				AbsolutePathCommand::T(p) => format!("T {:.12},{:.12}", p.x, p.y),
-				AbsolutePathCommand::A(rx, ry, x_axis_rotation, large_arc_flag, sweep_flag, p) => {
-					format!("A {:.12} {:.12} {:.12} {} {} {:.12},{:.12}", rx, ry, x_axis_rotation, large_arc_flag as u8, sweep_flag as u8, p.x, p.y)
-				}
-				AbsolutePathCommand::Z => "Z".to_string(),
-			},
-			PathCommand::Relative(rel_cmd) => match rel_cmd {
-				RelativePathCommand::M(dx, dy) => format!("m {dx:.12},{dy:.12}"),
-				RelativePathCommand::L(dx, dy) => format!("l {dx:.12},{dy:.12}"),
-				RelativePathCommand::H(dx) => format!("h {dx:.12}"),
-				RelativePathCommand::V(dy) => format!("v {dy:.12}"),
-				RelativePathCommand::C(dx1, dy1, dx2, dy2, dx, dy) => format!("c{dx1:.12},{dy1:.12} {dx2:.12},{dy2:.12} {dx:.12},{dy:.12}"),
-				RelativePathCommand::S(dx2, dy2, dx, dy) => {
-					format!("s {dx2:.12},{dy2:.12} {dx:.12},{dy:.12}")
-				}
-				RelativePathCommand::Q(dx1, dy1, dx, dy) => {
-					format!("q {dx1:.12},{dy1:.12} {dx:.12},{dy:.12}")
+	let mut writer = PathDataWriter::default();
+
+	let mut subpath_start = 0;
+	for index in 1..=path.len() {
+		if path.get(index).is_none_or(|segment| !segment.start().abs_diff_eq(path[index - 1].end(), eps)) {
+			let subpath = &path[subpath_start..index];
+			let closed = subpath[subpath.len() - 1].end().abs_diff_eq(subpath[0].start(), eps);
+			writer.write_subpath(subpath, closed);
+			subpath_start = index;
+		}
+	}
+
+	writer.data
+}
+
+/// Formats a number with at most [`DECIMAL_PLACES`] decimal places, leaving out trailing zeros and the zero before the decimal point.
+fn format_number(number: f64) -> String {
+	let mut text = format!("{number:.DECIMAL_PLACES$}");
+	if text.contains('.') {
+		text.truncate(text.trim_end_matches('0').trim_end_matches('.').len());
+	}
+
+	if text == "-0" {
+		"0".to_string()
+	} else if let Some(fraction) = text.strip_prefix("0.") {
+		format!(".{fraction}")
+	} else if let Some(fraction) = text.strip_prefix("-0.") {
+		format!("-.{fraction}")
+	} else {
+		text
+	}
+}
+
+/// A command to be written, along with the state a reader of the path data has after reading it.
+struct EncodedCommand {
+	command: char,
+	arguments: Vec<String>,
+	end: DVec2,
+	cubic_control: Option<DVec2>,
+	quadratic_control: Option<DVec2>,
+}
+
+/// Writes path data while following the current point and control points as a reader would compute them from the rounded numbers,
+/// so that rounding errors don't accumulate across relative commands.
+#[derive(Default)]
+struct PathDataWriter {
+	data: String,
+	current: DVec2,
+	subpath_start: DVec2,
+	last_command: Option<char>,
+	last_argument: Option<String>,
+	cubic_control: Option<DVec2>,
+	quadratic_control: Option<DVec2>,
+}
+
+impl PathDataWriter {
+	fn write_subpath(&mut self, segments: &[PathSegment], closed: bool) {
+		let start = segments[0].start();
+		let absolute = self.encode_move(start, false);
+		let relative = self.encode_move(start, true);
+		// The first moveto is always written as absolute, even though a relative one would be read the same way
+		let encoded = if self.data.is_empty() { absolute } else { self.shorter(absolute, relative) };
+		self.write(encoded);
+		self.subpath_start = self.current;
+
+		// The `Z` command draws the last line of a closed subpath
+		let drawn = match segments.split_last() {
+			Some((PathSegment::Line(..), rest)) if closed => rest,
+			_ => segments,
+		};
+		for segment in drawn {
+			let absolute = self.encode_segment(segment, false);
+			let relative = self.encode_segment(segment, true);
+			let encoded = self.shorter(absolute, relative);
+			self.write(encoded);
+		}
+
+		if closed {
+			self.write(EncodedCommand {
+				command: 'Z',
+				arguments: Vec::new(),
+				end: self.subpath_start,
+				cubic_control: None,
+				quadratic_control: None,
+			});
+		}
+	}
+
+	fn encode_move(&self, point: DVec2, relative: bool) -> EncodedCommand {
+		let mut arguments = Vec::new();
+		let end = self.encode_point(point, relative, &mut arguments);
+		EncodedCommand {
+			command: if relative { 'm' } else { 'M' },
+			arguments,
+			end,
+			cubic_control: None,
+			quadratic_control: None,
+		}
+	}
+
+	/// Adds the coordinates of the point to the arguments, and returns where a reader of the data will place it.
+	fn encode_point(&self, point: DVec2, relative: bool, arguments: &mut Vec<String>) -> DVec2 {
+		let origin = if relative { self.current } else { DVec2::ZERO };
+		let x = format_number(point.x - origin.x);
+		let y = format_number(point.y - origin.y);
+		let placed = origin + DVec2::new(x.parse().unwrap_or_default(), y.parse().unwrap_or_default());
+		arguments.extend([x, y]);
+		placed
+	}
+
+	fn encode_segment(&self, segment: &PathSegment, relative: bool) -> EncodedCommand {
+		let origin = if relative { self.current } else { DVec2::ZERO };
+		let is_same = |a: DVec2, b: DVec2| format_number(a.x) == format_number(b.x) && format_number(a.y) == format_number(b.y);
+
+		let mut arguments = Vec::new();
+		let mut cubic_control = None;
+		let mut quadratic_control = None;
+
+		let (command, end) = match *segment {
+			PathSegment::Line(_, end) if format_number(end.y - self.current.y) == "0" => {
+				let x = format_number(end.x - origin.x);
+				let placed = DVec2::new(origin.x + x.parse::<f64>().unwrap_or_default(), self.current.y);
+				arguments.push(x);
+				('H', placed)
+			}
+			PathSegment::Line(_, end) if format_number(end.x - self.current.x) == "0" => {
+				let y = format_number(end.y - origin.y);
+				let placed = DVec2::new(self.current.x, origin.y + y.parse::<f64>().unwrap_or_default());
+				arguments.push(y);
+				('V', placed)
+			}
+			PathSegment::Line(_, end) => ('L', self.encode_point(end, relative, &mut arguments)),
+			PathSegment::Cubic(_, c1, c2, end) => {
+				let reflected = self.cubic_control.map(|control| self.current * 2. - control);
+				let smooth = reflected.is_some_and(|reflected| is_same(reflected, c1));
+				if !smooth {
+					self.encode_point(c1, relative, &mut arguments);
 				}
-				RelativePathCommand::T(dx, dy) => format!("t{dx:.12},{dy:.12}"),
-				RelativePathCommand::A(rx, ry, x_axis_rotation, large_arc_flag, sweep_flag, dx, dy) => {
-					format!("a {:.12} {:.12} {:.12} {} {} {:.12},{:.12}", rx, ry, x_axis_rotation, large_arc_flag as u8, sweep_flag as u8, dx, dy)
+				cubic_control = Some(self.encode_point(c2, relative, &mut arguments));
+				(if smooth { 'S' } else { 'C' }, self.encode_point(end, relative, &mut arguments))
+			}
+			PathSegment::Quadratic(_, control, end) => {
+				let reflected = self.quadratic_control.map(|previous| self.current * 2. - previous);
+				let smooth = reflected.is_some_and(|reflected| is_same(reflected, control));
+				quadratic_control = if smooth { reflected } else { Some(self.encode_point(control, relative, &mut arguments)) };
+				(if smooth { 'T' } else { 'Q' }, self.encode_point(end, relative, &mut arguments))
+			}
+			PathSegment::Arc(_, rx, ry, x_axis_rotation, large_arc_flag, sweep_flag, end) => {
+				arguments.extend([format_number(rx), format_number(ry), format_number(x_axis_rotation)]);
+				arguments.extend([large_arc_flag, sweep_flag].map(|flag| (flag as u8).to_string()));
+				('A', self.encode_point(end, relative, &mut arguments))
+			}
+		};
+
+		EncodedCommand {
+			command: if relative { command.to_ascii_lowercase() } else { command },
+			arguments,
+			end,
+			cubic_control,
+			quadratic_control,
+		}
+	}
+
+	/// The text which writing the command adds to the path data, which leaves out a repeated command letter and any separators that aren't needed.
+	fn text(&self, encoded: &EncodedCommand) -> String {
+		let implicit = match (self.last_command, encoded.command) {
+			(Some('M'), 'L') | (Some('m'), 'l') => true,
+			(Some(last), command) => last == command && !matches!(command, 'M' | 'm' | 'Z' | 'z'),
+			(None, _) => false,
+		};
+
+		let mut text = String::new();
+		let mut previous = self.last_argument.as_deref();
+		if !implicit {
+			text.push(encoded.command);
+			previous = None;
+		}
+
+		for argument in &encoded.arguments {
+			// A separator is needed unless the sign or the decimal point of the next number ends the previous one
+			let separated = argument.starts_with('-') || (argument.starts_with('.') && previous.is_some_and(|previous| previous.contains('.')));
+			if previous.is_some() && !separated {
+				text.push(' ');
+			}
+			text.push_str(argument);
+			previous = Some(argument);
+		}
+
+		text
+	}
+
+	fn shorter(&self, absolute: EncodedCommand, relative: EncodedCommand) -> EncodedCommand {
+		if self.text(&relative).len() < self.text(&absolute).len() { relative } else { absolute }
+	}
+
+	fn write(&mut self, encoded: EncodedCommand) {
+		let text = self.text(&encoded);
+		self.data.push_str(&text);
+
+		self.last_command = Some(encoded.command);
+		self.last_argument = encoded.arguments.last().cloned();
+		self.current = encoded.end;
+		self.cubic_control = encoded.cubic_control;
+		self.quadratic_control = encoded.quadratic_control;
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	fn assert_paths_eq(a: &Path, b: &Path) {
+		assert_eq!(a.len(), b.len(), "{a:?} != {b:?}");
+		for (a, b) in a.iter().zip(b) {
+			let (a, b) = (a.arc_segment_to_cubics(0.1), b.arc_segment_to_cubics(0.1));
+			for (a, b) in a.iter().zip(&b) {
+				for (a, b) in a.to_cubic().iter().zip(b.to_cubic()) {
+					assert!(a.abs_diff_eq(b, 1e-9), "{a} != {b}");
 				}
-			},
-		})
-		.collect::<Vec<String>>()
-		.join(" ")
+			}
+		}
+	}
+
+	#[test]
+	fn compact_numbers_and_separators() {
+		let path = path_from_path_data("M10-20l.5.5-1.5e1,0h1E+1 .25v-.5z").unwrap();
+		let expected = path_from_path_data("M 10 -20 L 10.5 -19.5 L -4.5 -19.5 L 5.5 -19.5 L 5.75 -19.5 L 5.75 -20 L 10 -20").unwrap();
+		assert_paths_eq(&path, &expected);
+	}
+
+	#[test]
+	fn implicit_commands() {
+		let path = path_from_path_data("m 10 10 20 0 0 20 Z l 5 5 M 0 0 100 0").unwrap();
+		let expected = path_from_path_data("M 10 10 L 30 10 L 30 30 L 10 10 M 10 10 L 15 15 M 0 0 L 100 0").unwrap();
+		assert_paths_eq(&path, &expected);
+	}
+
+	#[test]
+	fn smooth_curves() {
+		let path = path_from_path_data("M 0 0 C 0 10 10 10 10 0 s 10 -10 10 0 Q 30 10 40 0 t 10 0 S 60 0 60 10").unwrap();
+		let expected = [
+			PathSegment::Cubic(DVec2::new(0., 0.), DVec2::new(0., 10.), DVec2::new(10., 10.), DVec2::new(10., 0.)),
+			PathSegment::Cubic(DVec2::new(10., 0.), DVec2::new(10., -10.), DVec2::new(20., -10.), DVec2::new(20., 0.)),
+			PathSegment::Quadratic(DVec2::new(20., 0.), DVec2::new(30., 10.), DVec2::new(40., 0.)),
+			PathSegment::Quadratic(DVec2::new(40., 0.), DVec2::new(50., -10.), DVec2::new(50., 0.)),
+			// A smooth cubic after a quadratic has no control point to reflect
+			PathSegment::Cubic(DVec2::new(50., 0.), DVec2::new(50., 0.), DVec2::new(60., 0.), DVec2::new(60., 10.)),
+		];
+		assert_paths_eq(&path, &expected.to_vec());
+	}
+
+	#[test]
+	fn arcs() {
+		let path = path_from_path_data("M 0 0 a 10 10 0 0 1 20 0 A 0 5 0 1 1 30 0 A 5 5 0 0 0 30 0 a-5 5 0 1020 0").unwrap();
+		assert_eq!(path.len(), 3);
+		assert!(matches!(path[0], PathSegment::Arc(_, 10., 10., 0., false, true, end) if end == DVec2::new(20., 0.)));
+		assert!(matches!(path[1], PathSegment::Line(_, end) if end == DVec2::new(30., 0.)));
+		assert!(matches!(path[2], PathSegment::Arc(_, 5., 5., 0., true, false, end) if end == DVec2::new(50., 0.)));
+
+		// Radii which are too small to reach the end point are scaled up to make a half circle
+		let cubics = PathSegment::Arc(DVec2::new(0., 0.), 1., 1., 0., false, true, DVec2::new(20., 0.)).arc_segment_to_cubics(0.1);
+		let middle = cubics[cubics.len() / 2].start();
+		assert!(middle.abs_diff_eq(DVec2::new(10., -10.), 1e-6), "{middle}");
+	}
+
+	#[test]
+	fn subpaths() {
+		let subpaths = subpaths_from_path_data("M 0 0 L 10 0 L 10 10 Z L 0 10 M 20 20 H 30 M 40 40").unwrap();
+		let closed = subpaths.iter().map(|(segments, closed)| (segments.len(), *closed)).collect::<Vec<_>>();
+		assert_eq!(closed, [(3, true), (1, false), (1, false)]);
+	}
+
+	#[test]
+	fn invalid_path_data() {
+		assert!(path_from_path_data("").unwrap().is_empty());
+		assert!(path_from_path_data("  \n").unwrap().is_empty());
+		assert!(matches!(path_from_path_data("L 10 10").map_err(|error| error.error), Err(BooleanError::InvalidPathData(0))));
+		assert!(matches!(path_from_path_data("M 10").map_err(|error| error.error), Err(BooleanError::InvalidPathData(4))));
+		assert!(matches!(path_from_path_data("M 10 10 L").map_err(|error| error.error), Err(BooleanError::InvalidPathData(9))));
+		assert!(matches!(path_from_path_data("M 10 10 Z 10").map_err(|error| error.error), Err(BooleanError::InvalidPathData(10))));
+		assert!(matches!(
+			path_from_path_data("M 10 10 A 1 1 0 2 0 5 5").map_err(|error| error.error),
+			Err(BooleanError::InvalidPathData(16))
+		));
+		assert!(matches!(path_from_path_data("M 10,,10").map_err(|error| error.error), Err(BooleanError::InvalidPathData(5))));
+		assert!(matches!(path_from_path_data("M 10 10 X 5").map_err(|error| error.error), Err(BooleanError::InvalidPathCommand('X'))));
+		assert!(matches!(path_from_path_data("M 1 1e 5").map_err(|error| error.error), Err(BooleanError::InvalidPathCommand('e'))));
+	}
+
+	#[test]
+	fn path_before_the_first_error() {
+		let error = subpaths_from_path_data("M 0 0 L 10 0 L 10 10 Z M 20 20 H 30 L 40").unwrap_err();
+		assert!(matches!(error.error, BooleanError::InvalidPathData(40)));
+		let closed = error.parsed.iter().map(|(segments, closed)| (segments.len(), *closed)).collect::<Vec<_>>();
+		assert_eq!(closed, [(3, true), (1, false)]);
+
+		let error = path_from_path_data("M 0 0 L 10 0 X").unwrap_err();
+		assert!(matches!(error.error, BooleanError::InvalidPathCommand('X')));
+		assert_paths_eq(&error.parsed, &path_from_path_data("M 0 0 L 10 0").unwrap());
+
+		assert!(path_from_path_data("L 10 10").unwrap_err().parsed.is_empty());
+	}
+
+	#[test]
+	fn compact_output() {
+		let path = path_from_path_data("M 0 0 L 10 0 L 10 10 L 0.5 10.5 Z M 100 100 C 100 110 110 110 110 100 C 110 90 120 90 120 100").unwrap();
+		assert_eq!(path_to_path_data(&path, 1e-6), "M0 0H10V10l-9.5.5ZM100 100c0 10 10 10 10 0s10-10 10 0");
+
+		let arc = path_from_path_data("M 0 0 A 5 5 0 0 1 10 0").unwrap();
+		assert_eq!(path_to_path_data(&arc, 1e-6), "M0 0A5 5 0 0 1 10 0");
+	}
+
+	#[test]
+	fn round_trip() {
+		let data = [
+			"M 10 10 L 50 10 L 30 40 Z M 60 60 Q 70 50 80 60 T 100 60 L 100 100",
+			"m 25.797222,29.08718 c 0,1.292706 -1.047946,2.340652 -2.340652,2.340652 -1.292707,0 -2.340652,-1.047946 -2.340652,-2.340652 0,-1.292707 1.047945,-2.340652 2.340652,-2.340652 1.292706,0 2.340652,1.047945 2.340652,2.340652 z",
+			"M 47,24 A 23,23 0 0 1 24,47 23,23 0 0 1 1,24 23,23 0 0 1 24,1 23,23 0 0 1 47,24 Z",
+			"M 0.1 0.2 l 0.1 0.2 l 0.1 0.2 l 0.1 0.2 l -0.3 -0.6 z",
+		];
+		for data in data {
+			let path = path_from_path_data(data).unwrap();
+			let written = path_to_path_data(&path, 1e-6);
+			assert_paths_eq(&path_from_path_data(&written).unwrap(), &path);
+		}
+	}
 }
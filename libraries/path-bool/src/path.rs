@@ -21,120 +21,96 @@ pub fn path_from_commands<I>(commands: I) -> impl Iterator<Item = PathSegment>
 where
 	I: IntoIterator<Item = PathCommand>,
 {
-	let mut first_point: Option<DVec2> = None;
-	let mut last_point: Option<DVec2> = None;
-	let mut last_control_point: Option<DVec2> = None;
-
-	to_absolute_commands(commands).filter_map(move |cmd| match cmd {
-		AbsolutePathCommand::M(point) => {
-			last_point = Some(point);
-			first_point = Some(point);
-			last_control_point = None;
-			None
-		}
-		AbsolutePathCommand::L(point) => {
-			let start = last_point.unwrap();
-			last_point = Some(point);
-			last_control_point = None;
-			Some(PathSegment::Line(start, point))
-		}
-		AbsolutePathCommand::H(x) => {
-			let start = last_point.unwrap();
-			let point = DVec2::new(x, start.y);
-			last_point = Some(point);
-			last_control_point = None;
-			Some(PathSegment::Line(start, point))
-		}
-		AbsolutePathCommand::V(y) => {
-			let start = last_point.unwrap();
-			let point = DVec2::new(start.x, y);
-			last_point = Some(point);
-			last_control_point = None;
-			Some(PathSegment::Line(start, point))
-		}
-		AbsolutePathCommand::C(c1, c2, end) => {
-			let start = last_point.unwrap();
-			last_point = Some(end);
-			last_control_point = Some(c2);
-			Some(PathSegment::Cubic(start, c1, c2, end))
-		}
-		AbsolutePathCommand::S(c2, end) => {
-			let start = last_point.unwrap();
-			let c1 = reflect_control_point(start, last_control_point.unwrap_or(start));
-			last_point = Some(end);
-			last_control_point = Some(c2);
-			Some(PathSegment::Cubic(start, c1, c2, end))
-		}
-		AbsolutePathCommand::Q(c, end) => {
-			let start = last_point.unwrap();
-			last_point = Some(end);
-			last_control_point = Some(c);
-			Some(PathSegment::Quadratic(start, c, end))
-		}
-		AbsolutePathCommand::T(end) => {
-			let start = last_point.unwrap();
-			let c = reflect_control_point(start, last_control_point.unwrap_or(start));
-			last_point = Some(end);
-			last_control_point = Some(c);
-			Some(PathSegment::Quadratic(start, c, end))
-		}
-		AbsolutePathCommand::A(rx, ry, x_axis_rotation, large_arc_flag, sweep_flag, end) => {
-			let start = last_point.unwrap();
-			last_point = Some(end);
-			last_control_point = None;
-			Some(PathSegment::Arc(start, rx, ry, x_axis_rotation, large_arc_flag, sweep_flag, end))
-		}
-		AbsolutePathCommand::Z => {
-			let start = last_point.unwrap();
-			let end = first_point.unwrap();
-			last_point = Some(end);
-			last_control_point = None;
-			Some(PathSegment::Line(start, end))
-		}
-	})
+	subpaths_from_commands(commands).into_iter().flat_map(|(segments, _)| segments)
 }
 
+/// Gaps up to this size between the end of a subpath and its start, which are left by rounding the numbers in path data, are closed by a `Z` command without adding a line segment.
+#[cfg(feature = "parsing")]
+const CLOSE_GAP_TOLERANCE: f64 = 1e-9;
+
+/// Builds the segments of each subpath described by the commands, along with whether the subpath was closed by a `Z` command.
+///
+/// This follows the rules of <https://www.w3.org/TR/SVG2/paths.html>: smooth curves only reflect the control point of a preceding curve of the same kind,
+/// arcs with a zero radius become lines, and arcs which end where they start are left out.
 #[cfg(feature = "parsing")]
-pub fn path_to_commands<'a, I>(segments: I, eps: f64) -> impl Iterator<Item = PathCommand> + 'a
+pub fn subpaths_from_commands<I>(commands: I) -> Vec<(Path, bool)>
 where
-	I: IntoIterator<Item = &'a PathSegment> + 'a,
+	I: IntoIterator<Item = PathCommand>,
 {
-	let mut last_point: Option<DVec2> = None;
+	let mut subpaths = Vec::new();
+	let mut segments = Vec::new();
+	let mut first_point = DVec2::ZERO;
+	let mut last_point = DVec2::ZERO;
+	let mut last_cubic_control: Option<DVec2> = None;
+	let mut last_quadratic_control: Option<DVec2> = None;
 
-	segments
-		.into_iter()
-		.flat_map(move |seg| {
-			let start = seg.start();
-			let mut commands = Vec::new();
+	for command in to_absolute_commands(commands) {
+		let start = last_point;
+		let mut cubic_control = None;
+		let mut quadratic_control = None;
 
-			if last_point.is_none_or(|lp| !start.abs_diff_eq(lp, eps)) {
-				if last_point.is_some() {
-					commands.push(PathCommand::Absolute(AbsolutePathCommand::Z));
+		let segment = match command {
+			AbsolutePathCommand::M(point) => {
+				if !segments.is_empty() {
+					subpaths.push((std::mem::take(&mut segments), false));
 				}
-
-				commands.push(PathCommand::Absolute(AbsolutePathCommand::M(start)));
+				first_point = point;
+				last_point = point;
+				None
 			}
-
-			match seg {
-				PathSegment::Line(_, end) => {
-					commands.push(PathCommand::Absolute(AbsolutePathCommand::L(*end)));
-					last_point = Some(*end);
-				}
-				PathSegment::Cubic(_, c1, c2, end) => {
-					commands.push(PathCommand::Absolute(AbsolutePathCommand::C(*c1, *c2, *end)));
-					last_point = Some(*end);
+			AbsolutePathCommand::L(point) => Some(PathSegment::Line(start, point)),
+			AbsolutePathCommand::H(x) => Some(PathSegment::Line(start, DVec2::new(x, start.y))),
+			AbsolutePathCommand::V(y) => Some(PathSegment::Line(start, DVec2::new(start.x, y))),
+			AbsolutePathCommand::C(c1, c2, end) => {
+				cubic_control = Some(c2);
+				Some(PathSegment::Cubic(start, c1, c2, end))
+			}
+			AbsolutePathCommand::S(c2, end) => {
+				let c1 = reflect_control_point(start, last_cubic_control.unwrap_or(start));
+				cubic_control = Some(c2);
+				Some(PathSegment::Cubic(start, c1, c2, end))
+			}
+			AbsolutePathCommand::Q(c, end) => {
+				quadratic_control = Some(c);
+				Some(PathSegment::Quadratic(start, c, end))
+			}
+			AbsolutePathCommand::T(end) => {
+				let c = reflect_control_point(start, last_quadratic_control.unwrap_or(start));
+				quadratic_control = Some(c);
+				Some(PathSegment::Quadratic(start, c, end))
+			}
+			AbsolutePathCommand::A(rx, ry, x_axis_rotation, large_arc_flag, sweep_flag, end) => {
+				if start == end {
+					None
+				} else if rx == 0. || ry == 0. {
+					Some(PathSegment::Line(start, end))
+				} else {
+					Some(PathSegment::Arc(start, rx.abs(), ry.abs(), x_axis_rotation, large_arc_flag, sweep_flag, end))
 				}
-				PathSegment::Quadratic(_, c, end) => {
-					commands.push(PathCommand::Absolute(AbsolutePathCommand::Q(*c, *end)));
-					last_point = Some(*end);
+			}
+			AbsolutePathCommand::Z => {
+				if !start.abs_diff_eq(first_point, CLOSE_GAP_TOLERANCE) {
+					segments.push(PathSegment::Line(start, first_point));
 				}
-				PathSegment::Arc(_, rx, ry, x_axis_rotation, large_arc_flag, sweep_flag, end) => {
-					commands.push(PathCommand::Absolute(AbsolutePathCommand::A(*rx, *ry, *x_axis_rotation, *large_arc_flag, *sweep_flag, *end)));
-					last_point = Some(*end);
+				if !segments.is_empty() {
+					subpaths.push((std::mem::take(&mut segments), true));
 				}
+				last_point = first_point;
+				None
 			}
+		};
+
+		if let Some(segment) = segment {
+			last_point = segment.end();
+			segments.push(segment);
+		}
+		last_cubic_control = cubic_control;
+		last_quadratic_control = quadratic_control;
+	}
+
+	if !segments.is_empty() {
+		subpaths.push((segments, false));
+	}
 
-			commands
-		})
-		.chain(std::iter::once(PathCommand::Absolute(AbsolutePathCommand::Z)))
+	subpaths
 }
@@ -298,7 +298,7 @@ impl PathSegment {
 			}
 
 			let sign = if fa == fs { -1. } else { 1. };
-			let multiplier = ((rx2 * ry2 - rx2 * y1_prime2 - ry2 * x1_prime2) / (rx2 * y1_prime2 + ry2 * x1_prime2)).sqrt();
+			let multiplier = ((rx2 * ry2 - rx2 * y1_prime2 - ry2 * x1_prime2) / (rx2 * y1_prime2 + ry2 * x1_prime2)).max(0.).sqrt();
 			let cx_prime = sign * multiplier * ((rx * xy1_prime.y) / ry);
 			let cy_prime = sign * multiplier * ((-ry * xy1_prime.x) / rx);
 
@@ -388,11 +388,12 @@ impl PathSegment {
 	///
 	/// A vector of `PathSegment::Cubic` approximating the original segment.
 	pub fn arc_segment_to_cubics(&self, max_delta_theta: f64) -> Vec<PathSegment> {
-		if let PathSegment::Arc(start, rx, ry, phi, _, _, end) = *self {
+		if let PathSegment::Arc(start, _, _, phi, _, _, end) = *self {
 			if let Some(center_param) = self.arc_segment_to_center() {
 				let count = ((center_param.delta_theta.abs() / max_delta_theta).ceil() as usize).max(1);
 
-				let from_unit = DMat3::from_translation(center_param.center) * DMat3::from_angle(phi.to_radians()) * DMat3::from_scale(DVec2::new(rx, ry));
+				// The radii of the center parametrization are scaled up when they're too small to reach the end point
+				let from_unit = DMat3::from_translation(center_param.center) * DMat3::from_angle(phi.to_radians()) * DMat3::from_scale(DVec2::new(center_param.rx, center_param.ry));
 
 				let theta = center_param.delta_theta / count as f64;
 				let k = (4. / 3.) * (theta / 4.).tan();
@@ -406,7 +407,7 @@ impl PathSegment {
 						let control2 = DVec2::new(cos_theta + k * sin_theta, sin_theta - k * cos_theta);
 						let end = DVec2::new(cos_theta, sin_theta);
 
-						let matrix = DMat3::from_angle(center_param.theta1 + i as f64 * theta) * from_unit;
+						let matrix = from_unit * DMat3::from_angle(center_param.theta1 + i as f64 * theta);
 						let start = (matrix * start.extend(1.)).truncate();
 						let control1 = (matrix * control1.extend(1.)).truncate();
 						let control2 = (matrix * control2.extend(1.)).truncate();
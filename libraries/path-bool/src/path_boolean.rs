@@ -112,6 +112,7 @@ pub enum PathBooleanOperation {
 	///
 	/// The result contains areas that are inside either path A or path B, but not in both.
 	/// This operation is useful for creating non-overlapping regions or finding boundaries.
+	/// With more than two paths, only the areas inside exactly one of them are kept.
 	Exclusion,
 
 	/// Divides the first path using the second path as a "knife".
@@ -126,6 +127,12 @@ pub enum PathBooleanOperation {
 	/// resulting in all possible non-overlapping segments from both paths.
 	/// It's useful for creating detailed breakdowns of overlapping shapes.
 	Fracture,
+
+	/// Computes the parity of the paths, keeping the areas that are inside an odd number of them.
+	///
+	/// With two paths, this is the same as [`PathBooleanOperation::Exclusion`]. With more, the areas where
+	/// three (or five, and so on) paths overlap are kept rather than cut away.
+	Xor,
 }
 
 /// Specifies how to determine the "inside" of a path for filling.
@@ -143,13 +150,58 @@ pub const EPS: Epsilons = Epsilons {
 	param: 1e-8,
 };
 
-type MajorGraphEdgeStage1 = (PathSegment, u8);
-type MajorGraphEdgeStage2 = (PathSegment, u8, Aabb);
+/// The index of an input path, in the order the paths are given to the boolean operation.
+type PathIndex = u32;
+
+type MajorGraphEdgeStage1 = (PathSegment, PathIndex);
+type MajorGraphEdgeStage2 = (PathSegment, PathIndex, Aabb);
+
+/// The input paths an edge belongs to, each paired with the change in that path's winding number when the edge is crossed in its forward direction.
+///
+/// Edges shared by several paths, like the common side of two adjacent shapes, carry one entry per path so each path's winding is tracked separately. Entries are sorted by path index.
+type EdgeWindings = SmallVec<[(PathIndex, i32); 2]>;
+
+fn add_winding(windings: &mut EdgeWindings, path: PathIndex, delta: i32) {
+	match windings.binary_search_by_key(&path, |&(index, _)| index) {
+		Ok(position) => windings[position].1 += delta,
+		Err(position) => windings.insert(position, (path, delta)),
+	}
+}
+
+fn contains_path(windings: &EdgeWindings, path: PathIndex) -> bool {
+	windings.iter().any(|&(index, _)| index == path)
+}
+
+/// The set of input paths whose interior contains a face, as a bit set indexed by path.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+struct Coverage(SmallVec<[u64; 1]>);
+
+impl Coverage {
+	fn new(path_count: usize) -> Self {
+		Self(smallvec::smallvec![0; path_count.div_ceil(64).max(1)])
+	}
+
+	fn insert(&mut self, path: usize) {
+		self.0[path / 64] |= 1 << (path % 64);
+	}
+
+	fn contains(&self, path: usize) -> bool {
+		self.0[path / 64] & (1 << (path % 64)) != 0
+	}
+
+	fn count(&self) -> usize {
+		self.0.iter().map(|word| word.count_ones() as usize).sum()
+	}
+
+	fn iter(&self) -> impl Iterator<Item = usize> + '_ {
+		(0..self.0.len() * 64).filter(|&path| self.contains(path))
+	}
+}
 
 #[derive(Debug, Clone)]
 pub struct MajorGraphEdge {
 	seg: PathSegment,
-	parent: u8,
+	windings: EdgeWindings,
 	incident_vertices: [MajorVertexKey; 2],
 	direction_flag: Direction,
 	twin: Option<MajorEdgeKey>,
@@ -174,7 +226,7 @@ struct MajorGraph {
 #[derive(Debug, Clone, PartialEq)]
 struct MinorGraphEdge {
 	segments: SmallVec<[PathSegment; 4]>,
-	parent: u8,
+	windings: EdgeWindings,
 	incident_vertices: [MinorVertexKey; 2],
 	direction_flag: Direction,
 	twin: Option<MinorEdgeKey>,
@@ -227,7 +279,7 @@ struct MinorGraphVertex {
 #[derive(Debug, Clone)]
 struct MinorGraphCycle {
 	segments: Vec<PathSegment>,
-	parent: u8,
+	windings: EdgeWindings,
 	direction_flag: Direction,
 }
 
@@ -244,7 +296,7 @@ struct MinorGraph {
 #[derive(Debug, Clone, PartialEq)]
 struct DualGraphHalfEdge {
 	segments: Vec<PathSegment>,
-	parent: u8,
+	windings: EdgeWindings,
 	incident_vertex: DualVertexKey,
 	direction_flag: Direction,
 	twin: Option<DualEdgeKey>,
@@ -315,10 +367,10 @@ fn major_graph_to_dot(graph: &MajorGraph) -> String {
 	}
 	for (_, edge) in &graph.edges {
 		dot.push_str(&format!(
-			"  {:?} -> {:?}: {:0b}\n",
+			"  {:?} -> {:?}: {:?}\n",
 			(edge.incident_vertices[0].0.as_ffi() & 0xFF),
 			(edge.incident_vertices[1].0.as_ffi() & 0xFF),
-			edge.parent
+			edge.windings
 		));
 	}
 	dot.push_str("}\n");
@@ -330,10 +382,10 @@ fn minor_graph_to_dot(edges: &SlotMap<MinorEdgeKey, MinorGraphEdge>) -> String {
 	let mut dot = String::from("digraph {\n");
 	for edge in edges.values() {
 		dot.push_str(&format!(
-			"  {:?} -> {:?}: {:0b}\n",
+			"  {:?} -> {:?}: {:?}\n",
 			(edge.incident_vertices[0].0.as_ffi() & 0xFF),
 			(edge.incident_vertices[1].0.as_ffi() & 0xFF),
-			edge.parent
+			edge.windings
 		));
 	}
 	dot.push_str("}\n");
@@ -357,7 +409,7 @@ fn dual_graph_to_dot(components: &[DualGraphComponent], edges: &SlotMap<DualEdge
 	dot
 }
 
-fn segment_to_edge(parent: u8) -> impl Fn(&PathSegment) -> Option<MajorGraphEdgeStage1> {
+fn segment_to_edge(parent: PathIndex) -> impl Fn(&PathSegment) -> Option<MajorGraphEdgeStage1> {
 	move |seg| {
 		if bounding_box_max_extent(&seg.bounding_box()) < EPS.point / 2. {
 			return None;
@@ -465,7 +517,7 @@ fn split_at_intersections(edges: &[MajorGraphEdgeStage1]) -> Vec<MajorGraphEdgeS
 		grid.query(&edge.2, &mut candidates);
 
 		for j in candidates.iter_set_bits() {
-			let candidate: &(PathSegment, u8) = &edges[j];
+			let candidate: &MajorGraphEdgeStage1 = &edges[j];
 			let include_endpoints = edge.1 != candidate.1 || !(candidate.0.end().abs_diff_eq(edge.0.start(), EPS.point) || candidate.0.start().abs_diff_eq(edge.0.end(), EPS.point));
 			let intersection = path_segment_intersection(&edge.0, &candidate.0, include_endpoints, &EPS);
 			for [t0, t1] in intersection {
@@ -551,7 +603,7 @@ fn round_point(point: DVec2) -> I64Vec2 {
 	(point * ROUNDING_FACTOR).round().as_i64vec2()
 }
 
-type Edges = SmallVec<[(PathSegment, u8, MajorEdgeKey, MajorEdgeKey); 2]>;
+type Edges = SmallVec<[(PathSegment, MajorEdgeKey, MajorEdgeKey); 2]>;
 
 fn find_vertices(edges: &[MajorGraphEdgeStage1]) -> MajorGraph {
 	let mut graph = MajorGraph {
@@ -605,21 +657,28 @@ fn find_vertices(edges: &[MajorGraphEdgeStage1]) -> MajorGraph {
 
 		let vertex_pair_id = (start_vertex.min(end_vertex), start_vertex.max(end_vertex));
 		if let Some(existing_edges) = vertex_pair_id_to_edges.get(&vertex_pair_id) {
-			if let Some(existing_edge) = existing_edges
-				.iter()
-				.find(|(other_seg, ..)| segments_equal(seg, other_seg, EPS.point) || segments_equal(&seg.reverse(), other_seg, EPS.point))
-			{
-				if existing_edge.1 != *parent {
-					graph.edges[existing_edge.2].parent = 0b11;
-					graph.edges[existing_edge.3].parent = 0b11;
+			// An edge which coincides with an existing one, possibly from another path, adds its winding to the existing edge instead
+			let coincident_edge = existing_edges.iter().find_map(|(other_seg, fwd_edge_key, bwd_edge_key)| {
+				if segments_equal(seg, other_seg, EPS.point) {
+					Some((1, *fwd_edge_key, *bwd_edge_key))
+				} else if segments_equal(&seg.reverse(), other_seg, EPS.point) {
+					Some((-1, *fwd_edge_key, *bwd_edge_key))
+				} else {
+					None
 				}
+			});
+			if let Some((delta, fwd_edge_key, bwd_edge_key)) = coincident_edge {
+				add_winding(&mut graph.edges[fwd_edge_key].windings, *parent, delta);
+				add_winding(&mut graph.edges[bwd_edge_key].windings, *parent, delta);
 				continue;
 			}
 		}
 
+		let windings: EdgeWindings = smallvec::smallvec![(*parent, 1)];
+
 		let fwd_edge_key = graph.edges.insert(MajorGraphEdge {
 			seg: *seg,
-			parent: *parent,
+			windings: windings.clone(),
 			incident_vertices: [start_vertex, end_vertex],
 			direction_flag: Direction::Forward,
 			twin: None,
@@ -627,7 +686,7 @@ fn find_vertices(edges: &[MajorGraphEdgeStage1]) -> MajorGraph {
 
 		let bwd_edge_key = graph.edges.insert(MajorGraphEdge {
 			seg: *seg,
-			parent: *parent,
+			windings,
 			incident_vertices: [end_vertex, start_vertex],
 			direction_flag: Direction::Backwards,
 			twin: Some(fwd_edge_key),
@@ -638,7 +697,7 @@ fn find_vertices(edges: &[MajorGraphEdgeStage1]) -> MajorGraph {
 		graph.vertices[start_vertex].outgoing_edges.push(fwd_edge_key);
 		graph.vertices[end_vertex].outgoing_edges.push(bwd_edge_key);
 
-		vertex_pair_id_to_edges.entry(vertex_pair_id).or_default().push((*seg, *parent, fwd_edge_key, bwd_edge_key));
+		vertex_pair_id_to_edges.entry(vertex_pair_id).or_default().push((*seg, fwd_edge_key, bwd_edge_key));
 	}
 
 	graph
@@ -648,14 +707,24 @@ fn get_order(vertex: &MajorGraphVertex) -> usize {
 	vertex.outgoing_edges.len()
 }
 
+/// Whether a vertex only joins two edges where the same paths continue in the same direction, so the edges can be contracted into one.
+fn is_contractible(vertex: &MajorGraphVertex, edges: &SlotMap<MajorEdgeKey, MajorGraphEdge>) -> bool {
+	if get_order(vertex) != 2 {
+		return false;
+	}
+	let (a, b) = (&edges[vertex.outgoing_edges[0]], &edges[vertex.outgoing_edges[1]]);
+	// The twin of `a` arrives at the vertex, so continuing onto `b` keeps going the same way when their directions are opposite
+	a.windings == b.windings && a.direction_flag != b.direction_flag
+}
+
 /// Computes the minor graph from the major graph.
 ///
 /// This function simplifies the graph structure by performing the following steps:
 /// 1. Iterates through vertices of the major graph.
-/// 2. For vertices with exactly two edges (degree 2):
-///    a. Combines the two edges into a single edge if they have the same parent.
+/// 2. For vertices with exactly two edges (degree 2) which belong to the same paths with the same windings and direction:
+///    a. Combines the two edges into a single edge.
 ///    b. Updates the endpoints of the new edge to skip the current vertex.
-/// 3. For vertices with degree != 2:
+/// 3. For all other vertices:
 ///    a. Creates a new vertex in the minor graph.
 ///    b. Creates new edges in the minor graph for each outgoing edge.
 /// 4. Handles any cyclic components (closed loops with no high-degree vertices).
@@ -683,7 +752,7 @@ fn compute_minor(major_graph: &MajorGraph) -> MinorGraph {
 	// Handle components that are not cycles
 	for (major_vertex_key, vertex) in &major_graph.vertices {
 		// Edges are contracted
-		if get_order(vertex) == 2 {
+		if is_contractible(vertex, &major_graph.edges) {
 			continue;
 		}
 		let start_vertex = *to_minor_vertex
@@ -695,10 +764,7 @@ fn compute_minor(major_graph: &MajorGraph) -> MinorGraph {
 			let mut edge_key = start_edge_key;
 			let mut edge = &major_graph.edges[edge_key];
 
-			while edge.parent == major_graph.edges[start_edge_key].parent
-				&& edge.direction_flag == major_graph.edges[start_edge_key].direction_flag
-				&& get_order(&major_graph.vertices[edge.incident_vertices[1]]) == 2
-			{
+			while is_contractible(&major_graph.vertices[edge.incident_vertices[1]], &major_graph.edges) {
 				segments.push(edge.seg);
 				visited.insert(edge.incident_vertices[1]);
 				let next_vertex = &major_graph.vertices[edge.incident_vertices[1]];
@@ -721,7 +787,7 @@ fn compute_minor(major_graph: &MajorGraph) -> MinorGraph {
 
 			let new_edge_key = new_edges.insert(MinorGraphEdge {
 				segments,
-				parent: major_graph.edges[start_edge_key].parent,
+				windings: major_graph.edges[start_edge_key].windings.clone(),
 				incident_vertices: [start_vertex, end_vertex],
 				direction_flag: major_graph.edges[start_edge_key].direction_flag,
 				twin: twin_key.copied(),
@@ -737,14 +803,14 @@ fn compute_minor(major_graph: &MajorGraph) -> MinorGraph {
 	// Handle cyclic components (if any)
 	let mut cycles = Vec::new();
 	for (major_vertex_key, vertex) in &major_graph.vertices {
-		if vertex.outgoing_edges.len() != 2 || visited.contains(&major_vertex_key) {
+		if !is_contractible(vertex, &major_graph.edges) || visited.contains(&major_vertex_key) {
 			continue;
 		}
 		let mut edge_key = vertex.outgoing_edges[0];
 		let mut edge = &major_graph.edges[edge_key];
 		let mut cycle = MinorGraphCycle {
 			segments: Vec::with_capacity(4),
-			parent: edge.parent,
+			windings: edge.windings.clone(),
 			direction_flag: edge.direction_flag,
 		};
 		loop {
@@ -768,11 +834,11 @@ fn compute_minor(major_graph: &MajorGraph) -> MinorGraph {
 	}
 }
 
-fn remove_dangling_edges(graph: &mut MinorGraph) {
+fn remove_dangling_edges(graph: &mut MinorGraph, path_count: usize) {
 	// Basically DFS for each parent with BFS number
-	fn walk(parent: u8, graph: &MinorGraph) -> HashSet<MinorVertexKey> {
+	fn walk(parent: PathIndex, parent_edges: &[MinorEdgeKey], graph: &MinorGraph) -> HashSet<MinorVertexKey> {
 		// merge
-		let vertex_count = graph.vertices.len();
+		let vertex_count = parent_edges.len();
 		let mut kept_vertices = HashSet::with_capacity_and_hasher(vertex_count, Default::default());
 		let mut vertex_to_level = new_hash_map(vertex_count);
 
@@ -783,7 +849,7 @@ fn remove_dangling_edges(graph: &mut MinorGraph) {
 			graph: &MinorGraph,
 			vertex_to_level: &mut HashMap<MinorVertexKey, usize>,
 			kept_vertices: &mut HashSet<MinorVertexKey>,
-			parent: u8,
+			parent: PathIndex,
 		) -> usize {
 			if let Some(&existing_level) = vertex_to_level.get(&vertex) {
 				return existing_level;
@@ -793,7 +859,7 @@ fn remove_dangling_edges(graph: &mut MinorGraph) {
 			let mut min_level = usize::MAX;
 			for &edge_key in &graph.vertices[vertex].outgoing_edges {
 				let edge = &graph.edges[edge_key];
-				if edge.parent & parent != 0 && Some(edge_key) != incoming_edge {
+				if contains_path(&edge.windings, parent) && Some(edge_key) != incoming_edge {
 					min_level = min_level.min(visit(edge.incident_vertices[1], edge.twin, level + 1, graph, vertex_to_level, kept_vertices, parent));
 				}
 			}
@@ -805,32 +871,36 @@ fn remove_dangling_edges(graph: &mut MinorGraph) {
 			min_level
 		}
 
-		for edge in graph.edges.values() {
-			if edge.parent & parent != 0 {
-				visit(edge.incident_vertices[0], None, 0, graph, &mut vertex_to_level, &mut kept_vertices, parent);
-			}
+		for &edge_key in parent_edges {
+			visit(graph.edges[edge_key].incident_vertices[0], None, 0, graph, &mut vertex_to_level, &mut kept_vertices, parent);
 		}
 
 		kept_vertices
 	}
 
-	let kept_vertices_a = walk(1, graph);
-	let kept_vertices_b = walk(2, graph);
+	// Group the edges by the paths they belong to, so each walk only visits the edges of its own path
+	let mut edges_by_parent = vec![Vec::new(); path_count];
+	for (edge_key, edge) in &graph.edges {
+		for &(parent, _) in &edge.windings {
+			edges_by_parent[parent as usize].push(edge_key);
+		}
+	}
+
+	let kept_vertices: Vec<_> = edges_by_parent.iter().enumerate().map(|(parent, edges)| walk(parent as PathIndex, edges, graph)).collect();
+	let is_kept_edge = |edge: &MinorGraphEdge| {
+		edge.windings.iter().any(|&(parent, _)| {
+			let kept_vertices = &kept_vertices[parent as usize];
+			kept_vertices.contains(&edge.incident_vertices[0]) && kept_vertices.contains(&edge.incident_vertices[1])
+		})
+	};
 
-	graph.vertices.retain(|k, _| kept_vertices_a.contains(&k) || kept_vertices_b.contains(&k));
+	graph.vertices.retain(|k, _| kept_vertices.iter().any(|kept_vertices| kept_vertices.contains(&k)));
 
 	for vertex in graph.vertices.values_mut() {
-		vertex.outgoing_edges.retain(|edge_key| {
-			let edge = &graph.edges[*edge_key];
-			(edge.parent & 1 == 1 && kept_vertices_a.contains(&edge.incident_vertices[0]) && kept_vertices_a.contains(&edge.incident_vertices[1]))
-				|| (edge.parent & 2 == 2 && kept_vertices_b.contains(&edge.incident_vertices[0]) && kept_vertices_b.contains(&edge.incident_vertices[1]))
-		});
+		vertex.outgoing_edges.retain(|edge_key| is_kept_edge(&graph.edges[*edge_key]));
 	}
 	// TODO(@TrueDoctor): merge
-	graph.edges.retain(|_, edge| {
-		(edge.parent & 1 == 1 && kept_vertices_a.contains(&edge.incident_vertices[0]) && kept_vertices_a.contains(&edge.incident_vertices[1]))
-			|| (edge.parent & 2 == 2 && kept_vertices_b.contains(&edge.incident_vertices[0]) && kept_vertices_b.contains(&edge.incident_vertices[1]))
-	});
+	graph.edges.retain(|_, edge| is_kept_edge(edge));
 }
 
 fn sort_outgoing_edges_by_angle(graph: &mut MinorGraph) {
@@ -1013,7 +1083,7 @@ fn compute_dual(minor_graph: &MinorGraph) -> Result<DualGraph, BooleanError> {
 
 			let new_edge_key = dual_edges.insert(DualGraphHalfEdge {
 				segments: edge.segments.to_vec(),
-				parent: edge.parent,
+				windings: edge.windings.clone(),
 				incident_vertex: face_key,
 				direction_flag: edge.direction_flag,
 				twin: twin_dual_key,
@@ -1046,7 +1116,7 @@ fn compute_dual(minor_graph: &MinorGraph) -> Result<DualGraph, BooleanError> {
 
 		let inner_half_edge_key = dual_edges.insert(DualGraphHalfEdge {
 			segments: cycle.segments.clone(),
-			parent: cycle.parent,
+			windings: cycle.windings.clone(),
 			incident_vertex: inner_face_key,
 			direction_flag: cycle.direction_flag,
 			twin: None,
@@ -1054,7 +1124,7 @@ fn compute_dual(minor_graph: &MinorGraph) -> Result<DualGraph, BooleanError> {
 
 		let outer_half_edge_key = dual_edges.insert(DualGraphHalfEdge {
 			segments: cycle.segments.iter().cloned().rev().collect(),
-			parent: cycle.parent,
+			windings: cycle.windings.clone(),
 			incident_vertex: outer_face_key,
 			direction_flag: !cycle.direction_flag,
 			twin: Some(inner_half_edge_key),
@@ -1160,7 +1230,8 @@ fn compute_dual(minor_graph: &MinorGraph) -> Result<DualGraph, BooleanError> {
 
 		let areas: Vec<_> = component_vertices
 			.iter()
-			.map(|face_key| (face_key, (compute_signed_area(&dual_vertices[*face_key], &dual_edges) * 1000.) as i64))
+			// Rounded rather than truncated, so the two faces of a cycle have the same magnitude and the tie is broken by the sign
+			.map(|face_key| (face_key, (compute_signed_area(&dual_vertices[*face_key], &dual_edges) * 1000.).round() as i64))
 			.collect();
 		#[cfg(feature = "logging")]
 		eprintln!("Found multiple outer faces: {areas:?}, falling back to area calculation");
@@ -1223,7 +1294,7 @@ fn test_inclusion(a: &DualGraphComponent, b: &DualGraphComponent, edges: &SlotMa
 		for &edge_key in &face.incident_edges {
 			let edge = &edges[edge_key];
 			for seg in &edge.segments {
-				count += path_segment_horizontal_ray_intersection_count(seg, tested_point);
+				count += horizontal_ray_crossings(seg, tested_point);
 			}
 		}
 		if count % 2 == 1 {
@@ -1242,6 +1313,14 @@ struct IntersectionSegment {
 	seg: PathSegment,
 }
 
+/// The number of times a horizontal ray from a point crosses a segment, where lines are tested exactly so a ray through the point where two lines meet crosses only one of them.
+fn horizontal_ray_crossings(segment: &PathSegment, point: DVec2) -> usize {
+	match *segment {
+		PathSegment::Line(start, end) => line_segment_intersects_horizontal_ray(start, end, point) as usize,
+		_ => path_segment_horizontal_ray_intersection_count(segment, point),
+	}
+}
+
 pub fn path_segment_horizontal_ray_intersection_count(orig_seg: &PathSegment, point: DVec2) -> usize {
 	let total_bounding_box = orig_seg.approx_bounding_box();
 	if !bounding_box_intersects_horizontal_ray(&total_bounding_box, point) {
@@ -1408,76 +1487,69 @@ fn insert_component(trees: &mut Vec<NestingTree>, component: DualGraphComponent,
 	trees.push(new_tree);
 }
 
-fn get_flag(count: i32, fill_rule: FillRule) -> u8 {
+fn is_inside(count: i32, fill_rule: FillRule) -> bool {
 	match fill_rule {
-		FillRule::NonZero => {
-			if count == 0 {
-				0
-			} else {
-				1
-			}
-		}
-		FillRule::EvenOdd => (count % 2).unsigned_abs() as u8,
+		FillRule::NonZero => count != 0,
+		FillRule::EvenOdd => count % 2 != 0,
 	}
 }
 
-/// Determines which faces should be included in the result based on the boolean operation.
+/// Determines which input paths contain each face, from the winding numbers of the paths around it.
 ///
-/// This function applies the specified boolean operation and fill rules to decide
-/// which regions of the dual graph should be part of the resulting path.
+/// This function labels each region of the dual graph with the winding number of every path,
+/// and applies each path's fill rule to decide the set of paths the region lies inside of.
 fn flag_faces(
 	nesting_trees: &[NestingTree],
-	a_fill_rule: FillRule,
-	b_fill_rule: FillRule,
+	fill_rules: &[FillRule],
 	edges: &SlotMap<DualEdgeKey, DualGraphHalfEdge>,
 	vertices: &SlotMap<DualVertexKey, DualGraphVertex>,
-	flags: &mut HashMap<DualVertexKey, u8>,
+	flags: &mut HashMap<DualVertexKey, Coverage>,
 ) {
 	let mut visited_faces = HashSet::default();
 	let mut face_stack = VecDeque::new();
 	for tree in nesting_trees.iter() {
-		let mut tree_stack = vec![(tree, 0, 0)];
+		let mut tree_stack = vec![(tree, vec![0; fill_rules.len()])];
 
-		while let Some((current_tree, a_running_count, b_running_count)) = tree_stack.pop() {
+		while let Some((current_tree, running_counts)) = tree_stack.pop() {
 			// TODO: Test if clearing is faster
 			visited_faces.clear();
 			face_stack.clear();
 
 			let outer_face_key = current_tree.component.outer_face.expect("Component doesn't have an outer face.");
-			face_stack.push_back((outer_face_key, a_running_count, b_running_count));
+			face_stack.push_back((outer_face_key, running_counts));
 
-			while let Some((face_key, a_count, b_count)) = face_stack.pop_front() {
+			while let Some((face_key, counts)) = face_stack.pop_front() {
 				if visited_faces.contains(&face_key) {
 					continue;
 				}
 				visited_faces.insert(face_key);
 
-				let a_flag = get_flag(a_count, a_fill_rule);
-				let b_flag = get_flag(b_count, b_fill_rule);
-				*flags.entry(face_key).or_default() = a_flag | (b_flag << 1);
+				let mut coverage = Coverage::new(fill_rules.len());
+				for (path, (&count, &fill_rule)) in counts.iter().zip(fill_rules).enumerate() {
+					if is_inside(count, fill_rule) {
+						coverage.insert(path);
+					}
+				}
+				flags.insert(face_key, coverage);
 
 				for edge_key in &vertices[face_key].incident_edges {
 					let edge = &edges[*edge_key];
 					let twin_key = edge.twin.expect("Edge doesn't have a twin");
 					#[cfg(feature = "logging")]
 					eprintln!("Processing edge: {:?} to: {:?}", edge_key.0, edges[twin_key].incident_vertex.0);
-					let mut next_a_count = a_count;
-					if edge.parent & 1 != 0 {
-						next_a_count += if edge.direction_flag.forward() { 1 } else { -1 };
-					}
-					let mut next_b_count = b_count;
-					if edge.parent & 2 != 0 {
-						next_b_count += if edge.direction_flag.forward() { 1 } else { -1 };
+					let mut next_counts = counts.clone();
+					for &(parent, winding) in &edge.windings {
+						next_counts[parent as usize] += if edge.direction_flag.forward() { winding } else { -winding };
 					}
 					#[cfg(feature = "logging")]
-					eprintln!("next_count a: {}, b:{}", next_a_count, next_b_count);
-					face_stack.push_back((edges[twin_key].incident_vertex, next_a_count, next_b_count));
+					eprintln!("next_counts: {next_counts:?}");
+					face_stack.push_back((edges[twin_key].incident_vertex, next_counts));
 				}
 
 				// Collect subtrees to be processed later
 				if let Some(subtrees) = current_tree.outgoing_edges.get(&face_key) {
 					for subtree in subtrees {
-						tree_stack.push((subtree, a_count, b_count));
+						tree_stack.push((subtree, counts.clone()));
 					}
 				}
 			}
@@ -1485,8 +1557,8 @@ fn flag_faces(
 	}
 }
 
-fn get_selected_faces<'a>(predicate: &'a impl Fn(u8) -> bool, flags: &'a HashMap<DualVertexKey, u8>) -> impl Iterator<Item = DualVertexKey> + 'a {
-	flags.iter().filter_map(|(key, &flag)| predicate(flag).then_some(*key))
+fn get_selected_faces<'a>(predicate: &'a impl Fn(&Coverage) -> bool, flags: &'a HashMap<DualVertexKey, Coverage>) -> impl Iterator<Item = DualVertexKey> + 'a {
+	flags.iter().filter_map(|(key, flag)| predicate(flag).then_some(*key))
 }
 
 fn walk_faces<'a>(faces: &'a [DualVertexKey], edges: &SlotMap<DualEdgeKey, DualGraphHalfEdge>, vertices: &SlotMap<DualVertexKey, DualGraphVertex>) -> impl Iterator<Item = PathSegment> + use<'a> {
@@ -1543,25 +1615,24 @@ fn walk_faces<'a>(faces: &'a [DualVertexKey], edges: &SlotMap<DualEdgeKey, DualG
 /// final output of the boolean operation.
 fn dump_faces(
 	nesting_trees: &[NestingTree],
-	predicate: impl Fn(u8) -> bool + Copy,
+	predicate: impl Fn(&Coverage) -> bool + Copy,
 	edges: &SlotMap<DualEdgeKey, DualGraphHalfEdge>,
 	vertices: &SlotMap<DualVertexKey, DualGraphVertex>,
-	flags: &HashMap<DualVertexKey, u8>,
-) -> Vec<Path> {
+	flags: &HashMap<DualVertexKey, Coverage>,
+) -> Vec<(DualVertexKey, Path)> {
 	let mut paths = Vec::new();
 
 	fn visit(
 		tree: &NestingTree,
-		predicate: impl Fn(u8) -> bool + Copy,
-		paths: &mut Vec<Path>,
+		predicate: impl Fn(&Coverage) -> bool + Copy,
+		paths: &mut Vec<(DualVertexKey, Path)>,
 		edges: &SlotMap<DualEdgeKey, DualGraphHalfEdge>,
 		vertices: &SlotMap<DualVertexKey, DualGraphVertex>,
-		flags: &HashMap<DualVertexKey, u8>,
+		flags: &HashMap<DualVertexKey, Coverage>,
 	) {
 		for &face_key in tree.component.vertices.iter() {
 			let face = &vertices[face_key];
-			let flag = flags[&face_key];
-			if !predicate(flag) || Some(face_key) == tree.component.outer_face {
+			if !predicate(&flags[&face_key]) || Some(face_key) == tree.component.outer_face {
 				continue;
 			}
 
@@ -1591,7 +1662,7 @@ fn dump_faces(
 				}
 			}
 
-			paths.push(path);
+			paths.push((face_key, path));
 		}
 
 		for subtrees in tree.outgoing_edges.values() {
@@ -1608,14 +1679,17 @@ fn dump_faces(
 	paths
 }
 
-const OPERATION_PREDICATES: [fn(u8) -> bool; 6] = [
-	|flag: u8| flag > 0,               // Union
-	|flag: u8| flag == 1,              // Difference
-	|flag: u8| flag == 0b11,           // Intersection
-	|flag: u8| flag == 1 || flag == 2, // Exclusion
-	|flag: u8| (flag & 1) == 1,        // Division
-	|flag: u8| flag > 0,               // Fracture
-];
+/// Whether a face inside the given set of paths, out of `path_count` paths, is part of the result of the operation.
+fn is_selected(op: PathBooleanOperation, coverage: &Coverage, path_count: usize) -> bool {
+	match op {
+		PathBooleanOperation::Union | PathBooleanOperation::Fracture => coverage.count() > 0,
+		PathBooleanOperation::Difference => coverage.contains(0) && coverage.count() == 1,
+		PathBooleanOperation::Intersection => coverage.count() == path_count,
+		PathBooleanOperation::Exclusion => coverage.count() == 1,
+		PathBooleanOperation::Xor => coverage.count() % 2 == 1,
+		PathBooleanOperation::Division => coverage.contains(0),
+	}
+}
 
 /// Represents errors that can occur during boolean operations on paths.
 #[derive(Debug)]
@@ -1625,6 +1699,8 @@ pub enum BooleanError {
 	/// Indicates that no valid ear was found in a polygon during triangulation. <https://en.wikipedia.org/wiki/Vertex_(geometry)#Ears>
 	NoEarInPolygon,
 	InvalidPathCommand(char),
+	/// Indicates that path data doesn't follow the SVG path data grammar, at the given byte index.
+	InvalidPathData(usize),
 }
 
 impl Display for BooleanError {
@@ -1633,6 +1709,7 @@ impl Display for BooleanError {
 			Self::MultipleOuterFaces => f.write_str("Found multiple candidates for the outer face in a connected component of the dual graph."),
 			Self::NoEarInPolygon => f.write_str("Failed to compute winding order for one of the faces, this usually happens when the polygon is malformed."),
 			Self::InvalidPathCommand(cmd) => f.write_fmt(format_args!("Encountered a '{cmd}' while parsing the svg data which was not recognized")),
+			Self::InvalidPathData(index) => f.write_fmt(format_args!("Encountered invalid svg path data at index {index}")),
 		}
 	}
 }
@@ -1659,7 +1736,7 @@ impl Display for BooleanError {
 /// ).unwrap();
 ///
 /// let result_data = path_to_path_data(&result[0], 0.001);
-/// assert_eq!(result_data, "M 36.666666666667,30.000000000000 L 23.333333333333,30.000000000000 L 30.000000000000,40.000000000000 L 36.666666666667,30.000000000000 Z");
+/// assert_eq!(result_data, "M36.666666666667 30H23.333333333333L30 40Z");
 /// ```
 ///
 /// # Operations
@@ -1671,6 +1748,7 @@ impl Display for BooleanError {
 /// - Exclusion
 /// - Division
 /// - Fracture
+/// - Xor
 ///
 /// See [`PathBooleanOperation`] for more details on each operation.
 ///
@@ -1694,10 +1772,292 @@ impl Display for BooleanError {
 /// - Issues arise in determining the nesting structure of the paths.
 #[inline(never)]
 pub fn path_boolean(a: &Path, a_fill_rule: FillRule, b: &Path, b_fill_rule: FillRule, op: PathBooleanOperation) -> Result<Vec<Path>, BooleanError> {
-	let mut unsplit_edges: Vec<MajorGraphEdgeStage1> = a.iter().map(segment_to_edge(1)).chain(b.iter().map(segment_to_edge(2))).flatten().collect();
+	path_boolean_many(&[(a, a_fill_rule), (b, b_fill_rule)], op)
+}
 
-	if unsplit_edges.is_empty() {
+/// Performs a boolean operation on any number of paths at once.
+///
+/// All paths are combined into a single arrangement, and each of its regions is labeled with the winding number of every path,
+/// which each path's own fill rule turns into whether the region is inside that path. This is faster and more accurate than
+/// folding [`path_boolean`] over the paths pairwise, since intersections are only found once and no intermediate result is rounded.
+///
+/// The operations generalize to many paths as:
+/// - Union: the areas inside any of the paths.
+/// - Difference: the areas inside the first path but none of the others.
+/// - Intersection: the areas inside all of the paths.
+/// - Exclusion: the areas inside exactly one of the paths.
+/// - Xor: the areas inside an odd number of the paths.
+/// - Division: the first path, split into pieces by all the others.
+/// - Fracture: every piece of every path, split by all the others.
+///
+/// With two paths, this gives the same result as [`path_boolean`].
+///
+/// # Examples
+///
+/// ```
+/// use path_bool::{path_boolean_many, FillRule, PathBooleanOperation, path_from_path_data};
+///
+/// let squares = ["M 0 0 L 20 0 L 20 20 L 0 20 Z", "M 10 0 L 30 0 L 30 20 L 10 20 Z", "M 20 0 L 40 0 L 40 20 L 20 20 Z"].map(|data| path_from_path_data(data).unwrap());
+/// let paths = squares.iter().map(|square| (square, FillRule::NonZero)).collect::<Vec<_>>();
+///
+/// let union = path_boolean_many(&paths, PathBooleanOperation::Union).unwrap();
+/// assert_eq!(union.len(), 1);
+/// ```
+///
+/// # Errors
+///
+/// Returns a [`BooleanError`] under the same conditions as [`path_boolean`].
+#[inline(never)]
+pub fn path_boolean_many(paths: &[(&Path, FillRule)], op: PathBooleanOperation) -> Result<Vec<Path>, BooleanError> {
+	let Some(Arrangement { dual_graph, nesting_trees, flags }) = compute_arrangement(paths)? else {
+		return Ok(Vec::new());
+	};
+	let DualGraph { edges, vertices, .. } = &dual_graph;
+
+	let predicate = |coverage: &Coverage| is_selected(op, coverage, paths.len());
+
+	match op {
+		PathBooleanOperation::Division | PathBooleanOperation::Fracture => Ok(dump_faces(&nesting_trees, predicate, edges, vertices, &flags).into_iter().map(|(_, path)| path).collect()),
+		_ => {
+			let mut selected_faces: Vec<DualVertexKey> = get_selected_faces(&predicate, &flags).collect();
+			selected_faces.sort_unstable();
+			Ok(vec![walk_faces(&selected_faces, edges, vertices).collect()])
+		}
+	}
+}
+
+/// A region of the arrangement of several paths, as found by [`path_fracture`].
+#[derive(Debug, Clone)]
+pub struct FracturedRegion {
+	/// The outline of the region, followed by the outlines of any holes in it.
+	pub path: Path,
+	/// The indices of the input paths whose interior contains the region, in increasing order.
+	pub covering_paths: Vec<usize>,
+}
+
+/// Breaks any number of paths into all the regions formed by their overlaps, like [`PathBooleanOperation::Fracture`],
+/// and labels each region with the paths that cover it.
+///
+/// # Examples
+///
+/// ```
+/// use path_bool::{path_fracture, FillRule, path_from_path_data};
+///
+/// let a = path_from_path_data("M 0 0 L 20 0 L 20 20 L 0 20 Z").unwrap();
+/// let b = path_from_path_data("M 10 0 L 30 0 L 30 20 L 10 20 Z").unwrap();
+///
+/// let mut regions = path_fracture(&[(&a, FillRule::NonZero), (&b, FillRule::NonZero)]).unwrap();
+/// regions.sort_by_key(|region| region.covering_paths.clone());
+///
+/// let covering_paths = regions.iter().map(|region| region.covering_paths.as_slice()).collect::<Vec<_>>();
+/// assert_eq!(covering_paths, [&[0][..], &[0, 1], &[1]]);
+/// ```
+///
+/// # Errors
+///
+/// Returns a [`BooleanError`] under the same conditions as [`path_boolean`].
+pub fn path_fracture(paths: &[(&Path, FillRule)]) -> Result<Vec<FracturedRegion>, BooleanError> {
+	let Some(Arrangement { dual_graph, nesting_trees, flags }) = compute_arrangement(paths)? else {
+		return Ok(Vec::new());
+	};
+	let DualGraph { edges, vertices, .. } = &dual_graph;
+
+	let predicate = |coverage: &Coverage| coverage.count() > 0;
+
+	let regions = dump_faces(&nesting_trees, predicate, edges, vertices, &flags)
+		.into_iter()
+		.map(|(face_key, path)| FracturedRegion {
+			path,
+			covering_paths: flags[&face_key].iter().collect(),
+		})
+		.collect();
+	Ok(regions)
+}
+
+/// Finds the enclosed regions formed by any number of open or closed paths, treating them as lines drawn on a plane rather than as filled shapes.
+/// This allows filling the areas between strokes which don't form closed shapes on their own.
+///
+/// So that strokes which fall slightly short of meeting still close off a region, each open end is joined to the other open ends within `gap_tolerance` of it,
+/// or otherwise extended in its direction by `gap_tolerance`.
+///
+/// # Examples
+///
+/// ```
+/// use path_bool::{path_regions, path_from_path_data};
+///
+/// // A cross of two lines inside a square gives four regions
+/// let square = path_from_path_data("M 0 0 L 20 0 L 20 20 L 0 20 Z").unwrap();
+/// let horizontal = path_from_path_data("M 0 10 L 20 10").unwrap();
+/// let vertical = path_from_path_data("M 10 0 L 10 20").unwrap();
+///
+/// let regions = path_regions(&[&square, &horizontal, &vertical], 0.).unwrap();
+/// assert_eq!(regions.len(), 4);
+/// ```
+///
+/// # Errors
+///
+/// Returns a [`BooleanError`] under the same conditions as [`path_boolean`].
+pub fn path_regions(paths: &[&Path], gap_tolerance: f64) -> Result<Vec<Path>, BooleanError> {
+	let mut lines: Path = paths.iter().flat_map(|path| path.iter().copied()).collect();
+	if gap_tolerance > 0. {
+		lines.extend(gap_closing_segments(paths, gap_tolerance));
+	}
+
+	// All the lines are treated as one path, so the regions they form together aren't discarded as dangling parts of each individual path
+	let Some(Arrangement { dual_graph, nesting_trees, flags }) = compute_arrangement(&[(&lines, FillRule::NonZero)])? else {
+		return Ok(Vec::new());
+	};
+	let DualGraph { edges, vertices, .. } = &dual_graph;
+
+	Ok(dump_faces(&nesting_trees, |_| true, edges, vertices, &flags).into_iter().map(|(_, path)| path).collect())
+}
+
+/// Splits a shape into pieces along any number of cutting paths, like a knife. The cutting paths are treated as lines, so they may be open.
+///
+/// A cutting path which ends inside the shape without crossing it completely leaves the shape uncut there.
+///
+/// # Examples
+///
+/// ```
+/// use path_bool::{path_knife, FillRule, path_from_path_data};
+///
+/// let square = path_from_path_data("M 0 0 L 20 0 L 20 20 L 0 20 Z").unwrap();
+/// let cut = path_from_path_data("M -5 10 L 25 10").unwrap();
+///
+/// let pieces = path_knife(&square, FillRule::NonZero, &[&cut]).unwrap();
+/// assert_eq!(pieces.len(), 2);
+/// ```
+///
+/// # Errors
+///
+/// Returns a [`BooleanError`] under the same conditions as [`path_boolean`].
+pub fn path_knife(shape: &Path, fill_rule: FillRule, cutters: &[&Path]) -> Result<Vec<Path>, BooleanError> {
+	// The second path joins the cutting paths to the outline of the shape, so the parts of them which cross the shape close off regions instead of being discarded as dangling.
+	// The first path alone decides which of those regions are inside the shape.
+	let lines: Path = shape.iter().chain(cutters.iter().flat_map(|cutter| cutter.iter())).copied().collect();
+	let Some(Arrangement { dual_graph, nesting_trees, flags }) = compute_arrangement(&[(shape, fill_rule), (&lines, FillRule::NonZero)])? else {
 		return Ok(Vec::new());
+	};
+	let DualGraph { edges, vertices, .. } = &dual_graph;
+
+	Ok(dump_faces(&nesting_trees, |coverage| coverage.contains(0), edges, vertices, &flags)
+		.into_iter()
+		.map(|(_, path)| path)
+		.collect())
+}
+
+/// Clips a path, which is treated as a line and may be open, to the inside or the outside of a region. The result keeps the direction of the original path.
+///
+/// # Examples
+///
+/// ```
+/// use path_bool::{path_trim, FillRule, path_from_path_data};
+///
+/// let square = path_from_path_data("M 0 0 L 20 0 L 20 20 L 0 20 Z").unwrap();
+/// let line = path_from_path_data("M -10 10 L 30 10").unwrap();
+///
+/// let inside = path_trim(&line, &square, FillRule::NonZero, true).unwrap();
+/// assert_eq!(inside.len(), 1);
+/// let outside = path_trim(&line, &square, FillRule::NonZero, false).unwrap();
+/// assert_eq!(outside.len(), 2);
+/// ```
+///
+/// # Errors
+///
+/// Returns a [`BooleanError`] under the same conditions as [`path_boolean`].
+pub fn path_trim(path: &Path, region: &Path, fill_rule: FillRule, keep_inside: bool) -> Result<Path, BooleanError> {
+	// Resolving the fill rule first leaves an outline whose inside is where a ray crosses it an odd number of times
+	let outline: Path = path_boolean_many(&[(region, fill_rule)], PathBooleanOperation::Union)?.into_iter().flatten().collect();
+	let is_inside = |point: DVec2| outline.iter().map(|segment| horizontal_ray_crossings(segment, point)).sum::<usize>() % 2 == 1;
+
+	let mut result = Vec::new();
+	for segment in path {
+		let mut splits: Vec<f64> = outline.iter().flat_map(|other| path_segment_intersection(segment, other, true, &EPS)).map(|[t, _]| t).collect();
+		splits.sort_by(f64::total_cmp);
+
+		// Split the segment at each crossing, rescaling the parameters of the remaining crossings to the part which is left
+		let mut rest = *segment;
+		let mut rest_start = 0.;
+		for t in splits {
+			let local_t = (t - rest_start) / (1. - rest_start);
+			if !(EPS.param..=1. - EPS.param).contains(&local_t) {
+				continue;
+			}
+			let (piece, remainder) = rest.split_at(local_t);
+			if is_inside(piece.sample_at(0.5)) == keep_inside {
+				result.push(piece);
+			}
+			rest = remainder;
+			rest_start = t;
+		}
+		if is_inside(rest.sample_at(0.5)) == keep_inside {
+			result.push(rest);
+		}
+	}
+
+	Ok(result)
+}
+
+/// Line segments which close the gaps at the open ends of the paths, for [`path_regions`].
+fn gap_closing_segments(paths: &[&Path], gap_tolerance: f64) -> Vec<PathSegment> {
+	// Each open end, with the direction pointing away from the rest of its subpath
+	let mut open_ends = Vec::new();
+	for path in paths {
+		let mut start = 0;
+		for index in 0..path.len() {
+			let is_subpath_end = path.get(index + 1).is_none_or(|next| !next.start().abs_diff_eq(path[index].end(), EPS.point));
+			if !is_subpath_end {
+				continue;
+			}
+
+			let (first, last) = (&path[start], &path[index]);
+			if !first.start().abs_diff_eq(last.end(), EPS.point) {
+				open_ends.push((first.start(), first.start() - first.sample_at(1e-3)));
+				open_ends.push((last.end(), last.end() - last.sample_at(1. - 1e-3)));
+			}
+			start = index + 1;
+		}
+	}
+
+	let mut segments = Vec::new();
+	let mut is_joined = vec![false; open_ends.len()];
+	for (index, &(point, _)) in open_ends.iter().enumerate() {
+		for (other_index, &(other_point, _)) in open_ends.iter().enumerate().skip(index + 1) {
+			let distance = point.distance(other_point);
+			if distance > EPS.point && distance <= gap_tolerance {
+				segments.push(PathSegment::Line(point, other_point));
+				is_joined[index] = true;
+				is_joined[other_index] = true;
+			}
+		}
+	}
+
+	for (&(point, direction), is_joined) in open_ends.iter().zip(is_joined) {
+		if !is_joined && let Some(direction) = direction.try_normalize() {
+			segments.push(PathSegment::Line(point, point + direction * gap_tolerance));
+		}
+	}
+
+	segments
+}
+
+/// The faces formed by all the input paths, along with which paths cover each face.
+struct Arrangement {
+	dual_graph: DualGraph,
+	nesting_trees: Vec<NestingTree>,
+	flags: HashMap<DualVertexKey, Coverage>,
+}
+
+/// Splits the paths at all their intersections and finds the faces they form. Returns `None` if the paths have no segments.
+fn compute_arrangement(paths: &[(&Path, FillRule)]) -> Result<Option<Arrangement>, BooleanError> {
+	let mut unsplit_edges: Vec<MajorGraphEdgeStage1> = paths
+		.iter()
+		.enumerate()
+		.flat_map(|(index, (path, _))| path.iter().filter_map(segment_to_edge(index as PathIndex)))
+		.collect();
+
+	if unsplit_edges.is_empty() {
+		return Ok(None);
 	}
 	split_at_self_intersections(&mut unsplit_edges);
 
@@ -1722,7 +2082,7 @@ pub fn path_boolean(a: &Path, a_fill_rule: FillRule, b: &Path, b_fill_rule: Fill
 	#[cfg(feature = "logging")]
 	eprintln!("{}", minor_graph_to_dot(&minor_graph.edges));
 
-	remove_dangling_edges(&mut minor_graph);
+	remove_dangling_edges(&mut minor_graph, paths.len());
 	#[cfg(feature = "logging")]
 	eprintln!("After removing dangling edges:");
 	#[cfg(feature = "logging")]
@@ -1766,24 +2126,16 @@ pub fn path_boolean(a: &Path, a_fill_rule: FillRule, b: &Path, b_fill_rule: Fill
 	#[cfg(feature = "logging")]
 	eprintln!("{}", dual_graph_to_dot(&dual_graph.components, edges));
 
+	let fill_rules: Vec<FillRule> = paths.iter().map(|&(_, fill_rule)| fill_rule).collect();
 	let mut flags = new_hash_map(vertices.len());
-	flag_faces(&nesting_trees, a_fill_rule, b_fill_rule, edges, vertices, &mut flags);
+	flag_faces(&nesting_trees, &fill_rules, edges, vertices, &mut flags);
 
 	#[cfg(feature = "logging")]
 	for (face, flag) in &flags {
-		eprintln!("{:?}: {:b}", face.0, flag);
+		eprintln!("{:?}: {:?}", face.0, flag);
 	}
 
-	let predicate = OPERATION_PREDICATES[op as usize];
-
-	match op {
-		PathBooleanOperation::Division | PathBooleanOperation::Fracture => Ok(dump_faces(&nesting_trees, predicate, edges, vertices, &flags)),
-		_ => {
-			let mut selected_faces: Vec<DualVertexKey> = get_selected_faces(&predicate, &flags).collect();
-			selected_faces.sort_unstable();
-			Ok(vec![walk_faces(&selected_faces, edges, vertices).collect()])
-		}
-	}
+	Ok(Some(Arrangement { dual_graph, nesting_trees, flags }))
 }
 
 #[cfg(test)]
@@ -1804,16 +2156,16 @@ mod tests {
 		// of your split_at_intersections function
 	}
 
-	fn unsplit_edges() -> Vec<(PathSegment, u8)> {
+	fn unsplit_edges() -> Vec<MajorGraphEdgeStage1> {
 		let unsplit_edges = vec![
-			(PathSegment::Arc(DVec2::new(39., 20.), 19., 19., 0., false, true, DVec2::new(20., 39.)), 1),
-			(PathSegment::Arc(DVec2::new(20., 39.), 19., 19., 0., false, true, DVec2::new(1., 20.)), 1),
-			(PathSegment::Arc(DVec2::new(1., 20.), 19., 19., 0., false, true, DVec2::new(20., 1.)), 1),
-			(PathSegment::Arc(DVec2::new(20., 1.), 19., 19., 0., false, true, DVec2::new(39., 20.)), 1),
-			(PathSegment::Arc(DVec2::new(47., 28.), 19., 19., 0., false, true, DVec2::new(28., 47.)), 2),
-			(PathSegment::Arc(DVec2::new(28., 47.), 19., 19., 0., false, true, DVec2::new(9., 28.)), 2),
-			(PathSegment::Arc(DVec2::new(9., 28.), 19., 19., 0., false, true, DVec2::new(28., 9.)), 2),
-			(PathSegment::Arc(DVec2::new(28., 9.), 19., 19., 0., false, true, DVec2::new(47., 28.)), 2),
+			(PathSegment::Arc(DVec2::new(39., 20.), 19., 19., 0., false, true, DVec2::new(20., 39.)), 0),
+			(PathSegment::Arc(DVec2::new(20., 39.), 19., 19., 0., false, true, DVec2::new(1., 20.)), 0),
+			(PathSegment::Arc(DVec2::new(1., 20.), 19., 19., 0., false, true, DVec2::new(20., 1.)), 0),
+			(PathSegment::Arc(DVec2::new(20., 1.), 19., 19., 0., false, true, DVec2::new(39., 20.)), 0),
+			(PathSegment::Arc(DVec2::new(47., 28.), 19., 19., 0., false, true, DVec2::new(28., 47.)), 1),
+			(PathSegment::Arc(DVec2::new(28., 47.), 19., 19., 0., false, true, DVec2::new(9., 28.)), 1),
+			(PathSegment::Arc(DVec2::new(9., 28.), 19., 19., 0., false, true, DVec2::new(28., 9.)), 1),
+			(PathSegment::Arc(DVec2::new(28., 9.), 19., 19., 0., false, true, DVec2::new(47., 28.)), 1),
 		];
 		unsplit_edges
 	}
@@ -1852,8 +2204,8 @@ mod tests {
 		}
 
 		// Check that parents are correctly assigned
-		assert_eq!(minor_graph.edges.values().filter(|e| e.parent == 1).count(), 4, "Expected 4 edges with parent 1");
-		assert_eq!(minor_graph.edges.values().filter(|e| e.parent == 2).count(), 4, "Expected 4 edges with parent 2");
+		assert_eq!(minor_graph.edges.values().filter(|e| e.windings[..] == [(0, 1)]).count(), 4, "Expected 4 edges with parent 0");
+		assert_eq!(minor_graph.edges.values().filter(|e| e.windings[..] == [(1, 1)]).count(), 4, "Expected 4 edges with parent 1");
 	}
 
 	fn print_minor_graph_state(graph: &MinorGraph) {
@@ -1867,7 +2219,7 @@ mod tests {
 
 		for (edge_key, edge) in &graph.edges {
 			eprintln!("    Edge {:?}:", edge_key);
-			eprintln!("      Parent: {}", edge.parent);
+			eprintln!("      Windings: {:?}", edge.windings);
 			eprintln!("      Twin: {:?}", edge.twin);
 			eprintln!("      Incident vertices: {:?}", edge.incident_vertices);
 		}
@@ -1966,4 +2318,194 @@ mod tests {
 		assert!(bounding_box_intersects_horizontal_ray(&bbox, DVec2::new(10., 20.)));
 		assert!(!bounding_box_intersects_horizontal_ray(&bbox, DVec2::new(30., 40.)));
 	}
+
+	/// A rectangle wound clockwise on screen, or counterclockwise if `reversed`.
+	fn rectangle(min: (f64, f64), max: (f64, f64), reversed: bool) -> Path {
+		let corners = [DVec2::new(min.0, min.1), DVec2::new(max.0, min.1), DVec2::new(max.0, max.1), DVec2::new(min.0, max.1)];
+		let rectangle: Path = (0..4).map(|i| PathSegment::Line(corners[i], corners[(i + 1) % 4])).collect();
+		if reversed { rectangle.iter().rev().map(PathSegment::reverse).collect() } else { rectangle }
+	}
+
+	/// Whether the point is inside the result of a boolean operation on polygons, by the nonzero winding number of its outlines.
+	/// The winding number is needed since faces nested inside a hole of another face repeat the outline of that hole.
+	fn result_contains(result: &[Path], point: DVec2) -> bool {
+		let winding: i32 = result
+			.iter()
+			.flatten()
+			.map(|segment| {
+				let PathSegment::Line(start, end) = *segment else {
+					panic!("Expected the result to only have lines")
+				};
+				match line_segment_intersects_horizontal_ray(start, end, point) {
+					true if end.y > start.y => 1,
+					true => -1,
+					false => 0,
+				}
+			})
+			.sum();
+		winding != 0
+	}
+
+	/// The sorted indices of the paths covering each face of the arrangement, leaving out the faces outside all the paths.
+	fn covered_faces(paths: &[(&Path, FillRule)]) -> Vec<Vec<usize>> {
+		let arrangement = compute_arrangement(paths).unwrap().expect("The paths should have edges");
+		let mut faces: Vec<Vec<usize>> = arrangement
+			.flags
+			.values()
+			.filter(|coverage| coverage.count() > 0)
+			.map(|coverage| (0..paths.len()).filter(|&path| coverage.contains(path)).collect())
+			.collect();
+		faces.sort();
+		faces
+	}
+
+	fn coverage_of(path_count: usize, paths: &[usize]) -> Coverage {
+		let mut coverage = Coverage::new(path_count);
+		for &path in paths {
+			coverage.insert(path);
+		}
+		coverage
+	}
+
+	#[test]
+	fn test_is_selected_with_three_paths() {
+		use PathBooleanOperation::*;
+
+		let cases: [(&[usize], [bool; 5]); 7] = [
+			// Union, Difference, Intersection, Exclusion, Xor
+			(&[0], [true, true, false, true, true]),
+			(&[1], [true, false, false, true, true]),
+			(&[0, 1], [true, false, false, false, false]),
+			(&[1, 2], [true, false, false, false, false]),
+			(&[0, 1, 2], [true, false, true, false, true]),
+			(&[2], [true, false, false, true, true]),
+			(&[], [false, false, false, false, false]),
+		];
+		for (paths, expected) in cases {
+			let coverage = coverage_of(3, paths);
+			let selected = [Union, Difference, Intersection, Exclusion, Xor].map(|op| is_selected(op, &coverage, 3));
+			assert_eq!(selected, expected, "Wrong selection for a face inside paths {paths:?}");
+			assert_eq!(is_selected(Division, &coverage, 3), paths.contains(&0));
+			assert_eq!(is_selected(Fracture, &coverage, 3), !paths.is_empty());
+		}
+	}
+
+	#[test]
+	fn test_coverage_of_more_than_64_paths() {
+		let coverage = coverage_of(130, &[0, 63, 64, 129]);
+
+		assert_eq!(coverage.0.len(), 3, "Expected one word per 64 paths");
+		assert_eq!(coverage.count(), 4);
+		assert!([0, 63, 64, 129].into_iter().all(|path| coverage.contains(path)));
+		assert!([1, 62, 65, 128].into_iter().all(|path| !coverage.contains(path)));
+
+		let last_only = coverage_of(130, &[129]);
+		assert!(is_selected(PathBooleanOperation::Exclusion, &last_only, 130));
+		assert!(!is_selected(PathBooleanOperation::Difference, &last_only, 130));
+		assert!(is_selected(PathBooleanOperation::Intersection, &coverage_of(130, &(0..130).collect::<Vec<_>>()), 130));
+		assert!(!is_selected(PathBooleanOperation::Intersection, &coverage_of(130, &(0..129).collect::<Vec<_>>()), 130));
+	}
+
+	#[test]
+	fn test_path_boolean_many_with_three_paths() {
+		// Overlapping rectangles spanning 0..20, 10..30, and 15..35, which split into areas covered by 1, 2, 3, 2, and 1 of them
+		let rectangles = [rectangle((0., 0.), (20., 20.), false), rectangle((10., 0.), (30., 20.), false), rectangle((15., 0.), (35., 20.), false)];
+		let paths = rectangles.iter().map(|rectangle| (rectangle, FillRule::NonZero)).collect::<Vec<_>>();
+
+		assert_eq!(covered_faces(&paths), [vec![0], vec![0, 1], vec![0, 1, 2], vec![1, 2], vec![2]]);
+
+		let samples = [5., 12., 17., 25., 32.].map(|x| DVec2::new(x, 10.));
+		let cases = [
+			(PathBooleanOperation::Union, [true, true, true, true, true]),
+			(PathBooleanOperation::Difference, [true, false, false, false, false]),
+			(PathBooleanOperation::Intersection, [false, false, true, false, false]),
+			(PathBooleanOperation::Exclusion, [true, false, false, false, true]),
+			(PathBooleanOperation::Xor, [true, false, true, false, true]),
+		];
+		for (op, expected) in cases {
+			let result = path_boolean_many(&paths, op).unwrap();
+			assert_eq!(samples.map(|point| result_contains(&result, point)), expected, "Wrong areas for {op:?}");
+		}
+	}
+
+	#[test]
+	fn test_coincident_edges() {
+		// Rectangles sharing their middle side, which must separate their faces rather than appear in the union
+		let left = rectangle((0., 0.), (10., 10.), false);
+		let right = rectangle((10., 0.), (20., 10.), true);
+		let paths = [(&left, FillRule::NonZero), (&right, FillRule::NonZero)];
+		assert_eq!(covered_faces(&paths), [vec![0], vec![1]]);
+		let union = path_boolean_many(&paths, PathBooleanOperation::Union).unwrap();
+		assert!(result_contains(&union, DVec2::new(5., 5.)) && result_contains(&union, DVec2::new(15., 5.)));
+		assert!(
+			union[0].iter().all(|segment| segment.start().x != 10. || segment.end().x != 10.),
+			"The shared side should not be part of the union"
+		);
+
+		// A rectangle traced twice by the first path, and once the other way around by the second path
+		let square = rectangle((0., 0.), (10., 10.), false);
+		let twice: Path = square.iter().chain(square.iter()).copied().collect();
+		let reversed = rectangle((0., 0.), (10., 10.), true);
+		let edges: Vec<MajorGraphEdgeStage1> = [(&twice, 0), (&reversed, 1)].iter().flat_map(|&(path, index)| path.iter().filter_map(segment_to_edge(index))).collect();
+		let major_graph = find_vertices(&split_at_intersections(&edges));
+		assert_eq!(major_graph.edges.len(), 8, "Expected the coincident sides to share edges");
+		for edge in major_graph.edges.values() {
+			assert_eq!(edge.windings[..], [(0, 2), (1, -1)], "Each coincident side should add its winding to the shared edge");
+		}
+
+		// The first path winds twice around the square, which is inside it only with the nonzero fill rule
+		let center = DVec2::new(5., 5.);
+		assert_eq!(covered_faces(&[(&twice, FillRule::NonZero), (&reversed, FillRule::NonZero)]), [vec![0, 1]]);
+		assert_eq!(covered_faces(&[(&twice, FillRule::EvenOdd), (&reversed, FillRule::NonZero)]), [vec![1]]);
+		let nonzero = path_boolean_many(&[(&twice, FillRule::NonZero), (&reversed, FillRule::NonZero)], PathBooleanOperation::Intersection).unwrap();
+		assert!(result_contains(&nonzero, center));
+		let even_odd = path_boolean_many(&[(&twice, FillRule::EvenOdd), (&reversed, FillRule::NonZero)], PathBooleanOperation::Intersection).unwrap();
+		assert!(!result_contains(&even_odd, center));
+	}
+
+	#[test]
+	fn test_path_boolean_many_with_mixed_fill_rules() {
+		// A square with an inner square wound the same way, so its middle has a winding number of 2, overlapped by a third square
+		let nested: Path = rectangle((0., 0.), (30., 30.), false).into_iter().chain(rectangle((10., 10.), (20., 20.), false)).collect();
+		let overlap = rectangle((5., 5.), (25., 25.), true);
+		let (ring, middle) = (DVec2::new(7., 15.), DVec2::new(15., 15.));
+
+		for (fill_rule, middle_is_inside) in [(FillRule::NonZero, true), (FillRule::EvenOdd, false)] {
+			for (first, second) in [((&nested, fill_rule), (&overlap, FillRule::EvenOdd)), ((&overlap, FillRule::NonZero), (&nested, fill_rule))] {
+				let paths = [first, second];
+				let intersection = path_boolean_many(&paths, PathBooleanOperation::Intersection).unwrap();
+				assert!(result_contains(&intersection, ring), "The ring should be in the intersection with {fill_rule:?}");
+				assert_eq!(result_contains(&intersection, middle), middle_is_inside, "Wrong middle of the intersection with {fill_rule:?}");
+
+				let exclusion = path_boolean_many(&paths, PathBooleanOperation::Exclusion).unwrap();
+				assert!(!result_contains(&exclusion, ring));
+				assert_eq!(result_contains(&exclusion, middle), !middle_is_inside, "Wrong middle of the exclusion with {fill_rule:?}");
+			}
+		}
+	}
+
+	#[test]
+	fn test_path_boolean_many_with_more_than_64_paths() {
+		// A row of 70 squares, each overlapping half of the next, so only the outer halves of the first and last are covered once
+		const COUNT: usize = 70;
+		let squares = (0..COUNT).map(|i| rectangle((5. * i as f64, 0.), (5. * i as f64 + 10., 10.), false)).collect::<Vec<_>>();
+		let paths = squares.iter().map(|square| (square, FillRule::NonZero)).collect::<Vec<_>>();
+
+		let faces = covered_faces(&paths);
+		assert_eq!(faces.len(), COUNT + 1);
+		assert!(faces.contains(&vec![COUNT - 1]) && faces.contains(&vec![63, 64]) && faces.contains(&vec![COUNT - 2, COUNT - 1]));
+
+		let samples = [2.5, 7.5, 322.5, 347.5, 352.5].map(|x| DVec2::new(x, 5.));
+		let cases = [
+			(PathBooleanOperation::Union, [true, true, true, true, true]),
+			(PathBooleanOperation::Difference, [true, false, false, false, false]),
+			(PathBooleanOperation::Intersection, [false, false, false, false, false]),
+			(PathBooleanOperation::Exclusion, [true, false, false, false, true]),
+			(PathBooleanOperation::Xor, [true, false, false, false, true]),
+		];
+		for (op, expected) in cases {
+			let result = path_boolean_many(&paths, op).unwrap();
+			assert_eq!(samples.map(|point| result_contains(&result, point)), expected, "Wrong areas for {op:?}");
+		}
+	}
 }
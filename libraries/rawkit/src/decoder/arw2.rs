@@ -2,7 +2,7 @@ use crate::tiff::file::{Endian, TiffRead};
 use crate::tiff::tags::{BitsPerSample, CfaPattern, CfaPatternDim, Compression, ImageLength, ImageWidth, SonyToneCurve, StripByteCounts, StripOffsets, Tag, WhiteBalanceRggbLevels};
 use crate::tiff::values::{CompressionValue, CurveLookupTable};
 use crate::tiff::{Ifd, TiffError};
-use crate::{OrientationValue, RawImage, SubtractBlack};
+use crate::{DecoderError, OrientationValue, RawImage, SubtractBlack};
 use rawkit_proc_macros::Tag;
 use std::io::{Read, Seek};
 
@@ -21,40 +21,49 @@ struct Arw2Ifd {
 	white_balance_levels: Option<WhiteBalanceRggbLevels>,
 }
 
-pub fn decode<R: Read + Seek>(ifd: Ifd, file: &mut TiffRead<R>) -> RawImage {
-	let ifd = ifd.get_value::<Arw2Ifd, _>(file).unwrap();
+pub fn decode<R: Read + Seek>(ifd: Ifd, file: &mut TiffRead<R>) -> Result<RawImage, DecoderError> {
+	let ifd = ifd.get_value::<Arw2Ifd, _>(file)?;
 
-	assert!(ifd.strip_offsets.len() == ifd.strip_byte_counts.len());
-	assert!(ifd.strip_offsets.len() == 1);
-	assert!(ifd.compression == CompressionValue::Sony_ARW_Compressed);
+	let ([strip_offset], [_]) = (ifd.strip_offsets.as_slice(), ifd.strip_byte_counts.as_slice()) else {
+		return Err(DecoderError::UnsupportedData("Sony ARW 2 image which isn't stored in a single strip"));
+	};
+	if ifd.compression != CompressionValue::Sony_ARW_Compressed {
+		return Err(DecoderError::UnsupportedData("Sony ARW 2 compression"));
+	}
 
-	let image_width: usize = ifd.image_width.try_into().unwrap();
-	let image_height: usize = ifd.image_height.try_into().unwrap();
+	let image_width: usize = ifd.image_width.try_into()?;
+	let image_height: usize = ifd.image_height.try_into()?;
 	let bits_per_sample: usize = ifd.bits_per_sample.into();
-	assert!(bits_per_sample == 12);
+	if bits_per_sample != 12 {
+		return Err(DecoderError::UnsupportedData("Sony ARW 2 bit depth other than 12 bits"));
+	}
 
-	let [cfa_pattern_width, cfa_pattern_height] = ifd.cfa_pattern_dim;
-	assert!(cfa_pattern_width == 2 && cfa_pattern_height == 2);
+	if ifd.cfa_pattern_dim != [2, 2] {
+		return Err(DecoderError::UnsupportedData("Sony ARW 2 color filter array which is not a 2x2 grid"));
+	}
+	let cfa_pattern: [u8; 4] = ifd.cfa_pattern.try_into().map_err(|_| TiffError::InvalidCount)?;
 
-	file.seek_from_start(ifd.strip_offsets[0]).unwrap();
-	let mut image = sony_arw2_load_raw(image_width, image_height, ifd.sony_tone_curve, file).unwrap();
+	file.seek_from_start(*strip_offset)?;
+	let mut image = sony_arw2_load_raw(image_width, image_height, ifd.sony_tone_curve, file)?;
 
 	// Converting the bps from 12 to 14 so that ARW 2.3.1 and 2.3.5 have the same 14 bps.
 	image.iter_mut().for_each(|x| *x <<= 2);
 
-	RawImage {
+	Ok(RawImage {
 		data: image,
 		width: image_width,
 		height: image_height,
-		cfa_pattern: ifd.cfa_pattern.try_into().unwrap(),
+		cfa_pattern,
+		xtrans_pattern: None,
 		maximum: (1 << 14) - 1,
 		black: SubtractBlack::CfaGrid([512, 512, 512, 512]), // TODO: Find the correct way to do this
 		orientation: OrientationValue::Horizontal,
 		camera_model: None,
 		camera_white_balance: ifd.white_balance_levels.map(|arr| arr.map(|x| x as f64)),
 		white_balance: None,
+		xyz_to_camera: None,
 		camera_to_rgb: None,
-	}
+	})
 }
 
 fn as_u32(buffer: &[u8], endian: Endian) -> Option<u32> {
@@ -71,18 +80,20 @@ fn as_u16(buffer: &[u8], endian: Endian) -> Option<u16> {
 	})
 }
 
-fn sony_arw2_load_raw<R: Read + Seek>(width: usize, height: usize, curve: CurveLookupTable, file: &mut TiffRead<R>) -> Option<Vec<u16>> {
+fn sony_arw2_load_raw<R: Read + Seek>(width: usize, height: usize, curve: CurveLookupTable, file: &mut TiffRead<R>) -> Result<Vec<u16>, DecoderError> {
+	const TRUNCATED_ROW: DecoderError = DecoderError::InvalidData("Sony ARW 2 block which extends past the end of its row");
+
 	let mut image = vec![0_u16; height * width];
 	let mut data = vec![0_u8; width + 1];
 
 	for row in 0..height {
-		file.read_exact(&mut data[0..width]).unwrap();
+		file.read_exact(&mut data[0..width])?;
 
 		let mut column = 0;
 		let mut data_index = 0;
 
-		while column < width - 30 {
-			let data_value = as_u32(&data[data_index..][..4], file.endian()).unwrap();
+		while column < width.saturating_sub(30) {
+			let data_value = data.get(data_index..data_index + 4).and_then(|bytes| as_u32(bytes, file.endian())).ok_or(TRUNCATED_ROW)?;
 			let max = (0x7ff & data_value) as u16;
 			let min = (0x7ff & data_value >> 11) as u16;
 			let index_to_set_max = 0x0f & data_value >> 22;
@@ -98,7 +109,8 @@ fn sony_arw2_load_raw<R: Read + Seek>(width: usize, height: usize, curve: CurveL
 					_ if i as u32 == index_to_set_max => max,
 					_ if i as u32 == index_to_set_min => min,
 					_ => {
-						let result = as_u16(&data[(data_index + (bit >> 3))..][..2], file.endian()).unwrap();
+						let start = data_index + (bit >> 3);
+						let result = data.get(start..start + 2).and_then(|bytes| as_u16(bytes, file.endian())).ok_or(TRUNCATED_ROW)?;
 						let result = ((result >> (bit & 7)) & 0x07f) << shift_by_bits;
 
 						bit += 7;
@@ -122,5 +134,5 @@ fn sony_arw2_load_raw<R: Read + Seek>(width: usize, height: usize, curve: CurveL
 		}
 	}
 
-	Some(image)
+	Ok(image)
 }
@@ -0,0 +1,152 @@
+use super::ljpeg::LosslessJpeg;
+use super::{read_bytes, values_in_cfa_order};
+use crate::tiff::file::TiffRead;
+use crate::tiff::tags::{CanonColorData, CanonSensorInfo, Cr2Slice, ExifIfd, MakerNote, StripByteCounts, StripOffsets, Tag};
+use crate::tiff::values::DataBlock;
+use crate::tiff::{Ifd, TiffError};
+use crate::{DecoderError, OrientationValue, RawImage, SubtractBlack};
+use rawkit_proc_macros::Tag;
+use std::io::{Read, Seek};
+
+/// Canon sensors are all arranged with the RGGB pattern.
+const CFA_PATTERN: [u8; 4] = [0, 1, 1, 2];
+
+#[allow(dead_code)]
+#[derive(Tag)]
+struct Cr2RawIfd {
+	strip_offsets: StripOffsets,
+	strip_byte_counts: StripByteCounts,
+	slice: Option<Cr2Slice>,
+}
+
+#[allow(dead_code)]
+#[derive(Tag)]
+struct CanonMakerNote {
+	sensor_info: Option<CanonSensorInfo>,
+	color_data: Option<CanonColorData>,
+}
+
+pub fn decode<R: Read + Seek>(ifd: Ifd, file: &mut TiffRead<R>) -> Result<RawImage, DecoderError> {
+	// The fourth IFD holds the raw data, after the full-size JPEG preview, the thumbnail, and the uncompressed small preview
+	let raw_ifd = ifd.next_ifd(file)?.next_ifd(file)?.next_ifd(file)?;
+	let raw = raw_ifd.get_value::<Cr2RawIfd, _>(file)?;
+
+	let (Some(&offset), Some(&length)) = (raw.strip_offsets.first(), raw.strip_byte_counts.first()) else {
+		return Err(TiffError::InvalidCount.into());
+	};
+	let jpeg = LosslessJpeg::decode(&read_bytes(file, DataBlock { offset, length })?)?;
+
+	// Some cameras encode two rows of the image as one row of the JPEG
+	let mut width = jpeg.width * jpeg.components;
+	let mut height = jpeg.height;
+	if width > 4 * height && jpeg.components % 2 == 0 {
+		width /= 2;
+		height *= 2;
+	}
+
+	// The image is split into vertical slices which are encoded one after the other, where all but the last slice have the same width
+	let slices = raw
+		.slice
+		.filter(|&[count, slice_width, last_width]| count > 0 && slice_width > 0 && last_width > 0)
+		.map(|slice| slice.map(usize::from));
+
+	let mut image = vec![0_u16; width * height];
+	for (index, &sample) in jpeg.data.iter().enumerate() {
+		let (row, column) = match slices {
+			Some([count, slice_width, last_width]) => {
+				let slice = (index / (slice_width * height)).min(count);
+				let index_in_slice = index - slice * slice_width * height;
+				let current_slice_width = if slice < count { slice_width } else { last_width };
+
+				(index_in_slice / current_slice_width, index_in_slice % current_slice_width + slice * slice_width)
+			}
+			None => (index / width, index % width),
+		};
+
+		if row < height && column < width {
+			image[row * width + column] = sample;
+		}
+	}
+
+	let maker_note = read_maker_note(&ifd, file).ok();
+	let active_area = maker_note
+		.as_ref()
+		.and_then(|maker_note| maker_note.sensor_info.as_deref())
+		.and_then(|sensor_info| active_area(sensor_info, width, height));
+
+	// The masked columns at the left of the sensor never receive any light, so they give the black level before the image is cropped to the active area
+	let black = active_area.map_or([0; 4], |[left, top, _, _]| black_level(&image, width, height, left, top));
+	let (image, width, height) = match active_area {
+		Some([left, top, right, bottom]) => {
+			let image = (top..bottom).flat_map(|row| image[row * width + left..row * width + right].iter().copied()).collect();
+			(image, right - left, bottom - top)
+		}
+		None => (image, width, height),
+	};
+
+	Ok(RawImage {
+		black: SubtractBlack::CfaGrid(black),
+		data: image,
+		width,
+		height,
+		cfa_pattern: CFA_PATTERN,
+		xtrans_pattern: None,
+		maximum: ((1_u32 << jpeg.precision) - 1) as u16,
+		orientation: OrientationValue::Horizontal,
+		camera_model: None,
+		camera_white_balance: maker_note.as_ref().and_then(|maker_note| maker_note.color_data.as_deref()).and_then(white_balance),
+		white_balance: None,
+		xyz_to_camera: None,
+		camera_to_rgb: None,
+	})
+}
+
+fn read_maker_note<R: Read + Seek>(ifd: &Ifd, file: &mut TiffRead<R>) -> Result<<CanonMakerNote as Tag>::Output, TiffError> {
+	let exif_ifd = ifd.get_value::<ExifIfd, _>(file)?;
+	let maker_note = exif_ifd.get_value::<MakerNote, _>(file)?;
+
+	// Canon stores its maker note as a plain IFD, with offsets from the start of the file
+	Ifd::new_from_offset(file, maker_note.offset)?.get_value::<CanonMakerNote, _>(file)
+}
+
+/// Reads the active area of the sensor as `[left, top, right, bottom]`, with the right and bottom edges exclusive.
+fn active_area(sensor_info: &[u16], width: usize, height: usize) -> Option<[usize; 4]> {
+	let &[_, _, _, _, _, left, top, right, bottom, ..] = sensor_info else { return None };
+	let [left, top, right, bottom] = [left, top, right, bottom].map(usize::from);
+
+	(left <= right && top <= bottom && right < width && bottom < height).then_some([left, top, right + 1, bottom + 1])
+}
+
+/// Averages the masked columns to the left of the active area for each position of the CFA grid.
+fn black_level(image: &[u16], width: usize, height: usize, left_border: usize, top_border: usize) -> [u16; 4] {
+	let mut sums = [0_u64; 4];
+	let mut counts = [0_u64; 4];
+	for row in top_border..height {
+		for column in 0..left_border {
+			// Positions in the CFA grid are relative to the corner of the active area
+			let cfa_index = 2 * ((row - top_border) % 2) + (left_border - column) % 2;
+			sums[cfa_index] += image[row * width + column] as u64;
+			counts[cfa_index] += 1;
+		}
+	}
+
+	std::array::from_fn(|index| sums[index].checked_div(counts[index]).unwrap_or(0) as u16)
+}
+
+/// Reads the white balance of the shot from the color data, whose layout depends on the camera generation as told by its length.
+fn white_balance(color_data: &[u16]) -> Option<[f64; 4]> {
+	let offset = match color_data.len() {
+		582 => 25,
+		653 => 34,
+		5120 => 71,
+		length if length > 500 => 63,
+		_ => return None,
+	};
+
+	let levels: [u16; 4] = color_data.get(offset..offset + 4)?.try_into().ok()?;
+	if levels.contains(&0) {
+		return None;
+	}
+
+	Some(values_in_cfa_order(CFA_PATTERN, levels.map(f64::from)))
+}
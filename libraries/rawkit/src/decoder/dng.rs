@@ -0,0 +1,188 @@
+use super::ljpeg::LosslessJpeg;
+use super::{find_raw_ifd, read_bytes, read_uncompressed};
+use crate::tiff::file::TiffRead;
+use crate::tiff::tags::{
+	AsShotNeutral, BitsPerSample, BlackLevelRepeatDim, CfaPattern, CfaPatternDim, ColorMatrix1, ColorMatrix2, Compression, DngBlackLevel, ImageLength, ImageWidth, LinearizationTable, RowsPerStrip,
+	StripByteCounts, StripOffsets, Tag, TileByteCounts, TileLength, TileOffsets, TileWidth, WhiteLevel,
+};
+use crate::tiff::values::{CompressionValue, DataBlock, ToFloat};
+use crate::tiff::{Ifd, TiffError};
+use crate::{DecoderError, OrientationValue, RawImage, SubtractBlack};
+use rawkit_proc_macros::Tag;
+use std::io::{Read, Seek};
+
+#[allow(dead_code)]
+#[derive(Tag)]
+struct DngRawIfd {
+	image_width: ImageWidth,
+	image_height: ImageLength,
+	bits_per_sample: BitsPerSample,
+	compression: Compression,
+	cfa_pattern: CfaPattern,
+	cfa_pattern_dim: CfaPatternDim,
+	linearization_table: Option<LinearizationTable>,
+	black_level_repeat_dim: Option<BlackLevelRepeatDim>,
+	black_level: Option<DngBlackLevel>,
+	white_level: Option<WhiteLevel>,
+}
+
+#[allow(dead_code)]
+#[derive(Tag)]
+struct DngStrips {
+	rows_per_strip: Option<RowsPerStrip>,
+	strip_offsets: StripOffsets,
+	strip_byte_counts: StripByteCounts,
+}
+
+#[allow(dead_code)]
+#[derive(Tag)]
+struct DngTiles {
+	tile_width: TileWidth,
+	tile_length: TileLength,
+	tile_offsets: TileOffsets,
+	tile_byte_counts: TileByteCounts,
+}
+
+#[allow(dead_code)]
+#[derive(Tag)]
+struct DngColorIfd {
+	as_shot_neutral: Option<AsShotNeutral>,
+	color_matrix_1: Option<ColorMatrix1>,
+	color_matrix_2: Option<ColorMatrix2>,
+}
+
+/// A strip or tile of the raw image, which is stored and compressed separately from the others.
+struct Segment {
+	data: DataBlock,
+	row: usize,
+	column: usize,
+	width: usize,
+	height: usize,
+}
+
+pub fn decode<R: Read + Seek>(ifd: Ifd, file: &mut TiffRead<R>) -> Result<RawImage, DecoderError> {
+	let raw_ifd = find_raw_ifd(&ifd, file)?;
+	let raw = raw_ifd.get_value::<DngRawIfd, _>(file)?;
+	let color = ifd.get_value::<DngColorIfd, _>(file)?;
+
+	let image_width: usize = raw.image_width.try_into()?;
+	let image_height: usize = raw.image_height.try_into()?;
+	let bits_per_sample: u32 = raw.bits_per_sample.into();
+
+	// Raw data is either split into tiles, or into strips of rows which span the whole width
+	let segments: Vec<_> = if let Ok(tiles) = raw_ifd.get_value::<DngTiles, _>(file) {
+		let tile_width: usize = tiles.tile_width.try_into()?;
+		let tile_height: usize = tiles.tile_length.try_into()?;
+		let tiles_across = image_width.div_ceil(tile_width.max(1));
+
+		tiles
+			.tile_offsets
+			.iter()
+			.zip(tiles.tile_byte_counts.iter())
+			.enumerate()
+			.map(|(index, (&offset, &length))| Segment {
+				data: DataBlock { offset, length },
+				row: (index / tiles_across) * tile_height,
+				column: (index % tiles_across) * tile_width,
+				width: tile_width,
+				height: tile_height,
+			})
+			.collect()
+	} else {
+		let strips = raw_ifd.get_value::<DngStrips, _>(file)?;
+		let rows_per_strip = strips.rows_per_strip.map_or(Ok(image_height), usize::try_from)?.min(image_height);
+
+		strips
+			.strip_offsets
+			.iter()
+			.zip(strips.strip_byte_counts.iter())
+			.enumerate()
+			.map(|(index, (&offset, &length))| Segment {
+				data: DataBlock { offset, length },
+				row: index * rows_per_strip,
+				column: 0,
+				width: image_width,
+				height: rows_per_strip.min(image_height.saturating_sub(index * rows_per_strip)),
+			})
+			.collect()
+	};
+
+	let mut image = vec![0_u16; image_width * image_height];
+	for segment in segments {
+		let data = read_bytes(file, segment.data)?;
+
+		let samples = match raw.compression {
+			CompressionValue::Uncompressed => read_uncompressed(&data, segment.width, segment.height, bits_per_sample, file.endian())?,
+			CompressionValue::JPEG => LosslessJpeg::decode(&data)?.data,
+			_ => return Err(DecoderError::UnsupportedData("DNG compression other than lossless JPEG")),
+		};
+
+		// Lossless JPEG data may interleave several components in each of its rows, which are laid out in order to fill the rows of the segment
+		for (index, &sample) in samples.iter().enumerate() {
+			let row = segment.row + index / segment.width;
+			let column = segment.column + index % segment.width;
+
+			if row < image_height && column < image_width && index / segment.width < segment.height {
+				image[row * image_width + column] = sample;
+			}
+		}
+	}
+
+	if let Some(table) = raw.linearization_table.filter(|table| !table.is_empty()) {
+		image.iter_mut().for_each(|sample| *sample = table[(*sample as usize).min(table.len() - 1)]);
+	}
+
+	let (cfa_pattern, xtrans_pattern) = match raw.cfa_pattern_dim {
+		[2, 2] => (raw.cfa_pattern.try_into().map_err(|_| TiffError::InvalidCount)?, None),
+		[6, 6] => {
+			let pattern: [u8; 36] = raw.cfa_pattern.try_into().map_err(|_| TiffError::InvalidCount)?;
+			([0, 1, 1, 2], Some(std::array::from_fn(|row| std::array::from_fn(|column| pattern[row * 6 + column]))))
+		}
+		_ => return Err(DecoderError::UnsupportedData("DNG color filter array which is neither Bayer nor X-Trans")),
+	};
+
+	// The black level repeats over a grid of the given size, which we sample at each position of the 2x2 CFA grid
+	let black = match raw.black_level.filter(|levels| !levels.is_empty()) {
+		Some(levels) => {
+			let [rows, columns] = raw.black_level_repeat_dim.unwrap_or([1, 1]).map(|x| x.max(1) as usize);
+			std::array::from_fn(|index| {
+				let (row, column) = (index / 2 % rows, index % 2 % columns);
+				levels.get(row * columns + column).unwrap_or(&levels[0]).round() as u16
+			})
+		}
+		None => [0; 4],
+	};
+
+	let maximum = match raw.white_level.as_deref() {
+		Some([white_level, ..]) => (*white_level).min(u16::MAX.into()) as u16,
+		_ => ((1_u32 << bits_per_sample) - 1) as u16,
+	};
+
+	// The neutral color gives the amount of each channel in a white object, so the white balance multiplies each channel by its inverse
+	let camera_white_balance = color
+		.as_shot_neutral
+		.filter(|neutral| neutral.len() == 3 && neutral.iter().all(|&x| x > 0.))
+		.map(|neutral| cfa_pattern.map(|color: u8| 1. / neutral[color as usize]));
+
+	let xyz_to_camera = color
+		.color_matrix_2
+		.or(color.color_matrix_1)
+		.filter(|matrix| matrix.len() == 9)
+		.map(|matrix| std::array::from_fn(|index| matrix[index].to_float()));
+
+	Ok(RawImage {
+		data: image,
+		width: image_width,
+		height: image_height,
+		cfa_pattern,
+		xtrans_pattern,
+		maximum,
+		black: SubtractBlack::CfaGrid(black),
+		orientation: OrientationValue::Horizontal,
+		camera_model: None,
+		camera_white_balance,
+		white_balance: None,
+		xyz_to_camera,
+		camera_to_rgb: None,
+	})
+}
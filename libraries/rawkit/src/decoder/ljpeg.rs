@@ -0,0 +1,399 @@
+use crate::DecoderError;
+
+const MARKER_SOF3: u8 = 0xc3;
+const MARKER_DHT: u8 = 0xc4;
+const MARKER_SOI: u8 = 0xd8;
+const MARKER_EOI: u8 = 0xd9;
+const MARKER_SOS: u8 = 0xda;
+const MARKER_DRI: u8 = 0xdd;
+
+/// Reads bits from a byte buffer starting with the most significant bit.
+///
+/// When reading JPEG entropy-coded data, the `0x00` byte stuffed after each `0xFF` byte is skipped and reading stops at the next marker.
+/// Reading past the end of the data, or past a marker, gives zero bits.
+pub struct BitPump<'a> {
+	data: &'a [u8],
+	position: usize,
+	buffer: u64,
+	available: u32,
+	jpeg_stuffing: bool,
+}
+
+impl<'a> BitPump<'a> {
+	pub fn new(data: &'a [u8]) -> Self {
+		Self {
+			data,
+			position: 0,
+			buffer: 0,
+			available: 0,
+			jpeg_stuffing: false,
+		}
+	}
+
+	pub fn new_jpeg(data: &'a [u8]) -> Self {
+		Self {
+			jpeg_stuffing: true,
+			..Self::new(data)
+		}
+	}
+
+	fn refill(&mut self) {
+		while self.available <= 56 {
+			let byte = match self.data.get(self.position) {
+				Some(&0xff) if self.jpeg_stuffing && self.data.get(self.position + 1) == Some(&0) => {
+					self.position += 2;
+					0xff
+				}
+				// Leave the marker in place so it can be found after the entropy-coded data
+				Some(&0xff) if self.jpeg_stuffing => 0,
+				Some(&byte) => {
+					self.position += 1;
+					byte
+				}
+				None => 0,
+			};
+
+			self.buffer |= (byte as u64) << (56 - self.available);
+			self.available += 8;
+		}
+	}
+
+	/// Looks at the next `count` bits, up to 32, without consuming them.
+	pub fn peek(&mut self, count: u32) -> u32 {
+		if count == 0 {
+			return 0;
+		}
+		if self.available < count {
+			self.refill();
+		}
+		(self.buffer >> (64 - count)) as u32
+	}
+
+	pub fn consume(&mut self, count: u32) {
+		self.buffer <<= count;
+		self.available -= count;
+	}
+
+	/// Reads the next `count` bits, up to 32.
+	pub fn read(&mut self, count: u32) -> u32 {
+		let bits = self.peek(count);
+		self.consume(count);
+		bits
+	}
+
+	/// Discards the rest of the current byte and skips over the restart marker which should follow it.
+	fn restart(&mut self) -> Result<(), DecoderError> {
+		self.buffer = 0;
+		self.available = 0;
+
+		match self.data.get(self.position..self.position + 2) {
+			Some(&[0xff, marker]) if (0xd0..=0xd7).contains(&marker) => {
+				self.position += 2;
+				Ok(())
+			}
+			_ => Err(DecoderError::InvalidData("missing restart marker in lossless JPEG data")),
+		}
+	}
+}
+
+/// A Huffman table in the format of the JPEG DHT segment, given as the number of codes of each length from 1 to 16 bits followed by the symbols in order of their codes.
+pub struct HuffmanTable {
+	max_code: [i32; 17],
+	value_offset: [i32; 17],
+	values: Vec<u8>,
+}
+
+impl HuffmanTable {
+	pub fn new(counts: &[u8], values: &[u8]) -> Result<Self, DecoderError> {
+		let counts = counts.get(..16).ok_or(DecoderError::InvalidData("Huffman table has fewer than 16 code counts"))?;
+
+		let mut max_code = [-1; 17];
+		let mut value_offset = [0; 17];
+
+		let mut code = 0;
+		let mut index = 0;
+		for length in 1..=16 {
+			let count = counts[length - 1] as i32;
+			if count > 0 {
+				value_offset[length] = index - code;
+				code += count;
+				index += count;
+				max_code[length] = code - 1;
+			}
+			code <<= 1;
+		}
+
+		if values.len() < index as usize {
+			return Err(DecoderError::InvalidData("Huffman table has fewer symbols than codes"));
+		}
+
+		Ok(Self {
+			max_code,
+			value_offset,
+			values: values[..index as usize].to_vec(),
+		})
+	}
+
+	/// Parses the table from its definition where the 16 counts come first, then the symbols.
+	pub fn from_definition(definition: &[u8]) -> Result<Self, DecoderError> {
+		let (counts, values) = definition.split_at_checked(16).ok_or(DecoderError::InvalidData("truncated Huffman table"))?;
+		Self::new(counts, values)
+	}
+
+	pub fn decode(&self, bits: &mut BitPump) -> Result<u8, DecoderError> {
+		let peeked = bits.peek(16) as i32;
+
+		for length in 1..=16 {
+			let code = peeked >> (16 - length);
+			if code <= self.max_code[length] {
+				bits.consume(length as u32);
+				return Ok(self.values[(code + self.value_offset[length]) as usize]);
+			}
+		}
+
+		Err(DecoderError::InvalidData("invalid Huffman code"))
+	}
+
+	/// Decodes the difference between a sample and its prediction, which is stored as the number of bits followed by the bits themselves.
+	pub fn decode_difference(&self, bits: &mut BitPump) -> Result<i32, DecoderError> {
+		let length = self.decode(bits)? as u32;
+
+		Ok(match length {
+			0 => 0,
+			// A length of 16 stands for the largest difference without any further bits
+			16 => -32768,
+			1..=15 => {
+				let difference = bits.read(length) as i32;
+				if difference & (1 << (length - 1)) == 0 { difference - (1 << length) + 1 } else { difference }
+			}
+			_ => return Err(DecoderError::InvalidData("Huffman symbol out of range for lossless JPEG")),
+		})
+	}
+}
+
+/// An image decoded from lossless JPEG (ITU-T T.81 process 14) data, where the samples of each row are interleaved by component.
+pub struct LosslessJpeg {
+	pub width: usize,
+	pub height: usize,
+	pub components: usize,
+	pub precision: u8,
+	pub data: Vec<u16>,
+}
+
+struct FrameComponent {
+	id: u8,
+	sampling: u8,
+}
+
+impl LosslessJpeg {
+	/// Decodes the lossless JPEG image in `data`, which holds the stream from its SOI marker onwards.
+	pub fn decode(data: &[u8]) -> Result<LosslessJpeg, DecoderError> {
+		let mut position = 0;
+		let mut frame: Option<(u8, usize, usize, Vec<FrameComponent>)> = None;
+		let mut tables: [Option<HuffmanTable>; 4] = [None, None, None, None];
+		let mut restart_interval = 0;
+
+		if next_marker(data, &mut position)? != MARKER_SOI {
+			return Err(DecoderError::InvalidData("lossless JPEG data does not start with an SOI marker"));
+		}
+
+		loop {
+			let marker = next_marker(data, &mut position)?;
+			if marker == MARKER_EOI {
+				return Err(DecoderError::InvalidData("lossless JPEG data has no scan"));
+			}
+
+			let length = match data.get(position..position + 2) {
+				Some(&[high, low]) => u16::from_be_bytes([high, low]) as usize,
+				_ => return Err(DecoderError::InvalidData("unexpected end of lossless JPEG data")),
+			};
+			let segment = data.get(position + 2..position + length).ok_or(DecoderError::InvalidData("unexpected end of lossless JPEG data"))?;
+			position += length;
+
+			match marker {
+				MARKER_SOF3 => {
+					let [precision, height_high, height_low, width_high, width_low, count, ..] = *segment else {
+						return Err(DecoderError::InvalidData("truncated lossless JPEG frame header"));
+					};
+					let components = segment[6..]
+						.chunks_exact(3)
+						.take(count as usize)
+						.map(|component| FrameComponent {
+							id: component[0],
+							sampling: component[1],
+						})
+						.collect::<Vec<_>>();
+					if components.len() != count as usize {
+						return Err(DecoderError::InvalidData("truncated lossless JPEG frame header"));
+					}
+
+					let height = u16::from_be_bytes([height_high, height_low]) as usize;
+					let width = u16::from_be_bytes([width_high, width_low]) as usize;
+					frame = Some((precision, width, height, components));
+				}
+				0xc0..=0xcf if marker != MARKER_DHT && marker != 0xc8 && marker != 0xcc => {
+					return Err(DecoderError::UnsupportedData("JPEG data which is not lossless"));
+				}
+				MARKER_DHT => {
+					let mut definition = segment;
+					while let [class_and_id, rest @ ..] = definition {
+						let counts = rest.get(..16).ok_or(DecoderError::InvalidData("truncated Huffman table"))?;
+						let count = counts.iter().map(|&count| count as usize).sum::<usize>();
+						let values = rest.get(16..16 + count).ok_or(DecoderError::InvalidData("truncated Huffman table"))?;
+
+						let table = tables.get_mut((class_and_id & 0x0f) as usize).ok_or(DecoderError::InvalidData("Huffman table index out of range"))?;
+						*table = Some(HuffmanTable::new(counts, values)?);
+						definition = &rest[16 + count..];
+					}
+				}
+				MARKER_DRI => {
+					let &[high, low, ..] = segment else {
+						return Err(DecoderError::InvalidData("truncated restart interval"));
+					};
+					restart_interval = u16::from_be_bytes([high, low]) as usize;
+				}
+				MARKER_SOS => {
+					let (precision, width, height, components) = frame.ok_or(DecoderError::InvalidData("lossless JPEG scan comes before its frame header"))?;
+					if components.iter().any(|component| component.sampling != 0x11) {
+						return Err(DecoderError::UnsupportedData("subsampled lossless JPEG components"));
+					}
+
+					let count = *segment.first().ok_or(DecoderError::InvalidData("truncated scan header"))? as usize;
+					let selectors = segment.get(1..1 + 2 * count).ok_or(DecoderError::InvalidData("truncated scan header"))?;
+					let Some(&[predictor, _, point_transform]) = segment.get(1 + 2 * count..4 + 2 * count) else {
+						return Err(DecoderError::InvalidData("truncated scan header"));
+					};
+					if count != components.len() {
+						return Err(DecoderError::UnsupportedData("lossless JPEG with more than one scan"));
+					}
+
+					// Find the Huffman table used by each component of the frame, in the order they are interleaved in the scan
+					let scan_tables = selectors
+						.chunks_exact(2)
+						.map(|selector| {
+							components
+								.iter()
+								.find(|component| component.id == selector[0])
+								.ok_or(DecoderError::InvalidData("scan refers to an unknown component"))?;
+							tables[(selector[1] >> 4) as usize & 3]
+								.as_ref()
+								.ok_or(DecoderError::InvalidData("scan refers to a missing Huffman table"))
+						})
+						.collect::<Result<Vec<_>, _>>()?;
+
+					let scan = Scan {
+						width,
+						height,
+						precision,
+						predictor,
+						point_transform: point_transform & 0x0f,
+						restart_interval,
+					};
+					let data = scan.decode(&data[position..], &scan_tables)?;
+
+					return Ok(LosslessJpeg {
+						width,
+						height,
+						components: components.len(),
+						precision,
+						data,
+					});
+				}
+				_ => {}
+			}
+		}
+	}
+}
+
+/// Finds the next marker, skipping over any fill bytes, and returns its code.
+fn next_marker(data: &[u8], position: &mut usize) -> Result<u8, DecoderError> {
+	while *position + 1 < data.len() {
+		if data[*position] == 0xff && data[*position + 1] != 0xff && data[*position + 1] != 0 {
+			let marker = data[*position + 1];
+			*position += 2;
+			return Ok(marker);
+		}
+		*position += 1;
+	}
+
+	Err(DecoderError::InvalidData("unexpected end of lossless JPEG data"))
+}
+
+struct Scan {
+	width: usize,
+	height: usize,
+	precision: u8,
+	predictor: u8,
+	point_transform: u8,
+	restart_interval: usize,
+}
+
+impl Scan {
+	fn decode(&self, data: &[u8], tables: &[&HuffmanTable]) -> Result<Vec<u16>, DecoderError> {
+		if !(1..=7).contains(&self.predictor) {
+			return Err(DecoderError::InvalidData("invalid lossless JPEG predictor"));
+		}
+		if self.precision < 2 || self.precision > 16 || self.point_transform >= self.precision {
+			return Err(DecoderError::InvalidData("invalid lossless JPEG precision"));
+		}
+
+		let components = tables.len();
+		let row_length = self.width * components;
+		let mut output = vec![0_u16; row_length * self.height];
+		let mut bits = BitPump::new_jpeg(data);
+
+		let initial_prediction = 1_i32 << (self.precision - self.point_transform - 1);
+		let mut samples_until_restart = self.restart_interval;
+		// The row where the last restart happened, which is predicted from the left like the first row
+		let mut first_row = 0;
+
+		for row in 0..self.height {
+			for column in 0..self.width {
+				let mut restarted = false;
+				if self.restart_interval > 0 {
+					if samples_until_restart == 0 {
+						bits.restart()?;
+						samples_until_restart = self.restart_interval;
+						first_row = row;
+						restarted = true;
+					}
+					samples_until_restart -= 1;
+				}
+
+				for (component, table) in tables.iter().enumerate() {
+					let index = row * row_length + column * components + component;
+					let difference = table.decode_difference(&mut bits)?;
+
+					let left = || output[index - components] as i32;
+					let above = || output[index - row_length] as i32;
+					let prediction = if restarted || (row == first_row && column == 0) {
+						initial_prediction
+					} else if row == first_row {
+						left()
+					} else if column == 0 {
+						above()
+					} else {
+						let (a, b, c) = (left(), above(), output[index - row_length - components] as i32);
+						match self.predictor {
+							1 => a,
+							2 => b,
+							3 => c,
+							4 => a + b - c,
+							5 => a + ((b - c) >> 1),
+							6 => b + ((a - c) >> 1),
+							_ => (a + b) >> 1,
+						}
+					};
+
+					output[index] = (prediction + difference) as u16;
+				}
+			}
+		}
+
+		if self.point_transform > 0 {
+			output.iter_mut().for_each(|sample| *sample <<= self.point_transform);
+		}
+
+		Ok(output)
+	}
+}
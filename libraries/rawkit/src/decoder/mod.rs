@@ -1,3 +1,98 @@
 pub mod arw1;
 pub mod arw2;
+pub mod cr2;
+pub mod dng;
+pub mod ljpeg;
+pub mod nef;
+pub mod raf;
 pub mod uncompressed;
+
+use crate::DecoderError;
+use crate::tiff::file::{Endian, TiffRead};
+use crate::tiff::tags::{NewSubfileType, PhotometricInterpretation, SubIfdOffsets};
+use crate::tiff::values::DataBlock;
+use crate::tiff::{Ifd, TiffError};
+use ljpeg::BitPump;
+use std::io::{Read, Seek};
+
+/// The photometric interpretation of raw data which is arranged in a color filter array.
+const PHOTOMETRIC_CFA: u16 = 32803;
+
+/// Finds the IFD holding the full-size raw image, which is either the first IFD itself or one of its SubIFDs.
+pub(crate) fn find_raw_ifd<R: Read + Seek>(ifd: &Ifd, file: &mut TiffRead<R>) -> Result<Ifd, DecoderError> {
+	let mut candidates = vec![ifd.clone()];
+	for offset in ifd.get_value::<Option<SubIfdOffsets>, _>(file)?.unwrap_or_default() {
+		candidates.push(Ifd::new_from_offset(file, offset)?);
+	}
+
+	for candidate in candidates {
+		// A subfile type of 0 marks the main image, rather than a reduced resolution preview
+		let subfile_type = candidate.get_value::<Option<NewSubfileType>, _>(file)?.unwrap_or(0);
+		let photometric_interpretation = candidate.get_value::<Option<PhotometricInterpretation>, _>(file)?;
+
+		if subfile_type == 0 && photometric_interpretation == Some(PHOTOMETRIC_CFA) {
+			return Ok(candidate);
+		}
+	}
+
+	Err(DecoderError::UnsupportedData("raw image data which is not arranged in a color filter array"))
+}
+
+pub(crate) fn read_bytes<R: Read + Seek>(file: &mut TiffRead<R>, block: DataBlock) -> Result<Vec<u8>, TiffError> {
+	file.seek_from_start(block.offset)?;
+
+	let mut data = Vec::new();
+	file.take(block.length.into()).read_to_end(&mut data)?;
+	if data.len() != block.length as usize {
+		return Err(TiffError::InvalidOffset);
+	}
+
+	Ok(data)
+}
+
+/// Reads uncompressed samples, where samples of 8 or 16 bits are stored whole and other sizes are packed together starting from the most significant bit.
+/// Each row of packed samples starts at a new byte.
+pub(crate) fn read_uncompressed(data: &[u8], width: usize, height: usize, bits_per_sample: u32, endian: Endian) -> Result<Vec<u16>, DecoderError> {
+	let row_length = (width * bits_per_sample as usize).div_ceil(8);
+	if !(1..=16).contains(&bits_per_sample) {
+		return Err(DecoderError::UnsupportedData("uncompressed samples of more than 16 bits"));
+	}
+	if data.len() < row_length * height {
+		return Err(DecoderError::InvalidData("uncompressed raw data is shorter than the image size"));
+	}
+
+	let rows = data.chunks_exact(row_length).take(height);
+	let samples = match bits_per_sample {
+		8 => rows.flat_map(|row| row.iter().map(|&sample| sample.into())).collect(),
+		16 => rows
+			.flat_map(|row| {
+				row.chunks_exact(2).map(|sample| match endian {
+					Endian::Little => u16::from_le_bytes([sample[0], sample[1]]),
+					Endian::Big => u16::from_be_bytes([sample[0], sample[1]]),
+				})
+			})
+			.collect(),
+		_ => rows
+			.flat_map(|row| {
+				let mut bits = BitPump::new(row);
+				(0..width).map(move |_| bits.read(bits_per_sample) as u16)
+			})
+			.collect(),
+	};
+
+	Ok(samples)
+}
+
+/// Arranges values given for the red, first green, second green, and blue channels in the order of the CFA pattern.
+pub(crate) fn values_in_cfa_order<T: Copy>(cfa_pattern: [u8; 4], [red, green, second_green, blue]: [T; 4]) -> [T; 4] {
+	let mut greens_seen = 0;
+
+	cfa_pattern.map(|color| match color {
+		0 => red,
+		2 => blue,
+		_ => {
+			greens_seen += 1;
+			if greens_seen == 1 { green } else { second_green }
+		}
+	})
+}
@@ -0,0 +1,267 @@
+use super::ljpeg::{BitPump, HuffmanTable};
+use super::{find_raw_ifd, read_bytes, read_uncompressed, values_in_cfa_order};
+use crate::tiff::file::{Endian, TiffRead};
+use crate::tiff::tags::{
+	BitsPerSample, CfaPattern, CfaPatternDim, Compression, ExifIfd, ImageLength, ImageWidth, MakerNote, NikonBlackLevel, NikonLinearization, NikonWhiteBalance, StripByteCounts, StripOffsets, Tag,
+};
+use crate::tiff::values::{CompressionValue, DataBlock};
+use crate::tiff::{Ifd, TiffError};
+use crate::{DecoderError, OrientationValue, RawImage, SubtractBlack};
+use rawkit_proc_macros::Tag;
+use std::io::{Cursor, Read, Seek};
+
+/// The Huffman tables used by Nikon, in the same format as JPEG tables.
+/// Each symbol holds the number of bits of a difference in its low nibble, and the number of low bits which are left out of it in its high nibble.
+const NIKON_TREES: [&[u8]; 6] = [
+	// 12-bit lossy
+	&[0, 1, 5, 1, 1, 1, 1, 1, 1, 2, 0, 0, 0, 0, 0, 0, 5, 4, 3, 6, 2, 7, 1, 0, 8, 9, 11, 10, 12],
+	// 12-bit lossy after the split
+	&[0, 1, 5, 1, 1, 1, 1, 1, 1, 2, 0, 0, 0, 0, 0, 0, 0x39, 0x5a, 0x38, 0x27, 0x16, 5, 4, 3, 2, 1, 0, 11, 12, 12],
+	// 12-bit lossless
+	&[0, 1, 4, 2, 3, 1, 2, 0, 0, 0, 0, 0, 0, 0, 0, 0, 5, 4, 6, 3, 7, 2, 8, 1, 9, 0, 10, 11, 12],
+	// 14-bit lossy
+	&[0, 1, 4, 3, 1, 1, 1, 1, 1, 2, 0, 0, 0, 0, 0, 0, 5, 6, 4, 7, 8, 3, 9, 2, 1, 0, 10, 11, 12, 13, 14],
+	// 14-bit lossy after the split
+	&[0, 1, 5, 1, 1, 1, 1, 1, 1, 1, 2, 0, 0, 0, 0, 0, 8, 0x5c, 0x4b, 0x3a, 0x29, 7, 6, 5, 4, 3, 2, 1, 0, 13, 14],
+	// 14-bit lossless
+	&[0, 1, 4, 2, 2, 3, 1, 2, 0, 0, 0, 0, 0, 0, 0, 0, 7, 6, 8, 5, 9, 4, 10, 3, 11, 12, 2, 0, 1, 13, 14],
+];
+
+/// The Nikon maker note starts with this header, followed by its own TIFF structure.
+const MAKER_NOTE_HEADER: &[u8] = b"Nikon\0";
+const MAKER_NOTE_TIFF_START: usize = 10;
+
+#[allow(dead_code)]
+#[derive(Tag)]
+struct NefRawIfd {
+	image_width: ImageWidth,
+	image_height: ImageLength,
+	bits_per_sample: BitsPerSample,
+	compression: Compression,
+	cfa_pattern: CfaPattern,
+	cfa_pattern_dim: CfaPatternDim,
+	strip_offsets: StripOffsets,
+	strip_byte_counts: StripByteCounts,
+}
+
+#[allow(dead_code)]
+#[derive(Tag)]
+struct NikonMakerNote {
+	white_balance: Option<NikonWhiteBalance>,
+	black_level: Option<NikonBlackLevel>,
+	linearization: Option<NikonLinearization>,
+}
+
+/// The decompression parameters from the linearization table in the maker note.
+struct Linearization {
+	/// The first vertical predictions of each CFA position, which every row continues from.
+	vertical_predictions: [[u16; 2]; 2],
+	/// The Huffman tree used, which is one of [`NIKON_TREES`].
+	tree: usize,
+	/// The row after which lossy files switch to the next Huffman tree, or 0 if they don't.
+	split_row: usize,
+	/// Maps the decoded values to the linear sensor values.
+	curve: Vec<u16>,
+	/// The number of entries of the curve which are in use.
+	curve_length: usize,
+}
+
+pub fn decode<R: Read + Seek>(ifd: Ifd, file: &mut TiffRead<R>) -> Result<RawImage, DecoderError> {
+	let raw_ifd = find_raw_ifd(&ifd, file)?;
+	let raw = raw_ifd.get_value::<NefRawIfd, _>(file)?;
+
+	let image_width: usize = raw.image_width.try_into()?;
+	let image_height: usize = raw.image_height.try_into()?;
+	let bits_per_sample: u32 = raw.bits_per_sample.into();
+
+	if raw.cfa_pattern_dim != [2, 2] {
+		return Err(DecoderError::UnsupportedData("NEF color filter array which is not a 2x2 grid"));
+	}
+	let cfa_pattern: [u8; 4] = raw.cfa_pattern.try_into().map_err(|_| TiffError::InvalidCount)?;
+
+	let (Some(&offset), Some(&length)) = (raw.strip_offsets.first(), raw.strip_byte_counts.first()) else {
+		return Err(TiffError::InvalidCount.into());
+	};
+	let data = read_bytes(file, DataBlock { offset, length })?;
+
+	let (maker_note, maker_note_endian) = match read_maker_note(&ifd, file) {
+		Ok((maker_note, endian)) => (Some(maker_note), endian),
+		Err(_) => (None, Endian::Big),
+	};
+
+	let (image, maximum) = match raw.compression {
+		CompressionValue::NikonNEFCompressed => {
+			let linearization_table = maker_note
+				.as_ref()
+				.and_then(|maker_note| maker_note.linearization.as_deref())
+				.ok_or(DecoderError::InvalidData("compressed NEF file without a linearization table"))?;
+			let linearization = Linearization::new(linearization_table, bits_per_sample, maker_note_endian)?;
+
+			let image = decode_compressed(&data, image_width, image_height, &linearization)?;
+			(image, linearization.curve[linearization.curve_length - 1])
+		}
+		CompressionValue::Uncompressed => {
+			// Some cameras store their uncompressed samples in 16-bit containers regardless of the bits per sample
+			let container_bits = if data.len() >= image_width * image_height * 2 { 16 } else { bits_per_sample };
+			let image = read_uncompressed(&data, image_width, image_height, container_bits, file.endian())?;
+			(image, ((1_u32 << bits_per_sample) - 1) as u16)
+		}
+		_ => return Err(DecoderError::UnsupportedData("NEF compression other than Nikon's lossless and lossy compression")),
+	};
+
+	// The maker note gives the black levels as 14-bit values, in the order of red, first green, second green, and blue
+	let black = maker_note.as_ref().and_then(|maker_note| maker_note.black_level).map_or([0; 4], |black_level| {
+		values_in_cfa_order(cfa_pattern, black_level.map(|level| level >> 14_u32.saturating_sub(bits_per_sample)))
+	});
+
+	let camera_white_balance = maker_note
+		.and_then(|maker_note| maker_note.white_balance)
+		.filter(|levels| levels.len() >= 2 && levels[0] > 0. && levels[1] > 0.)
+		.map(|levels| values_in_cfa_order(cfa_pattern, [levels[0], 1., 1., levels[1]]));
+
+	Ok(RawImage {
+		data: image,
+		width: image_width,
+		height: image_height,
+		cfa_pattern,
+		xtrans_pattern: None,
+		maximum,
+		black: SubtractBlack::CfaGrid(black),
+		orientation: OrientationValue::Horizontal,
+		camera_model: None,
+		camera_white_balance,
+		white_balance: None,
+		xyz_to_camera: None,
+		camera_to_rgb: None,
+	})
+}
+
+fn read_maker_note<R: Read + Seek>(ifd: &Ifd, file: &mut TiffRead<R>) -> Result<(<NikonMakerNote as Tag>::Output, Endian), TiffError> {
+	let exif_ifd = ifd.get_value::<ExifIfd, _>(file)?;
+	let block = exif_ifd.get_value::<MakerNote, _>(file)?;
+	let data = read_bytes(file, block)?;
+
+	if !data.starts_with(MAKER_NOTE_HEADER) || data.len() < MAKER_NOTE_TIFF_START {
+		return Err(TiffError::InvalidValue);
+	}
+
+	// Offsets within the maker note are relative to its own TIFF header
+	let mut maker_note_file = TiffRead::new(Cursor::new(&data[MAKER_NOTE_TIFF_START..]))?;
+	let maker_note_ifd = Ifd::new_first_ifd(&mut maker_note_file)?;
+	let maker_note = maker_note_ifd.get_value::<NikonMakerNote, _>(&mut maker_note_file)?;
+
+	Ok((maker_note, maker_note_file.endian()))
+}
+
+impl Linearization {
+	fn new(table: &[u8], bits_per_sample: u32, endian: Endian) -> Result<Self, DecoderError> {
+		let short_at = |index: usize| -> Result<u16, DecoderError> {
+			let bytes = table.get(index..index + 2).ok_or(DecoderError::InvalidData("NEF linearization table is too short"))?;
+			Ok(match endian {
+				Endian::Little => u16::from_le_bytes([bytes[0], bytes[1]]),
+				Endian::Big => u16::from_be_bytes([bytes[0], bytes[1]]),
+			})
+		};
+
+		let (&[version_0, version_1], _) = table.split_first_chunk().ok_or(DecoderError::InvalidData("NEF linearization table is too short"))?;
+		let lossless = version_0 == 0x46;
+		let tree = if lossless { 2 } else { 0 } + if bits_per_sample == 14 { 3 } else { 0 };
+
+		// Some versions put the predictions after a block of other data
+		let mut position = if version_0 == 0x49 || version_1 == 0x58 { 2 + 2110 } else { 2 };
+
+		let mut vertical_predictions = [[0; 2]; 2];
+		for prediction in vertical_predictions.iter_mut().flatten() {
+			*prediction = short_at(position)?;
+			position += 2;
+		}
+
+		let mut curve: Vec<u16> = (0..=u16::MAX).collect();
+		let mut curve_length = (1_usize << bits_per_sample) & 0x7fff;
+		let points = short_at(position)? as usize;
+		position += 2;
+
+		let step = if points > 1 { curve_length / (points - 1) } else { 0 };
+		let mut split_row = 0;
+
+		if version_0 == 0x44 && version_1 == 0x20 && step > 0 {
+			// Lossy files give points along the curve, which are linearly interpolated
+			for point in 0..points {
+				curve[point * step] = short_at(position)?;
+				position += 2;
+			}
+			for index in 0..curve_length {
+				let (base, remainder) = (index - index % step, index % step);
+				curve[index] = ((curve[base] as usize * (step - remainder) + curve[base + step] as usize * remainder) / step) as u16;
+			}
+
+			split_row = short_at(562)? as usize;
+		} else if !lossless && points <= 0x4001 {
+			for value in curve.iter_mut().take(points) {
+				*value = short_at(position)?;
+				position += 2;
+			}
+			curve_length = points;
+		}
+
+		// Trim the flat end of the curve, so its last entry gives the largest value
+		while curve_length > 2 && curve[curve_length - 2] == curve[curve_length - 1] {
+			curve_length -= 1;
+		}
+		if curve_length == 0 {
+			return Err(DecoderError::InvalidData("NEF linearization curve is empty"));
+		}
+
+		Ok(Self {
+			vertical_predictions,
+			tree,
+			split_row,
+			curve,
+			curve_length,
+		})
+	}
+}
+
+/// Decodes Nikon's compression, which predicts each sample from the previous sample of the same color in its row, or in its column for the first two columns.
+fn decode_compressed(data: &[u8], width: usize, height: usize, linearization: &Linearization) -> Result<Vec<u16>, DecoderError> {
+	let mut huffman_table = HuffmanTable::from_definition(NIKON_TREES[linearization.tree])?;
+	let mut bits = BitPump::new(data);
+
+	let mut vertical_predictions = linearization.vertical_predictions;
+	let mut horizontal_predictions = [0_u16; 2];
+	let mut image = Vec::with_capacity(width * height);
+
+	for row in 0..height {
+		if linearization.split_row > 0 && row == linearization.split_row {
+			huffman_table = HuffmanTable::from_definition(NIKON_TREES[linearization.tree + 1])?;
+		}
+
+		for column in 0..width {
+			let symbol = huffman_table.decode(&mut bits)? as u32;
+			let (length, shift) = (symbol & 15, symbol >> 4);
+
+			let difference = if length == 0 {
+				0
+			} else {
+				let value = (((bits.read(length.saturating_sub(shift)) << 1) + 1) << shift) >> 1;
+				let value = value as i32;
+				if value & (1 << (length - 1)) == 0 { value - (1 << length) + (shift == 0) as i32 } else { value }
+			};
+
+			let prediction = if column < 2 {
+				let prediction = &mut vertical_predictions[row & 1][column];
+				*prediction = prediction.wrapping_add(difference as u16);
+				horizontal_predictions[column] = *prediction;
+				*prediction
+			} else {
+				let prediction = &mut horizontal_predictions[column & 1];
+				*prediction = prediction.wrapping_add(difference as u16);
+				*prediction
+			};
+
+			image.push(linearization.curve[(prediction as i16).clamp(0, 0x3fff) as usize]);
+		}
+	}
+
+	Ok(image)
+}
@@ -0,0 +1,174 @@
+use super::read_uncompressed;
+use crate::metadata::identify::{CameraModel, identify_camera_model};
+use crate::tiff::file::TiffRead;
+use crate::tiff::tags::{FujiBitsPerSample, FujiBlackLevel, FujiRawHeight, FujiRawIfd, FujiRawWidth, FujiStripByteCount, FujiStripOffset, FujiWhiteBalance, Orientation, Tag};
+use crate::tiff::values::DataBlock;
+use crate::tiff::{Ifd, TiffError};
+use crate::{DecoderError, OrientationValue, RawImage, SubtractBlack};
+use rawkit_proc_macros::Tag;
+use std::io::{Cursor, Read, Seek, SeekFrom};
+
+const HEADER_LENGTH: usize = 108;
+/// The EXIF data of the embedded JPEG preview starts after its SOI marker, the APP1 marker and length, and the `Exif\0\0` identifier.
+const EXIF_START_IN_JPEG: u32 = 12;
+const MAX_EXIF_LENGTH: u32 = 0x10000;
+
+const CFA_HEADER_X_TRANS_PATTERN: u16 = 0x131;
+const CFA_HEADER_WHITE_BALANCE: u16 = 0x2ff0;
+
+#[allow(dead_code)]
+#[derive(Tag)]
+struct FujiRaw {
+	width: FujiRawWidth,
+	height: FujiRawHeight,
+	bits_per_sample: FujiBitsPerSample,
+	strip_offset: FujiStripOffset,
+	strip_byte_count: FujiStripByteCount,
+	black_level: Option<FujiBlackLevel>,
+	white_balance: Option<FujiWhiteBalance>,
+}
+
+/// The records of the CFA header, which describe the sensor of the camera.
+#[derive(Default)]
+struct CfaHeader {
+	xtrans_pattern: Option<[[u8; 6]; 6]>,
+	/// The white balance in the order of green, red, green, and blue.
+	white_balance: Option<[u16; 4]>,
+}
+
+pub fn decode<R: Read + Seek>(reader: &mut R) -> Result<RawImage, DecoderError> {
+	let mut header = [0; HEADER_LENGTH];
+	reader.rewind()?;
+	reader.read_exact(&mut header)?;
+
+	// The header has big-endian offsets and lengths of the embedded JPEG, the CFA header, and the CFA data
+	let header_u32 = |offset: usize| u32::from_be_bytes([header[offset], header[offset + 1], header[offset + 2], header[offset + 3]]);
+	let jpeg = DataBlock {
+		offset: header_u32(84),
+		length: header_u32(88),
+	};
+	let cfa_header = DataBlock {
+		offset: header_u32(92),
+		length: header_u32(96),
+	};
+	let cfa = DataBlock {
+		offset: header_u32(100),
+		length: header_u32(104),
+	};
+
+	let cfa_header = read_block(reader, cfa_header).map(|data| CfaHeader::parse(&data)).unwrap_or_default();
+	let (orientation, camera_model) = read_exif(reader, jpeg).unwrap_or((OrientationValue::Horizontal, None));
+	let camera_model = camera_model.unwrap_or_else(|| CameraModel {
+		make: "Fujifilm".to_string(),
+		model: String::from_utf8_lossy(&header[28..60]).trim_end_matches('\0').to_string(),
+	});
+
+	// Newer cameras store the CFA data with its metadata in a TIFF structure, whose offsets are relative to the start of the CFA data
+	let cfa = read_block(reader, cfa)?;
+	let mut cfa_file = TiffRead::new(Cursor::new(&cfa[..])).map_err(|_| DecoderError::UnsupportedData("Fujifilm raw data which is not stored in a TIFF structure"))?;
+	let raw_ifd = Ifd::new_first_ifd(&mut cfa_file)?.get_value::<FujiRawIfd, _>(&mut cfa_file)?;
+	let raw = raw_ifd.get_value::<FujiRaw, _>(&mut cfa_file)?;
+
+	let width: usize = raw.width.try_into()?;
+	let height: usize = raw.height.try_into()?;
+	let strip_offset: usize = raw.strip_offset.try_into()?;
+	let strip_byte_count: usize = raw.strip_byte_count.try_into()?;
+
+	let data = cfa.get(strip_offset..strip_offset + strip_byte_count).ok_or(TiffError::InvalidOffset)?;
+	if data.len() < width * height * 2 {
+		return Err(DecoderError::UnsupportedData("compressed Fujifilm raw data"));
+	}
+	let image = read_uncompressed(data, width, height, 16, cfa_file.endian())?;
+
+	// Fujifilm gives its per-channel values as red, green, green, and blue, which is also the order used alongside X-Trans patterns
+	let cfa_pattern = [0, 1, 1, 2];
+
+	let black = raw.black_level.filter(|levels| !levels.is_empty()).map_or(0, |levels| {
+		let sum: u64 = levels.iter().map(|&level| level as u64).sum();
+		(sum / levels.len() as u64) as u16
+	});
+
+	let camera_white_balance = match (raw.white_balance.as_deref(), cfa_header.white_balance) {
+		(Some(&[green, red, blue, ..]), _) if green > 0 && red > 0 && blue > 0 => Some([red, green, green, blue].map(f64::from)),
+		(_, Some([green, red, _, blue])) if green > 0 && red > 0 && blue > 0 => Some([red, green, green, blue].map(f64::from)),
+		_ => None,
+	};
+
+	Ok(RawImage {
+		data: image,
+		width,
+		height,
+		cfa_pattern,
+		xtrans_pattern: cfa_header.xtrans_pattern,
+		maximum: ((1_u32 << raw.bits_per_sample.min(16)) - 1) as u16,
+		black: SubtractBlack::CfaGrid([black; 4]),
+		orientation,
+		camera_model: Some(camera_model),
+		camera_white_balance,
+		white_balance: None,
+		xyz_to_camera: None,
+		camera_to_rgb: None,
+	})
+}
+
+fn read_block<R: Read + Seek>(reader: &mut R, block: DataBlock) -> Result<Vec<u8>, DecoderError> {
+	reader.seek(SeekFrom::Start(block.offset.into()))?;
+
+	let mut data = Vec::new();
+	reader.take(block.length.into()).read_to_end(&mut data)?;
+	if data.len() != block.length as usize {
+		return Err(TiffError::InvalidOffset.into());
+	}
+
+	Ok(data)
+}
+
+/// Reads the orientation and camera model from the EXIF data of the embedded JPEG preview.
+fn read_exif<R: Read + Seek>(reader: &mut R, jpeg: DataBlock) -> Result<(OrientationValue, Option<CameraModel>), DecoderError> {
+	let exif = DataBlock {
+		offset: jpeg.offset + EXIF_START_IN_JPEG,
+		length: jpeg.length.saturating_sub(EXIF_START_IN_JPEG).min(MAX_EXIF_LENGTH),
+	};
+	let exif = read_block(reader, exif)?;
+
+	let mut file = TiffRead::new(Cursor::new(&exif[..]))?;
+	let ifd = Ifd::new_first_ifd(&mut file)?;
+	let orientation = ifd.get_value::<Option<Orientation>, _>(&mut file)?.unwrap_or(OrientationValue::Horizontal);
+
+	Ok((orientation, identify_camera_model(&ifd, &mut file)))
+}
+
+impl CfaHeader {
+	/// Parses the big-endian records of the CFA header, each given as a tag and a length followed by its data.
+	fn parse(data: &[u8]) -> Self {
+		let mut header = Self::default();
+
+		let Some(&[a, b, c, d]) = data.first_chunk() else { return header };
+		let count = u32::from_be_bytes([a, b, c, d]);
+
+		let mut position = 4;
+		for _ in 0..count {
+			let Some(&[tag_high, tag_low, length_high, length_low]) = data.get(position..).and_then(|data| data.first_chunk()) else {
+				break;
+			};
+			let tag = u16::from_be_bytes([tag_high, tag_low]);
+			let length = u16::from_be_bytes([length_high, length_low]) as usize;
+
+			let Some(record) = data.get(position + 4..position + 4 + length) else { break };
+			position += 4 + length;
+
+			match tag {
+				// The pattern is stored in reverse, from the last position of the grid to the first
+				CFA_HEADER_X_TRANS_PATTERN if length >= 36 => {
+					header.xtrans_pattern = Some(std::array::from_fn(|row| std::array::from_fn(|column| record[35 - (row * 6 + column)] & 3)));
+				}
+				CFA_HEADER_WHITE_BALANCE if length >= 8 => {
+					header.white_balance = Some(std::array::from_fn(|index| u16::from_be_bytes([record[2 * index], record[2 * index + 1]])));
+				}
+				_ => {}
+			}
+		}
+
+		header
+	}
+}
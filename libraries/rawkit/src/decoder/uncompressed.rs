@@ -2,7 +2,7 @@ use crate::tiff::file::TiffRead;
 use crate::tiff::tags::{BitsPerSample, BlackLevel, CfaPattern, CfaPatternDim, Compression, ImageLength, ImageWidth, RowsPerStrip, StripByteCounts, StripOffsets, Tag, WhiteBalanceRggbLevels};
 use crate::tiff::values::CompressionValue;
 use crate::tiff::{Ifd, TiffError};
-use crate::{OrientationValue, RawImage, SubtractBlack};
+use crate::{DecoderError, OrientationValue, RawImage, SubtractBlack};
 use rawkit_proc_macros::Tag;
 use std::io::{Read, Seek};
 
@@ -22,46 +22,56 @@ struct ArwUncompressedIfd {
 	white_balance_levels: Option<WhiteBalanceRggbLevels>,
 }
 
-pub fn decode<R: Read + Seek>(ifd: Ifd, file: &mut TiffRead<R>) -> RawImage {
-	let ifd = ifd.get_value::<ArwUncompressedIfd, _>(file).unwrap();
+pub fn decode<R: Read + Seek>(ifd: Ifd, file: &mut TiffRead<R>) -> Result<RawImage, DecoderError> {
+	let ifd = ifd.get_value::<ArwUncompressedIfd, _>(file)?;
 
-	assert!(ifd.strip_offsets.len() == ifd.strip_byte_counts.len());
-	assert!(ifd.strip_offsets.len() == 1);
-	assert!(ifd.compression == CompressionValue::Uncompressed);
+	if ifd.strip_offsets.len() != 1 || ifd.strip_byte_counts.len() != 1 {
+		return Err(DecoderError::UnsupportedData("uncompressed image which isn't stored in a single strip"));
+	}
+	if ifd.compression != CompressionValue::Uncompressed {
+		return Err(DecoderError::InvalidData("uncompressed image with a compression method"));
+	}
 
-	let image_width: usize = ifd.image_width.try_into().unwrap();
-	let image_height: usize = ifd.image_height.try_into().unwrap();
-	let rows_per_strip: usize = ifd.rows_per_strip.try_into().unwrap();
+	let image_width: usize = ifd.image_width.try_into()?;
+	let image_height: usize = ifd.image_height.try_into()?;
+	let rows_per_strip: usize = ifd.rows_per_strip.try_into()?;
 	let bits_per_sample: usize = ifd.bits_per_sample.into();
-	let [cfa_pattern_width, cfa_pattern_height] = ifd.cfa_pattern_dim;
-	assert!(cfa_pattern_width == 2 && cfa_pattern_height == 2);
+	if bits_per_sample > 16 {
+		return Err(DecoderError::UnsupportedData("uncompressed bit depth above 16 bits"));
+	}
+	if ifd.cfa_pattern_dim != [2, 2] {
+		return Err(DecoderError::UnsupportedData("uncompressed color filter array which is not a 2x2 grid"));
+	}
+	let cfa_pattern: [u8; 4] = ifd.cfa_pattern.try_into().map_err(|_| TiffError::InvalidCount)?;
 
 	let mut image: Vec<u16> = Vec::with_capacity(image_height * image_width);
 
 	for i in 0..ifd.strip_offsets.len() {
-		file.seek_from_start(ifd.strip_offsets[i]).unwrap();
+		file.seek_from_start(ifd.strip_offsets[i])?;
 
 		let last = i == ifd.strip_offsets.len();
 		let rows = if last { image_height % rows_per_strip } else { rows_per_strip };
 
 		for _ in 0..rows {
 			for _ in 0..image_width {
-				image.push(file.read_u16().unwrap());
+				image.push(file.read_u16()?);
 			}
 		}
 	}
 
-	RawImage {
+	Ok(RawImage {
 		data: image,
 		width: image_width,
 		height: image_height,
-		cfa_pattern: ifd.cfa_pattern.try_into().unwrap(),
+		cfa_pattern,
+		xtrans_pattern: None,
 		maximum: if bits_per_sample == 16 { u16::MAX } else { (1 << bits_per_sample) - 1 },
 		black: SubtractBlack::CfaGrid(ifd.black_level),
 		orientation: OrientationValue::Horizontal,
 		camera_model: None,
 		camera_white_balance: ifd.white_balance_levels.map(|arr| arr.map(|x| x as f64)),
 		white_balance: None,
+		xyz_to_camera: None,
 		camera_to_rgb: None,
-	}
+	})
 }
@@ -0,0 +1,185 @@
+use super::clip;
+use crate::metadata::camera_data::RGB_TO_XYZ;
+use crate::{CHANNELS_IN_RGB, RawImage};
+
+/// The rows and columns at each edge of the image where the green interpolation would reach outside of it.
+const BORDER: usize = 2;
+/// The image is processed in square tiles of this size, to bound the memory of the intermediate images.
+const TILE_SIZE: usize = 256;
+/// The pixels around each tile which are interpolated along with it, since the homogeneity of each pixel depends on its neighbors.
+const TILE_MARGIN: usize = 3;
+
+/// The D65 white point, which normalizes the XYZ values before converting them to CIELAB.
+const D65_WHITE: [f64; 3] = [0.950456, 1., 1.088754];
+
+/// The horizontal and vertical interpolations of a tile, along with their homogeneity.
+struct Candidates {
+	rgb: [Vec<[i32; CHANNELS_IN_RGB]>; 2],
+	lab: [Vec<[f32; 3]>; 2],
+	homogeneity: [Vec<u8>; 2],
+}
+
+impl RawImage {
+	/// Refines a linearly interpolated image with Adaptive Homogeneity-Directed demosaicing, which interpolates each pixel both horizontally and vertically,
+	/// and keeps whichever interpolation gives the more homogeneous colors in its neighborhood.
+	pub(crate) fn ahd_demosaic(&self, image: &mut [u16]) {
+		let (width, height) = (self.width, self.height);
+		if width <= 2 * (BORDER + TILE_MARGIN) || height <= 2 * (BORDER + TILE_MARGIN) {
+			return;
+		}
+
+		let camera_to_xyz = self.camera_to_xyz();
+
+		for top in (BORDER..height - BORDER).step_by(TILE_SIZE) {
+			for left in (BORDER..width - BORDER).step_by(TILE_SIZE) {
+				self.ahd_demosaic_tile(image, top, left, &camera_to_xyz);
+			}
+		}
+	}
+
+	fn ahd_demosaic_tile(&self, image: &mut [u16], top: usize, left: usize, camera_to_xyz: &[[f64; 3]; 3]) {
+		let width = self.width;
+
+		// The region which is interpolated, extended by the margin but kept away from the border
+		let rows = top.saturating_sub(TILE_MARGIN).max(BORDER)..(top + TILE_SIZE + TILE_MARGIN).min(self.height - BORDER);
+		let columns = left.saturating_sub(TILE_MARGIN).max(BORDER)..(left + TILE_SIZE + TILE_MARGIN).min(width - BORDER);
+		let (tile_width, tile_height) = (columns.len(), rows.len());
+		let tile_index = |row: usize, column: usize| (row - rows.start) * tile_width + (column - columns.start);
+
+		let raw = |row: usize, column: usize| self.data[row * width + column] as i32;
+
+		// Interpolate green horizontally and vertically, correcting the average of the neighbors with the curvature of the pixel's own color
+		let mut green = [vec![0; tile_width * tile_height], vec![0; tile_width * tile_height]];
+		for row in rows.clone() {
+			for column in columns.clone() {
+				let color = self.cfa_color(row, column);
+				let index = tile_index(row, column);
+				if color == 1 {
+					green[0][index] = raw(row, column);
+					green[1][index] = raw(row, column);
+					continue;
+				}
+
+				let center = raw(row, column);
+				let interpolate = |previous: i32, next: i32, far_previous: i32, far_next: i32| {
+					let value = ((previous + center + next) * 2 - far_previous - far_next) >> 2;
+					value.clamp(previous.min(next), previous.max(next))
+				};
+				green[0][index] = interpolate(raw(row, column - 1), raw(row, column + 1), raw(row, column - 2), raw(row, column + 2));
+				green[1][index] = interpolate(raw(row - 1, column), raw(row + 1, column), raw(row - 2, column), raw(row + 2, column));
+			}
+		}
+
+		let mut candidates = Candidates {
+			rgb: [vec![[0; CHANNELS_IN_RGB]; tile_width * tile_height], vec![[0; CHANNELS_IN_RGB]; tile_width * tile_height]],
+			lab: [vec![[0.; 3]; tile_width * tile_height], vec![[0.; 3]; tile_width * tile_height]],
+			homogeneity: [vec![0; tile_width * tile_height], vec![0; tile_width * tile_height]],
+		};
+
+		// Interpolate red and blue from their differences to each of the green interpolations, and convert the results to CIELAB
+		for direction in 0..2 {
+			let green = &green[direction];
+			let difference = |row: usize, column: usize| raw(row, column) - green[tile_index(row, column)];
+
+			for row in rows.start + 1..rows.end - 1 {
+				for column in columns.start + 1..columns.end - 1 {
+					let own_color = self.cfa_color(row, column);
+					let index = tile_index(row, column);
+
+					let mut rgb = [0; CHANNELS_IN_RGB];
+					rgb[1] = green[index];
+					if own_color == 1 {
+						let horizontal_color = self.cfa_color(row, column + 1);
+						let horizontal = difference(row, column - 1) + difference(row, column + 1);
+						let vertical = difference(row - 1, column) + difference(row + 1, column);
+						rgb[horizontal_color] = rgb[1] + horizontal / 2;
+						rgb[2 - horizontal_color] = rgb[1] + vertical / 2;
+					} else {
+						let color = 2 - own_color;
+						let diagonals = difference(row - 1, column - 1) + difference(row - 1, column + 1) + difference(row + 1, column - 1) + difference(row + 1, column + 1);
+						rgb[own_color] = raw(row, column);
+						rgb[color] = rgb[1] + diagonals / 4;
+					}
+					let rgb = rgb.map(|value| clip(value) as i32);
+
+					candidates.rgb[direction][index] = rgb;
+					candidates.lab[direction][index] = cielab(rgb, camera_to_xyz);
+				}
+			}
+		}
+
+		// Count the neighbors of each pixel whose colors are within the smaller of the horizontal and vertical interpolations' own variation
+		const NEIGHBORS: [(isize, isize); 4] = [(0, -1), (0, 1), (-1, 0), (1, 0)];
+		for row in rows.start + 2..rows.end - 2 {
+			for column in columns.start + 2..columns.end - 2 {
+				let index = tile_index(row, column);
+
+				let mut lightness_differences = [[0.; 4]; 2];
+				let mut chroma_differences = [[0.; 4]; 2];
+				for direction in 0..2 {
+					let lab = &candidates.lab[direction];
+					let [l, a, b] = lab[index];
+					for (neighbor, &(row_offset, column_offset)) in NEIGHBORS.iter().enumerate() {
+						let [neighbor_l, neighbor_a, neighbor_b] = lab[tile_index(row.wrapping_add_signed(row_offset), column.wrapping_add_signed(column_offset))];
+						lightness_differences[direction][neighbor] = (l - neighbor_l).abs();
+						chroma_differences[direction][neighbor] = (a - neighbor_a).powi(2) + (b - neighbor_b).powi(2);
+					}
+				}
+
+				let epsilon = |differences: &[[f32; 4]; 2]| differences[0][0].max(differences[0][1]).min(differences[1][2].max(differences[1][3]));
+				let lightness_epsilon = epsilon(&lightness_differences);
+				let chroma_epsilon = epsilon(&chroma_differences);
+
+				for direction in 0..2 {
+					candidates.homogeneity[direction][index] = (0..NEIGHBORS.len())
+						.filter(|&neighbor| lightness_differences[direction][neighbor] <= lightness_epsilon && chroma_differences[direction][neighbor] <= chroma_epsilon)
+						.count() as u8;
+				}
+			}
+		}
+
+		// Pick the interpolation with the greater homogeneity over each pixel's 3x3 neighborhood, or average both if they are equally homogeneous
+		let output_rows = top.max(rows.start + TILE_MARGIN)..(top + TILE_SIZE).min(rows.end - TILE_MARGIN);
+		let output_columns = left.max(columns.start + TILE_MARGIN)..(left + TILE_SIZE).min(columns.end - TILE_MARGIN);
+		for row in output_rows {
+			for column in output_columns.clone() {
+				let [horizontal, vertical] = [0, 1].map(|direction| {
+					let homogeneity = &candidates.homogeneity[direction];
+					(row - 1..=row + 1)
+						.flat_map(|row| (column - 1..=column + 1).map(move |column| (row, column)))
+						.map(|(row, column)| homogeneity[tile_index(row, column)] as u32)
+						.sum::<u32>()
+				});
+
+				let index = tile_index(row, column);
+				let [horizontal_rgb, vertical_rgb] = [candidates.rgb[0][index], candidates.rgb[1][index]];
+				let rgb = match horizontal.cmp(&vertical) {
+					std::cmp::Ordering::Greater => horizontal_rgb,
+					std::cmp::Ordering::Less => vertical_rgb,
+					std::cmp::Ordering::Equal => std::array::from_fn(|color| (horizontal_rgb[color] + vertical_rgb[color]) / 2),
+				};
+
+				let pixel_index = CHANNELS_IN_RGB * (row * width + column);
+				image[pixel_index..pixel_index + CHANNELS_IN_RGB].copy_from_slice(&rgb.map(clip));
+			}
+		}
+	}
+
+	/// The matrix from the camera's color space to XYZ, which falls back to treating the camera's colors as linear sRGB.
+	fn camera_to_xyz(&self) -> [[f64; 3]; 3] {
+		let camera_to_rgb = self.camera_to_rgb.unwrap_or([[1., 0., 0.], [0., 1., 0.], [0., 0., 1.]]);
+
+		std::array::from_fn(|row| std::array::from_fn(|column| (0..3).map(|k| RGB_TO_XYZ[row][k] * camera_to_rgb[k][column]).sum()))
+	}
+}
+
+/// Converts a pixel from the camera's color space to CIELAB, where distances approximate perceived color differences.
+fn cielab(rgb: [i32; CHANNELS_IN_RGB], camera_to_xyz: &[[f64; 3]; 3]) -> [f32; 3] {
+	let [x, y, z] = std::array::from_fn(|row| {
+		let value = (0..CHANNELS_IN_RGB).map(|column| camera_to_xyz[row][column] * rgb[column] as f64).sum::<f64>() / u16::MAX as f64 / D65_WHITE[row];
+
+		if value > 0.008856 { value.cbrt() } else { 7.787 * value + 16. / 116. }
+	});
+
+	[(116. * y - 16.) as f32, (500. * (x - y)) as f32, (200. * (y - z)) as f32]
+}
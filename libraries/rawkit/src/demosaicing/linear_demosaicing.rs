@@ -14,14 +14,16 @@ fn average(data: &[u16], indexes: impl Iterator<Item = i64>) -> u16 {
 }
 
 impl RawImage {
-	pub fn linear_demosaic_iter(&self) -> impl Iterator<Item = Pixel> + use<'_> {
-		match self.cfa_pattern {
-			[0, 1, 1, 2] => self.linear_demosaic_rggb_iter(),
-			_ => todo!(),
-		}
+	/// Interpolates the Bayer color filter array by averaging the neighboring pixels of each color.
+	///
+	/// Only [`RawImage::demosaic`] calls this, after it has sent X-Trans images to their own interpolation.
+	pub(crate) fn linear_demosaic_iter(&self) -> impl Iterator<Item = Pixel> + use<'_> {
+		// Every Bayer pattern is RGGB when starting from its red pixel, so the other patterns are offset to start from there
+		let red_index = self.cfa_pattern.iter().position(|&color| color == 0).unwrap_or(0) as i64;
+		self.linear_demosaic_rggb_iter(red_index / 2, red_index % 2)
 	}
 
-	fn linear_demosaic_rggb_iter(&self) -> impl Iterator<Item = Pixel> + use<'_> {
+	fn linear_demosaic_rggb_iter(&self, row_offset: i64, column_offset: i64) -> impl Iterator<Item = Pixel> + use<'_> {
 		let width = self.width as i64;
 		let height = self.height as i64;
 
@@ -37,7 +39,7 @@ impl RawImage {
 				let diagonal_indexes = [pixel_index + width + 1, pixel_index - width + 1, pixel_index + width - 1, pixel_index - width - 1];
 
 				let pixel_index = pixel_index as usize;
-				match (row % 2 == 0, column % 2 == 0) {
+				match ((row + row_offset) % 2 == 0, (column + column_offset) % 2 == 0) {
 					(true, true) => Pixel {
 						values: [
 							self.data[pixel_index],
@@ -1 +1,67 @@
+pub mod ahd;
 pub mod linear_demosaicing;
+pub mod ppg;
+pub mod vng;
+pub mod x_trans;
+
+use crate::{CHANNELS_IN_RGB, RawImage};
+
+/// The algorithm used to interpolate the two missing colors at each pixel of the color filter array.
+///
+/// The algorithms other than [`Demosaicing::Linear`] apply to Bayer sensors. Fujifilm's X-Trans sensors always use their own interpolation.
+#[derive(Copy, Clone, Debug, Default, PartialEq, Eq)]
+pub enum Demosaicing {
+	/// Averages the neighboring pixels of each color. It is the fastest, but produces zippering along edges and color moiré on fine detail.
+	#[default]
+	Linear,
+	/// Patterned Pixel Grouping, which interpolates along the direction with the smaller gradient.
+	Ppg,
+	/// Threshold-based Variable Number of Gradients, which interpolates from the neighbors in every direction whose gradient is small.
+	Vng,
+	/// Adaptive Homogeneity-Directed demosaicing, which interpolates horizontally and vertically and picks whichever gives the more homogeneous colors.
+	/// It is the slowest, but gives the fewest artifacts.
+	Ahd,
+}
+
+impl RawImage {
+	/// The color of the color filter array at the given position, as 0, 1, and 2 for Red, Green, and Blue.
+	pub fn cfa_color(&self, row: usize, column: usize) -> usize {
+		match self.xtrans_pattern {
+			Some(pattern) => pattern[row % 6][column % 6] as usize,
+			None => self.cfa_pattern[2 * (row % 2) + (column % 2)] as usize,
+		}
+	}
+
+	/// Interpolates the full RGB image from the raw data, stored in a linear fashion with [`CHANNELS_IN_RGB`] values per pixel.
+	pub fn demosaic(&self, demosaicing: Demosaicing) -> Vec<u16> {
+		if self.xtrans_pattern.is_some() {
+			return self.x_trans_demosaic();
+		}
+
+		// The other algorithms refine the linear interpolation, which also fills in the borders they leave out
+		let mut image = vec![0; self.width * self.height * CHANNELS_IN_RGB];
+		for pixel in self.linear_demosaic_iter() {
+			let pixel_index = pixel.row * self.width + pixel.column;
+			image[CHANNELS_IN_RGB * pixel_index..CHANNELS_IN_RGB * (pixel_index + 1)].copy_from_slice(&pixel.values);
+		}
+
+		match demosaicing {
+			Demosaicing::Linear => {}
+			Demosaicing::Ppg => self.ppg_demosaic(&mut image),
+			Demosaicing::Vng => self.vng_demosaic(&mut image),
+			Demosaicing::Ahd => self.ahd_demosaic(&mut image),
+		}
+
+		image
+	}
+}
+
+/// Reads a color of the pixel at an offset from the given position, in an image with [`CHANNELS_IN_RGB`] values per pixel.
+fn channel(image: &[u16], width: usize, row: usize, column: usize, (row_offset, column_offset): (isize, isize), color: usize) -> i32 {
+	let (row, column) = (row.wrapping_add_signed(row_offset), column.wrapping_add_signed(column_offset));
+	image[CHANNELS_IN_RGB * (row * width + column) + color] as i32
+}
+
+fn clip(value: i32) -> u16 {
+	value.clamp(0, u16::MAX as i32) as u16
+}
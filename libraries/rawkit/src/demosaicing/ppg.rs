@@ -0,0 +1,90 @@
+use super::{channel, clip};
+use crate::{CHANNELS_IN_RGB, RawImage};
+
+/// The rows and columns at each edge of the image which are left with their linear interpolation, since the gradients reach three pixels away.
+const BORDER: usize = 3;
+
+impl RawImage {
+	/// Refines a linearly interpolated image with Patterned Pixel Grouping, which first interpolates green along the direction with the smaller gradient,
+	/// and then interpolates red and blue from their differences to green.
+	pub(crate) fn ppg_demosaic(&self, image: &mut [u16]) {
+		let (width, height) = (self.width, self.height);
+		if width <= 2 * BORDER || height <= 2 * BORDER {
+			return;
+		}
+
+		// Fill in green at the red and blue pixels, from whichever of the horizontal and vertical neighbors differ the least
+		for row in BORDER..height - BORDER {
+			for column in BORDER..width - BORDER {
+				let color = self.cfa_color(row, column);
+				if color == 1 {
+					continue;
+				}
+
+				let at = |step: isize, (row_step, column_step): (isize, isize), color: usize| channel(image, width, row, column, (step * row_step, step * column_step), color);
+				let center = at(0, (0, 0), color);
+
+				let [horizontal, vertical] = [(0, 1), (1, 0)].map(|direction| {
+					let guess = (at(-1, direction, 1) + center + at(1, direction, 1)) * 2 - at(-2, direction, color) - at(2, direction, color);
+					let difference = ((at(-2, direction, color) - center).abs() + (at(2, direction, color) - center).abs() + (at(-1, direction, 1) - at(1, direction, 1)).abs()) * 3
+						+ ((at(3, direction, 1) - at(1, direction, 1)).abs() + (at(-3, direction, 1) - at(-1, direction, 1)).abs()) * 2;
+					let (previous, next) = (at(-1, direction, 1), at(1, direction, 1));
+					((guess >> 2).clamp(previous.min(next), previous.max(next)), difference)
+				});
+				let green = if horizontal.1 <= vertical.1 { horizontal.0 } else { vertical.0 };
+
+				image[CHANNELS_IN_RGB * (row * width + column) + 1] = clip(green);
+			}
+		}
+
+		// Fill in red and blue at the green pixels, from the neighbors of each color and their differences to green
+		for row in BORDER..height - BORDER {
+			for column in BORDER..width - BORDER {
+				if self.cfa_color(row, column) != 1 {
+					continue;
+				}
+
+				let at = |offset: (isize, isize), color: usize| channel(image, width, row, column, offset, color);
+				let green = at((0, 0), 1);
+				let horizontal_color = self.cfa_color(row, column + 1);
+
+				let values = [(horizontal_color, (0, 1)), (2 - horizontal_color, (1, 0))].map(|(color, (row_step, column_step))| {
+					let (previous, next) = ((-row_step, -column_step), (row_step, column_step));
+					(color, (at(previous, color) + at(next, color) + 2 * green - at(previous, 1) - at(next, 1)) >> 1)
+				});
+
+				for (color, value) in values {
+					image[CHANNELS_IN_RGB * (row * width + column) + color] = clip(value);
+				}
+			}
+		}
+
+		// Fill in blue at the red pixels and red at the blue pixels, from the diagonal with the smaller gradient
+		for row in BORDER..height - BORDER {
+			for column in BORDER..width - BORDER {
+				let color = self.cfa_color(row, column);
+				if color == 1 {
+					continue;
+				}
+				let color = 2 - color;
+
+				let at = |offset: (isize, isize), color: usize| channel(image, width, row, column, offset, color);
+				let green = at((0, 0), 1);
+
+				let [falling, rising] = [(1, 1), (1, -1)].map(|(row_step, column_step)| {
+					let (previous, next) = ((-row_step, -column_step), (row_step, column_step));
+					let guess = at(previous, color) + at(next, color) + 2 * green - at(previous, 1) - at(next, 1);
+					let difference = (at(previous, color) - at(next, color)).abs() + (at(previous, 1) - green).abs() + (at(next, 1) - green).abs();
+					(guess, difference)
+				});
+				let value = match falling.1.cmp(&rising.1) {
+					std::cmp::Ordering::Less => falling.0 >> 1,
+					std::cmp::Ordering::Greater => rising.0 >> 1,
+					std::cmp::Ordering::Equal => (falling.0 + rising.0) >> 2,
+				};
+
+				image[CHANNELS_IN_RGB * (row * width + column) + color] = clip(value);
+			}
+		}
+	}
+}
@@ -0,0 +1,89 @@
+use super::clip;
+use crate::{CHANNELS_IN_RGB, RawImage};
+
+/// The rows and columns at each edge of the image which are left with their linear interpolation, since the gradients reach two pixels away.
+const BORDER: usize = 2;
+
+/// The north, south, west, east, northwest, northeast, southwest, and southeast directions, as row and column steps.
+const DIRECTIONS: [(isize, isize); 8] = [(-1, 0), (1, 0), (0, -1), (0, 1), (-1, -1), (-1, 1), (1, -1), (1, 1)];
+
+impl RawImage {
+	/// Refines a linearly interpolated image with threshold-based Variable Number of Gradients, which measures the gradient in eight directions around each pixel,
+	/// and interpolates the missing colors from the directions whose gradient falls below a threshold.
+	pub(crate) fn vng_demosaic(&self, image: &mut [u16]) {
+		let (width, height) = (self.width, self.height);
+		if width <= 2 * BORDER || height <= 2 * BORDER {
+			return;
+		}
+
+		for row in BORDER..height - BORDER {
+			for column in BORDER..width - BORDER {
+				let raw = |(row_offset, column_offset): (isize, isize)| {
+					let (row, column) = (row.wrapping_add_signed(row_offset), column.wrapping_add_signed(column_offset));
+					(self.data[row * width + column] as i32, self.cfa_color(row, column))
+				};
+				let difference = |a: (isize, isize), b: (isize, isize)| (raw(a).0 - raw(b).0).abs();
+
+				let own_color = self.cfa_color(row, column);
+				let center = raw((0, 0)).0;
+
+				// Each gradient is doubled so the half-weighted differences stay integers
+				let mut gradients = [0; DIRECTIONS.len()];
+				let mut samples = [[(0, 0); 6]; DIRECTIONS.len()];
+				for (index, &(y, x)) in DIRECTIONS.iter().enumerate() {
+					let main = (difference((y, x), (-y, -x)) + difference((2 * y, 2 * x), (0, 0))) * 2;
+
+					if y == 0 || x == 0 {
+						// The perpendicular step, for the differences along the parallel lines on both sides
+						let (py, px) = (x, y);
+						gradients[index] = main
+							+ difference((y + py, x + px), (-y + py, -x + px))
+							+ difference((y - py, x - px), (-y - py, -x - px))
+							+ difference((2 * y + py, 2 * x + px), (py, px))
+							+ difference((2 * y - py, 2 * x - px), (-py, -px));
+						samples[index] = [(y, x), (2 * y, 2 * x), (y + py, x + px), (y - py, x - px), (2 * y + py, 2 * x + px), (2 * y - py, 2 * x - px)];
+					} else {
+						gradients[index] = main + difference((y, 0), (0, -x)) + difference((0, x), (-y, 0)) + difference((2 * y, x), (y, 0)) + difference((y, 2 * x), (0, x));
+						samples[index] = [(y, x), (2 * y, 2 * x), (y, 0), (0, x), (2 * y, x), (y, 2 * x)];
+					}
+				}
+
+				let minimum = *gradients.iter().min().unwrap();
+				let maximum = *gradients.iter().max().unwrap();
+				// The threshold is 1.5 times the smallest gradient plus half of the range, doubled like the gradients
+				let threshold = 3 * minimum + (maximum - minimum);
+
+				// Sum the average of each color in the directions below the threshold, using the center for its own color if a direction lacks it
+				let mut totals = [0.; CHANNELS_IN_RGB];
+				let mut directions = 0;
+				for (&gradient, samples) in gradients.iter().zip(&samples) {
+					if 2 * gradient > threshold {
+						continue;
+					}
+
+					let mut sums = [0; CHANNELS_IN_RGB];
+					let mut counts = [0; CHANNELS_IN_RGB];
+					for &offset in samples {
+						let (value, color) = raw(offset);
+						sums[color] += value;
+						counts[color] += 1;
+					}
+					sums[own_color] += center;
+					counts[own_color] += 1;
+
+					for color in 0..CHANNELS_IN_RGB {
+						let value = if counts[color] > 0 { sums[color] as f64 / counts[color] as f64 } else { center as f64 };
+						totals[color] += value;
+					}
+					directions += 1;
+				}
+
+				let pixel = &mut image[CHANNELS_IN_RGB * (row * width + column)..CHANNELS_IN_RGB * (row * width + column + 1)];
+				for color in (0..CHANNELS_IN_RGB).filter(|&color| color != own_color) {
+					let value = center as f64 + (totals[color] - totals[own_color]) / directions as f64;
+					pixel[color] = clip(value.round() as i32);
+				}
+			}
+		}
+	}
+}
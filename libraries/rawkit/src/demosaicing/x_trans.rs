@@ -0,0 +1,64 @@
+use crate::{CHANNELS_IN_RGB, RawImage};
+
+/// The offsets to the pixels of one color around a position of the X-Trans pattern.
+type Neighbors = Vec<(isize, isize)>;
+
+impl RawImage {
+	/// Interpolates an image from an X-Trans sensor by averaging the nearest pixels of each missing color.
+	///
+	/// Every color appears within the 3x3 neighborhood of almost every position of the 6x6 pattern, but the 5x5 neighborhood is used where it doesn't,
+	/// and at the edges of the image where the nearest pixels fall outside of it.
+	pub(crate) fn x_trans_demosaic(&self) -> Vec<u16> {
+		let pattern = self.xtrans_pattern.expect("The image should have an X-Trans pattern");
+		let (width, height) = (self.width, self.height);
+
+		let neighbors_within = |row: usize, column: usize, color: u8, radius: isize| -> Neighbors {
+			(-radius..=radius)
+				.flat_map(|row_offset| (-radius..=radius).map(move |column_offset| (row_offset, column_offset)))
+				.filter(|&offset| offset != (0, 0))
+				.filter(|&(row_offset, column_offset)| pattern[(row as isize + row_offset).rem_euclid(6) as usize][(column as isize + column_offset).rem_euclid(6) as usize] == color)
+				.collect()
+		};
+		let neighbors: [[[(Neighbors, Neighbors); CHANNELS_IN_RGB]; 6]; 6] = std::array::from_fn(|row| {
+			std::array::from_fn(|column| {
+				std::array::from_fn(|color| {
+					let nearest = neighbors_within(row, column, color as u8, 1);
+					let wider = neighbors_within(row, column, color as u8, 2);
+					(if nearest.is_empty() { wider.clone() } else { nearest }, wider)
+				})
+			})
+		});
+
+		let average = |row: usize, column: usize, offsets: &Neighbors| {
+			let (sum, count) = offsets
+				.iter()
+				.filter_map(|&(row_offset, column_offset)| {
+					let row = row.checked_add_signed(row_offset).filter(|&row| row < height)?;
+					let column = column.checked_add_signed(column_offset).filter(|&column| column < width)?;
+					Some(self.data[row * width + column] as u32)
+				})
+				.fold((0, 0), |(sum, count), value| (sum + value, count + 1));
+
+			(count > 0).then(|| ((sum + count / 2) / count) as u16)
+		};
+
+		let mut image = vec![0; width * height * CHANNELS_IN_RGB];
+		for row in 0..height {
+			for column in 0..width {
+				let own_color = pattern[row % 6][column % 6] as usize;
+				let pixel = &mut image[CHANNELS_IN_RGB * (row * width + column)..CHANNELS_IN_RGB * (row * width + column + 1)];
+
+				for (color, value) in pixel.iter_mut().enumerate() {
+					*value = if color == own_color {
+						self.data[row * width + column]
+					} else {
+						let (nearest, wider) = &neighbors[row % 6][column % 6][color];
+						average(row, column, nearest).or_else(|| average(row, column, wider)).unwrap_or_default()
+					};
+				}
+			}
+		}
+
+		image
+	}
+}
@@ -0,0 +1,239 @@
+use crate::demosaicing::Demosaicing;
+use crate::postprocessing::transform::inverse_orientation_index;
+use crate::preprocessing::scale_white_balance::white_balance_index;
+use crate::{CHANNELS_IN_RGB, Image, OrientationValue, RawImage, RawPixel, SubtractBlack};
+
+/// The inverse of the conversion matrix from linear sRGB to XYZ.
+const XYZ_TO_RGB: [[f64; 3]; 3] = [
+	// Matrix:
+	[3.240479, -1.537150, -0.498535],
+	[-0.969256, 1.875992, 0.041556],
+	[0.055648, -0.204043, 1.057311],
+];
+
+/// The distance from the Planckian locus, in the CIE 1960 UCS, of one unit of tint. This matches the scale used by Adobe's DNG SDK.
+const TINT_SCALE: f64 = 1. / 3000.;
+
+/// The range of color temperatures in Kelvin which are covered by the approximation of the Planckian locus.
+const TEMPERATURE_RANGE: (f64, f64) = (1667., 25000.);
+
+/// How the channels of the camera are balanced so that neutral surfaces appear gray.
+#[derive(Copy, Clone, Debug, Default, PartialEq)]
+pub enum WhiteBalance {
+	/// The white balance recorded by the camera, or otherwise the one calculated by [`RawImage::calculate_conversion_matrices`].
+	#[default]
+	AsShot,
+	/// The white balance which neutralizes a light of the given color temperature in Kelvin and tint, like the sliders of photo editors.
+	/// Higher temperatures make the image warmer, and positive tints compensate for a green light, making the image more magenta.
+	Temperature { temperature: f64, tint: f64 },
+	/// Multipliers for the red, green, and blue channels of the camera.
+	Multipliers([f64; CHANNELS_IN_RGB]),
+}
+
+/// How the highlights are treated where some of the camera's channels have reached their maximum value.
+#[derive(Copy, Clone, Debug, Default, PartialEq, Eq)]
+pub enum HighlightRecovery {
+	/// Clips every channel at the level of the first one to saturate, which turns the highlights neutral white.
+	#[default]
+	Clip,
+	/// Leaves the channels which haven't saturated as they are, which keeps more detail but may tint the highlights.
+	Unclip,
+	/// Keeps the brightness of the unclipped channels, with the hue of the clipped ones.
+	Blend,
+}
+
+/// The color space of the developed image, whose primaries its linear RGB values refer to.
+#[derive(Copy, Clone, Debug, Default, PartialEq, Eq)]
+pub enum ColorSpace {
+	#[default]
+	Srgb,
+	DisplayP3,
+	AdobeRgb,
+	Rec2020,
+	/// ProPhoto RGB, which uses the D50 white point and is adapted from D65 with the Bradford transform.
+	ProPhotoRgb,
+}
+
+impl ColorSpace {
+	/// The conversion matrix from linear sRGB to this color space.
+	pub fn from_srgb(&self) -> [[f64; 3]; 3] {
+		match self {
+			Self::Srgb => [[1., 0., 0.], [0., 1., 0.], [0., 0., 1.]],
+			Self::DisplayP3 => [[0.822462, 0.177538, 0.], [0.033194, 0.966806, 0.], [0.017083, 0.072397, 0.910520]],
+			Self::AdobeRgb => [[0.715127, 0.284873, 0.], [0., 1., 0.], [0., 0.041162, 0.958838]],
+			Self::Rec2020 => [[0.627404, 0.329283, 0.043313], [0.069097, 0.919541, 0.011362], [0.016391, 0.088013, 0.895595]],
+			Self::ProPhotoRgb => [[0.529317, 0.330092, 0.140588], [0.098368, 0.873465, 0.028169], [0.016879, 0.117663, 0.865457]],
+		}
+	}
+}
+
+/// The parameters of [`RawImage::develop`].
+#[derive(Copy, Clone, Debug, Default, PartialEq)]
+pub struct DevelopSettings {
+	pub white_balance: WhiteBalance,
+	/// The exposure adjustment in stops, where each stop doubles the brightness.
+	pub exposure: f64,
+	pub highlight_recovery: HighlightRecovery,
+	pub color_space: ColorSpace,
+	pub demosaicing: Demosaicing,
+}
+
+impl RawImage {
+	/// The white balance multipliers of the 4 values of the CFA grid, following the same pattern as [`RawImage::cfa_pattern`].
+	pub fn white_balance_multipliers(&self, white_balance: WhiteBalance) -> [f64; 4] {
+		let multipliers = match white_balance {
+			WhiteBalance::AsShot => return self.white_balance.or(self.camera_white_balance).unwrap_or([1.; 4]),
+			WhiteBalance::Temperature { temperature, tint } => self.temperature_multipliers(temperature, tint),
+			WhiteBalance::Multipliers(multipliers) => multipliers,
+		};
+
+		self.cfa_pattern.map(|color| multipliers[color as usize])
+	}
+
+	/// The red, green, and blue multipliers which neutralize the white of a light with the given color temperature and tint.
+	fn temperature_multipliers(&self, temperature: f64, tint: f64) -> [f64; CHANNELS_IN_RGB] {
+		let (x, y) = white_chromaticity(temperature, tint);
+		let white = [x / y, 1., (1. - x - y) / y];
+
+		// Without color data for the camera, its colors are treated as sRGB
+		let camera_white: [f64; CHANNELS_IN_RGB] = match self.find_xyz_to_camera() {
+			Some(xyz_to_camera) => std::array::from_fn(|row| (0..3).map(|column| xyz_to_camera[row * 3 + column] * white[column]).sum()),
+			None => std::array::from_fn(|row| (0..3).map(|column| XYZ_TO_RGB[row][column] * white[column]).sum()),
+		};
+		if camera_white.iter().any(|&value| value <= 0.) {
+			return [1.; CHANNELS_IN_RGB];
+		}
+
+		camera_white.map(|value| camera_white[1] / value)
+	}
+
+	/// Develops the [`RawImage`] into an [`Image`] of linear RGB values in the chosen color space, with its orientation applied.
+	///
+	/// Unlike [`RawImage::process_16bit`], it doesn't stretch the brightness or apply a gamma curve, so values above 1 remain where the highlights are brighter than white.
+	/// The [`RawImage`] should have gone through [`RawImage::calculate_conversion_matrices`], as [`RawImage::decode`] does.
+	pub fn develop(self, settings: &DevelopSettings) -> Image<f32> {
+		let multipliers = self.white_balance_multipliers(settings.white_balance);
+		let minimum = multipliers.into_iter().filter(|&multiplier| multiplier > 0.).fold(f64::INFINITY, f64::min);
+		let multipliers = if minimum.is_finite() {
+			multipliers.map(|multiplier| multiplier.max(0.) / minimum)
+		} else {
+			[1.; 4]
+		};
+
+		// The channels are scaled down by the largest multiplier so that none of them saturate before the highlights are recovered
+		let headroom = multipliers.into_iter().fold(1., f64::max);
+		let black = match self.black {
+			SubtractBlack::CfaGrid(black) => black,
+			SubtractBlack::Value(black) => [black; 4],
+			SubtractBlack::None => [0; 4],
+		};
+		let range = self.maximum.saturating_sub(*black.iter().max().unwrap()).max(1) as f64;
+		let scale = multipliers.map(|multiplier| multiplier / headroom * u16::MAX as f64 / range);
+
+		let xtrans_pattern = self.xtrans_pattern;
+		let subtract_black = move |pixel: RawPixel| pixel.value.saturating_sub(black[2 * (pixel.row % 2) + (pixel.column % 2)]);
+		let scale_white_balance = move |pixel: RawPixel| (pixel.value as f64 * scale[white_balance_index(xtrans_pattern, pixel.row, pixel.column)]).min(u16::MAX as f64) as u16;
+
+		let camera_to_rgb = self.camera_to_rgb.unwrap_or([[1., 0., 0.], [0., 1., 0.], [0., 0., 1.]]);
+		let from_srgb = settings.color_space.from_srgb();
+		let exposure = 2_f64.powf(settings.exposure);
+		let camera_to_output: [[f64; 3]; 3] = std::array::from_fn(|row| std::array::from_fn(|column| (0..3).map(|k| from_srgb[row][k] * camera_to_rgb[k][column]).sum::<f64>() * exposure));
+
+		let raw_image = self.apply((subtract_black, scale_white_balance));
+		let camera_image = raw_image.demosaic(settings.demosaicing);
+
+		// Once scaled back up, the channel with the smallest multiplier saturates at 1
+		let develop_pixel = |values: &[u16]| -> [f32; CHANNELS_IN_RGB] {
+			let mut camera: [f64; CHANNELS_IN_RGB] = std::array::from_fn(|channel| values[channel] as f64 / u16::MAX as f64 * headroom);
+			match settings.highlight_recovery {
+				HighlightRecovery::Clip => camera = camera.map(|value| value.min(1.)),
+				HighlightRecovery::Unclip => {}
+				HighlightRecovery::Blend => blend_highlights(&mut camera, 1.),
+			}
+
+			camera_to_output.map(|row| row.iter().zip(camera).map(|(coefficient, value)| coefficient * value).sum::<f64>() as f32)
+		};
+
+		let (width, height) = (raw_image.width, raw_image.height);
+		let orientation = raw_image.orientation;
+		let (output_width, output_height) = if orientation.will_swap_coordinates() { (height, width) } else { (width, height) };
+
+		let mut data = Vec::with_capacity(output_width * output_height * CHANNELS_IN_RGB);
+		for row in 0..output_height {
+			for column in 0..output_width {
+				let (source_row, source_column) = inverse_orientation_index(orientation, row, column, width, height);
+				let index = CHANNELS_IN_RGB * (source_row as usize * width + source_column as usize);
+				data.extend(develop_pixel(&camera_image[index..index + CHANNELS_IN_RGB]));
+			}
+		}
+
+		Image {
+			data,
+			width: output_width,
+			height: output_height,
+			channels: CHANNELS_IN_RGB as u8,
+			orientation: OrientationValue::Horizontal,
+		}
+	}
+}
+
+/// The chromaticity of the white of a light with the given color temperature, found on the Planckian locus with the approximation by Kang et al. (2002),
+/// and then moved perpendicular to the locus toward green by the tint.
+fn white_chromaticity(temperature: f64, tint: f64) -> (f64, f64) {
+	let planckian_uv = |temperature: f64| {
+		let temperature = temperature.clamp(TEMPERATURE_RANGE.0, TEMPERATURE_RANGE.1);
+		let (t, t2, t3) = (temperature, temperature.powi(2), temperature.powi(3));
+
+		let x = if temperature <= 4000. {
+			-0.2661239e9 / t3 - 0.2343589e6 / t2 + 0.8776956e3 / t + 0.179910
+		} else {
+			-3.0258469e9 / t3 + 2.1070379e6 / t2 + 0.2226347e3 / t + 0.240390
+		};
+		let y = if temperature <= 2222. {
+			-1.1063814 * x.powi(3) - 1.34811020 * x.powi(2) + 2.18555832 * x - 0.20219683
+		} else if temperature <= 4000. {
+			-0.9549476 * x.powi(3) - 1.37418593 * x.powi(2) + 2.09137015 * x - 0.16748867
+		} else {
+			3.0817580 * x.powi(3) - 5.87338670 * x.powi(2) + 3.75112997 * x - 0.37001483
+		};
+
+		let denominator = -2. * x + 12. * y + 3.;
+		(4. * x / denominator, 6. * y / denominator)
+	};
+
+	let (u, v) = planckian_uv(temperature);
+
+	// The locus runs toward smaller u as the temperature rises, so turning its direction counterclockwise points above it, toward green
+	let (next_u, next_v) = planckian_uv(temperature * 1.01);
+	let (tangent_u, tangent_v) = (next_u - u, next_v - v);
+	let length = tangent_u.hypot(tangent_v);
+	let (u, v) = if length > 0. {
+		(u + tint * TINT_SCALE * tangent_v / length, v - tint * TINT_SCALE * tangent_u / length)
+	} else {
+		(u, v)
+	};
+
+	let denominator = 2. * u - 8. * v + 4.;
+	(3. * u / denominator, 2. * v / denominator)
+}
+
+/// Replaces the chroma of a pixel which has clipped channels with the chroma of its clipped colors, scaled to the brightness of its unclipped colors, following dcraw.
+fn blend_highlights(camera: &mut [f64; CHANNELS_IN_RGB], clip: f64) {
+	const TO_LAB: [[f64; 3]; 3] = [[1., 1., 1.], [1.7320508, -1.7320508, 0.], [-1., -1., 2.]];
+	const FROM_LAB: [[f64; 3]; 3] = [[1., 0.8660254, -0.5], [1., -0.8660254, -0.5], [1., 0., 1.]];
+
+	if camera.iter().all(|&value| value <= clip) {
+		return;
+	}
+
+	let clipped = camera.map(|value| value.min(clip));
+	let to_lab = |color: [f64; 3]| TO_LAB.map(|row| row.iter().zip(color).map(|(coefficient, value)| coefficient * value).sum::<f64>());
+	let (mut unclipped_lab, clipped_lab) = (to_lab(*camera), to_lab(clipped));
+
+	let chroma = |lab: [f64; 3]| lab[1].powi(2) + lab[2].powi(2);
+	let chroma_ratio = if chroma(unclipped_lab) > 0. { (chroma(clipped_lab) / chroma(unclipped_lab)).sqrt() } else { 0. };
+	unclipped_lab[1] *= chroma_ratio;
+	unclipped_lab[2] *= chroma_ratio;
+
+	*camera = FROM_LAB.map(|row| row.iter().zip(unclipped_lab).map(|(coefficient, value)| coefficient * value).sum::<f64>() / 3.);
+}
@@ -1,12 +1,14 @@
 pub mod decoder;
 pub mod demosaicing;
+pub mod develop;
 pub mod metadata;
 pub mod postprocessing;
 pub mod preprocessing;
 pub mod processing;
 pub mod tiff;
 
-use crate::metadata::identify::CameraModel;
+use crate::metadata::identify::{CameraModel, RawFormat};
+use demosaicing::Demosaicing;
 use processing::{Pixel, PixelTransform, RawPixel, RawPixelTransform};
 use rawkit_proc_macros::Tag;
 use std::io::{Read, Seek};
@@ -31,6 +33,7 @@ pub struct ThumbnailImage {
 }
 
 /// The amount of black level to be subtracted from Raw Image.
+#[derive(Clone, Copy)]
 pub enum SubtractBlack {
 	/// Don't subtract any value.
 	None,
@@ -43,6 +46,7 @@ pub enum SubtractBlack {
 }
 
 /// Represents a Raw Image along with its metadata.
+#[derive(Clone)]
 pub struct RawImage {
 	/// Raw pixel data stored in linear fashion.
 	pub data: Vec<u16>,
@@ -58,6 +62,11 @@ pub struct RawImage {
 	/// It encodes Red, Blue and Green as 0, 1, and 2 respectively.
 	pub cfa_pattern: [u8; 4],
 
+	/// The 6x6 color filter array of Fujifilm X-Trans sensors, which replaces the 2x2 Bayer grid of [`RawImage::cfa_pattern`] for those cameras.
+	///
+	/// It uses the same encoding of colors as [`RawImage::cfa_pattern`], which is then set to `[0, 1, 1, 2]` so the per-channel black levels and white balance are given as Red, Green, Green, Blue.
+	pub xtrans_pattern: Option<[[u8; 6]; 6]>,
+
 	/// Transformation to be applied to negate the orientation of camera.
 	pub orientation: OrientationValue,
 
@@ -85,6 +94,11 @@ pub struct RawImage {
 	/// It represents the 4 values of CFA Grid which follows the same pattern as [`RawImage::cfa_pattern`].
 	pub white_balance: Option<[f64; 4]>,
 
+	/// Color space conversion matrix from XYZ to the camera's color space, if it is embedded in the raw file (as DNG files do).
+	///
+	/// Otherwise it is looked up from the bundled data of known cameras.
+	pub xyz_to_camera: Option<[f64; 9]>,
+
 	/// Color space conversion matrix to convert from camera's color space to sRGB.
 	pub camera_to_rgb: Option<[[f64; 3]; 3]>,
 }
@@ -125,37 +139,63 @@ impl RawImage {
 	/// Create a [`RawImage`] from an input stream.
 	///
 	/// Decodes the contents of `reader` and extracts raw pixel data and metadata.
+	/// Supports Sony ARW, Adobe DNG, Canon CR2, Nikon NEF, and Fujifilm RAF files.
 	pub fn decode<R: Read + Seek>(reader: &mut R) -> Result<RawImage, DecoderError> {
-		let mut file = TiffRead::new(reader)?;
-		let ifd = Ifd::new_first_ifd(&mut file)?;
-
-		let camera_model = metadata::identify::identify_camera_model(&ifd, &mut file).unwrap();
-		let orientation = ifd.get_value::<Orientation, _>(&mut file)?;
-
-		let mut raw_image = if camera_model.model == "DSLR-A100" {
-			decoder::arw1::decode_a100(ifd, &mut file)
-		} else {
-			let sub_ifd = ifd.get_value::<SubIfd, _>(&mut file)?;
-			let arw_ifd = sub_ifd.get_value::<ArwIfd, _>(&mut file)?;
-
-			if arw_ifd.compression == CompressionValue::Uncompressed {
-				decoder::uncompressed::decode(sub_ifd, &mut file)
-			} else if arw_ifd.strip_byte_counts[0] == arw_ifd.image_width * arw_ifd.image_height {
-				decoder::arw2::decode(sub_ifd, &mut file)
-			} else {
-				// TODO: implement for arw 1.
-				todo!()
+		let format = metadata::identify::identify_format(reader)?;
+
+		let mut raw_image = match format {
+			RawFormat::Raf => decoder::raf::decode(reader)?,
+			RawFormat::Cr3 => return Err(DecoderError::UnsupportedFormat),
+			RawFormat::Arw | RawFormat::Dng | RawFormat::Cr2 | RawFormat::Nef => {
+				let mut file = TiffRead::new(reader)?;
+				let ifd = Ifd::new_first_ifd(&mut file)?;
+
+				let camera_model = metadata::identify::identify_camera_model(&ifd, &mut file);
+				let orientation = ifd.get_value::<Option<Orientation>, _>(&mut file)?.unwrap_or(OrientationValue::Horizontal);
+
+				let mut raw_image = match format {
+					RawFormat::Dng => decoder::dng::decode(ifd, &mut file)?,
+					RawFormat::Cr2 => decoder::cr2::decode(ifd, &mut file)?,
+					RawFormat::Nef => decoder::nef::decode(ifd, &mut file)?,
+					_ => Self::decode_arw(ifd, &mut file, camera_model.as_ref())?,
+				};
+
+				raw_image.camera_model = camera_model;
+				raw_image.orientation = orientation;
+				raw_image
 			}
 		};
 
-		raw_image.camera_model = Some(camera_model);
-		raw_image.orientation = orientation;
+		if raw_image.width.checked_mul(raw_image.height) != Some(raw_image.data.len()) {
+			return Err(DecoderError::InvalidData("image data which doesn't match the image dimensions"));
+		}
 
 		raw_image.calculate_conversion_matrices();
 
 		Ok(raw_image)
 	}
 
+	fn decode_arw<R: Read + Seek>(ifd: Ifd, file: &mut TiffRead<R>, camera_model: Option<&CameraModel>) -> Result<RawImage, DecoderError> {
+		if camera_model.is_some_and(|camera_model| camera_model.model == "DSLR-A100") {
+			return decoder::arw1::decode_a100(ifd, file);
+		}
+
+		let sub_ifd = ifd.get_value::<SubIfd, _>(file)?;
+		let arw_ifd = sub_ifd.get_value::<ArwIfd, _>(file)?;
+		let Some(&strip_byte_count) = arw_ifd.strip_byte_counts.first() else {
+			return Err(TiffError::InvalidCount.into());
+		};
+
+		if arw_ifd.compression == CompressionValue::Uncompressed {
+			decoder::uncompressed::decode(sub_ifd, file)
+		} else if arw_ifd.image_width.checked_mul(arw_ifd.image_height) == Some(strip_byte_count) {
+			decoder::arw2::decode(sub_ifd, file)
+		} else {
+			// TODO: implement for arw 1.
+			Err(DecoderError::UnsupportedData("Sony ARW 1 compression"))
+		}
+	}
+
 	/// Extracts the thumbnail image from the raw file.
 	pub fn extract_thumbnail<R: Read + Seek>(reader: &mut R) -> Result<ThumbnailImage, DecoderError> {
 		let mut file = TiffRead::new(reader)?;
@@ -171,7 +211,7 @@ impl RawImage {
 
 		// Check the first two bytes to determine the format of the thumbnail.
 		// JPEG format starts with 0xFF, 0xD8.
-		if thumbnail_data[0..2] == [0xFF, 0xD8] {
+		if thumbnail_data.starts_with(&[0xFF, 0xD8]) {
 			Ok(ThumbnailImage {
 				data: thumbnail_data,
 				format: ThumbnailFormat::Jpeg,
@@ -185,7 +225,12 @@ impl RawImage {
 	///
 	/// Applies all the processing steps to finally get RGB pixel data.
 	pub fn process_8bit(self) -> Image<u8> {
-		let image = self.process_16bit();
+		self.process_8bit_with(Demosaicing::default())
+	}
+
+	/// Converts the [`RawImage`] to an [`Image`] with 8 bit resolution for each channel, using the given demosaicing algorithm.
+	pub fn process_8bit_with(self, demosaicing: Demosaicing) -> Image<u8> {
+		let image = self.process_16bit_with(demosaicing);
 
 		Image {
 			channels: image.channels,
@@ -200,6 +245,11 @@ impl RawImage {
 	///
 	/// Applies all the processing steps to finally get RGB pixel data.
 	pub fn process_16bit(self) -> Image<u16> {
+		self.process_16bit_with(Demosaicing::default())
+	}
+
+	/// Converts the [`RawImage`] to an [`Image`] with 16 bit resolution for each channel, using the given demosaicing algorithm.
+	pub fn process_16bit_with(self, demosaicing: Demosaicing) -> Image<u16> {
 		let subtract_black = self.subtract_black_fn();
 		let scale_white_balance = self.scale_white_balance_fn();
 		let scale_to_16bit = self.scale_to_16bit_fn();
@@ -207,7 +257,7 @@ impl RawImage {
 
 		let convert_to_rgb = raw_image.convert_to_rgb_fn();
 		let mut record_histogram = raw_image.record_histogram_fn();
-		let image = raw_image.demosaic_and_apply((convert_to_rgb, &mut record_histogram));
+		let image = raw_image.demosaic_and_apply(demosaicing, (convert_to_rgb, &mut record_histogram));
 
 		let gamma_correction = image.gamma_correction_fn(&record_histogram.histogram);
 		if image.orientation == OrientationValue::Horizontal {
@@ -232,14 +282,15 @@ impl RawImage {
 		self
 	}
 
-	pub fn demosaic_and_apply(self, mut transform: impl PixelTransform) -> Image<u16> {
-		let mut image = vec![0; self.width * self.height * 3];
-		for Pixel { values, row, column } in self.linear_demosaic_iter().map(|mut pixel| {
-			pixel.values = transform.apply(pixel);
-			pixel
-		}) {
-			let pixel_index = row * self.width + column;
-			image[3 * pixel_index..3 * (pixel_index + 1)].copy_from_slice(&values);
+	pub fn demosaic_and_apply(self, demosaicing: Demosaicing, mut transform: impl PixelTransform) -> Image<u16> {
+		let mut image = self.demosaic(demosaicing);
+		for (index, values) in image.chunks_exact_mut(CHANNELS_IN_RGB).enumerate() {
+			let pixel = Pixel {
+				values: values.try_into().unwrap(),
+				row: index / self.width,
+				column: index % self.width,
+			};
+			values.copy_from_slice(&transform.apply(pixel));
 		}
 
 		Image {
@@ -297,4 +348,10 @@ pub enum DecoderError {
 	IoError(#[from] std::io::Error),
 	#[error("The thumbnail format is unsupported")]
 	UnsupportedThumbnailFormat,
+	#[error("The raw file format is unsupported")]
+	UnsupportedFormat,
+	#[error("The raw file uses an unsupported layout or compression: {0}")]
+	UnsupportedData(&'static str),
+	#[error("The raw file contains invalid data: {0}")]
+	InvalidData(&'static str),
 }
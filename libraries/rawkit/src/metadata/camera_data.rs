@@ -1,4 +1,4 @@
-use crate::RawImage;
+use crate::{RawImage, SubtractBlack};
 use rawkit_proc_macros::build_camera_data;
 
 pub struct CameraData {
@@ -17,7 +17,7 @@ impl CameraData {
 
 const CAMERA_DATA: [(&str, CameraData); 40] = build_camera_data!();
 
-const RGB_TO_XYZ: [[f64; 3]; 3] = [
+pub(crate) const RGB_TO_XYZ: [[f64; 3]; 3] = [
 	// Matrix:
 	[0.412453, 0.357580, 0.180423],
 	[0.212671, 0.715160, 0.072169],
@@ -25,15 +25,28 @@ const RGB_TO_XYZ: [[f64; 3]; 3] = [
 ];
 
 impl RawImage {
+	/// The conversion matrix from XYZ to the camera's color space, either embedded in the raw file or looked up from the bundled data of known cameras.
+	pub fn find_xyz_to_camera(&self) -> Option<[f64; 9]> {
+		self.xyz_to_camera.or_else(|| {
+			let camera_model = self.camera_model.as_ref()?;
+			let camera_name_needle = camera_model.make.to_owned() + " " + &camera_model.model;
+
+			CAMERA_DATA
+				.iter()
+				.find(|(camera_name_haystack, _)| camera_name_needle == *camera_name_haystack)
+				.map(|(_, data)| data.xyz_to_camera.map(|x| (x as f64) / 10_000.))
+		})
+	}
+
 	pub fn calculate_conversion_matrices(&mut self) {
-		let Some(ref camera_model) = self.camera_model else { return };
-		let camera_name_needle = camera_model.make.to_owned() + " " + &camera_model.model;
+		let xyz_to_camera = self.find_xyz_to_camera();
 
-		let xyz_to_camera = CAMERA_DATA
-			.iter()
-			.find(|(camera_name_haystack, _)| camera_name_needle == *camera_name_haystack)
-			.map(|(_, data)| data.xyz_to_camera.map(|x| (x as f64) / 10_000.));
-		let Some(xyz_to_camera) = xyz_to_camera else { return };
+		// Without color data for the camera, its colors are passed through as they are and balanced by the camera's white balance, or by assuming the scene averages to gray
+		let Some(xyz_to_camera) = xyz_to_camera else {
+			self.white_balance = Some(self.camera_white_balance.unwrap_or_else(|| self.gray_world_white_balance()));
+			self.camera_to_rgb = Some([[1., 0., 0.], [0., 1., 0.], [0., 0., 1.]]);
+			return;
+		};
 
 		let mut rgb_to_camera = [[0.; 3]; 3];
 		for i in 0..3 {
@@ -61,6 +74,37 @@ impl RawImage {
 	}
 }
 
+impl RawImage {
+	fn gray_world_white_balance(&self) -> [f64; 4] {
+		let black = match self.black {
+			SubtractBlack::CfaGrid(black) => black,
+			SubtractBlack::Value(black) => [black; 4],
+			SubtractBlack::None => [0; 4],
+		};
+
+		let mut sums = [0.; 3];
+		let mut counts = [0_usize; 3];
+		for (index, &value) in self.data.iter().enumerate() {
+			let (row, column) = (index / self.width, index % self.width);
+			let cfa_index = 2 * (row % 2) + column % 2;
+			let color = match self.xtrans_pattern {
+				Some(pattern) => pattern[row % 6][column % 6],
+				None => self.cfa_pattern[cfa_index],
+			} as usize;
+
+			sums[color] += value.saturating_sub(black[cfa_index]) as f64;
+			counts[color] += 1;
+		}
+
+		let averages = [0, 1, 2].map(|color| sums[color] / counts[color].max(1) as f64);
+		if averages.contains(&0.) {
+			return [1.; 4];
+		}
+
+		self.cfa_pattern.map(|color| averages[1] / averages[color as usize])
+	}
+}
+
 #[allow(clippy::needless_range_loop)]
 fn pseudoinverse<const N: usize>(matrix: [[f64; 3]; N]) -> [[f64; 3]; N] {
 	let mut output_matrix = [[0.; 3]; N];
@@ -1,15 +1,20 @@
+use crate::DecoderError;
 use crate::tiff::file::TiffRead;
 use crate::tiff::tags::{Make, Model, Tag};
-use crate::tiff::{Ifd, TiffError};
+use crate::tiff::{Ifd, TagId, TiffError};
 use rawkit_proc_macros::Tag;
 use std::io::{Read, Seek};
 
-const COMPANY_NAMES: [&str; 22] = [
+const COMPANY_NAMES: [&str; 27] = [
 	"AgfaPhoto",
+	"Apple",
 	"Canon",
 	"Casio",
+	"DJI",
 	"Epson",
 	"Fujifilm",
+	"Google",
+	"Hasselblad",
 	"Mamiya",
 	"Minolta",
 	"Motorola",
@@ -19,6 +24,7 @@ const COMPANY_NAMES: [&str; 22] = [
 	"Nikon",
 	"Nokia",
 	"Olympus",
+	"Panasonic",
 	"Ricoh",
 	"Pentax",
 	"Phase One",
@@ -36,13 +42,31 @@ struct CameraModelIfd {
 	model: Model,
 }
 
+/// The container formats of raw files, which determine the decoder used for them.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum RawFormat {
+	/// Sony Alpha Raw.
+	Arw,
+	/// Adobe Digital Negative, also written by many phones and some cameras.
+	Dng,
+	/// Canon Raw version 2, a TIFF-based format.
+	Cr2,
+	/// Canon Raw version 3, an ISO base media (MP4-like) format.
+	Cr3,
+	/// Nikon Electronic Format.
+	Nef,
+	/// Fujifilm Raw, with its own container holding an embedded JPEG and the raw data.
+	Raf,
+}
+
+#[derive(Clone)]
 pub struct CameraModel {
 	pub make: String,
 	pub model: String,
 }
 
 pub fn identify_camera_model<R: Read + Seek>(ifd: &Ifd, file: &mut TiffRead<R>) -> Option<CameraModel> {
-	let mut ifd = ifd.get_value::<CameraModelIfd, _>(file).unwrap();
+	let mut ifd = ifd.get_value::<CameraModelIfd, _>(file).ok()?;
 
 	ifd.make.make_ascii_lowercase();
 	for company_name in COMPANY_NAMES {
@@ -57,3 +81,44 @@ pub fn identify_camera_model<R: Read + Seek>(ifd: &Ifd, file: &mut TiffRead<R>)
 
 	None
 }
+
+/// Identifies the format of a raw file from its header and, for TIFF-based formats, from the camera's make.
+///
+/// The reader is rewound to the start afterwards.
+pub fn identify_format<R: Read + Seek>(reader: &mut R) -> Result<RawFormat, DecoderError> {
+	let mut header = [0; 16];
+	reader.read_exact(&mut header)?;
+	reader.rewind()?;
+
+	if &header == b"FUJIFILMCCD-RAW " {
+		return Ok(RawFormat::Raf);
+	}
+	if &header[4..12] == b"ftypcrx " {
+		return Ok(RawFormat::Cr3);
+	}
+
+	let format = {
+		let mut file = TiffRead::new(&mut *reader).map_err(|_| DecoderError::UnsupportedFormat)?;
+
+		// CR2 files mark themselves right after the TIFF header
+		if &header[8..10] == b"CR" {
+			RawFormat::Cr2
+		} else {
+			let ifd = Ifd::new_first_ifd(&mut file)?;
+
+			if ifd.has_tag(TagId::DngVersion) {
+				RawFormat::Dng
+			} else {
+				match identify_camera_model(&ifd, &mut file).as_ref().map(|camera_model| camera_model.make.as_str()) {
+					Some("Sony") => RawFormat::Arw,
+					Some("Nikon") => RawFormat::Nef,
+					Some("Canon") => RawFormat::Cr2,
+					_ => return Err(DecoderError::UnsupportedFormat),
+				}
+			}
+		}
+	};
+
+	reader.rewind()?;
+	Ok(format)
+}
@@ -1,8 +1,9 @@
 use crate::{CHANNELS_IN_RGB, Pixel, RawImage};
 
 impl RawImage {
+	/// Without a calculated [`RawImage::camera_to_rgb`] matrix, the camera's colors are passed through as they are.
 	pub fn convert_to_rgb_fn(&self) -> impl Fn(Pixel) -> [u16; CHANNELS_IN_RGB] + use<> {
-		let Some(camera_to_rgb) = self.camera_to_rgb else { todo!() };
+		let camera_to_rgb = self.camera_to_rgb.unwrap_or([[1., 0., 0.], [0., 1., 0.], [0., 0., 1.]]);
 
 		move |pixel: Pixel| {
 			std::array::from_fn(|i| i)
@@ -1,8 +1,9 @@
 use crate::{RawImage, RawPixel};
 
 impl RawImage {
+	/// Without a calculated [`RawImage::white_balance`], the channels are left unscaled.
 	pub fn scale_white_balance_fn(&self) -> impl Fn(RawPixel) -> u16 + use<> {
-		let Some(mut white_balance) = self.white_balance else { todo!() };
+		let mut white_balance = self.white_balance.unwrap_or([1.; 4]);
 
 		if white_balance[1] == 0. {
 			white_balance[1] = 1.;
@@ -23,9 +24,21 @@ impl RawImage {
 			[1., 1., 1., 1.]
 		};
 
+		let xtrans_pattern = self.xtrans_pattern;
+
 		move |pixel: RawPixel| {
-			let cfa_index = 2 * (pixel.row % 2) + (pixel.column % 2);
+			let cfa_index = white_balance_index(xtrans_pattern, pixel.row, pixel.column);
 			((pixel.value as f64) * normalized_white_balance[cfa_index]).min(u16::MAX as f64).max(0.) as u16
 		}
 	}
 }
+
+/// The index into the 4 per-channel white balance values for the pixel at the given position.
+///
+/// The white balance of X-Trans sensors is given in the order of red, green, green, and blue, since their pattern doesn't repeat every two pixels.
+pub(crate) fn white_balance_index(xtrans_pattern: Option<[[u8; 6]; 6]>, row: usize, column: usize) -> usize {
+	match xtrans_pattern {
+		Some(pattern) => [0, 1, 3][pattern[row % 6][column % 6] as usize],
+		None => 2 * (row % 2) + (column % 2),
+	}
+}
@@ -13,11 +13,16 @@ use thiserror::Error;
 #[derive(Copy, Clone, Debug, PartialEq, Eq, FromPrimitive, IntoPrimitive)]
 #[repr(u16)]
 pub enum TagId {
+	NikonWhiteBalance = 0xc,
+	NikonBlackLevel = 0x3d,
+	NikonLinearization = 0x96,
+	CanonSensorInfo = 0xe0,
+	NewSubfileType = 0xfe,
 	ImageWidth = 0x100,
 	ImageLength = 0x101,
 	BitsPerSample = 0x102,
 	Compression = 0x103,
-	PhotometricInterpretation = 0x104,
+	PhotometricInterpretation = 0x106,
 	Make = 0x10f,
 	Model = 0x110,
 	StripOffsets = 0x111,
@@ -25,16 +30,38 @@ pub enum TagId {
 	SamplesPerPixel = 0x115,
 	RowsPerStrip = 0x116,
 	StripByteCounts = 0x117,
+	TileWidth = 0x142,
+	TileLength = 0x143,
+	TileOffsets = 0x144,
+	TileByteCounts = 0x145,
 	SubIfd = 0x14a,
 	ThumbnailOffset = 0x201,
 	ThumbnailLength = 0x202,
+	CanonColorData = 0x4001,
 	SonyToneCurve = 0x7010,
 	BlackLevel = 0x7310,
 	WhiteBalanceRggbLevels = 0x7313,
 	CfaPatternDim = 0x828d,
 	CfaPattern = 0x828e,
+	ExifIfd = 0x8769,
+	MakerNote = 0x927c,
+	DngVersion = 0xc612,
+	LinearizationTable = 0xc618,
+	BlackLevelRepeatDim = 0xc619,
+	DngBlackLevel = 0xc61a,
+	WhiteLevel = 0xc61d,
 	ColorMatrix1 = 0xc621,
 	ColorMatrix2 = 0xc622,
+	AsShotNeutral = 0xc628,
+	Cr2Slice = 0xc640,
+	FujiRawIfd = 0xf000,
+	FujiRawWidth = 0xf001,
+	FujiRawHeight = 0xf002,
+	FujiBitsPerSample = 0xf003,
+	FujiStripOffset = 0xf007,
+	FujiStripByteCount = 0xf008,
+	FujiBlackLevel = 0xf00a,
+	FujiWhiteBalance = 0xf00e,
 
 	#[num_enum(catch_all)]
 	Unknown(u16),
@@ -55,6 +82,7 @@ pub enum IfdTagType {
 	SRational = 10,
 	Float = 11,
 	Double = 12,
+	Ifd = 13,
 
 	#[num_enum(catch_all)]
 	Unknown(u16),
@@ -110,7 +138,7 @@ impl Ifd {
 		})
 	}
 
-	fn _next_ifd<R: Read + Seek>(&self, file: &mut TiffRead<R>) -> Result<Self, TiffError> {
+	pub fn next_ifd<R: Read + Seek>(&self, file: &mut TiffRead<R>) -> Result<Self, TiffError> {
 		Ifd::new_from_offset(file, self.next_ifd_offset.unwrap_or(0))
 	}
 
@@ -125,6 +153,10 @@ impl Ifd {
 	pub fn get_value<T: Tag, R: Read + Seek>(&self, file: &mut TiffRead<R>) -> Result<T::Output, TiffError> {
 		T::get(self, file)
 	}
+
+	pub fn has_tag(&self, tag: TagId) -> bool {
+		self.ifd_entries.iter().any(|entry| entry.tag == tag)
+	}
 }
 
 impl Display for Ifd {
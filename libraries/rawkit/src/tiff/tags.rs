@@ -1,4 +1,7 @@
-use super::types::{Array, ConstArray, TagType, TypeByte, TypeCompression, TypeIfd, TypeLong, TypeNumber, TypeOrientation, TypeSRational, TypeSShort, TypeShort, TypeSonyToneCurve, TypeString};
+use super::types::{
+	Array, ConstArray, TagType, TypeByte, TypeCompression, TypeDataBlock, TypeIfd, TypeIfdOffset, TypeLong, TypeNumber, TypeOrientation, TypeReal, TypeSRational, TypeSShort, TypeShort,
+	TypeSonyToneCurve, TypeString, TypeUndefined,
+};
 use super::{Ifd, TagId, TiffError, TiffRead};
 use std::io::{Read, Seek};
 
@@ -9,6 +12,7 @@ pub trait SimpleTag {
 	const NAME: &'static str;
 }
 
+pub struct NewSubfileType;
 pub struct ImageWidth;
 pub struct ImageLength;
 pub struct BitsPerSample;
@@ -21,7 +25,12 @@ pub struct Orientation;
 pub struct SamplesPerPixel;
 pub struct RowsPerStrip;
 pub struct StripByteCounts;
+pub struct TileWidth;
+pub struct TileLength;
+pub struct TileOffsets;
+pub struct TileByteCounts;
 pub struct SubIfd;
+pub struct SubIfdOffsets;
 pub struct ThumbnailOffset;
 pub struct ThumbnailLength;
 pub struct SonyDataOffset;
@@ -32,6 +41,35 @@ pub struct CfaPatternDim;
 pub struct CfaPattern;
 pub struct ColorMatrix1;
 pub struct ColorMatrix2;
+pub struct ExifIfd;
+pub struct MakerNote;
+pub struct DngVersion;
+pub struct LinearizationTable;
+pub struct BlackLevelRepeatDim;
+pub struct DngBlackLevel;
+pub struct WhiteLevel;
+pub struct AsShotNeutral;
+pub struct Cr2Slice;
+pub struct CanonSensorInfo;
+pub struct CanonColorData;
+pub struct NikonWhiteBalance;
+pub struct NikonBlackLevel;
+pub struct NikonLinearization;
+pub struct FujiRawIfd;
+pub struct FujiRawWidth;
+pub struct FujiRawHeight;
+pub struct FujiBitsPerSample;
+pub struct FujiStripOffset;
+pub struct FujiStripByteCount;
+pub struct FujiBlackLevel;
+pub struct FujiWhiteBalance;
+
+impl SimpleTag for NewSubfileType {
+	type Type = TypeNumber;
+
+	const ID: TagId = TagId::NewSubfileType;
+	const NAME: &'static str = "New Subfile Type";
+}
 
 impl SimpleTag for ImageWidth {
 	type Type = TypeNumber;
@@ -117,6 +155,34 @@ impl SimpleTag for StripByteCounts {
 	const NAME: &'static str = "Strip Byte Counts";
 }
 
+impl SimpleTag for TileWidth {
+	type Type = TypeNumber;
+
+	const ID: TagId = TagId::TileWidth;
+	const NAME: &'static str = "Tile Width";
+}
+
+impl SimpleTag for TileLength {
+	type Type = TypeNumber;
+
+	const ID: TagId = TagId::TileLength;
+	const NAME: &'static str = "Tile Length";
+}
+
+impl SimpleTag for TileOffsets {
+	type Type = Array<TypeNumber>;
+
+	const ID: TagId = TagId::TileOffsets;
+	const NAME: &'static str = "Tile Offsets";
+}
+
+impl SimpleTag for TileByteCounts {
+	type Type = Array<TypeNumber>;
+
+	const ID: TagId = TagId::TileByteCounts;
+	const NAME: &'static str = "Tile Byte Counts";
+}
+
 impl SimpleTag for SubIfd {
 	type Type = TypeIfd;
 
@@ -124,6 +190,13 @@ impl SimpleTag for SubIfd {
 	const NAME: &'static str = "SubIFD";
 }
 
+impl SimpleTag for SubIfdOffsets {
+	type Type = Array<TypeIfdOffset>;
+
+	const ID: TagId = TagId::SubIfd;
+	const NAME: &'static str = "SubIFD Offsets";
+}
+
 impl SimpleTag for ThumbnailOffset {
 	type Type = TypeLong;
 
@@ -166,6 +239,160 @@ impl SimpleTag for ColorMatrix2 {
 	const NAME: &'static str = "Color Matrix 2";
 }
 
+impl SimpleTag for ExifIfd {
+	type Type = TypeIfd;
+
+	const ID: TagId = TagId::ExifIfd;
+	const NAME: &'static str = "Exif IFD";
+}
+
+impl SimpleTag for MakerNote {
+	type Type = TypeDataBlock;
+
+	const ID: TagId = TagId::MakerNote;
+	const NAME: &'static str = "Maker Note";
+}
+
+impl SimpleTag for DngVersion {
+	type Type = ConstArray<TypeByte, 4>;
+
+	const ID: TagId = TagId::DngVersion;
+	const NAME: &'static str = "DNG Version";
+}
+
+impl SimpleTag for LinearizationTable {
+	type Type = Array<TypeShort>;
+
+	const ID: TagId = TagId::LinearizationTable;
+	const NAME: &'static str = "Linearization Table";
+}
+
+impl SimpleTag for BlackLevelRepeatDim {
+	type Type = ConstArray<TypeShort, 2>;
+
+	const ID: TagId = TagId::BlackLevelRepeatDim;
+	const NAME: &'static str = "Black Level Repeat Dimension";
+}
+
+impl SimpleTag for DngBlackLevel {
+	type Type = Array<TypeReal>;
+
+	const ID: TagId = TagId::DngBlackLevel;
+	const NAME: &'static str = "Black Level (DNG)";
+}
+
+impl SimpleTag for WhiteLevel {
+	type Type = Array<TypeNumber>;
+
+	const ID: TagId = TagId::WhiteLevel;
+	const NAME: &'static str = "White Level";
+}
+
+impl SimpleTag for AsShotNeutral {
+	type Type = Array<TypeReal>;
+
+	const ID: TagId = TagId::AsShotNeutral;
+	const NAME: &'static str = "As Shot Neutral";
+}
+
+impl SimpleTag for Cr2Slice {
+	type Type = ConstArray<TypeShort, 3>;
+
+	const ID: TagId = TagId::Cr2Slice;
+	const NAME: &'static str = "CR2 Slice";
+}
+
+impl SimpleTag for CanonSensorInfo {
+	type Type = Array<TypeShort>;
+
+	const ID: TagId = TagId::CanonSensorInfo;
+	const NAME: &'static str = "Canon Sensor Info";
+}
+
+impl SimpleTag for CanonColorData {
+	type Type = Array<TypeShort>;
+
+	const ID: TagId = TagId::CanonColorData;
+	const NAME: &'static str = "Canon Color Data";
+}
+
+impl SimpleTag for NikonWhiteBalance {
+	type Type = Array<TypeReal>;
+
+	const ID: TagId = TagId::NikonWhiteBalance;
+	const NAME: &'static str = "Nikon White Balance Levels (RB)";
+}
+
+impl SimpleTag for NikonBlackLevel {
+	type Type = ConstArray<TypeShort, 4>;
+
+	const ID: TagId = TagId::NikonBlackLevel;
+	const NAME: &'static str = "Nikon Black Level";
+}
+
+impl SimpleTag for NikonLinearization {
+	type Type = Array<TypeUndefined>;
+
+	const ID: TagId = TagId::NikonLinearization;
+	const NAME: &'static str = "Nikon Linearization Table";
+}
+
+impl SimpleTag for FujiRawIfd {
+	type Type = TypeIfd;
+
+	const ID: TagId = TagId::FujiRawIfd;
+	const NAME: &'static str = "Fujifilm Raw IFD";
+}
+
+impl SimpleTag for FujiRawWidth {
+	type Type = TypeNumber;
+
+	const ID: TagId = TagId::FujiRawWidth;
+	const NAME: &'static str = "Fujifilm Raw Width";
+}
+
+impl SimpleTag for FujiRawHeight {
+	type Type = TypeNumber;
+
+	const ID: TagId = TagId::FujiRawHeight;
+	const NAME: &'static str = "Fujifilm Raw Height";
+}
+
+impl SimpleTag for FujiBitsPerSample {
+	type Type = TypeNumber;
+
+	const ID: TagId = TagId::FujiBitsPerSample;
+	const NAME: &'static str = "Fujifilm Bits per Sample";
+}
+
+impl SimpleTag for FujiStripOffset {
+	type Type = TypeNumber;
+
+	const ID: TagId = TagId::FujiStripOffset;
+	const NAME: &'static str = "Fujifilm Strip Offset";
+}
+
+impl SimpleTag for FujiStripByteCount {
+	type Type = TypeNumber;
+
+	const ID: TagId = TagId::FujiStripByteCount;
+	const NAME: &'static str = "Fujifilm Strip Byte Count";
+}
+
+impl SimpleTag for FujiBlackLevel {
+	type Type = Array<TypeNumber>;
+
+	const ID: TagId = TagId::FujiBlackLevel;
+	const NAME: &'static str = "Fujifilm Black Level";
+}
+
+impl SimpleTag for FujiWhiteBalance {
+	type Type = Array<TypeNumber>;
+
+	const ID: TagId = TagId::FujiWhiteBalance;
+	const NAME: &'static str = "Fujifilm White Balance Levels (GRB)";
+}
+
 impl SimpleTag for SonyDataOffset {
 	type Type = TypeLong;
 
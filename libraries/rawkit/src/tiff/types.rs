@@ -1,5 +1,5 @@
 use super::file::TiffRead;
-use super::values::{CompressionValue, CurveLookupTable, OrientationValue, Rational};
+use super::values::{CompressionValue, CurveLookupTable, DataBlock, OrientationValue, Rational, ToFloat};
 use super::{Ifd, IfdTagType, TiffError};
 use std::io::{Read, Seek};
 
@@ -18,6 +18,8 @@ pub struct TypeUndefined;
 
 pub struct TypeNumber;
 pub struct TypeSNumber;
+pub struct TypeReal;
+pub struct TypeIfdOffset;
 pub struct TypeIfd;
 
 pub trait PrimitiveType {
@@ -201,14 +203,17 @@ impl PrimitiveType for TypeDouble {
 }
 
 impl PrimitiveType for TypeUndefined {
-	type Output = ();
+	type Output = u8;
 
-	fn get_size(_: IfdTagType) -> Option<u32> {
-		todo!()
+	fn get_size(the_type: IfdTagType) -> Option<u32> {
+		match the_type {
+			IfdTagType::Undefined => Some(1),
+			_ => None,
+		}
 	}
 
-	fn read_primitive<R: Read + Seek>(_: IfdTagType, _: &mut TiffRead<R>) -> Result<Self::Output, TiffError> {
-		todo!()
+	fn read_primitive<R: Read + Seek>(_: IfdTagType, file: &mut TiffRead<R>) -> Result<Self::Output, TiffError> {
+		Ok(file.read_u8()?)
 	}
 }
 
@@ -256,15 +261,58 @@ impl PrimitiveType for TypeSNumber {
 	}
 }
 
+impl PrimitiveType for TypeReal {
+	type Output = f64;
+
+	fn get_size(the_type: IfdTagType) -> Option<u32> {
+		match the_type {
+			IfdTagType::Byte | IfdTagType::Short | IfdTagType::Long => TypeNumber::get_size(the_type),
+			IfdTagType::SByte | IfdTagType::SShort | IfdTagType::SLong => TypeSNumber::get_size(the_type),
+			IfdTagType::Rational => TypeRational::get_size(the_type),
+			IfdTagType::SRational => TypeSRational::get_size(the_type),
+			IfdTagType::Float => TypeFloat::get_size(the_type),
+			IfdTagType::Double => TypeDouble::get_size(the_type),
+			_ => None,
+		}
+	}
+
+	fn read_primitive<R: Read + Seek>(the_type: IfdTagType, file: &mut TiffRead<R>) -> Result<Self::Output, TiffError> {
+		Ok(match the_type {
+			IfdTagType::Byte | IfdTagType::Short | IfdTagType::Long => TypeNumber::read_primitive(the_type, file)?.into(),
+			IfdTagType::SByte | IfdTagType::SShort | IfdTagType::SLong => TypeSNumber::read_primitive(the_type, file)?.into(),
+			IfdTagType::Rational => TypeRational::read_primitive(the_type, file)?.to_float(),
+			IfdTagType::SRational => TypeSRational::read_primitive(the_type, file)?.to_float(),
+			IfdTagType::Float => TypeFloat::read_primitive(the_type, file)?.into(),
+			IfdTagType::Double => TypeDouble::read_primitive(the_type, file)?,
+			_ => unreachable!(),
+		})
+	}
+}
+
+impl PrimitiveType for TypeIfdOffset {
+	type Output = u32;
+
+	fn get_size(the_type: IfdTagType) -> Option<u32> {
+		match the_type {
+			IfdTagType::Long | IfdTagType::Ifd => Some(4),
+			_ => None,
+		}
+	}
+
+	fn read_primitive<R: Read + Seek>(_: IfdTagType, file: &mut TiffRead<R>) -> Result<Self::Output, TiffError> {
+		Ok(file.read_u32()?)
+	}
+}
+
 impl PrimitiveType for TypeIfd {
 	type Output = Ifd;
 
 	fn get_size(the_type: IfdTagType) -> Option<u32> {
-		TypeLong::get_size(the_type)
+		TypeIfdOffset::get_size(the_type)
 	}
 
 	fn read_primitive<R: Read + Seek>(the_type: IfdTagType, file: &mut TiffRead<R>) -> Result<Self::Output, TiffError> {
-		let offset = TypeLong::read_primitive(the_type, file)?;
+		let offset = TypeIfdOffset::read_primitive(the_type, file)?;
 		Ifd::new_from_offset(file, offset)
 	}
 }
@@ -351,6 +399,7 @@ impl<T: PrimitiveType, const N: usize> TagType for ConstArray<T, N> {
 }
 
 pub struct TypeCompression;
+pub struct TypeDataBlock;
 pub struct TypeString;
 pub struct TypeSonyToneCurve;
 pub struct TypeOrientation;
@@ -367,6 +416,29 @@ impl TagType for TypeString {
 	}
 }
 
+impl TagType for TypeDataBlock {
+	type Output = DataBlock;
+
+	fn read<R: Read + Seek>(file: &mut TiffRead<R>) -> Result<Self::Output, TiffError> {
+		let the_type = IfdTagType::from(file.read_u16()?);
+		let count = file.read_u32()?;
+
+		let size = match the_type {
+			IfdTagType::Byte | IfdTagType::Ascii | IfdTagType::SByte | IfdTagType::Undefined => 1,
+			IfdTagType::Short | IfdTagType::SShort => 2,
+			IfdTagType::Long | IfdTagType::SLong | IfdTagType::Float | IfdTagType::Ifd => 4,
+			IfdTagType::Rational | IfdTagType::SRational | IfdTagType::Double => 8,
+			IfdTagType::Unknown(_) => return Err(TiffError::InvalidType),
+		};
+		let length = count.checked_mul(size).ok_or(TiffError::InvalidCount)?;
+
+		// Data of up to four bytes is stored in place of the offset
+		let offset = if length > 4 { file.read_u32()? } else { file.stream_position()?.try_into()? };
+
+		Ok(DataBlock { offset, length })
+	}
+}
+
 impl TagType for TypeSonyToneCurve {
 	type Output = CurveLookupTable;
 
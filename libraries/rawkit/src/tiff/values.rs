@@ -27,6 +27,13 @@ impl<T: ToFloat> ToFloat for Rational<T> {
 	}
 }
 
+/// The location of a tag's data within the file, for data such as maker notes which is parsed separately.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub struct DataBlock {
+	pub offset: u32,
+	pub length: u32,
+}
+
 pub struct CurveLookupTable {
 	table: Vec<u16>,
 }
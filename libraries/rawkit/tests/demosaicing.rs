@@ -0,0 +1,89 @@
+//! Tests for each demosaicing algorithm, using synthetic color filter arrays of a known image.
+
+use rawkit::demosaicing::Demosaicing;
+use rawkit::tiff::values::OrientationValue;
+use rawkit::{RawImage, SubtractBlack};
+
+const ALGORITHMS: [Demosaicing; 4] = [Demosaicing::Linear, Demosaicing::Ppg, Demosaicing::Vng, Demosaicing::Ahd];
+const BAYER_PATTERNS: [[u8; 4]; 4] = [[0, 1, 1, 2], [1, 0, 2, 1], [1, 2, 0, 1], [2, 1, 1, 0]];
+const X_TRANS_PATTERN: [[u8; 6]; 6] = [[1, 1, 0, 1, 1, 2], [1, 1, 2, 1, 1, 0], [2, 0, 1, 0, 2, 1], [1, 1, 2, 1, 1, 0], [1, 1, 0, 1, 1, 2], [0, 2, 1, 2, 0, 1]];
+
+/// Samples an image through a color filter array, keeping only the color of the filter at each pixel.
+fn mosaic(width: usize, height: usize, cfa_pattern: [u8; 4], xtrans_pattern: Option<[[u8; 6]; 6]>, color_at: impl Fn(usize, usize) -> [u16; 3]) -> RawImage {
+	let mut raw_image = RawImage {
+		data: Vec::new(),
+		width,
+		height,
+		cfa_pattern,
+		xtrans_pattern,
+		orientation: OrientationValue::Horizontal,
+		maximum: u16::MAX,
+		black: SubtractBlack::None,
+		camera_model: None,
+		camera_white_balance: None,
+		white_balance: None,
+		xyz_to_camera: None,
+		camera_to_rgb: None,
+	};
+	raw_image.data = (0..width * height)
+		.map(|index| color_at(index / width, index % width)[raw_image.cfa_color(index / width, index % width)])
+		.collect();
+
+	raw_image
+}
+
+#[test]
+fn uniform_color_is_reproduced_by_every_algorithm() {
+	let color = [12000, 24000, 6000];
+	let (width, height) = (40, 30);
+
+	for cfa_pattern in BAYER_PATTERNS {
+		let raw_image = mosaic(width, height, cfa_pattern, None, |_, _| color);
+
+		for demosaicing in ALGORITHMS {
+			let image = raw_image.demosaic(demosaicing);
+
+			// The linear interpolation of the outermost pixels mixes in pixels from the opposite edge
+			for index in (1..height - 1).flat_map(|row| (1..width - 1).map(move |column| row * width + column)) {
+				assert_eq!(image[3 * index..3 * (index + 1)], color, "{demosaicing:?} changed a uniform color with the pattern {cfa_pattern:?}");
+			}
+		}
+	}
+}
+
+#[test]
+fn edges_stay_sharp_with_directional_algorithms() {
+	// A vertical edge between two colors, which linear interpolation blurs across the columns next to it
+	let (width, height) = (32, 32);
+	let color_at = |_, column: usize| if column < width / 2 { [4000, 8000, 2000] } else { [40000, 50000, 30000] };
+	let raw_image = mosaic(width, height, [0, 1, 1, 2], None, color_at);
+
+	let error = |demosaicing| {
+		let image = raw_image.demosaic(demosaicing);
+		let mut error = 0;
+		for row in 8..height - 8 {
+			for column in 8..width - 8 {
+				let pixel = &image[3 * (row * width + column)..3 * (row * width + column + 1)];
+				error += pixel.iter().zip(color_at(row, column)).map(|(&value, expected)| (value as i64 - expected as i64).abs()).sum::<i64>();
+			}
+		}
+		error
+	};
+
+	let linear = error(Demosaicing::Linear);
+	for demosaicing in [Demosaicing::Ppg, Demosaicing::Vng, Demosaicing::Ahd] {
+		assert!(error(demosaicing) < linear, "{demosaicing:?} should follow the edge more closely than linear interpolation");
+	}
+}
+
+#[test]
+fn x_trans_uniform_color_is_reproduced() {
+	let color = [12000, 24000, 6000];
+	let raw_image = mosaic(20, 14, [0, 1, 1, 2], Some(X_TRANS_PATTERN), |_, _| color);
+
+	for demosaicing in ALGORITHMS {
+		let image = raw_image.demosaic(demosaicing);
+		assert_eq!(image.len(), 20 * 14 * 3);
+		assert!(image.chunks_exact(3).all(|pixel| pixel == color));
+	}
+}
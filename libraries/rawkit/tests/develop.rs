@@ -0,0 +1,113 @@
+//! Tests for developing raw images with adjustable white balance, exposure, highlight recovery, and color space.
+
+use rawkit::develop::{ColorSpace, DevelopSettings, HighlightRecovery, WhiteBalance};
+use rawkit::tiff::values::OrientationValue;
+use rawkit::{RawImage, SubtractBlack};
+
+/// Builds an RGGB raw image of a uniform color, given as the camera's values before white balance, for a camera without color data.
+fn uniform_raw_image(width: usize, height: usize, color: [u16; 3], camera_white_balance: [f64; 4]) -> RawImage {
+	let cfa_pattern = [0, 1, 1, 2];
+	let mut raw_image = RawImage {
+		data: (0..width * height).map(|index| color[cfa_pattern[2 * (index / width % 2) + index % width % 2] as usize]).collect(),
+		width,
+		height,
+		cfa_pattern,
+		xtrans_pattern: None,
+		orientation: OrientationValue::Horizontal,
+		maximum: 4095,
+		black: SubtractBlack::CfaGrid([0; 4]),
+		camera_model: None,
+		camera_white_balance: Some(camera_white_balance),
+		white_balance: None,
+		xyz_to_camera: None,
+		camera_to_rgb: None,
+	};
+	raw_image.calculate_conversion_matrices();
+
+	raw_image
+}
+
+fn center_pixel(image: &rawkit::Image<f32>) -> [f32; 3] {
+	let index = 3 * (image.height / 2 * image.width + image.width / 2);
+	[image.data[index], image.data[index + 1], image.data[index + 2]]
+}
+
+fn assert_close(actual: [f32; 3], expected: [f32; 3]) {
+	assert!(
+		actual.iter().zip(expected).all(|(actual, expected)| (actual - expected).abs() < 1e-3),
+		"{actual:?} should be close to {expected:?}"
+	);
+}
+
+#[test]
+fn as_shot_white_balance_neutralizes_gray() {
+	let raw_image = uniform_raw_image(16, 16, [1000, 2000, 1000], [2., 1., 1., 2.]);
+	let image = raw_image.develop(&DevelopSettings::default());
+
+	assert_close(center_pixel(&image), [2000. / 4095.; 3]);
+}
+
+#[test]
+fn custom_multipliers_and_exposure() {
+	let raw_image = uniform_raw_image(16, 16, [1000, 2000, 1000], [2., 1., 1., 2.]);
+	let settings = DevelopSettings {
+		white_balance: WhiteBalance::Multipliers([1., 1., 1.]),
+		exposure: 1.,
+		..Default::default()
+	};
+	let image = raw_image.develop(&settings);
+
+	assert_close(center_pixel(&image), [2000. / 4095., 4000. / 4095., 2000. / 4095.]);
+}
+
+#[test]
+fn temperature_white_balance() {
+	let raw_image = uniform_raw_image(8, 8, [1000, 1000, 1000], [1.; 4]);
+
+	// Daylight is close to the white of sRGB, which the colors of a camera without color data are treated as, although D65 lies slightly off the Planckian locus
+	let daylight = raw_image.white_balance_multipliers(WhiteBalance::Temperature { temperature: 6504., tint: 0. });
+	assert!(daylight.iter().all(|multiplier| (multiplier - 1.).abs() < 0.1), "{daylight:?}");
+
+	// Warm light needs more blue and less red to be neutralized
+	let [red, _, _, blue] = raw_image.white_balance_multipliers(WhiteBalance::Temperature { temperature: 3000., tint: 0. });
+	assert!(blue > 1. && red < 1.);
+
+	// Compensating for green light lowers the green channel relative to the others
+	let [red, green, _, blue] = raw_image.white_balance_multipliers(WhiteBalance::Temperature { temperature: 6504., tint: 20. });
+	assert!(green < red && green < blue);
+}
+
+#[test]
+fn highlight_recovery() {
+	// The red channel is multiplied past the saturation of the green and blue channels
+	let raw_image = || uniform_raw_image(16, 16, [4095, 4095, 4095], [2., 1., 1., 1.]);
+
+	let develop = |highlight_recovery| {
+		center_pixel(&raw_image().develop(&DevelopSettings {
+			highlight_recovery,
+			..Default::default()
+		}))
+	};
+
+	assert_close(develop(HighlightRecovery::Clip), [1.; 3]);
+	assert_close(develop(HighlightRecovery::Unclip), [2., 1., 1.]);
+
+	let blended = develop(HighlightRecovery::Blend);
+	assert!(blended[0] > blended[1] && blended[0] < 2., "{blended:?}");
+}
+
+#[test]
+fn color_space_and_orientation() {
+	let mut raw_image = uniform_raw_image(12, 8, [2000, 2000, 2000], [1.; 4]);
+	raw_image.orientation = OrientationValue::Rotate90;
+
+	let image = raw_image.develop(&DevelopSettings {
+		color_space: ColorSpace::Rec2020,
+		..Default::default()
+	});
+
+	assert_eq!((image.width, image.height), (8, 12));
+	assert_eq!(image.data.len(), 8 * 12 * 3);
+	// Neutral colors are the same in every color space with the D65 white point
+	assert_close(center_pixel(&image), [2000. / 4095.; 3]);
+}
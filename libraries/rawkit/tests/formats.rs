@@ -0,0 +1,682 @@
+//! Tests for decoding each supported raw format, using small synthetic files which are built to exercise the layout and compression of each format.
+
+use rawkit::decoder::ljpeg::HuffmanTable;
+use rawkit::metadata::identify::{RawFormat, identify_format};
+use rawkit::tiff::values::OrientationValue;
+use rawkit::{DecoderError, RawImage, SubtractBlack};
+use std::io::Cursor;
+
+#[derive(Clone, Copy)]
+enum Endian {
+	Little,
+	Big,
+}
+
+enum Value {
+	Byte(Vec<u8>),
+	Ascii(&'static str),
+	Short(Vec<u16>),
+	Long(Vec<u32>),
+	Rational(Vec<(u32, u32)>),
+	Undefined(Vec<u8>),
+	SRational(Vec<(i32, i32)>),
+}
+
+/// Builds a TIFF file by appending data blocks and IFDs, which refer to each other by the offsets returned when appending them.
+struct TiffBuilder {
+	endian: Endian,
+	data: Vec<u8>,
+}
+
+impl TiffBuilder {
+	fn new(endian: Endian) -> Self {
+		let header = match endian {
+			Endian::Little => b"II*\0\0\0\0\0",
+			Endian::Big => b"MM\0*\0\0\0\0",
+		};
+		Self { endian, data: header.to_vec() }
+	}
+
+	fn u16(&self, value: u16) -> [u8; 2] {
+		match self.endian {
+			Endian::Little => value.to_le_bytes(),
+			Endian::Big => value.to_be_bytes(),
+		}
+	}
+
+	fn u32(&self, value: u32) -> [u8; 4] {
+		match self.endian {
+			Endian::Little => value.to_le_bytes(),
+			Endian::Big => value.to_be_bytes(),
+		}
+	}
+
+	fn append(&mut self, bytes: &[u8]) -> u32 {
+		if self.data.len() % 2 == 1 {
+			self.data.push(0);
+		}
+		let offset = self.data.len() as u32;
+		self.data.extend_from_slice(bytes);
+		offset
+	}
+
+	fn shorts(&self, values: &[u16]) -> Vec<u8> {
+		values.iter().flat_map(|&value| self.u16(value)).collect()
+	}
+
+	fn ifd(&mut self, mut entries: Vec<(u16, Value)>, next_ifd_offset: u32) -> u32 {
+		entries.sort_by_key(|(tag, _)| *tag);
+
+		let mut fields = Vec::new();
+		for (tag, value) in entries {
+			let (the_type, count, bytes): (u16, usize, Vec<u8>) = match value {
+				Value::Byte(values) => (1, values.len(), values),
+				Value::Ascii(text) => (2, text.len() + 1, [text.as_bytes(), &[0]].concat()),
+				Value::Short(values) => (3, values.len(), self.shorts(&values)),
+				Value::Long(values) => (4, values.len(), values.iter().flat_map(|&value| self.u32(value)).collect()),
+				Value::Rational(values) => (
+					5,
+					values.len(),
+					values.iter().flat_map(|&(numerator, denominator)| [self.u32(numerator), self.u32(denominator)].concat()).collect(),
+				),
+				Value::Undefined(values) => (7, values.len(), values),
+				Value::SRational(values) => (
+					10,
+					values.len(),
+					values
+						.iter()
+						.flat_map(|&(numerator, denominator)| [self.u32(numerator as u32), self.u32(denominator as u32)].concat())
+						.collect(),
+				),
+			};
+
+			let value = if bytes.len() > 4 {
+				let offset = self.append(&bytes);
+				self.u32(offset).to_vec()
+			} else {
+				let mut value = bytes.clone();
+				value.resize(4, 0);
+				value
+			};
+			fields.push((tag, the_type, count as u32, value));
+		}
+
+		let mut ifd = self.u16(fields.len() as u16).to_vec();
+		for (tag, the_type, count, value) in fields {
+			ifd.extend(self.u16(tag));
+			ifd.extend(self.u16(the_type));
+			ifd.extend(self.u32(count));
+			ifd.extend(value);
+		}
+		ifd.extend(self.u32(next_ifd_offset));
+
+		self.append(&ifd)
+	}
+
+	fn finish(mut self, first_ifd_offset: u32) -> Vec<u8> {
+		let offset = self.u32(first_ifd_offset);
+		self.data[4..8].copy_from_slice(&offset);
+		self.data
+	}
+}
+
+/// Writes bits starting from the most significant bit, optionally stuffing a zero byte after each `0xFF` byte as JPEG does.
+struct BitWriter {
+	data: Vec<u8>,
+	byte: u8,
+	bit_count: u32,
+	jpeg_stuffing: bool,
+}
+
+impl BitWriter {
+	fn new(jpeg_stuffing: bool) -> Self {
+		Self {
+			data: Vec::new(),
+			byte: 0,
+			bit_count: 0,
+			jpeg_stuffing,
+		}
+	}
+
+	fn write(&mut self, value: u32, length: u32) {
+		for bit in (0..length).rev() {
+			self.byte = (self.byte << 1) | ((value >> bit) & 1) as u8;
+			self.bit_count += 1;
+
+			if self.bit_count == 8 {
+				self.data.push(self.byte);
+				if self.jpeg_stuffing && self.byte == 0xff {
+					self.data.push(0);
+				}
+				self.byte = 0;
+				self.bit_count = 0;
+			}
+		}
+	}
+
+	fn finish(mut self) -> Vec<u8> {
+		if self.bit_count > 0 {
+			self.write(u32::MAX, 8 - self.bit_count);
+		}
+		self.data
+	}
+}
+
+/// Gives the code and its length for each symbol of a Huffman table given in the JPEG format.
+fn huffman_codes(definition: &[u8]) -> Vec<Option<(u32, u32)>> {
+	let mut codes = vec![None; 256];
+	let mut code = 0;
+	let mut symbols = definition[16..].iter();
+
+	for length in 1..=16 {
+		for _ in 0..definition[length - 1] {
+			codes[*symbols.next().unwrap() as usize] = Some((code, length as u32));
+			code += 1;
+		}
+		code <<= 1;
+	}
+
+	codes
+}
+
+/// Writes a difference as the Huffman code of its bit length followed by its bits, where negative differences are stored as one less than their value.
+fn write_difference(writer: &mut BitWriter, codes: &[Option<(u32, u32)>], difference: i32) {
+	let length = 32 - difference.unsigned_abs().leading_zeros();
+	let (code, code_length) = codes[length as usize].unwrap();
+	writer.write(code, code_length);
+
+	if length > 0 {
+		let bits = if difference < 0 { difference + (1 << length) - 1 } else { difference };
+		writer.write(bits as u32, length);
+	}
+}
+
+/// Encodes the interleaved samples as lossless JPEG, predicting each sample from the one to its left.
+fn encode_lossless_jpeg(samples: &[u16], width: usize, height: usize, components: usize, precision: u8) -> Vec<u8> {
+	let mut table = vec![0_u8; 16];
+	table[4] = 17;
+	table.extend(0..=16);
+	let codes = huffman_codes(&table);
+
+	let mut writer = BitWriter::new(true);
+	let row_length = width * components;
+	for (index, &sample) in samples.iter().enumerate() {
+		let (row, column) = (index / row_length, index % row_length);
+		let prediction = match (row, column < components) {
+			(0, true) => 1 << (precision - 1),
+			(_, true) => samples[index - row_length] as i32,
+			_ => samples[index - components] as i32,
+		};
+		write_difference(&mut writer, &codes, sample as i32 - prediction);
+	}
+
+	let mut jpeg = vec![0xff, 0xd8];
+	jpeg.extend([0xff, 0xc3]);
+	jpeg.extend((8 + 3 * components as u16).to_be_bytes());
+	jpeg.push(precision);
+	jpeg.extend((height as u16).to_be_bytes());
+	jpeg.extend((width as u16).to_be_bytes());
+	jpeg.push(components as u8);
+	for component in 0..components {
+		jpeg.extend([component as u8 + 1, 0x11, 0]);
+	}
+
+	jpeg.extend([0xff, 0xc4]);
+	jpeg.extend((3 + table.len() as u16).to_be_bytes());
+	jpeg.push(0);
+	jpeg.extend(&table);
+
+	jpeg.extend([0xff, 0xda]);
+	jpeg.extend((6 + 2 * components as u16).to_be_bytes());
+	jpeg.push(components as u8);
+	for component in 0..components {
+		jpeg.extend([component as u8 + 1, 0]);
+	}
+	jpeg.extend([1, 0, 0]);
+
+	jpeg.extend(writer.finish());
+	jpeg.extend([0xff, 0xd9]);
+	jpeg
+}
+
+/// Generates a pattern of samples which varies in both directions and differs between neighbors.
+fn test_samples(width: usize, height: usize, maximum: u16) -> Vec<u16> {
+	(0..width * height).map(|index| ((index * 37 + (index / width) * 101) % maximum as usize) as u16).collect()
+}
+
+fn decode(data: &[u8]) -> Result<RawImage, DecoderError> {
+	RawImage::decode(&mut Cursor::new(data))
+}
+
+fn black_levels(raw_image: &RawImage) -> [u16; 4] {
+	match raw_image.black {
+		SubtractBlack::CfaGrid(black) => black,
+		_ => panic!("expected a black level for each position of the CFA grid"),
+	}
+}
+
+#[test]
+fn identify_formats_from_headers() {
+	let cr3 = [&[0, 0, 0, 0x18][..], b"ftypcrx ", &[0; 16]].concat();
+	assert_eq!(identify_format(&mut Cursor::new(cr3)).unwrap(), RawFormat::Cr3);
+
+	let raf = [&b"FUJIFILMCCD-RAW 0201"[..], &[0; 100]].concat();
+	assert_eq!(identify_format(&mut Cursor::new(raf)).unwrap(), RawFormat::Raf);
+
+	assert!(matches!(identify_format(&mut Cursor::new(vec![0x42; 64])), Err(DecoderError::UnsupportedFormat)));
+
+	let mut tiff = TiffBuilder::new(Endian::Little);
+	let ifd = tiff.ifd(vec![(0x10f, Value::Ascii("Unknown Camera Company")), (0x110, Value::Ascii("Model"))], 0);
+	assert!(matches!(identify_format(&mut Cursor::new(tiff.finish(ifd))), Err(DecoderError::UnsupportedFormat)));
+}
+
+#[test]
+fn cr3_is_reported_as_unsupported() {
+	let cr3 = [&[0, 0, 0, 0x18][..], b"ftypcrx ", &[0; 16]].concat();
+	assert!(matches!(decode(&cr3), Err(DecoderError::UnsupportedFormat)));
+}
+
+#[test]
+fn dng_with_lossless_jpeg_tiles() {
+	let (width, height, tile_width) = (6, 4, 4);
+	let samples = test_samples(width, height, 1 << 12);
+
+	let mut tiff = TiffBuilder::new(Endian::Little);
+
+	// Each tile is encoded with two components, so each row of the JPEG covers a row of the tile
+	let tile_offsets_and_lengths: Vec<_> = [0, tile_width]
+		.into_iter()
+		.map(|tile_column| {
+			let tile: Vec<u16> = (0..height)
+				.flat_map(|row| (0..tile_width).map(move |column| (row, tile_column + column)))
+				.map(|(row, column)| if column < width { samples[row * width + column] } else { 0 })
+				.collect();
+			let jpeg = encode_lossless_jpeg(&tile, tile_width / 2, height, 2, 12);
+			(tiff.append(&jpeg), jpeg.len() as u32)
+		})
+		.collect();
+
+	let linearization_table: Vec<u16> = (0..1 << 12).map(|value| value * 2).collect();
+	let raw_ifd = tiff.ifd(
+		vec![
+			(0xfe, Value::Long(vec![0])),
+			(0x100, Value::Long(vec![width as u32])),
+			(0x101, Value::Long(vec![height as u32])),
+			(0x102, Value::Short(vec![12])),
+			(0x103, Value::Short(vec![7])),
+			(0x106, Value::Short(vec![32803])),
+			(0x142, Value::Long(vec![tile_width as u32])),
+			(0x143, Value::Long(vec![height as u32])),
+			(0x144, Value::Long(tile_offsets_and_lengths.iter().map(|&(offset, _)| offset).collect())),
+			(0x145, Value::Long(tile_offsets_and_lengths.iter().map(|&(_, length)| length).collect())),
+			(0x828d, Value::Short(vec![2, 2])),
+			(0x828e, Value::Byte(vec![1, 0, 2, 1])),
+			(0xc618, Value::Short(linearization_table)),
+			(0xc619, Value::Short(vec![2, 2])),
+			(0xc61a, Value::Rational(vec![(256, 1), (257, 1), (258, 1), (259, 1)])),
+			(0xc61d, Value::Long(vec![8000])),
+		],
+		0,
+	);
+
+	let preview_ifd = tiff.ifd(
+		vec![
+			(0xfe, Value::Long(vec![1])),
+			(0x10f, Value::Ascii("Example")),
+			(0x110, Value::Ascii("Example Camera")),
+			(0x112, Value::Short(vec![8])),
+			(0x14a, Value::Long(vec![raw_ifd])),
+			(0xc612, Value::Byte(vec![1, 4, 0, 0])),
+			(0xc628, Value::Rational(vec![(1, 2), (1, 1), (1, 4)])),
+			(0xc622, Value::SRational(vec![(1, 1), (0, 1), (0, 1), (0, 1), (1, 1), (0, 1), (0, 1), (0, 1), (1, 1)])),
+		],
+		0,
+	);
+	let data = tiff.finish(preview_ifd);
+
+	assert_eq!(identify_format(&mut Cursor::new(&data)).unwrap(), RawFormat::Dng);
+
+	let raw_image = decode(&data).unwrap();
+	assert_eq!((raw_image.width, raw_image.height), (width, height));
+	assert_eq!(raw_image.data, samples.iter().map(|sample| sample * 2).collect::<Vec<_>>());
+	assert_eq!(raw_image.cfa_pattern, [1, 0, 2, 1]);
+	assert_eq!(raw_image.maximum, 8000);
+	assert_eq!(black_levels(&raw_image), [256, 257, 258, 259]);
+	assert_eq!(raw_image.orientation, OrientationValue::Rotate270);
+	assert_eq!(raw_image.camera_white_balance, Some([1., 2., 4., 1.]));
+	assert_eq!(raw_image.xyz_to_camera, Some([1., 0., 0., 0., 1., 0., 0., 0., 1.]));
+
+	let image = raw_image.process_8bit();
+	assert_eq!((image.width, image.height), (height, width));
+}
+
+#[test]
+fn dng_with_packed_uncompressed_strips() {
+	let (width, height, rows_per_strip) = (5, 4, 3);
+	let samples = test_samples(width, height, 1 << 12);
+
+	let mut tiff = TiffBuilder::new(Endian::Big);
+
+	// Each row is packed into whole bytes, so rows of an odd width end with 4 bits of padding
+	let strips: Vec<_> = samples
+		.chunks(width * rows_per_strip)
+		.map(|strip| {
+			let mut writer = BitWriter::new(false);
+			for row in strip.chunks(width) {
+				row.iter().for_each(|&sample| writer.write(sample.into(), 12));
+				if writer.bit_count > 0 {
+					writer.write(0, 8 - writer.bit_count);
+				}
+			}
+			let data = writer.finish();
+			(tiff.append(&data), data.len() as u32)
+		})
+		.collect();
+
+	let ifd = tiff.ifd(
+		vec![
+			(0x100, Value::Long(vec![width as u32])),
+			(0x101, Value::Long(vec![height as u32])),
+			(0x102, Value::Short(vec![12])),
+			(0x103, Value::Short(vec![1])),
+			(0x106, Value::Short(vec![32803])),
+			(0x10f, Value::Ascii("Example")),
+			(0x110, Value::Ascii("Example Camera")),
+			(0x111, Value::Long(strips.iter().map(|&(offset, _)| offset).collect())),
+			(0x116, Value::Long(vec![rows_per_strip as u32])),
+			(0x117, Value::Long(strips.iter().map(|&(_, length)| length).collect())),
+			(0x828d, Value::Short(vec![2, 2])),
+			(0x828e, Value::Byte(vec![0, 1, 1, 2])),
+			(0xc612, Value::Byte(vec![1, 4, 0, 0])),
+		],
+		0,
+	);
+	let data = tiff.finish(ifd);
+
+	let raw_image = decode(&data).unwrap();
+	assert_eq!(raw_image.data, samples);
+	assert_eq!(raw_image.maximum, (1 << 12) - 1);
+	assert_eq!(black_levels(&raw_image), [0; 4]);
+	assert_eq!(raw_image.orientation, OrientationValue::Horizontal);
+
+	// Without any color data, the colors pass through and the white balance is estimated from the image
+	assert_eq!(raw_image.camera_to_rgb, Some([[1., 0., 0.], [0., 1., 0.], [0., 0., 1.]]));
+	assert!(raw_image.white_balance.is_some());
+}
+
+#[test]
+fn cr2_with_slices_and_maker_note() {
+	let (width, height) = (8, 4);
+	let (left_border, top_border) = (2, 1);
+	let mut samples = test_samples(width, height, 1 << 14);
+	for row in 0..height {
+		samples[row * width..row * width + left_border].fill(2048);
+	}
+
+	// The image is encoded as two slices of 4 columns each, one after the other
+	let sliced: Vec<u16> = (0..2)
+		.flat_map(|slice| (0..height).flat_map(move |row| (0..4).map(move |column| (row, slice * 4 + column))))
+		.map(|(row, column)| samples[row * width + column])
+		.collect();
+	let jpeg = encode_lossless_jpeg(&sliced, width / 2, height, 2, 14);
+
+	let mut tiff = TiffBuilder::new(Endian::Little);
+	tiff.append(b"CR\x02\0\0\0\0\0");
+	let jpeg_offset = tiff.append(&jpeg);
+
+	let raw_ifd = tiff.ifd(
+		vec![
+			(0x103, Value::Short(vec![6])),
+			(0x111, Value::Long(vec![jpeg_offset])),
+			(0x117, Value::Long(vec![jpeg.len() as u32])),
+			(0xc640, Value::Short(vec![1, 4, 4])),
+		],
+		0,
+	);
+	let small_preview_ifd = tiff.ifd(vec![(0x103, Value::Short(vec![1]))], raw_ifd);
+	let thumbnail_ifd = tiff.ifd(vec![(0x103, Value::Short(vec![6]))], small_preview_ifd);
+
+	let mut color_data = vec![0; 582];
+	color_data[25..29].copy_from_slice(&[2000, 1000, 1000, 1500]);
+	let sensor_info = vec![34, width as u16, height as u16, 0, 0, left_border as u16, top_border as u16, width as u16 - 1, height as u16 - 1];
+	let maker_note = tiff.ifd(vec![(0xe0, Value::Short(sensor_info)), (0x4001, Value::Short(color_data))], 0);
+	let maker_note_length = tiff.data.len() as u32 - maker_note;
+	let exif_ifd = tiff.ifd(vec![(0x927c, Value::Long(vec![0]))], 0);
+	// Point the maker note tag at the IFD written above, as the entry's count must be its length in bytes
+	let maker_note_entry = exif_ifd as usize + 2;
+	tiff.data[maker_note_entry + 2..maker_note_entry + 4].copy_from_slice(&7_u16.to_le_bytes());
+	tiff.data[maker_note_entry + 4..maker_note_entry + 8].copy_from_slice(&maker_note_length.to_le_bytes());
+	tiff.data[maker_note_entry + 8..maker_note_entry + 12].copy_from_slice(&maker_note.to_le_bytes());
+
+	let first_ifd = tiff.ifd(
+		vec![
+			(0x10f, Value::Ascii("Canon")),
+			(0x110, Value::Ascii("Canon EOS Example")),
+			(0x112, Value::Short(vec![1])),
+			(0x8769, Value::Long(vec![exif_ifd])),
+		],
+		thumbnail_ifd,
+	);
+	let data = tiff.finish(first_ifd);
+
+	assert_eq!(identify_format(&mut Cursor::new(&data)).unwrap(), RawFormat::Cr2);
+
+	let raw_image = decode(&data).unwrap();
+	let active_area: Vec<u16> = (top_border..height).flat_map(|row| samples[row * width + left_border..(row + 1) * width].to_vec()).collect();
+	assert_eq!((raw_image.width, raw_image.height), (width - left_border, height - top_border));
+	assert_eq!(raw_image.data, active_area);
+	assert_eq!(raw_image.maximum, (1 << 14) - 1);
+	assert_eq!(black_levels(&raw_image), [2048; 4]);
+	assert_eq!(raw_image.camera_white_balance, Some([2000., 1000., 1000., 1500.]));
+	assert_eq!(raw_image.camera_model.as_ref().map(|camera_model| camera_model.make.as_str()), Some("Canon"));
+}
+
+#[test]
+fn nef_with_lossless_compression() {
+	let (width, height) = (6, 4);
+	let samples = test_samples(width, height, 1 << 12);
+	let vertical_predictions = [2048, 2048, 2048, 2048];
+
+	// Encode with the 12-bit lossless Huffman tree, predicting from the previous sample of the same color
+	let tree = [&[0, 1, 4, 2, 3, 1, 2, 0, 0, 0, 0, 0, 0, 0, 0, 0][..], &[5, 4, 6, 3, 7, 2, 8, 1, 9, 0, 10, 11, 12]].concat();
+	let codes = huffman_codes(&tree);
+	let mut writer = BitWriter::new(false);
+	let mut vertical = [[2048_i32; 2]; 2];
+	let mut horizontal = [0_i32; 2];
+	for row in 0..height {
+		for column in 0..width {
+			let sample = samples[row * width + column] as i32;
+			let prediction = if column < 2 { vertical[row & 1][column] } else { horizontal[column & 1] };
+			write_difference(&mut writer, &codes, sample - prediction);
+
+			if column < 2 {
+				vertical[row & 1][column] = sample;
+			}
+			horizontal[column & 1] = sample;
+		}
+	}
+	let compressed = writer.finish();
+
+	let mut maker_note = TiffBuilder::new(Endian::Big);
+	let linearization = [&[0x46, 0x30][..], &maker_note.shorts(&vertical_predictions), &[0, 0]].concat();
+	let maker_note_ifd = maker_note.ifd(
+		vec![
+			(0x0c, Value::Rational(vec![(2, 1), (3, 2), (1, 1), (1, 1)])),
+			(0x3d, Value::Short(vec![600, 604, 608, 612])),
+			(0x96, Value::Undefined(linearization)),
+		],
+		0,
+	);
+	let maker_note = [&b"Nikon\0\x02\x10\0\0"[..], &maker_note.finish(maker_note_ifd)].concat();
+
+	let mut tiff = TiffBuilder::new(Endian::Little);
+	let data_offset = tiff.append(&compressed);
+	let raw_ifd = tiff.ifd(
+		vec![
+			(0xfe, Value::Long(vec![0])),
+			(0x100, Value::Long(vec![width as u32])),
+			(0x101, Value::Long(vec![height as u32])),
+			(0x102, Value::Short(vec![12])),
+			(0x103, Value::Short(vec![34713])),
+			(0x106, Value::Short(vec![32803])),
+			(0x111, Value::Long(vec![data_offset])),
+			(0x117, Value::Long(vec![compressed.len() as u32])),
+			(0x828d, Value::Short(vec![2, 2])),
+			(0x828e, Value::Byte(vec![1, 0, 2, 1])),
+		],
+		0,
+	);
+	let exif_ifd = tiff.ifd(vec![(0x927c, Value::Undefined(maker_note))], 0);
+	let first_ifd = tiff.ifd(
+		vec![
+			(0xfe, Value::Long(vec![1])),
+			(0x10f, Value::Ascii("NIKON CORPORATION")),
+			(0x110, Value::Ascii("NIKON EXAMPLE")),
+			(0x14a, Value::Long(vec![raw_ifd])),
+			(0x8769, Value::Long(vec![exif_ifd])),
+		],
+		0,
+	);
+	let data = tiff.finish(first_ifd);
+
+	assert_eq!(identify_format(&mut Cursor::new(&data)).unwrap(), RawFormat::Nef);
+
+	let raw_image = decode(&data).unwrap();
+	assert_eq!(raw_image.data, samples);
+	assert_eq!(raw_image.maximum, (1 << 12) - 1);
+	// The black levels are given for 14-bit values in the order of red, green, green, blue, and the CFA pattern here is GRBG
+	assert_eq!(black_levels(&raw_image), [151, 150, 153, 152]);
+	assert_eq!(raw_image.camera_white_balance, Some([1., 2., 1.5, 1.]));
+
+	let image = raw_image.process_8bit();
+	assert_eq!((image.width, image.height), (width, height));
+}
+
+#[test]
+fn raf_with_uncompressed_x_trans_data() {
+	let (width, height) = (12, 6);
+	let samples = test_samples(width, height, 1 << 14);
+	let x_trans_pattern: [[u8; 6]; 6] = [[1, 1, 0, 1, 1, 2], [1, 1, 2, 1, 1, 0], [2, 0, 1, 0, 2, 1], [1, 1, 2, 1, 1, 0], [1, 1, 0, 1, 1, 2], [0, 2, 1, 2, 0, 1]];
+
+	let mut exif = TiffBuilder::new(Endian::Little);
+	let exif_ifd = exif.ifd(vec![(0x10f, Value::Ascii("FUJIFILM")), (0x110, Value::Ascii("X-Example")), (0x112, Value::Short(vec![6]))], 0);
+	let exif = exif.finish(exif_ifd);
+	let jpeg = [&[0xff, 0xd8, 0xff, 0xe1][..], &(exif.len() as u16 + 8).to_be_bytes(), b"Exif\0\0", &exif, &[0xff, 0xd9]].concat();
+
+	// The CFA header stores the X-Trans pattern in reverse order
+	let pattern_record: Vec<u8> = x_trans_pattern.iter().flatten().rev().copied().collect();
+	let cfa_header = [
+		&2_u32.to_be_bytes()[..],
+		&[0x01, 0x31, 0, 36],
+		&pattern_record,
+		&[0x2f, 0xf0, 0, 8],
+		&[302_u16, 604, 302, 453].iter().flat_map(|value| value.to_be_bytes()).collect::<Vec<_>>(),
+	]
+	.concat();
+
+	let mut cfa = TiffBuilder::new(Endian::Little);
+	let strip_offset = cfa.append(&samples.iter().flat_map(|sample| sample.to_le_bytes()).collect::<Vec<_>>());
+	let fuji_ifd = cfa.ifd(
+		vec![
+			(0xf001, Value::Long(vec![width as u32])),
+			(0xf002, Value::Long(vec![height as u32])),
+			(0xf003, Value::Long(vec![14])),
+			(0xf007, Value::Long(vec![strip_offset])),
+			(0xf008, Value::Long(vec![(width * height * 2) as u32])),
+			(0xf00a, Value::Long(vec![1024; 36])),
+		],
+		0,
+	);
+	let cfa_first_ifd = cfa.ifd(vec![(0xf000, Value::Long(vec![fuji_ifd]))], 0);
+	let cfa = cfa.finish(cfa_first_ifd);
+
+	let mut header = b"FUJIFILMCCD-RAW 0201FF000000".to_vec();
+	header.extend(b"X-Example".iter().copied().chain(std::iter::repeat(0)).take(32));
+	header.resize(84, 0);
+	let jpeg_offset = 108;
+	let cfa_header_offset = jpeg_offset + jpeg.len();
+	let cfa_offset = cfa_header_offset + cfa_header.len();
+	for value in [jpeg_offset, jpeg.len(), cfa_header_offset, cfa_header.len(), cfa_offset, cfa.len()] {
+		header.extend((value as u32).to_be_bytes());
+	}
+	let data = [header, jpeg, cfa_header, cfa].concat();
+
+	assert_eq!(identify_format(&mut Cursor::new(&data)).unwrap(), RawFormat::Raf);
+
+	let raw_image = decode(&data).unwrap();
+	assert_eq!((raw_image.width, raw_image.height), (width, height));
+	assert_eq!(raw_image.data, samples);
+	assert_eq!(raw_image.xtrans_pattern, Some(x_trans_pattern));
+	assert_eq!(raw_image.maximum, (1 << 14) - 1);
+	assert_eq!(black_levels(&raw_image), [1024; 4]);
+	assert_eq!(raw_image.camera_white_balance, Some([604., 302., 302., 453.]));
+	assert_eq!(raw_image.orientation, OrientationValue::Rotate90);
+	assert_eq!(raw_image.camera_model.as_ref().map(|camera_model| camera_model.model.as_str()), Some("X-Example"));
+
+	let image = raw_image.process_8bit();
+	assert_eq!((image.width, image.height), (height, width));
+}
+
+/// Builds a Sony ARW file whose raw data is described by the given entries of its sub-IFD.
+fn arw(strip: &[u8], entries: impl FnOnce(u32) -> Vec<(u16, Value)>) -> Vec<u8> {
+	let mut tiff = TiffBuilder::new(Endian::Little);
+	let strip_offset = tiff.append(strip);
+	let sub_ifd = tiff.ifd(entries(strip_offset), 0);
+	let first_ifd = tiff.ifd(vec![(0x10f, Value::Ascii("Sony")), (0x110, Value::Ascii("ILCE-Example")), (0x14a, Value::Long(vec![sub_ifd]))], 0);
+	tiff.finish(first_ifd)
+}
+
+#[test]
+fn malformed_arw_files_are_errors() {
+	let (width, height) = (32, 64);
+	let arw2_entries = |strip_offset: u32, strip_byte_count: u32| {
+		vec![
+			(0x100, Value::Long(vec![width])),
+			(0x101, Value::Long(vec![height])),
+			(0x102, Value::Short(vec![12])),
+			(0x103, Value::Short(vec![32767])),
+			(0x111, Value::Long(vec![strip_offset])),
+			(0x117, Value::Long(vec![strip_byte_count])),
+			(0x7010, Value::Short(vec![8000, 10400, 12900, 14100])),
+			(0x828d, Value::Short(vec![2, 2])),
+			(0x828e, Value::Byte(vec![0, 1, 1, 2])),
+		]
+	};
+
+	// ARW 1 files, whose compressed data doesn't take one byte per pixel, aren't supported
+	let data = arw(&[0; 16], |strip_offset| arw2_entries(strip_offset, 16));
+	assert_eq!(identify_format(&mut Cursor::new(&data)).unwrap(), RawFormat::Arw);
+	assert!(matches!(decode(&data), Err(DecoderError::UnsupportedData(_))));
+
+	// The strip lies past the end of the file
+	let data = arw(&[0; 16], |strip_offset| arw2_entries(strip_offset + 0x10000, width * height));
+	assert!(matches!(decode(&data), Err(DecoderError::IoError(_))));
+
+	// Where the minimum and maximum of a block are given for the same pixel, its other pixels need more bits than the narrow image has left in the row
+	let data = arw(&vec![0; (width * height) as usize], |strip_offset| arw2_entries(strip_offset, width * height));
+	assert!(matches!(decode(&data), Err(DecoderError::InvalidData(_))));
+
+	// Without any strips, there is no raw data to decode
+	let data = arw(&[], |_| {
+		arw2_entries(0, 0)
+			.into_iter()
+			.filter(|(tag, _)| ![0x111, 0x117].contains(tag))
+			.chain([(0x117, Value::Long(Vec::new()))])
+			.collect()
+	});
+	assert!(decode(&data).is_err());
+}
+
+#[test]
+fn truncated_huffman_tables_are_errors() {
+	// One code of each length from 1 to 3 bits, for the symbols 0, 1, and 2
+	let definition = [[1, 1, 1].as_slice(), &[0; 13], &[0, 1, 2]].concat();
+	assert!(HuffmanTable::from_definition(&definition).is_ok());
+
+	// The counts are cut short
+	assert!(matches!(HuffmanTable::from_definition(&definition[..10]), Err(DecoderError::InvalidData(_))));
+	assert!(matches!(HuffmanTable::new(&definition[..10], &[0, 1, 2]), Err(DecoderError::InvalidData(_))));
+
+	// The symbols are cut short
+	assert!(matches!(HuffmanTable::from_definition(&definition[..18]), Err(DecoderError::InvalidData(_))));
+}
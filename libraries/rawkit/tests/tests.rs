@@ -14,6 +14,7 @@ use std::path::{Path, PathBuf};
 use std::sync::atomic::{AtomicUsize, Ordering};
 use std::time::Duration;
 
+// The DNG, CR2, NEF, and RAF decoders are covered by the small synthetic files built in `formats.rs` instead of downloaded samples
 const TEST_FILES: [&str; 3] = ["ILCE-7M3-ARW2.3.5-blossoms.arw", "ILCE-7RM4-ARW2.3.5-kestrel.arw", "ILCE-6000-ARW2.3.1-windsock.arw"];
 const BASE_URL: &str = "https://static.graphite.art/test-data/libraries/rawkit/";
 const BASE_PATH: &str = "./tests/images/";
@@ -120,7 +121,11 @@ fn download_images() {
 		path.push(filename);
 		if !path.exists() {
 			let url = BASE_URL.to_owned() + filename;
-			let mut response = client.get(url).send().unwrap();
+			let mut response = client
+				.get(url)
+				.send()
+				.and_then(|response| response.error_for_status())
+				.unwrap_or_else(|err| panic!("Failed to download the test image {filename}: {err}"));
 			let mut file = File::create(BASE_PATH.to_owned() + filename).unwrap();
 			std::io::copy(&mut response, &mut file).unwrap();
 		}
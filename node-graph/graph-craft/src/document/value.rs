@@ -224,6 +224,8 @@ tagged_value! {
 	Gradient(graphic_types::vector_types::vector::style::Gradient),
 	#[serde(alias = "GradientPositions")] // TODO: Eventually remove this alias document upgrade code
 	GradientStops(GradientStops),
+	WidthProfile(graphic_types::vector_types::vector::style::WidthProfile),
+	AppearanceStack(graphic_types::vector_types::vector::style::AppearanceStack),
 	Font(text_nodes::Font),
 	BrushStrokes(Vec<BrushStroke>),
 	BrushCache(BrushCache),
@@ -250,6 +252,10 @@ tagged_value! {
 	DomainWarpType(raster_nodes::adjustments::DomainWarpType),
 	RelativeAbsolute(raster_nodes::adjustments::RelativeAbsolute),
 	SelectiveColorChoice(raster_nodes::adjustments::SelectiveColorChoice),
+	RawWhiteBalance(raster_nodes::develop_raw::RawWhiteBalance),
+	HighlightRecovery(raster_nodes::develop_raw::HighlightRecovery),
+	RawColorSpace(raster_nodes::develop_raw::RawColorSpace),
+	RawDemosaicing(raster_nodes::develop_raw::RawDemosaicing),
 	GridType(vector::misc::GridType),
 	ArcType(vector::misc::ArcType),
 	RowsOrColumns(vector::misc::RowsOrColumns),
@@ -265,6 +271,7 @@ tagged_value! {
 	PaintOrder(vector::style::PaintOrder),
 	FillType(vector::style::FillType),
 	GradientType(vector::style::GradientType),
+	ImageFit(vector::style::ImageFit),
 	ReferencePoint(vector::ReferencePoint),
 	CentroidType(vector::misc::CentroidType),
 	BooleanOperation(path_bool_nodes::BooleanOperation),
@@ -465,6 +472,11 @@ pub enum RenderOutputType {
 		svg: String,
 		image_data: Vec<(u64, Image<Color>)>,
 	},
+	/// The PDF document, or the reason it couldn't be written.
+	#[serde(skip)]
+	Pdf {
+		data: Result<Vec<u8>, String>,
+	},
 }
 
 impl Hash for RenderOutput {
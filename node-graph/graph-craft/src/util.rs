@@ -3,9 +3,14 @@ use crate::graphene_compiler::Compiler;
 use crate::proto::ProtoNetwork;
 
 pub fn load_network(document_string: &str) -> NodeNetwork {
-	let document: serde_json::Value = serde_json::from_str(document_string).expect("Failed to parse document");
+	try_load_network(document_string).expect("Failed to parse document")
+}
+
+/// Reads the node network from a serialized document, or returns an error if the document is not valid.
+pub fn try_load_network(document_string: &str) -> Result<NodeNetwork, serde_json::Error> {
+	let document: serde_json::Value = serde_json::from_str(document_string)?;
 	let document = (document["network_interface"]["network"].clone()).to_string();
-	serde_json::from_str::<NodeNetwork>(&document).expect("Failed to parse document")
+	serde_json::from_str::<NodeNetwork>(&document)
 }
 
 pub fn compile(network: NodeNetwork) -> ProtoNetwork {
@@ -0,0 +1,186 @@
+use graph_craft::document::value::{DVec2, TaggedValue};
+use graph_craft::document::{DocumentNodeImplementation, NodeInput, NodeNetwork};
+use graphene_std::artboard::create_artboard;
+
+/// An artboard found in the document network, with the bounds it occupies in document space.
+#[derive(Debug, Clone, PartialEq)]
+pub struct ArtboardInfo {
+	pub name: String,
+	pub location: DVec2,
+	pub dimensions: DVec2,
+}
+
+/// Finds every artboard in the network whose name and bounds are set by values rather than computed by other nodes.
+/// The artboards are sorted by their position in the document, from top to bottom then left to right.
+pub fn find_artboards(network: &NodeNetwork) -> Vec<ArtboardInfo> {
+	let mut artboards = Vec::new();
+	collect_artboards(network, &[], &mut artboards);
+
+	artboards.sort_by(|a, b| a.location.y.total_cmp(&b.location.y).then(a.location.x.total_cmp(&b.location.x)).then_with(|| a.name.cmp(&b.name)));
+	artboards
+}
+
+/// Visits the network recursively, where `scope` holds the inputs of each enclosing network node so imports can be resolved to values.
+fn collect_artboards<'a>(network: &'a NodeNetwork, scope: &[&'a [NodeInput]], artboards: &mut Vec<ArtboardInfo>) {
+	for (node_id, node) in &network.nodes {
+		match &node.implementation {
+			DocumentNodeImplementation::Network(nested_network) => {
+				let mut nested_scope = scope.to_vec();
+				nested_scope.push(&node.inputs);
+				collect_artboards(nested_network, &nested_scope, artboards);
+			}
+			DocumentNodeImplementation::ProtoNode(identifier) if *identifier == create_artboard::IDENTIFIER => {
+				// Inputs of the artboard node: content, label, location, dimensions, background, clip
+				let input = |index: usize| node.inputs.get(index).and_then(|input| resolve_value(input, scope));
+
+				let (Some(TaggedValue::String(name)), Some(&TaggedValue::DVec2(location)), Some(&TaggedValue::DVec2(dimensions))) = (input(1), input(2), input(3)) else {
+					log::warn!("Skipping artboard node {node_id} because its label, location, or dimensions are not constant values");
+					continue;
+				};
+
+				artboards.push(ArtboardInfo {
+					name: name.clone(),
+					location,
+					dimensions,
+				});
+			}
+			_ => {}
+		}
+	}
+}
+
+/// Finds the value given to the input, following imports out through the enclosing networks whose node inputs are held in `scope`.
+pub fn resolve_value<'a>(input: &'a NodeInput, scope: &[&'a [NodeInput]]) -> Option<&'a TaggedValue> {
+	match input {
+		NodeInput::Value { tagged_value, .. } => Some(&**tagged_value),
+		NodeInput::Import { import_index, .. } => {
+			let (parent_inputs, parent_scope) = scope.split_last()?;
+			resolve_value(parent_inputs.get(*import_index)?, parent_scope)
+		}
+		_ => None,
+	}
+}
+
+/// Turns the artboard name into a string which is safe to use as part of a file name.
+pub fn file_name_for(name: &str) -> String {
+	let file_name: String = name
+		.trim()
+		.chars()
+		.map(|c| {
+			if c.is_control() || matches!(c, '/' | '\\' | ':' | '*' | '?' | '"' | '<' | '>' | '|') {
+				'_'
+			} else {
+				c
+			}
+		})
+		.collect();
+
+	if file_name.is_empty() { "Artboard".to_string() } else { file_name }
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use graph_craft::concrete;
+	use graph_craft::document::{DocumentNode, NodeId};
+
+	fn artboard_node(label: NodeInput, location: NodeInput, dimensions: NodeInput) -> DocumentNode {
+		DocumentNode {
+			inputs: vec![
+				NodeInput::value(TaggedValue::None, false),
+				label,
+				location,
+				dimensions,
+				NodeInput::value(TaggedValue::None, false),
+				NodeInput::value(TaggedValue::Bool(false), false),
+			],
+			implementation: DocumentNodeImplementation::ProtoNode(create_artboard::IDENTIFIER),
+			..Default::default()
+		}
+	}
+
+	fn constant_artboard(name: &str, location: DVec2, dimensions: DVec2) -> DocumentNode {
+		artboard_node(
+			NodeInput::value(TaggedValue::String(name.to_string()), false),
+			NodeInput::value(TaggedValue::DVec2(location), false),
+			NodeInput::value(TaggedValue::DVec2(dimensions), false),
+		)
+	}
+
+	fn network(nodes: impl IntoIterator<Item = DocumentNode>) -> NodeNetwork {
+		NodeNetwork {
+			nodes: nodes.into_iter().enumerate().map(|(index, node)| (NodeId(index as u64), node)).collect(),
+			..Default::default()
+		}
+	}
+
+	#[test]
+	fn artboards_are_sorted_by_position() {
+		let network = network([
+			constant_artboard("Bottom", DVec2::new(0., 500.), DVec2::splat(100.)),
+			constant_artboard("Top right", DVec2::new(300., 0.), DVec2::splat(100.)),
+			constant_artboard("Top left", DVec2::new(-300., 0.), DVec2::splat(100.)),
+		]);
+
+		let names = find_artboards(&network).into_iter().map(|artboard| artboard.name).collect::<Vec<_>>();
+		assert_eq!(names, ["Top left", "Top right", "Bottom"]);
+	}
+
+	#[test]
+	fn artboards_with_computed_inputs_are_skipped() {
+		let network = network([
+			constant_artboard("Constant", DVec2::ZERO, DVec2::splat(100.)),
+			artboard_node(
+				NodeInput::node(NodeId(0), 0),
+				NodeInput::value(TaggedValue::DVec2(DVec2::ZERO), false),
+				NodeInput::value(TaggedValue::DVec2(DVec2::splat(100.)), false),
+			),
+		]);
+
+		assert_eq!(
+			find_artboards(&network),
+			[ArtboardInfo {
+				name: "Constant".to_string(),
+				location: DVec2::ZERO,
+				dimensions: DVec2::splat(100.),
+			}]
+		);
+	}
+
+	#[test]
+	fn artboards_in_nested_networks_resolve_imports() {
+		let inner = network([artboard_node(
+			NodeInput::import(concrete!(String), 0),
+			NodeInput::value(TaggedValue::DVec2(DVec2::new(10., 20.)), false),
+			NodeInput::import(concrete!(DVec2), 1),
+		)]);
+		let outer = network([DocumentNode {
+			inputs: vec![
+				NodeInput::value(TaggedValue::String("Nested".to_string()), false),
+				NodeInput::value(TaggedValue::DVec2(DVec2::new(30., 40.)), false),
+			],
+			implementation: DocumentNodeImplementation::Network(inner),
+			..Default::default()
+		}]);
+
+		assert_eq!(
+			find_artboards(&outer),
+			[ArtboardInfo {
+				name: "Nested".to_string(),
+				location: DVec2::new(10., 20.),
+				dimensions: DVec2::new(30., 40.),
+			}]
+		);
+	}
+
+	#[test]
+	fn file_names_are_sanitized() {
+		assert_eq!(file_name_for("Cover"), "Cover");
+		assert_eq!(file_name_for("  Page 1  "), "Page 1");
+		assert_eq!(file_name_for("a/b\\c:d*e?f\"g<h>i|j"), "a_b_c_d_e_f_g_h_i_j");
+		assert_eq!(file_name_for("Tab\tName"), "Tab_Name");
+		assert_eq!(file_name_for("Ünïcødé ✓"), "Ünïcødé ✓");
+		assert_eq!(file_name_for(""), "Artboard");
+		assert_eq!(file_name_for("   "), "Artboard");
+	}
+}
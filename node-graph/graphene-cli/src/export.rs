@@ -1,42 +1,215 @@
-use graph_craft::document::value::{RenderOutputType, TaggedValue, UVec2};
+use crate::artboards::{self, ArtboardInfo};
+use graph_craft::document::value::{DAffine2, DVec2, RenderOutputType, TaggedValue, UVec2};
 use graph_craft::graphene_compiler::Executor;
-use graphene_std::application_io::{ExportFormat, RenderConfig};
+use graphene_std::application_io::{ExportFormat, RenderConfig, TimingInformation};
 use graphene_std::core_types::ops::Convert;
 use graphene_std::core_types::transform::Footprint;
 use graphene_std::raster_types::{CPU, GPU, Raster};
 use interpreted_executor::dynamic_executor::DynamicExecutor;
+use std::collections::HashSet;
 use std::error::Error;
-use std::io::Cursor;
+use std::fs::File;
+use std::io::{BufWriter, Cursor};
+use std::ops::Range;
 use std::path::{Path, PathBuf};
+use std::str::FromStr;
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+/// Placeholder in the output path which is replaced by the frame number when exporting an image sequence.
+const FRAME_PLACEHOLDER: &str = "{frame}";
+/// Placeholder in the output path which is replaced by the artboard name when exporting each artboard to its own file.
+const ARTBOARD_PLACEHOLDER: &str = "{artboard}";
 
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
 pub enum FileType {
 	Svg,
 	Png,
 	Jpg,
+	Gif,
+	Webp,
+	Avif,
+	Tiff,
+	Pdf,
+}
+
+impl FileType {
+	pub fn name(self) -> &'static str {
+		match self {
+			FileType::Svg => "SVG",
+			FileType::Png => "PNG",
+			FileType::Jpg => "JPG",
+			FileType::Gif => "GIF",
+			FileType::Webp => "WebP",
+			FileType::Avif => "AVIF",
+			FileType::Tiff => "TIFF",
+			FileType::Pdf => "PDF",
+		}
+	}
+
+	pub fn mime(self) -> &'static str {
+		match self {
+			FileType::Svg => "image/svg+xml",
+			FileType::Png => "image/png",
+			FileType::Jpg => "image/jpeg",
+			FileType::Gif => "image/gif",
+			FileType::Webp => "image/webp",
+			FileType::Avif => "image/avif",
+			FileType::Tiff => "image/tiff",
+			FileType::Pdf => "application/pdf",
+		}
+	}
 }
 
 pub fn detect_file_type(path: &Path) -> Result<FileType, String> {
 	match path.extension().and_then(|s| s.to_str()) {
 		Some("svg") => Ok(FileType::Svg),
-		Some("png") => Ok(FileType::Png),
+		Some("png" | "apng") => Ok(FileType::Png),
 		Some("jpg" | "jpeg") => Ok(FileType::Jpg),
-		_ => Err("Unsupported file extension. Supported formats: .svg, .png, .jpg".to_string()),
+		Some("gif") => Ok(FileType::Gif),
+		Some("webp") => Ok(FileType::Webp),
+		Some("avif") => Ok(FileType::Avif),
+		Some("tif" | "tiff") => Ok(FileType::Tiff),
+		Some("pdf") => Ok(FileType::Pdf),
+		_ => Err("Unsupported file extension. Supported formats: .svg, .png, .apng, .jpg, .gif, .webp, .avif, .tiff, .pdf".to_string()),
 	}
 }
 
-pub async fn export_document(
-	executor: &DynamicExecutor,
-	wgpu_executor: &wgpu_executor::WgpuExecutor,
-	output_path: PathBuf,
-	file_type: FileType,
-	scale: f64,
-	(width, height): (Option<u32>, Option<u32>),
-	transparent: bool,
-) -> Result<(), Box<dyn Error>> {
+/// The number of bits used for each color channel of an exported raster image.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum BitDepth {
+	#[default]
+	Eight,
+	Sixteen,
+}
+
+impl FromStr for BitDepth {
+	type Err = String;
+
+	fn from_str(s: &str) -> Result<Self, Self::Err> {
+		match s.trim() {
+			"8" => Ok(Self::Eight),
+			"16" => Ok(Self::Sixteen),
+			_ => Err(format!("Unsupported bit depth \"{s}\", expected 8 or 16")),
+		}
+	}
+}
+
+/// How the rendered pixels are encoded when writing raster image files.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub struct RasterOptions {
+	pub transparent: bool,
+	/// Quality from 1 to 100 for the lossy formats, or the format's default quality if not given.
+	pub quality: Option<u8>,
+	pub bit_depth: BitDepth,
+}
+
+/// A range of animation frames, parsed from `START..END` (end exclusive), `START..=END` (end inclusive), or just `START`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct FrameRange {
+	pub start: u32,
+	pub end: Option<u32>,
+}
+
+impl FromStr for FrameRange {
+	type Err = String;
+
+	fn from_str(s: &str) -> Result<Self, Self::Err> {
+		let parse = |number: &str| number.trim().parse::<u32>().map_err(|_| format!("Invalid frame number \"{number}\" in frame range \"{s}\""));
+
+		let Some((start, end)) = s.split_once("..") else {
+			return Ok(Self { start: parse(s)?, end: None });
+		};
+
+		let start = if start.trim().is_empty() { 0 } else { parse(start)? };
+		let end = match end.strip_prefix('=') {
+			Some(inclusive_end) => parse(inclusive_end)?
+				.checked_add(1)
+				.ok_or_else(|| format!("Frame range \"{s}\" ends past the last frame number {}", u32::MAX))?,
+			None => parse(end)?,
+		};
+		if end <= start {
+			return Err(format!("Frame range \"{s}\" is empty"));
+		}
+
+		Ok(Self { start, end: Some(end) })
+	}
+}
+
+/// How the frames of an animation are stepped through in time.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct AnimationSettings {
+	pub frames: Option<FrameRange>,
+	pub fps: f64,
+	/// Length of the animation in seconds, used to determine the last frame when the frame range has no end.
+	pub duration: Option<f64>,
+}
+
+impl AnimationSettings {
+	pub fn frame_indices(&self) -> Result<Range<u32>, String> {
+		if !(self.fps.is_finite() && self.fps > 0.) {
+			return Err(format!("The frame rate must be a positive number, got {}", self.fps));
+		}
+
+		let start = self.frames.map_or(0, |frames| frames.start);
+		let past_last_frame = || format!("The animation ends past the last frame number {}", u32::MAX);
+		let end = match (self.frames.and_then(|frames| frames.end), self.duration) {
+			(Some(_), Some(_)) => return Err("Specify either an end frame in --frames or a --duration, not both".to_string()),
+			(Some(end), None) => end,
+			(None, Some(duration)) if duration.is_finite() && duration > 0. => {
+				// Casting would saturate a frame count too large for a frame number, so it's rejected before the cast instead
+				let frame_count = (duration * self.fps).ceil();
+				let frame_count = (frame_count <= u32::MAX as f64).then_some(frame_count as u32);
+				frame_count.and_then(|frame_count| start.checked_add(frame_count)).ok_or_else(past_last_frame)?
+			}
+			(None, Some(duration)) => return Err(format!("The duration must be a positive number of seconds, got {duration}")),
+			(None, None) => start.checked_add(1).ok_or_else(past_last_frame)?,
+		};
+
+		Ok(start..end)
+	}
+
+	/// The time on the animation timeline at which the given frame is shown.
+	pub fn frame_time(&self, frame: u32) -> Duration {
+		Duration::from_secs_f64(frame as f64 / self.fps)
+	}
+}
+
+/// The container that the frames of an animation are written to.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum AnimationOutput {
+	/// One numbered file per frame, with the frame number substituted for the `{frame}` placeholder in the output path.
+	Sequence(FileType),
+	Gif,
+	Apng,
+}
+
+pub fn detect_animation_output(path: &Path, file_type: FileType) -> Result<AnimationOutput, String> {
+	if path.to_string_lossy().contains(FRAME_PLACEHOLDER) {
+		return Ok(AnimationOutput::Sequence(file_type));
+	}
+
+	match file_type {
+		FileType::Gif => Ok(AnimationOutput::Gif),
+		FileType::Png => Ok(AnimationOutput::Apng),
+		FileType::Svg | FileType::Jpg | FileType::Webp | FileType::Avif | FileType::Tiff | FileType::Pdf => Err(format!(
+			"Animated export to this format needs an image sequence, add a {FRAME_PLACEHOLDER} placeholder to the output file name"
+		)),
+	}
+}
+
+/// The result of rendering the document once.
+enum RenderedFrame {
+	Svg(String),
+	Raster { data: Vec<u8>, width: u32, height: u32 },
+	Pdf(Vec<u8>),
+}
+
+/// Creates the render settings for exporting the whole document.
+pub fn render_config(file_type: FileType, scale: f64, (width, height): (Option<u32>, Option<u32>)) -> RenderConfig {
 	// Determine export format based on file type
 	let export_format = match file_type {
 		FileType::Svg => ExportFormat::Svg,
+		FileType::Pdf => ExportFormat::Pdf,
 		_ => ExportFormat::Raster,
 	};
 
@@ -53,65 +226,516 @@ pub async fn export_document(
 		render_config.viewport.resolution = UVec2::new(w, h);
 	}
 
+	render_config
+}
+
+/// Creates the render settings for exporting the document without choosing individual artboards.
+/// Unless a region is given, a PDF covers every artboard so that each of them is placed on its own page.
+pub fn document_render_config(file_type: FileType, scale: f64, (width, height): (Option<u32>, Option<u32>), artboards: &[ArtboardInfo], transparent: bool) -> RenderConfig {
+	let all_artboards = (file_type == FileType::Pdf && width.is_none() && height.is_none())
+		.then(|| all_artboards_render_config(file_type, scale, artboards, transparent))
+		.flatten();
+	all_artboards.unwrap_or_else(|| render_config(file_type, scale, (width, height)))
+}
+
+/// Creates the render settings for exporting only the region covered by the given artboard.
+pub fn artboard_render_config(file_type: FileType, scale: f64, artboard: &ArtboardInfo, transparent: bool) -> RenderConfig {
+	let mut render_config = render_config(file_type, scale, (None, None));
+
+	render_config.viewport = Footprint {
+		transform: DAffine2::from_translation(-artboard.location),
+		resolution: (artboard.dimensions * scale).round().as_uvec2(),
+		..Default::default()
+	};
+	render_config.hide_artboards = transparent;
+
+	render_config
+}
+
+/// Creates the render settings for exporting a PDF with one page for each artboard, covering the region of every artboard.
+pub fn all_artboards_render_config(file_type: FileType, scale: f64, artboards: &[ArtboardInfo], transparent: bool) -> Option<RenderConfig> {
+	let (min, max) = artboards.iter().fold(None, |bounds: Option<(DVec2, DVec2)>, artboard| {
+		let (a, b) = (artboard.location, artboard.location + artboard.dimensions);
+		let (min, max) = (a.min(b), a.max(b));
+		Some(bounds.map_or((min, max), |(bounds_min, bounds_max)| (bounds_min.min(min), bounds_max.max(max))))
+	})?;
+
+	let mut render_config = render_config(file_type, scale, (None, None));
+	render_config.viewport = Footprint {
+		transform: DAffine2::from_translation(-min),
+		resolution: ((max - min) * scale).round().as_uvec2(),
+		..Default::default()
+	};
+	render_config.hide_artboards = transparent;
+
+	Some(render_config)
+}
+
+/// Pairs each selected artboard (or all of them if no names are given) with the output path and render settings used to export it.
+pub fn artboard_targets(artboards: &[ArtboardInfo], names: &[String], output_path: &Path, file_type: FileType, scale: f64, transparent: bool) -> Result<Vec<(PathBuf, RenderConfig)>, String> {
+	if let Some(missing) = names.iter().find(|name| !artboards.iter().any(|artboard| &artboard.name == *name)) {
+		let available = artboards.iter().map(|artboard| format!("\"{}\"", artboard.name)).collect::<Vec<_>>().join(", ");
+		return Err(format!("No artboard named \"{missing}\" was found in the document. Available artboards: {available}"));
+	}
+
+	let selected = artboards.iter().filter(|artboard| names.is_empty() || names.contains(&artboard.name)).collect::<Vec<_>>();
+	if selected.is_empty() {
+		return Err("The document contains no artboards to export".to_string());
+	}
+
+	let output_path = output_path.to_string_lossy();
+	if selected.len() > 1 && !output_path.contains(ARTBOARD_PLACEHOLDER) {
+		return Err(format!("Exporting {} artboards needs an {ARTBOARD_PLACEHOLDER} placeholder in the output file name", selected.len()));
+	}
+
+	let mut used_paths = HashSet::new();
+	selected
+		.into_iter()
+		.map(|artboard| {
+			let path = PathBuf::from(output_path.replace(ARTBOARD_PLACEHOLDER, &artboards::file_name_for(&artboard.name)));
+			if !used_paths.insert(path.clone()) {
+				return Err(format!("Multiple artboards named \"{}\" would be exported to the same file: {}", artboard.name, path.display()));
+			}
+			Ok((path, artboard_render_config(file_type, scale, artboard, transparent)))
+		})
+		.collect()
+}
+
+async fn render_frame(executor: &DynamicExecutor, wgpu_executor: &wgpu_executor::WgpuExecutor, render_config: RenderConfig) -> Result<RenderedFrame, Box<dyn Error>> {
 	// Execute the graph
 	let result = executor.execute(render_config).await?;
 
 	// Handle the result based on output type
 	match result {
 		TaggedValue::RenderOutput(output) => match output.data {
-			RenderOutputType::Svg { svg, .. } => {
-				// Write SVG directly to file
-				std::fs::write(&output_path, svg)?;
-				log::info!("Exported SVG to: {}", output_path.display());
-			}
+			RenderOutputType::Svg { svg, .. } => Ok(RenderedFrame::Svg(svg)),
 			RenderOutputType::Texture(image_texture) => {
 				// Convert GPU texture to CPU buffer
 				let gpu_raster = Raster::<GPU>::new_gpu(image_texture.texture);
 				let cpu_raster: Raster<CPU> = gpu_raster.convert(Footprint::BOUNDLESS, wgpu_executor).await;
 				let (data, width, height) = cpu_raster.to_flat_u8();
 
-				// Encode and write raster image
-				write_raster_image(output_path, file_type, data, width, height, transparent)?;
+				Ok(RenderedFrame::Raster { data, width, height })
 			}
-			RenderOutputType::Buffer { data, width, height } => {
-				// Encode and write raster image when buffer is already provided
-				write_raster_image(output_path, file_type, data, width, height, transparent)?;
+			// The buffer is already provided
+			RenderOutputType::Buffer { data, width, height } => Ok(RenderedFrame::Raster { data, width, height }),
+			RenderOutputType::Pdf { data } => match data {
+				Ok(data) => Ok(RenderedFrame::Pdf(data)),
+				Err(error) => Err(format!("Failed to render the document as a PDF: {error}").into()),
+			},
+			other => Err(format!(
+				"Unexpected render output type: {:?}. Expected Texture, Buffer for raster export, Svg for SVG export, or Pdf for PDF export.",
+				other
+			)
+			.into()),
+		},
+		other => Err(format!("Expected RenderOutput, got: {:?}", other).into()),
+	}
+}
+
+/// Turns the rendered frame into the contents of a file of the given type.
+fn encode_frame(file_type: FileType, frame: RenderedFrame, options: RasterOptions) -> Result<Vec<u8>, Box<dyn Error>> {
+	match frame {
+		RenderedFrame::Svg(svg) => Ok(svg.into_bytes()),
+		RenderedFrame::Pdf(data) => Ok(data),
+		RenderedFrame::Raster { data, width, height } => encode_raster_image(file_type, data, width, height, options),
+	}
+}
+
+fn write_frame(output_path: PathBuf, file_type: FileType, frame: RenderedFrame, options: RasterOptions) -> Result<(), Box<dyn Error>> {
+	std::fs::write(&output_path, encode_frame(file_type, frame, options)?)?;
+	log::info!("Exported {} to: {}", file_type.name(), output_path.display());
+	Ok(())
+}
+
+/// Renders the document and returns the contents of the resulting file rather than writing it to disk.
+pub async fn render_document(
+	executor: &DynamicExecutor,
+	wgpu_executor: &wgpu_executor::WgpuExecutor,
+	file_type: FileType,
+	render_config: RenderConfig,
+	options: RasterOptions,
+) -> Result<Vec<u8>, Box<dyn Error>> {
+	let frame = render_frame(executor, wgpu_executor, render_config).await?;
+	encode_frame(file_type, frame, options)
+}
+
+pub async fn export_document(
+	executor: &DynamicExecutor,
+	wgpu_executor: &wgpu_executor::WgpuExecutor,
+	output_path: PathBuf,
+	file_type: FileType,
+	render_config: RenderConfig,
+	options: RasterOptions,
+) -> Result<(), Box<dyn Error>> {
+	let frame = render_frame(executor, wgpu_executor, render_config).await?;
+	write_frame(output_path, file_type, frame, options)
+}
+
+/// Renders each frame of the animation by stepping the render time, then writes the frames as an image sequence, animated GIF, or APNG.
+pub async fn export_animation(
+	executor: &DynamicExecutor,
+	wgpu_executor: &wgpu_executor::WgpuExecutor,
+	output_path: PathBuf,
+	file_type: FileType,
+	animation: AnimationSettings,
+	render_config: RenderConfig,
+	options: RasterOptions,
+) -> Result<(), Box<dyn Error>> {
+	let output = detect_animation_output(&output_path, file_type)?;
+	if output == AnimationOutput::Gif && options.bit_depth == BitDepth::Sixteen {
+		return Err("GIF animations only support 8-bit color".into());
+	}
+	let frames = animation.frame_indices()?;
+	let frame_count = frames.len() as u32;
+	let frame_number_digits = (frames.end - 1).to_string().len().max(4);
+
+	// Real time advances together with the animation time, starting from the moment the export began
+	let start_time = SystemTime::now().duration_since(UNIX_EPOCH).unwrap_or_default().as_secs_f64() * 1000.;
+
+	let mut encoder = None;
+	for frame_index in frames {
+		let animation_time = animation.frame_time(frame_index);
+		let render_config = RenderConfig {
+			time: TimingInformation {
+				time: start_time + animation_time.as_secs_f64() * 1000.,
+				animation_time,
+			},
+			..render_config
+		};
+
+		log::info!("Rendering frame {frame_index} at {:.3}s", animation_time.as_secs_f64());
+		let frame = render_frame(executor, wgpu_executor, render_config).await?;
+
+		match output {
+			AnimationOutput::Sequence(file_type) => {
+				let frame_path = output_path.to_string_lossy().replace(FRAME_PLACEHOLDER, &format!("{frame_index:0frame_number_digits$}"));
+				write_frame(PathBuf::from(frame_path), file_type, frame, options)?;
 			}
-			other => {
-				return Err(format!("Unexpected render output type: {:?}. Expected Texture, Buffer for raster export or Svg for SVG export.", other).into());
+			AnimationOutput::Gif | AnimationOutput::Apng => {
+				let RenderedFrame::Raster { data, width, height } = frame else {
+					return Err("Expected a raster render for an animated GIF or APNG export".into());
+				};
+
+				let encoder = match &mut encoder {
+					Some(encoder) => encoder,
+					None => encoder.insert(AnimationEncoder::new(output, &output_path, (width, height), frame_count, animation.fps, options)?),
+				};
+				encoder.encode_frame(data, width, height)?;
 			}
-		},
-		other => return Err(format!("Expected RenderOutput, got: {:?}", other).into()),
+		}
+	}
+
+	if let Some(encoder) = encoder {
+		encoder.finish()?;
+		log::info!("Exported {frame_count} frame animation to: {}", output_path.display());
 	}
 
 	Ok(())
 }
 
-fn write_raster_image(output_path: PathBuf, file_type: FileType, data: Vec<u8>, width: u32, height: u32, transparent: bool) -> Result<(), Box<dyn Error>> {
-	use image::{ImageFormat, RgbaImage};
+/// Streams frames of the same size into a single animated image file.
+enum AnimationEncoder {
+	Gif {
+		encoder: image::codecs::gif::GifEncoder<BufWriter<File>>,
+		delay: image::Delay,
+		transparent: bool,
+	},
+	Apng {
+		writer: png::Writer<BufWriter<File>>,
+		size: (u32, u32),
+		transparent: bool,
+		bit_depth: BitDepth,
+	},
+}
+
+impl AnimationEncoder {
+	fn new(output: AnimationOutput, output_path: &Path, (width, height): (u32, u32), frame_count: u32, fps: f64, options: RasterOptions) -> Result<Self, Box<dyn Error>> {
+		let RasterOptions { transparent, bit_depth, .. } = options;
+		let file = BufWriter::new(File::create(output_path)?);
 
-	let image = RgbaImage::from_raw(width, height, data).ok_or("Failed to create image from buffer")?;
+		match output {
+			AnimationOutput::Gif => {
+				let mut encoder = image::codecs::gif::GifEncoder::new_with_speed(file, 10);
+				encoder.set_repeat(image::codecs::gif::Repeat::Infinite)?;
+				let delay = image::Delay::from_saturating_duration(Duration::from_secs_f64(1. / fps));
+				Ok(Self::Gif { encoder, delay, transparent })
+			}
+			AnimationOutput::Apng => {
+				let mut encoder = png::Encoder::new(file, width, height);
+				encoder.set_color(if transparent { png::ColorType::Rgba } else { png::ColorType::Rgb });
+				encoder.set_depth(match bit_depth {
+					BitDepth::Eight => png::BitDepth::Eight,
+					BitDepth::Sixteen => png::BitDepth::Sixteen,
+				});
+				encoder.set_animated(frame_count, 0)?;
+				let (numerator, denominator) = apng_frame_delay(fps);
+				encoder.set_frame_delay(numerator, denominator)?;
+				Ok(Self::Apng {
+					writer: encoder.write_header()?,
+					size: (width, height),
+					transparent,
+					bit_depth,
+				})
+			}
+			AnimationOutput::Sequence(_) => unreachable!("Image sequences are written one file per frame"),
+		}
+	}
+
+	fn encode_frame(&mut self, mut data: Vec<u8>, width: u32, height: u32) -> Result<(), Box<dyn Error>> {
+		match self {
+			Self::Gif { encoder, delay, transparent } => {
+				if !*transparent {
+					data.chunks_exact_mut(4).for_each(|pixel| pixel[3] = 255);
+				}
+				let image = image::RgbaImage::from_raw(width, height, data).ok_or("Failed to create image from buffer")?;
+				encoder.encode_frame(image::Frame::from_parts(image, 0, 0, *delay))?;
+			}
+			Self::Apng { writer, size, transparent, bit_depth } => {
+				if *size != (width, height) {
+					return Err(format!("Frame size {width}x{height} differs from the first frame's size {}x{}", size.0, size.1).into());
+				}
+				if !*transparent {
+					data = data.chunks_exact(4).flat_map(|pixel| [pixel[0], pixel[1], pixel[2]]).collect();
+				}
+				// Widening each 8-bit value `v` to `v * 257` fills the 16-bit range, which as big-endian bytes is `[v, v]`
+				if *bit_depth == BitDepth::Sixteen {
+					data = data.into_iter().flat_map(|value| [value, value]).collect();
+				}
+				writer.write_image_data(&data)?;
+			}
+		}
+		Ok(())
+	}
+
+	fn finish(self) -> Result<(), Box<dyn Error>> {
+		match self {
+			// The GIF trailer is written when the encoder is dropped
+			Self::Gif { .. } => {}
+			Self::Apng { writer, .. } => writer.finish()?,
+		}
+		Ok(())
+	}
+}
+
+/// Expresses the duration of one frame as the `numerator / denominator` seconds fraction used by APNG.
+fn apng_frame_delay(fps: f64) -> (u16, u16) {
+	if fps.fract() == 0. && fps <= u16::MAX as f64 {
+		(1, fps as u16)
+	} else {
+		(100, (fps * 100.).round().clamp(1., u16::MAX as f64) as u16)
+	}
+}
+
+/// Encodes the rendered RGBA pixels into the raster file format.
+/// The renderer outputs 8 bits per channel, so 16-bit images hold those values widened to the full 16-bit range.
+fn encode_raster_image(file_type: FileType, data: Vec<u8>, width: u32, height: u32, options: RasterOptions) -> Result<Vec<u8>, Box<dyn Error>> {
+	use image::codecs::avif::AvifEncoder;
+	use image::codecs::jpeg::JpegEncoder;
+	use image::codecs::webp::WebPEncoder;
+	use image::{DynamicImage, ImageFormat, RgbaImage};
+
+	let RasterOptions { transparent, quality, bit_depth } = options;
+
+	let image = DynamicImage::ImageRgba8(RgbaImage::from_raw(width, height, data).ok_or("Failed to create image from buffer")?);
+	let image = match (bit_depth, transparent) {
+		(BitDepth::Eight, true) => image,
+		(BitDepth::Eight, false) => image.to_rgb8().into(),
+		(BitDepth::Sixteen, _) if !matches!(file_type, FileType::Png | FileType::Tiff) => return Err("16-bit color is only supported for PNG and TIFF exports".into()),
+		(BitDepth::Sixteen, true) => image.to_rgba16().into(),
+		(BitDepth::Sixteen, false) => image.to_rgb16().into(),
+	};
 
 	let mut cursor = Cursor::new(Vec::new());
 
 	match file_type {
-		FileType::Png => {
-			if transparent {
-				image.write_to(&mut cursor, ImageFormat::Png)?;
-			} else {
-				let image: image::RgbImage = image::DynamicImage::ImageRgba8(image).to_rgb8();
-				image.write_to(&mut cursor, ImageFormat::Png)?;
-			}
-			log::info!("Exported PNG to: {}", output_path.display());
+		FileType::Png => image.write_to(&mut cursor, ImageFormat::Png)?,
+		FileType::Jpg => image.to_rgb8().write_with_encoder(JpegEncoder::new_with_quality(&mut cursor, quality.unwrap_or(75)))?,
+		FileType::Gif => image.write_to(&mut cursor, ImageFormat::Gif)?,
+		// The `image` crate only encodes WebP losslessly, so lossy WebP images are encoded with libwebp
+		FileType::Webp => match quality {
+			Some(quality) if quality < 100 => return encode_lossy_webp(&image, quality),
+			_ => image.write_with_encoder(WebPEncoder::new_lossless(&mut cursor))?,
+		},
+		FileType::Avif => image.write_with_encoder(AvifEncoder::new_with_speed_quality(&mut cursor, 4, quality.unwrap_or(80)))?,
+		FileType::Tiff => image.write_to(&mut cursor, ImageFormat::Tiff)?,
+		FileType::Svg | FileType::Pdf => unreachable!("Vector formats are not rendered to raster images"),
+	}
+
+	Ok(cursor.into_inner())
+}
+
+/// Encodes the image as a lossy WebP at a quality from 1 to 100.
+fn encode_lossy_webp(image: &image::DynamicImage, quality: u8) -> Result<Vec<u8>, Box<dyn Error>> {
+	let image = image.to_rgba8();
+	let encoded = webp::Encoder::from_rgba(image.as_raw(), image.width(), image.height())
+		.encode_simple(false, quality as f32)
+		.map_err(|err| format!("Failed to encode WebP: {err:?}"))?;
+	Ok(encoded.to_vec())
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	fn animation(frames: &str, fps: f64, duration: Option<f64>) -> AnimationSettings {
+		AnimationSettings {
+			frames: Some(frames.parse().unwrap()),
+			fps,
+			duration,
 		}
-		FileType::Jpg => {
-			let image: image::RgbImage = image::DynamicImage::ImageRgba8(image).to_rgb8();
-			image.write_to(&mut cursor, ImageFormat::Jpeg)?;
-			log::info!("Exported JPG to: {}", output_path.display());
+	}
+
+	#[test]
+	fn parse_frame_ranges() {
+		assert_eq!("5".parse(), Ok(FrameRange { start: 5, end: None }));
+		assert_eq!("2..10".parse(), Ok(FrameRange { start: 2, end: Some(10) }));
+		assert_eq!("2..=10".parse(), Ok(FrameRange { start: 2, end: Some(11) }));
+		assert_eq!("..4".parse(), Ok(FrameRange { start: 0, end: Some(4) }));
+		assert_eq!(" 1 .. 3 ".parse(), Ok(FrameRange { start: 1, end: Some(3) }));
+	}
+
+	#[test]
+	fn reject_invalid_frame_ranges() {
+		assert!("".parse::<FrameRange>().is_err());
+		assert!("a..3".parse::<FrameRange>().is_err());
+		assert!("1..".parse::<FrameRange>().is_err());
+		assert!("-1".parse::<FrameRange>().is_err());
+		assert!("4..4".parse::<FrameRange>().is_err());
+		assert!("5..2".parse::<FrameRange>().is_err());
+		assert!("3..=3".parse::<FrameRange>().is_ok());
+		assert!(format!("0..={}", u32::MAX).parse::<FrameRange>().is_err());
+	}
+
+	#[test]
+	fn frame_indices_from_range() {
+		assert_eq!(animation("2..10", 30., None).frame_indices(), Ok(2..10));
+		assert_eq!(animation("0..=4", 30., None).frame_indices(), Ok(0..5));
+	}
+
+	#[test]
+	fn frame_indices_from_duration() {
+		// A partial frame at the end of the duration is still rendered
+		assert_eq!(animation("0", 24., Some(1.)).frame_indices(), Ok(0..24));
+		assert_eq!(animation("10", 24., Some(0.5)).frame_indices(), Ok(10..22));
+		assert_eq!(animation("0", 10., Some(0.25)).frame_indices(), Ok(0..3));
+	}
+
+	#[test]
+	fn frame_indices_default_to_single_frame() {
+		let settings = AnimationSettings {
+			frames: None,
+			fps: 30.,
+			duration: None,
+		};
+		assert_eq!(settings.frame_indices(), Ok(0..1));
+		assert_eq!(animation("7", 30., None).frame_indices(), Ok(7..8));
+	}
+
+	#[test]
+	fn frame_indices_reject_invalid_settings() {
+		assert!(animation("0..10", 30., Some(1.)).frame_indices().is_err());
+		assert!(animation("0", 0., None).frame_indices().is_err());
+		assert!(animation("0", f64::NAN, None).frame_indices().is_err());
+		assert!(animation("0", 30., Some(0.)).frame_indices().is_err());
+		assert!(animation("0", 30., Some(f64::INFINITY)).frame_indices().is_err());
+	}
+
+	#[test]
+	fn frame_indices_reject_overflowing_ends() {
+		assert!(animation(&u32::MAX.to_string(), 30., None).frame_indices().is_err());
+		assert!(animation(&(u32::MAX - 10).to_string(), 30., Some(1.)).frame_indices().is_err());
+		assert!(animation("0", 30., Some(1e300)).frame_indices().is_err());
+		assert_eq!(animation(&(u32::MAX - 30).to_string(), 30., Some(1.)).frame_indices(), Ok(u32::MAX - 30..u32::MAX));
+	}
+
+	#[test]
+	fn frame_times() {
+		let settings = animation("0", 25., None);
+		assert_eq!(settings.frame_time(0), Duration::ZERO);
+		assert_eq!(settings.frame_time(50), Duration::from_secs(2));
+	}
+
+	#[test]
+	fn webp_is_lossless_unless_a_lower_quality_is_given() {
+		// Lossless WebP images store their pixels in a `VP8L` chunk and lossy ones in a `VP8 ` chunk
+		let chunk = |transparent, quality| {
+			let options = RasterOptions {
+				transparent,
+				quality,
+				..Default::default()
+			};
+			let encoded = encode_raster_image(FileType::Webp, vec![255; 4 * 4], 2, 2, options).unwrap();
+			assert_eq!(image::guess_format(&encoded).unwrap(), image::ImageFormat::WebP);
+			[b"VP8L", b"VP8 "].into_iter().find(|chunk| encoded.windows(4).any(|window| window == *chunk)).unwrap()
+		};
+
+		assert_eq!(chunk(true, None), b"VP8L");
+		assert_eq!(chunk(true, Some(100)), b"VP8L");
+		assert_eq!(chunk(true, Some(90)), b"VP8 ");
+		assert_eq!(chunk(false, Some(1)), b"VP8 ");
+	}
+
+	#[test]
+	fn sixteen_bit_images_widen_the_rendered_colors() {
+		let options = RasterOptions {
+			transparent: true,
+			quality: None,
+			bit_depth: BitDepth::Sixteen,
+		};
+		let encoded = encode_raster_image(FileType::Png, vec![0, 128, 255, 255], 1, 1, options).unwrap();
+		let image = image::load_from_memory(&encoded).unwrap();
+		assert_eq!(image.color(), image::ColorType::Rgba16);
+		assert_eq!(image.to_rgba16().into_raw(), [0, 128 * 257, u16::MAX, u16::MAX]);
+
+		assert!(encode_raster_image(FileType::Jpg, vec![0; 4], 1, 1, options).is_err());
+		assert!("12".parse::<BitDepth>().is_err());
+	}
+
+	fn artboard(name: &str, x: f64) -> ArtboardInfo {
+		ArtboardInfo {
+			name: name.to_string(),
+			location: DVec2::new(x, 0.),
+			dimensions: DVec2::new(100., 50.),
 		}
-		FileType::Svg => unreachable!("SVG should have been handled in export_document"),
 	}
 
-	std::fs::write(&output_path, cursor.into_inner())?;
-	Ok(())
+	fn target_paths(artboards: &[ArtboardInfo], names: &[&str], output_path: &str) -> Result<Vec<PathBuf>, String> {
+		let names = names.iter().map(|name| name.to_string()).collect::<Vec<_>>();
+		let targets = artboard_targets(artboards, &names, Path::new(output_path), FileType::Png, 1., false)?;
+		Ok(targets.into_iter().map(|(path, _)| path).collect())
+	}
+
+	#[test]
+	fn artboard_targets_substitute_file_names() {
+		let artboards = [artboard("Cover", 0.), artboard("Page 1/2", 200.)];
+
+		let paths = target_paths(&artboards, &[], "out/{artboard}.png").unwrap();
+		assert_eq!(paths, [PathBuf::from("out/Cover.png"), PathBuf::from("out/Page 1_2.png")]);
+
+		let paths = target_paths(&artboards, &["Cover"], "cover.png").unwrap();
+		assert_eq!(paths, [PathBuf::from("cover.png")]);
+	}
+
+	#[test]
+	fn artboard_targets_cover_artboard_regions() {
+		let targets = artboard_targets(&[artboard("Cover", 200.)], &[], Path::new("{artboard}.png"), FileType::Png, 2., true).unwrap();
+		let (_, render_config) = &targets[0];
+
+		assert_eq!(render_config.viewport.transform, DAffine2::from_translation(DVec2::new(-200., 0.)));
+		assert_eq!(render_config.viewport.resolution, UVec2::new(200, 100));
+		assert!(render_config.hide_artboards);
+	}
+
+	#[test]
+	fn artboard_targets_reject_collisions_and_missing_names() {
+		// Both names are sanitized to the same file name
+		let artboards = [artboard("A/B", 0.), artboard("A:B", 200.)];
+		assert!(target_paths(&artboards, &[], "{artboard}.png").is_err());
+
+		let artboards = [artboard("Cover", 0.), artboard("Back", 200.)];
+		assert!(target_paths(&artboards, &[], "all.png").is_err());
+		assert!(target_paths(&artboards, &["Missing"], "{artboard}.png").is_err());
+		assert!(target_paths(&[], &[], "{artboard}.png").is_err());
+	}
 }
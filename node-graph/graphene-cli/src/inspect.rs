@@ -0,0 +1,256 @@
+use crate::artboards::{self, ArtboardInfo};
+use crate::parameters::NetworkNames;
+use graph_craft::document::value::TaggedValue;
+use graph_craft::document::{DocumentNodeImplementation, NodeId, NodeInput, NodeNetwork};
+use graph_craft::proto::{GraphErrors, ProtoNetwork, TypingContext};
+use graphene_std::registry::NODE_METADATA;
+use graphene_std::wasm_application_io::load_resource;
+use interpreted_executor::node_registry::NODE_REGISTRY;
+use std::collections::{BTreeSet, HashSet};
+use std::error::Error;
+
+/// A layer and the layers nested within it.
+#[derive(Debug, Clone, PartialEq)]
+pub struct LayerEntry {
+	pub node_id: NodeId,
+	pub name: String,
+	pub children: Vec<LayerEntry>,
+}
+
+/// Builds the tree of layers in the document network, with each stack of layers ordered from top to bottom.
+pub fn layer_tree(network: &NodeNetwork, names: &NetworkNames) -> Vec<LayerEntry> {
+	let mut visited = HashSet::new();
+	layer_stack(network, names, network.exports.first().and_then(NodeInput::as_node), &mut visited)
+}
+
+/// Walks down a stack starting from the given node, following the primary input of each layer (and of any other nodes in between) to the next layer below it.
+fn layer_stack(network: &NodeNetwork, names: &NetworkNames, mut next: Option<NodeId>, visited: &mut HashSet<NodeId>) -> Vec<LayerEntry> {
+	let mut layers = Vec::new();
+
+	while let Some(node_id) = next {
+		let Some(node) = network.nodes.get(&node_id).filter(|_| visited.insert(node_id)) else { break };
+
+		if names.is_layer(node_id) {
+			// The content of a layer feeds into its secondary input
+			let children = layer_stack(network, names, node.inputs.get(1).and_then(NodeInput::as_node), visited);
+			layers.push(LayerEntry {
+				node_id,
+				name: names.display_name(node_id),
+				children,
+			});
+		}
+
+		next = node.inputs.first().and_then(NodeInput::as_node);
+	}
+
+	layers
+}
+
+/// The fonts, images, and nodes which the document depends on in order to be rendered.
+#[derive(Debug, Default)]
+pub struct Dependencies {
+	/// Font families with their styles.
+	pub fonts: BTreeSet<(String, String)>,
+	/// Images embedded in the document as `(node path, width, height)`.
+	pub images: Vec<(String, u32, u32)>,
+	/// URLs or file paths loaded by the document as `(node path, location)`.
+	pub resources: Vec<(String, String)>,
+	/// Proto node identifiers which have no implementation in this build, as `(node path, identifier)`.
+	pub unknown_nodes: Vec<(String, String)>,
+}
+
+pub fn find_dependencies(network: &NodeNetwork, names: &NetworkNames) -> Dependencies {
+	let mut dependencies = Dependencies::default();
+	collect_dependencies(network, names, &[], &[], &mut dependencies);
+	dependencies
+}
+
+/// Visits the network recursively, where `path` holds the names of the enclosing nodes and `scope` holds their inputs so imports can be resolved to values.
+fn collect_dependencies<'a>(network: &'a NodeNetwork, names: &NetworkNames, path: &[String], scope: &[&'a [NodeInput]], dependencies: &mut Dependencies) {
+	let mut node_ids = network.nodes.keys().copied().collect::<Vec<_>>();
+	node_ids.sort();
+
+	for node_id in node_ids {
+		let node = &network.nodes[&node_id];
+		let mut node_path = path.to_vec();
+		node_path.push(names.display_name(node_id));
+		let location = node_path.join(" > ");
+
+		for value in node.inputs.iter().filter_map(NodeInput::as_value) {
+			match value {
+				TaggedValue::Font(font) => {
+					dependencies.fonts.insert((font.font_family.clone(), font.font_style.clone()));
+				}
+				TaggedValue::Raster(table) => {
+					for row in table.iter() {
+						dependencies.images.push((location.clone(), row.element.width, row.element.height));
+					}
+				}
+				_ => {}
+			}
+		}
+
+		match &node.implementation {
+			DocumentNodeImplementation::Network(nested_network) => {
+				let mut nested_scope = scope.to_vec();
+				nested_scope.push(&node.inputs);
+				collect_dependencies(nested_network, names.nested(node_id), &node_path, &nested_scope, dependencies);
+			}
+			DocumentNodeImplementation::ProtoNode(identifier) => {
+				if *identifier == load_resource::IDENTIFIER {
+					// Inputs of the load resource node: primary, URL
+					match node.inputs.get(1).and_then(|input| artboards::resolve_value(input, scope)) {
+						Some(TaggedValue::String(url)) => dependencies.resources.push((location.clone(), url.clone())),
+						_ => dependencies.resources.push((location.clone(), "(computed by other nodes)".to_string())),
+					}
+				}
+
+				let is_known = NODE_REGISTRY.contains_key(identifier) || NODE_METADATA.lock().unwrap().contains_key(identifier);
+				if !is_known {
+					dependencies.unknown_nodes.push((location, identifier.as_str().to_string()));
+				}
+			}
+			DocumentNodeImplementation::Extract => {}
+		}
+	}
+}
+
+/// Infers the types of every node in the compiled network, which is the check the executor performs before it can run the network.
+pub fn type_check(proto_network: &ProtoNetwork) -> Result<(), GraphErrors> {
+	TypingContext::new(&NODE_REGISTRY).update(proto_network)
+}
+
+/// Prints a report of the document's contents, returning an error if the document can't be compiled or fails to type-check.
+pub fn print_report(network: &NodeNetwork, names: &NetworkNames, artboards: &[ArtboardInfo], proto_network: Result<ProtoNetwork, Box<dyn Error>>) -> Result<(), Box<dyn Error>> {
+	println!("Layers:");
+	let layers = layer_tree(network, names);
+	if layers.is_empty() {
+		println!("  (none)");
+	}
+	print_layers(&layers, 1);
+
+	println!("Artboards:");
+	if artboards.is_empty() {
+		println!("  (none)");
+	}
+	for artboard in artboards {
+		let ArtboardInfo { name, location, dimensions } = artboard;
+		println!("  \"{name}\" at ({}, {}) with size {}x{}", location.x, location.y, dimensions.x, dimensions.y);
+	}
+
+	let dependencies = find_dependencies(network, names);
+
+	println!("Fonts:");
+	if dependencies.fonts.is_empty() {
+		println!("  (none)");
+	}
+	for (family, style) in &dependencies.fonts {
+		println!("  {family} ({style})");
+	}
+
+	println!("Images:");
+	if dependencies.images.is_empty() && dependencies.resources.is_empty() {
+		println!("  (none)");
+	}
+	for (location, width, height) in &dependencies.images {
+		println!("  Embedded {width}x{height} image in {location}");
+	}
+	for (location, url) in &dependencies.resources {
+		println!("  Resource {url} loaded by {location}");
+	}
+
+	println!("Unknown nodes:");
+	if dependencies.unknown_nodes.is_empty() {
+		println!("  (none)");
+	}
+	for (location, identifier) in &dependencies.unknown_nodes {
+		println!("  {identifier} used by {location}");
+	}
+
+	println!("Type checking:");
+	let proto_network = proto_network.map_err(|error| format!("The document could not be compiled: {error}"))?;
+	let Err(errors) = type_check(&proto_network) else {
+		println!("  OK");
+		return Ok(());
+	};
+
+	for error in &errors {
+		// Look up the names of the nodes along the path, which is relative to the document network
+		let mut network_names = names;
+		let location = error
+			.node_path
+			.iter()
+			.map(|&node_id| {
+				let name = network_names.display_name(node_id);
+				network_names = network_names.nested(node_id);
+				name
+			})
+			.collect::<Vec<_>>()
+			.join(" > ");
+		println!("  {location} ({}): {:?}", error.identifier, error.error);
+	}
+
+	Err(format!("The document failed to type-check with {} error(s)", errors.len()).into())
+}
+
+fn print_layers(layers: &[LayerEntry], depth: usize) {
+	for layer in layers {
+		println!("{}{} ({})", "  ".repeat(depth), layer.name, layer.node_id);
+		print_layers(&layer.children, depth + 1);
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use graph_craft::ProtoNodeIdentifier;
+	use graph_craft::document::DocumentNode;
+	use graph_craft::graphene_compiler::Compiler;
+	use graph_craft::proto::GraphErrorType;
+	use graphene_std::math_nodes::add;
+
+	/// Compiles a network with a single node of the given implementation, whose inputs are the given values.
+	fn compile(identifier: ProtoNodeIdentifier, inputs: [TaggedValue; 2]) -> ProtoNetwork {
+		let network = NodeNetwork {
+			exports: vec![NodeInput::node(NodeId(0), 0)],
+			nodes: [(
+				NodeId(0),
+				DocumentNode {
+					inputs: inputs.into_iter().map(|value| NodeInput::value(value, false)).collect(),
+					implementation: DocumentNodeImplementation::ProtoNode(identifier),
+					..Default::default()
+				},
+			)]
+			.into_iter()
+			.collect(),
+			..Default::default()
+		};
+
+		Compiler {}.compile_single(network).expect("Graph should be generated")
+	}
+
+	#[test]
+	fn type_check_accepts_valid_network() {
+		let proto_network = compile(add::IDENTIFIER, [TaggedValue::F64(1.), TaggedValue::F64(2.)]);
+		assert!(type_check(&proto_network).is_ok());
+	}
+
+	#[test]
+	fn type_check_rejects_mismatched_inputs() {
+		let proto_network = compile(add::IDENTIFIER, [TaggedValue::String("1".to_string()), TaggedValue::Bool(true)]);
+		let errors = type_check(&proto_network).unwrap_err();
+		assert!(errors.iter().any(|error| error.identifier == add::IDENTIFIER.as_str()));
+	}
+
+	#[test]
+	fn type_check_rejects_unknown_nodes() {
+		let identifier = ProtoNodeIdentifier::new("graphene_cli::tests::NonexistentNode");
+		let proto_network = compile(identifier.clone(), [TaggedValue::F64(1.), TaggedValue::F64(2.)]);
+		let errors = type_check(&proto_network).unwrap_err();
+		assert!(
+			errors
+				.iter()
+				.any(|error| error.identifier == identifier.as_str() && matches!(error.error, GraphErrorType::NoImplementations))
+		);
+	}
+}
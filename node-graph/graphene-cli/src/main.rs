@@ -1,4 +1,8 @@
+mod artboards;
 mod export;
+mod inspect;
+mod parameters;
+mod watch;
 
 use clap::{Args, Parser, Subcommand};
 use fern::colors::{Color, ColoredLevelConfig};
@@ -6,7 +10,7 @@ use futures::executor::block_on;
 use graph_craft::document::*;
 use graph_craft::graphene_compiler::Compiler;
 use graph_craft::proto::ProtoNetwork;
-use graph_craft::util::load_network;
+use graph_craft::util::try_load_network;
 use graph_craft::wasm_application_io::EditorPreferences;
 use graphene_std::application_io::{ApplicationIo, NodeGraphUpdateMessage, NodeGraphUpdateSender};
 use graphene_std::text::FontCache;
@@ -14,7 +18,8 @@ use graphene_std::wasm_application_io::{WasmApplicationIo, WasmEditorApi};
 use interpreted_executor::dynamic_executor::DynamicExecutor;
 use interpreted_executor::util::wrap_network_in_scope;
 use std::error::Error;
-use std::path::PathBuf;
+use std::net::SocketAddr;
+use std::path::{Path, PathBuf};
 use std::sync::Arc;
 
 struct UpdateLogger {}
@@ -45,13 +50,21 @@ enum Command {
 
 		/// Path to the .graphite document
 		document: PathBuf,
+
+		#[clap(flatten)]
+		parameters: ParameterArgs,
 	},
-	/// Export a .graphite document to a file (SVG, PNG, or JPG).
+	/// Export a .graphite document to a file (SVG, PNG, JPG, GIF, WebP, AVIF, TIFF, or PDF), or an animation to an image sequence, GIF, or APNG.
 	Export {
 		/// Path to the .graphite document
 		document: PathBuf,
 
-		/// Output file path (extension determines format: .svg, .png, .jpg)
+		#[clap(flatten)]
+		parameters: ParameterArgs,
+
+		/// Output file path (extension determines format: .svg, .png, .apng, .jpg, .gif, .webp, .avif, .tiff, .pdf).
+		/// PDF exports contain one page for each artboard, unless a width and height are given.
+		/// For animations, include a `{frame}` placeholder to write a numbered image sequence.
 		#[clap(long, short = 'o')]
 		output: PathBuf,
 
@@ -59,25 +72,111 @@ enum Command {
 		#[clap(long)]
 		image: Option<PathBuf>,
 
-		/// Scale factor for export (default: 1.0)
-		#[clap(long, default_value = "1.0")]
-		scale: f64,
+		#[clap(flatten)]
+		render: RenderArgs,
 
-		/// Output width in pixels
+		/// Range of animation frames to render, as `START..END` (end exclusive), `START..=END`, or a start frame combined with --duration
 		#[clap(long)]
-		width: Option<u32>,
+		frames: Option<export::FrameRange>,
 
-		/// Output height in pixels
-		#[clap(long)]
-		height: Option<u32>,
+		/// Frame rate used to step the animation time between frames
+		#[clap(long, default_value = "30")]
+		fps: f64,
 
-		/// Transparent background for PNG exports
+		/// Length of the animation in seconds, counted from the start frame
 		#[clap(long)]
-		transparent: bool,
+		duration: Option<f64>,
+
+		/// Export each artboard to its own file, substituting the artboard name for an `{artboard}` placeholder in the output path
+		#[clap(long, conflicts_with_all = ["width", "height"])]
+		per_artboard: bool,
+
+		/// Only export the artboard with this name (can be specified multiple times, implies --per-artboard)
+		#[clap(long = "artboard", value_name = "NAME", conflicts_with_all = ["width", "height"])]
+		artboard_names: Vec<String>,
+	},
+	/// Print the layers, artboards, fonts, images, and unknown nodes of a .graphite document, exiting with an error if the document fails to type-check.
+	Inspect {
+		/// Path to the .graphite document
+		document: PathBuf,
+
+		#[clap(flatten)]
+		parameters: ParameterArgs,
+	},
+	/// Keep a .graphite document compiled, exporting it again whenever the file changes, and optionally answer HTTP render requests.
+	Watch {
+		/// Path to the .graphite document
+		document: PathBuf,
+
+		#[clap(flatten)]
+		parameters: ParameterArgs,
+
+		/// Output file path which is exported each time the document changes (extension determines format)
+		#[clap(long, short = 'o', required_unless_present = "serve")]
+		output: Option<PathBuf>,
+
+		#[clap(flatten)]
+		render: RenderArgs,
+
+		/// Address to serve renders on, such as `127.0.0.1:8080`. The file extension of the requested path determines the format,
+		/// and query string values set the render settings (`scale`, `width`, `height`, `transparent`, `quality`, `bit-depth`, `artboard`)
+		/// or override node inputs as `Node.Input=Value`, for example `/render.png?scale=2&Title.Text=Hello`.
+		#[clap(long, value_name = "ADDRESS")]
+		serve: Option<SocketAddr>,
 	},
 	ListNodeIdentifiers,
 }
 
+#[derive(Debug, Args)]
+struct RenderArgs {
+	/// Scale factor for export (default: 1.0)
+	#[clap(long, default_value = "1.0")]
+	scale: f64,
+
+	/// Output width in pixels
+	#[clap(long)]
+	width: Option<u32>,
+
+	/// Output height in pixels
+	#[clap(long)]
+	height: Option<u32>,
+
+	/// Transparent background for PNG exports
+	#[clap(long)]
+	transparent: bool,
+
+	/// Compression quality from 1 to 100 for JPG, WebP, and AVIF exports (default: 75 for JPG, 80 for AVIF). WebP exports are lossless unless a quality below 100 is given.
+	#[clap(long, value_parser = clap::value_parser!(u8).range(1..=100))]
+	quality: Option<u8>,
+
+	/// Bits per color channel, 8 or 16, where 16-bit color is supported for PNG, APNG, and TIFF exports.
+	/// The artwork is rendered with 8-bit color, which 16-bit images widen to the full 16-bit range
+	#[clap(long, default_value = "8")]
+	bit_depth: export::BitDepth,
+}
+
+impl RenderArgs {
+	fn raster_options(&self) -> export::RasterOptions {
+		export::RasterOptions {
+			transparent: self.transparent,
+			quality: self.quality,
+			bit_depth: self.bit_depth,
+		}
+	}
+}
+
+#[derive(Debug, Args)]
+struct ParameterArgs {
+	/// Override the value of a node input before compiling, as `Node.Input=Value` or `Parent/Node.Input=Value` (can be specified multiple times).
+	/// A node is addressed by its name or ID, or inside of a parent node by the name of the parent's output that it is connected to
+	#[clap(long = "set", value_name = "NODE.INPUT=VALUE")]
+	overrides: Vec<parameters::ParameterOverride>,
+
+	/// JSON file with an object of `"Node.Input": value` pairs to override, applied before any --set values
+	#[clap(long = "set-file", value_name = "PATH")]
+	overrides_file: Option<PathBuf>,
+}
+
 #[derive(Debug, Args)]
 struct GlobalOpts {
 	/// Verbosity level (can be specified multiple times)
@@ -93,9 +192,11 @@ async fn main() -> Result<(), Box<dyn Error>> {
 
 	init_logging(log_level);
 
-	let document_path = match app.command {
-		Command::Compile { ref document, .. } => document,
-		Command::Export { ref document, .. } => document,
+	let (document_path, parameter_args) = match app.command {
+		Command::Compile { ref document, ref parameters, .. } => (document, parameters),
+		Command::Export { ref document, ref parameters, .. } => (document, parameters),
+		Command::Inspect { ref document, ref parameters } => (document, parameters),
+		Command::Watch { ref document, ref parameters, .. } => (document, parameters),
 		Command::ListNodeIdentifiers => {
 			let mut nodes: Vec<_> = graphene_std::registry::NODE_METADATA.lock().unwrap().keys().cloned().collect();
 			nodes.sort_by_key(|x| x.as_str().to_string());
@@ -106,7 +207,22 @@ async fn main() -> Result<(), Box<dyn Error>> {
 		}
 	};
 
-	let document_string = std::fs::read_to_string(document_path).expect("Failed to read document");
+	// Apply the parameter overrides from the file first so individual values given on the command line take precedence
+	let mut overrides = match &parameter_args.overrides_file {
+		Some(path) => parameters::read_overrides_file(path)?,
+		None => Vec::new(),
+	};
+	overrides.extend(parameter_args.overrides.iter().cloned());
+	let (network, names) = load_document(document_path, &overrides)?;
+
+	// Artboards are looked up before the network is expanded and wrapped for compilation
+	let artboards = artboards::find_artboards(&network);
+
+	// Inspection only needs to compile the network, so no GPU context is created for it
+	if let Command::Inspect { .. } = app.command {
+		let proto_network = compile_graph(network.clone(), Arc::new(WasmEditorApi::default()));
+		return inspect::print_report(&network, &names, &artboards, proto_network);
+	}
 
 	log::info!("Creating GPU context");
 	let mut application_io = block_on(WasmApplicationIo::new_offscreen());
@@ -133,7 +249,7 @@ async fn main() -> Result<(), Box<dyn Error>> {
 		editor_preferences: Box::new(preferences),
 	});
 
-	let proto_graph = compile_graph(document_string, editor_api)?;
+	let proto_graph = compile_graph(network.clone(), editor_api.clone())?;
 
 	match app.command {
 		Command::Compile { print_proto, .. } => {
@@ -143,28 +259,54 @@ async fn main() -> Result<(), Box<dyn Error>> {
 		}
 		Command::Export {
 			output,
-			scale,
-			width,
-			height,
-			transparent,
+			render,
+			frames,
+			fps,
+			duration,
+			per_artboard,
+			artboard_names,
 			..
 		} => {
-			// Spawn thread to poll GPU device
-			std::thread::spawn(move || {
-				loop {
-					std::thread::sleep(std::time::Duration::from_nanos(10));
-					device.poll(wgpu::PollType::Poll).unwrap();
-				}
-			});
+			spawn_device_poller(device);
 
 			// Detect output file type
 			let file_type = export::detect_file_type(&output)?;
+			let animation = (frames.is_some() || duration.is_some()).then_some(export::AnimationSettings { frames, fps, duration });
+
+			// Determine the output file and render region of each export
+			let RenderArgs {
+				scale, width, height, transparent, ..
+			} = render;
+			let targets = if per_artboard || !artboard_names.is_empty() {
+				export::artboard_targets(&artboards, &artboard_names, &output, file_type, scale, transparent)?
+			} else {
+				vec![(output, export::document_render_config(file_type, scale, (width, height), &artboards, transparent))]
+			};
 
 			// Create executor
 			let executor = create_executor(proto_graph)?;
+			let options = render.raster_options();
 
 			// Perform export
-			export::export_document(&executor, wgpu_executor_ref, output, file_type, scale, (width, height), transparent).await?;
+			for (output, render_config) in targets {
+				match animation {
+					Some(animation) => export::export_animation(&executor, wgpu_executor_ref, output, file_type, animation, render_config, options).await?,
+					None => export::export_document(&executor, wgpu_executor_ref, output, file_type, render_config, options).await?,
+				}
+			}
+		}
+		Command::Watch { document, output, render, serve, .. } => {
+			spawn_device_poller(device);
+
+			let settings = watch::RenderSettings {
+				scale: render.scale,
+				size: (render.width, render.height),
+				options: render.raster_options(),
+			};
+			let executor = create_executor(proto_graph)?;
+			let live_document = watch::LiveDocument::new(document, overrides, network, names, artboards, executor, editor_api);
+
+			watch::run(live_document, wgpu_executor_ref, output, settings, serve).await?;
 		}
 		_ => unreachable!("All other commands should be handled before this match statement is run"),
 	}
@@ -172,6 +314,28 @@ async fn main() -> Result<(), Box<dyn Error>> {
 	Ok(())
 }
 
+fn spawn_device_poller(device: Arc<wgpu::Device>) {
+	// Spawn thread to poll GPU device
+	std::thread::spawn(move || {
+		loop {
+			std::thread::sleep(std::time::Duration::from_nanos(10));
+			device.poll(wgpu::PollType::Poll).unwrap();
+		}
+	});
+}
+
+/// Reads the document's network and applies the parameter overrides to it, returning the network with the names of its nodes.
+fn load_document(path: &Path, overrides: &[parameters::ParameterOverride]) -> Result<(NodeNetwork, parameters::NetworkNames), Box<dyn Error>> {
+	let document_string = std::fs::read_to_string(path).map_err(|e| format!("Failed to read document {}: {e}", path.display()))?;
+	let mut network = try_load_network(&document_string).map_err(|e| format!("Failed to parse document {}: {e}", path.display()))?;
+	fix_nodes(&mut network);
+
+	let names = parameters::NetworkNames::from_document(&document_string);
+	parameters::apply_overrides(&mut network, &names, overrides)?;
+
+	Ok((network, names))
+}
+
 fn init_logging(log_level: u8) {
 	let default_level = match log_level {
 		0 => log::LevelFilter::Error,
@@ -221,10 +385,7 @@ fn fix_nodes(network: &mut NodeNetwork) {
 		}
 	}
 }
-fn compile_graph(document_string: String, editor_api: Arc<WasmEditorApi>) -> Result<ProtoNetwork, Box<dyn Error>> {
-	let mut network = load_network(&document_string);
-	fix_nodes(&mut network);
-
+fn compile_graph(mut network: NodeNetwork, editor_api: Arc<WasmEditorApi>) -> Result<ProtoNetwork, Box<dyn Error>> {
 	let substitutions = preprocessor::generate_node_substitutions();
 	preprocessor::expand_network(&mut network, &substitutions);
 
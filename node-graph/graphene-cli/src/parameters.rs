@@ -0,0 +1,497 @@
+use graph_craft::document::value::TaggedValue;
+use graph_craft::document::{DocumentNodeImplementation, NodeId, NodeNetwork};
+use std::collections::HashMap;
+use std::path::Path;
+use std::str::FromStr;
+use std::sync::LazyLock;
+
+/// A new value for a node input, addressed as `Node.Input` or `Parent/Node.Input` for nodes inside of a node's own network.
+/// Nodes are matched by their display name or ID, or by the name of the network export they are connected to, and inputs by their name or index.
+#[derive(Debug, Clone, PartialEq)]
+pub struct ParameterOverride {
+	pub node_path: Vec<String>,
+	pub input: String,
+	pub value: serde_json::Value,
+}
+
+impl ParameterOverride {
+	fn new(address: &str, value: serde_json::Value) -> Result<Self, String> {
+		let Some((node_path, input)) = address.rsplit_once('.') else {
+			return Err(format!("Parameter \"{address}\" should be addressed as Node.Input"));
+		};
+
+		let node_path = node_path.split('/').map(|name| name.trim().to_string()).collect::<Vec<_>>();
+		let input = input.trim().to_string();
+		if node_path.iter().any(String::is_empty) || input.is_empty() {
+			return Err(format!("Parameter \"{address}\" should be addressed as Node.Input"));
+		}
+
+		Ok(Self { node_path, input, value })
+	}
+
+	fn address(&self) -> String {
+		format!("{}.{}", self.node_path.join("/"), self.input)
+	}
+}
+
+impl FromStr for ParameterOverride {
+	type Err = String;
+
+	fn from_str(s: &str) -> Result<Self, Self::Err> {
+		let Some((address, value)) = s.split_once('=') else {
+			return Err(format!("Parameter \"{s}\" should be given as Node.Input=Value"));
+		};
+
+		Self::new(address, serde_json::Value::String(value.to_string()))
+	}
+}
+
+/// Reads overrides from a JSON object which maps each `Node.Input` address to its new value.
+pub fn read_overrides_file(path: &Path) -> Result<Vec<ParameterOverride>, String> {
+	let contents = std::fs::read_to_string(path).map_err(|e| format!("Failed to read parameter file {}: {e}", path.display()))?;
+	let serde_json::Value::Object(parameters) = serde_json::from_str(&contents).map_err(|e| format!("Failed to parse parameter file {}: {e}", path.display()))? else {
+		return Err(format!("Parameter file {} should contain a JSON object of \"Node.Input\": value pairs", path.display()));
+	};
+
+	parameters.into_iter().map(|(address, value)| ParameterOverride::new(&address, value)).collect()
+}
+
+/// The names the editor shows for the nodes of a network and their inputs, which are stored alongside the network in the document.
+#[derive(Debug, Default)]
+pub struct NetworkNames {
+	nodes: HashMap<NodeId, NodeNames>,
+	/// Names of the network's exports, which are shown as the outputs of the node containing the network.
+	export_names: Vec<String>,
+}
+
+#[derive(Debug, Default)]
+struct NodeNames {
+	name: String,
+	is_layer: bool,
+	input_names: Vec<String>,
+	network: NetworkNames,
+}
+
+impl NetworkNames {
+	pub fn from_document(document_string: &str) -> Self {
+		let document: serde_json::Value = serde_json::from_str(document_string).expect("Failed to parse document");
+		Self::from_metadata(&document["network_interface"]["network_metadata"])
+	}
+
+	fn from_metadata(network_metadata: &serde_json::Value) -> Self {
+		let Some(node_metadata) = network_metadata["persistent_metadata"]["node_metadata"].as_array() else {
+			return Self::default();
+		};
+
+		let nodes = node_metadata
+			.iter()
+			.filter_map(|entry| {
+				let node_id = NodeId(entry[0].as_u64()?);
+				let metadata = &entry[1]["persistent_metadata"];
+
+				// Nodes without a custom display name are shown with the name of their definition
+				let name = [&metadata["display_name"], &metadata["reference"]]
+					.into_iter()
+					.filter_map(|name| name.as_str())
+					.find(|name| !name.is_empty())
+					.unwrap_or_default()
+					.to_string();
+				let is_layer = metadata["node_type_metadata"].get("Layer").is_some();
+				let input_names = metadata["input_metadata"]
+					.as_array()
+					.map(|inputs| inputs.iter().map(|input| input["persistent_metadata"]["input_name"].as_str().unwrap_or_default().to_string()).collect())
+					.unwrap_or_default();
+				let mut network = Self::from_metadata(&metadata["network_metadata"]);
+				network.export_names = metadata["output_names"]
+					.as_array()
+					.map(|outputs| outputs.iter().map(|output| output.as_str().unwrap_or_default().to_string()).collect())
+					.unwrap_or_default();
+
+				Some((node_id, NodeNames { name, is_layer, input_names, network }))
+			})
+			.collect();
+
+		Self { nodes, export_names: Vec::new() }
+	}
+
+	/// The name shown for the node, or its ID if the node has no name.
+	pub fn display_name(&self, node_id: NodeId) -> String {
+		match self.nodes.get(&node_id) {
+			Some(node_names) if !node_names.name.is_empty() => node_names.name.clone(),
+			_ => node_id.to_string(),
+		}
+	}
+
+	pub fn is_layer(&self, node_id: NodeId) -> bool {
+		self.nodes.get(&node_id).is_some_and(|node_names| node_names.is_layer)
+	}
+
+	/// The names within the network of the given node, which are empty if the node has no network.
+	pub fn nested(&self, node_id: NodeId) -> &NetworkNames {
+		static EMPTY: LazyLock<NetworkNames> = LazyLock::new(NetworkNames::default);
+		self.nodes.get(&node_id).map_or(&EMPTY, |node_names| &node_names.network)
+	}
+}
+
+/// Replaces the values of the addressed node inputs, converting each new value to the type of the value it replaces.
+pub fn apply_overrides(network: &mut NodeNetwork, names: &NetworkNames, overrides: &[ParameterOverride]) -> Result<(), String> {
+	for parameter in overrides {
+		apply_override(network, names, parameter).map_err(|e| format!("Cannot set parameter \"{}\": {e}", parameter.address()))?;
+	}
+	Ok(())
+}
+
+fn apply_override(mut network: &mut NodeNetwork, names: &NetworkNames, parameter: &ParameterOverride) -> Result<(), String> {
+	let mut names = names;
+
+	// Descend into the networks of the enclosing nodes
+	let (node_name, parent_path) = parameter.node_path.split_last().expect("Node path is never empty");
+	for parent_name in parent_path {
+		let parent_id = find_node(network, names, parent_name)?;
+		names = names.nested(parent_id);
+		let Some(DocumentNodeImplementation::Network(nested_network)) = network.nodes.get_mut(&parent_id).map(|node| &mut node.implementation) else {
+			return Err(format!("Node \"{parent_name}\" does not contain a network of nodes"));
+		};
+		network = nested_network;
+	}
+
+	let node_id = find_node(network, names, node_name)?;
+	let (node_id, input_index) = find_input(network, names, node_id, &parameter.input).ok_or_else(|| format!("Node \"{node_name}\" has no input named \"{}\"", parameter.input))?;
+
+	let input = &mut network.nodes.get_mut(&node_id).expect("Node was found above").inputs[input_index];
+	let Some(mut tagged_value) = input.as_value_mut() else {
+		return Err("The input is connected to another node rather than holding a value".to_string());
+	};
+	let converted = convert_value(&parameter.value, &tagged_value)?;
+	*tagged_value = converted;
+
+	Ok(())
+}
+
+fn find_node(network: &NodeNetwork, names: &NetworkNames, name: &str) -> Result<NodeId, String> {
+	if let Some(node_id) = name.parse().ok().map(NodeId).filter(|node_id| network.nodes.contains_key(node_id)) {
+		return Ok(node_id);
+	}
+
+	let mut matches = network
+		.nodes
+		.keys()
+		.filter(|node_id| names.nodes.get(node_id).is_some_and(|node_names| node_names.name.eq_ignore_ascii_case(name)));
+	match (matches.next(), matches.next()) {
+		(Some(&node_id), None) => return Ok(node_id),
+		(Some(_), Some(_)) => return Err(format!("Multiple nodes are named \"{name}\", address the node by its ID instead")),
+		(None, _) => {}
+	}
+
+	// Otherwise the name may be that of a network export, which addresses the node connected to it
+	let export_index = names.export_names.iter().position(|export_name| !export_name.is_empty() && export_name.eq_ignore_ascii_case(name));
+	match export_index.and_then(|index| network.exports.get(index)) {
+		Some(export) => export.as_node().ok_or_else(|| format!("The export \"{name}\" is not connected to a node")),
+		None => Err(format!("No node or export named \"{name}\" was found")),
+	}
+}
+
+/// Finds the input by name or index. For a layer, the nodes in its chain are also searched so that the layer's name can be used to address them.
+fn find_input(network: &NodeNetwork, names: &NetworkNames, node_id: NodeId, input: &str) -> Option<(NodeId, usize)> {
+	let input_index = |node_id: NodeId| {
+		let node = network.nodes.get(&node_id)?;
+		let by_index = input.parse::<usize>().ok().filter(|&index| index < node.inputs.len());
+		let by_name = || names.nodes.get(&node_id)?.input_names.iter().position(|name| name.eq_ignore_ascii_case(input));
+		by_index.or_else(by_name).filter(|&index| index < node.inputs.len())
+	};
+
+	if let Some(index) = input_index(node_id) {
+		return Some((node_id, index));
+	}
+	if !names.nodes.get(&node_id).is_some_and(|node_names| node_names.is_layer) {
+		return None;
+	}
+
+	// Walk up the layer's chain, which feeds into its secondary input, following the primary input of each node
+	let mut chain_node = network.nodes.get(&node_id)?.inputs.get(1)?.as_node();
+	for _ in 0..network.nodes.len() {
+		let chain_node_id = chain_node?;
+		if names.nodes.get(&chain_node_id).is_some_and(|node_names| node_names.is_layer) {
+			return None;
+		}
+		if let Some(index) = input_index(chain_node_id) {
+			return Some((chain_node_id, index));
+		}
+		chain_node = network.nodes.get(&chain_node_id)?.inputs.first()?.as_node();
+	}
+
+	None
+}
+
+/// Converts the new value into the same type as the value it replaces, using the same string syntax as node default values,
+/// or otherwise the JSON representation of the type within the document.
+fn convert_value(value: &serde_json::Value, existing: &TaggedValue) -> Result<TaggedValue, String> {
+	let primitive_string = match value {
+		serde_json::Value::String(string) => Some(string.clone()),
+		serde_json::Value::Number(number) => Some(number.to_string()),
+		serde_json::Value::Bool(bool) => Some(bool.to_string()),
+		_ => None,
+	};
+
+	if let Some(string) = primitive_string {
+		// Allow colors to be given as bare hex codes, which the default value syntax requires to be quoted
+		let is_hex_color = matches!(existing, TaggedValue::Color(_) | TaggedValue::ColorNotInTable(_) | TaggedValue::Fill(_)) && !string.starts_with('"') && !string.contains("::");
+		let string = if is_hex_color { format!("\"{string}\"") } else { string };
+
+		if let Some(converted) = TaggedValue::from_primitive_string(&string, &existing.ty()) {
+			return Ok(converted);
+		}
+	}
+
+	// Serialized tagged values are objects with the variant name as their only key
+	let serialized = serde_json::to_value(existing).map_err(|e| e.to_string())?;
+	let Some(variant) = serialized.as_object().and_then(|object| object.keys().next()) else {
+		return Err(format!("Values of type {} cannot be set", existing.ty()));
+	};
+
+	// Strings may contain JSON for structured values, or name an enum variant directly
+	let candidates = match value {
+		serde_json::Value::String(string) => serde_json::from_str(string).into_iter().chain([value.clone()]).collect(),
+		_ => vec![value.clone()],
+	};
+
+	candidates
+		.into_iter()
+		.find_map(|candidate| serde_json::from_value(serde_json::json!({ variant: candidate })).ok())
+		.ok_or_else(|| format!("{value} is not a valid {variant} value"))
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use graph_craft::document::{DocumentNode, NodeInput};
+	use graphene_std::Color;
+	use graphene_std::raster::BlendMode;
+	use serde_json::json;
+
+	/// Builds the node metadata of a document from `(node ID, display name, is layer, input names)` entries.
+	fn names(nodes: &[(u64, &str, bool, &[&str])]) -> NetworkNames {
+		let node_metadata = nodes
+			.iter()
+			.map(|&(node_id, name, is_layer, input_names)| {
+				let node_type_metadata = if is_layer { json!({ "Layer": {} }) } else { json!({ "Node": {} }) };
+				let input_metadata = input_names.iter().map(|name| json!({ "persistent_metadata": { "input_name": name } })).collect::<Vec<_>>();
+				json!([node_id, { "persistent_metadata": { "display_name": name, "node_type_metadata": node_type_metadata, "input_metadata": input_metadata } }])
+			})
+			.collect::<Vec<_>>();
+
+		NetworkNames::from_metadata(&json!({ "persistent_metadata": { "node_metadata": node_metadata } }))
+	}
+
+	fn node(inputs: Vec<NodeInput>) -> DocumentNode {
+		DocumentNode { inputs, ..Default::default() }
+	}
+
+	fn value(value: TaggedValue) -> NodeInput {
+		NodeInput::value(value, false)
+	}
+
+	/// A layer whose chain is a blur node fed by a circle node, followed by another layer whose chain is a rectangle node.
+	fn layer_network() -> (NodeNetwork, NetworkNames) {
+		let network = NodeNetwork {
+			exports: vec![NodeInput::node(NodeId(1), 0)],
+			nodes: [
+				(NodeId(1), node(vec![NodeInput::node(NodeId(4), 0), NodeInput::node(NodeId(2), 0)])),
+				(NodeId(2), node(vec![NodeInput::node(NodeId(3), 0), value(TaggedValue::F64(2.))])),
+				(NodeId(3), node(vec![value(TaggedValue::None), value(TaggedValue::F64(50.))])),
+				(NodeId(4), node(vec![value(TaggedValue::None), NodeInput::node(NodeId(5), 0)])),
+				(NodeId(5), node(vec![value(TaggedValue::None), value(TaggedValue::F64(10.))])),
+			]
+			.into_iter()
+			.collect(),
+			..Default::default()
+		};
+		let names = names(&[
+			(1, "Badge", true, &["Base", "Content"]),
+			(2, "Blur", false, &["Image", "Radius"]),
+			(3, "Circle", false, &["Vector", "Radius"]),
+			(4, "Backdrop", true, &["Base", "Content"]),
+			(5, "Rectangle", false, &["Vector", "Width"]),
+		]);
+		(network, names)
+	}
+
+	fn value_of(network: &NodeNetwork, node_id: u64, input_index: usize) -> &TaggedValue {
+		network.nodes[&NodeId(node_id)].inputs[input_index].as_value().unwrap()
+	}
+
+	#[test]
+	fn parse_overrides() {
+		let parameter: ParameterOverride = "Title.Text=Hello = World".parse().unwrap();
+		assert_eq!(parameter.node_path, ["Title"]);
+		assert_eq!(parameter.input, "Text");
+		assert_eq!(parameter.value, json!("Hello = World"));
+
+		let parameter: ParameterOverride = " Group / Node 1.Radius=".parse().unwrap();
+		assert_eq!(parameter.node_path, ["Group", "Node 1"]);
+		assert_eq!(parameter.input, "Radius");
+		assert_eq!(parameter.value, json!(""));
+
+		// The last dot separates the input, so node names may contain dots
+		let parameter: ParameterOverride = "v1.2.3=4".parse().unwrap();
+		assert_eq!(parameter.node_path, ["v1.2"]);
+		assert_eq!(parameter.input, "3");
+	}
+
+	#[test]
+	fn reject_malformed_overrides() {
+		for malformed in ["Title", "Title=Hello", ".Text=Hello", "Title.=Hello", "Group//Title.Text=Hello", "/Title.Text=Hello"] {
+			assert!(malformed.parse::<ParameterOverride>().is_err(), "{malformed} should not parse");
+		}
+	}
+
+	#[test]
+	fn read_overrides_from_file() {
+		let path = std::env::temp_dir().join(format!("graphene-cli-overrides-{}.json", std::process::id()));
+
+		std::fs::write(&path, r#"{ "Title.Text": "Hello", "Group/Circle.Radius": 5, "Shape.Position": [1, 2] }"#).unwrap();
+		let overrides = read_overrides_file(&path);
+
+		std::fs::write(&path, r#"["Title.Text"]"#).unwrap();
+		let not_an_object = read_overrides_file(&path);
+
+		std::fs::write(&path, r#"{ "Title": "Hello" }"#).unwrap();
+		let missing_input = read_overrides_file(&path);
+
+		std::fs::remove_file(&path).unwrap();
+
+		let mut overrides = overrides.unwrap();
+		overrides.sort_by_key(ParameterOverride::address);
+		let overrides = overrides.into_iter().map(|parameter| (parameter.address(), parameter.value)).collect::<Vec<_>>();
+		assert_eq!(
+			overrides,
+			[
+				("Group/Circle.Radius".to_string(), json!(5)),
+				("Shape.Position".to_string(), json!([1, 2])),
+				("Title.Text".to_string(), json!("Hello")),
+			]
+		);
+		assert!(not_an_object.is_err());
+		assert!(missing_input.is_err());
+		assert!(read_overrides_file(Path::new("nonexistent-parameters.json")).is_err());
+	}
+
+	#[test]
+	fn convert_primitive_values() {
+		assert_eq!(convert_value(&json!("2.5"), &TaggedValue::F64(0.)), Ok(TaggedValue::F64(2.5)));
+		assert_eq!(convert_value(&json!(3), &TaggedValue::U32(0)), Ok(TaggedValue::U32(3)));
+		assert_eq!(convert_value(&json!(true), &TaggedValue::Bool(false)), Ok(TaggedValue::Bool(true)));
+		assert_eq!(convert_value(&json!("Hello"), &TaggedValue::String(String::new())), Ok(TaggedValue::String("Hello".to_string())));
+		assert_eq!(convert_value(&json!("1, 2"), &TaggedValue::DVec2(Default::default())), Ok(TaggedValue::DVec2((1., 2.).into())));
+		assert!(convert_value(&json!("many"), &TaggedValue::F64(0.)).is_err());
+	}
+
+	#[test]
+	fn convert_colors() {
+		let red = Color::from_rgb_str("ff0000").unwrap();
+		let existing = TaggedValue::ColorNotInTable(Color::BLACK);
+
+		assert_eq!(convert_value(&json!("ff0000"), &existing), Ok(TaggedValue::ColorNotInTable(red)));
+		assert_eq!(convert_value(&json!("#ff0000"), &existing), Ok(TaggedValue::ColorNotInTable(red)));
+		assert_eq!(convert_value(&json!("Color::RED"), &existing), Ok(TaggedValue::ColorNotInTable(Color::RED)));
+	}
+
+	#[test]
+	fn convert_structured_values() {
+		let existing = TaggedValue::BlendMode(BlendMode::Normal);
+		assert_eq!(convert_value(&json!("Multiply"), &existing), Ok(TaggedValue::BlendMode(BlendMode::Multiply)));
+		assert_eq!(convert_value(&json!("\"Multiply\""), &existing), Ok(TaggedValue::BlendMode(BlendMode::Multiply)));
+		assert!(convert_value(&json!("Unknown"), &existing).is_err());
+
+		// Structured values may be given as JSON or as a string containing JSON
+		let existing = TaggedValue::VecF64(vec![]);
+		assert_eq!(convert_value(&json!([1., 2.]), &existing), Ok(TaggedValue::VecF64(vec![1., 2.])));
+		assert_eq!(convert_value(&json!("[3, 4]"), &existing), Ok(TaggedValue::VecF64(vec![3., 4.])));
+	}
+
+	#[test]
+	fn find_inputs_by_name_or_index() {
+		let (network, names) = layer_network();
+
+		assert_eq!(find_input(&network, &names, NodeId(2), "radius"), Some((NodeId(2), 1)));
+		assert_eq!(find_input(&network, &names, NodeId(2), "1"), Some((NodeId(2), 1)));
+		assert_eq!(find_input(&network, &names, NodeId(2), "2"), None);
+		assert_eq!(find_input(&network, &names, NodeId(3), "Width"), None);
+	}
+
+	#[test]
+	fn find_inputs_along_layer_chain() {
+		let (network, names) = layer_network();
+
+		// The first node up the chain with a matching input is used
+		assert_eq!(find_input(&network, &names, NodeId(1), "Radius"), Some((NodeId(2), 1)));
+		assert_eq!(find_input(&network, &names, NodeId(1), "Vector"), Some((NodeId(3), 0)));
+		assert_eq!(find_input(&network, &names, NodeId(4), "Width"), Some((NodeId(5), 1)));
+
+		// The walk stops at the layer's own chain rather than continuing into the layers below it
+		assert_eq!(find_input(&network, &names, NodeId(1), "Width"), None);
+	}
+
+	#[test]
+	fn apply_overrides_by_name_and_id() {
+		let (mut network, names) = layer_network();
+		let overrides = ["Badge.Radius=8", "circle.radius=20", "5.1=30"].map(|parameter| parameter.parse().unwrap());
+
+		apply_overrides(&mut network, &names, &overrides).unwrap();
+		assert_eq!(value_of(&network, 2, 1), &TaggedValue::F64(8.));
+		assert_eq!(value_of(&network, 3, 1), &TaggedValue::F64(20.));
+		assert_eq!(value_of(&network, 5, 1), &TaggedValue::F64(30.));
+	}
+
+	#[test]
+	fn apply_overrides_by_export_name() {
+		let inner = NodeNetwork {
+			exports: vec![NodeInput::node(NodeId(7), 0), NodeInput::value(TaggedValue::F64(0.), false)],
+			nodes: [
+				(NodeId(7), node(vec![value(TaggedValue::None), value(TaggedValue::F64(1.))])),
+				(NodeId(8), node(vec![value(TaggedValue::None), value(TaggedValue::F64(1.))])),
+			]
+			.into_iter()
+			.collect(),
+			..Default::default()
+		};
+		let mut network = NodeNetwork {
+			nodes: [(
+				NodeId(1),
+				DocumentNode {
+					implementation: DocumentNodeImplementation::Network(inner),
+					..Default::default()
+				},
+			)]
+			.into_iter()
+			.collect(),
+			..Default::default()
+		};
+		let inner_node = |node_id: u64| json!([node_id, { "persistent_metadata": { "input_metadata": [{}, { "persistent_metadata": { "input_name": "Radius" } }] } }]);
+		let names = NetworkNames::from_metadata(&json!({ "persistent_metadata": { "node_metadata": [[1, { "persistent_metadata": {
+			"display_name": "Group",
+			"output_names": ["Result", "Unconnected"],
+			"network_metadata": { "persistent_metadata": { "node_metadata": [inner_node(7), inner_node(8)] } }
+		} }]] } }));
+
+		apply_overrides(&mut network, &names, &["Group/result.Radius=4".parse().unwrap()]).unwrap();
+		let DocumentNodeImplementation::Network(inner) = &network.nodes[&NodeId(1)].implementation else {
+			unreachable!()
+		};
+		assert_eq!(value_of(inner, 7, 1), &TaggedValue::F64(4.));
+		assert_eq!(value_of(inner, 8, 1), &TaggedValue::F64(1.));
+
+		assert!(apply_overrides(&mut network, &names, &["Group/Unconnected.Radius=4".parse().unwrap()]).is_err());
+		assert!(apply_overrides(&mut network, &names, &["Result.Radius=4".parse().unwrap()]).is_err());
+	}
+
+	#[test]
+	fn reject_unresolvable_overrides() {
+		let (mut network, names) = layer_network();
+
+		for parameter in ["Missing.Radius=1", "Circle.Missing=1", "Badge.Content=1", "Circle.Radius=wide", "Circle/Blur.Radius=1"] {
+			let overrides = [parameter.parse().unwrap()];
+			assert!(apply_overrides(&mut network, &names, &overrides).is_err(), "{parameter} should not be applied");
+		}
+	}
+}
@@ -0,0 +1,479 @@
+use crate::artboards::{self, ArtboardInfo};
+use crate::export::{self, BitDepth, FileType, RasterOptions};
+use crate::parameters::{self, NetworkNames, ParameterOverride};
+use graph_craft::document::NodeNetwork;
+use graphene_std::application_io::RenderConfig;
+use graphene_std::wasm_application_io::WasmEditorApi;
+use interpreted_executor::dynamic_executor::DynamicExecutor;
+use std::error::Error;
+use std::net::SocketAddr;
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+use std::time::{Duration, SystemTime};
+use tokio::io::{AsyncBufReadExt, AsyncReadExt, AsyncWriteExt, BufReader};
+use tokio::net::{TcpListener, TcpStream};
+use tokio::sync::{mpsc, oneshot};
+
+/// How often the document file is checked for changes.
+const POLL_INTERVAL: Duration = Duration::from_millis(250);
+/// Upper limit on the size of an HTTP request's head, which is all that is read of a request.
+const MAX_REQUEST_HEAD_LENGTH: u64 = 16 * 1024;
+/// How long a client has to send its request, and later to receive the response, before its connection is closed.
+const CONNECTION_TIMEOUT: Duration = Duration::from_secs(10);
+/// Number of render requests which can wait for the watch loop before further connections wait to submit theirs.
+const MAX_QUEUED_REQUESTS: usize = 16;
+/// Range of the scale a render request may ask for, so a single request can't make the server render an enormous image.
+const REQUEST_SCALE_RANGE: (f64, f64) = (1. / 16., 16.);
+/// Largest width or height in pixels which a render request may ask for.
+const MAX_REQUEST_DIMENSION: u32 = 16384;
+
+/// The rendered file for a render request, or the HTTP status code and message explaining why it failed.
+type RenderResponse = Result<(FileType, Vec<u8>), (u16, String)>;
+
+/// A render request read by the task serving its connection, which the watch loop renders since it owns the document.
+struct QueuedRequest {
+	target: String,
+	respond: oneshot::Sender<RenderResponse>,
+}
+
+/// The render settings given on the command line, which render requests can override.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct RenderSettings {
+	pub scale: f64,
+	pub size: (Option<u32>, Option<u32>),
+	pub options: RasterOptions,
+}
+
+/// A document which stays compiled between renders, so changes to the file or to its parameters only recompile the nodes that changed.
+pub struct LiveDocument {
+	path: PathBuf,
+	/// Overrides given on the command line, which are applied to the network each time the file is loaded.
+	overrides: Vec<ParameterOverride>,
+	modified: Option<SystemTime>,
+	network: NodeNetwork,
+	names: NetworkNames,
+	artboards: Vec<ArtboardInfo>,
+	executor: DynamicExecutor,
+	editor_api: Arc<WasmEditorApi>,
+	/// The overrides of the render request which the executor was last compiled with, or `None` if that compilation failed.
+	compiled_request_overrides: Option<Vec<ParameterOverride>>,
+}
+
+impl LiveDocument {
+	pub fn new(
+		path: PathBuf,
+		overrides: Vec<ParameterOverride>,
+		network: NodeNetwork,
+		names: NetworkNames,
+		artboards: Vec<ArtboardInfo>,
+		executor: DynamicExecutor,
+		editor_api: Arc<WasmEditorApi>,
+	) -> Self {
+		Self {
+			modified: modified_time(&path),
+			path,
+			overrides,
+			network,
+			names,
+			artboards,
+			executor,
+			editor_api,
+			compiled_request_overrides: Some(Vec::new()),
+		}
+	}
+
+	fn has_changed(&self) -> bool {
+		modified_time(&self.path) != self.modified
+	}
+
+	/// Loads the document from its file again and recompiles the changed parts of its network.
+	async fn reload(&mut self) -> Result<(), Box<dyn Error>> {
+		self.modified = modified_time(&self.path);
+
+		let (network, names) = crate::load_document(&self.path, &self.overrides)?;
+		self.artboards = artboards::find_artboards(&network);
+		self.network = network;
+		self.names = names;
+
+		self.compiled_request_overrides = None;
+		self.compile(&[]).await
+	}
+
+	/// Recompiles the network with the request's overrides applied, unless it was already compiled with the same overrides.
+	async fn compile(&mut self, request_overrides: &[ParameterOverride]) -> Result<(), Box<dyn Error>> {
+		if self.compiled_request_overrides.as_deref() == Some(request_overrides) {
+			return Ok(());
+		}
+		self.compiled_request_overrides = None;
+
+		let mut network = self.network.clone();
+		parameters::apply_overrides(&mut network, &self.names, request_overrides)?;
+		let proto_network = crate::compile_graph(network, self.editor_api.clone())?;
+
+		self.executor.update(proto_network).await.map_err(|(_, errors)| {
+			let errors = errors.iter().map(|error| format!("{error:?}")).collect::<Vec<_>>().join("\n");
+			format!("The document failed to type-check:\n{errors}")
+		})?;
+
+		self.compiled_request_overrides = Some(request_overrides.to_vec());
+		Ok(())
+	}
+}
+
+fn modified_time(path: &Path) -> Option<SystemTime> {
+	std::fs::metadata(path).and_then(|metadata| metadata.modified()).ok()
+}
+
+/// Exports the document each time its file changes and answers render requests on the given address, until the process is stopped.
+pub async fn run(mut document: LiveDocument, wgpu_executor: &wgpu_executor::WgpuExecutor, output: Option<PathBuf>, settings: RenderSettings, serve: Option<SocketAddr>) -> Result<(), Box<dyn Error>> {
+	let output = output.map(|path| export::detect_file_type(&path).map(|file_type| (path, file_type))).transpose()?;
+	if let Some((path, file_type)) = &output {
+		export_output(&document, wgpu_executor, path, *file_type, settings).await;
+	}
+
+	let listener = match serve {
+		Some(address) => {
+			let listener = TcpListener::bind(address).await?;
+			log::info!("Serving renders on http://{}", listener.local_addr()?);
+			Some(listener)
+		}
+		None => None,
+	};
+	log::info!("Watching {} for changes", document.path.display());
+
+	// Each connection is served by its own task so slow clients don't stop the file from being watched
+	let (request_sender, mut request_receiver) = mpsc::channel::<QueuedRequest>(MAX_QUEUED_REQUESTS);
+
+	// Checks are skipped rather than bunched up when a render takes longer than the interval
+	let mut poll = tokio::time::interval(POLL_INTERVAL);
+	poll.set_missed_tick_behavior(tokio::time::MissedTickBehavior::Delay);
+
+	loop {
+		let connection = async {
+			match &listener {
+				Some(listener) => listener.accept().await,
+				None => std::future::pending().await,
+			}
+		};
+
+		tokio::select! {
+			connection = connection => match connection {
+				Ok((stream, _)) => {
+					let request_sender = request_sender.clone();
+					tokio::spawn(async move {
+						if let Err(error) = answer_request(stream, request_sender).await {
+							log::error!("Failed to answer render request: {error}");
+						}
+					});
+				}
+				Err(error) => log::error!("Failed to accept connection: {error}"),
+			},
+			Some(request) = request_receiver.recv() => {
+				let response = render_request(&request.target, &mut document, wgpu_executor, settings).await;
+				// The client may have already disconnected, in which case there is no one left to respond to
+				let _ = request.respond.send(response);
+			},
+			_ = poll.tick() => {
+				if !document.has_changed() {
+					continue;
+				}
+
+				log::info!("Reloading {}", document.path.display());
+				if let Err(error) = document.reload().await {
+					log::error!("{error}");
+					continue;
+				}
+				if let Some((path, file_type)) = &output {
+					export_output(&document, wgpu_executor, path, *file_type, settings).await;
+				}
+			}
+		}
+	}
+}
+
+async fn export_output(document: &LiveDocument, wgpu_executor: &wgpu_executor::WgpuExecutor, path: &Path, file_type: FileType, settings: RenderSettings) {
+	let render_config = export::document_render_config(file_type, settings.scale, settings.size, &document.artboards, settings.options.transparent);
+	if let Err(error) = export::export_document(&document.executor, wgpu_executor, path.to_path_buf(), file_type, render_config, settings.options).await {
+		log::error!("Failed to export {}: {error}", path.display());
+	}
+}
+
+/// A render request, read from a URL such as `/render.png?scale=2&Title.Text=Hello`.
+#[derive(Debug, Clone, PartialEq)]
+struct RenderRequest {
+	file_type: FileType,
+	settings: RenderSettings,
+	artboard: Option<String>,
+	overrides: Vec<ParameterOverride>,
+}
+
+impl RenderRequest {
+	fn parse(target: &str, defaults: RenderSettings) -> Result<Self, String> {
+		let (path, query) = target.split_once('?').unwrap_or((target, ""));
+
+		// Requests without a file extension, like `/`, are rendered as PNG
+		let file_type = match Path::new(path).extension() {
+			Some(_) => export::detect_file_type(Path::new(path))?,
+			None => FileType::Png,
+		};
+
+		let mut request = Self {
+			file_type,
+			settings: defaults,
+			artboard: None,
+			overrides: Vec::new(),
+		};
+
+		for pair in query.split('&').filter(|pair| !pair.is_empty()) {
+			let (key, value) = pair.split_once('=').unwrap_or((pair, ""));
+			let (key, value) = (decode_query_component(key)?, decode_query_component(value)?);
+			let number = |value: &str| {
+				let number = value.parse::<f64>().ok().filter(|number| number.is_finite());
+				number.ok_or_else(|| format!("Invalid value \"{value}\" for \"{key}\""))
+			};
+			let dimension = |value: &str| number(value).map(|number| number.clamp(1., MAX_REQUEST_DIMENSION as f64) as u32);
+
+			match key.as_str() {
+				"scale" => request.settings.scale = number(&value)?.clamp(REQUEST_SCALE_RANGE.0, REQUEST_SCALE_RANGE.1),
+				"width" => request.settings.size.0 = Some(dimension(&value)?),
+				"height" => request.settings.size.1 = Some(dimension(&value)?),
+				"transparent" => request.settings.options.transparent = matches!(value.as_str(), "" | "true" | "1"),
+				"quality" => request.settings.options.quality = Some(number(&value)?.clamp(1., 100.) as u8),
+				"bit-depth" => request.settings.options.bit_depth = value.parse::<BitDepth>()?,
+				"artboard" => request.artboard = Some(value),
+				_ if key.contains('.') => request.overrides.push(format!("{key}={value}").parse()?),
+				_ => return Err(format!("Unknown query parameter \"{key}\", node inputs are set as Node.Input=Value")),
+			}
+		}
+
+		Ok(request)
+	}
+
+	fn render_config(&self, artboards: &[ArtboardInfo]) -> Result<RenderConfig, String> {
+		let RenderSettings { scale, size, options } = self.settings;
+
+		match &self.artboard {
+			Some(name) => {
+				let artboard = artboards
+					.iter()
+					.find(|artboard| &artboard.name == name)
+					.ok_or_else(|| format!("No artboard named \"{name}\" was found in the document"))?;
+				Ok(export::artboard_render_config(self.file_type, scale, artboard, options.transparent))
+			}
+			None => Ok(export::document_render_config(self.file_type, scale, size, artboards, options.transparent)),
+		}
+	}
+}
+
+/// Decodes the percent-encoding of a URL query string key or value, where `+` also stands for a space.
+fn decode_query_component(component: &str) -> Result<String, String> {
+	let mut decoded = Vec::with_capacity(component.len());
+	let mut bytes = component.bytes();
+
+	while let Some(byte) = bytes.next() {
+		match byte {
+			b'+' => decoded.push(b' '),
+			b'%' => {
+				let hex = [bytes.next(), bytes.next()];
+				let value = match hex {
+					[Some(high), Some(low)] => std::str::from_utf8(&[high, low]).ok().and_then(|hex| u8::from_str_radix(hex, 16).ok()),
+					_ => None,
+				};
+				decoded.push(value.ok_or_else(|| format!("Invalid percent-encoding in \"{component}\""))?);
+			}
+			byte => decoded.push(byte),
+		}
+	}
+
+	String::from_utf8(decoded).map_err(|_| format!("Invalid UTF-8 in \"{component}\""))
+}
+
+/// Reads an HTTP request from the connection, queues it to be rendered by the watch loop, and responds with the rendered file.
+async fn answer_request(mut stream: TcpStream, request_sender: mpsc::Sender<QueuedRequest>) -> Result<(), Box<dyn Error + Send + Sync>> {
+	let request_line = tokio::time::timeout(CONNECTION_TIMEOUT, read_request_line(&mut stream))
+		.await
+		.map_err(|_| "Timed out while reading the request")??;
+
+	let response = match request_line.split_whitespace().collect::<Vec<_>>().as_slice() {
+		["GET", target, _] => {
+			let (respond, response) = oneshot::channel();
+			request_sender
+				.send(QueuedRequest { target: target.to_string(), respond })
+				.await
+				.map_err(|_| "The watch loop has stopped")?;
+			response.await.unwrap_or_else(|_| Err((500, "The render request was dropped".to_string())))
+		}
+		[_, _, _] => Err((405, "Only GET requests are supported".to_string())),
+		_ => Err((400, "Malformed request".to_string())),
+	};
+
+	let (status, content_type, body) = match response {
+		Ok((file_type, body)) => (200, file_type.mime(), body),
+		Err((status, message)) => {
+			log::warn!("Render request \"{}\" failed: {message}", request_line.trim());
+			(status, "text/plain; charset=utf-8", message.into_bytes())
+		}
+	};
+	let reason = match status {
+		200 => "OK",
+		400 => "Bad Request",
+		405 => "Method Not Allowed",
+		_ => "Internal Server Error",
+	};
+
+	let head = format!(
+		"HTTP/1.1 {status} {reason}\r\nContent-Type: {content_type}\r\nContent-Length: {}\r\nConnection: close\r\n\r\n",
+		body.len()
+	);
+	let write_response = async {
+		stream.write_all(head.as_bytes()).await?;
+		stream.write_all(&body).await?;
+		stream.shutdown().await
+	};
+	tokio::time::timeout(CONNECTION_TIMEOUT, write_response).await.map_err(|_| "Timed out while sending the response")??;
+
+	Ok(())
+}
+
+/// Reads the head of an HTTP request, up to its size limit, and returns its request line.
+/// Only the request line is needed, but the rest of the head is read so the client isn't cut off while still sending it.
+async fn read_request_line(stream: &mut TcpStream) -> std::io::Result<String> {
+	let mut reader = BufReader::new(stream).take(MAX_REQUEST_HEAD_LENGTH);
+
+	let mut request_line = String::new();
+	reader.read_line(&mut request_line).await?;
+	loop {
+		let mut header = String::new();
+		let length = reader.read_line(&mut header).await?;
+		if length == 0 || header.trim().is_empty() {
+			break;
+		}
+	}
+
+	Ok(request_line)
+}
+
+async fn render_request(target: &str, document: &mut LiveDocument, wgpu_executor: &wgpu_executor::WgpuExecutor, settings: RenderSettings) -> RenderResponse {
+	let request = RenderRequest::parse(target, settings).map_err(|error| (400, error))?;
+	let render_config = request.render_config(&document.artboards).map_err(|error| (400, error))?;
+
+	document.compile(&request.overrides).await.map_err(|error| (400, error.to_string()))?;
+
+	let body = export::render_document(&document.executor, wgpu_executor, request.file_type, render_config, request.settings.options)
+		.await
+		.map_err(|error| (500, error.to_string()))?;
+	log::info!("Rendered {} for request {target}", request.file_type.name());
+
+	Ok((request.file_type, body))
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	const DEFAULTS: RenderSettings = RenderSettings {
+		scale: 1.,
+		size: (None, None),
+		options: RasterOptions {
+			transparent: false,
+			quality: None,
+			bit_depth: BitDepth::Eight,
+		},
+	};
+
+	#[test]
+	fn decode_query_components() {
+		assert_eq!(decode_query_component("Hello"), Ok("Hello".to_string()));
+		assert_eq!(decode_query_component("Hello+World"), Ok("Hello World".to_string()));
+		assert_eq!(decode_query_component("50%25%20off"), Ok("50% off".to_string()));
+		assert_eq!(decode_query_component("%2b%2B"), Ok("++".to_string()));
+		assert_eq!(decode_query_component("%C3%A9t%C3%A9"), Ok("été".to_string()));
+		assert_eq!(decode_query_component(""), Ok(String::new()));
+	}
+
+	#[test]
+	fn reject_invalid_query_components() {
+		assert!(decode_query_component("%").is_err());
+		assert!(decode_query_component("%2").is_err());
+		assert!(decode_query_component("%zz").is_err());
+		assert!(decode_query_component("%C3").is_err());
+	}
+
+	#[test]
+	fn parse_request_defaults() {
+		let request = RenderRequest::parse("/", DEFAULTS).unwrap();
+		assert_eq!(request.file_type, FileType::Png);
+		assert_eq!(request.settings, DEFAULTS);
+		assert_eq!(request.artboard, None);
+		assert!(request.overrides.is_empty());
+
+		assert_eq!(RenderRequest::parse("/render.svg", DEFAULTS).unwrap().file_type, FileType::Svg);
+		assert_eq!(RenderRequest::parse("/render.jpg?", DEFAULTS).unwrap().file_type, FileType::Jpg);
+	}
+
+	#[test]
+	fn parse_request_settings() {
+		let request = RenderRequest::parse("/render.webp?scale=2&width=640&height=480.6&transparent&quality=150&bit-depth=8&artboard=Page+1", DEFAULTS).unwrap();
+		assert_eq!(request.file_type, FileType::Webp);
+		assert_eq!(
+			request.settings,
+			RenderSettings {
+				scale: 2.,
+				size: (Some(640), Some(480)),
+				options: RasterOptions {
+					transparent: true,
+					quality: Some(100),
+					bit_depth: BitDepth::Eight,
+				},
+			}
+		);
+		assert_eq!(request.artboard.as_deref(), Some("Page 1"));
+
+		let request = RenderRequest::parse("/render.png?transparent=false", DEFAULTS).unwrap();
+		assert!(!request.settings.options.transparent);
+	}
+
+	#[test]
+	fn clamp_request_sizes() {
+		let request = RenderRequest::parse("/render.png?scale=1000&width=0&height=1e9", DEFAULTS).unwrap();
+		assert_eq!(request.settings.scale, 16.);
+		assert_eq!(request.settings.size, (Some(1), Some(MAX_REQUEST_DIMENSION)));
+
+		let request = RenderRequest::parse("/render.png?scale=-2&width=-5", DEFAULTS).unwrap();
+		assert_eq!(request.settings.scale, 1. / 16.);
+		assert_eq!(request.settings.size, (Some(1), None));
+	}
+
+	#[test]
+	fn parse_request_overrides() {
+		let request = RenderRequest::parse("/render.png?Title.Text=Hello%2C+World&Group%2FCircle.Radius=5", DEFAULTS).unwrap();
+		let overrides = request
+			.overrides
+			.iter()
+			.map(|parameter| (parameter.node_path.join("/"), parameter.input.as_str(), parameter.value.clone()))
+			.collect::<Vec<_>>();
+		assert_eq!(
+			overrides,
+			[
+				("Title".to_string(), "Text", serde_json::json!("Hello, World")),
+				("Group/Circle".to_string(), "Radius", serde_json::json!("5")),
+			]
+		);
+	}
+
+	#[test]
+	fn reject_invalid_requests() {
+		for target in [
+			"/render.bmp",
+			"/render.png?scale=big",
+			"/render.png?scale=NaN",
+			"/render.png?width=inf",
+			"/render.png?unknown=1",
+			"/render.png?bit-depth=12",
+			"/render.png?.Text=Hello",
+			"/render.png?width=%G0",
+		] {
+			assert!(RenderRequest::parse(target, DEFAULTS).is_err(), "{target} should not parse");
+		}
+	}
+}
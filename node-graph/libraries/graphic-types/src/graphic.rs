@@ -10,6 +10,7 @@ use glam::DAffine2;
 use raster_types::{CPU, GPU, Raster};
 use std::hash::Hash;
 use vector_types::GradientStops;
+use vector_types::pattern::PatternElement;
 // use vector_types::Vector;
 
 pub type Vector = vector_types::Vector<Option<Table<Graphic>>>;
@@ -156,6 +157,101 @@ pub trait IntoGraphicTable {
 		flatten_table(&mut output, content);
 		output
 	}
+
+	/// Deeply flattens the vector and raster content within a graphic table into the elements of a pattern's tile, discarding other content.
+	fn into_pattern_content(self) -> Table<PatternElement>
+	where
+		Self: std::marker::Sized,
+	{
+		fn flatten_table(output: &mut Table<PatternElement>, current_graphic_table: Table<Graphic>) {
+			for current_graphic_row in current_graphic_table.into_iter() {
+				let parent_transform = current_graphic_row.transform;
+				let parent_opacity = current_graphic_row.alpha_blending.opacity;
+
+				// Apply the parent graphic's transform and opacity to each of its child elements
+				let mut push_leaves = |table: Table<PatternElement>| {
+					for mut row in table.into_iter() {
+						row.transform = parent_transform * row.transform;
+						row.alpha_blending.opacity *= parent_opacity;
+						output.push(row);
+					}
+				};
+
+				match current_graphic_row.element {
+					Graphic::Graphic(graphic_table) => {
+						let mut flattened = Table::new();
+						flatten_table(&mut flattened, graphic_table);
+						push_leaves(flattened);
+					}
+					Graphic::Vector(vector_table) => push_leaves(
+						vector_table
+							.into_iter()
+							.map(|row| TableRow {
+								element: PatternElement::Vector(vector_types::Vector {
+									style: row.element.style,
+									colinear_manipulators: row.element.colinear_manipulators,
+									point_domain: row.element.point_domain,
+									segment_domain: row.element.segment_domain,
+									region_domain: row.element.region_domain,
+									upstream_data: (),
+								}),
+								transform: row.transform,
+								alpha_blending: row.alpha_blending,
+								source_node_id: row.source_node_id,
+							})
+							.collect(),
+					),
+					Graphic::RasterCPU(raster_table) => push_leaves(
+						raster_table
+							.into_iter()
+							.map(|row| TableRow {
+								element: PatternElement::Raster(row.element),
+								transform: row.transform,
+								alpha_blending: row.alpha_blending,
+								source_node_id: row.source_node_id,
+							})
+							.collect(),
+					),
+					_ => {}
+				}
+			}
+		}
+
+		let mut output = Table::new();
+		flatten_table(&mut output, self.into_graphic_table());
+		output
+	}
+}
+
+impl IntoGraphicTable for Table<PatternElement> {
+	fn into_graphic_table(self) -> Table<Graphic> {
+		self.into_iter()
+			.map(|row| {
+				let graphic = match row.element {
+					PatternElement::Vector(vector) => Graphic::Vector(Table::new_from_row(TableRow {
+						element: Vector {
+							style: vector.style,
+							colinear_manipulators: vector.colinear_manipulators,
+							point_domain: vector.point_domain,
+							segment_domain: vector.segment_domain,
+							region_domain: vector.region_domain,
+							upstream_data: None,
+						},
+						transform: row.transform,
+						alpha_blending: row.alpha_blending,
+						source_node_id: row.source_node_id,
+					})),
+					PatternElement::Raster(raster) => Graphic::RasterCPU(Table::new_from_row(TableRow {
+						element: raster,
+						transform: row.transform,
+						alpha_blending: row.alpha_blending,
+						source_node_id: row.source_node_id,
+					})),
+				};
+				TableRow::new_from_element(graphic)
+			})
+			.collect()
+	}
 }
 
 impl IntoGraphicTable for Table<Graphic> {
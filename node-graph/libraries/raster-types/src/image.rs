@@ -152,6 +152,22 @@ impl Image<Color> {
 		encoder.write_image(&data, width, height, ::image::ExtendedColorType::Rgba8).expect("failed to encode image as png");
 		png
 	}
+
+	/// Encodes the image as a PNG data URI, unless one is already stored in [`Self::base64_string`].
+	pub fn to_base64_png(&self) -> String {
+		use base64::Engine;
+
+		if let Some(base64_string) = &self.base64_string {
+			return base64_string.clone();
+		}
+
+		let output = self.to_png();
+		let preamble = "data:image/png;base64,";
+		let mut base64_string = String::with_capacity(preamble.len() + output.len() * 4);
+		base64_string.push_str(preamble);
+		base64::engine::general_purpose::STANDARD.encode_string(output, &mut base64_string);
+		base64_string
+	}
 }
 
 use super::*;
@@ -0,0 +1,823 @@
+use crate::renderer::{RenderSvgSegmentList, SvgRender, format_transform_matrix};
+use core_types::color::Color;
+use dyn_any::DynAny;
+use glam::{DAffine2, DVec2};
+use pdf_writer::types::{BlendMode as PdfBlendMode, ColorSpaceOperand, FunctionShadingType, LineCapStyle, LineJoinStyle, MaskType, PaintType, TilingType};
+use pdf_writer::writers::Resources;
+use pdf_writer::{Content, Filter, Finish, Name, Pdf, Rect, Ref, TextStr};
+use std::fmt::Write;
+use usvg::tiny_skia_path::PathSegment;
+use usvg::{BlendMode, FillRule, Group, ImageKind, ImageRendering, LineCap, LineJoin, Node, Paint, SpreadMethod, Stop, Transform};
+
+/// PDF pages are measured in points, of which there are 72 per inch, while SVG user units are CSS pixels, of which there are 96 per inch.
+const POINTS_PER_PIXEL: f32 = 72. / 96.;
+
+/// Mutable state used whilst rendering to a PDF, which collects the SVG content of each page so it can be converted into PDF drawing operations.
+#[derive(Clone, Debug, Default, DynAny)]
+pub struct PdfRender {
+	pub pages: Vec<PdfPage>,
+}
+
+#[derive(Clone, Debug, DynAny)]
+pub struct PdfPage {
+	pub svg: String,
+	pub svg_defs: String,
+	/// The region of the document shown by this page, or `None` to show the region being exported.
+	pub bounds: Option<[DVec2; 2]>,
+}
+
+impl PdfRender {
+	pub fn new() -> Self {
+		Self::default()
+	}
+
+	pub fn add_page(&mut self, render: SvgRender, bounds: Option<[DVec2; 2]>) {
+		self.pages.push(PdfPage {
+			svg: render.svg.to_svg_string(),
+			svg_defs: render.svg_defs,
+			bounds,
+		});
+	}
+
+	/// Writes the pages into a PDF document. Pages with their own bounds, such as artboards, are only included if they overlap the exported region,
+	/// which is given by the viewport transform and size. Other pages show the exported region on top of the optional background color.
+	pub fn to_pdf(&self, viewport_transform: DAffine2, viewport_size: DVec2, viewport_background: Option<Color>) -> Result<Vec<u8>, String> {
+		let viewport_bounds = {
+			let inverse = viewport_transform.inverse();
+			let corners = [DVec2::ZERO, DVec2::new(viewport_size.x, 0.), viewport_size, DVec2::new(0., viewport_size.y)].map(|corner| inverse.transform_point2(corner));
+			let min = corners.iter().fold(DVec2::INFINITY, |min, &corner| min.min(corner));
+			let max = corners.iter().fold(DVec2::NEG_INFINITY, |max, &corner| max.max(corner));
+			[min, max]
+		};
+		let overlaps_viewport = |[min, max]: [DVec2; 2]| min.x < viewport_bounds[1].x && max.x > viewport_bounds[0].x && min.y < viewport_bounds[1].y && max.y > viewport_bounds[0].y;
+
+		let viewport_page = (viewport_transform, viewport_size, viewport_background);
+		let mut pages = self
+			.pages
+			.iter()
+			.filter_map(|page| match page.bounds {
+				Some([a, b]) => {
+					let (min, max) = (a.min(b), a.max(b));
+					overlaps_viewport([min, max]).then_some((page, (DAffine2::from_translation(-min), max - min, None)))
+				}
+				None => Some((page, viewport_page)),
+			})
+			.map(|(page, (transform, size, background))| Ok((page_tree(page, transform, size, background)?, size)))
+			.collect::<Result<Vec<_>, String>>()?;
+
+		// A document must have at least one page, so an empty export produces a blank page
+		if pages.is_empty() {
+			let blank_page = PdfPage {
+				svg: String::new(),
+				svg_defs: String::new(),
+				bounds: None,
+			};
+			pages.push((page_tree(&blank_page, viewport_transform, viewport_size, viewport_background)?, viewport_size));
+		}
+
+		let mut writer = PdfWriter::new();
+		let catalog_id = writer.alloc();
+		let page_tree_id = writer.alloc();
+		let document_info_id = writer.alloc();
+
+		let mut page_ids = Vec::with_capacity(pages.len());
+		for (tree, size) in &pages {
+			let page_id = writer.alloc();
+			let content_id = writer.alloc();
+			page_ids.push(page_id);
+
+			// SVG places the origin at the top left with the y-axis pointing down, while PDF places it at the bottom left with the y-axis pointing up
+			let (width, height) = (size.x as f32 * POINTS_PER_PIXEL, size.y as f32 * POINTS_PER_PIXEL);
+			let base_transform = Transform::from_row(POINTS_PER_PIXEL, 0., 0., -POINTS_PER_PIXEL, 0., height);
+
+			let mut canvas = Canvas::new();
+			canvas.content.transform(pdf_matrix(base_transform));
+			render_group(&mut writer, &mut canvas, tree.root(), base_transform);
+
+			let Canvas { content, resources } = canvas;
+			let content = compress(&content.finish());
+			writer.pdf.stream(content_id, &content).filter(Filter::FlateDecode);
+
+			let mut page = writer.pdf.page(page_id);
+			page.media_box(Rect::new(0., 0., width, height)).parent(page_tree_id).contents(content_id);
+			write_resources(&resources, page.resources());
+		}
+
+		writer.pdf.pages(page_tree_id).count(page_ids.len() as i32).kids(page_ids);
+		writer.pdf.catalog(catalog_id).pages(page_tree_id);
+		writer.pdf.document_info(document_info_id).producer(TextStr("Graphite"));
+
+		Ok(writer.pdf.finish())
+	}
+}
+
+/// Parses the SVG of a page, framed by the transform and size which map it onto the page, into a tree of resolved paths, images, and groups.
+fn page_tree(page: &PdfPage, transform: DAffine2, size: DVec2, background: Option<Color>) -> Result<usvg::Tree, String> {
+	let mut svg = String::new();
+	write!(
+		&mut svg,
+		r#"<svg xmlns="http://www.w3.org/2000/svg" viewBox="0 0 {} {}" width="{}" height="{}"><defs>{}</defs>"#,
+		size.x, size.y, size.x, size.y, page.svg_defs
+	)
+	.unwrap();
+
+	if let Some(background) = background {
+		write!(&mut svg, r##"<rect width="{}" height="{}" fill="#{}""##, size.x, size.y, background.to_rgb_hex_srgb_from_gamma()).unwrap();
+		if background.a() < 1. {
+			write!(&mut svg, r#" fill-opacity="{}""#, background.a()).unwrap();
+		}
+		svg.push_str("/>");
+	}
+
+	let matrix = format_transform_matrix(transform);
+	let transform = if matrix.is_empty() { String::new() } else { format!(r#" transform="{matrix}""#) };
+	write!(&mut svg, r#"<g{transform}>{}</g></svg>"#, page.svg).unwrap();
+
+	usvg::Tree::from_str(&svg, &usvg::Options::default()).map_err(|e| format!("Failed to prepare the page for PDF export: {e}"))
+}
+
+struct PdfWriter {
+	pdf: Pdf,
+	next_ref: Ref,
+}
+
+impl PdfWriter {
+	fn new() -> Self {
+		Self {
+			pdf: Pdf::new(),
+			next_ref: Ref::new(1),
+		}
+	}
+
+	fn alloc(&mut self) -> Ref {
+		self.next_ref.bump()
+	}
+}
+
+#[derive(Clone, Copy, PartialEq, Eq)]
+enum ResourceKind {
+	ExtGState,
+	Pattern,
+	XObject,
+}
+
+/// The drawing operations of a page or form XObject, together with the resources they refer to by name.
+struct Canvas {
+	content: Content,
+	resources: Vec<(ResourceKind, String, Ref)>,
+}
+
+impl Canvas {
+	fn new() -> Self {
+		Self {
+			content: Content::new(),
+			resources: Vec::new(),
+		}
+	}
+
+	fn add_resource(&mut self, kind: ResourceKind, id: Ref) -> String {
+		let prefix = match kind {
+			ResourceKind::ExtGState => "gs",
+			ResourceKind::Pattern => "p",
+			ResourceKind::XObject => "x",
+		};
+		let name = format!("{prefix}{}", self.resources.len());
+		self.resources.push((kind, name.clone(), id));
+		name
+	}
+
+	/// Sets the fill and stroke alpha and the blend mode for the following drawing operations.
+	fn set_graphics_state(&mut self, writer: &mut PdfWriter, alpha: f32, blend_mode: BlendMode, soft_mask: Option<(Ref, MaskType)>) {
+		let id = writer.alloc();
+		let mut state = writer.pdf.ext_graphics(id);
+		state.non_stroking_alpha(alpha).stroking_alpha(alpha).blend_mode(pdf_blend_mode(blend_mode));
+		if let Some((group, mask_type)) = soft_mask {
+			state.soft_mask().subtype(mask_type).group(group);
+		}
+		state.finish();
+
+		let name = self.add_resource(ResourceKind::ExtGState, id);
+		self.content.set_parameters(Name(name.as_bytes()));
+	}
+
+	/// Sets the pattern used to fill or stroke the following drawing operations.
+	fn set_pattern(&mut self, id: Ref, stroke: bool) {
+		let name = self.add_resource(ResourceKind::Pattern, id);
+		if stroke {
+			self.content.set_stroke_color_space(ColorSpaceOperand::Pattern);
+			self.content.set_stroke_pattern(None, Name(name.as_bytes()));
+		} else {
+			self.content.set_fill_color_space(ColorSpaceOperand::Pattern);
+			self.content.set_fill_pattern(None, Name(name.as_bytes()));
+		}
+	}
+}
+
+fn write_resources(list: &[(ResourceKind, String, Ref)], mut resources: Resources) {
+	for kind in [ResourceKind::ExtGState, ResourceKind::Pattern, ResourceKind::XObject] {
+		if !list.iter().any(|(resource_kind, _, _)| *resource_kind == kind) {
+			continue;
+		}
+
+		let mut dict = match kind {
+			ResourceKind::ExtGState => resources.ext_g_states(),
+			ResourceKind::Pattern => resources.patterns(),
+			ResourceKind::XObject => resources.x_objects(),
+		};
+		for (_, name, id) in list.iter().filter(|(resource_kind, _, _)| *resource_kind == kind) {
+			dict.pair(Name(name.as_bytes()), *id);
+		}
+	}
+}
+
+/// Writes a form XObject containing the canvas, which is drawn as a transparency group so opacity and blending apply to its content as a whole.
+fn write_form(writer: &mut PdfWriter, canvas: Canvas, bounds: usvg::Rect, luminosity: bool) -> Ref {
+	let id = writer.alloc();
+	let Canvas { content, resources } = canvas;
+	let content = compress(&content.finish());
+
+	let mut form = writer.pdf.form_xobject(id, &content);
+	form.filter(Filter::FlateDecode);
+	form.bbox(Rect::new(bounds.left(), bounds.top(), bounds.right(), bounds.bottom()));
+	let mut group = form.group();
+	group.transparency().isolated(true);
+	if luminosity {
+		group.color_space().device_rgb();
+	}
+	group.finish();
+	write_resources(&resources, form.resources());
+	form.finish();
+
+	id
+}
+
+/// Draws the group's children, where `transform` maps the group's parent coordinate system to the canvas coordinate system.
+fn render_group(writer: &mut PdfWriter, canvas: &mut Canvas, group: &Group, transform: Transform) {
+	if !group.filters().is_empty() {
+		log::warn!("Filters are not supported in PDF export, so they are left out");
+	}
+
+	let transform = transform.pre_concat(group.transform());
+	canvas.content.save_state();
+	canvas.content.transform(pdf_matrix(group.transform()));
+
+	if let Some(clip_path) = group.clip_path() {
+		apply_clip_path(canvas, clip_path, Transform::identity());
+	}
+
+	let needs_transparency_group = group.isolate() || group.opacity().get() < 1. || group.blend_mode() != BlendMode::Normal || group.mask().is_some();
+	if needs_transparency_group {
+		let bounds = group.layer_bounding_box().to_rect();
+
+		// The content of a form XObject is placed in the coordinate system in which it is drawn, so it starts again from the identity transform
+		let mut group_canvas = Canvas::new();
+		render_children(writer, &mut group_canvas, group, Transform::identity());
+		let form_id = write_form(writer, group_canvas, bounds, false);
+
+		let soft_mask = group.mask().map(|mask| {
+			if mask.mask().is_some() {
+				log::warn!("Nested masks are not supported in PDF export, so only the outermost mask is applied");
+			}
+			let mask_type = match mask.kind() {
+				usvg::MaskType::Luminance => MaskType::Luminosity,
+				usvg::MaskType::Alpha => MaskType::Alpha,
+			};
+
+			let mut mask_canvas = Canvas::new();
+			let rect = mask.rect();
+			mask_canvas.content.rect(rect.x(), rect.y(), rect.width(), rect.height()).clip_nonzero().end_path();
+			render_children(writer, &mut mask_canvas, mask.root(), Transform::identity());
+			(write_form(writer, mask_canvas, rect.to_rect(), mask_type == MaskType::Luminosity), mask_type)
+		});
+
+		canvas.set_graphics_state(writer, group.opacity().get(), group.blend_mode(), soft_mask);
+		let name = canvas.add_resource(ResourceKind::XObject, form_id);
+		canvas.content.x_object(Name(name.as_bytes()));
+	} else {
+		render_children(writer, canvas, group, transform);
+	}
+
+	canvas.content.restore_state();
+}
+
+fn render_children(writer: &mut PdfWriter, canvas: &mut Canvas, group: &Group, transform: Transform) {
+	for child in group.children() {
+		match child {
+			Node::Group(group) => render_group(writer, canvas, group, transform),
+			Node::Path(path) => render_path(writer, canvas, path, transform),
+			Node::Image(image) => render_image(writer, canvas, image, transform),
+			// Text is drawn as outlines so the document doesn't depend on its fonts being available
+			Node::Text(text) => render_group(writer, canvas, text.flattened(), transform),
+		}
+	}
+}
+
+/// Intersects the clipping region with the clip path, where `transform` maps the clip path's coordinates to the current coordinate system.
+fn apply_clip_path(canvas: &mut Canvas, clip_path: &usvg::ClipPath, transform: Transform) {
+	let transform = transform.pre_concat(clip_path.transform());
+	if let Some(parent_clip_path) = clip_path.clip_path() {
+		apply_clip_path(canvas, parent_clip_path, transform);
+	}
+
+	let mut fill_rule = FillRule::NonZero;
+	let mut is_empty = true;
+	visit_clip_paths(clip_path.root(), transform, &mut |path, transform| {
+		if let Some(data) = path.data().clone().transform(transform) {
+			write_path(&mut canvas.content, &data);
+			fill_rule = path.fill().map_or(FillRule::NonZero, |fill| fill.rule());
+			is_empty = false;
+		}
+	});
+
+	// An empty clip path hides everything it's applied to
+	if is_empty {
+		canvas.content.rect(0., 0., 0., 0.);
+	}
+
+	match fill_rule {
+		FillRule::NonZero => canvas.content.clip_nonzero(),
+		FillRule::EvenOdd => canvas.content.clip_even_odd(),
+	};
+	canvas.content.end_path();
+}
+
+fn visit_clip_paths(group: &Group, transform: Transform, visit: &mut impl FnMut(&usvg::Path, Transform)) {
+	let transform = transform.pre_concat(group.transform());
+	for child in group.children() {
+		match child {
+			Node::Group(group) => visit_clip_paths(group, transform, visit),
+			Node::Path(path) if path.is_visible() => visit(path, transform),
+			Node::Text(text) => visit_clip_paths(text.flattened(), transform, visit),
+			_ => {}
+		}
+	}
+}
+
+fn render_path(writer: &mut PdfWriter, canvas: &mut Canvas, path: &usvg::Path, transform: Transform) {
+	if !path.is_visible() {
+		return;
+	}
+
+	match path.paint_order() {
+		usvg::PaintOrder::FillAndStroke => {
+			fill_path(writer, canvas, path, transform);
+			stroke_path(writer, canvas, path, transform);
+		}
+		usvg::PaintOrder::StrokeAndFill => {
+			stroke_path(writer, canvas, path, transform);
+			fill_path(writer, canvas, path, transform);
+		}
+	}
+}
+
+fn fill_path(writer: &mut PdfWriter, canvas: &mut Canvas, path: &usvg::Path, transform: Transform) {
+	let Some(fill) = path.fill() else { return };
+
+	canvas.content.save_state();
+	set_paint(writer, canvas, fill.paint(), fill.opacity().get(), path, transform, false);
+	write_path(&mut canvas.content, path.data());
+	match fill.rule() {
+		FillRule::NonZero => canvas.content.fill_nonzero(),
+		FillRule::EvenOdd => canvas.content.fill_even_odd(),
+	};
+	canvas.content.restore_state();
+}
+
+fn stroke_path(writer: &mut PdfWriter, canvas: &mut Canvas, path: &usvg::Path, transform: Transform) {
+	let Some(stroke) = path.stroke() else { return };
+
+	canvas.content.save_state();
+	set_paint(writer, canvas, stroke.paint(), stroke.opacity().get(), path, transform, true);
+	canvas.content.set_line_width(stroke.width().get());
+	canvas.content.set_line_cap(match stroke.linecap() {
+		LineCap::Butt => LineCapStyle::ButtCap,
+		LineCap::Round => LineCapStyle::RoundCap,
+		LineCap::Square => LineCapStyle::ProjectingSquareCap,
+	});
+	canvas.content.set_line_join(match stroke.linejoin() {
+		LineJoin::Miter | LineJoin::MiterClip => LineJoinStyle::MiterJoin,
+		LineJoin::Round => LineJoinStyle::RoundJoin,
+		LineJoin::Bevel => LineJoinStyle::BevelJoin,
+	});
+	canvas.content.set_miter_limit(stroke.miterlimit().get());
+	if let Some(dash_array) = stroke.dasharray() {
+		canvas.content.set_dash_pattern(dash_array.iter().copied(), stroke.dashoffset());
+	}
+
+	write_path(&mut canvas.content, path.data());
+	canvas.content.stroke();
+	canvas.content.restore_state();
+}
+
+/// Sets the paint used to fill or stroke the path.
+fn set_paint(writer: &mut PdfWriter, canvas: &mut Canvas, paint: &Paint, opacity: f32, path: &usvg::Path, transform: Transform, stroke: bool) {
+	let gradient = match paint {
+		Paint::Color(color) => {
+			let [r, g, b] = [color.red, color.green, color.blue].map(|channel| channel as f32 / 255.);
+			if stroke {
+				canvas.content.set_stroke_rgb(r, g, b);
+			} else {
+				canvas.content.set_fill_rgb(r, g, b);
+			}
+			if opacity < 1. {
+				canvas.set_graphics_state(writer, opacity, BlendMode::Normal, None);
+			}
+			return;
+		}
+		Paint::LinearGradient(gradient) => Gradient::Linear(gradient),
+		Paint::RadialGradient(gradient) => Gradient::Radial(gradient),
+		Paint::Pattern(pattern) => {
+			let pattern_id = write_tiling_pattern(writer, pattern, transform);
+			canvas.set_pattern(pattern_id, stroke);
+			if opacity < 1. {
+				canvas.set_graphics_state(writer, opacity, BlendMode::Normal, None);
+			}
+			return;
+		}
+	};
+
+	// Varying opacity is applied by a soft mask which draws the same gradient in grayscale, where white is opaque
+	let stops = gradient.stops();
+	let uniform_opacity = stops.iter().all(|stop| stop.opacity() == stops[0].opacity());
+	if uniform_opacity {
+		let alpha = opacity * stops.first().map_or(1., |stop| stop.opacity().get());
+		if alpha < 1. {
+			canvas.set_graphics_state(writer, alpha, BlendMode::Normal, None);
+		}
+	} else if let Some(bounds) = path.stroke_bounding_box().to_non_zero_rect() {
+		let mut mask_canvas = Canvas::new();
+		let mask_pattern = write_gradient_pattern(writer, gradient, gradient.transform(), true);
+		let name = mask_canvas.add_resource(ResourceKind::Pattern, mask_pattern);
+		mask_canvas.content.set_fill_color_space(ColorSpaceOperand::Pattern);
+		mask_canvas.content.set_fill_pattern(None, Name(name.as_bytes()));
+		mask_canvas.content.rect(bounds.x(), bounds.y(), bounds.width(), bounds.height()).fill_nonzero();
+
+		let mask_id = write_form(writer, mask_canvas, bounds.to_rect(), true);
+		canvas.set_graphics_state(writer, opacity, BlendMode::Normal, Some((mask_id, MaskType::Luminosity)));
+	}
+
+	// The pattern is positioned in the coordinate system of the canvas rather than that of the path
+	let pattern_id = write_gradient_pattern(writer, gradient, transform.pre_concat(gradient.transform()), false);
+	canvas.set_pattern(pattern_id, stroke);
+}
+
+/// Writes a tiling pattern which repeats the pattern's tile, such as the tiles of pattern fills and the single tile of image fills.
+/// Like gradients, the pattern is positioned in the coordinate system of the canvas given by `transform`.
+fn write_tiling_pattern(writer: &mut PdfWriter, pattern: &usvg::Pattern, transform: Transform) -> Ref {
+	let rect = pattern.rect();
+
+	// The tile's content is drawn with the top left corner of the tile at the origin
+	let mut tile_canvas = Canvas::new();
+	render_children(writer, &mut tile_canvas, pattern.root(), Transform::identity());
+	let Canvas { content, resources } = tile_canvas;
+	let content = compress(&content.finish());
+
+	let id = writer.alloc();
+	let mut tiling = writer.pdf.tiling_pattern(id, &content);
+	tiling.filter(Filter::FlateDecode);
+	tiling.paint_type(PaintType::Colored).tiling_type(TilingType::ConstantSpacing);
+	tiling.bbox(Rect::new(0., 0., rect.width(), rect.height()));
+	tiling.x_step(rect.width()).y_step(rect.height());
+	tiling.matrix(pdf_matrix(transform.pre_concat(pattern.transform()).pre_translate(rect.x(), rect.y())));
+	write_resources(&resources, tiling.resources());
+	tiling.finish();
+
+	id
+}
+
+#[derive(Clone, Copy)]
+enum Gradient<'a> {
+	Linear(&'a usvg::LinearGradient),
+	Radial(&'a usvg::RadialGradient),
+}
+
+impl Gradient<'_> {
+	fn stops(&self) -> &[Stop] {
+		match self {
+			Self::Linear(gradient) => gradient.stops(),
+			Self::Radial(gradient) => gradient.stops(),
+		}
+	}
+
+	fn transform(&self) -> Transform {
+		match self {
+			Self::Linear(gradient) => gradient.transform(),
+			Self::Radial(gradient) => gradient.transform(),
+		}
+	}
+
+	fn spread_method(&self) -> SpreadMethod {
+		match self {
+			Self::Linear(gradient) => gradient.spread_method(),
+			Self::Radial(gradient) => gradient.spread_method(),
+		}
+	}
+}
+
+/// Writes a shading pattern for the gradient, either in color or, for use in a soft mask, as the grayscale opacity of its stops.
+fn write_gradient_pattern(writer: &mut PdfWriter, gradient: Gradient, transform: Transform, opacity_only: bool) -> Ref {
+	if gradient.spread_method() != SpreadMethod::Pad {
+		log::warn!("Repeating and reflecting gradients are not supported in PDF export, so they are extended with their end colors instead");
+	}
+
+	let function_id = if opacity_only {
+		write_gradient_function(writer, gradient.stops(), |stop| vec![stop.opacity().get()])
+	} else {
+		write_gradient_function(writer, gradient.stops(), |stop| {
+			let color = stop.color();
+			vec![color.red as f32 / 255., color.green as f32 / 255., color.blue as f32 / 255.]
+		})
+	};
+
+	let pattern_id = writer.alloc();
+	let mut pattern = writer.pdf.shading_pattern(pattern_id);
+	pattern.matrix(pdf_matrix(transform));
+	let mut shading = pattern.function_shading();
+	match gradient {
+		Gradient::Linear(linear) => {
+			shading.shading_type(FunctionShadingType::Axial).coords([linear.x1(), linear.y1(), linear.x2(), linear.y2()]);
+		}
+		Gradient::Radial(radial) => {
+			shading
+				.shading_type(FunctionShadingType::Radial)
+				.coords([radial.fx(), radial.fy(), 0., radial.cx(), radial.cy(), radial.r().get()]);
+		}
+	}
+	if opacity_only {
+		shading.color_space().device_gray();
+	} else {
+		shading.color_space().device_rgb();
+	}
+	shading.function(function_id).extend([true, true]);
+	shading.finish();
+	pattern.finish();
+
+	pattern_id
+}
+
+/// Writes a function which interpolates between the values of the gradient stops over the domain from 0 to 1.
+fn write_gradient_function(writer: &mut PdfWriter, stops: &[Stop], value: impl Fn(&Stop) -> Vec<f32>) -> Ref {
+	// The first and last stops are repeated at the ends of the domain so the stitched segments cover all of it
+	let mut points = stops.iter().map(|stop| (stop.offset().get(), value(stop))).collect::<Vec<_>>();
+	if let Some((_, first)) = points.first().cloned().filter(|(offset, _)| *offset > 0.) {
+		points.insert(0, (0., first));
+	}
+	if let Some((_, last)) = points.last().cloned().filter(|(offset, _)| *offset < 1.) {
+		points.push((1., last));
+	}
+
+	let segment_ids = points
+		.windows(2)
+		.map(|pair| {
+			let id = writer.alloc();
+			writer.pdf.exponential_function(id).domain([0., 1.]).c0(pair[0].1.iter().copied()).c1(pair[1].1.iter().copied()).n(1.);
+			id
+		})
+		.collect::<Vec<_>>();
+
+	if let [segment_id] = segment_ids[..] {
+		return segment_id;
+	}
+
+	let id = writer.alloc();
+	let bounds = points[1..points.len() - 1].iter().map(|(offset, _)| *offset);
+	let encode = segment_ids.iter().flat_map(|_| [0., 1.]);
+	writer.pdf.stitching_function(id).domain([0., 1.]).functions(segment_ids.iter().copied()).bounds(bounds).encode(encode);
+	id
+}
+
+fn render_image(writer: &mut PdfWriter, canvas: &mut Canvas, image: &usvg::Image, transform: Transform) {
+	if !image.is_visible() {
+		return;
+	}
+
+	let size = image.size();
+	let data = match image.kind() {
+		ImageKind::SVG(tree) => {
+			canvas.content.save_state();
+			canvas.content.rect(0., 0., size.width(), size.height()).clip_nonzero().end_path();
+			let scale = Transform::from_scale(size.width() / tree.size().width(), size.height() / tree.size().height());
+			canvas.content.transform(pdf_matrix(scale));
+			render_group(writer, canvas, tree.root(), transform.pre_concat(scale));
+			canvas.content.restore_state();
+			return;
+		}
+		ImageKind::JPEG(data) | ImageKind::PNG(data) | ImageKind::GIF(data) | ImageKind::WEBP(data) => data,
+	};
+
+	let decoded = match image::load_from_memory(data) {
+		Ok(decoded) => decoded.to_rgba8(),
+		Err(e) => {
+			log::warn!("Failed to decode an image for PDF export, so it is left out: {e}");
+			return;
+		}
+	};
+	let (width, height) = decoded.dimensions();
+	let mut color = Vec::with_capacity(decoded.len() / 4 * 3);
+	let mut alpha = Vec::with_capacity(decoded.len() / 4);
+	for pixel in decoded.pixels() {
+		color.extend_from_slice(&pixel.0[..3]);
+		alpha.push(pixel.0[3]);
+	}
+	let interpolate = image.rendering_mode() == ImageRendering::OptimizeQuality;
+
+	let alpha_id = alpha.iter().any(|&alpha| alpha < u8::MAX).then(|| {
+		let id = writer.alloc();
+		let alpha = compress(&alpha);
+		let mut mask = writer.pdf.image_xobject(id, &alpha);
+		mask.width(width as i32).height(height as i32).bits_per_component(8).interpolate(interpolate);
+		mask.filter(Filter::FlateDecode);
+		mask.color_space().device_gray();
+		mask.finish();
+		id
+	});
+
+	let image_id = writer.alloc();
+	let color = compress(&color);
+	let mut image_xobject = writer.pdf.image_xobject(image_id, &color);
+	image_xobject.width(width as i32).height(height as i32).bits_per_component(8).interpolate(interpolate);
+	image_xobject.filter(Filter::FlateDecode);
+	image_xobject.color_space().device_rgb();
+	if let Some(alpha_id) = alpha_id {
+		image_xobject.s_mask(alpha_id);
+	}
+	image_xobject.finish();
+
+	// Images fill the unit square with their first row at the top, which is flipped because the y-axis of the SVG coordinate system points down
+	let name = canvas.add_resource(ResourceKind::XObject, image_id);
+	canvas.content.save_state();
+	canvas.content.transform([size.width(), 0., 0., -size.height(), 0., size.height()]);
+	canvas.content.x_object(Name(name.as_bytes()));
+	canvas.content.restore_state();
+}
+
+fn write_path(content: &mut Content, path: &usvg::tiny_skia_path::Path) {
+	let mut last = (0., 0.);
+	for segment in path.segments() {
+		match segment {
+			PathSegment::MoveTo(point) => {
+				content.move_to(point.x, point.y);
+				last = (point.x, point.y);
+			}
+			PathSegment::LineTo(point) => {
+				content.line_to(point.x, point.y);
+				last = (point.x, point.y);
+			}
+			PathSegment::QuadTo(control, point) => {
+				// Elevate the quadratic curve to the equivalent cubic curve, which is all PDF supports
+				let handle_start = (last.0 + (control.x - last.0) * 2. / 3., last.1 + (control.y - last.1) * 2. / 3.);
+				let handle_end = (point.x + (control.x - point.x) * 2. / 3., point.y + (control.y - point.y) * 2. / 3.);
+				content.cubic_to(handle_start.0, handle_start.1, handle_end.0, handle_end.1, point.x, point.y);
+				last = (point.x, point.y);
+			}
+			PathSegment::CubicTo(handle_start, handle_end, point) => {
+				content.cubic_to(handle_start.x, handle_start.y, handle_end.x, handle_end.y, point.x, point.y);
+				last = (point.x, point.y);
+			}
+			PathSegment::Close => {
+				content.close_path();
+			}
+		}
+	}
+}
+
+fn pdf_matrix(transform: Transform) -> [f32; 6] {
+	[transform.sx, transform.ky, transform.kx, transform.sy, transform.tx, transform.ty]
+}
+
+fn pdf_blend_mode(blend_mode: BlendMode) -> PdfBlendMode {
+	match blend_mode {
+		BlendMode::Normal => PdfBlendMode::Normal,
+		BlendMode::Multiply => PdfBlendMode::Multiply,
+		BlendMode::Screen => PdfBlendMode::Screen,
+		BlendMode::Overlay => PdfBlendMode::Overlay,
+		BlendMode::Darken => PdfBlendMode::Darken,
+		BlendMode::Lighten => PdfBlendMode::Lighten,
+		BlendMode::ColorDodge => PdfBlendMode::ColorDodge,
+		BlendMode::ColorBurn => PdfBlendMode::ColorBurn,
+		BlendMode::HardLight => PdfBlendMode::HardLight,
+		BlendMode::SoftLight => PdfBlendMode::SoftLight,
+		BlendMode::Difference => PdfBlendMode::Difference,
+		BlendMode::Exclusion => PdfBlendMode::Exclusion,
+		BlendMode::Hue => PdfBlendMode::Hue,
+		BlendMode::Saturation => PdfBlendMode::Saturation,
+		BlendMode::Color => PdfBlendMode::Color,
+		BlendMode::Luminosity => PdfBlendMode::Luminosity,
+	}
+}
+
+fn compress(data: &[u8]) -> Vec<u8> {
+	miniz_oxide::deflate::compress_to_vec_zlib(data, 6)
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	fn page(svg: &str, bounds: Option<[DVec2; 2]>) -> PdfPage {
+		PdfPage {
+			svg: svg.to_string(),
+			svg_defs: String::new(),
+			bounds,
+		}
+	}
+
+	fn artboard(x: f64) -> PdfPage {
+		page(r##"<rect width="100" height="50" fill="#ff0000"/>"##, Some([DVec2::new(x, 0.), DVec2::new(x + 100., 50.)]))
+	}
+
+	/// Renders the pages with a viewport covering the region from the origin to the given size, returning the document as text.
+	/// Content streams are compressed, but the dictionaries describing the pages and their resources are readable.
+	fn render(pages: Vec<PdfPage>, viewport_size: DVec2) -> String {
+		let data = PdfRender { pages }.to_pdf(DAffine2::IDENTITY, viewport_size, None).unwrap();
+		assert!(data.starts_with(b"%PDF-"));
+		String::from_utf8_lossy(&data).into_owned()
+	}
+
+	fn page_count(pdf: &str) -> usize {
+		pdf.matches("/Type /Page\n").count()
+	}
+
+	#[test]
+	fn one_page_per_artboard() {
+		let pdf = render(vec![artboard(0.), artboard(200.), artboard(400.)], DVec2::new(500., 50.));
+		assert_eq!(page_count(&pdf), 3);
+		assert!(pdf.contains("/Count 3"));
+
+		// Pages are sized to their artboards, in points rather than pixels
+		assert_eq!(pdf.matches("/MediaBox [0 0 75 37.5]").count(), 3);
+	}
+
+	#[test]
+	fn artboards_outside_viewport_are_left_out() {
+		let pdf = render(vec![artboard(0.), artboard(200.), artboard(1000.)], DVec2::new(300., 50.));
+		assert_eq!(page_count(&pdf), 2);
+		assert!(pdf.contains("/Count 2"));
+	}
+
+	#[test]
+	fn content_without_artboards_fills_viewport() {
+		let pdf = render(vec![page(r#"<rect width="10" height="10"/>"#, None)], DVec2::new(400., 200.));
+		assert_eq!(page_count(&pdf), 1);
+		assert!(pdf.contains("/MediaBox [0 0 300 150]"));
+
+		// A document always has a page, even with nothing to show
+		let pdf = render(Vec::new(), DVec2::new(400., 200.));
+		assert_eq!(page_count(&pdf), 1);
+		assert!(pdf.contains("/MediaBox [0 0 300 150]"));
+	}
+
+	#[test]
+	fn opaque_content_is_drawn_without_transparency_groups() {
+		let pdf = render(vec![page(r##"<g><rect width="10" height="10" fill="#00ff00"/></g>"##, None)], DVec2::splat(100.));
+		assert!(!pdf.contains("/Transparency"));
+		assert!(!pdf.contains("/ExtGState"));
+	}
+
+	#[test]
+	fn opacity_and_blending_use_transparency_groups() {
+		let svg = r#"<g opacity="0.5"><rect width="10" height="10"/></g><g style="mix-blend-mode:multiply"><rect width="10" height="10"/></g>"#;
+		let pdf = render(vec![page(svg, None)], DVec2::splat(100.));
+
+		// Each group is drawn as an isolated transparency group, with its opacity and blend mode applied to the group as a whole
+		assert_eq!(pdf.matches("/S /Transparency").count(), 2);
+		assert_eq!(pdf.matches("/I true").count(), 2);
+		assert!(pdf.contains("/ca 0.5"));
+		assert!(pdf.contains("/CA 0.5"));
+		assert!(pdf.contains("/BM /Multiply"));
+	}
+
+	#[test]
+	fn masks_use_soft_mask_groups() {
+		let svg = r#"<mask id="m"><rect width="5" height="10" fill="white"/></mask><g mask="url(#m)"><rect width="10" height="10"/></g>"#;
+		let pdf = render(vec![page(svg, None)], DVec2::splat(100.));
+
+		assert!(pdf.contains("/SMask"));
+		assert!(pdf.contains("/S /Luminosity"));
+		assert!(pdf.contains("/CS /DeviceRGB"));
+	}
+
+	#[test]
+	fn pattern_fills_use_tiling_patterns() {
+		let svg = r##"<pattern id="p" width="4" height="4" patternUnits="userSpaceOnUse"><rect width="2" height="2" fill="#0000ff"/></pattern><rect width="10" height="10" fill="url(#p)"/>"##;
+		let pdf = render(vec![page(svg, None)], DVec2::splat(100.));
+
+		assert!(pdf.contains("/PatternType 1"));
+		assert!(pdf.contains("/BBox [0 0 4 4]"));
+		assert!(pdf.contains("/XStep 4"));
+		assert!(pdf.contains("/YStep 4"));
+	}
+
+	#[test]
+	fn image_fills_draw_their_image_in_the_tile() {
+		const PIXEL: &str = "data:image/png;base64,iVBORw0KGgoAAAANSUhEUgAAAAEAAAABCAYAAAAfFcSJAAAADUlEQVR4nGP4z8DwHwAFAAH/iZk9HQAAAABJRU5ErkJggg==";
+		let svg = format!(
+			r#"<pattern id="i" width="10" height="10" patternUnits="userSpaceOnUse"><image width="10" height="10" href="{PIXEL}"/></pattern><rect width="10" height="10" fill="url(#i)" fill-opacity="0.5"/>"#
+		);
+		let pdf = render(vec![page(&svg, None)], DVec2::splat(100.));
+
+		assert!(pdf.contains("/PatternType 1"));
+		assert!(pdf.contains("/Subtype /Image"));
+		assert!(pdf.contains("/ca 0.5"));
+	}
+}
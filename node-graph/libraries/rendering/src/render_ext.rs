@@ -1,11 +1,15 @@
-use crate::renderer::{RenderParams, black_or_white_for_best_contrast, format_transform_matrix};
+use crate::renderer::{Render, RenderParams, RenderSvgSegmentList, SvgRender, black_or_white_for_best_contrast, format_transform_matrix};
 use core_types::consts::LAYER_OUTLINE_STROKE_WEIGHT;
 use core_types::uuid::generate_uuid;
-use glam::DAffine2;
+use glam::{DAffine2, DVec2};
+use graphic_types::IntoGraphicTable;
 use graphic_types::vector_types::gradient::{Gradient, GradientType};
-use graphic_types::vector_types::vector::style::{Fill, PaintOrder, PathStyle, RenderMode, Stroke, StrokeAlign, StrokeCap, StrokeJoin};
+use graphic_types::vector_types::vector::style::{AppearancePaint, Fill, PaintOrder, PathStyle, RenderMode, Stroke, StrokeAlign, StrokeCap, StrokeJoin};
 use std::fmt::Write;
 
+/// The number of wedges of solid color which approximate a conic gradient in SVG.
+const CONIC_WEDGES: usize = 360;
+
 pub trait RenderExt {
 	type Output;
 	fn render(&self, svg_defs: &mut String, element_transform: DAffine2, stroke_transform: DAffine2, bounds: DAffine2, transformed_bounds: DAffine2, render_params: &RenderParams) -> Self::Output;
@@ -16,8 +20,14 @@ impl RenderExt for Gradient {
 
 	// /// Adds the gradient def through mutating the first argument, returning the gradient ID.
 	fn render(&self, svg_defs: &mut String, element_transform: DAffine2, stroke_transform: DAffine2, bounds: DAffine2, transformed_bounds: DAffine2, _render_params: &RenderParams) -> Self::Output {
+		// SVG only blends stops evenly in sRGB, so other interpolation spaces and midpoints are approximated with extra stops
+		let stops = match self.gradient_type {
+			GradientType::Reflected => self.stops.mirrored(),
+			_ => self.stops.clone(),
+		}
+		.to_plain_srgb();
 		let mut stop = String::new();
-		for (position, color) in self.stops.0.iter() {
+		for (position, color) in stops.iter() {
 			stop.push_str("<stop");
 			if *position != 0. {
 				let _ = write!(stop, r#" offset="{}""#, (position * 1_000_000.).round() / 1_000_000.);
@@ -33,21 +43,29 @@ impl RenderExt for Gradient {
 		let start = transform_points.transform_point2(self.start);
 		let end = transform_points.transform_point2(self.end);
 
-		let gradient_transform = if transformed_bounds.matrix2.determinant() != 0. {
+		// Maps the gradient's unit space into the space of the transformed start and end points, which is only invertible if they differ
+		let unit_transform = self.unit_transform(transform_points);
+		let invertible = unit_transform.matrix2.determinant() != 0. && element_transform.matrix2.determinant() != 0.;
+		let gradient_type = if invertible { self.gradient_type } else { GradientType::Linear };
+
+		let bounds_inverse = if transformed_bounds.matrix2.determinant() != 0. {
 			transformed_bounds.inverse()
 		} else {
 			DAffine2::IDENTITY // Ignore if the transform cannot be inverted (the bounds are zero). See issue #1944.
 		};
-		let gradient_transform = format_transform_matrix(gradient_transform);
-		let gradient_transform = if gradient_transform.is_empty() {
-			String::new()
-		} else {
-			format!(r#" gradientTransform="{gradient_transform}""#)
+		let gradient_transform_attribute = |gradient_transform: DAffine2| {
+			let gradient_transform = format_transform_matrix(gradient_transform);
+			if gradient_transform.is_empty() {
+				String::new()
+			} else {
+				format!(r#" gradientTransform="{gradient_transform}""#)
+			}
 		};
+		let gradient_transform = gradient_transform_attribute(bounds_inverse);
 
 		let gradient_id = generate_uuid();
 
-		match self.gradient_type {
+		match gradient_type {
 			GradientType::Linear => {
 				let _ = write!(
 					svg_defs,
@@ -55,12 +73,87 @@ impl RenderExt for Gradient {
 					gradient_id, start.x, start.y, end.x, end.y, stop
 				);
 			}
+			GradientType::Reflected => {
+				let reflected_end = start * 2. - end;
+				let _ = write!(
+					svg_defs,
+					r#"<linearGradient id="{}" x1="{}" y1="{}" x2="{}" y2="{}"{gradient_transform}>{}</linearGradient>"#,
+					gradient_id, reflected_end.x, reflected_end.y, end.x, end.y, stop
+				);
+			}
 			GradientType::Radial => {
-				let radius = (f64::powi(start.x - end.x, 2) + f64::powi(start.y - end.y, 2)).sqrt();
+				let gradient_transform = gradient_transform_attribute(bounds_inverse * unit_transform);
+				let focal_point = unit_transform.inverse().transform_point2(transform_points.transform_point2(self.focal_point()));
+				let focal_attributes = if focal_point == DVec2::ZERO {
+					String::new()
+				} else {
+					format!(r#" fx="{}" fy="{}""#, focal_point.x, focal_point.y)
+				};
+				let _ = write!(
+					svg_defs,
+					r#"<radialGradient id="{}" cx="0" cy="0" r="1"{focal_attributes}{gradient_transform}>{}</radialGradient>"#,
+					gradient_id, stop
+				);
+			}
+			GradientType::Conic | GradientType::Diamond => {
+				// SVG has no conic or diamond gradients, so they are drawn in unit space as the tile of a pattern large enough to cover the whole shape
+				let pattern_transform = format_transform_matrix(element_transform.inverse() * unit_transform);
+				let pattern_transform = if pattern_transform.is_empty() {
+					String::new()
+				} else {
+					format!(r#" patternTransform="{pattern_transform}""#)
+				};
+				let shape_to_unit_space = unit_transform.inverse() * transformed_bounds;
+				let extent = [DVec2::ZERO, DVec2::X, DVec2::Y, DVec2::ONE]
+					.into_iter()
+					.map(|corner| shape_to_unit_space.transform_point2(corner).abs().max_element())
+					.fold(1., f64::max)
+					.ceil();
+
+				let mut content = String::new();
+				if gradient_type == GradientType::Conic {
+					// Reaches the corners of the tile from its center
+					let radius = extent * std::f64::consts::SQRT_2 * 1.01;
+					for index in 0..CONIC_WEDGES {
+						// Each wedge overlaps the next by a little so no seams show through between them
+						let angle = |fraction: f64| (fraction / CONIC_WEDGES as f64) * std::f64::consts::TAU;
+						let (start_angle, end_angle) = (angle(index as f64), angle(index as f64 + 1.5).min(std::f64::consts::TAU));
+						let (start_point, end_point) = (DVec2::from_angle(start_angle) * radius, DVec2::from_angle(end_angle) * radius);
+						let color = self.stops.evaluate((index as f64 + 0.5) / CONIC_WEDGES as f64);
+						let _ = write!(
+							content,
+							r##"<polygon points="0,0 {},{} {},{}" fill="#{}""##,
+							start_point.x,
+							start_point.y,
+							end_point.x,
+							end_point.y,
+							color.to_rgb_hex_srgb_from_gamma()
+						);
+						if color.a() < 1. {
+							let _ = write!(content, r#" fill-opacity="{}""#, (color.a() * 1000.).round() / 1000.);
+						}
+						content.push_str(" />");
+					}
+				} else {
+					// Each quadrant of the diamond is a linear gradient reaching its edge halfway along the diagonal, and the others are mirror images of it
+					let quadrant_id = generate_uuid();
+					let _ = write!(
+						svg_defs,
+						r#"<linearGradient id="{quadrant_id}" gradientUnits="userSpaceOnUse" x1="0" y1="0" x2="0.5" y2="0.5">{stop}</linearGradient>"#
+					);
+					for scale in ["", r#" transform="scale(-1 1)""#, r#" transform="scale(1 -1)""#, r#" transform="scale(-1 -1)""#] {
+						let _ = write!(content, r##"<rect width="{extent}" height="{extent}"{scale} fill="url('#{quadrant_id}')" />"##);
+					}
+				}
+
+				// The tile's contents are positioned from its corner, so they're moved to put the origin at its center
 				let _ = write!(
 					svg_defs,
-					r#"<radialGradient id="{}" cx="{}" cy="{}" r="{}"{gradient_transform}>{}</radialGradient>"#,
-					gradient_id, start.x, start.y, radius, stop
+					r#"<pattern id="{gradient_id}" patternUnits="userSpaceOnUse" x="{}" y="{}" width="{}" height="{}"{pattern_transform}><g transform="translate({extent} {extent})">{content}</g></pattern>"#,
+					-extent,
+					-extent,
+					extent * 2.,
+					extent * 2.,
 				);
 			}
 		}
@@ -87,10 +180,71 @@ impl RenderExt for Fill {
 				let gradient_id = gradient.render(svg_defs, element_transform, stroke_transform, bounds, transformed_bounds, render_params);
 				format!(r##" fill="url('#{gradient_id}')""##)
 			}
+			Self::Pattern(pattern) => {
+				let Some([tile_min, tile_max]) = pattern.tile() else {
+					return r#" fill="none""#.to_string();
+				};
+
+				// Images inside a pattern can't be canvas placeholders, so the content is always rendered as it would be for export
+				let content_params = RenderParams { for_export: true, ..*render_params };
+				let mut content = SvgRender::new();
+				pattern.content.clone().into_graphic_table().render_svg(&mut content, &content_params);
+				svg_defs.push_str(&content.svg_defs);
+
+				// The tile's contents are positioned from its corner, so they're moved back by where the tile starts, less the offset
+				let content_min = tile_min - pattern.offset;
+				let pattern_id = generate_uuid();
+				write_pattern(
+					svg_defs,
+					pattern_id,
+					[tile_min, tile_max],
+					stroke_transform * pattern.transform,
+					-content_min,
+					&content.svg.to_svg_string(),
+				);
+				format!(r##" fill="url('#{pattern_id}')""##)
+			}
+			Self::Image(image) => {
+				let bounds_min = bounds.translation;
+				let bounds_max = bounds.transform_point2(DVec2::ONE);
+				if image.image.data.is_empty() || bounds_max.cmple(bounds_min).any() {
+					return r#" fill="none""#.to_string();
+				}
+
+				// A single tile spanning the layer's bounds holds the image, which can't be a canvas placeholder inside a pattern
+				let image_transform = format_transform_matrix(image.image_transform([bounds_min, bounds_max]));
+				let image_transform = if image_transform.is_empty() {
+					String::new()
+				} else {
+					format!(r#" transform="{image_transform}""#)
+				};
+				let content = format!(r#"<image width="1" height="1" preserveAspectRatio="none" href="{}"{image_transform} />"#, image.image.to_base64_png());
+
+				let pattern_id = generate_uuid();
+				write_pattern(svg_defs, pattern_id, [bounds_min, bounds_max], stroke_transform, -bounds_min, &content);
+				format!(r##" fill="url('#{pattern_id}')""##)
+			}
 		}
 	}
 }
 
+/// Adds a `<pattern>` def repeating a tile over the given rectangle, with its content moved by the given translation from the tile's corner.
+fn write_pattern(svg_defs: &mut String, pattern_id: u64, [tile_min, tile_max]: [DVec2; 2], pattern_transform: DAffine2, content_translation: DVec2, content: &str) {
+	let pattern_transform = format_transform_matrix(pattern_transform);
+	let pattern_transform = if pattern_transform.is_empty() {
+		String::new()
+	} else {
+		format!(r#" patternTransform="{pattern_transform}""#)
+	};
+	let size = tile_max - tile_min;
+
+	let _ = write!(
+		svg_defs,
+		r#"<pattern id="{pattern_id}" patternUnits="userSpaceOnUse" x="{}" y="{}" width="{}" height="{}"{pattern_transform}><g transform="translate({} {})">{content}</g></pattern>"#,
+		tile_min.x, tile_min.y, size.x, size.y, content_translation.x, content_translation.y,
+	);
+}
+
 impl RenderExt for Stroke {
 	type Output = String;
 
@@ -157,10 +311,57 @@ impl RenderExt for Stroke {
 	}
 }
 
+/// The fill and stroke to draw a path with, borrowed from its [`PathStyle`] or given separately so drawing the path with other paint doesn't need a copy of it.
+#[derive(Clone, Copy)]
+pub(crate) struct VectorPaint<'a> {
+	pub fill: &'a Fill,
+	pub stroke: Option<&'a Stroke>,
+}
+
+impl<'a> From<&'a PathStyle> for VectorPaint<'a> {
+	fn from(style: &'a PathStyle) -> Self {
+		Self {
+			fill: &style.fill,
+			stroke: style.stroke.as_ref(),
+		}
+	}
+}
+
+impl<'a> From<&'a AppearancePaint> for VectorPaint<'a> {
+	fn from(paint: &'a AppearancePaint) -> Self {
+		match paint {
+			AppearancePaint::Fill(fill) => Self { fill, stroke: None },
+			AppearancePaint::Stroke(stroke) => Self {
+				fill: &Fill::None,
+				stroke: Some(stroke),
+			},
+		}
+	}
+}
+
+impl VectorPaint<'_> {
+	pub fn without_fill(self) -> Self {
+		Self { fill: &Fill::None, ..self }
+	}
+
+	pub fn without_stroke(self) -> Self {
+		Self { stroke: None, ..self }
+	}
+}
+
 impl RenderExt for PathStyle {
 	type Output = String;
 
 	/// Renders the shape's fill and stroke attributes as a string with them concatenated together.
+	fn render(&self, svg_defs: &mut String, element_transform: DAffine2, stroke_transform: DAffine2, bounds: DAffine2, transformed_bounds: DAffine2, render_params: &RenderParams) -> String {
+		VectorPaint::from(self).render(svg_defs, element_transform, stroke_transform, bounds, transformed_bounds, render_params)
+	}
+}
+
+impl RenderExt for VectorPaint<'_> {
+	type Output = String;
+
+	/// Renders the fill and stroke attributes as a string with them concatenated together.
 	#[allow(clippy::too_many_arguments)]
 	fn render(&self, svg_defs: &mut String, element_transform: DAffine2, stroke_transform: DAffine2, bounds: DAffine2, transformed_bounds: DAffine2, render_params: &RenderParams) -> String {
 		let render_mode = render_params.render_mode;
@@ -180,7 +381,6 @@ impl RenderExt for PathStyle {
 				let fill_attribute = self.fill.render(svg_defs, element_transform, stroke_transform, bounds, transformed_bounds, render_params);
 				let stroke_attribute = self
 					.stroke
-					.as_ref()
 					.map(|stroke| stroke.render(svg_defs, element_transform, stroke_transform, bounds, transformed_bounds, render_params))
 					.unwrap_or_default();
 				format!("{fill_attribute}{stroke_attribute}")
@@ -188,3 +388,212 @@ impl RenderExt for PathStyle {
 		}
 	}
 }
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use core_types::color::Color;
+	use core_types::table::Table;
+	use graphic_types::Vector;
+	use graphic_types::raster_types::{Image, Raster};
+	use graphic_types::vector_types::subpath::Subpath;
+	use graphic_types::vector_types::vector::style::{ImageFill, ImageFit, PatternElement, PatternFill};
+
+	/// The bounds of the filled shape, chosen so the coordinates of the gradients below work out to exact binary fractions.
+	fn bounds() -> DAffine2 {
+		DAffine2::from_scale(DVec2::splat(128.))
+	}
+
+	/// Renders the fill over the given bounds, returning its attributes and the defs it added.
+	fn render_fill(fill: Fill, bounds: DAffine2) -> (String, String) {
+		let mut svg_defs = String::new();
+		let attributes = fill.render(&mut svg_defs, DAffine2::IDENTITY, DAffine2::IDENTITY, bounds, bounds, &RenderParams::default());
+		(attributes, svg_defs)
+	}
+
+	/// Checks the fill refers to a def, returning the defs from that def's tag onwards.
+	fn referenced_def<'a>(attributes: &str, svg_defs: &'a str) -> &'a str {
+		let id = attributes
+			.strip_prefix(r#" fill="url('#"#)
+			.and_then(|id| id.strip_suffix(r#"')""#))
+			.expect("The fill should refer to a def");
+		let start = svg_defs.find(&format!(r#" id="{id}""#)).expect("The referenced def should exist");
+		let tag_start = svg_defs[..start].rfind('<').unwrap();
+		&svg_defs[tag_start..]
+	}
+
+	fn gradient(gradient_type: GradientType) -> Gradient {
+		Gradient::new(DVec2::new(0.5, 0.5), Color::RED, DVec2::new(1., 0.5), Color::BLUE, gradient_type)
+	}
+
+	#[test]
+	fn reflected_gradient_mirrors_stops_about_start() {
+		let (attributes, svg_defs) = render_fill(Fill::Gradient(gradient(GradientType::Reflected)), bounds());
+		let def = referenced_def(&attributes, &svg_defs);
+
+		// The line runs from the start point's mirror image of the end point to the end point
+		assert!(def.starts_with("<linearGradient"));
+		assert!(def.contains(r#" x1="0" y1="64" x2="128" y2="64""#));
+		assert!(def.contains(r##"<stop stop-color="#0000ff" /><stop offset="0.5" stop-color="#ff0000" /><stop offset="0.5" stop-color="#ff0000" /><stop offset="1" stop-color="#0000ff" />"##));
+	}
+
+	#[test]
+	fn elliptical_radial_gradient_has_focal_point() {
+		let gradient = Gradient {
+			minor_axis_ratio: 0.5,
+			focal_offset: DVec2::new(0.25, 0.),
+			..gradient(GradientType::Radial)
+		};
+		let (attributes, svg_defs) = render_fill(Fill::Gradient(gradient), bounds());
+		let def = referenced_def(&attributes, &svg_defs);
+
+		// The unit circle is stretched onto the ellipse, with the focal point halfway along its major axis
+		assert!(def.starts_with("<radialGradient"));
+		assert!(def.contains(r#" cx="0" cy="0" r="1" fx="0.5" fy="0""#));
+		assert!(def.contains(r#" gradientTransform="matrix(0.5,0,0,0.25,0.5,0.5)""#));
+		assert!(def.contains(r##"<stop stop-color="#ff0000" /><stop offset="1" stop-color="#0000ff" />"##));
+	}
+
+	#[test]
+	fn radial_gradient_without_focal_offset_omits_focal_point() {
+		let (attributes, svg_defs) = render_fill(Fill::Gradient(gradient(GradientType::Radial)), bounds());
+		let def = referenced_def(&attributes, &svg_defs);
+
+		assert!(def.contains(r#" cx="0" cy="0" r="1" gradientTransform="matrix(0.5,0,0,0.5,0.5,0.5)""#));
+		assert!(!def.contains(" fx="));
+	}
+
+	#[test]
+	fn conic_gradient_is_a_pattern_of_wedges() {
+		let (attributes, svg_defs) = render_fill(Fill::Gradient(gradient(GradientType::Conic)), bounds());
+		let def = referenced_def(&attributes, &svg_defs);
+
+		// The tile covers the shape in unit space, centered on the start point
+		assert!(def.starts_with("<pattern"));
+		assert!(def.contains(r#" patternUnits="userSpaceOnUse" x="-1" y="-1" width="2" height="2" patternTransform="matrix(64,0,0,64,64,64)"><g transform="translate(1 1)">"#));
+
+		// The wedges sweep from the first color to the last
+		let wedge_colors = def
+			.split("<polygon ")
+			.skip(1)
+			.map(|wedge| {
+				let hex = &wedge[wedge.find(r##"fill="#"##).unwrap() + 7..][..6];
+				let channel = |index: usize| u8::from_str_radix(&hex[index..index + 2], 16).unwrap();
+				(channel(0), channel(4))
+			})
+			.collect::<Vec<_>>();
+		assert_eq!(wedge_colors.len(), CONIC_WEDGES);
+		let (first_red, first_blue) = wedge_colors[0];
+		let (last_red, last_blue) = wedge_colors[CONIC_WEDGES - 1];
+		assert!(first_red > 250 && first_blue < 5, "The first wedge should be red, not {:?}", wedge_colors[0]);
+		assert!(last_red < 5 && last_blue > 250, "The last wedge should be blue, not {:?}", wedge_colors[CONIC_WEDGES - 1]);
+	}
+
+	#[test]
+	fn diamond_gradient_is_a_pattern_of_mirrored_quadrants() {
+		let gradient = Gradient {
+			minor_axis_ratio: 0.5,
+			..gradient(GradientType::Diamond)
+		};
+		let (attributes, svg_defs) = render_fill(Fill::Gradient(gradient), bounds());
+		let def = referenced_def(&attributes, &svg_defs);
+
+		// The minor axis squashes the tile, which then needs to reach twice as far in unit space to cover the shape
+		assert!(def.starts_with("<pattern"));
+		assert!(def.contains(r#" patternUnits="userSpaceOnUse" x="-2" y="-2" width="4" height="4" patternTransform="matrix(64,0,0,32,64,64)"><g transform="translate(2 2)">"#));
+
+		// Each quadrant is filled with the same gradient, reaching the last stop along the diagonal
+		let quadrant_start = svg_defs.find("<linearGradient").expect("The quadrant gradient should be defined");
+		let quadrant_def = &svg_defs[quadrant_start..];
+		assert!(quadrant_def.contains(r#" gradientUnits="userSpaceOnUse" x1="0" y1="0" x2="0.5" y2="0.5">"#));
+		let quadrant_id = quadrant_def.split('"').nth(1).unwrap();
+		assert_eq!(def.matches(r#"<rect width="2" height="2""#).count(), 4);
+		assert_eq!(def.matches(&format!(r##"fill="url('#{quadrant_id}')""##)).count(), 4);
+	}
+
+	#[test]
+	fn degenerate_gradient_falls_back_to_linear() {
+		let gradient = Gradient {
+			end: DVec2::new(0.5, 0.5),
+			..gradient(GradientType::Conic)
+		};
+		let (attributes, svg_defs) = render_fill(Fill::Gradient(gradient), bounds());
+		assert!(referenced_def(&attributes, &svg_defs).starts_with("<linearGradient"));
+		assert!(!svg_defs.contains("<pattern"));
+	}
+
+	fn pattern_content() -> Table<PatternElement> {
+		let mut square = Vector::from_subpath(Subpath::new_rectangle(DVec2::new(10., 20.), DVec2::new(20., 30.)));
+		square.style = PathStyle::new(None, Fill::Solid(Color::RED));
+		Table::new_from_element(square).into_pattern_content()
+	}
+
+	#[test]
+	fn pattern_fill_tiles_its_content() {
+		let pattern = PatternFill {
+			content: pattern_content(),
+			transform: DAffine2::from_scale(DVec2::splat(2.)),
+			spacing: DVec2::splat(5.),
+			offset: DVec2::new(2., 3.),
+		};
+		let (attributes, svg_defs) = render_fill(Fill::Pattern(pattern), bounds());
+		let def = referenced_def(&attributes, &svg_defs);
+
+		// Each tile is the content's bounds plus the spacing, moved by the offset, while the content inside stays put
+		assert!(def.starts_with("<pattern"));
+		assert!(def.contains(r#" patternUnits="userSpaceOnUse" x="12" y="23" width="15" height="15" patternTransform="matrix(2,0,0,2,0,0)"><g transform="translate(-10 -20)">"#));
+		assert!(def.contains("<path"));
+		assert!(def.contains(r##"fill="#ff0000""##));
+	}
+
+	#[test]
+	fn empty_pattern_fill_is_none() {
+		let (attributes, svg_defs) = render_fill(Fill::Pattern(PatternFill::default()), bounds());
+		assert_eq!(attributes, r#" fill="none""#);
+		assert!(svg_defs.is_empty());
+	}
+
+	/// Renders an image twice as wide as it is tall into a square shape from (20, 10) to (120, 110), returning the image's transform.
+	fn image_fill_transform(fit: ImageFit) -> String {
+		let image = Raster::new_cpu(Image::new(2, 1, Color::RED));
+		let bounds = DAffine2::from_scale_angle_translation(DVec2::splat(100.), 0., DVec2::new(20., 10.));
+		let (attributes, svg_defs) = render_fill(Fill::Image(ImageFill { image, fit }), bounds);
+		let def = referenced_def(&attributes, &svg_defs);
+
+		// A single tile covers the shape's bounds
+		assert!(def.starts_with("<pattern"));
+		assert!(def.contains(r#" patternUnits="userSpaceOnUse" x="20" y="10" width="100" height="100"><g transform="translate(-20 -10)">"#));
+		assert!(def.contains(r#"href="data:image/png;base64,"#));
+
+		let transform_start = def.find(r#"transform="matrix("#).expect("The image should be transformed") + r#"transform=""#.len();
+		let transform_end = transform_start + def[transform_start..].find('"').unwrap();
+		def[transform_start..transform_end].to_string()
+	}
+
+	#[test]
+	fn image_fill_fits_image() {
+		// Centered and cropped at the sides to cover the square
+		assert_eq!(image_fill_transform(ImageFit::Cover), "matrix(200,0,0,100,-30,10)");
+		// Centered with space above and below to fit in the square
+		assert_eq!(image_fill_transform(ImageFit::Contain), "matrix(100,0,0,50,20,35)");
+		// Squashed to the square
+		assert_eq!(image_fill_transform(ImageFit::Stretch), "matrix(100,0,0,100,20,10)");
+	}
+
+	#[test]
+	fn image_fill_reuses_encoded_image() {
+		let mut image = Raster::new_cpu(Image::new(2, 1, Color::RED));
+		image.base64_string = Some("data:image/png;base64,cached".to_string());
+		let (attributes, svg_defs) = render_fill(Fill::Image(ImageFill { image, fit: ImageFit::Cover }), bounds());
+		let def = referenced_def(&attributes, &svg_defs);
+
+		assert!(def.contains(r#"href="data:image/png;base64,cached""#));
+	}
+
+	#[test]
+	fn empty_image_fill_is_none() {
+		let (attributes, svg_defs) = render_fill(Fill::Image(ImageFill::default()), bounds());
+		assert_eq!(attributes, r#" fill="none""#);
+		assert!(svg_defs.is_empty());
+	}
+}
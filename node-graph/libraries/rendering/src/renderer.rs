@@ -1,6 +1,7 @@
-use crate::render_ext::RenderExt;
+use crate::pdf::PdfRender;
+use crate::render_ext::{RenderExt, VectorPaint};
 use crate::to_peniko::BlendModeExt;
-use core_types::blending::BlendMode;
+use core_types::blending::{AlphaBlending, BlendMode};
 use core_types::bounds::BoundingBox;
 use core_types::bounds::RenderBoundingBox;
 use core_types::color::Color;
@@ -18,16 +19,20 @@ use graphic_types::raster_types::{CPU, GPU, Raster};
 use graphic_types::vector_types::gradient::GradientStops;
 use graphic_types::vector_types::gradient::GradientType;
 use graphic_types::vector_types::subpath::Subpath;
+use graphic_types::vector_types::vector::algorithms::width_profile::stroke_width_profile_outline;
 use graphic_types::vector_types::vector::click_target::{ClickTarget, FreePoint};
-use graphic_types::vector_types::vector::style::{Fill, PaintOrder, RenderMode, Stroke, StrokeAlign};
-use graphic_types::{Artboard, Graphic};
+use graphic_types::vector_types::vector::style::{AppearanceEntry, Fill, PaintOrder, PathStyle, RenderMode, Stroke, StrokeAlign};
+use graphic_types::{Artboard, Graphic, IntoGraphicTable};
 use kurbo::Affine;
 use kurbo::Shape;
 use num_traits::Zero;
+use std::cell::RefCell;
+use std::collections::hash_map::DefaultHasher;
 use std::collections::{HashMap, HashSet};
 use std::fmt::Write;
 use std::hash::{Hash, Hasher};
 use std::ops::Deref;
+use std::rc::Rc;
 use std::sync::{Arc, LazyLock};
 use vello::*;
 
@@ -150,6 +155,9 @@ impl Default for SvgRender {
 	}
 }
 
+/// The most tiles of a pattern fill drawn by Vello for a single shape, beyond which the pattern is left undrawn rather than stalling the renderer.
+const MAX_PATTERN_TILES: f64 = 100_000.;
+
 #[derive(Clone, Debug, Default)]
 pub struct RenderContext {
 	pub resource_overrides: Vec<(peniko::ImageBrush, wgpu::Texture)>,
@@ -160,6 +168,7 @@ pub enum RenderOutputType {
 	#[default]
 	Svg,
 	Vello,
+	Pdf,
 }
 
 /// Static state used whilst rendering
@@ -231,6 +240,690 @@ pub fn format_transform_matrix(transform: DAffine2) -> String {
 	}) + ")"
 }
 
+/// The number of traced width profile outlines kept by each thread, beyond which the least recently used one is discarded.
+const MAX_WIDTH_PROFILE_OUTLINES: usize = 64;
+
+thread_local! {
+	/// The most recently traced outlines of strokes with a width profile, keyed by the hash of their path and stroke, so a stroke isn't traced again each time it's rendered.
+	static WIDTH_PROFILE_OUTLINES: RefCell<Vec<(u64, Rc<Vector>)>> = RefCell::default();
+}
+
+/// Gets the outline of a stroke with a width profile, filled with the stroke's color, from the outlines kept by this thread or by tracing it.
+///
+/// The stroker behind the Solidify Stroke node and the offsetting behind the Offset Path node both keep one width along the whole path, so a width which varies along it is traced by [`stroke_width_profile_outline`] instead.
+fn width_profile_outline(vector: &Vector, stroke: &Stroke) -> Rc<Vector> {
+	let mut hasher = DefaultHasher::new();
+	vector.point_domain.hash(&mut hasher);
+	vector.segment_domain.hash(&mut hasher);
+	stroke.hash(&mut hasher);
+	let key = hasher.finish();
+
+	WIDTH_PROFILE_OUTLINES.with_borrow_mut(|outlines| {
+		let entry = match outlines.iter().position(|(hash, _)| *hash == key) {
+			Some(index) => outlines.remove(index),
+			None => {
+				let mut outline = Vector::default();
+				outline.append_bezpath(stroke_width_profile_outline(vector.stroke_bezpath_iter(), stroke));
+				outline.style = PathStyle::new(None, Fill::solid_or_none(stroke.color));
+				(key, Rc::new(outline))
+			}
+		};
+		let outline = entry.1.clone();
+
+		outlines.insert(0, entry);
+		outlines.truncate(MAX_WIDTH_PROFILE_OUTLINES);
+
+		outline
+	})
+}
+
+/// Renders a vector's path to SVG with the given paint. A stroke with a width profile is drawn as its outline filled with the stroke's color, grouped with the path's fill in the stroke's paint order.
+fn render_vector_svg(vector: &Vector, paint: VectorPaint, transform: DAffine2, alpha_blending: AlphaBlending, render: &mut SvgRender, render_params: &RenderParams) {
+	let Some(stroke) = paint
+		.stroke
+		.filter(|stroke| render_params.render_mode != RenderMode::Outline && stroke.has_width_profile() && stroke.has_renderable_stroke())
+	else {
+		render_vector_path_svg(vector, paint, transform, alpha_blending, render, render_params);
+		return;
+	};
+
+	let outline = width_profile_outline(vector, stroke);
+	let mut layers = [(vector, paint.without_stroke()), (&*outline, VectorPaint::from(&outline.style))];
+	if stroke.paint_order == PaintOrder::StrokeBelow {
+		layers.reverse();
+	}
+
+	render.parent_tag(
+		"g",
+		|attributes| push_alpha_blending_attributes(attributes, alpha_blending, render_params),
+		|render| {
+			for (vector, paint) in layers {
+				render_vector_path_svg(vector, paint, transform, AlphaBlending::default(), render, render_params);
+			}
+		},
+	);
+}
+
+/// Adds the attributes which composite an SVG element with the given opacity and blend mode.
+fn push_alpha_blending_attributes(attributes: &mut SvgRenderAttrs, alpha_blending: AlphaBlending, render_params: &RenderParams) {
+	let opacity = alpha_blending.opacity(render_params.for_mask);
+	if opacity < 1. {
+		attributes.push("opacity", opacity.to_string());
+	}
+	if alpha_blending.blend_mode != BlendMode::default() {
+		attributes.push("style", alpha_blending.blend_mode.render());
+	}
+}
+
+/// The opacity and blend mode which a paint of an appearance stack is composited over those below it with.
+fn appearance_alpha_blending(entry: &AppearanceEntry) -> AlphaBlending {
+	AlphaBlending {
+		opacity: entry.opacity as f32,
+		blend_mode: entry.blend_mode,
+		..Default::default()
+	}
+}
+
+/// Renders a vector's path to SVG as a single path with the given paint, drawing its stroke at a uniform width.
+fn render_vector_path_svg(vector: &Vector, paint: VectorPaint, transform: DAffine2, alpha_blending: AlphaBlending, render: &mut SvgRender, render_params: &RenderParams) {
+	// Only consider strokes with non-zero weight, since default strokes with zero weight would prevent assigning the correct stroke transform
+	let has_real_stroke = paint.stroke.filter(|stroke| stroke.weight() > 0.);
+	let set_stroke_transform = has_real_stroke.map(|stroke| stroke.transform).filter(|transform| transform.matrix2.determinant() != 0.);
+	let applied_stroke_transform = set_stroke_transform.unwrap_or(transform);
+	let applied_stroke_transform = render_params.alignment_parent_transform.unwrap_or(applied_stroke_transform);
+	let element_transform = set_stroke_transform.map(|stroke_transform| transform * stroke_transform.inverse());
+	let element_transform = element_transform.unwrap_or(DAffine2::IDENTITY);
+	let layer_bounds = vector.bounding_box().unwrap_or_default();
+	let transformed_bounds = vector.bounding_box_with_transform(applied_stroke_transform).unwrap_or_default();
+
+	let bounds_matrix = DAffine2::from_scale_angle_translation(layer_bounds[1] - layer_bounds[0], 0., layer_bounds[0]);
+	let transformed_bounds_matrix = element_transform * DAffine2::from_scale_angle_translation(transformed_bounds[1] - transformed_bounds[0], 0., transformed_bounds[0]);
+
+	let mut path = String::new();
+
+	for mut bezpath in vector.stroke_bezpath_iter() {
+		bezpath.apply_affine(Affine::new(applied_stroke_transform.to_cols_array()));
+		path.push_str(bezpath.to_svg().as_str());
+	}
+
+	let mask_type = if paint.stroke.map(|x| x.align) == Some(StrokeAlign::Inside) {
+		MaskType::Clip
+	} else {
+		MaskType::Mask
+	};
+
+	let path_is_closed = vector.stroke_bezier_paths().all(|path| path.closed());
+	let can_draw_aligned_stroke = path_is_closed && paint.stroke.is_some_and(|stroke| stroke.has_renderable_stroke() && stroke.align.is_not_centered());
+	let can_use_paint_order = !(paint.fill.is_none() || !paint.fill.is_opaque() || mask_type == MaskType::Clip);
+
+	let needs_separate_alignment_fill = can_draw_aligned_stroke && !can_use_paint_order;
+	let wants_stroke_below = paint.stroke.map(|s| s.paint_order) == Some(PaintOrder::StrokeBelow);
+
+	if needs_separate_alignment_fill && !wants_stroke_below {
+		render.leaf_tag("path", |attributes| {
+			attributes.push("d", path.clone());
+			let matrix = format_transform_matrix(element_transform);
+			if !matrix.is_empty() {
+				attributes.push("transform", matrix);
+			}
+			let fill_and_stroke = paint.without_stroke().render(
+				&mut attributes.0.svg_defs,
+				element_transform,
+				applied_stroke_transform,
+				bounds_matrix,
+				transformed_bounds_matrix,
+				render_params,
+			);
+			attributes.push_val(fill_and_stroke);
+		});
+	}
+
+	let push_id = needs_separate_alignment_fill.then_some({
+		let id = format!("alignment-{}", generate_uuid());
+
+		// The mask only needs the shape of the path, so none of its paint is kept
+		let mut element = vector.clone();
+		element.style = PathStyle::new(None, Fill::solid(Color::BLACK));
+
+		let vector_row = Table::new_from_row(TableRow {
+			element,
+			alpha_blending,
+			transform,
+			source_node_id: None,
+		});
+
+		(id, mask_type, vector_row)
+	});
+
+	if vector.is_branching() {
+		for mut face_path in vector.construct_faces().filter(|face| !(face.area() < 0.0)) {
+			face_path.apply_affine(Affine::new(applied_stroke_transform.to_cols_array()));
+
+			let face_d = face_path.to_svg();
+			render.leaf_tag("path", |attributes| {
+				attributes.push("d", face_d.clone());
+				let matrix = format_transform_matrix(element_transform);
+				if !matrix.is_empty() {
+					attributes.push("transform", matrix);
+				}
+				let fill_only = paint.without_stroke().render(
+					&mut attributes.0.svg_defs,
+					element_transform,
+					applied_stroke_transform,
+					bounds_matrix,
+					transformed_bounds_matrix,
+					render_params,
+				);
+				attributes.push_val(fill_only);
+			});
+		}
+	}
+
+	render.leaf_tag("path", |attributes| {
+		attributes.push("d", path.clone());
+		let matrix = format_transform_matrix(element_transform);
+		if !matrix.is_empty() {
+			attributes.push("transform", matrix);
+		}
+
+		let defs = &mut attributes.0.svg_defs;
+		if let Some((ref id, mask_type, ref vector_row)) = push_id {
+			let mut svg = SvgRender::new();
+			vector_row.render_svg(&mut svg, &render_params.for_alignment(applied_stroke_transform));
+			let stroke = paint.stroke.unwrap();
+			let weight = stroke.effective_width() * max_scale(applied_stroke_transform);
+			let quad = Quad::from_box(transformed_bounds).inflate(weight);
+			let (x, y) = quad.top_left().into();
+			let (width, height) = (quad.bottom_right() - quad.top_left()).into();
+
+			write!(defs, r##"{}"##, svg.svg_defs).unwrap();
+			let rect = format!(r##"<rect x="{x}" y="{y}" width="{width}" height="{height}" fill="white" />"##);
+
+			match mask_type {
+				MaskType::Clip => write!(defs, r##"<clipPath id="{id}">{}</clipPath>"##, svg.svg.to_svg_string()).unwrap(),
+				MaskType::Mask => write!(
+					defs,
+					r##"<mask id="{id}" maskUnits="userSpaceOnUse" maskContentUnits="userSpaceOnUse" x="{x}" y="{y}" width="{width}" height="{height}">{}{}</mask>"##,
+					rect,
+					svg.svg.to_svg_string()
+				)
+				.unwrap(),
+			}
+		}
+
+		let mut render_params = render_params.clone();
+		render_params.aligned_strokes = can_draw_aligned_stroke;
+		render_params.override_paint_order = can_draw_aligned_stroke && can_use_paint_order;
+
+		let paint = if needs_separate_alignment_fill || vector.is_branching() { paint.without_fill() } else { paint };
+		let fill_and_stroke = paint.render(defs, element_transform, applied_stroke_transform, bounds_matrix, transformed_bounds_matrix, &render_params);
+
+		if let Some((id, mask_type, _)) = push_id {
+			let selector = format!("url(#{id})");
+			attributes.push(mask_type.to_attribute(), selector);
+		}
+		attributes.push_val(fill_and_stroke);
+
+		let opacity = alpha_blending.opacity(render_params.for_mask);
+		if opacity < 1. {
+			attributes.push("opacity", opacity.to_string());
+		}
+
+		if alpha_blending.blend_mode != BlendMode::default() {
+			attributes.push("style", alpha_blending.blend_mode.render());
+		}
+	});
+
+	// When splitting passes and stroke is below, draw the fill after the stroke.
+	if needs_separate_alignment_fill && wants_stroke_below {
+		render.leaf_tag("path", |attributes| {
+			attributes.push("d", path);
+			let matrix = format_transform_matrix(element_transform);
+			if !matrix.is_empty() {
+				attributes.push("transform", matrix);
+			}
+			let fill_and_stroke = paint.without_stroke().render(
+				&mut attributes.0.svg_defs,
+				element_transform,
+				applied_stroke_transform,
+				bounds_matrix,
+				transformed_bounds_matrix,
+				render_params,
+			);
+			attributes.push_val(fill_and_stroke);
+		});
+	}
+}
+
+/// Renders a vector's path to Vello with the given paint. A stroke with a width profile is drawn as its outline filled with the stroke's color, composited with the path's fill in the stroke's paint order.
+#[allow(clippy::too_many_arguments)]
+fn render_vector_vello(
+	vector: &Vector,
+	paint: VectorPaint,
+	transform: DAffine2,
+	alpha_blending: AlphaBlending,
+	scene: &mut Scene,
+	parent_transform: DAffine2,
+	context: &mut RenderContext,
+	render_params: &RenderParams,
+) {
+	let Some(stroke) = paint
+		.stroke
+		.filter(|stroke| render_params.render_mode != RenderMode::Outline && stroke.has_width_profile() && stroke.has_renderable_stroke())
+	else {
+		render_vector_path_vello(vector, paint, transform, alpha_blending, scene, parent_transform, context, render_params);
+		return;
+	};
+
+	let outline = width_profile_outline(vector, stroke);
+	let mut layers = [(vector, paint.without_stroke()), (&*outline, VectorPaint::from(&outline.style))];
+	if stroke.paint_order == PaintOrder::StrokeBelow {
+		layers.reverse();
+	}
+
+	let bounds = BoundingBox::bounding_box(vector, parent_transform * transform, true);
+	let layer = push_alpha_blending_layer(scene, alpha_blending, bounds, render_params);
+	for (vector, paint) in layers {
+		render_vector_path_vello(vector, paint, transform, AlphaBlending::default(), scene, parent_transform, context, render_params);
+	}
+	if layer {
+		scene.pop_layer();
+	}
+}
+
+/// Pushes a layer which composites everything drawn until it's popped with the given opacity and blend mode, if they need one, returning whether it was pushed.
+fn push_alpha_blending_layer(scene: &mut Scene, alpha_blending: AlphaBlending, bounds: RenderBoundingBox, render_params: &RenderParams) -> bool {
+	let opacity = alpha_blending.opacity(render_params.for_mask);
+	let blends = render_params.render_mode != RenderMode::Outline && alpha_blending.blend_mode != BlendMode::default();
+	let RenderBoundingBox::Rectangle(bounds) = bounds else { return false };
+	if opacity == 1. && !blends {
+		return false;
+	}
+
+	let blend_mode = if blends { alpha_blending.blend_mode.to_peniko() } else { peniko::Mix::Normal };
+	scene.push_layer(
+		peniko::BlendMode::new(blend_mode, peniko::Compose::SrcOver),
+		opacity,
+		kurbo::Affine::IDENTITY,
+		&kurbo::Rect::new(bounds[0].x, bounds[0].y, bounds[1].x, bounds[1].y),
+	);
+	true
+}
+
+/// Renders a vector's path to Vello with the given paint, drawing its stroke at a uniform width.
+#[allow(clippy::too_many_arguments)]
+fn render_vector_path_vello(
+	vector: &Vector,
+	paint: VectorPaint,
+	transform: DAffine2,
+	alpha_blending: AlphaBlending,
+	scene: &mut Scene,
+	parent_transform: DAffine2,
+	context: &mut RenderContext,
+	render_params: &RenderParams,
+) {
+	use core_types::consts::LAYER_OUTLINE_STROKE_WEIGHT;
+	use graphic_types::vector_types::vector::style::{GradientType, StrokeCap, StrokeJoin};
+	use vello::kurbo::{Cap, Join};
+
+	let multiplied_transform = parent_transform * transform;
+	let has_real_stroke = paint.stroke.filter(|stroke| stroke.weight() > 0.);
+	let set_stroke_transform = has_real_stroke.map(|stroke| stroke.transform).filter(|transform| transform.matrix2.determinant() != 0.);
+	let mut applied_stroke_transform = set_stroke_transform.unwrap_or(multiplied_transform);
+	let mut element_transform = set_stroke_transform
+		.map(|stroke_transform| multiplied_transform * stroke_transform.inverse())
+		.unwrap_or(DAffine2::IDENTITY);
+	if let Some(alignment_transform) = render_params.alignment_parent_transform {
+		applied_stroke_transform = alignment_transform;
+		element_transform = if alignment_transform.matrix2.determinant() != 0. {
+			multiplied_transform * alignment_transform.inverse()
+		} else {
+			multiplied_transform
+		};
+	}
+	let layer_bounds = vector.bounding_box().unwrap_or_default();
+
+	let to_point = |p: DVec2| kurbo::Point::new(p.x, p.y);
+	let mut path = kurbo::BezPath::new();
+	for mut bezpath in vector.stroke_bezpath_iter() {
+		bezpath.apply_affine(Affine::new(applied_stroke_transform.to_cols_array()));
+		for element in bezpath {
+			path.push(element);
+		}
+	}
+
+	// If we're using opacity or a blend mode, we need to push a layer
+	let blend_mode = match render_params.render_mode {
+		RenderMode::Outline => peniko::Mix::Normal,
+		_ => alpha_blending.blend_mode.to_peniko(),
+	};
+	let mut layer = false;
+
+	let opacity = alpha_blending.opacity(render_params.for_mask);
+	if opacity < 1. || alpha_blending.blend_mode != BlendMode::default() {
+		layer = true;
+		let weight = paint.stroke.map_or(0., Stroke::effective_width);
+		let quad = Quad::from_box(layer_bounds).inflate(weight * max_scale(applied_stroke_transform));
+		let layer_bounds = quad.bounding_box();
+		scene.push_layer(
+			peniko::BlendMode::new(blend_mode, peniko::Compose::SrcOver),
+			opacity,
+			kurbo::Affine::new(multiplied_transform.to_cols_array()),
+			&kurbo::Rect::new(layer_bounds[0].x, layer_bounds[0].y, layer_bounds[1].x, layer_bounds[1].y),
+		);
+	}
+
+	let can_draw_aligned_stroke = paint.stroke.is_some_and(|stroke| stroke.has_renderable_stroke() && stroke.align.is_not_centered()) && vector.stroke_bezier_paths().all(|path| path.closed());
+
+	let use_layer = can_draw_aligned_stroke;
+	let wants_stroke_below = paint.stroke.is_some_and(|s| s.paint_order == PaintOrder::StrokeBelow);
+
+	// Closures to avoid duplicated fill/stroke drawing logic
+	let do_fill_path = |scene: &mut Scene, path: &kurbo::BezPath| match paint.fill {
+		Fill::Solid(color) => {
+			let fill = peniko::Brush::Solid(peniko::Color::new([color.r(), color.g(), color.b(), color.a()]));
+			scene.fill(peniko::Fill::NonZero, kurbo::Affine::new(element_transform.to_cols_array()), &fill, None, path);
+		}
+		Fill::Gradient(gradient) => {
+			// Vello's stops are blended evenly in sRGB like those of SVG, so other interpolation spaces and midpoints are approximated the same way
+			let gradient_stops = match gradient.gradient_type {
+				GradientType::Reflected => gradient.stops.mirrored(),
+				_ => gradient.stops.clone(),
+			}
+			.to_plain_srgb();
+			let mut stops = peniko::ColorStops::new();
+			for &(offset, color) in &gradient_stops {
+				stops.push(peniko::ColorStop {
+					offset: offset as f32,
+					color: peniko::color::DynamicColor::from_alpha_color(peniko::Color::new([color.r(), color.g(), color.b(), color.a()])),
+				});
+			}
+
+			let bounds = vector.nonzero_bounding_box();
+			let bound_transform = DAffine2::from_scale_angle_translation(bounds[1] - bounds[0], 0., bounds[0]);
+
+			// The gradient is drawn in its unit space, where its start point is the origin and its end point is (1, 0)
+			let unit_transform = gradient.unit_transform(multiplied_transform * bound_transform);
+			let element_affine = kurbo::Affine::new(element_transform.to_cols_array());
+			if unit_transform.matrix2.determinant() == 0. || element_transform.matrix2.determinant() == 0. {
+				// Without distinct start and end points, the last color covers the whole shape like it does in SVG
+				let color = gradient_stops.last().map_or(Color::BLACK, |(_, color)| *color);
+				let fill = peniko::Brush::Solid(peniko::Color::new([color.r(), color.g(), color.b(), color.a()]));
+				scene.fill(peniko::Fill::NonZero, element_affine, &fill, None, path);
+				return;
+			}
+			let gradient_brush = |kind: peniko::GradientKind| {
+				peniko::Brush::Gradient(peniko::Gradient {
+					kind,
+					stops: stops.clone(),
+					interpolation_alpha_space: peniko::InterpolationAlphaSpace::Premultiplied,
+					..Default::default()
+				})
+			};
+
+			let kind = match gradient.gradient_type {
+				GradientType::Linear => peniko::LinearGradientPosition {
+					start: kurbo::Point::ZERO,
+					end: kurbo::Point::new(1., 0.),
+				}
+				.into(),
+				GradientType::Reflected => peniko::LinearGradientPosition {
+					start: kurbo::Point::new(-1., 0.),
+					end: kurbo::Point::new(1., 0.),
+				}
+				.into(),
+				GradientType::Radial => {
+					let focal_point = (multiplied_transform * bound_transform).transform_point2(gradient.focal_point());
+					peniko::RadialGradientPosition {
+						start_center: to_point(unit_transform.inverse().transform_point2(focal_point)),
+						start_radius: 0.,
+						end_center: kurbo::Point::ZERO,
+						end_radius: 1.,
+					}
+					.into()
+				}
+				GradientType::Conic => peniko::SweepGradientPosition {
+					center: kurbo::Point::ZERO,
+					start_angle: 0.,
+					end_angle: std::f32::consts::TAU,
+				}
+				.into(),
+				GradientType::Diamond => {
+					// Vello has no diamond gradients, so each quadrant of the diamond is clipped to the shape and filled with a linear gradient reaching its edge halfway along the diagonal
+					let shape_to_unit_space = unit_transform.inverse() * element_transform;
+					let path_bounds = kurbo::Shape::bounding_box(path);
+					let extent = [
+						(path_bounds.x0, path_bounds.y0),
+						(path_bounds.x1, path_bounds.y0),
+						(path_bounds.x0, path_bounds.y1),
+						(path_bounds.x1, path_bounds.y1),
+					]
+					.into_iter()
+					.map(|(x, y)| shape_to_unit_space.transform_point2(DVec2::new(x, y)).abs().max_element())
+					.fold(1., f64::max)
+					.ceil();
+
+					scene.push_clip_layer(element_affine, path);
+					for (x, y) in [(1., 1.), (-1., 1.), (1., -1.), (-1., -1.)] {
+						let fill = gradient_brush(
+							peniko::LinearGradientPosition {
+								start: kurbo::Point::ZERO,
+								end: kurbo::Point::new(x * 0.5, y * 0.5),
+							}
+							.into(),
+						);
+						let quadrant = kurbo::Rect::new(0., 0., x * extent, y * extent);
+						scene.fill(peniko::Fill::NonZero, kurbo::Affine::new(unit_transform.to_cols_array()), &fill, None, &quadrant);
+					}
+					scene.pop_layer();
+					return;
+				}
+			};
+
+			let inverse_element_transform = element_transform.inverse();
+			let brush_transform = kurbo::Affine::new((inverse_element_transform * unit_transform).to_cols_array());
+			scene.fill(peniko::Fill::NonZero, element_affine, &gradient_brush(kind), Some(brush_transform), path);
+		}
+		Fill::Pattern(pattern) => {
+			let pattern_to_world = multiplied_transform * pattern.transform;
+			let Some([tile_min, tile_max]) = pattern.tile() else { return };
+			if pattern_to_world.matrix2.determinant() == 0. {
+				return;
+			}
+
+			// Find the range of tiles covering the shape's bounds in the pattern's space
+			let shape_to_pattern_space = pattern_to_world.inverse() * element_transform;
+			let path_bounds = kurbo::Shape::bounding_box(path);
+			let corners = [
+				(path_bounds.x0, path_bounds.y0),
+				(path_bounds.x1, path_bounds.y0),
+				(path_bounds.x0, path_bounds.y1),
+				(path_bounds.x1, path_bounds.y1),
+			]
+			.map(|(x, y)| shape_to_pattern_space.transform_point2(DVec2::new(x, y)));
+			let pattern_min = corners.into_iter().reduce(DVec2::min).unwrap_or_default();
+			let pattern_max = corners.into_iter().reduce(DVec2::max).unwrap_or_default();
+			let tile_size = tile_max - tile_min;
+			// Content extending past its tile is caught by going one tile further in each direction
+			let first_tile = ((pattern_min - tile_min) / tile_size).floor() - DVec2::ONE;
+			let last_tile = ((pattern_max - tile_min) / tile_size).floor() + DVec2::ONE;
+			let tile_count = (last_tile - first_tile + DVec2::ONE).element_product();
+			if !tile_count.is_finite() || tile_count > MAX_PATTERN_TILES {
+				return;
+			}
+
+			let content = pattern.content.clone().into_graphic_table();
+			scene.push_clip_layer(kurbo::Affine::new(element_transform.to_cols_array()), path);
+			for y in first_tile.y as i64..=last_tile.y as i64 {
+				for x in first_tile.x as i64..=last_tile.x as i64 {
+					let tile_translation = pattern.offset + DVec2::new(x as f64, y as f64) * tile_size;
+					let tile_transform = pattern_to_world * DAffine2::from_translation(tile_translation);
+					content.render_to_vello(scene, tile_transform, &mut RenderContext::default(), render_params);
+				}
+			}
+			scene.pop_layer();
+		}
+		Fill::Image(image) => {
+			if image.image.data.is_empty() {
+				return;
+			}
+
+			let image_row = TableRow {
+				transform: image.image_transform(layer_bounds),
+				..TableRow::new_from_element(image.image.clone())
+			};
+			scene.push_clip_layer(kurbo::Affine::new(element_transform.to_cols_array()), path);
+			Table::new_from_row(image_row).render_to_vello(scene, multiplied_transform, &mut RenderContext::default(), render_params);
+			scene.pop_layer();
+		}
+		Fill::None => {}
+	};
+
+	let do_fill = |scene: &mut Scene| {
+		if vector.is_branching() {
+			// For branching paths, fill each face separately
+			for mut face_path in vector.construct_faces().filter(|face| !(face.area() < 0.0)) {
+				face_path.apply_affine(Affine::new(applied_stroke_transform.to_cols_array()));
+				let mut kurbo_path = kurbo::BezPath::new();
+				for element in face_path {
+					kurbo_path.push(element);
+				}
+				do_fill_path(scene, &kurbo_path);
+			}
+		} else {
+			// Simple fill of the entire path
+			do_fill_path(scene, &path);
+		}
+	};
+
+	let do_stroke = |scene: &mut Scene, width_scale: f64| {
+		if let Some(stroke) = paint.stroke {
+			let color = match stroke.color {
+				Some(color) => peniko::Color::new([color.r(), color.g(), color.b(), color.a()]),
+				None => peniko::Color::TRANSPARENT,
+			};
+			let cap = match stroke.cap {
+				StrokeCap::Butt => Cap::Butt,
+				StrokeCap::Round => Cap::Round,
+				StrokeCap::Square => Cap::Square,
+			};
+			let join = match stroke.join {
+				StrokeJoin::Miter => Join::Miter,
+				StrokeJoin::Bevel => Join::Bevel,
+				StrokeJoin::Round => Join::Round,
+			};
+			let dash_pattern = stroke.dash_lengths.iter().map(|l| l.max(0.)).collect();
+			let stroke = kurbo::Stroke {
+				width: stroke.weight * width_scale,
+				miter_limit: stroke.join_miter_limit,
+				join,
+				start_cap: cap,
+				end_cap: cap,
+				dash_pattern,
+				dash_offset: stroke.dash_offset,
+			};
+
+			if stroke.width > 0. {
+				scene.stroke(&stroke, kurbo::Affine::new(element_transform.to_cols_array()), color, None, &path);
+			}
+		}
+	};
+
+	// Render the path
+	match render_params.render_mode {
+		RenderMode::Outline => {
+			let outline_stroke = kurbo::Stroke {
+				width: LAYER_OUTLINE_STROKE_WEIGHT,
+				miter_limit: 4.,
+				join: Join::Miter,
+				start_cap: Cap::Butt,
+				end_cap: Cap::Butt,
+				dash_pattern: Default::default(),
+				dash_offset: 0.,
+			};
+
+			let outline_color = black_or_white_for_best_contrast(render_params.artboard_background);
+			let outline_color = peniko::Color::new([outline_color.r(), outline_color.g(), outline_color.b(), outline_color.a()]);
+
+			scene.stroke(&outline_stroke, kurbo::Affine::new(element_transform.to_cols_array()), outline_color, None, &path);
+		}
+		_ => {
+			if use_layer {
+				// The mask only needs the shape of the path, so none of its paint is kept
+				let mut element = vector.clone();
+				element.style = PathStyle::new(None, Fill::solid(Color::BLACK));
+
+				let vector_table = Table::new_from_row(TableRow {
+					element,
+					alpha_blending,
+					transform,
+					source_node_id: None,
+				});
+
+				let bounds = vector.bounding_box_with_transform(multiplied_transform).unwrap_or(layer_bounds);
+				let weight = paint.stroke.map_or(0., Stroke::effective_width);
+				let quad = Quad::from_box(bounds).inflate(weight * max_scale(applied_stroke_transform));
+				let bounds = quad.bounding_box();
+				let rect = kurbo::Rect::new(bounds[0].x, bounds[0].y, bounds[1].x, bounds[1].y);
+
+				let compose = if paint.stroke.is_some_and(|x| x.align == StrokeAlign::Outside) {
+					peniko::Compose::SrcOut
+				} else {
+					peniko::Compose::SrcIn
+				};
+
+				if wants_stroke_below {
+					scene.push_layer(peniko::Mix::Normal, 1., kurbo::Affine::IDENTITY, &rect);
+					vector_table.render_to_vello(scene, parent_transform, context, &render_params.for_alignment(applied_stroke_transform));
+					scene.push_layer(peniko::BlendMode::new(peniko::Mix::Normal, compose), 1., kurbo::Affine::IDENTITY, &rect);
+
+					do_stroke(scene, 2.);
+
+					scene.pop_layer();
+					scene.pop_layer();
+
+					do_fill(scene);
+				} else {
+					// Fill first (unclipped), then stroke (clipped) above
+					do_fill(scene);
+
+					scene.push_layer(peniko::Mix::Normal, 1., kurbo::Affine::IDENTITY, &rect);
+					vector_table.render_to_vello(scene, parent_transform, context, &render_params.for_alignment(applied_stroke_transform));
+					scene.push_layer(peniko::BlendMode::new(peniko::Mix::Normal, compose), 1., kurbo::Affine::IDENTITY, &rect);
+
+					do_stroke(scene, 2.);
+
+					scene.pop_layer();
+					scene.pop_layer();
+				}
+			} else {
+				// Non-aligned strokes or open paths: default order behavior
+				enum Op {
+					Fill,
+					Stroke,
+				}
+
+				let order = match paint.stroke.is_some_and(|stroke| !stroke.paint_order.is_default()) {
+					true => [Op::Stroke, Op::Fill],
+					false => [Op::Fill, Op::Stroke], // Default
+				};
+
+				for operation in &order {
+					match operation {
+						Op::Fill => do_fill(scene),
+						Op::Stroke => do_stroke(scene, 1.),
+					}
+				}
+			}
+		}
+	}
+
+	// If we pushed a layer for opacity or a blend mode, we need to pop it
+	if layer {
+		scene.pop_layer();
+	}
+}
+
 fn max_scale(transform: DAffine2) -> f64 {
 	let sx = transform.x_axis.length_squared();
 	let sy = transform.y_axis.length_squared();
@@ -288,6 +981,13 @@ pub trait Render: BoundingBox + RenderComplexity {
 
 	fn render_to_vello(&self, scene: &mut Scene, transform: DAffine2, context: &mut RenderContext, _render_params: &RenderParams);
 
+	/// Renders onto the pages of a PDF document. By default, everything is drawn onto a single page showing the exported region.
+	fn render_pdf(&self, render: &mut PdfRender, render_params: &RenderParams) {
+		let mut svg_render = SvgRender::new();
+		self.render_svg(&mut svg_render, render_params);
+		render.add_page(svg_render, None);
+	}
+
 	/// The upstream click targets for each layer are collected during the render so that they do not have to be calculated for each click detection.
 	fn add_upstream_click_targets(&self, _click_targets: &mut Vec<ClickTarget>) {}
 
@@ -532,6 +1232,19 @@ impl Render for Table<Artboard> {
 		}
 	}
 
+	fn render_pdf(&self, render: &mut PdfRender, render_params: &RenderParams) {
+		// Each artboard gets its own page covering the artboard's bounds
+		for row in self.iter() {
+			let artboard = row.element;
+
+			let mut svg_render = SvgRender::new();
+			artboard.render_svg(&mut svg_render, render_params);
+
+			let location = artboard.location.as_dvec2();
+			render.add_page(svg_render, Some([location, location + artboard.dimensions.as_dvec2()]));
+		}
+	}
+
 	fn collect_metadata(&self, metadata: &mut RenderMetadata, footprint: Footprint, _element_id: Option<NodeId>) {
 		for row in self.iter() {
 			row.element.collect_metadata(metadata, footprint, *row.source_node_id);
@@ -725,451 +1438,53 @@ impl Render for Table<Graphic> {
 impl Render for Table<Vector> {
 	fn render_svg(&self, render: &mut SvgRender, render_params: &RenderParams) {
 		for row in self.iter() {
-			let multiplied_transform = *row.transform;
-			let vector = &row.element;
-			// Only consider strokes with non-zero weight, since default strokes with zero weight would prevent assigning the correct stroke transform
-			let has_real_stroke = vector.style.stroke().filter(|stroke| stroke.weight() > 0.);
-			let set_stroke_transform = has_real_stroke.map(|stroke| stroke.transform).filter(|transform| transform.matrix2.determinant() != 0.);
-			let applied_stroke_transform = set_stroke_transform.unwrap_or(*row.transform);
-			let applied_stroke_transform = render_params.alignment_parent_transform.unwrap_or(applied_stroke_transform);
-			let element_transform = set_stroke_transform.map(|stroke_transform| multiplied_transform * stroke_transform.inverse());
-			let element_transform = element_transform.unwrap_or(DAffine2::IDENTITY);
-			let layer_bounds = vector.bounding_box().unwrap_or_default();
-			let transformed_bounds = vector.bounding_box_with_transform(applied_stroke_transform).unwrap_or_default();
-
-			let bounds_matrix = DAffine2::from_scale_angle_translation(layer_bounds[1] - layer_bounds[0], 0., layer_bounds[0]);
-			let transformed_bounds_matrix = element_transform * DAffine2::from_scale_angle_translation(transformed_bounds[1] - transformed_bounds[0], 0., transformed_bounds[0]);
-
-			let mut path = String::new();
-
-			for mut bezpath in row.element.stroke_bezpath_iter() {
-				bezpath.apply_affine(Affine::new(applied_stroke_transform.to_cols_array()));
-				path.push_str(bezpath.to_svg().as_str());
-			}
-
-			let mask_type = if vector.style.stroke().map(|x| x.align) == Some(StrokeAlign::Inside) {
-				MaskType::Clip
-			} else {
-				MaskType::Mask
-			};
-
-			let path_is_closed = vector.stroke_bezier_paths().all(|path| path.closed());
-			let can_draw_aligned_stroke = path_is_closed && vector.style.stroke().is_some_and(|stroke| stroke.has_renderable_stroke() && stroke.align.is_not_centered());
-			let can_use_paint_order = !(row.element.style.fill().is_none() || !row.element.style.fill().is_opaque() || mask_type == MaskType::Clip);
-
-			let needs_separate_alignment_fill = can_draw_aligned_stroke && !can_use_paint_order;
-			let wants_stroke_below = vector.style.stroke().map(|s| s.paint_order) == Some(PaintOrder::StrokeBelow);
-
-			if needs_separate_alignment_fill && !wants_stroke_below {
-				render.leaf_tag("path", |attributes| {
-					attributes.push("d", path.clone());
-					let matrix = format_transform_matrix(element_transform);
-					if !matrix.is_empty() {
-						attributes.push("transform", matrix);
-					}
-					let mut style = row.element.style.clone();
-					style.clear_stroke();
-					let fill_and_stroke = style.render(
-						&mut attributes.0.svg_defs,
-						element_transform,
-						applied_stroke_transform,
-						bounds_matrix,
-						transformed_bounds_matrix,
-						render_params,
-					);
-					attributes.push_val(fill_and_stroke);
-				});
-			}
-
-			let push_id = needs_separate_alignment_fill.then_some({
-				let id = format!("alignment-{}", generate_uuid());
-
-				let mut element = row.element.clone();
-				element.style.clear_stroke();
-				element.style.set_fill(Fill::solid(Color::BLACK));
-
-				let vector_row = Table::new_from_row(TableRow {
-					element,
-					alpha_blending: *row.alpha_blending,
-					transform: *row.transform,
-					source_node_id: None,
-				});
-
-				(id, mask_type, vector_row)
-			});
-
-			if vector.is_branching() {
-				for mut face_path in vector.construct_faces().filter(|face| !(face.area() < 0.0)) {
-					face_path.apply_affine(Affine::new(applied_stroke_transform.to_cols_array()));
-
-					let face_d = face_path.to_svg();
-					render.leaf_tag("path", |attributes| {
-						attributes.push("d", face_d.clone());
-						let matrix = format_transform_matrix(element_transform);
-						if !matrix.is_empty() {
-							attributes.push("transform", matrix);
-						}
-						let mut style = row.element.style.clone();
-						style.clear_stroke();
-						let fill_only = style.render(
-							&mut attributes.0.svg_defs,
-							element_transform,
-							applied_stroke_transform,
-							bounds_matrix,
-							transformed_bounds_matrix,
-							render_params,
-						);
-						attributes.push_val(fill_only);
-					});
-				}
+			let vector = row.element;
+			let paint = VectorPaint::from(&vector.style);
+			if render_params.render_mode == RenderMode::Outline || vector.style.appearance.is_empty() {
+				render_vector_svg(vector, paint, *row.transform, *row.alpha_blending, render, render_params);
+				continue;
 			}
 
-			render.leaf_tag("path", |attributes| {
-				attributes.push("d", path.clone());
-				let matrix = format_transform_matrix(element_transform);
-				if !matrix.is_empty() {
-					attributes.push("transform", matrix);
-				}
-
-				let defs = &mut attributes.0.svg_defs;
-				if let Some((ref id, mask_type, ref vector_row)) = push_id {
-					let mut svg = SvgRender::new();
-					vector_row.render_svg(&mut svg, &render_params.for_alignment(applied_stroke_transform));
-					let stroke = row.element.style.stroke().unwrap();
-					let weight = stroke.effective_width() * max_scale(applied_stroke_transform);
-					let quad = Quad::from_box(transformed_bounds).inflate(weight);
-					let (x, y) = quad.top_left().into();
-					let (width, height) = (quad.bottom_right() - quad.top_left()).into();
-
-					write!(defs, r##"{}"##, svg.svg_defs).unwrap();
-					let rect = format!(r##"<rect x="{x}" y="{y}" width="{width}" height="{height}" fill="white" />"##);
-
-					match mask_type {
-						MaskType::Clip => write!(defs, r##"<clipPath id="{id}">{}</clipPath>"##, svg.svg.to_svg_string()).unwrap(),
-						MaskType::Mask => write!(
-							defs,
-							r##"<mask id="{id}" maskUnits="userSpaceOnUse" maskContentUnits="userSpaceOnUse" x="{x}" y="{y}" width="{width}" height="{height}">{}{}</mask>"##,
-							rect,
-							svg.svg.to_svg_string()
-						)
-						.unwrap(),
-					}
-				}
-
-				let mut render_params = render_params.clone();
-				render_params.aligned_strokes = can_draw_aligned_stroke;
-				render_params.override_paint_order = can_draw_aligned_stroke && can_use_paint_order;
-
-				let mut style = row.element.style.clone();
-				if needs_separate_alignment_fill || vector.is_branching() {
-					style.clear_fill();
-				}
-
-				let fill_and_stroke = style.render(defs, element_transform, applied_stroke_transform, bounds_matrix, transformed_bounds_matrix, &render_params);
-
-				if let Some((id, mask_type, _)) = push_id {
-					let selector = format!("url(#{id})");
-					attributes.push(mask_type.to_attribute(), selector);
-				}
-				attributes.push_val(fill_and_stroke);
-
-				let opacity = row.alpha_blending.opacity(render_params.for_mask);
-				if opacity < 1. {
-					attributes.push("opacity", opacity.to_string());
-				}
-
-				if row.alpha_blending.blend_mode != BlendMode::default() {
-					attributes.push("style", row.alpha_blending.blend_mode.render());
-				}
-			});
-
-			// When splitting passes and stroke is below, draw the fill after the stroke.
-			if needs_separate_alignment_fill && wants_stroke_below {
-				render.leaf_tag("path", |attributes| {
-					attributes.push("d", path);
-					let matrix = format_transform_matrix(element_transform);
-					if !matrix.is_empty() {
-						attributes.push("transform", matrix);
+			// The path is drawn again from the same row for each paint in the stack, which are grouped so the row's opacity and blend mode apply to them together
+			render.parent_tag(
+				"g",
+				|attributes| push_alpha_blending_attributes(attributes, *row.alpha_blending, render_params),
+				|render| {
+					render_vector_svg(vector, paint, *row.transform, AlphaBlending::default(), render, render_params);
+					for entry in &vector.style.appearance.0 {
+						render_vector_svg(vector, VectorPaint::from(&entry.paint), *row.transform, appearance_alpha_blending(entry), render, render_params);
 					}
-					let mut style = row.element.style.clone();
-					style.clear_stroke();
-					let fill_and_stroke = style.render(
-						&mut attributes.0.svg_defs,
-						element_transform,
-						applied_stroke_transform,
-						bounds_matrix,
-						transformed_bounds_matrix,
-						render_params,
-					);
-					attributes.push_val(fill_and_stroke);
-				});
-			}
+				},
+			);
 		}
 	}
 
-	fn render_to_vello(&self, scene: &mut Scene, parent_transform: DAffine2, _context: &mut RenderContext, render_params: &RenderParams) {
-		use core_types::consts::LAYER_OUTLINE_STROKE_WEIGHT;
-		use graphic_types::vector_types::vector::style::{GradientType, StrokeCap, StrokeJoin};
-		use vello::kurbo::{Cap, Join};
-		use vello::peniko;
-
+	fn render_to_vello(&self, scene: &mut Scene, parent_transform: DAffine2, context: &mut RenderContext, render_params: &RenderParams) {
 		for row in self.iter() {
-			use graphic_types::vector_types::vector;
-
-			let multiplied_transform = parent_transform * *row.transform;
-			let has_real_stroke = row.element.style.stroke().filter(|stroke| stroke.weight() > 0.);
-			let set_stroke_transform = has_real_stroke.map(|stroke| stroke.transform).filter(|transform| transform.matrix2.determinant() != 0.);
-			let mut applied_stroke_transform = set_stroke_transform.unwrap_or(multiplied_transform);
-			let mut element_transform = set_stroke_transform
-				.map(|stroke_transform| multiplied_transform * stroke_transform.inverse())
-				.unwrap_or(DAffine2::IDENTITY);
-			if let Some(alignment_transform) = render_params.alignment_parent_transform {
-				applied_stroke_transform = alignment_transform;
-				element_transform = if alignment_transform.matrix2.determinant() != 0. {
-					multiplied_transform * alignment_transform.inverse()
-				} else {
-					multiplied_transform
-				};
-			}
-			let layer_bounds = row.element.bounding_box().unwrap_or_default();
-
-			let to_point = |p: DVec2| kurbo::Point::new(p.x, p.y);
-			let mut path = kurbo::BezPath::new();
-			for mut bezpath in row.element.stroke_bezpath_iter() {
-				bezpath.apply_affine(Affine::new(applied_stroke_transform.to_cols_array()));
-				for element in bezpath {
-					path.push(element);
-				}
+			let vector = row.element;
+			let paint = VectorPaint::from(&vector.style);
+			let transform = *row.transform;
+			if render_params.render_mode == RenderMode::Outline || vector.style.appearance.is_empty() {
+				render_vector_vello(vector, paint, transform, *row.alpha_blending, scene, parent_transform, context, render_params);
+				continue;
 			}
 
-			// If we're using opacity or a blend mode, we need to push a layer
-			let blend_mode = match render_params.render_mode {
-				RenderMode::Outline => peniko::Mix::Normal,
-				_ => row.alpha_blending.blend_mode.to_peniko(),
-			};
-			let mut layer = false;
-
-			let opacity = row.alpha_blending.opacity(render_params.for_mask);
-			if opacity < 1. || row.alpha_blending.blend_mode != BlendMode::default() {
-				layer = true;
-				let weight = row.element.style.stroke().as_ref().map_or(0., Stroke::effective_width);
-				let quad = Quad::from_box(layer_bounds).inflate(weight * max_scale(applied_stroke_transform));
-				let layer_bounds = quad.bounding_box();
-				scene.push_layer(
-					peniko::BlendMode::new(blend_mode, peniko::Compose::SrcOver),
-					opacity,
-					kurbo::Affine::new(multiplied_transform.to_cols_array()),
-					&kurbo::Rect::new(layer_bounds[0].x, layer_bounds[0].y, layer_bounds[1].x, layer_bounds[1].y),
+			// The path is drawn again from the same row for each paint in the stack, which are composited together before the row's opacity and blend mode apply to them
+			let bounds = BoundingBox::bounding_box(vector, parent_transform * transform, true);
+			let layer = push_alpha_blending_layer(scene, *row.alpha_blending, bounds, render_params);
+			render_vector_vello(vector, paint, transform, AlphaBlending::default(), scene, parent_transform, context, render_params);
+			for entry in &vector.style.appearance.0 {
+				render_vector_vello(
+					vector,
+					VectorPaint::from(&entry.paint),
+					transform,
+					appearance_alpha_blending(entry),
+					scene,
+					parent_transform,
+					context,
+					render_params,
 				);
 			}
-
-			let can_draw_aligned_stroke =
-				row.element.style.stroke().is_some_and(|stroke| stroke.has_renderable_stroke() && stroke.align.is_not_centered()) && row.element.stroke_bezier_paths().all(|path| path.closed());
-
-			let use_layer = can_draw_aligned_stroke;
-			let wants_stroke_below = row.element.style.stroke().is_some_and(|s| s.paint_order == vector::style::PaintOrder::StrokeBelow);
-
-			// Closures to avoid duplicated fill/stroke drawing logic
-			let do_fill_path = |scene: &mut Scene, path: &kurbo::BezPath| match row.element.style.fill() {
-				Fill::Solid(color) => {
-					let fill = peniko::Brush::Solid(peniko::Color::new([color.r(), color.g(), color.b(), color.a()]));
-					scene.fill(peniko::Fill::NonZero, kurbo::Affine::new(element_transform.to_cols_array()), &fill, None, path);
-				}
-				Fill::Gradient(gradient) => {
-					let mut stops = peniko::ColorStops::new();
-					for &(offset, color) in &gradient.stops {
-						stops.push(peniko::ColorStop {
-							offset: offset as f32,
-							color: peniko::color::DynamicColor::from_alpha_color(peniko::Color::new([color.r(), color.g(), color.b(), color.a()])),
-						});
-					}
-
-					let bounds = row.element.nonzero_bounding_box();
-					let bound_transform = DAffine2::from_scale_angle_translation(bounds[1] - bounds[0], 0., bounds[0]);
-
-					let inverse_parent_transform = if parent_transform.matrix2.determinant() != 0. {
-						parent_transform.inverse()
-					} else {
-						Default::default()
-					};
-					let mod_points = inverse_parent_transform * multiplied_transform * bound_transform;
-
-					let start = mod_points.transform_point2(gradient.start);
-					let end = mod_points.transform_point2(gradient.end);
-
-					let fill = peniko::Brush::Gradient(peniko::Gradient {
-						kind: match gradient.gradient_type {
-							GradientType::Linear => peniko::LinearGradientPosition {
-								start: to_point(start),
-								end: to_point(end),
-							}
-							.into(),
-							GradientType::Radial => {
-								let radius = start.distance(end);
-								peniko::RadialGradientPosition {
-									start_center: to_point(start),
-									start_radius: 0.,
-									end_center: to_point(start),
-									end_radius: radius as f32,
-								}
-								.into()
-							}
-						},
-						stops,
-						interpolation_alpha_space: peniko::InterpolationAlphaSpace::Premultiplied,
-						..Default::default()
-					});
-					let inverse_element_transform = if element_transform.matrix2.determinant() != 0. {
-						element_transform.inverse()
-					} else {
-						Default::default()
-					};
-					let brush_transform = kurbo::Affine::new((inverse_element_transform * parent_transform).to_cols_array());
-					scene.fill(peniko::Fill::NonZero, kurbo::Affine::new(element_transform.to_cols_array()), &fill, Some(brush_transform), path);
-				}
-				Fill::None => {}
-			};
-
-			let do_fill = |scene: &mut Scene| {
-				if row.element.is_branching() {
-					// For branching paths, fill each face separately
-					for mut face_path in row.element.construct_faces().filter(|face| !(face.area() < 0.0)) {
-						face_path.apply_affine(Affine::new(applied_stroke_transform.to_cols_array()));
-						let mut kurbo_path = kurbo::BezPath::new();
-						for element in face_path {
-							kurbo_path.push(element);
-						}
-						do_fill_path(scene, &kurbo_path);
-					}
-				} else {
-					// Simple fill of the entire path
-					do_fill_path(scene, &path);
-				}
-			};
-
-			let do_stroke = |scene: &mut Scene, width_scale: f64| {
-				if let Some(stroke) = row.element.style.stroke() {
-					let color = match stroke.color {
-						Some(color) => peniko::Color::new([color.r(), color.g(), color.b(), color.a()]),
-						None => peniko::Color::TRANSPARENT,
-					};
-					let cap = match stroke.cap {
-						StrokeCap::Butt => Cap::Butt,
-						StrokeCap::Round => Cap::Round,
-						StrokeCap::Square => Cap::Square,
-					};
-					let join = match stroke.join {
-						StrokeJoin::Miter => Join::Miter,
-						StrokeJoin::Bevel => Join::Bevel,
-						StrokeJoin::Round => Join::Round,
-					};
-					let dash_pattern = stroke.dash_lengths.iter().map(|l| l.max(0.)).collect();
-					let stroke = kurbo::Stroke {
-						width: stroke.weight * width_scale,
-						miter_limit: stroke.join_miter_limit,
-						join,
-						start_cap: cap,
-						end_cap: cap,
-						dash_pattern,
-						dash_offset: stroke.dash_offset,
-					};
-
-					if stroke.width > 0. {
-						scene.stroke(&stroke, kurbo::Affine::new(element_transform.to_cols_array()), color, None, &path);
-					}
-				}
-			};
-
-			// Render the path
-			match render_params.render_mode {
-				RenderMode::Outline => {
-					let outline_stroke = kurbo::Stroke {
-						width: LAYER_OUTLINE_STROKE_WEIGHT,
-						miter_limit: 4.,
-						join: Join::Miter,
-						start_cap: Cap::Butt,
-						end_cap: Cap::Butt,
-						dash_pattern: Default::default(),
-						dash_offset: 0.,
-					};
-
-					let outline_color = black_or_white_for_best_contrast(render_params.artboard_background);
-					let outline_color = peniko::Color::new([outline_color.r(), outline_color.g(), outline_color.b(), outline_color.a()]);
-
-					scene.stroke(&outline_stroke, kurbo::Affine::new(element_transform.to_cols_array()), outline_color, None, &path);
-				}
-				_ => {
-					if use_layer {
-						let mut element = row.element.clone();
-						element.style.clear_stroke();
-						element.style.set_fill(Fill::solid(Color::BLACK));
-
-						let vector_table = Table::new_from_row(TableRow {
-							element,
-							alpha_blending: *row.alpha_blending,
-							transform: *row.transform,
-							source_node_id: None,
-						});
-
-						let bounds = row.element.bounding_box_with_transform(multiplied_transform).unwrap_or(layer_bounds);
-						let weight = row.element.style.stroke().as_ref().map_or(0., Stroke::effective_width);
-						let quad = Quad::from_box(bounds).inflate(weight * max_scale(applied_stroke_transform));
-						let bounds = quad.bounding_box();
-						let rect = kurbo::Rect::new(bounds[0].x, bounds[0].y, bounds[1].x, bounds[1].y);
-
-						let compose = if row.element.style.stroke().is_some_and(|x| x.align == StrokeAlign::Outside) {
-							peniko::Compose::SrcOut
-						} else {
-							peniko::Compose::SrcIn
-						};
-
-						if wants_stroke_below {
-							scene.push_layer(peniko::Mix::Normal, 1., kurbo::Affine::IDENTITY, &rect);
-							vector_table.render_to_vello(scene, parent_transform, _context, &render_params.for_alignment(applied_stroke_transform));
-							scene.push_layer(peniko::BlendMode::new(peniko::Mix::Normal, compose), 1., kurbo::Affine::IDENTITY, &rect);
-
-							do_stroke(scene, 2.);
-
-							scene.pop_layer();
-							scene.pop_layer();
-
-							do_fill(scene);
-						} else {
-							// Fill first (unclipped), then stroke (clipped) above
-							do_fill(scene);
-
-							scene.push_layer(peniko::Mix::Normal, 1., kurbo::Affine::IDENTITY, &rect);
-							vector_table.render_to_vello(scene, parent_transform, _context, &render_params.for_alignment(applied_stroke_transform));
-							scene.push_layer(peniko::BlendMode::new(peniko::Mix::Normal, compose), 1., kurbo::Affine::IDENTITY, &rect);
-
-							do_stroke(scene, 2.);
-
-							scene.pop_layer();
-							scene.pop_layer();
-						}
-					} else {
-						// Non-aligned strokes or open paths: default order behavior
-						enum Op {
-							Fill,
-							Stroke,
-						}
-
-						let order = match row.element.style.stroke().is_some_and(|stroke| !stroke.paint_order.is_default()) {
-							true => [Op::Stroke, Op::Fill],
-							false => [Op::Fill, Op::Stroke], // Default
-						};
-
-						for operation in &order {
-							match operation {
-								Op::Fill => do_fill(scene),
-								Op::Stroke => do_stroke(scene, 1.),
-							}
-						}
-					}
-				}
-			}
-
-			// If we pushed a layer for opacity or a blend mode, we need to pop it
 			if layer {
 				scene.pop_layer();
 			}
@@ -1182,8 +1497,8 @@ impl Render for Table<Vector> {
 			let vector = row.element;
 
 			if let Some(element_id) = element_id {
-				let stroke_width = vector.style.stroke().as_ref().map_or(0., Stroke::effective_width);
-				let filled = vector.style.fill() != &Fill::None;
+				let stroke_width = vector.style.max_stroke_width();
+				let filled = vector.style.has_any_fill();
 				let fill = |mut subpath: Subpath<_>| {
 					if filled {
 						subpath.set_closed(true);
@@ -1224,8 +1539,8 @@ impl Render for Table<Vector> {
 
 	fn add_upstream_click_targets(&self, click_targets: &mut Vec<ClickTarget>) {
 		for row in self.iter() {
-			let stroke_width = row.element.style.stroke().as_ref().map_or(0., Stroke::effective_width);
-			let filled = row.element.style.fill() != &Fill::None;
+			let stroke_width = row.element.style.max_stroke_width();
+			let filled = row.element.style.has_any_fill();
 			let fill = |mut subpath: Subpath<_>| {
 				if filled {
 					subpath.set_closed(true);
@@ -1313,16 +1628,7 @@ impl Render for Table<Raster<CPU>> {
 					},
 				);
 			} else {
-				let base64_string = image.base64_string.clone().unwrap_or_else(|| {
-					use base64::Engine;
-
-					let output = image.to_png();
-					let preamble = "data:image/png;base64,";
-					let mut base64_string = String::with_capacity(preamble.len() + output.len() * 4);
-					base64_string.push_str(preamble);
-					base64::engine::general_purpose::STANDARD.encode_string(output, &mut base64_string);
-					base64_string
-				});
+				let base64_string = image.to_base64_png();
 
 				render.leaf_tag("image", |attributes| {
 					attributes.push("width", "1");
@@ -1537,7 +1843,7 @@ impl Render for Table<GradientStops> {
 				attributes.push("points", format!("{max},{max} -{max},{max} -{max},-{max} {max},-{max}"));
 
 				let mut stop_string = String::new();
-				for (position, color) in row.element.0.iter() {
+				for (position, color) in row.element.to_plain_srgb().iter() {
 					let _ = write!(stop_string, r##"<stop offset="{}" stop-color="#{}""##, position, color.to_rgb_hex_srgb_from_gamma());
 					if color.a() < 1. {
 						let _ = write!(stop_string, r#" stop-opacity="{}""#, color.a());
@@ -1566,7 +1872,8 @@ impl Render for Table<GradientStops> {
 							r#"<linearGradient id="{gradient_id}" gradientUnits="userSpaceOnUse" x1="{x1}" y1="{y1}" x2="{x2}" y2="{y2}"{gradient_transform_attribute}>{stop_string}</linearGradient>"#
 						);
 					}
-					GradientType::Radial => {
+					// Stops alone have no start and end points to give the other gradient types their shapes
+					_ => {
 						let (cx, cy) = (start.x, start.y);
 						let r = start.distance(end);
 						let _ = write!(
@@ -1599,7 +1906,7 @@ impl Render for Table<GradientStops> {
 			let blend_mode = alpha_blending.blend_mode.to_peniko();
 			let opacity = alpha_blending.opacity(render_params.for_mask);
 
-			let color = row.element.0.first().map(|stop| stop.1).unwrap_or(Color::MAGENTA);
+			let color = row.element.first().map(|stop| stop.1).unwrap_or(Color::MAGENTA);
 			let vello_color = peniko::Color::new([color.r(), color.g(), color.b(), color.a()]);
 
 			let rect = kurbo::Rect::from_origin_size(kurbo::Point::ZERO, kurbo::Size::new(1., 1.));
@@ -1673,3 +1980,155 @@ impl SvgRenderAttrs<'_> {
 		self.0.svg.push(value.into());
 	}
 }
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use graphic_types::vector_types::vector::style::{AppearancePaint, AppearanceStack, WidthPoint, WidthProfile};
+
+	/// A square filled red with a thick black stroke, then a thin translucent white stroke and a blue fill stacked over them.
+	fn square_with_appearance_stack() -> Table<Vector> {
+		let mut vector = Vector::from_subpath(Subpath::new_rectangle(DVec2::ZERO, DVec2::splat(100.)));
+		vector.style = PathStyle::new(Some(Stroke::new(Some(Color::BLACK), 8.)), Fill::Solid(Color::RED));
+		vector.style.appearance = AppearanceStack(vec![
+			AppearanceEntry {
+				paint: AppearancePaint::Stroke(Stroke::new(Some(Color::WHITE), 2.)),
+				opacity: 0.5,
+				blend_mode: BlendMode::Multiply,
+			},
+			AppearanceEntry::new(AppearancePaint::Fill(Fill::Solid(Color::BLUE))),
+		]);
+		Table::new_from_element(vector)
+	}
+
+	fn render_svg(table: &Table<Vector>, render_mode: RenderMode) -> String {
+		let mut render = SvgRender::new();
+		table.render_svg(&mut render, &RenderParams { render_mode, ..Default::default() });
+		render.svg.to_svg_string()
+	}
+
+	/// The attributes of each path in the SVG, in drawing order.
+	fn paths(svg: &str) -> Vec<&str> {
+		svg.split("<path").skip(1).map(|path| &path[..path.find("/>").unwrap()]).collect()
+	}
+
+	#[test]
+	fn appearance_stack_is_drawn_in_order_over_main_fill_and_stroke() {
+		let svg = render_svg(&square_with_appearance_stack(), RenderMode::Normal);
+		assert!(
+			svg.trim_start().starts_with("<g>"),
+			"The paths should be grouped so the layer's blending applies to them together: {svg}"
+		);
+
+		let paths = paths(&svg);
+		assert_eq!(paths.len(), 3, "{svg}");
+
+		// The main fill and stroke are drawn first
+		assert!(paths[0].contains(r##" fill="#ff0000""##));
+		assert!(paths[0].contains(r##" stroke="#000000" stroke-width="8""##));
+		assert!(!paths[0].contains("opacity="));
+
+		// Then each entry with its own opacity and blend mode
+		assert!(paths[1].contains(r#" fill="none""#));
+		assert!(paths[1].contains(r##" stroke="#ffffff" stroke-width="2""##));
+		assert!(paths[1].contains(r#" opacity="0.5""#));
+		assert!(paths[1].contains("mix-blend-mode: multiply;"));
+
+		assert!(paths[2].contains(r##" fill="#0000ff""##));
+		assert!(!paths[2].contains("stroke="));
+		assert!(!paths[2].contains("opacity="));
+		assert!(!paths[2].contains("mix-blend-mode"));
+	}
+
+	#[test]
+	fn appearance_stack_is_grouped_with_row_opacity() {
+		let mut table = square_with_appearance_stack();
+		for row in table.iter_mut() {
+			row.alpha_blending.opacity = 0.25;
+		}
+
+		let svg = render_svg(&table, RenderMode::Normal);
+		assert!(svg.trim_start().starts_with(r#"<g opacity="0.25">"#), "{svg}");
+		// The entries keep only their own opacity
+		let paths = paths(&svg);
+		assert!(!paths[0].contains("opacity="));
+		assert!(paths[1].contains(r#" opacity="0.5""#));
+	}
+
+	#[test]
+	fn appearance_stack_is_ignored_in_outline_mode() {
+		let svg = render_svg(&square_with_appearance_stack(), RenderMode::Outline);
+		assert!(!svg.contains("<g"));
+		assert_eq!(paths(&svg).len(), 1, "{svg}");
+	}
+
+	#[test]
+	fn vector_without_appearance_stack_is_a_single_path() {
+		let mut table = square_with_appearance_stack();
+		for row in table.iter_mut() {
+			row.element.style.appearance = AppearanceStack::default();
+		}
+
+		let svg = render_svg(&table, RenderMode::Normal);
+		assert!(!svg.contains("<g"));
+		let paths = paths(&svg);
+		assert_eq!(paths.len(), 1, "{svg}");
+		assert!(paths[0].contains(r##" fill="#ff0000""##));
+		assert!(paths[0].contains(r##" stroke="#000000" stroke-width="8""##));
+	}
+
+	/// A square filled red with a black stroke which widens along the path.
+	fn square_with_width_profile(paint_order: PaintOrder) -> Vector {
+		let mut vector = Vector::from_subpath(Subpath::new_rectangle(DVec2::ZERO, DVec2::splat(100.)));
+		let profile = WidthProfile::new([WidthPoint::new(0., 1., 1.), WidthPoint::new(1., 3., 3.)]);
+		let mut stroke = Stroke::new(Some(Color::BLACK), 8.).with_width_profile(profile);
+		stroke.paint_order = paint_order;
+		vector.style = PathStyle::new(Some(stroke), Fill::Solid(Color::RED));
+		vector
+	}
+
+	#[test]
+	fn width_profile_stroke_is_drawn_as_filled_outline() {
+		let svg = render_svg(&Table::new_from_element(square_with_width_profile(PaintOrder::StrokeAbove)), RenderMode::Normal);
+		let paths = paths(&svg);
+		assert_eq!(paths.len(), 2, "{svg}");
+
+		assert!(paths[0].contains(r##" fill="#ff0000""##));
+		assert!(!paths[0].contains("stroke="));
+		assert!(paths[1].contains(r##" fill="#000000""##));
+		assert!(!paths[1].contains("stroke="));
+
+		// With the stroke below, its outline is drawn first
+		let svg = render_svg(&Table::new_from_element(square_with_width_profile(PaintOrder::StrokeBelow)), RenderMode::Normal);
+		let paths = paths(&svg);
+		assert!(paths[0].contains(r##" fill="#000000""##));
+		assert!(paths[1].contains(r##" fill="#ff0000""##));
+	}
+
+	#[test]
+	fn width_profile_does_not_apply_to_appearance_stack_strokes() {
+		let mut vector = square_with_width_profile(PaintOrder::StrokeAbove);
+		vector.style.appearance = AppearanceStack(vec![AppearanceEntry::new(AppearancePaint::Stroke(Stroke::new(Some(Color::WHITE), 2.)))]);
+
+		let svg = render_svg(&Table::new_from_element(vector), RenderMode::Normal);
+		let paths = paths(&svg);
+		assert_eq!(paths.len(), 3, "{svg}");
+
+		// The main stroke is drawn as its outline, then the stack's stroke at its own uniform width
+		assert!(paths[1].contains(r##" fill="#000000""##));
+		assert!(paths[2].contains(r##" stroke="#ffffff" stroke-width="2""##));
+	}
+
+	#[test]
+	fn width_profile_outline_is_traced_once() {
+		let vector = square_with_width_profile(PaintOrder::StrokeAbove);
+		let stroke = vector.style.stroke.as_ref().unwrap();
+		let outline = width_profile_outline(&vector, stroke);
+		assert!(Rc::ptr_eq(&outline, &width_profile_outline(&vector, stroke)));
+
+		// A different width traces a new outline
+		let mut wider = vector.clone();
+		wider.style.stroke.as_mut().unwrap().weight = 16.;
+		assert!(!Rc::ptr_eq(&outline, &width_profile_outline(&wider, wider.style.stroke.as_ref().unwrap())));
+	}
+}
@@ -5,30 +5,134 @@ use glam::{DAffine2, DVec2};
 #[derive(Default, PartialEq, Eq, Clone, Copy, Debug, Hash, serde::Serialize, serde::Deserialize, DynAny, specta::Type, node_macro::ChoiceType)]
 #[widget(Radio)]
 pub enum GradientType {
+	/// Blends the colors along the line from the start point to the end point.
 	#[default]
 	Linear,
+	/// Blends the colors outward from the start point in a circle or ellipse reaching the end point.
 	Radial,
+	/// Blends the colors along the line from the start point to the end point, mirrored back the other way from the start point.
+	Reflected,
+	/// Sweeps the colors around the start point, beginning and ending in the direction of the end point.
+	Conic,
+	/// Blends the colors outward from the start point in a diamond with a corner at the end point.
+	Diamond,
 }
 
+impl GradientType {
+	/// Whether the gradient can be squashed along the axis perpendicular to its start and end points.
+	pub fn has_minor_axis(self) -> bool {
+		matches!(self, Self::Radial | Self::Diamond)
+	}
+}
+
+/// The color space in which a gradient blends between neighboring stops.
+#[derive(Default, PartialEq, Eq, Clone, Copy, Debug, Hash, serde::Serialize, serde::Deserialize, DynAny, specta::Type, node_macro::ChoiceType)]
+#[widget(Dropdown)]
+pub enum GradientInterpolation {
+	/// Blends the gamma-encoded sRGB channels, matching how SVG and most other software draw gradients.
+	#[default]
+	#[label("sRGB")]
+	Srgb,
+	/// Blends the physical intensity of light, which keeps mixtures bright but spends most of the gradient near the lighter color.
+	#[label("Linear sRGB")]
+	LinearSrgb,
+	/// Blends in a perceptually uniform space, avoiding the dull and dark middle of gradients between saturated colors.
+	#[label("OKLab")]
+	Oklab,
+	/// Blends lightness, chroma, and hue separately in a perceptually uniform space, keeping colors saturated as the hue turns.
+	#[label("OKLCH")]
+	Oklch,
+}
+
+/// The way around the color wheel taken by gradients which blend hues, such as those in [`GradientInterpolation::Oklch`].
+#[derive(Default, PartialEq, Eq, Clone, Copy, Debug, Hash, serde::Serialize, serde::Deserialize, DynAny, specta::Type, node_macro::ChoiceType)]
+#[widget(Dropdown)]
+pub enum HueDirection {
+	/// Turns the hue through the smaller angle between the colors.
+	#[default]
+	Shorter,
+	/// Turns the hue through the larger angle between the colors, passing through the rest of the color wheel.
+	Longer,
+	/// Always turns the hue with increasing angle.
+	Increasing,
+	/// Always turns the hue with decreasing angle.
+	Decreasing,
+}
+
+/// The midpoint of the blend between two neighboring stops when it lies halfway between them.
+pub const DEFAULT_GRADIENT_MIDPOINT: f64 = 0.5;
+
+/// The number of intermediate stops added between each pair of stops to approximate blending which isn't linear in sRGB.
+const SAMPLES_PER_SPAN: usize = 16;
+
 // TODO: Someday we could switch this to a Box[T] to avoid over-allocation
-// TODO: Use linear not gamma colors
-/// A list of colors associated with positions (in the range 0 to 1) along a gradient.
-#[derive(Debug, Clone, PartialEq, serde::Serialize, serde::Deserialize, DynAny, specta::Type)]
-pub struct GradientStops(pub Vec<(f64, Color)>);
+/// A list of colors associated with positions (in the range 0 to 1) along a gradient, with the choices of how to blend between them.
+///
+/// The colors are gamma-encoded regardless of the interpolation space, which only affects how the colors between the stops are found.
+#[derive(Debug, Clone, PartialEq, serde::Serialize, DynAny, specta::Type)]
+pub struct GradientStops {
+	pub stops: Vec<(f64, Color)>,
+	/// For the span following each stop, the fraction of the way to the next stop where the two colors are blended evenly.
+	/// Spans without a midpoint listed here are blended evenly halfway along.
+	#[serde(default)]
+	pub midpoints: Vec<f64>,
+	#[serde(default)]
+	pub interpolation: GradientInterpolation,
+	#[serde(default)]
+	pub hue_direction: HueDirection,
+}
+
+/// Documents saved before gradients had midpoints and interpolation spaces store their stops as a plain list.
+#[derive(serde::Deserialize)]
+#[serde(untagged)]
+enum GradientStopsFormat {
+	Stops(Vec<(f64, Color)>),
+	Full {
+		stops: Vec<(f64, Color)>,
+		#[serde(default)]
+		midpoints: Vec<f64>,
+		#[serde(default)]
+		interpolation: GradientInterpolation,
+		#[serde(default)]
+		hue_direction: HueDirection,
+	},
+}
+
+impl<'de> serde::Deserialize<'de> for GradientStops {
+	fn deserialize<D: serde::Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+		Ok(match GradientStopsFormat::deserialize(deserializer)? {
+			GradientStopsFormat::Stops(stops) => Self::from_stops(stops),
+			GradientStopsFormat::Full {
+				stops,
+				midpoints,
+				interpolation,
+				hue_direction,
+			} => Self {
+				stops,
+				midpoints,
+				interpolation,
+				hue_direction,
+			},
+		})
+	}
+}
 
 impl std::hash::Hash for GradientStops {
 	fn hash<H: std::hash::Hasher>(&self, state: &mut H) {
-		self.0.len().hash(state);
-		self.0.iter().for_each(|(position, color)| {
+		self.stops.len().hash(state);
+		self.stops.iter().for_each(|(position, color)| {
 			position.to_bits().hash(state);
 			color.hash(state);
 		});
+		self.midpoints.iter().for_each(|midpoint| midpoint.to_bits().hash(state));
+		self.interpolation.hash(state);
+		self.hue_direction.hash(state);
 	}
 }
 
 impl Default for GradientStops {
 	fn default() -> Self {
-		Self(vec![(0., Color::BLACK), (1., Color::WHITE)])
+		Self::from_stops(vec![(0., Color::BLACK), (1., Color::WHITE)])
 	}
 }
 
@@ -43,7 +147,7 @@ impl IntoIterator for GradientStops {
 	type IntoIter = std::vec::IntoIter<(f64, Color)>;
 
 	fn into_iter(self) -> Self::IntoIter {
-		self.0.into_iter()
+		self.stops.into_iter()
 	}
 }
 
@@ -52,7 +156,7 @@ impl<'a> IntoIterator for &'a GradientStops {
 	type IntoIter = std::slice::Iter<'a, (f64, Color)>;
 
 	fn into_iter(self) -> Self::IntoIter {
-		self.0.iter()
+		self.stops.iter()
 	}
 }
 
@@ -60,7 +164,7 @@ impl std::ops::Index<usize> for GradientStops {
 	type Output = (f64, Color);
 
 	fn index(&self, index: usize) -> &Self::Output {
-		&self.0[index]
+		&self.stops[index]
 	}
 }
 
@@ -68,60 +172,329 @@ impl std::ops::Deref for GradientStops {
 	type Target = Vec<(f64, Color)>;
 
 	fn deref(&self) -> &Self::Target {
-		&self.0
+		&self.stops
 	}
 }
 
 impl std::ops::DerefMut for GradientStops {
 	fn deref_mut(&mut self) -> &mut Self::Target {
-		&mut self.0
+		&mut self.stops
 	}
 }
 
 impl GradientStops {
 	pub fn new(stops: Vec<(f64, Color)>) -> Self {
-		let mut stops = Self(stops);
+		let mut stops = Self::from_stops(stops);
 		stops.sort();
 		stops
 	}
 
+	/// Uses the stops in their given order, blended evenly in sRGB.
+	fn from_stops(stops: Vec<(f64, Color)>) -> Self {
+		Self {
+			stops,
+			midpoints: Vec::new(),
+			interpolation: GradientInterpolation::default(),
+			hue_direction: HueDirection::default(),
+		}
+	}
+
+	/// Takes the interpolation space and hue direction from another gradient, along with its midpoints if it has the same number of stops.
+	pub fn with_blending_of(mut self, other: &Self) -> Self {
+		self.interpolation = other.interpolation;
+		self.hue_direction = other.hue_direction;
+		if self.stops.len() == other.stops.len() {
+			self.midpoints = other.midpoints.clone();
+		}
+		self
+	}
+
+	/// The midpoint of the span between the stop at the index and the next one, as a fraction of the way between them.
+	pub fn midpoint(&self, index: usize) -> f64 {
+		self.midpoints.get(index).copied().unwrap_or(DEFAULT_GRADIENT_MIDPOINT)
+	}
+
+	/// The position along the whole gradient of the midpoint of the span between the stop at the index and the next one.
+	pub fn midpoint_position(&self, index: usize) -> Option<f64> {
+		let (start, end) = (self.stops.get(index)?.0, self.stops.get(index + 1)?.0);
+		Some(start + (end - start) * self.midpoint(index))
+	}
+
+	/// Moves the midpoint of the span between the stop at the index and the next one, keeping it strictly between the two stops.
+	pub fn set_midpoint(&mut self, index: usize, midpoint: f64) {
+		if index + 1 >= self.stops.len() {
+			return;
+		}
+		self.midpoints_mut()[index] = midpoint.clamp(0.01, 0.99);
+	}
+
+	/// The midpoints of every span, filled in with the default for any which are missing.
+	fn midpoints_mut(&mut self) -> &mut Vec<f64> {
+		self.midpoints.resize(self.stops.len().saturating_sub(1), DEFAULT_GRADIENT_MIDPOINT);
+		&mut self.midpoints
+	}
+
+	/// Whether every span is blended evenly in sRGB, as SVG and Vello draw gradients.
+	pub fn is_plain_srgb(&self) -> bool {
+		self.interpolation == GradientInterpolation::Srgb && self.midpoints.iter().all(|&midpoint| midpoint == DEFAULT_GRADIENT_MIDPOINT)
+	}
+
 	pub fn evaluate(&self, t: f64) -> Color {
-		if self.0.is_empty() {
+		if self.stops.is_empty() {
 			return Color::BLACK;
 		}
 
-		if t <= self.0[0].0 {
-			return self.0[0].1;
+		if t <= self.stops[0].0 {
+			return self.stops[0].1;
 		}
-		if t >= self.0[self.0.len() - 1].0 {
-			return self.0[self.0.len() - 1].1;
+		if t >= self.stops[self.stops.len() - 1].0 {
+			return self.stops[self.stops.len() - 1].1;
 		}
 
-		for i in 0..self.0.len() - 1 {
-			let (t1, c1) = self.0[i];
-			let (t2, c2) = self.0[i + 1];
+		for i in 0..self.stops.len() - 1 {
+			let (t1, c1) = self.stops[i];
+			let (t2, c2) = self.stops[i + 1];
 			if t >= t1 && t <= t2 {
 				let normalized_t = (t - t1) / (t2 - t1);
-				return c1.lerp(&c2, normalized_t as f32);
+				let eased_t = ease_to_midpoint(normalized_t, self.midpoint(i));
+				return self.interpolation.blend(c1, c2, eased_t, self.hue_direction);
 			}
 		}
 
 		Color::BLACK
 	}
 
+	/// Approximates the gradient with stops blended evenly in sRGB, adding intermediate stops to any spans which are blended otherwise.
+	///
+	/// This is needed by renderers, which only blend stops evenly in sRGB.
+	pub fn to_plain_srgb(&self) -> Self {
+		if self.is_plain_srgb() {
+			return Self::from_stops(self.stops.clone());
+		}
+
+		let mut stops = Vec::with_capacity(self.stops.len() + self.stops.len().saturating_sub(1) * (SAMPLES_PER_SPAN - 1));
+		for (index, pair) in self.stops.windows(2).enumerate() {
+			let [(t1, c1), (t2, c2)] = [pair[0], pair[1]];
+			stops.push((t1, c1));
+
+			// A zero-length span is a hard edge between the colors with nothing in between
+			if t2 <= t1 {
+				continue;
+			}
+			let midpoint = self.midpoint(index);
+			stops.extend((1..SAMPLES_PER_SPAN).map(|sample| {
+				let normalized_t = sample as f64 / SAMPLES_PER_SPAN as f64;
+				let color = self.interpolation.blend(c1, c2, ease_to_midpoint(normalized_t, midpoint), self.hue_direction);
+				(t1 + (t2 - t1) * normalized_t, color)
+			}));
+		}
+		stops.extend(self.stops.last().copied());
+
+		Self::from_stops(stops)
+	}
+
+	/// Sorts the stops by position, keeping each midpoint with the stop that begins its span.
 	pub fn sort(&mut self) {
-		self.0.sort_unstable_by(|a, b| a.0.partial_cmp(&b.0).unwrap());
+		let midpoints = self.midpoints.clone();
+		let mut order = (0..self.stops.len()).collect::<Vec<_>>();
+		order.sort_by(|&a, &b| self.stops[a].0.partial_cmp(&self.stops[b].0).unwrap());
+
+		self.stops = order.iter().map(|&index| self.stops[index]).collect();
+		if !midpoints.is_empty() {
+			self.midpoints = order
+				.iter()
+				.take(self.stops.len().saturating_sub(1))
+				.map(|&index| midpoints.get(index).copied().unwrap_or(DEFAULT_GRADIENT_MIDPOINT))
+				.collect();
+		}
+	}
+
+	/// Inserts a stop at the index, splitting the span it lands in so that the span's midpoint stays in place.
+	pub fn insert(&mut self, index: usize, stop: (f64, Color)) {
+		// A stop added before the first or after the last begins a new span blended evenly
+		if index == 0 || index >= self.stops.len() {
+			if index == 0 && !self.midpoints.is_empty() {
+				self.midpoints.insert(0, DEFAULT_GRADIENT_MIDPOINT);
+			}
+			self.stops.insert(index, stop);
+			return;
+		}
+
+		let (start, end) = (self.stops[index - 1].0, self.stops[index].0);
+		let midpoint = start + (end - start) * self.midpoint(index - 1);
+		let split = stop.0;
+		let fraction = |from: f64, to: f64| {
+			if to > from {
+				((midpoint - from) / (to - from)).clamp(0.01, 0.99)
+			} else {
+				DEFAULT_GRADIENT_MIDPOINT
+			}
+		};
+		let (before, after) = if midpoint <= split {
+			(fraction(start, split), DEFAULT_GRADIENT_MIDPOINT)
+		} else {
+			(DEFAULT_GRADIENT_MIDPOINT, fraction(split, end))
+		};
+
+		let midpoints = self.midpoints_mut();
+		midpoints[index - 1] = before;
+		midpoints.insert(index, after);
+		self.stops.insert(index, stop);
+	}
+
+	/// Removes the stop at the index, merging the spans on either side of it into one blended evenly.
+	pub fn remove(&mut self, index: usize) -> (f64, Color) {
+		let stop_count = self.stops.len();
+		if !self.midpoints.is_empty() && stop_count > 1 {
+			let midpoints = self.midpoints_mut();
+			midpoints.remove(index.min(stop_count - 2));
+			if index > 0 && index < stop_count - 1 {
+				midpoints[index - 1] = DEFAULT_GRADIENT_MIDPOINT;
+			}
+		}
+		self.stops.remove(index)
+	}
+
+	/// Removes the last stop, along with the midpoint of the span leading up to it.
+	pub fn pop(&mut self) -> Option<(f64, Color)> {
+		let index = self.stops.len().checked_sub(1)?;
+		Some(self.remove(index))
 	}
 
 	pub fn reversed(&self) -> Self {
-		Self(self.0.iter().rev().map(|(position, color)| (1. - position, *color)).collect())
+		Self {
+			stops: self.stops.iter().rev().map(|(position, color)| (1. - position, *color)).collect(),
+			midpoints: self.reversed_midpoints().collect(),
+			..self.clone()
+		}
+	}
+
+	/// The midpoints of the spans in reverse order, measured from the other end of each span.
+	fn reversed_midpoints(&self) -> impl Iterator<Item = f64> + '_ {
+		(0..self.stops.len().saturating_sub(1)).rev().map(|index| 1. - self.midpoint(index))
 	}
 
 	pub fn map_colors<F: Fn(&Color) -> Color>(&self, f: F) -> Self {
-		Self(self.0.iter().map(|(position, color)| (*position, f(color))).collect())
+		Self {
+			stops: self.stops.iter().map(|(position, color)| (*position, f(color))).collect(),
+			..self.clone()
+		}
+	}
+
+	/// Squeezes the stops into the second half of the range, preceded by a mirror image of them in the first half.
+	pub fn mirrored(&self) -> Self {
+		let before = self.stops.iter().rev().map(|(position, color)| (0.5 - position / 2., *color));
+		let after = self.stops.iter().map(|(position, color)| (0.5 + position / 2., *color));
+		let midpoints = if self.midpoints.is_empty() || self.stops.is_empty() {
+			Vec::new()
+		} else {
+			let original = (0..self.stops.len() - 1).map(|index| self.midpoint(index));
+			self.reversed_midpoints().chain(std::iter::once(DEFAULT_GRADIENT_MIDPOINT)).chain(original).collect()
+		};
+
+		Self {
+			stops: before.chain(after).collect(),
+			midpoints,
+			..self.clone()
+		}
+	}
+}
+
+/// Remaps the fraction of the way between two stops so that it reaches one half at the midpoint, following the CSS color hint curve.
+fn ease_to_midpoint(t: f64, midpoint: f64) -> f64 {
+	if midpoint == DEFAULT_GRADIENT_MIDPOINT {
+		return t;
+	}
+	let midpoint = midpoint.clamp(1e-6, 1. - 1e-6);
+	t.clamp(0., 1.).powf(0.5_f64.ln() / midpoint.ln())
+}
+
+impl GradientInterpolation {
+	/// Blends between two gamma-encoded colors in this interpolation space, with the alpha blended linearly.
+	pub fn blend(self, start: Color, end: Color, t: f64, hue_direction: HueDirection) -> Color {
+		let t = t.clamp(0., 1.);
+		let lerp = |a: [f64; 3], b: [f64; 3]| std::array::from_fn(|i| a[i] + (b[i] - a[i]) * t);
+
+		let channels = match self {
+			Self::Srgb => return start.lerp(&end, t as f32),
+			Self::LinearSrgb => lerp(linear_channels(start), linear_channels(end)),
+			Self::Oklab => oklab_to_linear(lerp(linear_to_oklab(linear_channels(start)), linear_to_oklab(linear_channels(end)))),
+			Self::Oklch => {
+				let [l1, c1, h1] = oklab_to_oklch(linear_to_oklab(linear_channels(start)));
+				let [l2, c2, h2] = oklab_to_oklch(linear_to_oklab(linear_channels(end)));
+
+				// A gray has no hue of its own, so it takes on the hue of the other color
+				const ACHROMATIC: f64 = 1e-4;
+				let (h1, h2) = match (c1 < ACHROMATIC, c2 < ACHROMATIC) {
+					(true, false) => (h2, h2),
+					(false, true) => (h1, h1),
+					_ => (h1, h2),
+				};
+				let hue = h1 + hue_difference(h1, h2, hue_direction) * t;
+
+				oklab_to_linear(oklch_to_oklab([l1 + (l2 - l1) * t, c1 + (c2 - c1) * t, hue]))
+			}
+		};
+
+		let [red, green, blue] = channels.map(|channel| Color::linear_to_srgb(channel.clamp(0., 1.) as f32));
+		let alpha = start.a() + (end.a() - start.a()) * t as f32;
+		Color::from_rgbaf32_unchecked(red, green, blue, alpha)
 	}
 }
 
+/// The signed angle in degrees to turn from the first hue to reach the second, going around the color wheel in the given direction.
+fn hue_difference(from: f64, to: f64, direction: HueDirection) -> f64 {
+	let difference = (to - from).rem_euclid(360.);
+	match direction {
+		HueDirection::Shorter if difference > 180. => difference - 360.,
+		HueDirection::Shorter => difference,
+		HueDirection::Longer if difference == 0. => 0.,
+		HueDirection::Longer if difference <= 180. => difference - 360.,
+		HueDirection::Longer => difference,
+		HueDirection::Increasing => difference,
+		HueDirection::Decreasing if difference == 0. => 0.,
+		HueDirection::Decreasing => difference - 360.,
+	}
+}
+
+fn linear_channels(color: Color) -> [f64; 3] {
+	[color.r(), color.g(), color.b()].map(|channel| Color::srgb_to_linear(channel) as f64)
+}
+
+// Conversions from https://bottosson.github.io/posts/oklab/
+fn linear_to_oklab([r, g, b]: [f64; 3]) -> [f64; 3] {
+	let l = (0.4122214708 * r + 0.5363325363 * g + 0.0514459929 * b).cbrt();
+	let m = (0.2119034982 * r + 0.6806995451 * g + 0.1073969566 * b).cbrt();
+	let s = (0.0883024619 * r + 0.2817188376 * g + 0.6299787005 * b).cbrt();
+
+	[
+		0.2104542553 * l + 0.7936177850 * m - 0.0040720468 * s,
+		1.9779984951 * l - 2.4285922050 * m + 0.4505937099 * s,
+		0.0259040371 * l + 0.7827717662 * m - 0.8086757660 * s,
+	]
+}
+
+fn oklab_to_linear([lightness, a, b]: [f64; 3]) -> [f64; 3] {
+	let l = (lightness + 0.3963377774 * a + 0.2158037573 * b).powi(3);
+	let m = (lightness - 0.1055613458 * a - 0.0638541728 * b).powi(3);
+	let s = (lightness - 0.0894841775 * a - 1.2914855480 * b).powi(3);
+
+	[
+		4.0767416621 * l - 3.3077115913 * m + 0.2309699292 * s,
+		-1.2684380046 * l + 2.6097574011 * m - 0.3413193965 * s,
+		-0.0041960863 * l - 0.7034186147 * m + 1.7076147010 * s,
+	]
+}
+
+fn oklab_to_oklch([lightness, a, b]: [f64; 3]) -> [f64; 3] {
+	[lightness, a.hypot(b), b.atan2(a).to_degrees().rem_euclid(360.)]
+}
+
+fn oklch_to_oklab([lightness, chroma, hue]: [f64; 3]) -> [f64; 3] {
+	let (sin, cos) = hue.to_radians().sin_cos();
+	[lightness, chroma * cos, chroma * sin]
+}
+
 /// A gradient fill.
 ///
 /// Contains the start and end points, along with the colors at varying points along the length.
@@ -132,6 +505,17 @@ pub struct Gradient {
 	pub gradient_type: GradientType,
 	pub start: DVec2,
 	pub end: DVec2,
+	/// The length of the minor axis, perpendicular to the line from the start point to the end point, as a fraction of that line's length.
+	/// Only used by gradient types with a minor axis, making a radial gradient elliptical or a diamond gradient rhombic.
+	#[serde(default = "one")]
+	pub minor_axis_ratio: f64,
+	/// The offset from the start point to the focal point of a radial gradient, where its first color stop is centered.
+	#[serde(default)]
+	pub focal_offset: DVec2,
+}
+
+fn one() -> f64 {
+	1.
 }
 
 impl Default for Gradient {
@@ -141,19 +525,21 @@ impl Default for Gradient {
 			gradient_type: GradientType::Linear,
 			start: DVec2::new(0., 0.5),
 			end: DVec2::new(1., 0.5),
+			minor_axis_ratio: 1.,
+			focal_offset: DVec2::ZERO,
 		}
 	}
 }
 
 impl std::hash::Hash for Gradient {
 	fn hash<H: std::hash::Hasher>(&self, state: &mut H) {
-		self.stops.0.len().hash(state);
+		self.stops.hash(state);
 		[].iter()
 			.chain(self.start.to_array().iter())
 			.chain(self.end.to_array().iter())
-			.chain(self.stops.0.iter().map(|(position, _)| position))
+			.chain(std::iter::once(&self.minor_axis_ratio))
+			.chain(self.focal_offset.to_array().iter())
 			.for_each(|x| x.to_bits().hash(state));
-		self.stops.0.iter().for_each(|(_, color)| color.hash(state));
 		self.gradient_type.hash(state);
 	}
 }
@@ -163,7 +549,6 @@ impl std::fmt::Display for Gradient {
 		let round = |x: f64| (x * 1e3).round() / 1e3;
 		let stops = self
 			.stops
-			.0
 			.iter()
 			.map(|(position, color)| format!("[{}%: #{}]", round(position * 100.), color.to_rgba_hex_srgb()))
 			.collect::<Vec<_>>()
@@ -177,7 +562,13 @@ impl Gradient {
 	pub fn new(start: DVec2, start_color: Color, end: DVec2, end_color: Color, gradient_type: GradientType) -> Self {
 		let stops = GradientStops::new(vec![(0., start_color.to_gamma_srgb()), (1., end_color.to_gamma_srgb())]);
 
-		Self { start, end, stops, gradient_type }
+		Self {
+			start,
+			end,
+			stops,
+			gradient_type,
+			..Default::default()
+		}
 	}
 
 	pub fn lerp(&self, other: &Self, time: f64) -> Self {
@@ -185,19 +576,68 @@ impl Gradient {
 		let end = self.end + (other.end - self.end) * time;
 		let stops = self
 			.stops
-			.0
 			.iter()
-			.zip(other.stops.0.iter())
+			.zip(other.stops.iter())
 			.map(|((a_pos, a_color), (b_pos, b_color))| {
 				let position = a_pos + (b_pos - a_pos) * time;
 				let color = a_color.lerp(b_color, time as f32);
 				(position, color)
 			})
 			.collect::<Vec<_>>();
-		let stops = GradientStops::new(stops);
+		let midpoints = (0..stops.len().saturating_sub(1))
+			.map(|index| {
+				let (a, b) = (self.stops.midpoint(index), other.stops.midpoint(index));
+				a + (b - a) * time
+			})
+			.collect();
+		let blending = if time < 0.5 { &self.stops } else { &other.stops };
+		let mut stops = GradientStops {
+			stops,
+			midpoints,
+			interpolation: blending.interpolation,
+			hue_direction: blending.hue_direction,
+		};
+		stops.sort();
 		let gradient_type = if time < 0.5 { self.gradient_type } else { other.gradient_type };
+		let minor_axis_ratio = self.minor_axis_ratio + (other.minor_axis_ratio - self.minor_axis_ratio) * time;
+		let focal_offset = self.focal_offset + (other.focal_offset - self.focal_offset) * time;
 
-		Self { start, end, stops, gradient_type }
+		Self {
+			start,
+			end,
+			stops,
+			gradient_type,
+			minor_axis_ratio,
+			focal_offset,
+		}
+	}
+
+	/// The focal point of a radial gradient, in the same coordinate space as the start and end points.
+	pub fn focal_point(&self) -> DVec2 {
+		self.start + self.focal_offset
+	}
+
+	/// The end of the minor axis after the start and end points are transformed, which lies perpendicular to the line between them.
+	pub fn minor_axis_end(&self, transform: DAffine2) -> DVec2 {
+		let start = transform.transform_point2(self.start);
+		let major_axis = transform.transform_point2(self.end) - start;
+		start + major_axis.perp() * self.minor_axis_ratio
+	}
+
+	/// Maps the gradient's unit space onto the space the start and end points are transformed into.
+	///
+	/// In unit space, the start point is the origin and the end point is `(1, 0)`, with the minor axis (if the gradient type has one) scaled to `(0, 1)`.
+	/// Its colors are then found from the coordinates `(u, v)` of each point as the stop position:
+	/// - Linear: `u`
+	/// - Radial: the distance from the origin, with the focal point moved from the origin
+	/// - Reflected: `|u|`
+	/// - Conic: the angle from the `u` axis towards the `v` axis, as a fraction of a full turn
+	/// - Diamond: `|u| + |v|`
+	pub fn unit_transform(&self, transform: DAffine2) -> DAffine2 {
+		let start = transform.transform_point2(self.start);
+		let major_axis = transform.transform_point2(self.end) - start;
+		let minor_axis_ratio = if self.gradient_type.has_minor_axis() { self.minor_axis_ratio } else { 1. };
+		DAffine2::from_cols(major_axis, major_axis.perp() * minor_axis_ratio, start)
 	}
 
 	/// Insert a stop into the gradient, the index if successful
@@ -213,27 +653,17 @@ impl Gradient {
 			return None;
 		}
 
-		// Compute the color of the inserted stop
-		let get_color = |index: usize, time: f64| match (self.stops.0[index].1, self.stops.0.get(index + 1).map(|(_, c)| *c)) {
-			// Lerp between the nearest colors if applicable
-			(a, Some(b)) => a.lerp(
-				&b,
-				((time - self.stops.0[index].0) / self.stops.0.get(index + 1).map(|end| end.0 - self.stops.0[index].0).unwrap_or_default()) as f32,
-			),
-			// Use the start or the end color if applicable
-			(v, _) => v,
-		};
-
 		// Compute the correct index to keep the positions in order
 		let mut index = 0;
-		while self.stops.0.len() > index && self.stops.0[index].0 <= new_position {
+		while self.stops.len() > index && self.stops[index].0 <= new_position {
 			index += 1;
 		}
 
-		let new_color = get_color(index - 1, new_position);
+		// Match the color already found at that position
+		let new_color = self.stops.evaluate(new_position);
 
 		// Insert the new stop
-		self.stops.0.insert(index, (new_position, new_color));
+		self.stops.insert(index, (new_position, new_color));
 
 		Some(index)
 	}
@@ -244,3 +674,109 @@ impl core_types::bounds::BoundingBox for GradientStops {
 		core_types::bounds::RenderBoundingBox::Infinite
 	}
 }
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	fn assert_color_near(actual: Color, expected: Color) {
+		let difference = [actual.r() - expected.r(), actual.g() - expected.g(), actual.b() - expected.b(), actual.a() - expected.a()];
+		assert!(difference.iter().all(|d| d.abs() < 1e-3), "Expected {expected:?}, found {actual:?}");
+	}
+
+	fn red_to_blue(interpolation: GradientInterpolation) -> GradientStops {
+		let red = Color::from_rgbaf32_unchecked(1., 0., 0., 1.);
+		let blue = Color::from_rgbaf32_unchecked(0., 0., 1., 1.);
+		GradientStops {
+			interpolation,
+			..GradientStops::new(vec![(0., red), (1., blue)])
+		}
+	}
+
+	#[test]
+	fn interpolation_spaces_keep_stop_colors() {
+		for interpolation in [
+			GradientInterpolation::Srgb,
+			GradientInterpolation::LinearSrgb,
+			GradientInterpolation::Oklab,
+			GradientInterpolation::Oklch,
+		] {
+			let gradient = red_to_blue(interpolation);
+			assert_color_near(gradient.evaluate(0.), gradient[0].1);
+			assert_color_near(gradient.evaluate(1.), gradient[1].1);
+			assert_color_near(interpolation.blend(gradient[0].1, gradient[1].1, 1., HueDirection::Shorter), gradient[1].1);
+		}
+	}
+
+	#[test]
+	fn perceptual_interpolation_is_brighter_than_srgb() {
+		let srgb_middle = red_to_blue(GradientInterpolation::Srgb).evaluate(0.5);
+		assert_color_near(srgb_middle, Color::from_rgbaf32_unchecked(0.5, 0., 0.5, 1.));
+
+		for interpolation in [GradientInterpolation::LinearSrgb, GradientInterpolation::Oklab] {
+			let middle = red_to_blue(interpolation).evaluate(0.5);
+			assert!(middle.r() > srgb_middle.r() && middle.b() > srgb_middle.b(), "{interpolation:?} gave {middle:?}");
+		}
+	}
+
+	#[test]
+	fn hue_directions() {
+		assert_eq!(hue_difference(350., 10., HueDirection::Shorter), 20.);
+		assert_eq!(hue_difference(10., 350., HueDirection::Shorter), -20.);
+		assert_eq!(hue_difference(350., 10., HueDirection::Longer), -340.);
+		assert_eq!(hue_difference(10., 350., HueDirection::Increasing), 340.);
+		assert_eq!(hue_difference(350., 10., HueDirection::Decreasing), -340.);
+	}
+
+	#[test]
+	fn midpoint_moves_the_even_blend() {
+		let mut gradient = GradientStops::new(vec![(0., Color::BLACK), (1., Color::WHITE)]);
+		gradient.set_midpoint(0, 0.25);
+		assert_color_near(gradient.evaluate(0.25), GradientInterpolation::Srgb.blend(Color::BLACK, Color::WHITE, 0.5, HueDirection::Shorter));
+		assert!(!gradient.is_plain_srgb());
+
+		// Approximating the midpoint with extra stops matches the gradient everywhere in between
+		let approximation = gradient.to_plain_srgb();
+		assert!(approximation.is_plain_srgb());
+		assert_eq!(approximation.len(), SAMPLES_PER_SPAN + 1);
+		for &(position, color) in &approximation {
+			assert_color_near(color, gradient.evaluate(position));
+		}
+	}
+
+	#[test]
+	fn midpoints_follow_their_stops() {
+		let gray = Color::from_rgbaf32_unchecked(0.5, 0.5, 0.5, 1.);
+		let mut gradient = GradientStops::new(vec![(0., Color::BLACK), (0.5, gray), (1., Color::WHITE)]);
+		gradient.set_midpoint(0, 0.2);
+		gradient.set_midpoint(1, 0.7);
+
+		assert_eq!(gradient.reversed().midpoints, vec![1. - 0.7, 1. - 0.2]);
+
+		// Dragging the middle stop past the last one carries its span's midpoint along with it
+		gradient.stops[1].0 = 1.5;
+		gradient.sort();
+		assert_eq!(gradient.midpoints, vec![0.2, DEFAULT_GRADIENT_MIDPOINT]);
+		gradient.stops[2].0 = 0.5;
+		gradient.sort();
+
+		// Splitting the first span keeps its midpoint at the same position
+		gradient.insert(1, (0.25, gray));
+		assert_eq!(gradient.len(), 4);
+		assert!((gradient.midpoint(0) - 0.4).abs() < 1e-9);
+		assert_eq!(gradient.midpoint(1), DEFAULT_GRADIENT_MIDPOINT);
+
+		gradient.remove(1);
+		assert_eq!(gradient.midpoints.len(), 2);
+		assert_eq!(gradient.midpoint(0), DEFAULT_GRADIENT_MIDPOINT);
+	}
+
+	#[test]
+	fn deserializes_plain_stop_lists() {
+		let stops: GradientStops = serde_json::from_str(r#"[[0.0, {"red": 0.0, "green": 0.0, "blue": 0.0, "alpha": 1.0}], [1.0, {"red": 1.0, "green": 1.0, "blue": 1.0, "alpha": 1.0}]]"#).unwrap();
+		assert_eq!(stops, GradientStops::default());
+
+		let round_trip: GradientStops = serde_json::from_str(&serde_json::to_string(&red_to_blue(GradientInterpolation::Oklch)).unwrap()).unwrap();
+		assert_eq!(round_trip, red_to_blue(GradientInterpolation::Oklch));
+	}
+}
@@ -3,6 +3,7 @@ extern crate log;
 
 pub mod gradient;
 pub mod math;
+pub mod pattern;
 pub mod subpath;
 pub mod vector;
 
@@ -0,0 +1,123 @@
+use crate::vector::Vector;
+use core_types::bounds::{BoundingBox, RenderBoundingBox};
+use core_types::table::Table;
+use dyn_any::DynAny;
+use glam::{DAffine2, DVec2};
+use raster_types::{CPU, Raster};
+
+/// A graphical element making up part of the tile of a [`PatternFill`].
+///
+/// Since a fill can't refer to the graphic types which themselves contain fills, the tile's content is flattened from its nested groups into their vector and raster leaves, kept in drawing order.
+#[derive(Debug, Clone, PartialEq, Hash, serde::Serialize, serde::Deserialize, DynAny)]
+pub enum PatternElement {
+	Vector(Vector<()>),
+	Raster(Raster<CPU>),
+}
+
+impl BoundingBox for PatternElement {
+	fn bounding_box(&self, transform: DAffine2, include_stroke: bool) -> RenderBoundingBox {
+		match self {
+			Self::Vector(vector) => BoundingBox::bounding_box(vector, transform, include_stroke),
+			Self::Raster(raster) => raster.bounding_box(transform, include_stroke),
+		}
+	}
+}
+
+/// A fill which repeats a tile of graphical content across the shape.
+#[derive(Debug, Clone, PartialEq, serde::Serialize, serde::Deserialize, DynAny, specta::Type)]
+pub struct PatternFill {
+	/// The graphics drawn in each tile, positioned in the pattern's space.
+	#[specta(skip)]
+	pub content: Table<PatternElement>,
+	/// The placement of the pattern's space within the local space of the filled shape.
+	pub transform: DAffine2,
+	/// The gap between neighboring tiles, added to the size of the content's bounding box.
+	pub spacing: DVec2,
+	/// The shift of every tile from where its content lies.
+	pub offset: DVec2,
+}
+
+impl Default for PatternFill {
+	fn default() -> Self {
+		Self {
+			content: Table::new(),
+			transform: DAffine2::IDENTITY,
+			spacing: DVec2::ZERO,
+			offset: DVec2::ZERO,
+		}
+	}
+}
+
+impl std::hash::Hash for PatternFill {
+	fn hash<H: std::hash::Hasher>(&self, state: &mut H) {
+		self.content.hash(state);
+		self.transform.to_cols_array().iter().for_each(|x| x.to_bits().hash(state));
+		self.spacing.to_array().iter().for_each(|x| x.to_bits().hash(state));
+		self.offset.to_array().iter().for_each(|x| x.to_bits().hash(state));
+	}
+}
+
+impl PatternFill {
+	/// The rectangle covered by the first tile in the pattern's space, which the other tiles repeat in a grid of its size.
+	///
+	/// Returns `None` if the content has no finite area to repeat.
+	pub fn tile(&self) -> Option<[DVec2; 2]> {
+		let RenderBoundingBox::Rectangle([min, max]) = self.content.bounding_box(DAffine2::IDENTITY, true) else {
+			return None;
+		};
+
+		let size = max - min + self.spacing;
+		if size.x <= 0. || size.y <= 0. || !size.is_finite() {
+			return None;
+		}
+
+		let min = min + self.offset;
+		Some([min, min + size])
+	}
+}
+
+/// How an image is sized to fit the bounds of the shape it fills.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash, serde::Serialize, serde::Deserialize, DynAny, specta::Type, node_macro::ChoiceType)]
+#[widget(Radio)]
+pub enum ImageFit {
+	/// Scales the image to cover the whole shape, cropping whatever extends beyond it.
+	#[default]
+	Cover,
+	/// Scales the image to fit entirely inside the shape, leaving empty space beside it.
+	Contain,
+	/// Stretches the image to the shape's bounds, distorting its aspect ratio.
+	Stretch,
+}
+
+/// A fill which places a single image within the bounds of the shape.
+#[derive(Debug, Clone, Default, PartialEq, Hash, serde::Serialize, serde::Deserialize, DynAny, specta::Type)]
+pub struct ImageFill {
+	#[specta(skip)]
+	pub image: Raster<CPU>,
+	pub fit: ImageFit,
+}
+
+impl ImageFill {
+	/// The transform which maps the unit square of the image onto its place within the given bounds.
+	pub fn image_transform(&self, [min, max]: [DVec2; 2]) -> DAffine2 {
+		let bounds_size = max - min;
+		let image_size = DVec2::new(self.image.width as f64, self.image.height as f64);
+		if image_size.x <= 0. || image_size.y <= 0. {
+			return DAffine2::from_scale_angle_translation(bounds_size, 0., min);
+		}
+
+		let size = match self.fit {
+			ImageFit::Cover => image_size * (bounds_size / image_size).max_element(),
+			ImageFit::Contain => image_size * (bounds_size / image_size).min_element(),
+			ImageFit::Stretch => bounds_size,
+		};
+		let center = (min + max) / 2.;
+
+		DAffine2::from_scale_angle_translation(size, 0., center - size / 2.)
+	}
+
+	/// Whether the image covers the whole shape with only opaque pixels.
+	pub fn is_opaque(&self) -> bool {
+		self.fit != ImageFit::Contain && !self.image.data.is_empty() && self.image.data.iter().all(|pixel| pixel.is_opaque())
+	}
+}
@@ -6,3 +6,4 @@ pub mod offset_subpath;
 pub mod poisson_disk;
 pub mod spline;
 pub mod util;
+pub mod width_profile;
@@ -0,0 +1,399 @@
+use super::util::pathseg_tangent;
+use crate::vector::misc::{dvec2_to_point, point_to_dvec2};
+use crate::vector::style::{Stroke, StrokeCap, StrokeJoin, WidthProfile};
+use glam::{DAffine2, DVec2};
+use kurbo::{Affine, Arc, BezPath, ParamCurve, ParamCurveArclen, ParamCurveNearest, PathEl, PathSeg, Vec2};
+
+/// The approximate distance between the samples taken along a path to trace the edges of its outline.
+const SAMPLE_SPACING: f64 = 2.;
+/// The fewest samples taken along any one segment of a path.
+const MIN_SEGMENT_SAMPLES: usize = 4;
+/// The most samples taken along any one segment of a path.
+const MAX_SEGMENT_SAMPLES: usize = 128;
+/// The accuracy of the arc length measurements used to space out the samples.
+const ARC_LENGTH_ACCURACY: f64 = 1e-3;
+/// The largest angle (in radians) between the directions on either side of an anchor for it to be traced smoothly rather than given a join.
+const SMOOTH_ANGLE_TOLERANCE: f64 = 1e-2;
+/// Segments shorter than this have no direction to offset them by, so they're skipped.
+const MIN_SEGMENT_LENGTH: f64 = 1e-9;
+/// The length of the handles which make a quarter of a circle or ellipse from a cubic curve.
+const QUARTER_ARC_HANDLE_FACTOR: f64 = 0.551784777779014;
+
+/// Outlines the stroke of each of the subpaths with its width profile, ready to be filled with the stroke's color.
+///
+/// The subpaths and the resulting outline are in the same space, while the stroke's weight applies in the space of its transform.
+pub fn stroke_width_profile_outline(bezpaths: impl IntoIterator<Item = BezPath>, stroke: &Stroke) -> BezPath {
+	let transform = if stroke.transform.matrix2.determinant() != 0. { stroke.transform } else { DAffine2::IDENTITY };
+
+	let mut outline = BezPath::new();
+	for mut bezpath in bezpaths {
+		bezpath.apply_affine(Affine::new(transform.to_cols_array()));
+		outline.extend(width_profile_outline(&bezpath, &stroke.width_profile, stroke.weight, stroke.cap, stroke.join, stroke.join_miter_limit));
+	}
+	outline.apply_affine(Affine::new(transform.inverse().to_cols_array()));
+
+	outline
+}
+
+/// Outlines a stroke along the subpath which varies in width according to the profile, where each width point's widths are multiples of half the weight.
+///
+/// An open subpath is outlined by a single shape running along its left edge, around its end cap, back along its right edge, and around its start cap.
+/// A closed subpath is outlined by its left edge and its reversed right edge, which together fill the ring between them.
+pub fn width_profile_outline(bezpath: &BezPath, profile: &WidthProfile, weight: f64, cap: StrokeCap, join: StrokeJoin, miter_limit: f64) -> BezPath {
+	let mut outline = BezPath::new();
+
+	let half_weight = weight / 2.;
+	let segments = bezpath.segments().filter(|segment| segment.arclen(ARC_LENGTH_ACCURACY) > MIN_SEGMENT_LENGTH).collect::<Vec<_>>();
+	if segments.is_empty() || half_weight <= 0. || !half_weight.is_finite() {
+		return outline;
+	}
+	let closed = bezpath.elements().last() == Some(&PathEl::ClosePath);
+
+	// The right edge is traced as the left edge of the reversed subpath, so the outline continues around in one direction
+	let reversed_segments = segments.iter().rev().map(PathSeg::reverse).collect::<Vec<_>>();
+	let left_width = |position: f64| profile.evaluate(position).0 * half_weight;
+	let right_width = |position: f64| profile.evaluate(1. - position).1 * half_weight;
+
+	trace_left_edge(&mut outline, &segments, closed, left_width, join, miter_limit);
+	let mut right_edge = BezPath::new();
+	trace_left_edge(&mut right_edge, &reversed_segments, closed, right_width, join, miter_limit);
+
+	if closed {
+		outline.extend(right_edge);
+		return outline;
+	}
+
+	let (Some(left_start), Some(left_end), Some(right_start), Some(right_end)) = (start_point(&outline), end_point(&outline), start_point(&right_edge), end_point(&right_edge)) else {
+		return outline;
+	};
+	let (first_segment, last_segment) = (segments[0], segments[segments.len() - 1]);
+
+	let end_center = point_to_dvec2(last_segment.end());
+	add_cap(&mut outline, cap, end_center, direction(last_segment, 1.), left_end, right_start);
+	outline.extend(right_edge.elements().iter().skip(1).copied());
+
+	let start_center = point_to_dvec2(first_segment.start());
+	add_cap(&mut outline, cap, start_center, -direction(first_segment, 0.), right_end, left_start);
+	outline.close_path();
+
+	outline
+}
+
+/// Finds the point at a fraction of the subpath's total length, along with the unit normal pointing to its left there, which is where a width point at that position is drawn.
+pub fn width_profile_frame(bezpath: &BezPath, position: f64) -> Option<(DVec2, DVec2)> {
+	let segments = bezpath.segments().filter(|segment| segment.arclen(ARC_LENGTH_ACCURACY) > MIN_SEGMENT_LENGTH).collect::<Vec<_>>();
+	let lengths = segments.iter().map(|segment| segment.arclen(ARC_LENGTH_ACCURACY)).collect::<Vec<_>>();
+	let total_length = lengths.iter().sum::<f64>();
+	if total_length <= 0. {
+		return None;
+	}
+
+	let mut remaining = position.clamp(0., 1.) * total_length;
+	for (index, (&segment, &length)) in segments.iter().zip(lengths.iter()).enumerate() {
+		if remaining <= length || index + 1 == segments.len() {
+			let t = segment.inv_arclen(remaining.min(length), ARC_LENGTH_ACCURACY);
+			return Some((point_to_dvec2(segment.eval(t)), left_normal(segment, t)));
+		}
+		remaining -= length;
+	}
+
+	None
+}
+
+/// Finds the fraction of the subpath's total length at which it passes nearest to the given point, and the distance from there to the point.
+pub fn width_profile_nearest_position(bezpath: &BezPath, point: DVec2) -> Option<(f64, f64)> {
+	let segments = bezpath.segments().filter(|segment| segment.arclen(ARC_LENGTH_ACCURACY) > MIN_SEGMENT_LENGTH).collect::<Vec<_>>();
+	let total_length = segments.iter().map(|segment| segment.arclen(ARC_LENGTH_ACCURACY)).sum::<f64>();
+	if total_length <= 0. {
+		return None;
+	}
+
+	let mut nearest: Option<(f64, f64)> = None;
+	let mut traveled = 0.;
+	for segment in segments {
+		let found = segment.nearest(dvec2_to_point(point), ARC_LENGTH_ACCURACY);
+		let distance = found.distance_sq.sqrt();
+		if nearest.is_none_or(|(_, nearest_distance)| distance < nearest_distance) {
+			let position = (traveled + segment.subsegment(0. ..found.t).arclen(ARC_LENGTH_ACCURACY)) / total_length;
+			nearest = Some((position, distance));
+		}
+		traveled += segment.arclen(ARC_LENGTH_ACCURACY);
+	}
+
+	nearest
+}
+
+/// Traces the edge of the outline along the left side of the segments, the given width away from them at each fraction of their total length.
+///
+/// Runs of segments which meet smoothly are traced together as one smooth curve through samples along them, and the runs are met with joins at corners.
+fn trace_left_edge(edge: &mut BezPath, segments: &[PathSeg], closed: bool, width: impl Fn(f64) -> f64, join: StrokeJoin, miter_limit: f64) {
+	let lengths = segments.iter().map(|segment| segment.arclen(ARC_LENGTH_ACCURACY)).collect::<Vec<_>>();
+	let total_length = lengths.iter().sum::<f64>();
+	if total_length <= 0. {
+		return;
+	}
+
+	let mut run = Vec::new();
+	let mut traveled = 0.;
+	for (index, (&segment, &length)) in segments.iter().zip(lengths.iter()).enumerate() {
+		let samples = ((length / SAMPLE_SPACING).ceil() as usize).clamp(MIN_SEGMENT_SAMPLES, MAX_SEGMENT_SAMPLES);
+		// Within a run, the first sample of a segment repeats the last sample of the one before it
+		let first_sample = if run.is_empty() { 0 } else { 1 };
+		for sample in first_sample..=samples {
+			let distance = length * sample as f64 / samples as f64;
+			let t = segment.inv_arclen(distance, ARC_LENGTH_ACCURACY);
+			let point = point_to_dvec2(segment.eval(t));
+			run.push(point + left_normal(segment, t) * width((traveled + distance) / total_length));
+		}
+		traveled += length;
+
+		let is_last = index + 1 == segments.len();
+		let next_segment = if !is_last {
+			segments[index + 1]
+		} else if closed {
+			segments[0]
+		} else {
+			break;
+		};
+
+		let in_direction = direction(segment, 1.);
+		let out_direction = direction(next_segment, 0.);
+		let turn = in_direction.angle_to(out_direction);
+		if !is_last && turn.abs() < SMOOTH_ANGLE_TOLERANCE {
+			continue;
+		}
+
+		// A corner ends the run, and is met with a join on the outer side of the turn or by pivoting around the anchor on the inner side
+		trace_run(edge, &run);
+		run.clear();
+
+		let pivot = point_to_dvec2(segment.end());
+		let next_width = if is_last { width(0.) } else { width(traveled / total_length) };
+		let next_start = pivot + left_normal(next_segment, 0.) * next_width;
+		let Some(previous_end) = end_point(edge) else { continue };
+
+		if turn.abs() < SMOOTH_ANGLE_TOLERANCE {
+			edge.line_to(dvec2_to_point(next_start));
+		} else if turn < 0. {
+			edge.line_to(dvec2_to_point(pivot));
+			edge.line_to(dvec2_to_point(next_start));
+		} else {
+			add_join(edge, join, miter_limit, pivot, previous_end, in_direction, next_start, out_direction, turn);
+		}
+	}
+
+	trace_run(edge, &run);
+	if closed {
+		edge.close_path();
+	}
+}
+
+/// Adds a smooth curve through the points of a run, as a Catmull-Rom spline converted into cubic segments.
+fn trace_run(edge: &mut BezPath, run: &[DVec2]) {
+	let Some(&first) = run.first() else { return };
+
+	match end_point(edge) {
+		None => edge.move_to(dvec2_to_point(first)),
+		Some(end) if end.distance_squared(first) > MIN_SEGMENT_LENGTH * MIN_SEGMENT_LENGTH => edge.line_to(dvec2_to_point(first)),
+		Some(_) => {}
+	}
+
+	let last_index = run.len() - 1;
+	for index in 0..last_index {
+		let before = run[index.saturating_sub(1)];
+		let start = run[index];
+		let end = run[index + 1];
+		let after = run[(index + 2).min(last_index)];
+
+		let handle_start = start + (end - before) / 6.;
+		let handle_end = end - (after - start) / 6.;
+		edge.curve_to(dvec2_to_point(handle_start), dvec2_to_point(handle_end), dvec2_to_point(end));
+	}
+}
+
+/// Adds the join on the outer side of a corner, from the end of the edge before it to the start of the edge after it.
+#[allow(clippy::too_many_arguments)]
+fn add_join(edge: &mut BezPath, join: StrokeJoin, miter_limit: f64, pivot: DVec2, from: DVec2, in_direction: DVec2, to: DVec2, out_direction: DVec2, turn: f64) {
+	match join {
+		StrokeJoin::Bevel => edge.line_to(dvec2_to_point(to)),
+		StrokeJoin::Miter => {
+			// Extend both edges along their directions until they meet, unless that point lies beyond the miter limit
+			let denominator = in_direction.perp_dot(out_direction);
+			let distance_along_in = (to - from).perp_dot(out_direction) / denominator;
+			let miter_point = from + in_direction * distance_along_in;
+			let width = (from - pivot).length().max((to - pivot).length());
+
+			if denominator.abs() > f64::EPSILON && distance_along_in >= 0. && miter_point.distance(pivot) <= miter_limit.max(1.) * width {
+				edge.line_to(dvec2_to_point(miter_point));
+			}
+			edge.line_to(dvec2_to_point(to));
+		}
+		StrokeJoin::Round => {
+			let from_pivot = from - pivot;
+			let arc = Arc {
+				center: dvec2_to_point(pivot),
+				radii: Vec2::new(from_pivot.length(), from_pivot.length()),
+				start_angle: from_pivot.to_angle(),
+				sweep_angle: turn,
+				x_rotation: 0.,
+			};
+			arc.to_cubic_beziers(0.1, |handle_start, handle_end, end| edge.curve_to(handle_start, handle_end, end));
+			edge.line_to(dvec2_to_point(to));
+		}
+	}
+}
+
+/// Adds the cap at an end of an open subpath, going around the center from one edge to the other while facing in the given direction.
+fn add_cap(outline: &mut BezPath, cap: StrokeCap, center: DVec2, direction: DVec2, from: DVec2, to: DVec2) {
+	let (from_width, to_width) = (from.distance(center), to.distance(center));
+
+	match cap {
+		StrokeCap::Butt => {}
+		StrokeCap::Square => {
+			outline.line_to(dvec2_to_point(from + direction * from_width));
+			outline.line_to(dvec2_to_point(to + direction * to_width));
+		}
+		StrokeCap::Round => {
+			// Two quarters of an ellipse meet at the tip, which reaches out by the average of the widths on either side
+			let reach = (from_width + to_width) / 2.;
+			let tip = center + direction * reach;
+			let handle = reach * QUARTER_ARC_HANDLE_FACTOR;
+			outline.curve_to(
+				dvec2_to_point(from + direction * handle),
+				dvec2_to_point(tip + (from - center) * QUARTER_ARC_HANDLE_FACTOR),
+				dvec2_to_point(tip),
+			);
+			outline.curve_to(
+				dvec2_to_point(tip + (to - center) * QUARTER_ARC_HANDLE_FACTOR),
+				dvec2_to_point(to + direction * handle),
+				dvec2_to_point(to),
+			);
+		}
+	}
+	outline.line_to(dvec2_to_point(to));
+}
+
+/// The unit direction of travel along the segment at the given parametric position.
+fn direction(segment: PathSeg, t: f64) -> DVec2 {
+	if let Some(direction) = pathseg_tangent(segment, t).try_normalize() {
+		return direction;
+	}
+
+	// Handles lying on their anchors give no direction at the ends, so look slightly further inward along the segment
+	let nudged_t = if t < 0.5 { t + 1e-3 } else { t - 1e-3 };
+	pathseg_tangent(segment, nudged_t)
+		.try_normalize()
+		.unwrap_or_else(|| (point_to_dvec2(segment.end()) - point_to_dvec2(segment.start())).normalize_or_zero())
+}
+
+/// The unit normal pointing to the left of the direction of travel along the segment.
+fn left_normal(segment: PathSeg, t: f64) -> DVec2 {
+	-direction(segment, t).perp()
+}
+
+fn start_point(bezpath: &BezPath) -> Option<DVec2> {
+	match bezpath.elements().first()? {
+		PathEl::MoveTo(point) => Some(point_to_dvec2(*point)),
+		_ => None,
+	}
+}
+
+fn end_point(bezpath: &BezPath) -> Option<DVec2> {
+	let point = match bezpath.elements().last()? {
+		PathEl::MoveTo(point) | PathEl::LineTo(point) | PathEl::QuadTo(_, point) | PathEl::CurveTo(_, _, point) => *point,
+		PathEl::ClosePath => return None,
+	};
+	Some(point_to_dvec2(point))
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use crate::vector::style::WidthPoint;
+	use kurbo::Shape;
+
+	fn bounds(bezpath: &BezPath) -> [DVec2; 2] {
+		let rect = bezpath.bounding_box();
+		[DVec2::new(rect.x0, rect.y0), DVec2::new(rect.x1, rect.y1)]
+	}
+
+	fn assert_bounds(bezpath: &BezPath, expected: [DVec2; 2]) {
+		let [min, max] = bounds(bezpath);
+		assert!(
+			min.abs_diff_eq(expected[0], 1e-6) && max.abs_diff_eq(expected[1], 1e-6),
+			"Expected bounds {expected:?} but found {:?}",
+			[min, max]
+		);
+	}
+
+	fn line() -> BezPath {
+		let mut bezpath = BezPath::new();
+		bezpath.move_to((0., 0.));
+		bezpath.line_to((100., 0.));
+		bezpath
+	}
+
+	#[test]
+	fn evaluate_profile() {
+		assert_eq!(WidthProfile::default().evaluate(0.5), (1., 1.));
+
+		let profile = WidthProfile::new([WidthPoint::new(0.8, 3., 1.), WidthPoint::new(0.2, 1., 2.)]);
+		assert_eq!(profile.0[0].position, 0.2);
+		assert_eq!(profile.evaluate(0.), (1., 2.));
+		let (left, right) = profile.evaluate(0.5);
+		assert!((left - 2.).abs() < 1e-9 && (right - 1.5).abs() < 1e-9);
+		assert_eq!(profile.evaluate(1.), (3., 1.));
+		assert_eq!(profile.max_width(), 3.);
+	}
+
+	#[test]
+	fn tapered_line() {
+		let profile = WidthProfile::new([WidthPoint::new(0., 0., 0.), WidthPoint::new(1., 3., 1.)]);
+		let outline = width_profile_outline(&line(), &profile, 2., StrokeCap::Butt, StrokeJoin::Miter, 4.);
+
+		// The left side of a path heading to the right is upward, toward negative y
+		assert_bounds(&outline, [DVec2::new(0., -3.), DVec2::new(100., 1.)]);
+		assert!((outline.area().abs() - 200.).abs() < 1e-3, "Area {} should be half of 100 by 4", outline.area());
+	}
+
+	#[test]
+	fn frame_and_nearest_position() {
+		let (point, normal) = width_profile_frame(&line(), 0.25).unwrap();
+		assert!(point.abs_diff_eq(DVec2::new(25., 0.), 1e-6) && normal.abs_diff_eq(DVec2::new(0., -1.), 1e-9));
+
+		let (position, distance) = width_profile_nearest_position(&line(), DVec2::new(75., 10.)).unwrap();
+		assert!((position - 0.75).abs() < 1e-6 && (distance - 10.).abs() < 1e-6);
+	}
+
+	#[test]
+	fn caps() {
+		let profile = WidthProfile::new([WidthPoint::new(0., 1., 1.)]);
+
+		let outline = width_profile_outline(&line(), &profile, 4., StrokeCap::Square, StrokeJoin::Miter, 4.);
+		assert_bounds(&outline, [DVec2::new(-2., -2.), DVec2::new(102., 2.)]);
+
+		let outline = width_profile_outline(&line(), &profile, 4., StrokeCap::Round, StrokeJoin::Miter, 4.);
+		assert_bounds(&outline, [DVec2::new(-2., -2.), DVec2::new(102., 2.)]);
+		assert!((outline.area().abs() - (400. + std::f64::consts::PI * 4.)).abs() < 0.1);
+	}
+
+	#[test]
+	fn closed_square_joins() {
+		let mut square = BezPath::new();
+		square.move_to((0., 0.));
+		square.line_to((100., 0.));
+		square.line_to((100., 100.));
+		square.line_to((0., 100.));
+		square.close_path();
+		let profile = WidthProfile::new([WidthPoint::new(0., 1., 1.)]);
+
+		let outline = width_profile_outline(&square, &profile, 2., StrokeCap::Butt, StrokeJoin::Miter, 4.);
+		assert_bounds(&outline, [DVec2::new(-1., -1.), DVec2::new(101., 101.)]);
+		assert_eq!(outline.elements().iter().filter(|element| **element == PathEl::ClosePath).count(), 2);
+
+		let outline = width_profile_outline(&square, &profile, 2., StrokeCap::Butt, StrokeJoin::Bevel, 4.);
+		let [min, max] = bounds(&outline);
+		assert!(min.abs_diff_eq(DVec2::splat(-1.), 1e-6) && max.abs_diff_eq(DVec2::splat(101.), 1e-6));
+		assert!(!outline.contains(kurbo::Point::new(-0.9, -0.9)), "A beveled corner should be cut off");
+	}
+}
@@ -1,16 +1,18 @@
 //! Contains stylistic options for SVG elements.
 
 pub use crate::gradient::*;
+pub use crate::pattern::*;
 use core_types::Color;
+use core_types::blending::BlendMode;
 use core_types::table::Table;
 use dyn_any::DynAny;
 use glam::DAffine2;
 
 /// Describes the fill of a layer.
 ///
-/// Can be None, a solid [Color], or a linear/radial [Gradient].
+/// Can be None, a solid [Color], a [Gradient], a repeating [PatternFill], or an [ImageFill].
 ///
-/// In the future we'll probably also add a pattern fill. This will probably be named "Paint" in the future.
+/// This will probably be named "Paint" in the future.
 #[repr(C)]
 #[derive(Default, Debug, Clone, PartialEq, serde::Serialize, serde::Deserialize, DynAny, Hash, specta::Type)]
 pub enum Fill {
@@ -18,6 +20,8 @@ pub enum Fill {
 	None,
 	Solid(Color),
 	Gradient(Gradient),
+	Pattern(PatternFill),
+	Image(ImageFill),
 }
 
 impl std::fmt::Display for Fill {
@@ -26,6 +30,8 @@ impl std::fmt::Display for Fill {
 			Self::None => write!(f, "None"),
 			Self::Solid(color) => write!(f, "#{} (Alpha: {}%)", color.to_rgb_hex_srgb(), color.a() * 100.),
 			Self::Gradient(gradient) => write!(f, "{gradient}"),
+			Self::Pattern(pattern) => write!(f, "Pattern ({} element{})", pattern.content.len(), if pattern.content.len() == 1 { "" } else { "s" }),
+			Self::Image(image) => write!(f, "Image ({}x{}, {:?})", image.image.width, image.image.height, image.fit),
 		}
 	}
 }
@@ -44,13 +50,13 @@ impl Fill {
 		}
 	}
 
-	/// Evaluate the color at some point on the fill. Doesn't currently work for Gradient.
+	/// Evaluate the color at some point on the fill. Doesn't currently work for Gradient, Pattern, or Image.
 	pub fn color(&self) -> Color {
 		match self {
-			Self::None => Color::BLACK,
+			Self::None | Self::Pattern(_) | Self::Image(_) => Color::BLACK,
 			Self::Solid(color) => *color,
 			// TODO: Should correctly sample the gradient the equation here: https://svgwg.org/svg2-draft/pservers.html#Gradients
-			Self::Gradient(Gradient { stops, .. }) => stops.0[0].1,
+			Self::Gradient(Gradient { stops, .. }) => stops[0].1,
 		}
 	}
 
@@ -63,17 +69,25 @@ impl Fill {
 			(Self::Solid(a), Self::Solid(b)) => Self::Solid(a.lerp(b, time as f32)),
 			(Self::Solid(a), Self::Gradient(b)) => {
 				let mut solid_to_gradient = b.clone();
-				solid_to_gradient.stops.0.iter_mut().for_each(|(_, color)| *color = *a);
+				solid_to_gradient.stops.iter_mut().for_each(|(_, color)| *color = *a);
 				let a = &solid_to_gradient;
 				Self::Gradient(a.lerp(b, time))
 			}
 			(Self::Gradient(a), Self::Solid(b)) => {
 				let mut gradient_to_solid = a.clone();
-				gradient_to_solid.stops.0.iter_mut().for_each(|(_, color)| *color = *b);
+				gradient_to_solid.stops.iter_mut().for_each(|(_, color)| *color = *b);
 				let b = &gradient_to_solid;
 				Self::Gradient(a.lerp(b, time))
 			}
 			(Self::Gradient(a), Self::Gradient(b)) => Self::Gradient(a.lerp(b, time)),
+			// Patterns and images can't be blended, so they switch over halfway through
+			(Self::Pattern(_) | Self::Image(_), _) | (_, Self::Pattern(_) | Self::Image(_)) => {
+				if time < 0.5 {
+					self.clone()
+				} else {
+					other.clone()
+				}
+			}
 			_ => Self::None,
 		}
 	}
@@ -86,6 +100,22 @@ impl Fill {
 		}
 	}
 
+	/// Extract a pattern from the fill
+	pub fn as_pattern(&self) -> Option<&PatternFill> {
+		match self {
+			Self::Pattern(pattern) => Some(pattern),
+			_ => None,
+		}
+	}
+
+	/// Extract an image from the fill
+	pub fn as_image(&self) -> Option<&ImageFill> {
+		match self {
+			Self::Image(image) => Some(image),
+			_ => None,
+		}
+	}
+
 	/// Extract a solid color from the fill
 	pub fn as_solid(&self) -> Option<Color> {
 		match self {
@@ -99,6 +129,9 @@ impl Fill {
 		match self {
 			Fill::Solid(color) => color.is_opaque(),
 			Fill::Gradient(gradient) => gradient.stops.iter().all(|(_, color)| color.is_opaque()),
+			// The gaps between tiles are see-through
+			Fill::Pattern(_) => false,
+			Fill::Image(image) => image.is_opaque(),
 			Fill::None => true,
 		}
 	}
@@ -184,12 +217,16 @@ impl FillChoice {
 	}
 }
 
-impl From<Fill> for FillChoice {
-	fn from(fill: Fill) -> Self {
+impl TryFrom<Fill> for FillChoice {
+	type Error = Fill;
+
+	/// Fails for patterns and images, which the color picker has no way to show, so they aren't mistaken for no fill and replaced by editing it.
+	fn try_from(fill: Fill) -> Result<Self, Self::Error> {
 		match fill {
-			Fill::None => FillChoice::None,
-			Fill::Solid(color) => FillChoice::Solid(color),
-			Fill::Gradient(gradient) => FillChoice::Gradient(gradient.stops),
+			Fill::None => Ok(FillChoice::None),
+			Fill::Solid(color) => Ok(FillChoice::Solid(color)),
+			Fill::Gradient(gradient) => Ok(FillChoice::Gradient(gradient.stops)),
+			Fill::Pattern(_) | Fill::Image(_) => Err(fill),
 		}
 	}
 }
@@ -275,6 +312,123 @@ impl PaintOrder {
 	}
 }
 
+/// A point along a stroke's path which sets the stroke's width on either side of the path there.
+#[derive(Debug, Clone, Copy, PartialEq, serde::Serialize, serde::Deserialize, DynAny, specta::Type)]
+pub struct WidthPoint {
+	/// The distance along the path, as a fraction of its length from 0 at its start to 1 at its end.
+	pub position: f64,
+	/// The width on the left side of the path (facing in its direction), as a multiple of half the stroke weight.
+	pub left: f64,
+	/// The width on the right side of the path (facing in its direction), as a multiple of half the stroke weight.
+	pub right: f64,
+}
+
+impl WidthPoint {
+	pub const fn new(position: f64, left: f64, right: f64) -> Self {
+		Self { position, left, right }
+	}
+}
+
+/// The varying width of a stroke along each of its subpaths, given by width points sorted by their position.
+///
+/// Between two width points, the width eases smoothly from one to the other. Before the first and after the last, it stays the same as that point.
+/// An empty profile leaves the stroke at its uniform weight.
+#[derive(Debug, Clone, Default, PartialEq, serde::Serialize, serde::Deserialize, DynAny, specta::Type)]
+pub struct WidthProfile(pub Vec<WidthPoint>);
+
+impl std::hash::Hash for WidthProfile {
+	fn hash<H: std::hash::Hasher>(&self, state: &mut H) {
+		self.0.len().hash(state);
+		for point in &self.0 {
+			point.position.to_bits().hash(state);
+			point.left.to_bits().hash(state);
+			point.right.to_bits().hash(state);
+		}
+	}
+}
+
+impl std::fmt::Display for WidthProfile {
+	fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+		let points = self.0.iter().map(|point| format!("({}, {}, {})", point.position, point.left, point.right)).collect::<Vec<_>>();
+		write!(f, "{}", points.join(", "))
+	}
+}
+
+impl WidthProfile {
+	/// Creates a profile from width points in any order, keeping their positions within the path.
+	pub fn new(points: impl IntoIterator<Item = WidthPoint>) -> Self {
+		let mut profile = Self(Vec::new());
+		for point in points {
+			profile.insert(point);
+		}
+		profile
+	}
+
+	pub fn is_empty(&self) -> bool {
+		self.0.is_empty()
+	}
+
+	pub fn iter(&self) -> std::slice::Iter<'_, WidthPoint> {
+		self.0.iter()
+	}
+
+	/// Adds a width point in order of position, returning its index.
+	pub fn insert(&mut self, mut point: WidthPoint) -> usize {
+		point.position = point.position.clamp(0., 1.);
+		point.left = point.left.max(0.);
+		point.right = point.right.max(0.);
+
+		let index = self.0.partition_point(|existing| existing.position <= point.position);
+		self.0.insert(index, point);
+		index
+	}
+
+	/// The multiples of half the stroke weight on the left and right sides of the path at the given fraction of its length.
+	pub fn evaluate(&self, position: f64) -> (f64, f64) {
+		let (Some(first), Some(last)) = (self.0.first(), self.0.last()) else { return (1., 1.) };
+		if position <= first.position {
+			return (first.left, first.right);
+		}
+		if position >= last.position {
+			return (last.left, last.right);
+		}
+
+		let index = self.0.partition_point(|point| point.position <= position);
+		let (start, end) = (self.0[index - 1], self.0[index]);
+		let span = end.position - start.position;
+		if span <= 0. {
+			return (end.left, end.right);
+		}
+
+		// Ease in and out of each width point so the stroke's edges have no kinks there
+		let time = (position - start.position) / span;
+		let time = time * time * (3. - 2. * time);
+		(start.left + (end.left - start.left) * time, start.right + (end.right - start.right) * time)
+	}
+
+	/// The largest multiple of half the stroke weight on either side of the path.
+	pub fn max_width(&self) -> f64 {
+		if self.is_empty() {
+			return 1.;
+		}
+		self.0.iter().map(|point| point.left.max(point.right)).fold(0., f64::max)
+	}
+
+	pub fn lerp(&self, other: &Self, time: f64) -> Self {
+		if self.0.len() != other.0.len() {
+			return if time < 0.5 { self.clone() } else { other.clone() };
+		}
+
+		let lerp = |a: f64, b: f64| a + (b - a) * time;
+		Self::new(
+			self.0
+				.iter()
+				.zip(other.0.iter())
+				.map(|(a, b)| WidthPoint::new(lerp(a.position, b.position), lerp(a.left, b.left), lerp(a.right, b.right))),
+		)
+	}
+}
+
 fn daffine2_identity() -> DAffine2 {
 	DAffine2::IDENTITY
 }
@@ -303,6 +457,9 @@ pub struct Stroke {
 	pub non_scaling: bool,
 	#[serde(default)]
 	pub paint_order: PaintOrder,
+	/// The varying width of the stroke along its path, which draws it as an outline with a centered alignment and no dashes.
+	#[serde(default)]
+	pub width_profile: WidthProfile,
 }
 
 impl std::hash::Hash for Stroke {
@@ -321,6 +478,7 @@ impl std::hash::Hash for Stroke {
 		self.transform.to_cols_array().iter().for_each(|x| x.to_bits().hash(state));
 		self.non_scaling.hash(state);
 		self.paint_order.hash(state);
+		self.width_profile.hash(state);
 	}
 }
 
@@ -338,6 +496,7 @@ impl Stroke {
 			transform: DAffine2::IDENTITY,
 			non_scaling: false,
 			paint_order: PaintOrder::StrokeAbove,
+			width_profile: WidthProfile(Vec::new()),
 		}
 	}
 
@@ -357,6 +516,7 @@ impl Stroke {
 			),
 			non_scaling: if time < 0.5 { self.non_scaling } else { other.non_scaling },
 			paint_order: if time < 0.5 { self.paint_order } else { other.paint_order },
+			width_profile: self.width_profile.lerp(&other.width_profile, time),
 		}
 	}
 
@@ -372,6 +532,10 @@ impl Stroke {
 
 	/// Get the effective stroke weight.
 	pub fn effective_width(&self) -> f64 {
+		if self.has_width_profile() {
+			return self.weight * self.width_profile.max_width();
+		}
+
 		self.weight
 			* match self.align {
 				StrokeAlign::Center => 1.,
@@ -458,9 +622,19 @@ impl Stroke {
 		self
 	}
 
+	pub fn with_width_profile(mut self, width_profile: WidthProfile) -> Self {
+		self.width_profile = width_profile;
+		self
+	}
+
 	pub fn has_renderable_stroke(&self) -> bool {
 		self.weight > 0. && self.color.is_some_and(|color| color.a() != 0.)
 	}
+
+	/// Whether the stroke varies in width along its path, so it must be drawn as an outline rather than a regular stroke.
+	pub fn has_width_profile(&self) -> bool {
+		!self.width_profile.is_empty()
+	}
 }
 
 // Having an alpha of 1 to start with leads to a better experience with the properties panel
@@ -478,21 +652,144 @@ impl Default for Stroke {
 			transform: DAffine2::IDENTITY,
 			non_scaling: false,
 			paint_order: PaintOrder::default(),
+			width_profile: WidthProfile::default(),
 		}
 	}
 }
 
+/// The paint of an [`AppearanceEntry`], covering either the area within the path or a line along it.
+#[derive(Debug, Clone, PartialEq, Hash, serde::Serialize, serde::Deserialize, DynAny, specta::Type)]
+pub enum AppearancePaint {
+	Fill(Fill),
+	Stroke(Stroke),
+}
+
+impl AppearancePaint {
+	pub fn lerp(&self, other: &Self, time: f64) -> Self {
+		match (self, other) {
+			(Self::Fill(a), Self::Fill(b)) => Self::Fill(a.lerp(b, time)),
+			(Self::Stroke(a), Self::Stroke(b)) => Self::Stroke(a.lerp(b, time)),
+			_ => {
+				if time < 0.5 {
+					self.clone()
+				} else {
+					other.clone()
+				}
+			}
+		}
+	}
+}
+
+/// One of the additional fills or strokes in an [`AppearanceStack`], which is composited with its own opacity and blend mode over everything drawn before it.
+#[derive(Debug, Clone, PartialEq, serde::Serialize, serde::Deserialize, DynAny, specta::Type)]
+pub struct AppearanceEntry {
+	pub paint: AppearancePaint,
+	/// The opacity from 0 to 1.
+	pub opacity: f64,
+	pub blend_mode: BlendMode,
+}
+
+impl std::hash::Hash for AppearanceEntry {
+	fn hash<H: std::hash::Hasher>(&self, state: &mut H) {
+		self.paint.hash(state);
+		self.opacity.to_bits().hash(state);
+		self.blend_mode.hash(state);
+	}
+}
+
+impl AppearanceEntry {
+	pub fn new(paint: AppearancePaint) -> Self {
+		Self {
+			paint,
+			opacity: 1.,
+			blend_mode: BlendMode::Normal,
+		}
+	}
+
+	pub fn lerp(&self, other: &Self, time: f64) -> Self {
+		Self {
+			paint: self.paint.lerp(&other.paint, time),
+			opacity: self.opacity + (other.opacity - self.opacity) * time,
+			blend_mode: if time < 0.5 { self.blend_mode } else { other.blend_mode },
+		}
+	}
+}
+
+/// The ordered fills and strokes a path is drawn with in addition to its main fill and stroke, starting from the bottom one which is drawn just over them.
+///
+/// Each stroke in the stack is drawn with only its own style, so a width profile given to the main stroke doesn't vary the width of these.
+#[derive(Debug, Clone, Default, PartialEq, Hash, serde::Serialize, serde::Deserialize, DynAny, specta::Type)]
+pub struct AppearanceStack(pub Vec<AppearanceEntry>);
+
+impl std::fmt::Display for AppearanceStack {
+	fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+		let entries = self
+			.0
+			.iter()
+			.map(|entry| {
+				let paint = match &entry.paint {
+					AppearancePaint::Fill(fill) => format!("Fill {fill}"),
+					AppearancePaint::Stroke(stroke) => format!("Stroke #{} (Weight: {} px)", stroke.color.map_or("None".to_string(), |c| c.to_rgba_hex_srgb()), stroke.weight),
+				};
+				format!("{paint} ({}%, {})", (entry.opacity * 100.).round(), entry.blend_mode)
+			})
+			.collect::<Vec<_>>();
+		write!(f, "{}", entries.join(", "))
+	}
+}
+
+impl AppearanceStack {
+	pub fn is_empty(&self) -> bool {
+		self.0.is_empty()
+	}
+
+	pub fn iter(&self) -> impl Iterator<Item = &AppearanceEntry> {
+		self.0.iter()
+	}
+
+	/// The thickest stroke in the stack, for finding how far it may reach beyond the path.
+	pub fn max_stroke_width(&self) -> f64 {
+		self.0
+			.iter()
+			.filter_map(|entry| match &entry.paint {
+				AppearancePaint::Stroke(stroke) => Some(stroke.effective_width()),
+				AppearancePaint::Fill(_) => None,
+			})
+			.fold(0., f64::max)
+	}
+
+	/// Blends the entries which the stacks share in the same places, while those in only the longer stack switch over halfway.
+	pub fn lerp(&self, other: &Self, time: f64) -> Self {
+		let self_is_longer = self.0.len() >= other.0.len();
+		let longer = if self_is_longer { self } else { other };
+		let entries = longer
+			.0
+			.iter()
+			.enumerate()
+			.filter_map(|(index, entry)| match (self.0.get(index), other.0.get(index)) {
+				(Some(a), Some(b)) => Some(a.lerp(b, time)),
+				_ => (if self_is_longer { time < 0.5 } else { time >= 0.5 }).then(|| entry.clone()),
+			})
+			.collect();
+		Self(entries)
+	}
+}
+
 #[repr(C)]
 #[derive(Debug, Clone, PartialEq, Default, serde::Serialize, serde::Deserialize, DynAny, specta::Type)]
 pub struct PathStyle {
 	pub stroke: Option<Stroke>,
 	pub fill: Fill,
+	/// The additional fills and strokes drawn in order over the main fill and stroke.
+	#[serde(default)]
+	pub appearance: AppearanceStack,
 }
 
 impl std::hash::Hash for PathStyle {
 	fn hash<H: std::hash::Hasher>(&self, state: &mut H) {
 		self.stroke.hash(state);
 		self.fill.hash(state);
+		self.appearance.hash(state);
 	}
 }
 
@@ -505,13 +802,21 @@ impl std::fmt::Display for PathStyle {
 			None => "None".to_string(),
 		};
 
-		write!(f, "Fill: {fill}\nStroke: {stroke}")
+		write!(f, "Fill: {fill}\nStroke: {stroke}")?;
+		if !self.appearance.is_empty() {
+			write!(f, "\nAppearance: {}", self.appearance)?;
+		}
+		Ok(())
 	}
 }
 
 impl PathStyle {
 	pub const fn new(stroke: Option<Stroke>, fill: Fill) -> Self {
-		Self { stroke, fill }
+		Self {
+			stroke,
+			fill,
+			appearance: AppearanceStack(Vec::new()),
+		}
 	}
 
 	pub fn lerp(&self, other: &Self, time: f64) -> Self {
@@ -535,6 +840,7 @@ impl PathStyle {
 				}
 				(None, None) => None,
 			},
+			appearance: self.appearance.lerp(&other.appearance, time),
 		}
 	}
 
@@ -587,6 +893,16 @@ impl PathStyle {
 		self.fill = fill;
 	}
 
+	/// The width of the widest stroke among the main stroke and those in the appearance stack.
+	pub fn max_stroke_width(&self) -> f64 {
+		self.stroke.as_ref().map_or(0., Stroke::effective_width).max(self.appearance.max_stroke_width())
+	}
+
+	/// Whether the area within the path is painted by the main fill or any fill in the appearance stack.
+	pub fn has_any_fill(&self) -> bool {
+		self.fill != Fill::None || self.appearance.iter().any(|entry| matches!(&entry.paint, AppearancePaint::Fill(fill) if *fill != Fill::None))
+	}
+
 	pub fn set_stroke_transform(&mut self, transform: DAffine2) {
 		if let Some(stroke) = &mut self.stroke {
 			stroke.transform = transform;
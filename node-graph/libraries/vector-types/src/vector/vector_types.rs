@@ -481,7 +481,7 @@ impl<Upstream> BoundingBox for Vector<Upstream> {
 		}
 
 		// Include stroke by adding offset based on stroke width
-		let stroke_width = self.style.stroke().map(|s| s.weight()).unwrap_or_default();
+		let stroke_width = self.style.stroke().map(|s| s.weight()).unwrap_or_default().max(self.style.appearance.max_stroke_width());
 		let miter_limit = self.style.stroke().map(|s| s.join_miter_limit).unwrap_or(1.);
 		let scale = transform.decompose_scale();
 
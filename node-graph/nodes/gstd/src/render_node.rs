@@ -11,6 +11,7 @@ use graphic_types::Graphic;
 use graphic_types::Vector;
 use graphic_types::raster_types::Image;
 use graphic_types::raster_types::{CPU, Raster};
+use rendering::pdf::PdfRender;
 use rendering::{Render, RenderOutputType as RenderOutputTypeRequest, RenderParams, RenderSvgSegmentList, SvgRender, format_transform_matrix};
 use rendering::{RenderMetadata, SvgSegment};
 use std::collections::HashMap;
@@ -25,6 +26,7 @@ type ImageData = HashMap<Image<Color>, u64>;
 pub enum RenderIntermediateType {
 	Vello(Arc<(vello::Scene, RenderContext)>),
 	Svg(Arc<(String, ImageData, String)>),
+	Pdf(Arc<PdfRender>),
 }
 #[derive(Clone, dyn_any::DynAny)]
 pub struct RenderIntermediate {
@@ -84,6 +86,17 @@ async fn render_intermediate<'a: 'n, T: 'static + Render + WasmNotSend + Send +
 				contains_artboard,
 			}
 		}
+		RenderOutputTypeRequest::Pdf => {
+			let mut render = PdfRender::new();
+
+			data.render_pdf(&mut render, render_params);
+
+			RenderIntermediate {
+				ty: RenderIntermediateType::Pdf(Arc::new(render)),
+				metadata,
+				contains_artboard,
+			}
+		}
 	}
 }
 
@@ -98,6 +111,7 @@ async fn create_context<'a: 'n>(
 	let render_output_type = match render_config.export_format {
 		ExportFormat::Svg => RenderOutputTypeRequest::Svg,
 		ExportFormat::Raster => RenderOutputTypeRequest::Vello,
+		ExportFormat::Pdf => RenderOutputTypeRequest::Pdf,
 	};
 
 	let render_params = RenderParams {
@@ -202,6 +216,12 @@ async fn render<'a: 'n>(ctx: impl Ctx + ExtractFootprint + ExtractVarArgs, edito
 
 			RenderOutputType::Texture(ImageTexture { texture })
 		}
+		(RenderOutputTypeRequest::Pdf, RenderIntermediateType::Pdf(pdf)) => {
+			let background = (!contains_artboard && !render_params.hide_artboards).then_some(Color::WHITE);
+			let data = pdf.to_pdf(footprint.transform, logical_resolution, background);
+
+			RenderOutputType::Pdf { data }
+		}
 		_ => unreachable!("Render node did not receive its requested data type"),
 	};
 	RenderOutput { data, metadata }
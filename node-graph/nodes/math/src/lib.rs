@@ -4,75 +4,173 @@ use core_types::transform::Footprint;
 use core_types::{Color, Ctx, num_traits};
 use glam::{DAffine2, DVec2};
 use log::warn;
-use math_parser::ast;
-use math_parser::context::{EvalContext, NothingMap, ValueProvider};
+use math_parser::expression::CompiledExpression;
 use math_parser::value::{Number, Value};
 use num_traits::Pow;
 use rand::{Rng, SeedableRng};
-use std::ops::{Add, Div, Mul, Rem, Sub};
+use std::cell::RefCell;
+use std::collections::HashMap;
+use std::ops::{Add, Div, Mul, Range, Rem, Sub};
+use std::rc::Rc;
 use vector_types::GradientStops;
 
-/// The struct that stores the context for the maths parser.
-/// This is currently just limited to supplying `a` and `b` until we add better node graph support and UI for variadic inputs.
-struct MathNodeContext {
-	a: f64,
-	b: f64,
+/// The maximum number of compiled expressions kept by each thread, beyond which the least recently used one is discarded so expressions which are no longer used don't accumulate.
+const MAX_COMPILED_EXPRESSIONS: usize = 256;
+
+#[derive(Default)]
+struct CompiledExpressions {
+	/// Each expression with its compiled form and the time it was last used.
+	expressions: HashMap<String, (Rc<CompiledExpression>, u64)>,
+	/// Counts every use of an expression, which orders them by how recently they were used.
+	time: u64,
 }
 
-impl ValueProvider for MathNodeContext {
-	fn get_value(&self, name: &str) -> Option<Value> {
-		if name.eq_ignore_ascii_case("a") {
-			Some(Value::from_f64(self.a))
-		} else if name.eq_ignore_ascii_case("b") {
-			Some(Value::from_f64(self.b))
-		} else {
-			None
+thread_local! {
+	/// The expressions of the math nodes, which are compiled once rather than every time the node is evaluated, such as for each instance in a loop.
+	static COMPILED_EXPRESSIONS: RefCell<CompiledExpressions> = RefCell::default();
+}
+
+fn compile_expression(expression: &str) -> Option<Rc<CompiledExpression>> {
+	COMPILED_EXPRESSIONS.with_borrow_mut(|CompiledExpressions { expressions, time }| {
+		*time += 1;
+
+		if let Some((compiled, last_used)) = expressions.get_mut(expression) {
+			*last_used = *time;
+			return Some(compiled.clone());
 		}
-	}
+
+		let compiled = match CompiledExpression::compile(expression) {
+			Ok(compiled) => Rc::new(compiled),
+			Err(e) => {
+				warn!("Invalid expression: `{expression}`\n{e}");
+				return None;
+			}
+		};
+
+		if expressions.len() >= MAX_COMPILED_EXPRESSIONS
+			&& let Some(least_recently_used) = expressions.iter().min_by_key(|(_, (_, last_used))| *last_used).map(|(expression, _)| expression.clone())
+		{
+			expressions.remove(&least_recently_used);
+		}
+		expressions.insert(expression.to_string(), (compiled.clone(), *time));
+
+		Some(compiled)
+	})
+}
+
+fn is_operand(name: &str) -> bool {
+	name.eq_ignore_ascii_case("a") || name.eq_ignore_ascii_case("b")
 }
 
-/// Calculates a mathematical expression with input values "A" and "B".
+/// The names of the variables in a *Math* node's expression besides "A" and "B", in the order they first appear, which is the order of the node's inputs for them.
+/// Returns `None` if the expression can't be parsed.
+pub fn named_variables(expression: &str) -> Option<Vec<String>> {
+	let compiled = compile_expression(expression)?;
+	Some(compiled.variables().iter().filter(|name| !is_operand(name)).cloned().collect())
+}
+
+/// Evaluates an expression with the values of the operands "A" and "B", and of the named variables in the order they first appear. Missing named variables are 0.
+fn evaluate_expression(expression: &str, a: Value, b: Value, named_values: &[f64]) -> Option<Value> {
+	let compiled = compile_expression(expression)?;
+
+	let mut named_values = named_values.iter().copied();
+	let values = compiled
+		.variables()
+		.iter()
+		.map(|name| match name {
+			name if name.eq_ignore_ascii_case("a") => a,
+			name if name.eq_ignore_ascii_case("b") => b,
+			_ => Value::from_f64(named_values.next().unwrap_or_default()),
+		})
+		.collect::<Vec<_>>();
+
+	compiled.evaluate(&values).inspect_err(|e| warn!("Expression evaluation error in `{expression}`: {e}")).ok()
+}
+
+/// The message of the first error in a *Math* node's expression and the part of the expression it concerns, if it can't be parsed or evaluated.
+/// The expression is evaluated with all its variables set to 0, which reveals errors like unknown functions and wrong arguments that don't depend on the values.
+pub fn expression_error(expression: &str) -> Option<(String, Range<usize>)> {
+	let compiled = match CompiledExpression::compile(expression) {
+		Ok(compiled) => compiled,
+		Err(e) => return Some((e.to_string(), e.span())),
+	};
+
+	let values = vec![Value::from_f64(0.); compiled.variables().len()];
+	compiled.evaluate(&values).err().map(|e| (e.to_string(), e.span))
+}
+
+/// Calculates a mathematical expression with input values "A" and "B", and any other named variables it incorporates.
 #[node_macro::node(category("Math: Arithmetic"), properties("math_properties"))]
 fn math<T: num_traits::float::Float>(
 	_: impl Ctx,
 	/// The value of "A" when calculating the expression.
 	#[implementations(f64, f32)]
 	operand_a: T,
-	/// A math expression that may incorporate "A" and/or "B", such as `sqrt(A + B) - B^2`.
+	/// A math expression that may incorporate "A" and/or "B", such as `sqrt(A + B) - B^2` or `A > B ? A : B`. Conditions that are true produce 1 and false produce 0.
 	#[default(A + B)]
 	expression: String,
 	/// The value of "B" when calculating the expression.
 	#[implementations(f64, f32)]
 	#[default(1.)]
 	operand_b: T,
+	/// The values of the other variables incorporated by the expression, like `columns` in `A % columns`, in the order they first appear.
+	/// The editor gives the node an input after this one for each of them, which are collected here by a chain of *Math Variable* nodes when the graph is compiled.
+	variables: Vec<f64>,
 ) -> T {
-	let (node, _unit) = match ast::Node::try_parse_from_str(&expression) {
-		Ok(expr) => expr,
-		Err(e) => {
-			warn!("Invalid expression: `{expression}`\n{e:?}");
-			return T::from(0.).unwrap();
-		}
+	let Some(value) = evaluate_expression(&expression, Value::from_f64(operand_a.to_f64().unwrap()), Value::from_f64(operand_b.to_f64().unwrap()), &variables) else {
+		return T::from(0.).unwrap();
 	};
-	let context = EvalContext::new(
-		MathNodeContext {
-			a: operand_a.to_f64().unwrap(),
-			b: operand_b.to_f64().unwrap(),
-		},
-		NothingMap,
-	);
-
-	let value = match node.eval(&context) {
-		Ok(value) => value,
-		Err(e) => {
-			warn!("Expression evaluation error: {e:?}");
-			return T::from(0.).unwrap();
+
+	match value {
+		Value::Number(Number::Real(val)) => T::from(val).unwrap(),
+		Value::Number(Number::Complex(c)) => T::from(c.re).unwrap(),
+		Value::Bool(val) => T::from(if val { 1. } else { 0. }).unwrap(),
+		Value::Vec2(_) | Value::Color(_) => {
+			warn!("Expression `{expression}` produced {value} instead of a number");
+			T::from(0.).unwrap()
 		}
-	};
+	}
+}
+
+/// Appends the value of one of the named variables of a *Math* node to the values of the variables before it.
+#[node_macro::node(category(""))]
+fn math_variable(
+	_: impl Ctx,
+	/// The values of the variables which appear earlier in the expression.
+	variables: Vec<f64>,
+	/// The value of the next variable.
+	value: f64,
+) -> Vec<f64> {
+	let mut variables = variables;
+	variables.push(value);
+	variables
+}
 
-	let Value::Number(num) = value;
-	match num {
-		Number::Real(val) => T::from(val).unwrap(),
-		Number::Complex(c) => T::from(c.re).unwrap(),
+/// Calculates a mathematical expression with input values "A" and "B", which may each be a vec2 or a number, producing a vec2.
+///
+/// The components of a vec2 are accessed like `A.x` and new ones are constructed like `vec2(A.y, A.x)`. Built-in functions include `length`, `distance`, `dot`, `cross`, `normalize`, `angle`, and `rotate` (with an angle in radians).
+/// A number result is used for both components.
+#[node_macro::node(name("Vec2 Math"), category("Math: Vector"))]
+fn vec2_math<A: Into<Value>, B: Into<Value>>(
+	_: impl Ctx,
+	/// The value of "A" when calculating the expression.
+	#[implementations(DVec2, DVec2, f64)]
+	operand_a: A,
+	/// A math expression that may incorporate "A" and/or "B", such as `rotate(A, B) + vec2(10, 0)` or `normalize(B - A) * length(A)`.
+	#[default(A + B)]
+	expression: String,
+	/// The value of "B" when calculating the expression.
+	#[implementations(DVec2, f64, DVec2)]
+	operand_b: B,
+) -> DVec2 {
+	match evaluate_expression(&expression, operand_a.into(), operand_b.into(), &[]) {
+		Some(Value::Vec2(vec)) => vec,
+		Some(Value::Number(Number::Real(val))) => DVec2::splat(val),
+		Some(value) => {
+			warn!("Expression `{expression}` produced {value} instead of a vec2");
+			DVec2::ZERO
+		}
+		None => DVec2::ZERO,
 	}
 }
 
@@ -904,28 +1002,80 @@ mod test {
 
 	#[test]
 	fn test_basic_expression() {
-		let result = math((), 0., "2 + 2".to_string(), 0.);
+		let result = math((), 0., "2 + 2".to_string(), 0., Vec::new());
 		assert_eq!(result, 4.);
 	}
 
 	#[test]
 	fn test_complex_expression() {
-		let result = math((), 0., "(5 * 3) + (10 / 2)".to_string(), 0.);
+		let result = math((), 0., "(5 * 3) + (10 / 2)".to_string(), 0., Vec::new());
 		assert_eq!(result, 20.);
 	}
 
 	#[test]
 	fn test_default_expression() {
-		let result = math((), 0., "0".to_string(), 0.);
+		let result = math((), 0., "0".to_string(), 0., Vec::new());
 		assert_eq!(result, 0.);
 	}
 
 	#[test]
 	fn test_invalid_expression() {
-		let result = math((), 0., "invalid".to_string(), 0.);
+		let result = math((), 0., "invalid".to_string(), 0., Vec::new());
 		assert_eq!(result, 0.);
 	}
 
+	#[test]
+	fn test_conditional_expression() {
+		let result = math((), 3., "A > B ? A : B".to_string(), 5., Vec::new());
+		assert_eq!(result, 5.);
+	}
+
+	#[test]
+	fn test_named_variables() {
+		let expression = "A % columns * spacing + b".to_string();
+		assert_eq!(named_variables(&expression).unwrap(), ["columns", "spacing"]);
+
+		let result = math((), 5., expression, 1., vec![4., 10.]);
+		assert_eq!(result, 11.);
+
+		assert_eq!(named_variables("A % columns *"), None);
+	}
+
+	#[test]
+	fn test_compiled_expressions_evict_least_recently_used() {
+		let hot = "A * 2";
+		compile_expression(hot).unwrap();
+		for i in 0..MAX_COMPILED_EXPRESSIONS * 2 {
+			compile_expression(&format!("A + {i}")).unwrap();
+			compile_expression(hot).unwrap();
+		}
+
+		COMPILED_EXPRESSIONS.with_borrow(|compiled_expressions| {
+			assert_eq!(compiled_expressions.expressions.len(), MAX_COMPILED_EXPRESSIONS);
+			assert!(compiled_expressions.expressions.contains_key(hot));
+			assert!(!compiled_expressions.expressions.contains_key("A + 0"));
+		});
+	}
+
+	#[test]
+	fn test_math_variables() {
+		let variables = math_variable((), math_variable((), Vec::new(), 4.), 10.);
+		assert_eq!(variables, [4., 10.]);
+	}
+
+	#[test]
+	fn test_expression_error() {
+		assert_eq!(expression_error("A * columns + 1"), None);
+		assert_eq!(expression_error("A + sinn(B)"), Some(("Unknown function `sinn`".to_string(), 4..8)));
+		assert_eq!(expression_error("A +"), Some(("Unexpected end of expression".to_string(), 3..3)));
+	}
+
+	#[test]
+	fn test_vec2_expression() {
+		let result = vec2_math((), DVec2::new(3., 4.), "normalize(A) * B + vec2(A.y, 0)".to_string(), 10.);
+		assert_eq!(result, DVec2::new(10., 8.));
+	}
+
 	#[test]
 	pub fn foo() {
 		let fnn = FnNode::new(|(a, b)| (b, a));
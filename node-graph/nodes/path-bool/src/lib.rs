@@ -32,6 +32,16 @@ pub enum BooleanOperation {
 	Intersect,
 	#[icon("BooleanDifference")]
 	Difference,
+	/// Cuts the closed paths into separate pieces along the open paths.
+	#[icon("Cut")]
+	Knife,
+	/// Keeps only the parts of the open paths which are inside the closed paths.
+	TrimInside,
+	/// Keeps only the parts of the open paths which are outside the closed paths.
+	TrimOutside,
+	/// Keeps the areas covered by an odd number of paths, so unlike Difference, the areas where three paths overlap are kept.
+	#[label("XOR")]
+	Xor,
 }
 
 /// Combines the geometric forms of one or more closed paths into a new vector path that results from cutting or joining the paths by the chosen method.
@@ -46,7 +56,10 @@ async fn boolean_operation<I: graphic_types::IntoGraphicTable + 'n + Send + Clon
 	/// Union combines all paths while cutting out overlapping areas (even the interiors of a single path).
 	/// Subtraction cuts overlapping areas out from the last (Subtract Front) or first (Subtract Back) path.
 	/// Intersection cuts away all but the overlapping areas shared by every path.
-	/// Difference cuts away the overlapping areas shared by every path, leaving only the non-overlapping areas.
+	/// Difference cuts away the overlapping areas shared by any of the paths, leaving only the non-overlapping areas.
+	/// XOR cuts away the areas where an even number of paths overlap, leaving the areas covered by an odd number of paths.
+	/// Knife uses the open paths as cutters which split the closed paths into separate pieces.
+	/// Trim Inside and Trim Outside clip the open paths to the inside or the outside of the closed paths.
 	operation: BooleanOperation,
 ) -> Table<Vector> {
 	let content = content.into_graphic_table();
@@ -55,7 +68,7 @@ async fn boolean_operation<I: graphic_types::IntoGraphicTable + 'n + Send + Clon
 	let mut result_vector_table = boolean_operation_on_vector_table(flatten_vector(&content).iter(), operation);
 
 	// Replace the transformation matrix with a mutation of the vector points themselves
-	if let Some(result_vector) = result_vector_table.iter_mut().next() {
+	for result_vector in result_vector_table.iter_mut() {
 		let transform = *result_vector.transform;
 		*result_vector.transform = DAffine2::IDENTITY;
 
@@ -70,164 +83,157 @@ async fn boolean_operation<I: graphic_types::IntoGraphicTable + 'n + Send + Clon
 	result_vector_table
 }
 
-fn boolean_operation_on_vector_table<'a>(vector: impl DoubleEndedIterator<Item = TableRowRef<'a, Vector>> + Clone, boolean_operation: BooleanOperation) -> Table<Vector> {
+/// Generates the shape described by SVG path data, the format of the `d` attribute of an SVG `<path>` element.
+#[node_macro::node(category("Vector: Shape"))]
+fn path_data(
+	_: impl Ctx,
+	_primary: (),
+	/// The path commands, which may use arcs, smooth curves, and relative coordinates. Invalid path data is drawn up to its first error, as in SVG.
+	#[widget(ParsedWidgetOverride::Custom = "text_area")]
+	#[default("M 0 0 L 100 0 L 50 100 Z")]
+	d: String,
+) -> Table<Vector> {
+	let vector = vector_from_path_data(&d).unwrap_or_else(|error| {
+		log::warn!("{error}");
+		error.parsed
+	});
+	Table::new_from_element(vector)
+}
+
+fn boolean_operation_on_vector_table<'a>(vector: impl DoubleEndedIterator<Item = TableRowRef<'a, Vector>>, boolean_operation: BooleanOperation) -> Table<Vector> {
 	match boolean_operation {
 		BooleanOperation::Union => union(vector),
 		BooleanOperation::SubtractFront => subtract(vector),
 		BooleanOperation::SubtractBack => subtract(vector.rev()),
 		BooleanOperation::Intersect => intersect(vector),
 		BooleanOperation::Difference => difference(vector),
+		BooleanOperation::Knife => knife(vector),
+		BooleanOperation::TrimInside => trim(vector, true),
+		BooleanOperation::TrimOutside => trim(vector, false),
+		BooleanOperation::Xor => xor(vector),
 	}
 }
 
 fn union<'a>(vector: impl DoubleEndedIterator<Item = TableRowRef<'a, Vector>>) -> Table<Vector> {
 	// Reverse the vector table rows so that the result style is the style of the first vector row
-	let mut vector_reversed = vector.rev();
+	boolean_operation_on_rows(vector.rev(), PathBooleanOperation::Union)
+}
 
-	let mut result_vector_table = Table::new_from_row(vector_reversed.next().map(|x| x.into_cloned()).unwrap_or_default());
-	let mut first_row = result_vector_table.iter_mut().next().expect("Expected the one row we just pushed");
+fn subtract<'a>(vector: impl Iterator<Item = TableRowRef<'a, Vector>>) -> Table<Vector> {
+	boolean_operation_on_rows(vector, PathBooleanOperation::Difference)
+}
 
-	// Loop over all vector table rows and union it with the result
+fn intersect<'a>(vector: impl DoubleEndedIterator<Item = TableRowRef<'a, Vector>>) -> Table<Vector> {
 	let default = TableRow::default();
-	let mut second_vector = Some(vector_reversed.next().unwrap_or(default.as_ref()));
-	while let Some(lower_vector) = second_vector {
-		let transform_of_lower_into_space_of_upper = first_row.transform.inverse() * *lower_vector.transform;
-
-		let result = &mut first_row.element;
+	let mut rows = vector.rev().collect::<Vec<_>>();
 
-		let upper_path_string = to_path(result, DAffine2::IDENTITY);
-		let lower_path_string = to_path(lower_vector.element, transform_of_lower_into_space_of_upper);
-
-		#[allow(unused_unsafe)]
-		let boolean_operation_string = unsafe { boolean_union(upper_path_string, lower_path_string) };
-		let boolean_operation_result = from_path(&boolean_operation_string);
-
-		result.colinear_manipulators = boolean_operation_result.colinear_manipulators;
-		result.point_domain = boolean_operation_result.point_domain;
-		result.segment_domain = boolean_operation_result.segment_domain;
-		result.region_domain = boolean_operation_result.region_domain;
-
-		second_vector = vector_reversed.next();
+	// A single path has nothing to intersect with, so it's intersected with an empty path and nothing is left
+	if rows.len() == 1 {
+		rows.push(default.as_ref());
 	}
 
-	result_vector_table
+	boolean_operation_on_rows(rows.into_iter(), PathBooleanOperation::Intersection)
 }
 
-fn subtract<'a>(vector: impl Iterator<Item = TableRowRef<'a, Vector>>) -> Table<Vector> {
-	let mut vector = vector.into_iter();
-
-	let mut result_vector_table = Table::new_from_row(vector.next().map(|x| x.into_cloned()).unwrap_or_default());
-	let mut first_row = result_vector_table.iter_mut().next().expect("Expected the one row we just pushed");
-	let first_row_transform = if first_row.transform.matrix2.determinant() != 0. {
-		first_row.transform.inverse()
-	} else {
-		DAffine2::IDENTITY
-	};
-
-	let mut next_vector = vector.next();
-
-	while let Some(lower_vector) = next_vector {
-		let transform_of_lower_into_space_of_upper = first_row_transform * *lower_vector.transform;
-
-		let result = &mut first_row.element;
-
-		let upper_path_string = to_path(result, DAffine2::IDENTITY);
-		let lower_path_string = to_path(lower_vector.element, transform_of_lower_into_space_of_upper);
+fn difference<'a>(vector: impl DoubleEndedIterator<Item = TableRowRef<'a, Vector>>) -> Table<Vector> {
+	boolean_operation_on_rows(vector.rev(), PathBooleanOperation::Exclusion)
+}
 
-		#[allow(unused_unsafe)]
-		let boolean_operation_string = unsafe { boolean_subtract(upper_path_string, lower_path_string) };
-		let boolean_operation_result = from_path(&boolean_operation_string);
+fn xor<'a>(vector: impl DoubleEndedIterator<Item = TableRowRef<'a, Vector>>) -> Table<Vector> {
+	boolean_operation_on_rows(vector.rev(), PathBooleanOperation::Xor)
+}
 
-		result.colinear_manipulators = boolean_operation_result.colinear_manipulators;
-		result.point_domain = boolean_operation_result.point_domain;
-		result.segment_domain = boolean_operation_result.segment_domain;
-		result.region_domain = boolean_operation_result.region_domain;
+/// Cuts the closed subpaths of every row into pieces along the open subpaths of all the rows.
+/// Each piece becomes its own row, with the style of the row it was cut from.
+fn knife<'a>(vector: impl Iterator<Item = TableRowRef<'a, Vector>>) -> Table<Vector> {
+	let rows = vector.collect::<Vec<_>>();
+	let cutters = rows.iter().map(|row| to_path_of_subpaths(row.element, *row.transform, false)).collect::<Vec<_>>();
+	let cutters = cutters.iter().filter(|cutter| !cutter.is_empty()).collect::<Vec<_>>();
+
+	let mut result_vector_table = Table::new();
+	for row in rows {
+		let shape = to_path_of_subpaths(row.element, *row.transform, true);
+		if shape.is_empty() {
+			continue;
+		}
 
-		next_vector = vector.next();
+		let pieces = path_bool::path_knife(&shape, FillRule::NonZero, &cutters).unwrap_or_else(|e| {
+			log::error!("Boolean error {e:?} encountered while cutting\n {}", path_bool::path_to_path_data(&shape, 0.001));
+			Vec::new()
+		});
+		for piece in pieces {
+			result_vector_table.push(row_with_paths(row, &[piece], true));
+		}
 	}
 
 	result_vector_table
 }
 
-fn intersect<'a>(vector: impl DoubleEndedIterator<Item = TableRowRef<'a, Vector>>) -> Table<Vector> {
-	let mut vector = vector.rev();
-
-	let mut result_vector_table = Table::new_from_row(vector.next().map(|x| x.into_cloned()).unwrap_or_default());
-	let mut first_row = result_vector_table.iter_mut().next().expect("Expected the one row we just pushed");
-
-	let default = TableRow::default();
-	let mut second_vector = Some(vector.next().unwrap_or(default.as_ref()));
-
-	// For each vector table row, set the result to the intersection of that path and the current result
-	while let Some(lower_vector) = second_vector {
-		let transform_of_lower_into_space_of_upper = first_row.transform.inverse() * *lower_vector.transform;
-
-		let result = &mut first_row.element;
-
-		let upper_path_string = to_path(result, DAffine2::IDENTITY);
-		let lower_path_string = to_path(lower_vector.element, transform_of_lower_into_space_of_upper);
-
-		#[allow(unused_unsafe)]
-		let boolean_operation_string = unsafe { boolean_intersect(upper_path_string, lower_path_string) };
-		let boolean_operation_result = from_path(&boolean_operation_string);
+/// Clips the open subpaths of every row to the inside or outside of the region covered by the closed subpaths of all the rows.
+/// The rows without any open subpaths are left out.
+fn trim<'a>(vector: impl Iterator<Item = TableRowRef<'a, Vector>>, keep_inside: bool) -> Table<Vector> {
+	let rows = vector.collect::<Vec<_>>();
+	let region = rows.iter().flat_map(|row| to_path_of_subpaths(row.element, *row.transform, true)).collect::<Path>();
+
+	let mut result_vector_table = Table::new();
+	for row in rows {
+		let path = to_path_of_subpaths(row.element, *row.transform, false);
+		if path.is_empty() {
+			continue;
+		}
 
-		result.colinear_manipulators = boolean_operation_result.colinear_manipulators;
-		result.point_domain = boolean_operation_result.point_domain;
-		result.segment_domain = boolean_operation_result.segment_domain;
-		result.region_domain = boolean_operation_result.region_domain;
-		second_vector = vector.next();
+		let trimmed = path_bool::path_trim(&path, &region, FillRule::NonZero, keep_inside).unwrap_or_else(|e| {
+			log::error!("Boolean error {e:?} encountered while trimming\n {}", path_bool::path_to_path_data(&path, 0.001));
+			Vec::new()
+		});
+		if !trimmed.is_empty() {
+			result_vector_table.push(row_with_paths(row, &[trimmed], false));
+		}
 	}
 
 	result_vector_table
 }
 
-fn difference<'a>(vector: impl DoubleEndedIterator<Item = TableRowRef<'a, Vector>> + Clone) -> Table<Vector> {
-	let mut vector_iter = vector.clone().rev();
-	let mut any_intersection = TableRow::default();
-	let default = TableRow::default();
-	let mut second_vector = Some(vector_iter.next().unwrap_or(default.as_ref()));
-
-	// Find where all vector table row paths intersect at least once
-	while let Some(lower_vector) = second_vector {
-		let filtered_vector = vector.clone().filter(|v| *v != lower_vector).collect::<Vec<_>>().into_iter();
-		let unioned = boolean_operation_on_vector_table(filtered_vector, BooleanOperation::Union);
-		let first_row = unioned.iter().next().expect("Expected at least one row after the boolean union");
-
-		let transform_of_lower_into_space_of_upper = first_row.transform.inverse() * *lower_vector.transform;
-
-		let upper_path_string = to_path(first_row.element, DAffine2::IDENTITY);
-		let lower_path_string = to_path(lower_vector.element, transform_of_lower_into_space_of_upper);
-
-		#[allow(unused_unsafe)]
-		let boolean_intersection_string = unsafe { boolean_intersect(upper_path_string, lower_path_string) };
-		let mut element = from_path(&boolean_intersection_string);
-		element.style = first_row.element.style.clone();
-		let boolean_intersection_result = TableRow {
-			element,
-			transform: *first_row.transform,
-			alpha_blending: *first_row.alpha_blending,
-			source_node_id: *first_row.source_node_id,
-		};
+/// A copy of a row with its geometry replaced by paths which are in the space of the table.
+fn row_with_paths(row: TableRowRef<Vector>, paths: &[Path], closed: bool) -> TableRow<Vector> {
+	let mut row = row.into_cloned();
+	let geometry = from_path(paths, closed);
+
+	row.element.colinear_manipulators = geometry.colinear_manipulators;
+	row.element.point_domain = geometry.point_domain;
+	row.element.segment_domain = geometry.segment_domain;
+	row.element.region_domain = geometry.region_domain;
+	row.transform = DAffine2::IDENTITY;
 
-		let transform_of_lower_into_space_of_upper = boolean_intersection_result.transform.inverse() * any_intersection.transform;
+	row
+}
 
-		let upper_path_string = to_path(&boolean_intersection_result.element, DAffine2::IDENTITY);
-		let lower_path_string = to_path(&any_intersection.element, transform_of_lower_into_space_of_upper);
+/// Performs the boolean operation on the paths of all vector table rows in a single pass.
+/// The result takes on the style and transform of the first row, and the paths of the other rows are brought into its space.
+fn boolean_operation_on_rows<'a>(mut vector: impl Iterator<Item = TableRowRef<'a, Vector>>, operation: PathBooleanOperation) -> Table<Vector> {
+	let mut result_vector_table = Table::new_from_row(vector.next().map(|x| x.into_cloned()).unwrap_or_default());
+	let mut first_row = result_vector_table.iter_mut().next().expect("Expected the one row we just pushed");
+	let first_row_transform = if first_row.transform.matrix2.determinant() != 0. {
+		first_row.transform.inverse()
+	} else {
+		DAffine2::IDENTITY
+	};
 
-		#[allow(unused_unsafe)]
-		let union_result = from_path(&unsafe { boolean_union(upper_path_string, lower_path_string) });
-		any_intersection.element = union_result;
+	let mut paths = vec![to_path(first_row.element, DAffine2::IDENTITY)];
+	paths.extend(vector.map(|lower_vector| {
+		let transform_of_lower_into_space_of_upper = first_row_transform * *lower_vector.transform;
+		to_path(lower_vector.element, transform_of_lower_into_space_of_upper)
+	}));
 
-		any_intersection.transform = boolean_intersection_result.transform;
-		any_intersection.element.style = boolean_intersection_result.element.style.clone();
-		any_intersection.alpha_blending = boolean_intersection_result.alpha_blending;
+	let boolean_operation_result = from_path(&path_bool(&paths, operation), true);
 
-		second_vector = vector_iter.next();
-	}
+	let result = &mut first_row.element;
+	result.colinear_manipulators = boolean_operation_result.colinear_manipulators;
+	result.point_domain = boolean_operation_result.point_domain;
+	result.segment_domain = boolean_operation_result.segment_domain;
+	result.region_domain = boolean_operation_result.region_domain;
 
-	// Subtract the area where they intersect at least once from the union of all vector paths
-	let union = boolean_operation_on_vector_table(vector, BooleanOperation::Union);
-	boolean_operation_on_vector_table(union.iter().chain(std::iter::once(any_intersection.as_ref())), BooleanOperation::SubtractFront)
+	result_vector_table
 }
 
 fn flatten_vector(graphic_table: &Table<Graphic>) -> Table<Vector> {
@@ -330,12 +336,21 @@ fn flatten_vector(graphic_table: &Table<Graphic>) -> Table<Vector> {
 fn to_path(vector: &Vector, transform: DAffine2) -> Vec<path_bool::PathSegment> {
 	let mut path = Vec::new();
 	for subpath in vector.stroke_bezier_paths() {
-		to_path_segments(&mut path, &subpath, transform);
+		to_path_segments(&mut path, &subpath, transform, true);
+	}
+	path
+}
+
+/// Either the closed or the open subpaths of a vector, where the open ones are left open instead of being closed like a fill would.
+fn to_path_of_subpaths(vector: &Vector, transform: DAffine2, closed: bool) -> Vec<path_bool::PathSegment> {
+	let mut path = Vec::new();
+	for subpath in vector.stroke_bezier_paths().filter(|subpath| subpath.closed() == closed) {
+		to_path_segments(&mut path, &subpath, transform, closed);
 	}
 	path
 }
 
-fn to_path_segments(path: &mut Vec<path_bool::PathSegment>, subpath: &Subpath<PointId>, transform: DAffine2) {
+fn to_path_segments(path: &mut Vec<path_bool::PathSegment>, subpath: &Subpath<PointId>, transform: DAffine2, close: bool) {
 	use path_bool::PathSegment;
 	let mut global_start = None;
 	let mut global_end = DVec2::ZERO;
@@ -364,12 +379,12 @@ fn to_path_segments(path: &mut Vec<path_bool::PathSegment>, subpath: &Subpath<Po
 
 		path.push(segment);
 	}
-	if let Some(start) = global_start {
+	if close && let Some(start) = global_start {
 		path.push(PathSegment::Line(global_end, start));
 	}
 }
 
-fn from_path(path_data: &[Path]) -> Vector {
+fn from_path(path_data: &[Path], closed: bool) -> Vector {
 	const EPSILON: f64 = 1e-5;
 
 	fn is_close(a: DVec2, b: DVec2) -> bool {
@@ -389,7 +404,7 @@ fn from_path(path_data: &[Path]) -> Vector {
 			if current_start.is_none() || !is_close(start, current_start.unwrap()) {
 				// Start a new subpath
 				if !manipulators_list.is_empty() {
-					all_subpaths.push(Subpath::new(std::mem::take(&mut manipulators_list), true));
+					all_subpaths.push(Subpath::new(std::mem::take(&mut manipulators_list), closed));
 				}
 				// Use the correct in-handle (None) and out-handle for the start point
 				manipulators_list.push(ManipulatorGroup::new(start, None, Some(handle1)));
@@ -407,7 +422,7 @@ fn from_path(path_data: &[Path]) -> Vector {
 
 			// Check if this is the last segment
 			if index == cubics.len() - 1 {
-				all_subpaths.push(Subpath::new(manipulators_list, true));
+				all_subpaths.push(Subpath::new(manipulators_list, closed));
 				manipulators_list = Vec::new(); // Reset manipulators for the next path
 			}
 		}
@@ -416,28 +431,61 @@ fn from_path(path_data: &[Path]) -> Vector {
 	Vector::from_subpaths(all_subpaths, false)
 }
 
-type Path = Vec<path_bool::PathSegment>;
+/// Parses SVG path data into a vector, where subpaths ended by a `Z` command are closed and arcs are approximated by cubic segments.
+///
+/// Path data with an error gives the vector of the commands before the error along with it.
+pub fn vector_from_path_data(d: &str) -> Result<Vector, path_bool::PathDataError<Vector>> {
+	match path_bool::subpaths_from_path_data(d) {
+		Ok(subpaths) => Ok(vector_from_subpaths(subpaths)),
+		Err(error) => Err(path_bool::PathDataError {
+			parsed: vector_from_subpaths(error.parsed),
+			error: error.error,
+		}),
+	}
+}
 
-fn boolean_union(a: Path, b: Path) -> Vec<Path> {
-	path_bool(a, b, PathBooleanOperation::Union)
+fn vector_from_subpaths(subpaths: Vec<(Path, bool)>) -> Vector {
+	let subpaths = subpaths.into_iter().map(|(segments, closed)| {
+		let mut manipulator_groups = vec![ManipulatorGroup::new_anchor(segments[0].start())];
+
+		for segment in segments.iter().flat_map(|segment| segment.arc_segment_to_cubics(std::f64::consts::FRAC_PI_2)) {
+			let (out_handle, in_handle) = match segment {
+				path_bool::PathSegment::Line(..) | path_bool::PathSegment::Arc(..) => (None, None),
+				path_bool::PathSegment::Quadratic(_, handle, _) => (Some(handle), None),
+				path_bool::PathSegment::Cubic(_, handle_start, handle_end, _) => (Some(handle_start), Some(handle_end)),
+			};
+			if let Some(last) = manipulator_groups.last_mut() {
+				last.out_handle = out_handle;
+			}
+			manipulator_groups.push(ManipulatorGroup::new(segment.end(), in_handle, None));
+		}
+
+		// The last segment of a closed subpath returns to its start, so its end becomes the start again
+		if closed && manipulator_groups.len() > 2 {
+			let last = manipulator_groups.pop().unwrap();
+			manipulator_groups[0].in_handle = last.in_handle;
+		}
+
+		Subpath::new(manipulator_groups, closed)
+	});
+
+	Vector::from_subpaths(subpaths, false)
 }
 
-fn path_bool(a: Path, b: Path, op: PathBooleanOperation) -> Vec<Path> {
-	match path_bool::path_boolean(&a, FillRule::NonZero, &b, FillRule::NonZero, op) {
+type Path = Vec<path_bool::PathSegment>;
+
+fn path_bool(paths: &[Path], op: PathBooleanOperation) -> Vec<Path> {
+	let paths_with_fill_rules = paths.iter().map(|path| (path, FillRule::NonZero)).collect::<Vec<_>>();
+	match path_bool::path_boolean_many(&paths_with_fill_rules, op) {
 		Ok(results) => results,
 		Err(e) => {
-			let a_path = path_bool::path_to_path_data(&a, 0.001);
-			let b_path = path_bool::path_to_path_data(&b, 0.001);
-			log::error!("Boolean error {e:?} encountered while processing {a_path}\n {op:?}\n {b_path}");
+			let path_strings = paths.iter().map(|path| path_bool::path_to_path_data(path, 0.001)).collect::<Vec<_>>();
+			log::error!("Boolean error {e:?} encountered while processing {op:?} on\n {}", path_strings.join("\n "));
 			Vec::new()
 		}
 	}
 }
 
-fn boolean_subtract(a: Path, b: Path) -> Vec<Path> {
-	path_bool(a, b, PathBooleanOperation::Difference)
-}
-
 pub fn boolean_intersect(a: Path, b: Path) -> Vec<Path> {
-	path_bool(a, b, PathBooleanOperation::Intersection)
+	path_bool(&[a, b], PathBooleanOperation::Intersection)
 }
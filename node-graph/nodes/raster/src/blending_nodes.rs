@@ -75,7 +75,7 @@ mod blend_std {
 					(position, color)
 				})
 				.collect::<Vec<_>>();
-			GradientStops::new(stops)
+			GradientStops::new(stops).with_blending_of(self)
 		}
 	}
 }
@@ -15,6 +15,8 @@ pub mod curve;
 #[cfg(feature = "std")]
 pub mod dehaze;
 #[cfg(feature = "std")]
+pub mod develop_raw;
+#[cfg(feature = "std")]
 pub mod filter;
 #[cfg(feature = "std")]
 pub mod generate_curves;
@@ -22,11 +22,12 @@ use vector_types::vector::algorithms::bezpath_algorithms::{self, TValue, evaluat
 use vector_types::vector::algorithms::merge_by_distance::MergeByDistanceExt;
 use vector_types::vector::algorithms::offset_subpath::offset_bezpath;
 use vector_types::vector::algorithms::spline::{solve_spline_first_handle_closed, solve_spline_first_handle_open};
+use vector_types::vector::algorithms::width_profile::stroke_width_profile_outline;
 use vector_types::vector::misc::{CentroidType, ExtrudeJoiningAlgorithm, RowsOrColumns, bezpath_from_manipulator_groups, bezpath_to_manipulator_groups, point_to_dvec2};
 use vector_types::vector::misc::{MergeByDistanceAlgorithm, PointSpacingType, is_linear};
 use vector_types::vector::misc::{handles_to_segment, segment_to_handles};
-use vector_types::vector::style::{Fill, Gradient, GradientStops, Stroke};
-use vector_types::vector::style::{PaintOrder, StrokeAlign, StrokeCap, StrokeJoin};
+use vector_types::vector::style::{AppearancePaint, AppearanceStack, PaintOrder, StrokeAlign, StrokeCap, StrokeJoin, WidthProfile};
+use vector_types::vector::style::{Fill, Gradient, GradientStops, ImageFill, ImageFit, PatternFill, Stroke};
 use vector_types::vector::{FillId, RegionId};
 use vector_types::vector::{PointId, SegmentDomain, SegmentId, StrokeId, VectorExt};
 
@@ -148,6 +149,49 @@ async fn fill<F: Into<Fill> + 'n + Send, V: VectorTableIterMut + 'n + Send>(
 	content
 }
 
+/// Creates a pattern fill, which repeats the content in a grid of tiles across the area of the shape it fills. Connect it to the fill input of the Fill node to apply it.
+#[node_macro::node(category("Vector: Style"), path(graphene_core::vector))]
+async fn pattern_fill<T: IntoGraphicTable + 'n + Send>(
+	_: impl Ctx,
+	/// The vector or raster content drawn in each tile.
+	#[implementations(Table<Graphic>, Table<Vector>, Table<Raster<CPU>>)]
+	content: T,
+	/// The gap between neighboring tiles, added to the size of the content.
+	spacing: PixelSize,
+	/// The shift of every tile from where the content lies.
+	offset: PixelSize,
+	/// The rotation of the grid of tiles.
+	rotation: Angle,
+	/// The scale of the grid of tiles.
+	#[default(1., 1.)]
+	scale: DVec2,
+) -> Fill {
+	Fill::Pattern(PatternFill {
+		content: content.into_pattern_content(),
+		transform: DAffine2::from_scale_angle_translation(scale, rotation.to_radians(), DVec2::ZERO),
+		spacing: spacing.max(DVec2::ZERO),
+		offset,
+	})
+}
+
+/// Creates an image fill, which places the image within the bounds of the shape it fills. Connect it to the fill input of the Fill node to apply it.
+#[node_macro::node(category("Vector: Style"), path(graphene_core::vector))]
+async fn image_fill(
+	_: impl Ctx,
+	/// The image to fill with. Only its first row is used.
+	image: Table<Raster<CPU>>,
+	/// How the image is sized to fit the bounds of the shape.
+	fit: ImageFit,
+) -> Fill {
+	let Some(row) = image.into_iter().next() else { return Fill::None };
+
+	// Encoded once here, since the SVG renderer would otherwise encode the image again every time it draws the fill
+	let mut image = row.element;
+	image.base64_string = Some(image.to_base64_png());
+
+	Fill::Image(ImageFill { image, fit })
+}
+
 trait IntoF64Vec {
 	fn into_vec(self) -> Vec<f64>;
 }
@@ -215,6 +259,7 @@ where
 		transform: DAffine2::IDENTITY,
 		non_scaling: false,
 		paint_order,
+		width_profile: WidthProfile::default(),
 	};
 
 	for vector in content.vector_iter_mut() {
@@ -226,6 +271,57 @@ where
 	content
 }
 
+/// Varies the width of the stroke along each of its subpaths. Each width point sets the width on the left and right sides of the path, as multiples of half the stroke weight, at a fraction of the distance along it.
+///
+/// This applies to the stroke given by an upstream Stroke node, since a Stroke node replaces the whole stroke including its width profile. The strokes of an appearance stack aren't affected.
+#[node_macro::node(category("Vector: Style"), path(graphene_core::vector))]
+async fn width_profile<V>(
+	_: impl Ctx,
+	/// The content with vector paths to vary the stroke width of.
+	#[implementations(Table<Vector>, Table<Graphic>)]
+	mut content: Table<V>,
+	/// The width points, each given as its position along the path (0 to 1) followed by its left and right widths.
+	profile: WidthProfile,
+) -> Table<V>
+where
+	Table<V>: VectorTableIterMut + 'n + Send,
+{
+	for vector in content.vector_iter_mut() {
+		let mut stroke = vector.element.style.stroke().unwrap_or_default();
+		stroke.width_profile = profile.clone();
+		vector.element.style.set_stroke(stroke);
+	}
+
+	content
+}
+
+/// Gives the vector content additional fills and strokes, which are drawn in order over its main fill and stroke, each with its own opacity and blend mode.
+#[node_macro::node(category("Vector: Style"), path(graphene_core::vector), properties("appearance_properties"))]
+async fn appearance<V>(
+	_: impl Ctx,
+	/// The content with vector paths to give the additional fills and strokes to.
+	#[implementations(Table<Vector>, Table<Graphic>)]
+	mut content: Table<V>,
+	/// The fills and strokes, listed from the bottom of the stack to the top.
+	stack: AppearanceStack,
+) -> Table<V>
+where
+	Table<V>: VectorTableIterMut + 'n + Send,
+{
+	for vector in content.vector_iter_mut() {
+		let mut appearance = stack.clone();
+		for entry in &mut appearance.0 {
+			// Like the Stroke node, strokes are weighted in the space of the content at this point in the graph
+			if let AppearancePaint::Stroke(stroke) = &mut entry.paint {
+				stroke.transform *= *vector.transform;
+			}
+		}
+		vector.element.style.appearance = appearance;
+	}
+
+	content
+}
+
 #[node_macro::node(category("Instancing"), path(core_types::vector))]
 async fn repeat<I: 'n + Send + Clone>(
 	_: impl Ctx,
@@ -1247,6 +1343,9 @@ async fn solidify_stroke(_: impl Ctx, content: Table<Vector>) -> Table<Vector> {
 			let bezpaths = vector.stroke_bezpath_iter();
 			let mut result = Vector::default();
 
+			// A stroke with a width profile is solidified into the same outline it's drawn with
+			let width_profile_outline = stroke.has_width_profile().then(|| stroke_width_profile_outline(vector.stroke_bezpath_iter(), &stroke));
+
 			// Taking the existing stroke data and passing it to kurbo::stroke to generate new fill paths.
 			let join = match stroke.join {
 				StrokeJoin::Miter => kurbo::Join::Miter,
@@ -1273,15 +1372,19 @@ async fn solidify_stroke(_: impl Ctx, content: Table<Vector>) -> Table<Vector> {
 			// 0.25 is balanced between performace and accuracy of the curve.
 			const STROKE_TOLERANCE: f64 = 0.25;
 
-			for mut path in bezpaths {
-				path.apply_affine(Affine::new(stroke.transform.to_cols_array()));
+			if let Some(outline) = width_profile_outline {
+				result.append_bezpath(outline);
+			} else {
+				for mut path in bezpaths {
+					path.apply_affine(Affine::new(stroke.transform.to_cols_array()));
+
+					let mut solidified = kurbo::stroke(path, &stroke_style, &stroke_options, STROKE_TOLERANCE);
+					if stroke.transform.matrix2.determinant() != 0. {
+						solidified.apply_affine(Affine::new(stroke.transform.inverse().to_cols_array()));
+					}
 
-				let mut solidified = kurbo::stroke(path, &stroke_style, &stroke_options, STROKE_TOLERANCE);
-				if stroke.transform.matrix2.determinant() != 0. {
-					solidified.apply_affine(Affine::new(stroke.transform.inverse().to_cols_array()));
+					result.append_bezpath(solidified);
 				}
-
-				result.append_bezpath(solidified);
 			}
 
 			// We set our fill to our stroke's color, then clear our stroke.
@@ -2518,6 +2621,7 @@ mod test {
 	use std::pin::Pin;
 	use vector_types::vector::algorithms::bezpath_algorithms::{TValue, trim_pathseg};
 	use vector_types::vector::misc::pathseg_abs_diff_eq;
+	use vector_types::vector::style::WidthPoint;
 
 	#[derive(Clone)]
 	pub struct FutureWrapperNode<T: Clone>(T);
@@ -2862,4 +2966,33 @@ mod test {
 		assert_eq!(beveled.point_domain.positions().len(), 6);
 		assert_eq!(beveled.segment_domain.ids().len(), 5);
 	}
+
+	#[tokio::test]
+	async fn stroke_replaces_width_profile() {
+		let profile = WidthProfile::new([WidthPoint::new(0., 1., 1.), WidthPoint::new(1., 3., 3.)]);
+		let stroke = |content: Table<Vector>| {
+			super::stroke(
+				Footprint::default(),
+				content,
+				Table::new_from_element(Color::BLACK),
+				2.,
+				StrokeAlign::default(),
+				StrokeCap::default(),
+				StrokeJoin::default(),
+				4.,
+				PaintOrder::default(),
+				Vec::<f64>::new(),
+				0.,
+			)
+		};
+		let line = || vector_node_from_bezpath(Line::new(Point::ZERO, Point::new(100., 0.)).to_path(DEFAULT_ACCURACY));
+
+		// A width profile given to the stroke applies to it
+		let profiled = super::width_profile(Footprint::default(), stroke(line()).await, profile.clone()).await;
+		assert_eq!(profiled.iter().next().unwrap().element.style.stroke().unwrap().width_profile, profile);
+
+		// A later Stroke node replaces the stroke along with its width profile
+		let restroked = stroke(super::width_profile(Footprint::default(), line(), profile).await).await;
+		assert!(!restroked.iter().next().unwrap().element.style.stroke().unwrap().has_width_profile());
+	}
 }
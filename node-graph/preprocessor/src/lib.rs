@@ -18,7 +18,9 @@ pub fn expand_network(network: &mut NodeNetwork, substitutions: &HashMap<ProtoNo
 		match &mut node.implementation {
 			DocumentNodeImplementation::Network(node_network) => expand_network(node_network, substitutions),
 			DocumentNodeImplementation::ProtoNode(proto_node_identifier) => {
-				if let Some(new_node) = substitutions.get(proto_node_identifier) {
+				if *proto_node_identifier == math_nodes::math::IDENTIFIER && node.inputs.len() > MATH_VARIABLES_INDEX + 1 {
+					collect_math_variables(node, substitutions);
+				} else if let Some(new_node) = substitutions.get(proto_node_identifier) {
 					node.implementation = new_node.implementation.clone();
 				}
 			}
@@ -27,6 +29,52 @@ pub fn expand_network(network: &mut NodeNetwork, substitutions: &HashMap<ProtoNo
 	}
 }
 
+const MATH_VARIABLES_INDEX: usize = math_nodes::math::VariablesInput::INDEX;
+
+/// The *Math* node has an input for each named variable in its expression after its `variables` input, which its proto node doesn't have.
+/// So it is wrapped in a network where a chain of *Math Variable* nodes appends the value of each of those inputs to its `variables` input.
+fn collect_math_variables(node: &mut DocumentNode, substitutions: &HashMap<ProtoNodeIdentifier, DocumentNode>) {
+	let proto_node = |identifier: ProtoNodeIdentifier, inputs, context_features| DocumentNode {
+		inputs,
+		call_argument: node.call_argument.clone(),
+		implementation: substitutions
+			.get(&identifier)
+			.map_or(DocumentNodeImplementation::ProtoNode(identifier), |new_node| new_node.implementation.clone()),
+		visible: true,
+		context_features,
+		..Default::default()
+	};
+
+	let variable_count = node.inputs.len() - MATH_VARIABLES_INDEX - 1;
+	let mut nodes: HashMap<_, _, _> = (0..variable_count)
+		.map(|index| {
+			let variables = if index == 0 {
+				NodeInput::import(concrete!(Vec<f64>), MATH_VARIABLES_INDEX)
+			} else {
+				NodeInput::node(NodeId(index as u64 - 1), 0)
+			};
+			let value = NodeInput::import(concrete!(f64), MATH_VARIABLES_INDEX + 1 + index);
+			(
+				NodeId(index as u64),
+				proto_node(math_nodes::math_variable::IDENTIFIER, vec![variables, value], ContextDependencies::default()),
+			)
+		})
+		.collect();
+
+	let math_inputs = (0..MATH_VARIABLES_INDEX)
+		.map(|index| NodeInput::import(generic!(T), index))
+		.chain([NodeInput::node(NodeId(variable_count as u64 - 1), 0)])
+		.collect();
+	nodes.insert(NodeId(variable_count as u64), proto_node(math_nodes::math::IDENTIFIER, math_inputs, node.context_features));
+
+	node.implementation = DocumentNodeImplementation::Network(NodeNetwork {
+		exports: vec![NodeInput::node(NodeId(variable_count as u64), 0)],
+		nodes,
+		scope_injections: Default::default(),
+		generated: true,
+	});
+}
+
 pub fn generate_node_substitutions() -> HashMap<ProtoNodeIdentifier, DocumentNode> {
 	let mut custom = HashMap::new();
 	// We pre initialize the node registry here to avoid a deadlock